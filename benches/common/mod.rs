@@ -0,0 +1,55 @@
+//! Shared setup of the benchmarks.
+
+use lfest::{account_tracker::NoAccountTracker, prelude::*};
+
+/// The best bid of the initial market state.
+pub const BID: Decimal = Dec!(1000);
+/// The best ask of the initial market state.
+pub const ASK: Decimal = Dec!(1000.01);
+
+/// An exchange with a tick size of 0.01, enough balance to never hit a margin limit
+/// and an initial `Bba` of `BID` and `ASK` at timestamp 0.
+pub fn exchange(max_num_open_orders: usize) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let contract_specification = ContractSpecification {
+        ticker: "TESTUSD".to_string(),
+        initial_margin: Dec!(0.01),
+        maintenance_margin: Dec!(0.02),
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter {
+            tick_size: quote!(0.01),
+            ..Default::default()
+        },
+        quantity_filter: QuantityFilter::default(),
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+    };
+    let config = Config::new(
+        quote!(100000000),
+        max_num_open_orders,
+        leverage!(1),
+        contract_specification,
+    )
+    .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(QuoteCurrency::new(BID), QuoteCurrency::new(ASK)))
+        .unwrap();
+    exchange
+}
+
+/// An exchange with `num_orders` resting buy and sell orders, like a market maker with a deep quote ladder.
+/// Each order is one tick further away from the best bid or ask than the previous one on its side.
+pub fn exchange_with_resting_orders(num_orders: usize) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let mut exchange = exchange(num_orders + 1);
+    for i in 0..num_orders {
+        let offset = QuoteCurrency::new(Decimal::from(i as u64 / 2 + 1) / Decimal::from(100));
+        let order = if i % 2 == 0 {
+            Order::limit(Side::Buy, QuoteCurrency::new(BID) - offset, base!(1)).unwrap()
+        } else {
+            Order::limit(Side::Sell, QuoteCurrency::new(ASK) + offset, base!(1)).unwrap()
+        };
+        exchange.submit_order(order).unwrap();
+    }
+    exchange
+}
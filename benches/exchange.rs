@@ -0,0 +1,138 @@
+//! Benchmark the hot paths of the `Exchange`:
+//! processing market updates, submitting and cancelling orders and matching resting limit orders.
+//!
+//! Profile a single benchmark with e.g. `cargo bench --bench exchange -- --profile-time 10 update_state_bba`.
+
+use common::{exchange, exchange_with_resting_orders, ASK, BID};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use lfest::{candle, prelude::*, trade};
+
+mod common;
+
+const NUM_ORDERS: [usize; 4] = [0, 10, 100, 1_000];
+
+/// A market update which does not cross any resting order, alternating the trade side.
+fn market_update(i: u64, kind: &str) -> MarketUpdate<BaseCurrency> {
+    let bid = QuoteCurrency::new(BID);
+    let ask = QuoteCurrency::new(ASK);
+    match kind {
+        "bba" => bba!(bid, ask),
+        "candle" => candle!(bid, ask, bid, ask),
+        "trade" => {
+            let side = if i.is_multiple_of(2) {
+                Side::Buy
+            } else {
+                Side::Sell
+            };
+            trade!(ask, base!(1), side)
+        }
+        _ => unreachable!("unknown market update kind {kind}"),
+    }
+}
+
+fn update_state(c: &mut Criterion) {
+    for kind in ["bba", "candle", "trade"] {
+        let mut group = c.benchmark_group(format!("update_state_{kind}"));
+        for num_orders in NUM_ORDERS {
+            let mut exchange = exchange_with_resting_orders(num_orders);
+            let mut ts = 0;
+            group.bench_with_input(
+                BenchmarkId::from_parameter(num_orders),
+                &num_orders,
+                |b, _| {
+                    b.iter(|| {
+                        ts += 1;
+                        exchange
+                            .update_state(ts, black_box(market_update(ts, kind)))
+                            .unwrap()
+                    })
+                },
+            );
+        }
+        group.finish();
+    }
+}
+
+/// Process 1000 `Candle` updates with 100 resting orders, one by one or as a batch.
+fn update_state_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_state_batch");
+    let mut exchange = exchange_with_resting_orders(100);
+    let mut ts = 0;
+    group.bench_function("single", |b| {
+        b.iter(|| {
+            for _ in 0..1_000 {
+                ts += 1;
+                exchange
+                    .update_state(ts, black_box(market_update(ts, "candle")))
+                    .unwrap();
+            }
+        })
+    });
+    group.bench_function("batch", |b| {
+        b.iter_batched(
+            || {
+                Vec::from_iter((0..1_000).map(|_| {
+                    ts += 1;
+                    (ts, market_update(ts, "candle"))
+                }))
+            },
+            |updates| exchange.update_state_batch(black_box(&updates)).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn market_order_round_trip(c: &mut Criterion) {
+    let mut exchange = exchange(1);
+    c.bench_function("market_order_round_trip", |b| {
+        b.iter(|| {
+            for side in [Side::Buy, Side::Sell] {
+                let order = Order::market(side, base!(1)).unwrap();
+                exchange.submit_order(black_box(order)).unwrap();
+            }
+        })
+    });
+}
+
+/// Fill all `num_orders` resting buy orders at once with a sell `Trade` crossing them.
+fn limit_order_matching(c: &mut Criterion) {
+    let mut group = c.benchmark_group("limit_order_matching");
+    for num_orders in [1, 10, 100, 1_000] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_orders),
+            &num_orders,
+            |b, &num_orders| {
+                b.iter_batched(
+                    || {
+                        let mut exchange = exchange(num_orders);
+                        for _ in 0..num_orders {
+                            let order =
+                                Order::limit(Side::Buy, QuoteCurrency::new(BID), base!(1)).unwrap();
+                            exchange.submit_order(order).unwrap();
+                        }
+                        exchange
+                    },
+                    |mut exchange| {
+                        let fills = exchange
+                            .update_state(1, trade!(quote!(999), base!(1), Side::Sell))
+                            .unwrap();
+                        assert_eq!(fills.len(), num_orders);
+                        fills
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    update_state,
+    update_state_batch,
+    market_order_round_trip,
+    limit_order_matching
+);
+criterion_main!(benches);
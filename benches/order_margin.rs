@@ -0,0 +1,43 @@
+//! Benchmark the order margin calculation with many resting limit orders,
+//! which is done in the risk check of every limit order submission.
+
+use common::exchange_with_resting_orders;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use lfest::prelude::*;
+
+mod common;
+
+fn submit_and_cancel_limit_order(c: &mut Criterion) {
+    let mut group = c.benchmark_group("submit_and_cancel_limit_order");
+    for num_orders in [10, 100, 1_000] {
+        let mut exchange = exchange_with_resting_orders(num_orders);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_orders),
+            &num_orders,
+            |b, _| {
+                b.iter(|| {
+                    let order = Order::limit(Side::Buy, quote!(900), base!(1)).unwrap();
+                    let order = exchange.submit_order(black_box(order)).unwrap();
+                    exchange.cancel_order(order.id()).unwrap();
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn available_balance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("available_balance");
+    for num_orders in [10, 100, 1_000] {
+        let exchange = exchange_with_resting_orders(num_orders);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_orders),
+            &num_orders,
+            |b, _| b.iter(|| black_box(exchange.account().available_balance())),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, submit_and_cancel_limit_order, available_balance);
+criterion_main!(benches);
@@ -27,8 +27,9 @@ fn main() {
         mark_method: MarkMethod::MidPrice,
         price_filter: PriceFilter::default(),
         quantity_filter: QuantityFilter::default(),
-        fee_maker: fee!(0.0002),
-        fee_taker: fee!(0.0006),
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
     };
     let config = Config::new(starting_balance, 200, leverage!(1), contract_specification).unwrap();
     let mut exchange =
@@ -78,7 +79,7 @@ fn main() {
         exchange.account_tracker().num_trades(),
         t0.elapsed().as_millis()
     );
-    analyze_results(&exchange.account_tracker());
+    analyze_results(exchange.account_tracker());
 }
 
 /// analyze the resulting performance metrics of the traded orders
@@ -0,0 +1,222 @@
+//! Feeds random sequences of orders, cancels and market updates into an `Exchange`
+//! and checks its invariants after every step.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use lfest::{account_tracker::NoAccountTracker, candle, prelude::*, trade};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    hedge: bool,
+    negative_balance_protection: bool,
+    leverage: u8,
+    actions: Vec<Action>,
+}
+
+/// Prices are denoted in ticks of 0.1 and quantities in steps of 0.001.
+#[derive(Debug, Arbitrary)]
+enum Action {
+    Quote {
+        bid: u16,
+        spread: u8,
+    },
+    Trade {
+        price: u16,
+        quantity: u16,
+        buy: bool,
+    },
+    Candle {
+        bid: u16,
+        spread: u8,
+        down: u16,
+        up: u16,
+    },
+    Market {
+        buy: bool,
+        quantity: u16,
+        reduce_only: bool,
+    },
+    Limit {
+        buy: bool,
+        price: u16,
+        quantity: u16,
+        display_quantity: Option<u16>,
+        reduce_only: bool,
+        time_in_force: u8,
+    },
+    Cancel {
+        index: u8,
+    },
+    Deposit(u16),
+    Withdraw(u16),
+    SetLeverage(u8),
+    DepositPositionMargin {
+        buy: bool,
+        amount: u16,
+    },
+    WithdrawPositionMargin {
+        buy: bool,
+        amount: u16,
+    },
+}
+
+fn price(ticks: u16) -> QuoteCurrency {
+    QuoteCurrency::new(Decimal::from(ticks as u64 + 1) * Dec!(0.1))
+}
+
+fn quantity(steps: u16) -> BaseCurrency {
+    BaseCurrency::new(Decimal::from(steps as u64) * Dec!(0.001))
+}
+
+fn side(buy: bool) -> Side {
+    if buy {
+        Side::Buy
+    } else {
+        Side::Sell
+    }
+}
+
+fn exchange(input: &Input) -> Option<Exchange<NoAccountTracker, BaseCurrency>> {
+    let contract_spec = ContractSpecification {
+        ticker: "FUZZUSD".to_string(),
+        initial_margin: Dec!(0.02),
+        maintenance_margin: Dec!(0.01),
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter {
+            tick_size: quote!(0.1),
+            ..Default::default()
+        },
+        quantity_filter: QuantityFilter {
+            min_quantity: base!(0),
+            max_quantity: base!(0),
+            max_market_quantity: base!(0),
+            step_size: base!(0.001),
+        },
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+    };
+    let leverage = Leverage::new(input.leverage.clamp(1, 50)).ok()?;
+    let config = Config::new(quote!(1000), 50, leverage, contract_spec)
+        .ok()?
+        .with_position_mode(if input.hedge {
+            PositionMode::Hedge
+        } else {
+            PositionMode::Netting
+        })
+        .with_bankruptcy_mode(if input.negative_balance_protection {
+            BankruptcyMode::NegativeBalanceProtection
+        } else {
+            BankruptcyMode::NegativeEquity
+        });
+    Some(Exchange::new(NoAccountTracker, config))
+}
+
+fuzz_target!(|input: Input| {
+    let Some(mut exchange) = exchange(&input) else {
+        return;
+    };
+    if exchange
+        .update_state(0, bba!(quote!(100), quote!(100.1)))
+        .is_err()
+    {
+        return;
+    }
+    exchange.assert_invariants();
+
+    for (i, action) in input.actions.iter().enumerate() {
+        let ts = i as u64 + 1;
+        // Rejected requests and liquidations are expected, only panics and invariant violations are bugs.
+        let _ = match *action {
+            Action::Quote { bid, spread } => exchange
+                .update_state(
+                    ts,
+                    bba!(price(bid), price(bid.saturating_add(spread as u16 + 1))),
+                )
+                .map(drop),
+            Action::Trade {
+                price: p,
+                quantity: q,
+                buy,
+            } => exchange
+                .update_state(ts, trade!(price(p), quantity(q), side(buy)))
+                .map(drop),
+            Action::Candle {
+                bid,
+                spread,
+                down,
+                up,
+            } => {
+                let ask = bid.saturating_add(spread as u16 + 1);
+                exchange
+                    .update_state(
+                        ts,
+                        candle!(
+                            price(bid),
+                            price(ask),
+                            price(bid.saturating_sub(down)),
+                            price(ask.saturating_add(up))
+                        ),
+                    )
+                    .map(drop)
+            }
+            Action::Market {
+                buy,
+                quantity: q,
+                reduce_only,
+            } => Order::market(side(buy), quantity(q))
+                .map_err(Error::from)
+                .and_then(|mut order| {
+                    order.set_reduce_only(reduce_only);
+                    exchange.submit_order(order)
+                })
+                .map(drop),
+            Action::Limit {
+                buy,
+                price: p,
+                quantity: q,
+                display_quantity,
+                reduce_only,
+                time_in_force,
+            } => Order::limit(side(buy), price(p), quantity(q))
+                .map_err(Error::from)
+                .and_then(|mut order| {
+                    if let Some(display_quantity) = display_quantity {
+                        order.set_display_quantity(quantity(display_quantity))?;
+                    }
+                    order.set_reduce_only(reduce_only);
+                    order.set_time_in_force(match time_in_force % 4 {
+                        0 => TimeInForce::GoodTilCancel,
+                        1 => TimeInForce::ImmediateOrCancel,
+                        2 => TimeInForce::FillOrKill,
+                        _ => TimeInForce::GoodTilDate(ts as i64 + time_in_force as i64),
+                    });
+                    exchange.submit_order(order)
+                })
+                .map(drop),
+            Action::Cancel { index } => {
+                let mut ids =
+                    Vec::from_iter(exchange.account().active_limit_orders().keys().copied());
+                ids.sort_unstable();
+                match ids.get(index as usize % ids.len().max(1)) {
+                    Some(&id) => exchange.cancel_order(id).map(drop),
+                    None => Ok(()),
+                }
+            }
+            Action::Deposit(amount) => exchange.deposit(price(amount)),
+            Action::Withdraw(amount) => exchange.withdraw(price(amount)),
+            Action::SetLeverage(leverage) => {
+                Leverage::new(leverage).and_then(|leverage| exchange.set_leverage(leverage))
+            }
+            Action::DepositPositionMargin { buy, amount } => {
+                exchange.deposit_position_margin(side(buy), price(amount))
+            }
+            Action::WithdrawPositionMargin { buy, amount } => {
+                exchange.withdraw_position_margin(side(buy), price(amount))
+            }
+        };
+        exchange.assert_invariants();
+    }
+});
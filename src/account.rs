@@ -1,16 +1,33 @@
+use alloc::{collections::BinaryHeap, sync::Arc, vec::Vec};
+use core::cmp::Reverse;
+
 use hashbrown::HashMap;
 
 use crate::{
-    order_margin::compute_order_margin,
+    collateral::{CollateralAsset, CollateralBalance},
+    exchange::EXPECT_LIMIT_PRICE,
+    fee_token::{FeeToken, FeeTokenBalance},
+    ledger::{Ledger, LedgerEntryKind},
+    market_state::MarketState,
+    options::OptionPosition,
+    order_book::OrderBook,
+    order_margin::{OrderMargin, OrderMarginModel},
     position::Position,
+    position_history::PositionHistory,
     prelude::AccountTracker,
-    types::{Currency, Error, Fee, Leverage, MarginCurrency, Order, OrderType, Result},
+    risk_engine::RiskError,
+    types::{
+        Currency, Error, Fee, Leverage, MarginCurrency, MarginShock, Mon, Order, OrderType,
+        PositionMode, QuoteCurrency, Result, Side, UserOrderId,
+    },
+    utils::max,
 };
 
 #[derive(Debug, Clone)]
 /// The users account
 /// Generic over:
 /// S: The `Currency` representing the order quantity
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct Account<M>
 where
     M: Currency + MarginCurrency,
@@ -18,12 +35,32 @@ where
     /// The wallet balance of the user denoted in the margin currency.
     pub wallet_balance: M,
     pub(crate) position: Position<M>,
+    /// The short position in `PositionMode::Hedge`, where `position` holds the long position.
+    pub(crate) short_position: Position<M>,
+    position_mode: PositionMode,
     // Maps the order `id` to the actual `Order`.
     pub(crate) active_limit_orders: HashMap<u64, Order<M::PairedCurrency>>,
+    // The `id`s of the `active_limit_orders` by side and price level, in price-time priority.
+    pub(crate) order_book: OrderBook<M::Num>,
+    /// The open European option positions.
+    pub(crate) option_positions: Vec<OptionPosition<M>>,
     // Maps the `user_order_id` to the internal order nonce
-    pub(crate) lookup_order_nonce_from_user_order_id: HashMap<u64, u64>,
-    maker_fee: Fee,
-    order_margin: M,
+    pub(crate) lookup_order_nonce_from_user_order_id: HashMap<UserOrderId, u64>,
+    // Maps the one-cancels-other `group_id` to the `id`s of its active orders.
+    pub(crate) order_groups: HashMap<u64, Vec<u64>>,
+    // A min-heap of `(expires_at, id)` of the active orders which expire,
+    // so expiring them does not scan every resting order.
+    // Filled and cancelled orders are skipped when they reach the top.
+    expiry_index: BinaryHeap<Reverse<(i64, u64)>>,
+    maker_fee: Fee<M::Num>,
+    // Aggregates the `active_limit_orders` for the order margin requirement.
+    order_margin: OrderMargin<M>,
+    ledger: Ledger<M>,
+    position_history: PositionHistory<M>,
+    /// The collateral held in assets other than the margin currency.
+    collateral: Vec<CollateralBalance<M>>,
+    /// The balance of the token in which fees are paid at a discount, if configured.
+    fee_token: Option<FeeTokenBalance<M>>,
 }
 
 #[cfg(test)]
@@ -32,14 +69,23 @@ where
     M: Currency + MarginCurrency,
 {
     fn default() -> Self {
-        use crate::prelude::{fee, Dec, Decimal};
         Self {
-            wallet_balance: M::new(Dec!(1)),
+            wallet_balance: M::new(M::Num::ONE),
             position: Position::default(),
+            short_position: Position::default(),
+            position_mode: PositionMode::Netting,
+            option_positions: Vec::new(),
             active_limit_orders: HashMap::default(),
+            order_book: OrderBook::default(),
             lookup_order_nonce_from_user_order_id: HashMap::default(),
-            maker_fee: fee!(0.0),
-            order_margin: M::new(Dec!(0)),
+            order_groups: HashMap::default(),
+            expiry_index: BinaryHeap::new(),
+            maker_fee: Fee::new(M::Num::ZERO),
+            order_margin: OrderMargin::default(),
+            ledger: Ledger::default(),
+            position_history: PositionHistory::default(),
+            collateral: Vec::new(),
+            fee_token: None,
         }
     }
 }
@@ -49,35 +95,159 @@ where
     M: Currency + MarginCurrency,
 {
     /// Create a new [`Account`] instance.
-    pub(crate) fn new(starting_balance: M, leverage: Leverage, maker_fee: Fee) -> Self {
+    pub(crate) fn new(
+        starting_balance: M,
+        leverage: Leverage,
+        maker_fee: Fee<M::Num>,
+        position_mode: PositionMode,
+    ) -> Self {
         let position = Position::new(leverage);
 
         Self {
             wallet_balance: starting_balance,
             position,
+            short_position: Position::new(leverage),
+            position_mode,
+            option_positions: Vec::new(),
             active_limit_orders: HashMap::new(),
+            order_book: OrderBook::default(),
             lookup_order_nonce_from_user_order_id: HashMap::new(),
+            order_groups: HashMap::new(),
+            expiry_index: BinaryHeap::new(),
             maker_fee,
-            order_margin: M::new_zero(),
+            order_margin: OrderMargin::default(),
+            ledger: Ledger::default(),
+            position_history: PositionHistory::default(),
+            collateral: Vec::new(),
+            fee_token: None,
         }
     }
 
     /// Return a reference to the accounts position.
+    /// In `PositionMode::Hedge` this is the long position.
     #[inline(always)]
     pub fn position(&self) -> &Position<M> {
         &self.position
     }
 
+    /// Return a reference to the short position in `PositionMode::Hedge`.
+    /// Always empty in `PositionMode::Netting`.
+    #[inline(always)]
+    pub fn short_position(&self) -> &Position<M> {
+        &self.short_position
+    }
+
+    /// Return the `PositionMode` of the account.
+    #[inline(always)]
+    pub fn position_mode(&self) -> PositionMode {
+        self.position_mode
+    }
+
+    /// Return the unrealized profit and loss of all positions.
+    pub fn unrealized_pnl(&self, bid: QuoteCurrency<M::Num>, ask: QuoteCurrency<M::Num>) -> M {
+        self.position.unrealized_pnl(bid, ask) + self.short_position.unrealized_pnl(bid, ask)
+    }
+
+    /// Return the collateral backing all positions.
+    #[inline]
+    pub fn position_margin(&self) -> M {
+        self.position.position_margin + self.short_position.position_margin
+    }
+
+    /// Return the open European option positions.
+    #[inline(always)]
+    pub fn option_positions(&self) -> &[OptionPosition<M>] {
+        &self.option_positions
+    }
+
+    /// Return the margin reserved for short option positions.
+    pub fn option_margin(&self) -> M {
+        self.option_positions
+            .iter()
+            .fold(M::new_zero(), |acc, p| acc + p.margin)
+    }
+
+    /// The quantity of the position which an order of `side` can reduce.
+    pub(crate) fn reducible_quantity(&self, side: Side) -> M::PairedCurrency {
+        let zero = M::PairedCurrency::new_zero();
+        match (self.position_mode, side) {
+            (PositionMode::Netting, Side::Buy) => max(self.position.size.into_negative(), zero),
+            (PositionMode::Netting, Side::Sell) => max(self.position.size, zero),
+            (PositionMode::Hedge, Side::Buy) => self.short_position.size.abs(),
+            (PositionMode::Hedge, Side::Sell) => self.position.size,
+        }
+    }
+
+    /// Compute the order margin requirement of the active limit orders for the current positions,
+    /// as if the `additional` order was active as well.
+    pub(crate) fn order_margin_with(
+        &self,
+        additional: Option<&Order<M::PairedCurrency>>,
+        fee: Fee<M::Num>,
+    ) -> M {
+        self.order_margin
+            .order_margin(self.position_mode, &self.position, fee, additional)
+    }
+
+    /// Compute the order margin requirement of the active limit orders,
+    /// as if the `position` was held instead of the current one, e.g. after a fill.
+    pub(crate) fn order_margin_for(&self, position: &Position<M>) -> M {
+        self.order_margin
+            .order_margin(self.position_mode, position, self.maker_fee, None)
+    }
+
     /// Return the current wallet balance of the account.
     #[inline(always)]
     pub fn wallet_balance(&self) -> M {
         self.wallet_balance
     }
 
+    /// Return the ledger of every change of the wallet balance.
+    #[inline(always)]
+    pub fn ledger(&self) -> &Ledger<M> {
+        &self.ledger
+    }
+
+    /// Return the history of every mutation of the positions.
+    #[inline(always)]
+    pub fn position_history(&self) -> &PositionHistory<M> {
+        &self.position_history
+    }
+
+    /// A copy of the long and short position, to record their changes with `record_position_changes`.
+    pub(crate) fn snapshot_positions(&self) -> (Position<M>, Position<M>) {
+        (self.position.clone(), self.short_position.clone())
+    }
+
+    /// Record the changes of the positions since the `before` snapshot in the position history.
+    pub(crate) fn record_position_changes(
+        &mut self,
+        before: (Position<M>, Position<M>),
+        fill_price: QuoteCurrency<M::Num>,
+        liquidation: bool,
+        ts_ns: i64,
+    ) {
+        self.position_history
+            .record(ts_ns, &before.0, &self.position, fill_price, liquidation);
+        self.position_history.record(
+            ts_ns,
+            &before.1,
+            &self.short_position,
+            fill_price,
+            liquidation,
+        );
+    }
+
+    /// Change the wallet balance by the signed `amount` and record it in the ledger.
+    pub(crate) fn change_balance(&mut self, amount: M, kind: LedgerEntryKind, ts_ns: i64) {
+        self.wallet_balance += amount;
+        self.ledger.record(ts_ns, kind, amount, self.wallet_balance);
+    }
+
     /// Return the current order margin
     #[inline(always)]
     pub fn order_margin(&self) -> M {
-        self.order_margin
+        self.order_margin_with(None, self.maker_fee)
     }
 
     /// Return a reference to the currently active limit orders of the account
@@ -86,24 +256,423 @@ where
         &self.active_limit_orders
     }
 
-    /// Return the available balance of the `Account`
+    /// Return the active limit order with the given `user_order_id`, either a `u64` or a string.
+    pub fn active_order_by_user_id<I: Into<UserOrderId>>(
+        &self,
+        user_order_id: I,
+    ) -> Option<&Order<M::PairedCurrency>> {
+        self.lookup_order_nonce_from_user_order_id
+            .get(&user_order_id.into())
+            .and_then(|order_id| self.active_limit_orders.get(order_id))
+    }
+
+    /// Return the available balance of the `Account`, which includes the value of the collateral,
+    /// and is only negative if the effective balance is, after a bankruptcy in `BankruptcyMode::NegativeEquity`.
     #[inline(always)]
     pub fn available_balance(&self) -> M {
-        // TODO: this call is expensive so maybe compute once and store
-        let order_margin =
-            compute_order_margin(&self.position, &self.active_limit_orders, self.maker_fee);
-        let ab = self.wallet_balance - self.position.position_margin - order_margin;
-        debug_assert!(ab >= M::new_zero());
+        let ab = self.effective_balance() - self.required_margin();
+        debug_assert!(ab >= M::new_zero() || self.effective_balance() < M::new_zero());
         ab
     }
 
+    /// The position, order and option margin, which the effective balance must cover.
+    #[inline(always)]
+    pub(crate) fn required_margin(&self) -> M {
+        self.position_margin() + self.order_margin() + self.option_margin()
+    }
+
+    /// Return the collateral held in assets other than the margin currency.
+    #[inline(always)]
+    pub fn collateral(&self) -> &[CollateralBalance<M>] {
+        &self.collateral
+    }
+
+    /// The value of the collateral after haircuts, denoted in the margin currency.
+    pub fn collateral_value(&self) -> M {
+        self.collateral
+            .iter()
+            .fold(M::new_zero(), |value, balance| value + balance.value())
+    }
+
+    /// The wallet balance plus the value of the collateral after haircuts,
+    /// which backs the position, order and option margin.
+    /// Equals the wallet balance without any collateral.
+    #[inline(always)]
+    pub fn effective_balance(&self) -> M {
+        self.wallet_balance + self.collateral_value()
+    }
+
+    pub(crate) fn set_order_margin_model(&mut self, model: Arc<dyn OrderMarginModel<M>>) {
+        self.order_margin.set_model(model);
+    }
+
+    pub(crate) fn set_collateral_assets(&mut self, assets: &[CollateralAsset<M::Num>]) {
+        self.collateral = assets.iter().cloned().map(CollateralBalance::new).collect();
+    }
+
+    /// The collateral held in the asset of `ticker`.
+    ///
+    /// # Returns:
+    /// If Err, the asset is not configured as collateral.
+    pub(crate) fn collateral_mut(&mut self, ticker: &str) -> Result<&mut CollateralBalance<M>> {
+        self.collateral
+            .iter_mut()
+            .find(|balance| balance.asset().ticker == ticker)
+            .ok_or(Error::UnknownCollateralAsset)
+    }
+
+    /// The balance of the token in which fees are paid at a discount, if configured.
+    #[inline(always)]
+    pub fn fee_token(&self) -> Option<&FeeTokenBalance<M>> {
+        self.fee_token.as_ref()
+    }
+
+    pub(crate) fn set_fee_token(&mut self, token: Option<&FeeToken<M::Num>>) {
+        self.fee_token = token.cloned().map(FeeTokenBalance::new);
+    }
+
+    /// The balance of the fee token.
+    ///
+    /// # Returns:
+    /// If Err, no fee token is configured.
+    pub(crate) fn fee_token_mut(&mut self) -> Result<&mut FeeTokenBalance<M>> {
+        self.fee_token.as_mut().ok_or(Error::FeeTokenNotConfigured)
+    }
+
+    /// Pay a fee, in the fee token at its discount if the held quantity covers it,
+    /// and from the wallet balance otherwise. Rebates are always credited to the wallet balance.
+    ///
+    /// # Returns:
+    /// The paid fee, denoted in the margin currency.
+    pub(crate) fn pay_fee(&mut self, fee: M, fee_kind: LedgerEntryKind, ts_ns: i64) -> M {
+        if fee > M::new_zero() {
+            if let Some(paid) = self.fee_token.as_mut().and_then(|balance| balance.pay(fee)) {
+                return paid;
+            }
+        }
+        self.change_balance(fee.into_negative(), fee_kind, ts_ns);
+        fee
+    }
+
+    /// Sell collateral in the configured order of the assets to credit up to `amount` to the wallet balance.
+    ///
+    /// # Returns:
+    /// The credited amount.
+    pub(crate) fn convert_collateral(&mut self, amount: M, ts_ns: i64) -> M {
+        let mut converted = M::new_zero();
+        for balance in self.collateral.iter_mut() {
+            if converted >= amount {
+                break;
+            }
+            converted += balance.convert(amount - converted);
+        }
+        if converted > M::new_zero() {
+            self.change_balance(converted, LedgerEntryKind::CollateralConversion, ts_ns);
+        }
+        converted
+    }
+
+    /// Check the internal consistency of the account, panicking with a description of the first violation.
+    ///
+    /// # Arguments:
+    /// `starting_balance`: The balance the account started with, from which the ledger records all changes.
+    pub(crate) fn assert_invariants(&self, starting_balance: M) {
+        let zero = M::new_zero();
+        let position_margin = self.position_margin();
+        let order_margin = self.order_margin();
+        let option_margin = self.option_margin();
+        assert!(
+            position_margin >= zero,
+            "negative position margin {position_margin}"
+        );
+        assert!(order_margin >= zero, "negative order margin {order_margin}");
+        assert!(
+            option_margin >= zero,
+            "negative option margin {option_margin}"
+        );
+        let effective_balance = self.effective_balance();
+        if effective_balance >= zero {
+            let available = effective_balance - position_margin - order_margin - option_margin;
+            assert!(
+                available >= zero,
+                "negative available balance {available}: effective balance {effective_balance} < position margin {position_margin} + order margin {order_margin} + option margin {option_margin}",
+            );
+        }
+        for balance in self.collateral.iter() {
+            assert!(
+                balance.quantity() >= M::Num::ZERO,
+                "negative collateral of {}",
+                balance.asset().ticker
+            );
+        }
+        assert_eq!(
+            starting_balance + self.ledger.net_change(),
+            self.wallet_balance,
+            "the ledger does not add up to the wallet balance"
+        );
+
+        for (name, position) in [("long", &self.position), ("short", &self.short_position)] {
+            if position.size.is_zero() {
+                assert_eq!(
+                    position.position_margin, zero,
+                    "the empty {name} position holds margin"
+                );
+            } else {
+                assert!(
+                    position.entry_price > QuoteCurrency::new_zero(),
+                    "the {name} position has no entry price"
+                );
+            }
+        }
+        match self.position_mode {
+            PositionMode::Netting => assert!(
+                self.short_position.size.is_zero(),
+                "short position in PositionMode::Netting"
+            ),
+            PositionMode::Hedge => {
+                assert!(
+                    self.position.size >= M::PairedCurrency::new_zero(),
+                    "negative long position in PositionMode::Hedge"
+                );
+                assert!(
+                    self.short_position.size <= M::PairedCurrency::new_zero(),
+                    "positive short position in PositionMode::Hedge"
+                );
+            }
+        }
+
+        let mut recomputed = OrderMargin::with_model(self.order_margin.model().clone());
+        for (id, order) in self.active_limit_orders.iter() {
+            assert_eq!(*id, order.id(), "active order stored under the wrong id");
+            assert!(
+                order.remaining_quantity() > M::PairedCurrency::new_zero(),
+                "active order {id} has nothing left to fill"
+            );
+            recomputed.insert(order);
+        }
+        assert_eq!(
+            recomputed.order_margin(self.position_mode, &self.position, self.maker_fee, None),
+            order_margin,
+            "the incrementally tracked order margin diverged from the active orders"
+        );
+        let mut num_queued = 0;
+        for side in [Side::Buy, Side::Sell] {
+            for (price, id) in self.order_book.orders(side) {
+                num_queued += 1;
+                let order = self
+                    .active_limit_orders
+                    .get(&id)
+                    .unwrap_or_else(|| panic!("the order book queues the inactive order {id}"));
+                assert!(
+                    order.side() == side && order.limit_price() == Some(price),
+                    "order {id} is queued at the wrong side or price level"
+                );
+            }
+        }
+        assert_eq!(
+            num_queued,
+            self.active_limit_orders.len(),
+            "the order book does not queue every active order exactly once"
+        );
+        for (user_order_id, id) in self.lookup_order_nonce_from_user_order_id.iter() {
+            assert!(
+                self.active_limit_orders.contains_key(id),
+                "user order id {user_order_id:?} refers to the inactive order {id}"
+            );
+        }
+    }
+
+    /// Return the liquidation price of the position of `side` with isolated margin.
+    /// The `side` only matters in `PositionMode::Hedge`,
+    /// where `Buy` selects the long and `Sell` the short position.
+    ///
+    /// # Arguments:
+    /// `maintenance_margin`: The maintenance margin requirement as a fraction.
+    pub fn liquidation_price(
+        &self,
+        side: Side,
+        maintenance_margin: M::Num,
+    ) -> Option<QuoteCurrency<M::Num>> {
+        self.position_of_side(side)
+            .liquidation_price(maintenance_margin)
+    }
+
+    /// Return the liquidation price of the position of `side` with cross margin,
+    /// where the available balance backs the position in addition to its position margin.
+    /// This is further away than the isolated liquidation price as long as there is available balance.
+    ///
+    /// # Arguments:
+    /// `maintenance_margin`: The maintenance margin requirement as a fraction.
+    pub fn cross_liquidation_price(
+        &self,
+        side: Side,
+        maintenance_margin: M::Num,
+    ) -> Option<QuoteCurrency<M::Num>> {
+        let position = self.position_of_side(side);
+        let collateral = position.position_margin + max(self.available_balance(), M::new_zero());
+        position.liquidation_price_with_collateral(collateral, maintenance_margin)
+    }
+
+    /// The margin required to keep all positions open at the current mark price.
+    ///
+    /// # Arguments:
+    /// `market_state`: The current market information.
+    /// `maintenance_margin`: The maintenance margin requirement as a fraction.
+    pub fn maintenance_margin(
+        &self,
+        market_state: &MarketState<M::Num>,
+        maintenance_margin: M::Num,
+    ) -> M {
+        let mark_price = market_state.mark_price();
+        self.position
+            .maintenance_margin(mark_price, maintenance_margin)
+            + self
+                .short_position
+                .maintenance_margin(mark_price, maintenance_margin)
+    }
+
+    /// The maintenance margin of a position divided by its margin balance, i.e. the position margin
+    /// plus the unrealized profit and loss at the current mark price.
+    /// With two positions in `PositionMode::Hedge` the larger ratio is returned,
+    /// as each position is liquidated on its own.
+    ///
+    /// # Arguments:
+    /// `market_state`: The current market information.
+    /// `maintenance_margin`: The maintenance margin requirement as a fraction.
+    ///
+    /// # Returns:
+    /// Zero without a position. A value above one means the position gets liquidated.
+    pub fn margin_ratio(
+        &self,
+        market_state: &MarketState<M::Num>,
+        maintenance_margin: M::Num,
+    ) -> M::Num {
+        let mark_price = market_state.mark_price();
+        [&self.position, &self.short_position]
+            .into_iter()
+            .filter(|position| !position.size.is_zero())
+            .map(|position| {
+                let margin_balance =
+                    position.position_margin + position.unrealized_pnl(mark_price, mark_price);
+                if margin_balance <= M::new_zero() {
+                    return M::Num::MAX;
+                }
+                (position.maintenance_margin(mark_price, maintenance_margin) / margin_balance)
+                    .inner()
+            })
+            .fold(
+                M::Num::ZERO,
+                |max, ratio| if ratio > max { ratio } else { max },
+            )
+    }
+
+    /// Recompute the margin ratio and liquidation distance of the position
+    /// under an instantaneous relative price shock, without mutating the account.
+    ///
+    /// # Arguments:
+    /// `market_state`: The current market information from which the shock is applied.
+    /// `maintenance_margin`: The maintenance margin requirement as a fraction.
+    /// `pct_move`: The relative price move, e.g.: -0.1 for a 10% drop.
+    ///
+    /// # Returns:
+    /// The resulting `MarginShock`.
+    pub fn margin_after_shock(
+        &self,
+        market_state: &MarketState<M::Num>,
+        maintenance_margin: M::Num,
+        pct_move: M::Num,
+    ) -> MarginShock<M> {
+        let shock = M::Num::ONE + pct_move;
+        let bid = market_state.bid() * shock;
+        let ask = market_state.ask() * shock;
+        let shocked_price = (bid + ask) / QuoteCurrency::new(M::Num::TWO);
+
+        let unrealized_pnl = self.position.unrealized_pnl(bid, ask);
+        let margin_balance = self.position.position_margin + unrealized_pnl;
+        let maintenance_margin_req = self
+            .position
+            .maintenance_margin(shocked_price, maintenance_margin);
+        let margin_ratio = if self.position.size.is_zero() {
+            M::Num::ZERO
+        } else if margin_balance <= M::new_zero() {
+            M::Num::MAX
+        } else {
+            (maintenance_margin_req / margin_balance).inner()
+        };
+        let liquidation_price = self.position.liquidation_price(maintenance_margin);
+        let liquidation_distance = liquidation_price
+            .map(|liq_price| ((liq_price - shocked_price) / shocked_price).inner());
+
+        MarginShock {
+            shocked_price,
+            unrealized_pnl,
+            margin_balance,
+            maintenance_margin: maintenance_margin_req,
+            margin_ratio,
+            liquidation_price,
+            liquidation_distance,
+        }
+    }
+
     /// Allows the user to update their desired leverage.
-    /// This will deposit or release variation margin from the position if any.
+    /// This will deposit or release variation margin from the position if any,
+    /// and recompute the order margin of the resting orders.
     ///
     /// # Returns:
-    /// If Err, the account is unable to provide enough variation margin for the desired leverage.
-    pub fn update_desired_leverage(&mut self, _leverage: Leverage) -> Result<()> {
-        todo!("Support `update_desired_leverage`")
+    /// If Err, the account is unable to provide enough variation margin for the desired leverage
+    /// and remains unchanged.
+    pub(crate) fn update_desired_leverage(&mut self, leverage: Leverage) -> Result<()> {
+        debug!("update_desired_leverage: {}", leverage);
+        let previous = self.position.leverage;
+        self.position.set_leverage(leverage);
+        self.short_position.set_leverage(leverage);
+        if self.required_margin() > self.effective_balance() {
+            self.position.set_leverage(previous);
+            self.short_position.set_leverage(previous);
+            return Err(Error::RiskError(RiskError::NotEnoughAvailableBalance));
+        }
+
+        Ok(())
+    }
+
+    /// The position which holds the exposure of `side`.
+    /// In `PositionMode::Netting` this is always the single position.
+    fn position_of_side(&self, side: Side) -> &Position<M> {
+        match (self.position_mode, side) {
+            (PositionMode::Hedge, Side::Sell) => &self.short_position,
+            _ => &self.position,
+        }
+    }
+
+    /// The position which holds the exposure of `side`, mutably.
+    fn position_of_side_mut(&mut self, side: Side) -> &mut Position<M> {
+        match (self.position_mode, side) {
+            (PositionMode::Hedge, Side::Sell) => &mut self.short_position,
+            _ => &mut self.position,
+        }
+    }
+
+    /// Move `amount` from the available balance into the margin of the position of `side`.
+    ///
+    /// # Returns:
+    /// If Err, the account does not have enough available balance or there is no position.
+    pub(crate) fn deposit_position_margin(&mut self, side: Side, amount: M) -> Result<()> {
+        debug!("deposit_position_margin: {} {}", side, amount);
+        if amount > self.available_balance() {
+            return Err(Error::RiskError(RiskError::NotEnoughAvailableBalance));
+        }
+        self.position_of_side_mut(side)
+            .deposit_variation_margin(amount)
+    }
+
+    /// Move `amount` from the margin of the position of `side` back into the available balance.
+    ///
+    /// # Returns:
+    /// If Err, the position margin would fall below the margin required by the leverage of the position.
+    pub(crate) fn withdraw_position_margin(&mut self, side: Side, amount: M) -> Result<()> {
+        debug!("withdraw_position_margin: {} {}", side, amount);
+        self.position_of_side_mut(side)
+            .release_variation_margin(amount)
     }
 
     /// Cancel an active order based on the user_order_id of an Order
@@ -113,7 +682,7 @@ where
     /// not found
     pub(crate) fn cancel_order_by_user_id<A>(
         &mut self,
-        user_order_id: u64,
+        user_order_id: &UserOrderId,
         account_tracker: &mut A,
     ) -> Result<Order<M::PairedCurrency>>
     where
@@ -122,7 +691,7 @@ where
         debug!("cancel_order_by_user_id: user_order_id: {}", user_order_id);
         let id: u64 = match self
             .lookup_order_nonce_from_user_order_id
-            .remove(&user_order_id)
+            .remove(user_order_id)
         {
             None => return Err(Error::UserOrderIdNotFound),
             Some(id) => id,
@@ -138,7 +707,14 @@ where
 
         // self.account_tracker.log_limit_order_submission();
         let order_id = order.id();
-        let user_order_id = *order.user_order_id();
+        let user_order_id = order.user_order_id().clone();
+        self.join_order_group(&order);
+        self.order_margin.insert(&order);
+        self.order_book.insert(
+            order.side(),
+            order.limit_price().expect(EXPECT_LIMIT_PRICE),
+            order_id,
+        );
         match self.active_limit_orders.insert(order_id, order) {
             None => {}
             Some(_) => {
@@ -156,8 +732,38 @@ where
                     .insert(user_order_id, order_id);
             }
         };
-        self.order_margin =
-            compute_order_margin(&self.position, &self.active_limit_orders, self.maker_fee);
+    }
+
+    /// Expire the active order `order_id` at the timestamp `expires_at` in nanoseconds.
+    pub(crate) fn index_expiry(&mut self, order_id: u64, expires_at: i64) {
+        debug_assert!(self.active_limit_orders.contains_key(&order_id));
+        // Drop the entries of orders which were filled or cancelled before their expiry,
+        // once they make up most of the index.
+        if self.expiry_index.len() >= 64
+            && self.expiry_index.len() > 2 * self.active_limit_orders.len()
+        {
+            let active_limit_orders = &self.active_limit_orders;
+            self.expiry_index
+                .retain(|Reverse((_, id))| active_limit_orders.contains_key(id));
+        }
+        self.expiry_index.push(Reverse((expires_at, order_id)));
+    }
+
+    /// Remove the next active order which is expired at `ts_ns` from the expiry index.
+    ///
+    /// # Returns:
+    /// The id of the order, which remains active, or `None` if no more orders are expired.
+    pub(crate) fn pop_expired(&mut self, ts_ns: i64) -> Option<u64> {
+        while let Some(Reverse((expires_at, order_id))) = self.expiry_index.peek().copied() {
+            if expires_at > ts_ns {
+                return None;
+            }
+            self.expiry_index.pop();
+            if self.active_limit_orders.contains_key(&order_id) {
+                return Some(order_id);
+            }
+        }
+        None
     }
 
     /// Cancel an active order
@@ -171,29 +777,342 @@ where
         A: AccountTracker<M>,
     {
         debug!("cancel_order: {}", order_id);
+        let removed_order = self.remove_active_order(order_id)?;
+        account_tracker.log_limit_order_cancellation();
+
+        Ok(removed_order)
+    }
+
+    /// Expire an active order, due to its `TimeInForce`.
+    pub(crate) fn expire_order<A>(
+        &mut self,
+        order_id: u64,
+        account_tracker: &mut A,
+    ) -> Order<M::PairedCurrency>
+    where
+        A: AccountTracker<M>,
+    {
+        debug!("expire_order: {}", order_id);
+        let order = self
+            .remove_active_order(order_id)
+            .expect("The order must have been active; qed");
+        account_tracker.log_limit_order_expiry();
+        order
+    }
+
+    /// Add the `order` to its one-cancels-other group, if any.
+    pub(crate) fn join_order_group(&mut self, order: &Order<M::PairedCurrency>) {
+        if let Some(group_id) = order.group_id() {
+            self.order_groups
+                .entry(group_id)
+                .or_default()
+                .push(order.id());
+        }
+    }
+
+    /// Remove the `order` from its one-cancels-other group, if any.
+    pub(crate) fn leave_order_group(&mut self, order: &Order<M::PairedCurrency>) {
+        let Some(group_id) = order.group_id() else {
+            return;
+        };
+        if let Some(group) = self.order_groups.get_mut(&group_id) {
+            group.retain(|id| *id != order.id());
+            if group.is_empty() {
+                self.order_groups.remove(&group_id);
+            }
+        }
+    }
+
+    /// Dissolve the one-cancels-other group of `order`.
+    ///
+    /// # Returns:
+    /// The ids of the other orders of the group, which are active limit or stop orders.
+    pub(crate) fn take_order_group_siblings(
+        &mut self,
+        order: &Order<M::PairedCurrency>,
+    ) -> Vec<u64> {
+        let Some(siblings) = order
+            .group_id()
+            .and_then(|group_id| self.order_groups.remove(&group_id))
+        else {
+            return Vec::new();
+        };
+        Vec::from_iter(siblings.into_iter().filter(|id| *id != order.id()))
+    }
+
+    /// Update the maker fee used in the order margin calculation.
+    pub(crate) fn set_maker_fee(&mut self, maker_fee: Fee<M::Num>) {
+        self.maker_fee = maker_fee;
+    }
+
+    /// Replace a partially executed active order, e.g. after an iceberg order got refilled,
+    /// and update the order margin.
+    pub(crate) fn update_partially_filled_order(&mut self, order: Order<M::PairedCurrency>) {
+        debug!("update_partially_filled_order: {:?}", order);
+        let Some(active_order) = self.active_limit_orders.get_mut(&order.id()) else {
+            debug_assert!(false, "The order must be active");
+            return;
+        };
+        self.order_margin.remove(active_order);
+        self.order_margin.insert(&order);
+        if order.timestamp() != active_order.timestamp() {
+            self.order_book.requeue(
+                order.side(),
+                order.limit_price().expect(EXPECT_LIMIT_PRICE),
+                order.id(),
+            );
+        }
+        *active_order = order;
+    }
+
+    /// A copy of the account with an empty `Ledger` and `PositionHistory`,
+    /// to try orders against it without copying the history.
+    pub(crate) fn clone_without_history(&self) -> Self {
+        Self {
+            wallet_balance: self.wallet_balance,
+            position: self.position.clone(),
+            short_position: self.short_position.clone(),
+            position_mode: self.position_mode,
+            active_limit_orders: self.active_limit_orders.clone(),
+            order_book: self.order_book.clone(),
+            option_positions: self.option_positions.clone(),
+            lookup_order_nonce_from_user_order_id: self
+                .lookup_order_nonce_from_user_order_id
+                .clone(),
+            order_groups: self.order_groups.clone(),
+            expiry_index: self.expiry_index.clone(),
+            maker_fee: self.maker_fee,
+            order_margin: self.order_margin.clone(),
+            ledger: Ledger::default(),
+            position_history: PositionHistory::default(),
+            collateral: self.collateral.clone(),
+            fee_token: self.fee_token.clone(),
+        }
+    }
+
+    /// Remove an order from the active ones and update the order margin.
+    pub(crate) fn remove_active_order(
+        &mut self,
+        order_id: u64,
+    ) -> Result<Order<M::PairedCurrency>> {
         let removed_order = self
             .active_limit_orders
             .remove(&order_id)
             .ok_or(Error::OrderIdNotFound)?;
-        self.order_margin =
-            compute_order_margin(&self.position, &self.active_limit_orders, self.maker_fee);
-
-        account_tracker.log_limit_order_cancellation();
+        self.order_margin.remove(&removed_order);
+        self.order_book.remove(
+            removed_order.side(),
+            removed_order.limit_price().expect(EXPECT_LIMIT_PRICE),
+            order_id,
+        );
+        if let Some(user_order_id) = removed_order.user_order_id() {
+            self.lookup_order_nonce_from_user_order_id
+                .remove(user_order_id);
+        }
+        self.leave_order_group(&removed_order);
 
         Ok(removed_order)
     }
 
     /// Removes an executed limit order from the list of active ones
     pub(crate) fn remove_executed_order_from_active(&mut self, order_id: u64) {
-        let order = self
-            .active_limit_orders
-            .remove(&order_id)
+        self.remove_active_order(order_id)
             .expect("The order must have been active; qed");
-        self.order_margin =
-            compute_order_margin(&self.position, &self.active_limit_orders, self.maker_fee);
-        if let Some(user_order_id) = order.user_order_id() {
-            self.lookup_order_nonce_from_user_order_id
-                .remove(user_order_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::{base, fee, leverage, quote, Dec, Decimal, PriceFilter},
+        types::BaseCurrency,
+    };
+
+    #[test]
+    fn account_margin_after_shock() {
+        let mut account = Account::new(
+            quote!(1000),
+            leverage!(10),
+            fee!(0.0002),
+            PositionMode::Netting,
+        );
+        account.position = Position {
+            size: BaseCurrency::new(Dec!(1)),
+            entry_price: quote!(100),
+            position_margin: quote!(10),
+            leverage: leverage!(10),
+        };
+        let market_state =
+            MarketState::from_components(PriceFilter::default(), quote!(100), quote!(100), 0, 0);
+
+        let shock = account.margin_after_shock(&market_state, Dec!(0.02), Dec!(-0.05));
+        assert_eq!(shock.shocked_price, quote!(95));
+        assert_eq!(shock.unrealized_pnl, quote!(-5));
+        assert_eq!(shock.margin_balance, quote!(5));
+        assert_eq!(shock.maintenance_margin, quote!(1.9));
+        assert_eq!(shock.margin_ratio, Dec!(0.38));
+        assert_eq!(shock.liquidation_price, Some(quote!(91.836734693877551020)));
+        assert!(shock.liquidation_distance.unwrap() < Decimal::ZERO);
+
+        // A shock through the liquidation price results in a margin ratio above one.
+        let shock = account.margin_after_shock(&market_state, Dec!(0.02), Dec!(-0.1));
+        assert!(shock.margin_ratio > Decimal::ONE);
+        assert!(shock.liquidation_distance.unwrap() > Decimal::ZERO);
+
+        // No position, no risk.
+        let account = Account::<QuoteCurrency>::new(
+            quote!(1000),
+            leverage!(10),
+            fee!(0.0002),
+            PositionMode::Netting,
+        );
+        let shock = account.margin_after_shock(&market_state, Dec!(0.02), Dec!(-0.5));
+        assert_eq!(shock.margin_ratio, Decimal::ZERO);
+        assert_eq!(shock.liquidation_price, None);
+    }
+
+    #[test]
+    fn account_liquidation_price_linear() {
+        let mut account = Account::new(
+            quote!(1000),
+            leverage!(10),
+            fee!(0.0002),
+            PositionMode::Netting,
+        );
+        assert_eq!(account.liquidation_price(Side::Buy, Dec!(0.02)), None);
+
+        account.position = Position {
+            size: BaseCurrency::new(Dec!(20)),
+            entry_price: quote!(100),
+            position_margin: quote!(200),
+            leverage: leverage!(10),
+        };
+        let isolated = account.liquidation_price(Side::Buy, Dec!(0.02)).unwrap();
+        assert_eq!(isolated, quote!(91.83673469387755102));
+        // The available balance pushes the liquidation price further away.
+        let cross = account
+            .cross_liquidation_price(Side::Buy, Dec!(0.02))
+            .unwrap();
+        assert_eq!(cross, quote!(51.020408163265306122));
+        // In netting mode the side is ignored.
+        assert_eq!(
+            account.liquidation_price(Side::Sell, Dec!(0.02)),
+            Some(isolated)
+        );
+
+        account.position.size = BaseCurrency::new(Dec!(-20));
+        let isolated = account.liquidation_price(Side::Sell, Dec!(0.02)).unwrap();
+        assert_eq!(isolated, quote!(107.843137254901960784));
+        let cross = account
+            .cross_liquidation_price(Side::Sell, Dec!(0.02))
+            .unwrap();
+        assert_eq!(cross, quote!(147.058823529411764706));
+    }
+
+    #[test]
+    fn account_liquidation_price_inverse() {
+        let mut account = Account::new(base!(1), leverage!(10), fee!(0.0002), PositionMode::Hedge);
+        account.position = Position {
+            size: quote!(1000),
+            entry_price: quote!(100),
+            position_margin: base!(1),
+            leverage: leverage!(1),
+        };
+        // A 10x long of an inverse future, worth 10 base at the entry price.
+        let price = account.liquidation_price(Side::Buy, Dec!(0.02)).unwrap();
+        assert_eq!(price, quote!(92.727272727272727273));
+        assert_eq!(account.liquidation_price(Side::Sell, Dec!(0.02)), None);
+    }
+
+    #[test]
+    fn account_margin_ratio() {
+        let mut account = Account::new(
+            quote!(1000),
+            leverage!(10),
+            fee!(0.0002),
+            PositionMode::Hedge,
+        );
+        let market_state =
+            MarketState::from_components(PriceFilter::default(), quote!(94), quote!(96), 0, 0);
+        assert_eq!(
+            account.margin_ratio(&market_state, Dec!(0.02)),
+            Decimal::ZERO
+        );
+        assert_eq!(
+            account.maintenance_margin(&market_state, Dec!(0.02)),
+            quote!(0)
+        );
+
+        account.position = Position {
+            size: BaseCurrency::new(Dec!(20)),
+            entry_price: quote!(100),
+            position_margin: quote!(200),
+            leverage: leverage!(10),
+        };
+        assert_eq!(account.position.bankruptcy_price(), Some(quote!(90)));
+        // 20 * 95 * 0.02 = 38 required against 200 - 100 = 100 margin balance.
+        assert_eq!(
+            account.maintenance_margin(&market_state, Dec!(0.02)),
+            quote!(38)
+        );
+        assert_eq!(account.margin_ratio(&market_state, Dec!(0.02)), Dec!(0.38));
+
+        // The short is further from liquidation, 19 required against 100 + 50, so the ratio of the long remains.
+        account.short_position = Position {
+            size: BaseCurrency::new(Dec!(-10)),
+            entry_price: quote!(100),
+            position_margin: quote!(100),
+            leverage: leverage!(10),
+        };
+        assert_eq!(account.short_position.bankruptcy_price(), Some(quote!(110)));
+        assert_eq!(
+            account.maintenance_margin(&market_state, Dec!(0.02)),
+            quote!(57)
+        );
+        assert_eq!(account.margin_ratio(&market_state, Dec!(0.02)), Dec!(0.38));
+
+        // Beyond the bankruptcy price of the long.
+        let market_state =
+            MarketState::from_components(PriceFilter::default(), quote!(89), quote!(89), 0, 0);
+        assert_eq!(
+            account.margin_ratio(&market_state, Dec!(0.02)),
+            Decimal::MAX
+        );
+    }
+
+    #[test]
+    fn account_expiry_index() {
+        let mut account = Account::<QuoteCurrency>::new(
+            quote!(1000),
+            leverage!(1),
+            fee!(0.0002),
+            PositionMode::Netting,
+        );
+        let mut tracker = crate::account_tracker::NoAccountTracker;
+        for id in 0..100 {
+            let mut order = Order::limit(Side::Buy, quote!(100), base!(0.01)).unwrap();
+            order.set_id(id);
+            account.append_limit_order(order);
+            account.index_expiry(id, 100 - id as i64);
+            // Keep only every fourth order active.
+            if id % 4 != 0 {
+                account.cancel_order(id, &mut tracker).unwrap();
+            }
         }
+        // The entries of the cancelled orders got dropped along the way.
+        assert!(account.expiry_index.len() < 100);
+
+        assert_eq!(account.pop_expired(3), None);
+        assert_eq!(account.pop_expired(4), Some(96));
+        account.cancel_order(96, &mut tracker).unwrap();
+        assert_eq!(account.pop_expired(4), None);
+        let expired = Vec::from_iter(core::iter::from_fn(|| {
+            account.pop_expired(100).inspect(|id| {
+                account.cancel_order(*id, &mut tracker).unwrap();
+            })
+        }));
+        assert_eq!(expired, Vec::from_iter((0..24).rev().map(|i| i * 4)));
+        assert!(account.active_limit_orders.is_empty());
     }
 }
@@ -1,5 +1,6 @@
 use crate::{
     prelude::{Account, Currency, MarketState, QuoteCurrency, Side},
+    risk_engine::RiskError,
     types::MarginCurrency,
 };
 
@@ -18,16 +19,23 @@ where
     /// `timestamp_ns`: timestamp of latest tick in nanoseconds
     /// `price`: price of latest tick
     /// `upnl`: unrealized profit and loss of account in current tick
-    fn update(&mut self, timestamp_ns: u64, market_state: &MarketState, account: &Account<M>);
+    fn update(
+        &mut self,
+        timestamp_ns: u64,
+        market_state: &MarketState<M::Num>,
+        account: &Account<M>,
+    );
 
     /// Log a realized profit and loss event
     ///
     /// # Arguments:
     /// `net_rpnl`: The realized profit and loss, denoted in margin currency.
+    /// `position_side`: The side of the reduced position, `Buy` for a long and `Sell` for a short.
     /// `ts_ns`: The timestamp in nanoseconds of this event.
-    fn log_rpnl(&mut self, net_rpnl: M, ts_ns: i64);
+    fn log_rpnl(&mut self, net_rpnl: M, position_side: Side, ts_ns: i64);
 
-    /// Log a fee, measured in the margin currency
+    /// Log a fee, measured in the margin currency.
+    /// A negative fee is a rebate received from the exchange.
     fn log_fee(&mut self, fee_in_margin: M);
 
     /// Log a limit order submission event
@@ -36,6 +44,9 @@ where
     /// Log a limit order cancellation event
     fn log_limit_order_cancellation(&mut self);
 
+    /// Log a limit order expiry event, due to its `TimeInForce`.
+    fn log_limit_order_expiry(&mut self);
+
     /// Log a limit order fill event.
     fn log_limit_order_fill(&mut self);
 
@@ -43,5 +54,41 @@ where
     fn log_market_order_fill(&mut self);
 
     /// Log a trade event where some order got filled and the position changed
-    fn log_trade(&mut self, side: Side, price: QuoteCurrency, quantity: M::PairedCurrency);
+    fn log_trade(&mut self, side: Side, price: QuoteCurrency<M::Num>, quantity: M::PairedCurrency);
+
+    /// Log an order that has been rejected by the `RiskEngine`.
+    fn log_risk_violation(&mut self, risk_error: &RiskError);
+
+    /// Log the interest paid on the borrowed notional of a leveraged position,
+    /// measured in the margin currency.
+    fn log_borrow_cost(&mut self, borrow_cost: M);
+
+    /// Log the interest earned on idle margin, measured in the margin currency.
+    fn log_idle_margin_interest(&mut self, interest: M);
+
+    /// Log the cost of a market order filling worse than the best bid or ask,
+    /// measured in the margin currency.
+    fn log_slippage(&mut self, slippage: M);
+
+    /// Log a bankruptcy event, where a loss exceeded the wallet balance.
+    ///
+    /// # Arguments:
+    /// `deficit`: The amount by which the wallet balance became negative, denoted in the margin currency.
+    /// `covered_by_insurance_fund`: Whether the deficit was covered due to negative balance protection.
+    /// `ts_ns`: The timestamp in nanoseconds of this event.
+    fn log_bankruptcy(&mut self, deficit: M, covered_by_insurance_fund: bool, ts_ns: i64);
+
+    /// Log an external cash flow, which changes the wallet balance without being trading profit and loss.
+    ///
+    /// # Arguments:
+    /// `amount`: A deposit if positive, a withdrawal if negative, denoted in the margin currency.
+    /// `ts_ns`: The timestamp in nanoseconds of the cash flow.
+    fn log_cash_flow(&mut self, amount: M, ts_ns: i64);
+
+    /// Log the settlement of an expired dated futures contract.
+    ///
+    /// # Arguments:
+    /// `settlement_price`: The price at which open positions got settled.
+    /// `ts_ns`: The timestamp in nanoseconds of the settlement.
+    fn log_settlement(&mut self, settlement_price: QuoteCurrency<M::Num>, ts_ns: i64);
 }
@@ -0,0 +1,148 @@
+//! Combine several `AccountTracker`s, which all receive the same updates,
+//! e.g. `(FullAccountTracker<M>, MyCsvLogger)` or `Vec<Box<dyn AccountTracker<M>>>`.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::{
+    account_tracker::AccountTracker,
+    prelude::{Account, MarketState},
+    risk_engine::RiskError,
+    types::{Currency, MarginCurrency, QuoteCurrency, Side},
+};
+
+/// Forward every method of `AccountTracker` to all trackers yielded by `$trackers`.
+macro_rules! forward_to_all {
+    ($self:ident, $trackers:expr) => {
+        fn update(&mut $self, timestamp_ns: u64, market_state: &MarketState<M::Num>, account: &Account<M>) {
+            for tracker in $trackers {
+                tracker.update(timestamp_ns, market_state, account);
+            }
+        }
+
+        fn log_rpnl(&mut $self, net_rpnl: M, position_side: Side, ts_ns: i64) {
+            for tracker in $trackers {
+                tracker.log_rpnl(net_rpnl, position_side, ts_ns);
+            }
+        }
+
+        fn log_fee(&mut $self, fee_in_margin: M) {
+            for tracker in $trackers {
+                tracker.log_fee(fee_in_margin);
+            }
+        }
+
+        fn log_limit_order_submission(&mut $self) {
+            for tracker in $trackers {
+                tracker.log_limit_order_submission();
+            }
+        }
+
+        fn log_limit_order_cancellation(&mut $self) {
+            for tracker in $trackers {
+                tracker.log_limit_order_cancellation();
+            }
+        }
+
+        fn log_limit_order_expiry(&mut $self) {
+            for tracker in $trackers {
+                tracker.log_limit_order_expiry();
+            }
+        }
+
+        fn log_limit_order_fill(&mut $self) {
+            for tracker in $trackers {
+                tracker.log_limit_order_fill();
+            }
+        }
+
+        fn log_market_order_fill(&mut $self) {
+            for tracker in $trackers {
+                tracker.log_market_order_fill();
+            }
+        }
+
+        fn log_trade(&mut $self, side: Side, price: QuoteCurrency<M::Num>, quantity: M::PairedCurrency) {
+            for tracker in $trackers {
+                tracker.log_trade(side, price, quantity);
+            }
+        }
+
+        fn log_risk_violation(&mut $self, risk_error: &RiskError) {
+            for tracker in $trackers {
+                tracker.log_risk_violation(risk_error);
+            }
+        }
+
+        fn log_borrow_cost(&mut $self, borrow_cost: M) {
+            for tracker in $trackers {
+                tracker.log_borrow_cost(borrow_cost);
+            }
+        }
+
+        fn log_idle_margin_interest(&mut $self, interest: M) {
+            for tracker in $trackers {
+                tracker.log_idle_margin_interest(interest);
+            }
+        }
+
+        fn log_slippage(&mut $self, slippage: M) {
+            for tracker in $trackers {
+                tracker.log_slippage(slippage);
+            }
+        }
+
+        fn log_bankruptcy(&mut $self, deficit: M, covered_by_insurance_fund: bool, ts_ns: i64) {
+            for tracker in $trackers {
+                tracker.log_bankruptcy(deficit, covered_by_insurance_fund, ts_ns);
+            }
+        }
+
+        fn log_cash_flow(&mut $self, amount: M, ts_ns: i64) {
+            for tracker in $trackers {
+                tracker.log_cash_flow(amount, ts_ns);
+            }
+        }
+
+        fn log_settlement(&mut $self, settlement_price: QuoteCurrency<M::Num>, ts_ns: i64) {
+            for tracker in $trackers {
+                tracker.log_settlement(settlement_price, ts_ns);
+            }
+        }
+    };
+}
+
+/// Implement `AccountTracker` for a tuple, forwarding to its elements in order.
+macro_rules! impl_tuple {
+    ($($idx:tt: $T:ident),+) => {
+        impl<M, $($T),+> AccountTracker<M> for ($($T,)+)
+        where
+            M: Currency + MarginCurrency,
+            $($T: AccountTracker<M>,)+
+        {
+            forward_to_all!(
+                self,
+                [$(&mut self.$idx as &mut dyn AccountTracker<M>),+]
+            );
+        }
+    };
+}
+
+impl_tuple!(0: A, 1: B);
+impl_tuple!(0: A, 1: B, 2: C);
+impl_tuple!(0: A, 1: B, 2: C, 3: D);
+
+impl<M, T> AccountTracker<M> for Vec<T>
+where
+    M: Currency + MarginCurrency,
+    T: AccountTracker<M>,
+{
+    forward_to_all!(self, self.iter_mut());
+}
+
+impl<M, T> AccountTracker<M> for Box<T>
+where
+    M: Currency + MarginCurrency,
+    T: AccountTracker<M> + ?Sized,
+{
+    forward_to_all!(self, core::iter::once(self.as_mut()));
+}
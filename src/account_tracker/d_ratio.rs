@@ -1,3 +1,5 @@
+use num_traits::Float;
+
 use crate::{
     cornish_fisher::cornish_fisher_value_at_risk,
     types::{Currency, LnReturns, MarginCurrency},
@@ -14,11 +16,13 @@ use crate::{
 /// - `returns_bnh`: The ln returns of buy and hold aka the market returns.
 /// - `wallet_balance_start`: The starting margin balance of the account.
 /// - `num_trading_days`: The number of trading days.
+/// - `trading_days_per_year`: The number of trading days in a year, e.g. 365 for crypto or 252 for equities.
 pub fn d_ratio<'a, M>(
     returns_account: LnReturns<'a, f64>,
     returns_bnh: LnReturns<'a, f64>,
     wallet_balance_start: M,
     num_trading_days: u64,
+    trading_days_per_year: f64,
 ) -> Result<f64>
 where
     M: Currency + MarginCurrency + Send,
@@ -33,13 +37,13 @@ where
     let roi_acc = returns_account
         .0
         .iter()
-        .fold(1.0, |acc, x| acc * x.exp())
-        .powf(365.0 / num_trading_days);
+        .fold(1.0, |acc: f64, x| acc * Float::exp(*x));
+    let roi_acc = Float::powf(roi_acc, trading_days_per_year / num_trading_days);
     let roi_bnh = returns_bnh
         .0
         .iter()
-        .fold(1.0, |acc, x| acc * x.exp())
-        .powf(365.0 / num_trading_days);
+        .fold(1.0, |acc: f64, x| acc * Float::exp(*x));
+    let roi_bnh = Float::powf(roi_bnh, trading_days_per_year / num_trading_days);
 
     Ok((1.0 + (roi_acc - roi_bnh) / roi_bnh.abs()) * (cf_var_bnh / cf_var_acc))
 }
@@ -0,0 +1,50 @@
+use crate::types::{Currency, MarginCurrency};
+
+/// The realized trades of one direction, either long or short, as tracked by the `FullAccountTracker`
+/// to tell whether an edge is one-sided.
+/// Every realization of profit and loss counts as a trade, e.g. each partial close of a position.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct DirectionStats<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The number of realized trades.
+    pub num_trades: usize,
+    /// The number of realized trades with a positive net profit and loss.
+    pub num_wins: usize,
+    /// The sum of the realized profit and loss, net of the closing fees.
+    pub net_pnl: M,
+    /// The sum of the durations in nanoseconds from the opening of the position to each realization.
+    pub total_duration_ns: i64,
+}
+
+impl<M> DirectionStats<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The fraction of realized trades with a positive net profit and loss.
+    pub fn win_ratio(&self) -> f64 {
+        if self.num_trades == 0 {
+            return 0.0;
+        }
+        self.num_wins as f64 / self.num_trades as f64
+    }
+
+    /// The average duration in nanoseconds from the opening of the position to a realization.
+    pub fn avg_duration_ns(&self) -> i64 {
+        if self.num_trades == 0 {
+            return 0;
+        }
+        self.total_duration_ns / self.num_trades as i64
+    }
+
+    pub(crate) fn log_trade(&mut self, net_pnl: M, duration_ns: i64) {
+        self.num_trades += 1;
+        if net_pnl > M::new_zero() {
+            self.num_wins += 1;
+        }
+        self.net_pnl += net_pnl;
+        self.total_duration_ns += duration_ns;
+    }
+}
@@ -0,0 +1,206 @@
+use crate::types::{Currency, Mon};
+
+/// Running drawdown statistics of the equity, which is the wallet balance including unrealized profit and loss.
+/// A drawdown lasts from the moment the equity falls below its previous peak until it reaches a new peak.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Drawdown<M>
+where
+    M: Currency,
+{
+    peak: M,
+    peak_ts_ns: i64,
+    current: M,
+    current_pct: M::Num,
+    max: M,
+    max_pct: M::Num,
+    longest_duration_ns: i64,
+    time_under_water_ns: i64,
+    num_drawdowns: usize,
+    first_ts_ns: Option<i64>,
+    last_ts_ns: i64,
+}
+
+impl<M> Drawdown<M>
+where
+    M: Currency,
+{
+    /// Create a new instance, starting with the `equity` as its peak.
+    pub fn new(equity: M) -> Self {
+        Self {
+            peak: equity,
+            peak_ts_ns: 0,
+            current: M::new_zero(),
+            current_pct: M::Num::ZERO,
+            max: M::new_zero(),
+            max_pct: M::Num::ZERO,
+            longest_duration_ns: 0,
+            time_under_water_ns: 0,
+            num_drawdowns: 0,
+            first_ts_ns: None,
+            last_ts_ns: 0,
+        }
+    }
+
+    /// Update the statistics with the `equity` observed at `ts_ns`.
+    pub fn update(&mut self, ts_ns: i64, equity: M) {
+        if self.first_ts_ns.is_none() {
+            self.first_ts_ns = Some(ts_ns);
+            self.peak_ts_ns = ts_ns;
+            self.last_ts_ns = ts_ns;
+        }
+        if self.is_under_water() {
+            self.time_under_water_ns += ts_ns - self.last_ts_ns;
+        }
+        self.last_ts_ns = ts_ns;
+        if equity >= self.peak {
+            if self.is_under_water() {
+                // The drawdown lasted until the peak was recovered.
+                self.longest_duration_ns = self.longest_duration_ns.max(ts_ns - self.peak_ts_ns);
+            }
+            self.peak = equity;
+            self.peak_ts_ns = ts_ns;
+            self.current = M::new_zero();
+            self.current_pct = M::Num::ZERO;
+            return;
+        }
+        if !self.is_under_water() {
+            self.num_drawdowns += 1;
+        }
+
+        self.current = self.peak - equity;
+        self.current_pct = if self.peak > M::new_zero() {
+            (self.current / self.peak).inner()
+        } else {
+            M::Num::ZERO
+        };
+        if self.current > self.max {
+            self.max = self.current;
+        }
+        if self.current_pct > self.max_pct {
+            self.max_pct = self.current_pct;
+        }
+        self.longest_duration_ns = self.longest_duration_ns.max(self.current_duration_ns());
+    }
+
+    /// Shift the peak by an external cash flow, so deposits and withdrawals don't count as gains or drawdowns.
+    pub(crate) fn apply_cash_flow(&mut self, amount: M) {
+        self.peak += amount;
+    }
+
+    /// Whether the equity is currently below its peak.
+    #[inline(always)]
+    pub fn is_under_water(&self) -> bool {
+        self.current > M::new_zero()
+    }
+
+    /// The highest equity observed so far.
+    #[inline(always)]
+    pub fn peak(&self) -> M {
+        self.peak
+    }
+
+    /// The current distance of the equity from its peak.
+    #[inline(always)]
+    pub fn current(&self) -> M {
+        self.current
+    }
+
+    /// The current distance of the equity from its peak as a fraction of the peak.
+    #[inline(always)]
+    pub fn current_pct(&self) -> M::Num {
+        self.current_pct
+    }
+
+    /// The duration of the current drawdown in nanoseconds, zero when at the peak.
+    pub fn current_duration_ns(&self) -> i64 {
+        if self.is_under_water() {
+            self.last_ts_ns - self.peak_ts_ns
+        } else {
+            0
+        }
+    }
+
+    /// The maximum absolute drawdown.
+    #[inline(always)]
+    pub fn max(&self) -> M {
+        self.max
+    }
+
+    /// The maximum drawdown as a fraction of the peak it started from.
+    #[inline(always)]
+    pub fn max_pct(&self) -> M::Num {
+        self.max_pct
+    }
+
+    /// The duration of the longest drawdown in nanoseconds, from its peak until the recovery,
+    /// including the current one.
+    #[inline(always)]
+    pub fn longest_duration_ns(&self) -> i64 {
+        self.longest_duration_ns
+    }
+
+    /// The total time in nanoseconds the equity spent below its peak.
+    #[inline(always)]
+    pub fn time_under_water_ns(&self) -> i64 {
+        self.time_under_water_ns
+    }
+
+    /// The fraction of the observed time the equity spent below its peak.
+    pub fn time_under_water_ratio(&self) -> f64 {
+        match self.first_ts_ns {
+            Some(first_ts_ns) if self.last_ts_ns > first_ts_ns => {
+                self.time_under_water_ns as f64 / (self.last_ts_ns - first_ts_ns) as f64
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// The number of distinct drawdowns, including the current one.
+    #[inline(always)]
+    pub fn num_drawdowns(&self) -> usize {
+        self.num_drawdowns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn drawdown() {
+        let mut dd = Drawdown::new(quote!(100));
+        dd.update(10, quote!(100));
+        assert!(!dd.is_under_water());
+
+        dd.update(20, quote!(90));
+        assert_eq!(dd.current(), quote!(10));
+        assert_eq!(dd.current_pct(), Dec!(0.1));
+        assert_eq!(dd.current_duration_ns(), 10);
+
+        dd.update(30, quote!(80));
+        dd.update(40, quote!(95));
+        assert_eq!(dd.max(), quote!(20));
+        assert_eq!(dd.max_pct(), Dec!(0.2));
+        assert_eq!(dd.longest_duration_ns(), 30);
+
+        // A new peak ends the drawdown.
+        dd.update(50, quote!(200));
+        assert!(!dd.is_under_water());
+        assert_eq!(dd.current_duration_ns(), 0);
+        assert_eq!(dd.longest_duration_ns(), 40);
+        assert_eq!(dd.time_under_water_ns(), 30);
+
+        dd.update(60, quote!(150));
+        assert_eq!(dd.current_pct(), Dec!(0.25));
+        assert_eq!(dd.max(), quote!(50));
+        assert_eq!(dd.max_pct(), Dec!(0.25));
+        assert_eq!(dd.longest_duration_ns(), 40);
+        assert_eq!(dd.num_drawdowns(), 2);
+
+        dd.update(70, quote!(150));
+        assert_eq!(dd.time_under_water_ns(), 40);
+        assert_eq!(dd.time_under_water_ratio(), 40.0 / 60.0);
+    }
+}
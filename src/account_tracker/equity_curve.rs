@@ -0,0 +1,79 @@
+use crate::types::{Currency, MarginCurrency};
+
+/// A sample of the account equity, taken by the `FullAccountTracker` at the start of every hour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct EquityPoint<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The timestamp in nanoseconds of the sample.
+    pub timestamp_ns: u64,
+    /// The realized wallet balance.
+    pub wallet_balance: M,
+    /// The unrealized profit and loss of all positions.
+    pub unrealized_pnl: M,
+    /// The net position size, negative when short.
+    pub position_size: M::PairedCurrency,
+}
+
+impl<M> EquityPoint<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The wallet balance including the unrealized profit and loss.
+    #[inline(always)]
+    pub fn equity(&self) -> M {
+        self.wallet_balance + self.unrealized_pnl
+    }
+}
+
+/// Write the `points` as CSV with a header row.
+#[cfg(feature = "std")]
+pub(crate) fn write_equity_curve_csv<M, W>(
+    points: &[EquityPoint<M>],
+    mut writer: W,
+) -> std::io::Result<()>
+where
+    M: Currency + MarginCurrency,
+    W: std::io::Write,
+{
+    writeln!(
+        writer,
+        "timestamp_ns,wallet_balance,unrealized_pnl,equity,position_size"
+    )?;
+    for point in points {
+        writeln!(
+            writer,
+            "{},{},{},{},{}",
+            point.timestamp_ns,
+            point.wallet_balance.inner(),
+            point.unrealized_pnl.inner(),
+            point.equity().inner(),
+            point.position_size.inner(),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn equity_curve_csv() {
+        let points = [EquityPoint {
+            timestamp_ns: 1,
+            wallet_balance: quote!(100),
+            unrealized_pnl: quote!(-5.5),
+            position_size: base!(0.1),
+        }];
+        let mut buf = Vec::new();
+        write_equity_curve_csv(&points, &mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "timestamp_ns,wallet_balance,unrealized_pnl,equity,position_size\n1,100,-5.5,94.5,0.1\n"
+        );
+    }
+}
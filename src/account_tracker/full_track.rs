@@ -1,21 +1,31 @@
-use std::{fmt::Display, iter::FromIterator};
+use alloc::{vec, vec::Vec};
+use core::{fmt::Display, iter::FromIterator};
 
-use fpdec::{Dec, Decimal};
+use num_traits::Float;
 
-use super::d_ratio;
+use super::{
+    d_ratio,
+    rolling::{ln_sharpe, ln_volatility, window_ranges},
+    statistical_moments, DirectionStats, Drawdown, EquityPoint, Histogram, MetricsConfig,
+    PerformanceReport, PnlAttribution, RollingWindow, StatisticalMoments, TailRisk,
+};
 use crate::{
     account_tracker::AccountTracker,
     cornish_fisher::cornish_fisher_value_at_risk,
     prelude::{Account, MarketState},
-    quote,
-    types::{Currency, LnReturns, MarginCurrency, QuoteCurrency, Side},
-    utils::{decimal_pow, decimal_sqrt, decimal_sum, decimal_to_f64, min, variance},
+    risk_engine::RiskError,
+    session_calendar::SessionCalendar,
+    types::{Currency, Error, LnReturns, MarginCurrency, Mon, QuoteCurrency, Result, Side},
+    utils::{min, pow, sum, variance},
 };
 
 const DAILY_NS: u64 = 86_400_000_000_000;
 const HOURLY_NS: u64 = 3_600_000_000_000;
 
-/// Defines the possible sources of returns to use
+/// Defines the possible sources of returns to use.
+/// Returns are sampled on calendar bars of simulated time,
+/// so they do not depend on how regular the market updates are.
+/// A bar closes with the last values observed within it and bars without any update have a return of zero.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ReturnsSource {
     /// Daily sampled returns
@@ -24,11 +34,76 @@ pub enum ReturnsSource {
     Hourly,
 }
 
+impl ReturnsSource {
+    /// The length of the calendar bars in nanoseconds, aligned to the unix epoch.
+    pub fn period_ns(&self) -> u64 {
+        match self {
+            ReturnsSource::Daily => DAILY_NS,
+            ReturnsSource::Hourly => HOURLY_NS,
+        }
+    }
+
+    /// The number of return periods in a year for markets trading around the clock,
+    /// e.g. 365 for daily returns. See `MetricsConfig::periods_per_year` for other markets.
+    pub fn periods_per_year(&self) -> f64 {
+        match self {
+            ReturnsSource::Daily => 365.0,
+            ReturnsSource::Hourly => 365.0 * 24.0,
+        }
+    }
+}
+
+/// The bar of a `ReturnsSource` which is currently being sampled.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+struct ReturnBar<M>
+where
+    M: Currency,
+{
+    /// The index of the bar since the unix epoch, none before the first update.
+    index: Option<u64>,
+    /// The profit and loss at the close of the previous bar.
+    prev_close_pnl: M,
+    /// The price at the close of the previous bar.
+    prev_close_price: QuoteCurrency<M::Num>,
+}
+
+/// The annualized risk-adjusted return ratios of the logarithmic returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RiskAdjustedReturns {
+    /// The mean return per unit of volatility.
+    pub sharpe: f64,
+    /// The mean return per unit of downside deviation.
+    pub sortino: f64,
+    /// The annual return per unit of maximum drawdown.
+    pub calmar: f64,
+}
+
+/// The year and month in UTC of a timestamp in nanoseconds since the unix epoch,
+/// after the civil from days algorithm of Howard Hinnant.
+fn year_month(ts_ns: u64) -> (i64, u32) {
+    let z = (ts_ns / DAILY_NS) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month as u32)
+}
+
 /// Keep track of many possible Account performance statistics
 /// This can be quite memory intensive, easily reaching beyond 10GB
 /// if using tick-by-tick data due to the storage of many returns
 #[derive(Debug, Clone)]
-pub struct FullAccountTracker<M> {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct FullAccountTracker<M>
+where
+    M: Currency + MarginCurrency,
+{
     wallet_balance_last: M,  // last wallet balance recording
     wallet_balance_start: M, // wallet balance at start
     wallet_balance_high: M,  // maximum wallet balance observed
@@ -41,13 +116,17 @@ pub struct FullAccountTracker<M> {
     num_losses: usize,
     num_submitted_limit_orders: usize,
     num_cancelled_limit_orders: usize,
+    num_expired_limit_orders: usize,
     num_limit_order_fills: usize,
     num_market_order_fills: usize,
     num_trading_opportunities: usize,
+    num_risk_violations: usize,
     total_turnover: M,
-    max_drawdown_wallet_balance: Decimal,
-    max_drawdown_total: Decimal,
+    max_drawdown_wallet_balance: M::Num,
+    max_drawdown_total: M::Num,
     max_drawdown_duration_hours: i64,
+    drawdown: Drawdown<M>,
+    equity_curve: Vec<EquityPoint<M>>,
     // historical daily absolute returns
     hist_returns_daily_acc: Vec<M>,
     hist_returns_daily_bnh: Vec<M>,
@@ -60,22 +139,50 @@ pub struct FullAccountTracker<M> {
     // historical hourly logarithmic returns
     hist_ln_returns_hourly_acc: Vec<f64>,
     hist_ln_returns_hourly_bnh: Vec<f64>,
-    // timestamps for when to trigger the next pnl snapshots
-    next_daily_trigger_ts: u64,
-    next_hourly_trigger_ts: u64,
-    last_daily_pnl: M,
-    last_hourly_pnl: M,
+    // timestamps of the daily and hourly samples
+    hist_ts_daily: Vec<u64>,
+    hist_ts_hourly: Vec<u64>,
+    // the current calendar bars of the pnl snapshots
+    daily_bar: ReturnBar<M>,
+    hourly_bar: ReturnBar<M>,
     last_tick_pnl: M,
+    // net deposits minus withdrawals, overall and as of the last tick
+    net_cash_flow: M,
+    last_tick_cash_flow: M,
     cumulative_fees: M,
+    cumulative_rebates: M,
+    cumulative_borrow_cost: M,
+    cumulative_idle_margin_interest: M,
+    cumulative_slippage: M,
+    settlement: Option<(i64, QuoteCurrency<M::Num>)>,
+    num_bankruptcies: usize,
+    cumulative_bankruptcy_deficit: M,
+    cumulative_insurance_fund_payout: M,
     total_profit: M,
     total_loss: M,
-    price_first: QuoteCurrency,
-    price_last: QuoteCurrency,
-    price_a_day_ago: QuoteCurrency,
-    price_an_hour_ago: QuoteCurrency,
-    price_a_tick_ago: QuoteCurrency,
+    price_first: QuoteCurrency<M::Num>,
+    price_last: QuoteCurrency<M::Num>,
+    price_a_tick_ago: QuoteCurrency<M::Num>,
     ts_first: u64,
     ts_last: u64,
+    // Derives the number of trading days, if provided.
+    session_calendar: Option<SessionCalendar>,
+    // The drawdown threshold of the `risk_of_ruin` in the summary.
+    ruin_drawdown: f64,
+    metrics_config: MetricsConfig,
+    // The fee of the fill being settled, which is attributed to the trade it realizes.
+    fill_fee: M,
+    // Whether the fill being settled realized a trade, to which its slippage belongs.
+    fill_realized: bool,
+    // The costs since the last realized trade, which belong to the open position.
+    open_attribution: PnlAttribution<M>,
+    realized_attribution: PnlAttribution<M>,
+    trade_attributions: Vec<(i64, PnlAttribution<M>)>,
+    // The timestamps in nanoseconds of the first update at which the long and short positions were open.
+    long_open_ts_ns: Option<i64>,
+    short_open_ts_ns: Option<i64>,
+    long_stats: DirectionStats<M>,
+    short_stats: DirectionStats<M>,
 }
 
 /// TODO: create its own `risk` crate out of these implementations for better
@@ -101,13 +208,17 @@ where
             num_losses: 0,
             num_submitted_limit_orders: 0,
             num_cancelled_limit_orders: 0,
+            num_expired_limit_orders: 0,
             num_limit_order_fills: 0,
             num_market_order_fills: 0,
             num_trading_opportunities: 0,
+            num_risk_violations: 0,
             total_turnover: M::new_zero(),
-            max_drawdown_wallet_balance: Decimal::from(0),
-            max_drawdown_total: Decimal::from(0),
+            max_drawdown_wallet_balance: M::Num::ZERO,
+            max_drawdown_total: M::Num::ZERO,
             max_drawdown_duration_hours: 0,
+            drawdown: Drawdown::new(starting_wb),
+            equity_curve: vec![],
             hist_returns_daily_acc: vec![],
             hist_returns_daily_bnh: vec![],
             hist_returns_hourly_acc: vec![],
@@ -116,22 +227,77 @@ where
             hist_ln_returns_daily_bnh: vec![],
             hist_ln_returns_hourly_acc: vec![],
             hist_ln_returns_hourly_bnh: vec![],
-            next_daily_trigger_ts: 0,
-            next_hourly_trigger_ts: 0,
-            last_daily_pnl: M::new_zero(),
-            last_hourly_pnl: M::new_zero(),
+            hist_ts_daily: vec![],
+            hist_ts_hourly: vec![],
+            daily_bar: ReturnBar::default(),
+            hourly_bar: ReturnBar::default(),
             last_tick_pnl: M::new_zero(),
+            net_cash_flow: M::new_zero(),
+            last_tick_cash_flow: M::new_zero(),
             cumulative_fees: M::new_zero(),
+            cumulative_rebates: M::new_zero(),
+            cumulative_borrow_cost: M::new_zero(),
+            cumulative_idle_margin_interest: M::new_zero(),
+            cumulative_slippage: M::new_zero(),
+            settlement: None,
+            num_bankruptcies: 0,
+            cumulative_bankruptcy_deficit: M::new_zero(),
+            cumulative_insurance_fund_payout: M::new_zero(),
             total_profit: M::new_zero(),
             total_loss: M::new_zero(),
-            price_first: quote!(0.0),
-            price_last: quote!(0.0),
-            price_a_day_ago: quote!(0.0),
-            price_an_hour_ago: quote!(0.0),
-            price_a_tick_ago: quote!(0.0),
+            price_first: QuoteCurrency::new_zero(),
+            price_last: QuoteCurrency::new_zero(),
+            price_a_tick_ago: QuoteCurrency::new_zero(),
             ts_first: 0,
             ts_last: 0,
+            session_calendar: None,
+            ruin_drawdown: 0.5,
+            metrics_config: MetricsConfig::default(),
+            fill_fee: M::new_zero(),
+            fill_realized: false,
+            open_attribution: PnlAttribution::default(),
+            realized_attribution: PnlAttribution::default(),
+            trade_attributions: Vec::new(),
+            long_open_ts_ns: None,
+            short_open_ts_ns: None,
+            long_stats: DirectionStats::default(),
+            short_stats: DirectionStats::default(),
+        }
+    }
+
+    /// Derive the number of trading days, e.g. for the `d_ratio`, from the `session_calendar`,
+    /// instead of counting every elapsed day.
+    #[must_use]
+    pub fn with_session_calendar(mut self, session_calendar: SessionCalendar) -> Self {
+        self.session_calendar = Some(session_calendar);
+        self
+    }
+
+    /// Annualize the ratio metrics according to the `metrics_config`,
+    /// instead of assuming a market which trades around the clock.
+    #[must_use]
+    pub fn with_metrics_config(mut self, metrics_config: MetricsConfig) -> Self {
+        self.metrics_config = metrics_config;
+        self
+    }
+
+    /// The `MetricsConfig` which annualizes the ratio metrics.
+    #[inline(always)]
+    pub fn metrics_config(&self) -> &MetricsConfig {
+        &self.metrics_config
+    }
+
+    /// The drawdown threshold, as a fraction of the starting wallet balance,
+    /// of the `risk_of_ruin` in the summary. Defaults to 0.5.
+    ///
+    /// # Returns:
+    /// An Error if `max_drawdown` is not in range (0, 1].
+    pub fn with_ruin_drawdown(mut self, max_drawdown: f64) -> Result<Self> {
+        if !(max_drawdown > 0.0 && max_drawdown <= 1.0) {
+            return Err(Error::InvalidRuinDrawdown);
         }
+        self.ruin_drawdown = max_drawdown;
+        Ok(self)
     }
 
     /// Vector of absolute returns the account has generated, including
@@ -164,19 +330,263 @@ where
 
     /// Ratio of cumulative trade profit over cumulative trade loss
     #[inline(always)]
-    pub fn profit_loss_ratio(&self) -> Decimal {
+    pub fn profit_loss_ratio(&self) -> M::Num {
         if self.total_loss == M::new_zero() {
-            return Decimal::MAX;
+            return M::Num::MAX;
         }
         (self.total_profit / self.total_loss).inner()
     }
 
+    /// The net profit and loss of every realized trade, as attributed in `trade_attributions`.
+    fn trade_pnls(&self) -> Vec<f64> {
+        self.trade_attributions
+            .iter()
+            .map(|(_, attribution)| attribution.net_pnl().inner().to_f64())
+            .collect()
+    }
+
+    /// The optimal Kelly fraction `W - (1 - W) / R` of the realized trades,
+    /// with `W` being the win ratio and `R` the ratio of the average win to the average loss.
+    /// A negative fraction means the trades have no edge.
+    ///
+    /// # Returns:
+    /// None unless there is at least one winning and one losing trade.
+    pub fn kelly_fraction(&self) -> Option<f64> {
+        let pnls = self.trade_pnls();
+        let (wins, losses): (Vec<f64>, Vec<f64>) = pnls.iter().partition(|pnl| **pnl > 0.0);
+        if wins.is_empty() || losses.is_empty() {
+            return None;
+        }
+        let win_ratio = wins.len() as f64 / pnls.len() as f64;
+        let avg_win = wins.iter().sum::<f64>() / wins.len() as f64;
+        let avg_loss = losses.iter().sum::<f64>().abs() / losses.len() as f64;
+        if avg_loss == 0.0 {
+            return None;
+        }
+        Some(win_ratio - (1.0 - win_ratio) / (avg_win / avg_loss))
+    }
+
+    /// The probability of the wallet balance ever drawing down by `max_drawdown`,
+    /// a fraction of the starting wallet balance, when repeating the realized trades.
+    /// Approximates the cumulative trade profit and loss as a brownian motion with the mean
+    /// `mu` and variance `sigma^2` of the trades, which hits a drawdown `d` with
+    /// probability `exp(-2 * mu * d / sigma^2)` if `mu > 0` and certainly otherwise.
+    ///
+    /// # Returns:
+    /// None if there are less than two realized trades.
+    pub fn risk_of_ruin(&self, max_drawdown: f64) -> Option<f64> {
+        let pnls = self.trade_pnls();
+        if pnls.len() < 2 {
+            return None;
+        }
+        let n = pnls.len() as f64;
+        let mean = pnls.iter().sum::<f64>() / n;
+        if mean <= 0.0 {
+            return Some(1.0);
+        }
+        let variance = pnls
+            .iter()
+            .map(|pnl| Float::powi(pnl - mean, 2))
+            .sum::<f64>()
+            / n;
+        if variance == 0.0 {
+            return Some(0.0);
+        }
+        let drawdown = max_drawdown * self.wallet_balance_start.inner().to_f64();
+        Some(Float::exp(-2.0 * mean * drawdown / variance).min(1.0))
+    }
+
     /// Cumulative fees paid to the exchange
     #[inline(always)]
     pub fn cumulative_fees(&self) -> M {
         self.cumulative_fees
     }
 
+    /// Cumulative rebates received from the exchange due to negative fees.
+    #[inline(always)]
+    pub fn cumulative_rebates(&self) -> M {
+        self.cumulative_rebates
+    }
+
+    /// Cumulative interest paid on the borrowed notional of leveraged positions.
+    #[inline(always)]
+    pub fn cumulative_borrow_cost(&self) -> M {
+        self.cumulative_borrow_cost
+    }
+
+    /// Cumulative interest earned on idle margin.
+    #[inline(always)]
+    pub fn cumulative_idle_margin_interest(&self) -> M {
+        self.cumulative_idle_margin_interest
+    }
+
+    /// Cumulative cost of market orders filling worse than the best bid or ask.
+    #[inline(always)]
+    pub fn cumulative_slippage(&self) -> M {
+        self.cumulative_slippage
+    }
+
+    /// The profit and loss of the run split into price moves, fees, funding and slippage,
+    /// including the unrealized profit and loss as of the last update.
+    pub fn pnl_attribution(&self) -> PnlAttribution<M> {
+        let mut attribution = self.realized_attribution;
+        attribution.merge(&self.open_attribution);
+        attribution.price_pnl += self.upnl;
+        attribution
+    }
+
+    /// The timestamp in nanoseconds and the `PnlAttribution` of every realized trade,
+    /// i.e. every fill which reduced a position and the settlement of expired contracts.
+    /// The fees, funding and slippage since the previous realized trade are attributed to it.
+    #[inline(always)]
+    pub fn trade_attributions(&self) -> &[(i64, PnlAttribution<M>)] {
+        &self.trade_attributions
+    }
+
+    /// The average `PnlAttribution` of the realized trades, if any.
+    pub fn mean_trade_attribution(&self) -> Option<PnlAttribution<M>> {
+        if self.trade_attributions.is_empty() {
+            return None;
+        }
+        let n = M::Num::from_u64(self.trade_attributions.len() as u64);
+        let total = self.realized_attribution;
+        Some(PnlAttribution {
+            price_pnl: total.price_pnl / n,
+            fees_paid: total.fees_paid / n,
+            fees_received: total.fees_received / n,
+            funding_paid: total.funding_paid / n,
+            funding_received: total.funding_received / n,
+            slippage: total.slippage / n,
+        })
+    }
+
+    /// The statistics of the trades which reduced a long position.
+    /// Durations are measured from the first update at which the position was open.
+    #[inline(always)]
+    pub fn long_stats(&self) -> &DirectionStats<M> {
+        &self.long_stats
+    }
+
+    /// The statistics of the trades which reduced a short position.
+    /// Durations are measured from the first update at which the position was open.
+    #[inline(always)]
+    pub fn short_stats(&self) -> &DirectionStats<M> {
+        &self.short_stats
+    }
+
+    /// The return of every calendar month in UTC as a fraction, compounded from the daily returns.
+    ///
+    /// # Returns:
+    /// The year, the month from 1 to 12 and the return, in chronological order.
+    pub fn monthly_returns(&self) -> Vec<(i64, u32, f64)> {
+        let mut months: Vec<(i64, u32, f64)> = Vec::new();
+        for (ts_ns, ln_ret) in self
+            .hist_ts_daily
+            .iter()
+            .zip(self.hist_ln_returns_daily_acc.iter())
+        {
+            // The sample timestamp is the close of the daily bar, which belongs to the previous day.
+            let (year, month) = year_month(ts_ns - 1);
+            match months.last_mut() {
+                Some((y, m, sum)) if *y == year && *m == month => *sum += ln_ret,
+                _ => months.push((year, month, *ln_ret)),
+            }
+        }
+        months
+            .into_iter()
+            .map(|(year, month, sum)| (year, month, Float::exp_m1(sum)))
+            .collect()
+    }
+
+    /// A summary of all metrics, e.g. to print with `println!("{}", tracker.report())`
+    /// or to export as JSON.
+    pub fn report(&self) -> PerformanceReport<M> {
+        PerformanceReport {
+            total_rpnl: self.total_rpnl,
+            upnl: self.upnl,
+            annualized_roi: self.annualized_roi(),
+            buy_and_hold_return: self.buy_and_hold_return(),
+            risk_adjusted_returns_daily: self.risk_adjusted_returns(ReturnsSource::Daily),
+            risk_adjusted_returns_hourly: self.risk_adjusted_returns(ReturnsSource::Hourly),
+            max_drawdown_wallet_balance: self.max_drawdown_wallet_balance,
+            max_drawdown_total: self.max_drawdown_total,
+            longest_drawdown_duration_ns: self.drawdown.longest_duration_ns(),
+            time_under_water_ratio: self.drawdown.time_under_water_ratio(),
+            historical_value_at_risk_daily: self
+                .historical_value_at_risk(ReturnsSource::Daily, 0.01),
+            cornish_fisher_value_at_risk_daily: self
+                .cornish_fisher_value_at_risk(ReturnsSource::Daily, 0.01)
+                .ok(),
+            d_ratio_daily: self.d_ratio(ReturnsSource::Daily).ok(),
+            num_trades: self.num_trades,
+            buy_ratio: self.buy_ratio(),
+            win_ratio: self.win_ratio(),
+            profit_loss_ratio: self.profit_loss_ratio(),
+            turnover: self.total_turnover,
+            trade_percentage: self.trade_percentage(),
+            cumulative_fees: self.cumulative_fees,
+            cumulative_rebates: self.cumulative_rebates,
+            cumulative_borrow_cost: self.cumulative_borrow_cost,
+            cumulative_idle_margin_interest: self.cumulative_idle_margin_interest,
+            cumulative_slippage: self.cumulative_slippage,
+            num_bankruptcies: self.num_bankruptcies,
+            cumulative_bankruptcy_deficit: self.cumulative_bankruptcy_deficit,
+            num_trading_days: self.num_trading_days(),
+            pnl_attribution: self.pnl_attribution(),
+            long_stats: self.long_stats,
+            short_stats: self.short_stats,
+            kelly_fraction: self.kelly_fraction(),
+            risk_of_ruin: self.risk_of_ruin(self.ruin_drawdown),
+        }
+    }
+
+    /// The number of times a loss exceeded the wallet balance.
+    #[inline(always)]
+    pub fn num_bankruptcies(&self) -> usize {
+        self.num_bankruptcies
+    }
+
+    /// The cumulative amount by which losses exceeded the wallet balance,
+    /// which quantifies the gap risk.
+    #[inline(always)]
+    pub fn cumulative_bankruptcy_deficit(&self) -> M {
+        self.cumulative_bankruptcy_deficit
+    }
+
+    /// The part of the `cumulative_bankruptcy_deficit` covered by the insurance fund
+    /// due to negative balance protection.
+    #[inline(always)]
+    pub fn cumulative_insurance_fund_payout(&self) -> M {
+        self.cumulative_insurance_fund_payout
+    }
+
+    /// Return the timestamp in nanoseconds and price of the settlement of an expired dated futures contract, if any.
+    #[inline(always)]
+    pub fn settlement(&self) -> Option<(i64, QuoteCurrency<M::Num>)> {
+        self.settlement
+    }
+
+    /// The deposits net of the withdrawals, which are excluded from the profit and loss and the returns.
+    #[inline(always)]
+    pub fn net_cash_flow(&self) -> M {
+        self.net_cash_flow
+    }
+
+    /// The time-weighted return of the account as a fraction, e.g.: 0.1 -> 10%,
+    /// which chains the returns of each sample so it is not distorted by deposits and withdrawals.
+    ///
+    /// # Parameters:
+    /// `returns_source`: the sampling interval of pnl snapshots
+    pub fn time_weighted_return(&self, returns_source: ReturnsSource) -> f64 {
+        Float::exp(self.ln_returns_acc(returns_source).iter().sum::<f64>()) - 1.0
+    }
+
+    /// The fees paid net of the rebates received.
+    #[inline(always)]
+    pub fn net_fees(&self) -> M {
+        self.cumulative_fees - self.cumulative_rebates
+    }
+
     /// Would be return of buy and hold strategy
     #[inline(always)]
     pub fn buy_and_hold_return(&self) -> M {
@@ -190,27 +600,36 @@ where
         self.buy_and_hold_return().into_negative()
     }
 
+    /// The square root of the periods per year of the `returns_source`, which annualizes a ratio.
+    fn annualization_mult(&self, returns_source: ReturnsSource) -> M::Num {
+        Mon::sqrt(
+            M::Num::from_f64(self.metrics_config.periods_per_year(returns_source))
+                .expect("The periods per year are representable; qed"),
+        )
+    }
+
+    /// The return of the starting wallet balance at the risk-free rate of the `MetricsConfig`
+    /// over a single period of the `returns_source`.
+    fn risk_free_return(&self, returns_source: ReturnsSource) -> M::Num {
+        let rate = Float::exp_m1(self.metrics_config.risk_free_ln_return(returns_source));
+        self.wallet_balance_start.inner()
+            * M::Num::from_f64(rate).expect("The risk-free rate is finite; qed")
+    }
+
     /// Return the annualized sharpe ratio using a specific sampling frequency.
     ///
     /// # Parameters:
     /// `returns_source`: the sampling interval of pnl snapshots
     /// `risk_free_is_buy_and_hold`: if true, it will use the market returns as
-    /// the risk-free comparison     else risk-free rate is zero
-    pub fn sharpe(
-        &self,
-        returns_source: ReturnsSource,
-        risk_free_is_buy_and_hold: bool,
-    ) -> Decimal {
+    /// the risk-free comparison     else the risk-free rate of the `MetricsConfig`
+    pub fn sharpe(&self, returns_source: ReturnsSource, risk_free_is_buy_and_hold: bool) -> M::Num {
         let rets_acc = match returns_source {
             ReturnsSource::Daily => &self.hist_returns_daily_acc,
             ReturnsSource::Hourly => &self.hist_returns_hourly_acc,
         };
-        let annualization_mult = match returns_source {
-            ReturnsSource::Daily => Dec!(19.10497),  // sqrt(365)
-            ReturnsSource::Hourly => Dec!(93.59487), // sqrt(365 * 24)
-        };
-        let n: Decimal = (rets_acc.len() as u64).into();
-        let mean_ret_acc: Decimal = decimal_sum(rets_acc.iter().map(|v| v.inner())) / n;
+        let annualization_mult = self.annualization_mult(returns_source);
+        let n = M::Num::from_u64(rets_acc.len() as u64);
+        let mean_ret_acc: M::Num = sum(rets_acc.iter().map(|v| v.inner())) / n;
 
         if risk_free_is_buy_and_hold {
             // Compute the mean buy and hold returns
@@ -218,26 +637,26 @@ where
                 ReturnsSource::Daily => &self.hist_returns_daily_bnh,
                 ReturnsSource::Hourly => &self.hist_returns_hourly_bnh,
             };
-            let mean_bnh_ret = decimal_sum(rets_bnh.iter().map(|v| v.inner())) / n;
+            let mean_bnh_ret = sum(rets_bnh.iter().map(|v| v.inner())) / n;
 
             // compute the difference of returns of account and market
-            let diff_returns: Vec<Decimal> =
+            let diff_returns: Vec<M::Num> =
                 rets_acc.iter().map(|v| v.inner() - mean_bnh_ret).collect();
             let var = variance(&diff_returns);
-            if var == Decimal::ZERO {
-                return Decimal::ZERO;
+            if var == M::Num::ZERO {
+                return M::Num::ZERO;
             }
-            let std_dev = decimal_sqrt(var);
+            let std_dev = Mon::sqrt(var);
 
             annualization_mult * mean_ret_acc / std_dev
         } else {
             let var = variance(&rets_acc.iter().map(|v| v.inner()).collect::<Vec<_>>());
-            if var == Decimal::ZERO {
-                return Decimal::ZERO;
+            if var == M::Num::ZERO {
+                return M::Num::ZERO;
             }
-            let std_dev = decimal_sqrt(var);
+            let std_dev = Mon::sqrt(var);
 
-            annualization_mult * mean_ret_acc / std_dev
+            annualization_mult * (mean_ret_acc - self.risk_free_return(returns_source)) / std_dev
         }
     }
 
@@ -247,25 +666,22 @@ where
     /// # Parameters:
     /// `returns_source`: the sampling interval of pnl snapshots
     /// `risk_free_is_buy_and_hold`: if true, it will use the market returns as
-    /// the risk-free comparison     else risk-free rate is zero
+    /// the risk-free comparison     else the risk-free rate of the `MetricsConfig`
     pub fn sortino(
         &self,
         returns_source: ReturnsSource,
         risk_free_is_buy_and_hold: bool,
-    ) -> Decimal {
+    ) -> M::Num {
         let rets_acc = match returns_source {
             ReturnsSource::Daily => &self.hist_returns_daily_acc,
             ReturnsSource::Hourly => &self.hist_returns_hourly_acc,
         };
         if rets_acc.is_empty() {
-            return Decimal::ZERO;
+            return M::Num::ZERO;
         }
-        let annualization_mult = match returns_source {
-            ReturnsSource::Daily => Dec!(19.10497),  // sqrt(365)
-            ReturnsSource::Hourly => Dec!(93.59487), // sqrt(365 * 24)
-        };
+        let annualization_mult = self.annualization_mult(returns_source);
 
-        let target_return: Decimal = if risk_free_is_buy_and_hold {
+        let target_return: M::Num = if risk_free_is_buy_and_hold {
             let rets_bnh = match returns_source {
                 ReturnsSource::Daily => &self.hist_returns_daily_bnh,
                 ReturnsSource::Hourly => &self.hist_returns_hourly_bnh,
@@ -274,44 +690,296 @@ where
                 !rets_bnh.is_empty(),
                 "The buy and hold returns should not be empty at this point"
             );
-            let n: Decimal = (rets_bnh.len() as u64).into();
-            decimal_sum(rets_bnh.iter().map(|v| v.inner())) / n
+            let n = M::Num::from_u64(rets_bnh.len() as u64);
+            sum(rets_bnh.iter().map(|v| v.inner())) / n
         } else {
-            Decimal::ZERO
+            self.risk_free_return(returns_source)
         };
 
-        let n: Decimal = (rets_acc.len() as u64).into();
-        let mean_acc_ret = decimal_sum(rets_acc.iter().map(|v| v.inner())) / n;
+        let n = M::Num::from_u64(rets_acc.len() as u64);
+        let mean_acc_ret = sum(rets_acc.iter().map(|v| v.inner())) / n;
 
-        let underperformance = Vec::<Decimal>::from_iter(
+        let underperformance = Vec::<M::Num>::from_iter(
             rets_acc
                 .iter()
-                .map(|v| decimal_pow(min(Decimal::ZERO, v.inner() - target_return), 2)),
+                .map(|v| pow(min(M::Num::ZERO, v.inner() - target_return), 2)),
         );
 
-        let avg_underperformance = decimal_sum(underperformance.iter().cloned()) / n;
+        let avg_underperformance = sum(underperformance.iter().cloned()) / n;
 
-        let target_downside_deviation = decimal_sqrt(avg_underperformance);
+        let target_downside_deviation = Mon::sqrt(avg_underperformance);
 
         ((mean_acc_ret - target_return) * annualization_mult) / target_downside_deviation
     }
 
+    /// The logarithmic returns of the account for the `returns_source`.
+    fn ln_returns_acc(&self, returns_source: ReturnsSource) -> &[f64] {
+        match returns_source {
+            ReturnsSource::Daily => &self.hist_ln_returns_daily_acc,
+            ReturnsSource::Hourly => &self.hist_ln_returns_hourly_acc,
+        }
+    }
+
+    /// Return the annualized sharpe ratio of the logarithmic returns in excess of the risk-free rate,
+    /// annualized according to the `MetricsConfig`.
+    ///
+    /// # Parameters:
+    /// `returns_source`: the sampling interval of pnl snapshots
+    pub fn sharpe_ratio(&self, returns_source: ReturnsSource) -> f64 {
+        ln_sharpe(
+            self.ln_returns_acc(returns_source),
+            self.metrics_config.periods_per_year(returns_source),
+            self.metrics_config.risk_free_ln_return(returns_source),
+        )
+    }
+
+    /// Close all calendar bars of the `returns_source` which ended before `timestamp_ns`,
+    /// using the last observed profit and loss and price as their close.
+    ///
+    /// # Returns:
+    /// true if `timestamp_ns` is the first update within a new bar.
+    fn sample_bars(
+        &mut self,
+        returns_source: ReturnsSource,
+        timestamp_ns: u64,
+        pnl: M,
+        price: QuoteCurrency<M::Num>,
+    ) -> bool {
+        let period_ns = returns_source.period_ns();
+        let bar = timestamp_ns / period_ns;
+        let close_pnl = self.last_tick_pnl;
+        let close_price = self.price_a_tick_ago;
+        let close_cash_flow = self.last_tick_cash_flow;
+        let equity_start = self.wallet_balance_start;
+        let bnh_qty = self.wallet_balance_start.convert(self.price_first);
+        let (state, rets_acc, rets_bnh, ln_rets_acc, ln_rets_bnh, timestamps) = match returns_source
+        {
+            ReturnsSource::Daily => (
+                &mut self.daily_bar,
+                &mut self.hist_returns_daily_acc,
+                &mut self.hist_returns_daily_bnh,
+                &mut self.hist_ln_returns_daily_acc,
+                &mut self.hist_ln_returns_daily_bnh,
+                &mut self.hist_ts_daily,
+            ),
+            ReturnsSource::Hourly => (
+                &mut self.hourly_bar,
+                &mut self.hist_returns_hourly_acc,
+                &mut self.hist_returns_hourly_bnh,
+                &mut self.hist_ln_returns_hourly_acc,
+                &mut self.hist_ln_returns_hourly_bnh,
+                &mut self.hist_ts_hourly,
+            ),
+        };
+        let Some(prev_bar) = state.index else {
+            *state = ReturnBar {
+                index: Some(bar),
+                prev_close_pnl: pnl,
+                prev_close_price: price,
+            };
+            return true;
+        };
+        if bar <= prev_bar {
+            return false;
+        }
+
+        rets_acc.push(close_pnl - state.prev_close_pnl);
+        // Cash flows during the bar are treated as if they happened at its start,
+        // so the return is not distorted by deposits and withdrawals.
+        let capital = equity_start + close_cash_flow;
+        ln_rets_acc.push(Float::ln(
+            ((capital + close_pnl) / (capital + state.prev_close_pnl))
+                .inner()
+                .to_f64(),
+        ));
+        rets_bnh.push(M::pnl(state.prev_close_price, close_price, bnh_qty));
+        ln_rets_bnh.push(Float::ln(
+            (close_price / state.prev_close_price).inner().to_f64(),
+        ));
+        timestamps.push((prev_bar + 1) * period_ns);
+
+        // Nothing changed during bars without any update.
+        for skipped_bar in prev_bar + 1..bar {
+            rets_acc.push(M::new_zero());
+            ln_rets_acc.push(0.0);
+            rets_bnh.push(M::new_zero());
+            ln_rets_bnh.push(0.0);
+            timestamps.push((skipped_bar + 1) * period_ns);
+        }
+
+        *state = ReturnBar {
+            index: Some(bar),
+            prev_close_pnl: close_pnl,
+            prev_close_price: close_price,
+        };
+        true
+    }
+
+    /// The timestamps of the samples of the `returns_source`.
+    fn sample_timestamps(&self, returns_source: ReturnsSource) -> &[u64] {
+        match returns_source {
+            ReturnsSource::Daily => &self.hist_ts_daily,
+            ReturnsSource::Hourly => &self.hist_ts_hourly,
+        }
+    }
+
+    /// Return the annualized sharpe ratio of the logarithmic returns over a rolling `window`,
+    /// e.g. `RollingWindow::Samples(30)` on daily returns for the 30-day rolling sharpe ratio.
+    ///
+    /// # Returns:
+    /// The timestamp of the last sample in each window and the ratio,
+    /// once there are enough samples to fill the first window.
+    pub fn rolling_sharpe(
+        &self,
+        returns_source: ReturnsSource,
+        window: RollingWindow,
+    ) -> Vec<(u64, f64)> {
+        let rets = self.ln_returns_acc(returns_source);
+        let ts = self.sample_timestamps(returns_source);
+        let periods_per_year = self.metrics_config.periods_per_year(returns_source);
+        let risk_free_ln_return = self.metrics_config.risk_free_ln_return(returns_source);
+        window_ranges(ts, window)
+            .into_iter()
+            .map(|range| {
+                (
+                    ts[range.end - 1],
+                    ln_sharpe(&rets[range], periods_per_year, risk_free_ln_return),
+                )
+            })
+            .collect()
+    }
+
+    /// Return the annualized volatility of the logarithmic returns over a rolling `window`.
+    ///
+    /// # Returns:
+    /// The timestamp of the last sample in each window and the volatility.
+    pub fn rolling_volatility(
+        &self,
+        returns_source: ReturnsSource,
+        window: RollingWindow,
+    ) -> Vec<(u64, f64)> {
+        let rets = self.ln_returns_acc(returns_source);
+        let ts = self.sample_timestamps(returns_source);
+        let periods_per_year = self.metrics_config.periods_per_year(returns_source);
+        window_ranges(ts, window)
+            .into_iter()
+            .map(|range| {
+                (
+                    ts[range.end - 1],
+                    ln_volatility(&rets[range], periods_per_year),
+                )
+            })
+            .collect()
+    }
+
+    /// Return the d-ratio over a rolling `window`. See `d_ratio` for details.
+    ///
+    /// # Returns:
+    /// The timestamp of the last sample in each window and the d-ratio.
+    pub fn rolling_d_ratio(
+        &self,
+        returns_source: ReturnsSource,
+        window: RollingWindow,
+    ) -> crate::Result<Vec<(u64, f64)>> {
+        let rets_acc = self.ln_returns_acc(returns_source);
+        let rets_bnh = match returns_source {
+            ReturnsSource::Daily => &self.hist_ln_returns_daily_bnh,
+            ReturnsSource::Hourly => &self.hist_ln_returns_hourly_bnh,
+        };
+        let samples_per_day = match returns_source {
+            ReturnsSource::Daily => 1,
+            ReturnsSource::Hourly => 24,
+        };
+        let ts = self.sample_timestamps(returns_source);
+        window_ranges(ts, window)
+            .into_iter()
+            .map(|range| {
+                let num_days = (range.len() / samples_per_day).max(1) as u64;
+                let ratio = d_ratio(
+                    LnReturns(&rets_acc[range.clone()]),
+                    LnReturns(&rets_bnh[range.clone()]),
+                    self.wallet_balance_start,
+                    num_days,
+                    self.metrics_config.trading_days_per_year(),
+                )?;
+                Ok((ts[range.end - 1], ratio))
+            })
+            .collect()
+    }
+
+    /// Return the annualized Sortino ratio of the logarithmic returns in excess of the risk-free rate,
+    /// which only penalizes the deviation of returns below the risk-free rate.
+    ///
+    /// # Parameters:
+    /// `returns_source`: the sampling interval of pnl snapshots
+    pub fn sortino_ratio(&self, returns_source: ReturnsSource) -> f64 {
+        let rets = self.ln_returns_acc(returns_source);
+        if rets.is_empty() {
+            return 0.0;
+        }
+        let risk_free = self.metrics_config.risk_free_ln_return(returns_source);
+        let n = rets.len() as f64;
+        let mean = rets.iter().sum::<f64>() / n - risk_free;
+        let downside_deviation = Float::sqrt(
+            rets.iter()
+                .map(|r| Float::powi((r - risk_free).min(0.0), 2))
+                .sum::<f64>()
+                / n,
+        );
+        if downside_deviation == 0.0 {
+            return 0.0;
+        }
+
+        mean / downside_deviation
+            * Float::sqrt(self.metrics_config.periods_per_year(returns_source))
+    }
+
+    /// Return the Calmar ratio, which is the annualized return of the logarithmic returns
+    /// divided by the maximum drawdown including unrealized profit and loss.
+    ///
+    /// # Parameters:
+    /// `returns_source`: the sampling interval of pnl snapshots
+    pub fn calmar_ratio(&self, returns_source: ReturnsSource) -> f64 {
+        let rets = self.ln_returns_acc(returns_source);
+        let max_drawdown = self.max_drawdown_total.to_f64();
+        if rets.is_empty() || max_drawdown == 0.0 {
+            return 0.0;
+        }
+        let mean = rets.iter().sum::<f64>() / rets.len() as f64;
+        let annual_return =
+            Float::exp(mean * self.metrics_config.periods_per_year(returns_source)) - 1.0;
+
+        annual_return / max_drawdown
+    }
+
+    /// The Sharpe, Sortino and Calmar ratios of the logarithmic returns.
+    ///
+    /// # Parameters:
+    /// `returns_source`: the sampling interval of pnl snapshots
+    pub fn risk_adjusted_returns(&self, returns_source: ReturnsSource) -> RiskAdjustedReturns {
+        RiskAdjustedReturns {
+            sharpe: self.sharpe_ratio(returns_source),
+            sortino: self.sortino_ratio(returns_source),
+            calmar: self.calmar_ratio(returns_source),
+        }
+    }
+
     /// Return the theoretical kelly leverage that would maximize the compounded growth rate,
     /// assuming the returns are normally distributed.
     /// WHICH THEY ARE NOT!
     /// I'd consider using the kelly leverage outright as "leveraging to the tits".
     /// Essentially: kelly f* = mean return / variance
-    pub fn kelly_leverage(&self, returns_source: ReturnsSource) -> Decimal {
+    pub fn kelly_leverage(&self, returns_source: ReturnsSource) -> M::Num {
         let rets_acc = match returns_source {
             ReturnsSource::Daily => &self.hist_returns_daily_acc,
             ReturnsSource::Hourly => &self.hist_returns_hourly_acc,
         };
-        let n: Decimal = (rets_acc.len() as u64).into();
-        let mean_return = decimal_sum(rets_acc.iter().map(|v| v.inner())) / n;
-        let rets_dec = Vec::<Decimal>::from_iter(rets_acc.iter().map(|v| v.inner()));
+        let n = M::Num::from_u64(rets_acc.len() as u64);
+        let mean_return = sum(rets_acc.iter().map(|v| v.inner())) / n;
+        let rets_dec = Vec::<M::Num>::from_iter(rets_acc.iter().map(|v| v.inner()));
         let variance = variance(&rets_dec);
-        if variance == Decimal::ZERO {
-            return Decimal::ZERO;
+        if variance == M::Num::ZERO {
+            return M::Num::ZERO;
         }
 
         mean_return / variance
@@ -335,8 +1003,8 @@ where
         let idx = (rets.len() as f64 * percentile) as usize;
         match rets.get(idx) {
             Some(r) => {
-                decimal_to_f64(self.wallet_balance_start.inner())
-                    - (decimal_to_f64(self.wallet_balance_start.inner()) * r.exp())
+                self.wallet_balance_start.inner().to_f64()
+                    - (self.wallet_balance_start.inner().to_f64() * Float::exp(*r))
             }
             None => 0.0,
         }
@@ -362,7 +1030,7 @@ where
         for i in n..rets.len() {
             let mut r = 1.0;
             for ret in rets.iter().take(i).skip(i - n) {
-                r *= ret.exp();
+                r *= Float::exp(*ret);
             }
             ret_streaks.push(r);
         }
@@ -371,13 +1039,40 @@ where
         let idx = (ret_streaks.len() as f64 * percentile) as usize;
         match ret_streaks.get(idx) {
             Some(r) => {
-                decimal_to_f64(self.wallet_balance_start.inner())
-                    - (decimal_to_f64(self.wallet_balance_start.inner()) * r)
+                self.wallet_balance_start.inner().to_f64()
+                    - (self.wallet_balance_start.inner().to_f64() * r)
             }
             None => 0.0,
         }
     }
 
+    /// The mean, standard deviation, skew and excess kurtosis of the logarithmic returns.
+    ///
+    /// # Parameters:
+    /// `returns_source`: the sampling interval of pnl snapshots
+    pub fn return_moments(&self, returns_source: ReturnsSource) -> StatisticalMoments {
+        statistical_moments(self.ln_returns_acc(returns_source))
+    }
+
+    /// The empirical value at risk and conditional value at risk of the logarithmic returns,
+    /// as opposed to the parametric `cornish_fisher_value_at_risk`.
+    ///
+    /// # Parameters:
+    /// `returns_source`: the sampling interval of pnl snapshots
+    /// `confidence`: in range (0.0, 1.0), usually something like 0.95 or 0.99
+    pub fn tail_risk(&self, returns_source: ReturnsSource, confidence: f64) -> TailRisk {
+        TailRisk::empirical(self.ln_returns_acc(returns_source), confidence)
+    }
+
+    /// A histogram of the logarithmic returns, e.g. to plot their distribution.
+    ///
+    /// # Parameters:
+    /// `returns_source`: the sampling interval of pnl snapshots
+    /// `num_bins`: the number of bins of equal width
+    pub fn returns_histogram(&self, returns_source: ReturnsSource, num_bins: usize) -> Histogram {
+        Histogram::new(self.ln_returns_acc(returns_source), num_bins)
+    }
+
     /// Calculate the cornish fisher value at risk of the account.
     ///
     /// # Arguments:
@@ -398,10 +1093,14 @@ where
         )
     }
 
-    /// Return the number of trading days
+    /// Return the number of trading days,
+    /// which are the trading days of the `SessionCalendar` if provided.
     #[inline(always)]
     pub fn num_trading_days(&self) -> u64 {
-        (self.ts_last - self.ts_first) / DAILY_NS
+        match &self.session_calendar {
+            Some(calendar) => calendar.num_trading_days(self.ts_first as i64, self.ts_last as i64),
+            None => (self.ts_last - self.ts_first) / DAILY_NS,
+        }
     }
 
     /// Also called discriminant-ratio, which focuses on the added value of the
@@ -426,33 +1125,35 @@ where
             LnReturns(rets_bnh),
             self.wallet_balance_start,
             self.num_trading_days(),
+            self.metrics_config.trading_days_per_year(),
         )
     }
 
-    /// Annualized return on investment as a factor, e.g.: 100% -> 2x
-    pub fn annualized_roi(&self) -> Decimal {
+    /// Annualized return on investment as a factor, e.g.: 100% -> 2x,
+    /// over the trading days per year of the `MetricsConfig`.
+    pub fn annualized_roi(&self) -> M::Num {
         let num_trading_days = if self.num_trading_days() == 0 {
             1
         } else {
             self.num_trading_days() as u32
         };
-        let power: u32 = 365 / num_trading_days;
-        decimal_pow(
-            Dec!(1) + self.total_rpnl.inner() / self.wallet_balance_start.inner(),
+        let power: u32 = self.metrics_config.trading_days_per_year() as u32 / num_trading_days;
+        pow(
+            M::Num::ONE + self.total_rpnl.inner() / self.wallet_balance_start.inner(),
             power,
         )
     }
 
     /// Maximum drawdown of the wallet balance
     #[inline(always)]
-    pub fn max_drawdown_wallet_balance(&self) -> Decimal {
+    pub fn max_drawdown_wallet_balance(&self) -> M::Num {
         self.max_drawdown_wallet_balance
     }
 
     /// Maximum drawdown of the wallet balance including unrealized profit and
     /// loss
     #[inline(always)]
-    pub fn max_drawdown_total(&self) -> Decimal {
+    pub fn max_drawdown_total(&self) -> M::Num {
         self.max_drawdown_total
     }
 
@@ -464,6 +1165,28 @@ where
         self.max_drawdown_duration_hours
     }
 
+    /// The running drawdown statistics of the wallet balance including unrealized profit and loss,
+    /// updated on every `update_state`.
+    #[inline(always)]
+    pub fn drawdown(&self) -> &Drawdown<M> {
+        &self.drawdown
+    }
+
+    /// The equity curve, sampled at the start of every hour.
+    #[inline(always)]
+    pub fn equity_curve(&self) -> &[EquityPoint<M>] {
+        &self.equity_curve
+    }
+
+    /// Write the `equity_curve` as CSV with a header row, e.g. to a file for plotting.
+    #[cfg(feature = "std")]
+    pub fn write_equity_curve_csv<W>(&self, writer: W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        super::equity_curve::write_equity_curve_csv(&self.equity_curve, writer)
+    }
+
     /// Return the number of trades the account made
     #[inline(always)]
     pub fn num_trades(&self) -> i64 {
@@ -476,6 +1199,12 @@ where
         self.num_submitted_limit_orders
     }
 
+    /// Return the number of limit orders that expired due to their `TimeInForce`.
+    #[inline(always)]
+    pub fn num_expired_limit_orders(&self) -> usize {
+        self.num_expired_limit_orders
+    }
+
     /// Return the ratio of executed trades vs total trading opportunities
     /// Higher values means a more active trading agent
     #[inline(always)]
@@ -531,6 +1260,12 @@ where
         self.num_cancelled_limit_orders as f64 / self.num_submitted_limit_orders as f64
     }
 
+    /// Return the number of orders rejected by the `RiskEngine`.
+    #[inline(always)]
+    pub fn num_risk_violations(&self) -> usize {
+        self.num_risk_violations
+    }
+
     /// The ratio of market order fills relative to total trades.
     #[inline(always)]
     pub fn market_order_trade_ratio(&self) -> f64 {
@@ -542,22 +1277,18 @@ impl<M> AccountTracker<M> for FullAccountTracker<M>
 where
     M: Currency + MarginCurrency + Send,
 {
-    fn update(&mut self, timestamp_ns: u64, market_state: &MarketState, account: &Account<M>) {
+    fn update(
+        &mut self,
+        timestamp_ns: u64,
+        market_state: &MarketState<M::Num>,
+        account: &Account<M>,
+    ) {
         let price = market_state.mid_price();
-        if price == quote!(0) {
+        if price == QuoteCurrency::new_zero() {
             trace!("Price is 0, not updating the `FullAccountTracker`");
             return;
         }
         self.price_last = price;
-        if self.price_a_day_ago.is_zero() {
-            self.price_a_day_ago = price;
-        }
-        if self.price_an_hour_ago.is_zero() {
-            self.price_an_hour_ago = price;
-        }
-        if self.price_a_tick_ago.is_zero() {
-            self.price_a_tick_ago = price;
-        }
         if self.price_first.is_zero() {
             self.price_first = price;
         }
@@ -566,67 +1297,36 @@ where
             self.ts_first = timestamp_ns;
         }
         self.ts_last = timestamp_ns;
-        let upnl = account
-            .position()
-            .unrealized_pnl(market_state.bid(), market_state.ask());
-        if timestamp_ns > self.next_daily_trigger_ts {
-            self.next_daily_trigger_ts = timestamp_ns + DAILY_NS;
-
-            // calculate daily return of account
-            let pnl = (self.total_rpnl + upnl) - self.last_daily_pnl;
-            self.hist_returns_daily_acc.push(pnl);
-
-            // calculate daily log return of account
-            let ln_ret: f64 = decimal_to_f64(
-                ((self.wallet_balance_last + upnl)
-                    / (self.wallet_balance_start + self.last_daily_pnl))
-                    .inner(),
-            )
-            .ln();
-            self.hist_ln_returns_daily_acc.push(ln_ret);
-
-            // calculate daily return of buy_and_hold
-            let bnh_qty = self.wallet_balance_start.convert(self.price_first);
-            let pnl_bnh = M::pnl(self.price_a_day_ago, price, bnh_qty);
-            self.hist_returns_daily_bnh.push(pnl_bnh);
-
-            // calculate daily log return of market
-            let ln_ret = decimal_to_f64((price / self.price_a_day_ago).inner()).ln();
-            self.hist_ln_returns_daily_bnh.push(ln_ret);
-
-            self.last_daily_pnl = self.total_rpnl + upnl;
-            self.price_a_day_ago = price;
-        }
-        if timestamp_ns > self.next_hourly_trigger_ts {
-            self.next_hourly_trigger_ts = timestamp_ns + HOURLY_NS;
-
-            // calculate hourly return of account
-            let pnl = (self.total_rpnl + upnl) - self.last_hourly_pnl;
-            self.hist_returns_hourly_acc.push(pnl);
-
-            // calculate hourly logarithmic return of account
-            let ln_ret: f64 = decimal_to_f64(
-                ((self.wallet_balance_last + upnl)
-                    / (self.wallet_balance_start + self.last_hourly_pnl))
-                    .inner(),
-            )
-            .ln();
-            self.hist_ln_returns_hourly_acc.push(ln_ret);
-
-            // calculate hourly return of buy_and_hold
-            let bnh_qty = self.wallet_balance_start.convert(self.price_first);
-            let pnl_bnh = M::pnl(self.price_an_hour_ago, price, bnh_qty);
-            self.hist_returns_hourly_bnh.push(pnl_bnh);
-
-            // calculate hourly logarithmic return of buy_and_hold
-            let ln_ret = decimal_to_f64((price / self.price_an_hour_ago).inner()).ln();
-            self.hist_ln_returns_hourly_bnh.push(ln_ret);
-
-            self.last_hourly_pnl = self.total_rpnl + upnl;
-            self.price_an_hour_ago = price;
-        }
-
-        self.last_tick_pnl = self.total_rpnl + upnl;
+        let ts_ns = timestamp_ns as i64;
+        if account.position().size() > M::PairedCurrency::new_zero() {
+            self.long_open_ts_ns.get_or_insert(ts_ns);
+        } else {
+            self.long_open_ts_ns = None;
+        }
+        if account.position().size() < M::PairedCurrency::new_zero()
+            || account.short_position().size() < M::PairedCurrency::new_zero()
+        {
+            self.short_open_ts_ns.get_or_insert(ts_ns);
+        } else {
+            self.short_open_ts_ns = None;
+        }
+        let upnl = account.unrealized_pnl(market_state.bid(), market_state.ask());
+        self.upnl = upnl;
+        self.fill_fee = M::new_zero();
+        let pnl = self.total_rpnl + upnl;
+
+        self.sample_bars(ReturnsSource::Daily, timestamp_ns, pnl, price);
+        if self.sample_bars(ReturnsSource::Hourly, timestamp_ns, pnl, price) {
+            self.equity_curve.push(EquityPoint {
+                timestamp_ns,
+                wallet_balance: self.wallet_balance_last,
+                unrealized_pnl: upnl,
+                position_size: account.position().size() + account.short_position().size(),
+            });
+        }
+
+        self.last_tick_pnl = pnl;
+        self.last_tick_cash_flow = self.net_cash_flow;
         self.price_a_tick_ago = price;
 
         // update max_drawdown_total
@@ -638,11 +1338,31 @@ where
         }
 
         // update max drawdown duration
-        self.max_drawdown_duration_hours =
-            (timestamp_ns as i64 - self.high_water_mark_ts) / HOURLY_NS as i64;
+        self.max_drawdown_duration_hours = self
+            .max_drawdown_duration_hours
+            .max((timestamp_ns as i64 - self.high_water_mark_ts) / HOURLY_NS as i64);
+
+        self.drawdown
+            .update(timestamp_ns as i64, self.wallet_balance_last + upnl);
     }
 
-    fn log_rpnl(&mut self, net_rpnl: M, ts_ns: i64) {
+    fn log_rpnl(&mut self, net_rpnl: M, position_side: Side, ts_ns: i64) {
+        let (stats, open_ts_ns) = match position_side {
+            Side::Buy => (&mut self.long_stats, self.long_open_ts_ns),
+            Side::Sell => (&mut self.short_stats, self.short_open_ts_ns),
+        };
+        stats.log_trade(
+            net_rpnl,
+            open_ts_ns.map_or(0, |open_ts_ns| ts_ns - open_ts_ns),
+        );
+
+        let mut trade = core::mem::take(&mut self.open_attribution);
+        trade.price_pnl += net_rpnl + self.fill_fee;
+        self.fill_fee = M::new_zero();
+        self.fill_realized = true;
+        self.realized_attribution.merge(&trade);
+        self.trade_attributions.push((ts_ns, trade));
+
         self.total_rpnl += net_rpnl;
         self.wallet_balance_last += net_rpnl;
         if net_rpnl < M::new_zero() {
@@ -665,7 +1385,13 @@ where
 
     #[inline(always)]
     fn log_fee(&mut self, fee_in_margin: M) {
-        self.cumulative_fees += fee_in_margin
+        self.fill_fee = fee_in_margin;
+        self.open_attribution.add_fee(fee_in_margin);
+        if fee_in_margin >= M::new_zero() {
+            self.cumulative_fees += fee_in_margin
+        } else {
+            self.cumulative_rebates += fee_in_margin.abs()
+        }
     }
 
     #[inline(always)]
@@ -678,6 +1404,11 @@ where
         self.num_cancelled_limit_orders += 1;
     }
 
+    #[inline(always)]
+    fn log_limit_order_expiry(&mut self) {
+        self.num_expired_limit_orders += 1;
+    }
+
     #[inline(always)]
     fn log_limit_order_fill(&mut self) {
         self.num_limit_order_fills += 1;
@@ -688,83 +1419,87 @@ where
         self.num_market_order_fills += 1;
     }
 
-    fn log_trade(&mut self, side: Side, price: QuoteCurrency, quantity: M::PairedCurrency) {
+    fn log_trade(&mut self, side: Side, price: QuoteCurrency<M::Num>, quantity: M::PairedCurrency) {
+        self.fill_fee = M::new_zero();
+        self.fill_realized = false;
         self.total_turnover += quantity.abs().convert(price);
         self.num_trades += 1;
         if let Side::Buy = side {
             self.num_buys += 1
         }
     }
+
+    #[inline(always)]
+    fn log_risk_violation(&mut self, _risk_error: &RiskError) {
+        self.num_risk_violations += 1;
+    }
+
+    #[inline(always)]
+    fn log_borrow_cost(&mut self, borrow_cost: M) {
+        self.cumulative_borrow_cost += borrow_cost;
+        self.open_attribution.add_funding(borrow_cost);
+    }
+
+    fn log_idle_margin_interest(&mut self, interest: M) {
+        self.cumulative_idle_margin_interest += interest;
+        self.open_attribution.add_funding(interest.into_negative());
+    }
+
+    fn log_slippage(&mut self, slippage: M) {
+        self.cumulative_slippage += slippage;
+        match self.trade_attributions.last_mut() {
+            Some((_, trade)) if self.fill_realized => {
+                trade.add_slippage(slippage);
+                self.realized_attribution.add_slippage(slippage);
+            }
+            _ => self.open_attribution.add_slippage(slippage),
+        }
+    }
+
+    fn log_bankruptcy(&mut self, deficit: M, covered_by_insurance_fund: bool, _ts_ns: i64) {
+        self.num_bankruptcies += 1;
+        self.cumulative_bankruptcy_deficit += deficit;
+        if covered_by_insurance_fund {
+            self.cumulative_insurance_fund_payout += deficit;
+        }
+    }
+
+    fn log_cash_flow(&mut self, amount: M, _ts_ns: i64) {
+        self.net_cash_flow += amount;
+        // Cash flows are neither gains nor losses, so the high water marks move along.
+        self.wallet_balance_last += amount;
+        self.wallet_balance_high += amount;
+        self.drawdown.apply_cash_flow(amount);
+    }
+
+    #[inline(always)]
+    fn log_settlement(&mut self, settlement_price: QuoteCurrency<M::Num>, ts_ns: i64) {
+        self.settlement = Some((ts_ns, settlement_price));
+    }
 }
 
 impl<M> Display for FullAccountTracker<M>
 where
     M: Currency + MarginCurrency + Send,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "
-rpnl: {},
-annualized_roi: {},
-sharpe_daily_returns: {},
-sharpe_hourly_returns: {},
-sortino_daily_returns: {},
-sortino_hourly_returns: {},
-drawdown_wallet_balance: {},
-drawdown_total: {},
-historical_value_at_risk_daily: {},
-historical_value_at_risk_hourly: {},
-cornish_fisher_value_at_risk_daily: {:?},
-d_ratio_daily: {:?},
-d_ratio_hourly: {:?},
-num_trades: {},
-buy_ratio: {},
-turnover: {},
-win_ratio: {},
-profit_loss_ratio: {},
-buy_and_hold_returns: {},
-trade_percentage: {},
-cumulative_fees: {},
-num_trading_days: {},
-            ",
-            self.total_rpnl(),
-            self.annualized_roi(),
-            self.sharpe(ReturnsSource::Daily, true),
-            self.sharpe(ReturnsSource::Hourly, true),
-            self.sortino(ReturnsSource::Daily, true),
-            self.sortino(ReturnsSource::Hourly, true),
-            self.max_drawdown_wallet_balance(),
-            self.max_drawdown_total(),
-            self.historical_value_at_risk(ReturnsSource::Daily, 0.01),
-            self.historical_value_at_risk_from_n_hourly_returns(24, 0.01),
-            self.cornish_fisher_value_at_risk(ReturnsSource::Daily, 0.01),
-            self.d_ratio(ReturnsSource::Daily),
-            self.d_ratio(ReturnsSource::Hourly),
-            self.num_trades(),
-            self.buy_ratio(),
-            self.turnover(),
-            self.win_ratio(),
-            self.profit_loss_ratio(),
-            self.buy_and_hold_return(),
-            self.trade_percentage(),
-            self.cumulative_fees(),
-            self.num_trading_days(),
-        )
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.report())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::convert::TryFrom;
+    use core::convert::TryFrom;
 
-    use fpdec::Round;
+    use fpdec::{Dec, Decimal, Round};
 
     use super::*;
     use crate::{
-        prelude::PriceFilter,
+        base,
+        prelude::{BaseCurrency, PriceFilter},
+        quote,
         test_helpers::LN_RETS_H,
-        utils::{f64_to_decimal, tests::round},
+        utils::{decimal_to_f64, f64_to_decimal, tests::round},
     };
 
     // Example pulled from the following article about the Sortino ratio:
@@ -789,6 +1524,12 @@ mod tests {
         at.log_fee(quote!(0.1));
         at.log_fee(quote!(0.2));
         assert_eq!(at.cumulative_fees(), quote!(0.3));
+        assert_eq!(at.cumulative_rebates(), quote!(0));
+
+        at.log_fee(quote!(-0.05));
+        assert_eq!(at.cumulative_fees(), quote!(0.3));
+        assert_eq!(at.cumulative_rebates(), quote!(0.05));
+        assert_eq!(at.net_fees(), quote!(0.25));
     }
 
     #[test]
@@ -831,7 +1572,7 @@ mod tests {
             .collect();
         let mut acc_tracker = FullAccountTracker::new(quote!(100.0));
         for r in rpnls {
-            acc_tracker.log_rpnl(QuoteCurrency::new(r), 0);
+            acc_tracker.log_rpnl(QuoteCurrency::new(r), Side::Buy, 0);
         }
 
         assert_eq!(
@@ -857,6 +1598,183 @@ mod tests {
         assert_eq!(acc_tracker.buy_and_hold_return(), quote!(100.0));
     }
 
+    #[test]
+    fn acc_tracker_drawdown() {
+        let mut acc_tracker = FullAccountTracker::new(quote!(100.0));
+        let market_state = mock_market_state_from_mid_price(quote!(100.0));
+        acc_tracker.update(0, &market_state, &Account::default());
+        acc_tracker.log_rpnl(quote!(-10), Side::Buy, 1);
+        acc_tracker.update(HOURLY_NS, &market_state, &Account::default());
+        assert_eq!(acc_tracker.drawdown().current(), quote!(10));
+        assert_eq!(acc_tracker.drawdown().current_pct(), Dec!(0.1));
+
+        acc_tracker.log_rpnl(quote!(20), Side::Buy, 2);
+        acc_tracker.update(2 * HOURLY_NS, &market_state, &Account::default());
+        assert!(!acc_tracker.drawdown().is_under_water());
+        assert_eq!(acc_tracker.drawdown().max(), quote!(10));
+        assert_eq!(
+            acc_tracker.drawdown().longest_duration_ns(),
+            2 * HOURLY_NS as i64
+        );
+        assert_eq!(acc_tracker.drawdown().time_under_water_ratio(), 0.5);
+    }
+
+    #[test]
+    fn acc_tracker_equity_curve() {
+        let mut acc_tracker = FullAccountTracker::new(quote!(100.0));
+        let market_state = mock_market_state_from_mid_price(quote!(100.0));
+        acc_tracker.update(1, &market_state, &Account::default());
+        acc_tracker.log_rpnl(quote!(-10), Side::Buy, 2);
+        // Not sampled within the same hour.
+        acc_tracker.update(2, &market_state, &Account::default());
+        acc_tracker.update(HOURLY_NS + 2, &market_state, &Account::default());
+
+        let curve = acc_tracker.equity_curve();
+        assert_eq!(curve.len(), 2);
+        assert_eq!(curve[0].timestamp_ns, 1);
+        assert_eq!(curve[0].wallet_balance, quote!(100));
+        assert_eq!(curve[1].equity(), quote!(90));
+        assert_eq!(curve[1].position_size, base!(0));
+
+        #[cfg(feature = "std")]
+        {
+            let mut csv = Vec::new();
+            acc_tracker.write_equity_curve_csv(&mut csv).unwrap();
+            assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 3);
+        }
+    }
+
+    #[test]
+    fn acc_tracker_rolling_metrics() {
+        let mut at = FullAccountTracker::new(quote!(100.0));
+        at.hist_ln_returns_hourly_acc = LN_RETS_H[..48].to_vec();
+        at.hist_ln_returns_hourly_bnh = LN_RETS_H[1..49].to_vec();
+        at.hist_ts_hourly = (0..48).map(|i| i * HOURLY_NS).collect();
+
+        let sharpe = at.rolling_sharpe(ReturnsSource::Hourly, RollingWindow::Samples(24));
+        assert_eq!(sharpe.len(), 25);
+        assert_eq!(sharpe[0].0, 23 * HOURLY_NS);
+        assert_eq!(sharpe[0].1, ln_sharpe(&LN_RETS_H[..24], 8760.0, 0.0));
+
+        // The same windows in terms of simulated time.
+        let volatility = at.rolling_volatility(
+            ReturnsSource::Hourly,
+            RollingWindow::Duration(24 * HOURLY_NS),
+        );
+        assert_eq!(volatility.len(), 24);
+        assert_eq!(volatility[0].0, 24 * HOURLY_NS);
+        assert_eq!(volatility[0].1, ln_volatility(&LN_RETS_H[1..25], 8760.0));
+
+        let d_ratio = at
+            .rolling_d_ratio(ReturnsSource::Hourly, RollingWindow::Samples(24))
+            .unwrap();
+        assert_eq!(d_ratio.len(), 25);
+        assert!(d_ratio.iter().all(|(_, ratio)| ratio.is_finite()));
+    }
+
+    #[test]
+    fn acc_tracker_kelly_fraction_and_risk_of_ruin() {
+        let mut at = FullAccountTracker::new(quote!(100.0));
+        assert_eq!(at.kelly_fraction(), None);
+        assert_eq!(at.risk_of_ruin(0.5), None);
+        for (i, pnl) in [quote!(20), quote!(-10), quote!(20), quote!(-10)]
+            .into_iter()
+            .enumerate()
+        {
+            at.log_rpnl(pnl, Side::Buy, i as i64);
+        }
+        assert_eq!(at.kelly_fraction(), Some(0.25));
+        // mean 5, variance 225
+        assert_eq!(
+            round(at.risk_of_ruin(0.5).unwrap(), 4),
+            round((-2.0 * 5.0 * 50.0 / 225.0_f64).exp(), 4)
+        );
+        assert!(at.risk_of_ruin(0.1).unwrap() > at.risk_of_ruin(0.5).unwrap());
+        assert_eq!(
+            at.with_ruin_drawdown(0.0).map(|_| ()),
+            Err(Error::InvalidRuinDrawdown)
+        );
+
+        let mut losing = FullAccountTracker::new(quote!(100.0));
+        losing.log_rpnl(quote!(-1), Side::Sell, 0);
+        losing.log_rpnl(quote!(-2), Side::Sell, 1);
+        assert_eq!(losing.kelly_fraction(), None);
+        assert_eq!(losing.risk_of_ruin(0.5), Some(1.0));
+    }
+
+    #[test]
+    fn acc_tracker_year_month() {
+        assert_eq!(year_month(0), (1970, 1));
+        // 2024-02-29 12:00 UTC
+        assert_eq!(year_month(1_709_208_000_000_000_000), (2024, 2));
+        // 2023-12-31 23:59:59 UTC
+        assert_eq!(year_month(1_704_067_199_000_000_000), (2023, 12));
+    }
+
+    #[test]
+    fn acc_tracker_monthly_returns() {
+        let mut at = FullAccountTracker::new(quote!(100.0));
+        // The daily bars closing on the 1st and 2nd of February 1970.
+        at.hist_ts_daily = vec![31 * DAILY_NS, 32 * DAILY_NS, 33 * DAILY_NS];
+        at.hist_ln_returns_daily_acc = vec![0.01, 0.02, -0.01];
+        let months = at.monthly_returns();
+        assert_eq!(months.len(), 2);
+        assert_eq!((months[0].0, months[0].1), (1970, 1));
+        assert_eq!(months[0].2, 0.01_f64.exp_m1());
+        assert_eq!((months[1].0, months[1].1), (1970, 2));
+        assert_eq!(round(months[1].2, 12), round(0.01_f64.exp_m1(), 12));
+    }
+
+    #[test]
+    fn acc_tracker_calendar_bars() {
+        let mut at = FullAccountTracker::new(quote!(100.0));
+        let market_state = mock_market_state_from_mid_price(quote!(100.0));
+        at.update(HOURLY_NS / 4, &market_state, &Account::default());
+        at.log_rpnl(quote!(10), Side::Buy, 0);
+        at.update(HOURLY_NS / 2, &market_state, &Account::default());
+        assert!(at.ln_returns(&ReturnsSource::Hourly).is_empty());
+
+        // The first bar closes with the last update within it, the two bars without updates are flat.
+        at.log_rpnl(quote!(11), Side::Buy, 0);
+        at.update(3 * HOURLY_NS + 1, &market_state, &Account::default());
+        assert_eq!(
+            at.absolute_returns(&ReturnsSource::Hourly),
+            &vec![quote!(10), quote!(0), quote!(0)]
+        );
+        assert_eq!(
+            at.ln_returns(&ReturnsSource::Hourly)[0],
+            (110.0_f64 / 100.0).ln()
+        );
+        assert_eq!(
+            at.hist_ts_hourly,
+            vec![HOURLY_NS, 2 * HOURLY_NS, 3 * HOURLY_NS]
+        );
+        assert!(at.ln_returns(&ReturnsSource::Daily).is_empty());
+
+        at.update(DAILY_NS, &market_state, &Account::default());
+        assert_eq!(
+            at.absolute_returns(&ReturnsSource::Daily),
+            &vec![quote!(21)]
+        );
+        assert_eq!(at.absolute_returns(&ReturnsSource::Hourly).len(), 24);
+    }
+
+    #[test]
+    fn acc_tracker_return_distribution() {
+        let mut at = FullAccountTracker::new(quote!(100.0));
+        at.hist_ln_returns_hourly_acc = LN_RETS_H.to_vec();
+
+        let moments = at.return_moments(ReturnsSource::Hourly);
+        assert_eq!(moments.skew, statistical_moments(&LN_RETS_H).skew);
+
+        let tail_risk = at.tail_risk(ReturnsSource::Hourly, 0.99);
+        assert!(tail_risk.value_at_risk > 0.0);
+        assert!(tail_risk.conditional_value_at_risk >= tail_risk.value_at_risk);
+
+        let histogram = at.returns_histogram(ReturnsSource::Hourly, 10);
+        assert_eq!(histogram.counts().iter().sum::<usize>(), LN_RETS_H.len());
+    }
+
     #[test]
     fn acc_tracker_sell_and_hold() {
         let mut acc_tracker = FullAccountTracker::new(quote!(100.0));
@@ -875,7 +1793,7 @@ mod tests {
 
     #[test]
     fn acc_tracker_historical_value_at_risk() {
-        if let Err(_e) = pretty_env_logger::try_init() {}
+        let _ = pretty_env_logger::try_init();
 
         let mut acc_tracker = FullAccountTracker::new(quote!(100.0));
         acc_tracker.hist_ln_returns_hourly_acc = LN_RETS_H.into();
@@ -898,7 +1816,7 @@ mod tests {
 
     #[test]
     fn acc_tracker_historical_value_at_risk_from_n_hourly_returns() {
-        if let Err(_) = pretty_env_logger::try_init() {}
+        let _ = pretty_env_logger::try_init();
 
         let mut at = FullAccountTracker::new(quote!(100.0));
         at.hist_ln_returns_hourly_acc = LN_RETS_H.into();
@@ -921,7 +1839,7 @@ mod tests {
 
     #[test]
     fn acc_tracker_cornish_fisher_value_at_risk() {
-        if let Err(_e) = pretty_env_logger::try_init() {}
+        let _ = pretty_env_logger::try_init();
 
         let mut acc_tracker = FullAccountTracker::new(quote!(100.0));
         acc_tracker.hist_ln_returns_hourly_acc = LN_RETS_H.into();
@@ -954,7 +1872,7 @@ mod tests {
 
     #[test]
     fn acc_tracker_sortino() {
-        if let Err(_) = pretty_env_logger::try_init() {}
+        let _ = pretty_env_logger::try_init();
 
         let mut at = FullAccountTracker::new(quote!(100.0));
 
@@ -964,11 +1882,68 @@ mod tests {
                 .map(|v| QuoteCurrency::new(f64_to_decimal(*v, Dec!(0.001)))),
         );
 
-        const EXPECTED_SORTINO_RATIO: Decimal = Dec!(413.434120785921266504);
+        const EXPECTED_SORTINO_RATIO: Decimal = Dec!(413.434127853558555375);
 
         assert!(
             at.sortino(ReturnsSource::Hourly, false) - EXPECTED_SORTINO_RATIO
                 < Dec!(0.0000000000000001),
         );
     }
+
+    #[test]
+    fn acc_tracker_risk_adjusted_returns() {
+        let mut at = FullAccountTracker::new(quote!(100.0));
+        assert_eq!(
+            at.risk_adjusted_returns(ReturnsSource::Daily),
+            RiskAdjustedReturns {
+                sharpe: 0.0,
+                sortino: 0.0,
+                calmar: 0.0,
+            }
+        );
+
+        at.hist_ln_returns_daily_acc = vec![0.02, -0.01, 0.02, -0.01];
+        at.max_drawdown_total = Dec!(0.1);
+        let mut at = at.with_metrics_config(
+            MetricsConfig::default()
+                .with_trading_days_per_year(4.0)
+                .unwrap(),
+        );
+        // mean = 0.005, std_dev = 0.015, downside deviation = sqrt(0.0001 / 2)
+        assert_eq!(round(at.sharpe_ratio(ReturnsSource::Daily), 6), 0.666667);
+        assert_eq!(
+            round(
+                at.clone()
+                    .with_metrics_config(
+                        MetricsConfig::default()
+                            .with_trading_days_per_year(9.0)
+                            .unwrap()
+                    )
+                    .sortino_ratio(ReturnsSource::Daily),
+                6
+            ),
+            2.12132
+        );
+        // exp(0.005 * 4) - 1 = 0.020201
+        assert_eq!(round(at.calmar_ratio(ReturnsSource::Daily), 6), 0.202013);
+        assert_eq!(
+            at.risk_adjusted_returns(ReturnsSource::Daily).sharpe,
+            at.sharpe_ratio(ReturnsSource::Daily)
+        );
+
+        at.hist_returns_daily_acc = vec![quote!(2), quote!(-1), quote!(2), quote!(-1)];
+        let sharpe = at.sharpe(ReturnsSource::Daily, false);
+        let sortino = at.sortino(ReturnsSource::Daily, false);
+
+        // A risk-free rate equal to the mean return leaves no excess return.
+        at.metrics_config = at
+            .metrics_config
+            .with_risk_free_rate(0.02_f64.exp() - 1.0)
+            .unwrap();
+        assert_eq!(round(at.sharpe_ratio(ReturnsSource::Daily), 6), 0.0);
+        assert_eq!(round(at.sortino_ratio(ReturnsSource::Daily), 6), 0.0);
+        // The absolute returns are measured against the risk-free return of the starting wallet balance.
+        assert!(at.sharpe(ReturnsSource::Daily, false) < sharpe);
+        assert!(at.sortino(ReturnsSource::Daily, false) < sortino);
+    }
 }
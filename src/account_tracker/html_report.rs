@@ -0,0 +1,233 @@
+//! Renders a self-contained HTML report of the `FullAccountTracker` with inline SVG charts.
+
+use std::{fmt::Display, io::Write, ops::Range};
+
+use plotters::prelude::*;
+
+use super::FullAccountTracker;
+use crate::types::{Currency, MarginCurrency, Mon};
+
+const DAILY_NS: f64 = 86_400_000_000_000.0;
+const CHART_SIZE: (u32, u32) = (960, 360);
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+fn to_io_error(err: impl Display) -> std::io::Error {
+    std::io::Error::other(err.to_string())
+}
+
+/// The range covering all `values`, padded so a constant series is still visible.
+fn padded_range(values: impl Iterator<Item = f64>) -> Range<f64> {
+    let (min, max) = values.fold((f64::MAX, f64::MIN), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    if min > max {
+        return 0.0..1.0;
+    }
+    let pad = ((max - min) * 0.05).max(max.abs() * 0.01).max(1e-9);
+    (min - pad)..(max + pad)
+}
+
+/// A line chart of `points` with the elapsed days on the x axis.
+fn line_chart(title: &str, y_desc: &str, points: &[(f64, f64)]) -> std::io::Result<String> {
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, CHART_SIZE).into_drawing_area();
+        root.fill(&WHITE).map_err(to_io_error)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption(title, ("sans-serif", 20))
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(70)
+            .build_cartesian_2d(
+                padded_range(points.iter().map(|(x, _)| *x)),
+                padded_range(points.iter().map(|(_, y)| *y)),
+            )
+            .map_err(to_io_error)?;
+        chart
+            .configure_mesh()
+            .x_desc("days")
+            .y_desc(y_desc)
+            .draw()
+            .map_err(to_io_error)?;
+        chart
+            .draw_series(LineSeries::new(points.iter().copied(), &BLUE))
+            .map_err(to_io_error)?;
+        root.present().map_err(to_io_error)?;
+    }
+    Ok(svg)
+}
+
+/// A scatter chart of the net profit and loss of the realized trades over the elapsed days.
+fn trade_scatter(points: &[(f64, f64)]) -> std::io::Result<String> {
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, CHART_SIZE).into_drawing_area();
+        root.fill(&WHITE).map_err(to_io_error)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption("Realized trades", ("sans-serif", 20))
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(70)
+            .build_cartesian_2d(
+                padded_range(points.iter().map(|(x, _)| *x)),
+                padded_range(points.iter().map(|(_, y)| *y).chain([0.0])),
+            )
+            .map_err(to_io_error)?;
+        chart
+            .configure_mesh()
+            .x_desc("days")
+            .y_desc("net pnl")
+            .draw()
+            .map_err(to_io_error)?;
+        chart
+            .draw_series(points.iter().map(|(x, y)| {
+                let color = if *y >= 0.0 { GREEN } else { RED };
+                Circle::new((*x, *y), 3, color.filled())
+            }))
+            .map_err(to_io_error)?;
+        root.present().map_err(to_io_error)?;
+    }
+    Ok(svg)
+}
+
+/// A heatmap of the monthly returns with a row per year, green for gains and red for losses.
+fn monthly_heatmap(monthly_returns: &[(i64, u32, f64)]) -> std::io::Result<String> {
+    let first_year = monthly_returns.first().map_or(1970, |(year, _, _)| *year);
+    let last_year = monthly_returns.last().map_or(1970, |(year, _, _)| *year);
+    let num_years = (last_year - first_year + 1) as u32;
+    let max_abs = monthly_returns
+        .iter()
+        .fold(0.0_f64, |acc, (_, _, ret)| acc.max(ret.abs()))
+        .max(1e-9);
+
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, (CHART_SIZE.0, 80 + 40 * num_years))
+            .into_drawing_area();
+        root.fill(&WHITE).map_err(to_io_error)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption("Monthly returns", ("sans-serif", 20))
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(70)
+            .build_cartesian_2d(0.0..12.0, 0.0..num_years as f64)
+            .map_err(to_io_error)?;
+        chart
+            .configure_mesh()
+            .disable_mesh()
+            .x_labels(0)
+            .y_labels(0)
+            .draw()
+            .map_err(to_io_error)?;
+        for (month, name) in MONTHS.iter().enumerate() {
+            chart
+                .draw_series([Text::new(
+                    name.to_string(),
+                    (month as f64 + 0.4, 0.0),
+                    ("sans-serif", 12),
+                )])
+                .map_err(to_io_error)?;
+        }
+        for year in first_year..=last_year {
+            let row = (last_year - year) as f64;
+            chart
+                .draw_series([Text::new(
+                    year.to_string(),
+                    (-0.6, row + 0.6),
+                    ("sans-serif", 12),
+                )])
+                .map_err(to_io_error)?;
+        }
+        for (year, month, ret) in monthly_returns.iter() {
+            let row = (last_year - year) as f64;
+            let col = (*month - 1) as f64;
+            let intensity = (ret.abs() / max_abs * 200.0) as u8;
+            let color = if *ret >= 0.0 {
+                RGBColor(255 - intensity, 255, 255 - intensity)
+            } else {
+                RGBColor(255, 255 - intensity, 255 - intensity)
+            };
+            chart
+                .draw_series([Rectangle::new(
+                    [(col, row), (col + 1.0, row + 1.0)],
+                    color.filled(),
+                )])
+                .map_err(to_io_error)?;
+            chart
+                .draw_series([Text::new(
+                    format!("{:.1}%", ret * 100.0),
+                    (col + 0.3, row + 0.6),
+                    ("sans-serif", 12),
+                )])
+                .map_err(to_io_error)?;
+        }
+        root.present().map_err(to_io_error)?;
+    }
+    Ok(svg)
+}
+
+impl<M> FullAccountTracker<M>
+where
+    M: Currency + MarginCurrency + Send,
+{
+    /// Write a self-contained HTML report with the `PerformanceReport`,
+    /// the equity curve, the drawdown, a heatmap of the monthly returns and a scatter of the realized trades.
+    /// The charts are inline SVG, so the file can be shared without any other assets.
+    pub fn write_html_report<W>(&self, mut writer: W) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        let first_ts_ns = self
+            .equity_curve()
+            .first()
+            .map_or(0, |point| point.timestamp_ns);
+        let days = |ts_ns: u64| ts_ns.saturating_sub(first_ts_ns) as f64 / DAILY_NS;
+
+        let equity = Vec::from_iter(self.equity_curve().iter().map(|point| {
+            (
+                days(point.timestamp_ns),
+                (point.wallet_balance + point.unrealized_pnl)
+                    .inner()
+                    .to_f64(),
+            )
+        }));
+        let mut high = f64::MIN;
+        let drawdown = Vec::from_iter(equity.iter().map(|(x, equity)| {
+            high = high.max(*equity);
+            (*x, (equity / high - 1.0) * 100.0)
+        }));
+        let trades = Vec::from_iter(self.trade_attributions().iter().map(|(ts_ns, trade)| {
+            (
+                days((*ts_ns).max(0) as u64),
+                trade.net_pnl().inner().to_f64(),
+            )
+        }));
+
+        writeln!(
+            writer,
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Backtest report</title>
+<style>body {{ font-family: sans-serif; margin: 2em; }} pre {{ font-size: 13px; }}</style>
+</head>
+<body>
+<h1>Backtest report</h1>
+<h2>Summary</h2>
+<pre>{}</pre>",
+            self.report()
+        )?;
+        for (title, svg) in [
+            ("Equity curve", line_chart("Equity", "equity", &equity)?),
+            ("Drawdown", line_chart("Drawdown", "%", &drawdown)?),
+            ("Monthly returns", monthly_heatmap(&self.monthly_returns())?),
+            ("Realized trades", trade_scatter(&trades)?),
+        ] {
+            writeln!(writer, "<h2>{title}</h2>\n{svg}")?;
+        }
+        writeln!(writer, "</body>\n</html>")
+    }
+}
@@ -0,0 +1,173 @@
+use num_traits::Float;
+
+use super::ReturnsSource;
+use crate::types::{Error, Result};
+
+/// The calendar of the traded market, which determines how the `FullAccountTracker` annualizes its metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MarketCalendar {
+    /// Trading around the clock on 365 days per year, as crypto venues do.
+    #[default]
+    Crypto,
+    /// Trading 6.5 hours a day on 252 days per year, as equity venues do.
+    TradFi,
+}
+
+impl MarketCalendar {
+    /// The number of trading days per year.
+    pub fn trading_days_per_year(&self) -> f64 {
+        match self {
+            MarketCalendar::Crypto => 365.0,
+            MarketCalendar::TradFi => 252.0,
+        }
+    }
+
+    /// The number of trading hours per trading day.
+    pub fn trading_hours_per_day(&self) -> f64 {
+        match self {
+            MarketCalendar::Crypto => 24.0,
+            MarketCalendar::TradFi => 6.5,
+        }
+    }
+}
+
+/// Configures how the `FullAccountTracker` annualizes its ratio metrics,
+/// so backtests of markets which don't trade around the clock aren't inflated.
+/// Defaults to the `MarketCalendar::Crypto` with a risk-free rate of zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MetricsConfig {
+    calendar: MarketCalendar,
+    trading_days_per_year: f64,
+    trading_hours_per_day: f64,
+    risk_free_rate: f64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self::new(MarketCalendar::default())
+    }
+}
+
+impl MetricsConfig {
+    /// Create a new `MetricsConfig` with the trading days and hours of the `calendar`.
+    pub fn new(calendar: MarketCalendar) -> Self {
+        Self {
+            calendar,
+            trading_days_per_year: calendar.trading_days_per_year(),
+            trading_hours_per_day: calendar.trading_hours_per_day(),
+            risk_free_rate: 0.0,
+        }
+    }
+
+    /// Override the number of trading days per year of the calendar, e.g. 250 for some exchanges.
+    ///
+    /// # Returns:
+    /// An Error unless `0 < days <= 366`.
+    pub fn with_trading_days_per_year(mut self, days: f64) -> Result<Self> {
+        if !(days > 0.0 && days <= 366.0) {
+            return Err(Error::InvalidMetricsConfig);
+        }
+        self.trading_days_per_year = days;
+        Ok(self)
+    }
+
+    /// Override the number of trading hours per trading day of the calendar.
+    ///
+    /// # Returns:
+    /// An Error unless `0 < hours <= 24`.
+    pub fn with_trading_hours_per_day(mut self, hours: f64) -> Result<Self> {
+        if !(hours > 0.0 && hours <= 24.0) {
+            return Err(Error::InvalidMetricsConfig);
+        }
+        self.trading_hours_per_day = hours;
+        Ok(self)
+    }
+
+    /// The annual risk-free rate, e.g. 0.05 for 5%, which the Sharpe and Sortino ratios
+    /// of the logarithmic returns measure the excess return against.
+    ///
+    /// # Returns:
+    /// An Error if `rate <= -1`.
+    pub fn with_risk_free_rate(mut self, rate: f64) -> Result<Self> {
+        if !(rate > -1.0 && rate.is_finite()) {
+            return Err(Error::InvalidMetricsConfig);
+        }
+        self.risk_free_rate = rate;
+        Ok(self)
+    }
+
+    /// The calendar of the traded market.
+    #[inline(always)]
+    pub fn calendar(&self) -> MarketCalendar {
+        self.calendar
+    }
+
+    /// The number of trading days per year.
+    #[inline(always)]
+    pub fn trading_days_per_year(&self) -> f64 {
+        self.trading_days_per_year
+    }
+
+    /// The number of trading hours per trading day.
+    #[inline(always)]
+    pub fn trading_hours_per_day(&self) -> f64 {
+        self.trading_hours_per_day
+    }
+
+    /// The annual risk-free rate.
+    #[inline(always)]
+    pub fn risk_free_rate(&self) -> f64 {
+        self.risk_free_rate
+    }
+
+    /// The number of return periods of the `returns_source` in a year.
+    pub fn periods_per_year(&self, returns_source: ReturnsSource) -> f64 {
+        match returns_source {
+            ReturnsSource::Daily => self.trading_days_per_year,
+            ReturnsSource::Hourly => self.trading_days_per_year * self.trading_hours_per_day,
+        }
+    }
+
+    /// The risk-free logarithmic return of a single period of the `returns_source`.
+    pub fn risk_free_ln_return(&self, returns_source: ReturnsSource) -> f64 {
+        Float::ln_1p(self.risk_free_rate) / self.periods_per_year(returns_source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_config_periods_per_year() {
+        let crypto = MetricsConfig::default();
+        assert_eq!(crypto.periods_per_year(ReturnsSource::Daily), 365.0);
+        assert_eq!(crypto.periods_per_year(ReturnsSource::Hourly), 8760.0);
+        assert_eq!(crypto.risk_free_ln_return(ReturnsSource::Daily), 0.0);
+
+        let tradfi = MetricsConfig::new(MarketCalendar::TradFi)
+            .with_risk_free_rate(0.05)
+            .unwrap();
+        assert_eq!(tradfi.periods_per_year(ReturnsSource::Daily), 252.0);
+        assert_eq!(tradfi.periods_per_year(ReturnsSource::Hourly), 1638.0);
+        assert_eq!(
+            tradfi.risk_free_ln_return(ReturnsSource::Daily),
+            0.05_f64.ln_1p() / 252.0
+        );
+
+        assert_eq!(
+            MetricsConfig::default().with_trading_days_per_year(0.0),
+            Err(Error::InvalidMetricsConfig)
+        );
+        assert_eq!(
+            MetricsConfig::default().with_trading_hours_per_day(25.0),
+            Err(Error::InvalidMetricsConfig)
+        );
+        assert_eq!(
+            MetricsConfig::default().with_risk_free_rate(-1.0),
+            Err(Error::InvalidMetricsConfig)
+        );
+    }
+}
@@ -1,13 +1,34 @@
 //! Provides trait and implementations to track accounts performance
 
 mod account_tracker_trait;
+mod composite;
 mod d_ratio;
+mod direction_stats;
+mod drawdown;
+mod equity_curve;
 mod full_track;
+#[cfg(feature = "html_report")]
+mod html_report;
+mod metrics_config;
 mod no_track;
+mod performance_report;
+mod pnl_attribution;
+mod return_distribution;
+mod rolling;
 mod statistical_moments;
+mod time_series;
 
 pub use account_tracker_trait::AccountTracker;
 pub use d_ratio::d_ratio;
-pub use full_track::{FullAccountTracker, ReturnsSource};
+pub use direction_stats::DirectionStats;
+pub use drawdown::Drawdown;
+pub use equity_curve::EquityPoint;
+pub use full_track::{FullAccountTracker, ReturnsSource, RiskAdjustedReturns};
+pub use metrics_config::{MarketCalendar, MetricsConfig};
 pub use no_track::NoAccountTracker;
+pub use performance_report::PerformanceReport;
+pub use pnl_attribution::PnlAttribution;
+pub use return_distribution::{Histogram, TailRisk};
+pub use rolling::RollingWindow;
 pub use statistical_moments::*;
+pub use time_series::{RecordedField, TimeSeriesRecorder};
@@ -1,22 +1,30 @@
-use std::fmt::Display;
+use core::fmt::Display;
 
 use crate::{
     account_tracker::AccountTracker,
     prelude::{Account, MarketState},
+    risk_engine::RiskError,
     types::{Currency, MarginCurrency, QuoteCurrency, Side},
 };
 
 /// Performs no tracking of account performance
 #[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NoAccountTracker;
 
 impl<M> AccountTracker<M> for NoAccountTracker
 where
     M: Currency + MarginCurrency,
 {
-    fn update(&mut self, _timestamp: u64, _market_state: &MarketState, _account: &Account<M>) {}
+    fn update(
+        &mut self,
+        _timestamp: u64,
+        _market_state: &MarketState<M::Num>,
+        _account: &Account<M>,
+    ) {
+    }
 
-    fn log_rpnl(&mut self, _rpnl: M, _ts_ns: i64) {}
+    fn log_rpnl(&mut self, _rpnl: M, _position_side: Side, _ts_ns: i64) {}
 
     fn log_fee(&mut self, _fee: M) {}
 
@@ -24,15 +32,35 @@ where
 
     fn log_limit_order_cancellation(&mut self) {}
 
+    fn log_limit_order_expiry(&mut self) {}
+
     fn log_limit_order_fill(&mut self) {}
 
     fn log_market_order_fill(&mut self) {}
 
-    fn log_trade(&mut self, _side: Side, _price: QuoteCurrency, _size: M::PairedCurrency) {}
+    fn log_trade(&mut self, _side: Side, _price: QuoteCurrency<M::Num>, _size: M::PairedCurrency) {}
+
+    fn log_risk_violation(&mut self, _risk_error: &RiskError) {}
+
+    fn log_borrow_cost(&mut self, _borrow_cost: M) {}
+
+    fn log_idle_margin_interest(&mut self, _interest: M) {}
+
+    #[inline(always)]
+    fn log_slippage(&mut self, _slippage: M) {}
+
+    #[inline(always)]
+    fn log_bankruptcy(&mut self, _deficit: M, _covered_by_insurance_fund: bool, _ts_ns: i64) {}
+
+    #[inline(always)]
+    fn log_cash_flow(&mut self, _amount: M, _ts_ns: i64) {}
+
+    #[inline(always)]
+    fn log_settlement(&mut self, _settlement_price: QuoteCurrency<M::Num>, _ts_ns: i64) {}
 }
 
 impl Display for NoAccountTracker {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "")
     }
 }
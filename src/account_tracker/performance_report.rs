@@ -0,0 +1,230 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use super::{DirectionStats, PnlAttribution, RiskAdjustedReturns};
+use crate::types::{Currency, MarginCurrency};
+
+/// A summary of all metrics of the `FullAccountTracker` at the end of a backtest,
+/// created with `FullAccountTracker::report`.
+/// `Display` prints it as an aligned table and with the `serde` feature it can be exported as JSON.
+/// Metrics which can't be computed, e.g. due to too few samples, are `None`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct PerformanceReport<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The total realized profit and loss.
+    pub total_rpnl: M,
+    /// The unrealized profit and loss as of the last update.
+    pub upnl: M,
+    /// The annualized return on investment as a factor.
+    pub annualized_roi: M::Num,
+    /// The return of buying and holding the starting wallet balance.
+    pub buy_and_hold_return: M,
+    /// The Sharpe, Sortino and Calmar ratios of the daily logarithmic returns.
+    pub risk_adjusted_returns_daily: RiskAdjustedReturns,
+    /// The Sharpe, Sortino and Calmar ratios of the hourly logarithmic returns.
+    pub risk_adjusted_returns_hourly: RiskAdjustedReturns,
+    /// The maximum drawdown of the wallet balance as a fraction.
+    pub max_drawdown_wallet_balance: M::Num,
+    /// The maximum drawdown including unrealized profit and loss as a fraction.
+    pub max_drawdown_total: M::Num,
+    /// The duration in nanoseconds of the longest drawdown.
+    pub longest_drawdown_duration_ns: i64,
+    /// The fraction of the time spent in a drawdown.
+    pub time_under_water_ratio: f64,
+    /// The historical value at risk of the daily returns at the 1% percentile.
+    pub historical_value_at_risk_daily: f64,
+    /// The Cornish-Fisher value at risk of the daily returns at the 1% percentile.
+    pub cornish_fisher_value_at_risk_daily: Option<M>,
+    /// The d-ratio of the daily returns.
+    pub d_ratio_daily: Option<f64>,
+    /// The number of trades.
+    pub num_trades: i64,
+    /// The ratio of buys to all trades.
+    pub buy_ratio: f64,
+    /// The ratio of winning to all realized trades.
+    pub win_ratio: f64,
+    /// The ratio of the cumulative profit to the cumulative loss.
+    pub profit_loss_ratio: M::Num,
+    /// The cumulative traded notional.
+    pub turnover: M,
+    /// The ratio of trades to trading opportunities.
+    pub trade_percentage: f64,
+    /// The cumulative fees paid.
+    pub cumulative_fees: M,
+    /// The cumulative rebates received.
+    pub cumulative_rebates: M,
+    /// The cumulative interest paid on borrowed notional.
+    pub cumulative_borrow_cost: M,
+    /// The cumulative interest earned on idle margin.
+    pub cumulative_idle_margin_interest: M,
+    /// The cumulative slippage of market orders.
+    pub cumulative_slippage: M,
+    /// The number of times a loss exceeded the wallet balance.
+    pub num_bankruptcies: usize,
+    /// The cumulative amount by which losses exceeded the wallet balance.
+    pub cumulative_bankruptcy_deficit: M,
+    /// The number of trading days.
+    pub num_trading_days: u64,
+    /// The profit and loss split into price moves, fees, funding and slippage.
+    pub pnl_attribution: PnlAttribution<M>,
+    /// The statistics of the long trades.
+    pub long_stats: DirectionStats<M>,
+    /// The statistics of the short trades.
+    pub short_stats: DirectionStats<M>,
+    /// The optimal Kelly fraction of the realized trades.
+    pub kelly_fraction: Option<f64>,
+    /// The probability of hitting the drawdown threshold of the tracker.
+    pub risk_of_ruin: Option<f64>,
+}
+
+impl<M> PerformanceReport<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// Write the report as pretty printed JSON.
+    #[cfg(feature = "serde")]
+    pub fn write_json<W>(&self, writer: W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
+
+fn optional<T: Display>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+impl<M> Display for PerformanceReport<M>
+where
+    M: Currency + MarginCurrency,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let rows: Vec<(&str, String)> = vec![
+            ("total_rpnl", self.total_rpnl.to_string()),
+            ("upnl", self.upnl.to_string()),
+            ("annualized_roi", self.annualized_roi.to_string()),
+            ("buy_and_hold_return", self.buy_and_hold_return.to_string()),
+            (
+                "sharpe_daily",
+                format!("{:.4}", self.risk_adjusted_returns_daily.sharpe),
+            ),
+            (
+                "sortino_daily",
+                format!("{:.4}", self.risk_adjusted_returns_daily.sortino),
+            ),
+            (
+                "calmar_daily",
+                format!("{:.4}", self.risk_adjusted_returns_daily.calmar),
+            ),
+            (
+                "sharpe_hourly",
+                format!("{:.4}", self.risk_adjusted_returns_hourly.sharpe),
+            ),
+            (
+                "sortino_hourly",
+                format!("{:.4}", self.risk_adjusted_returns_hourly.sortino),
+            ),
+            (
+                "calmar_hourly",
+                format!("{:.4}", self.risk_adjusted_returns_hourly.calmar),
+            ),
+            (
+                "max_drawdown_wallet_balance",
+                self.max_drawdown_wallet_balance.to_string(),
+            ),
+            ("max_drawdown_total", self.max_drawdown_total.to_string()),
+            (
+                "longest_drawdown_duration_ns",
+                self.longest_drawdown_duration_ns.to_string(),
+            ),
+            (
+                "time_under_water_ratio",
+                format!("{:.4}", self.time_under_water_ratio),
+            ),
+            (
+                "historical_value_at_risk_daily",
+                format!("{:.4}", self.historical_value_at_risk_daily),
+            ),
+            (
+                "cornish_fisher_value_at_risk_daily",
+                optional(&self.cornish_fisher_value_at_risk_daily),
+            ),
+            ("d_ratio_daily", optional(&self.d_ratio_daily)),
+            ("num_trades", self.num_trades.to_string()),
+            ("buy_ratio", format!("{:.4}", self.buy_ratio)),
+            ("win_ratio", format!("{:.4}", self.win_ratio)),
+            ("profit_loss_ratio", self.profit_loss_ratio.to_string()),
+            ("turnover", self.turnover.to_string()),
+            ("trade_percentage", format!("{:.4}", self.trade_percentage)),
+            ("cumulative_fees", self.cumulative_fees.to_string()),
+            ("cumulative_rebates", self.cumulative_rebates.to_string()),
+            (
+                "cumulative_borrow_cost",
+                self.cumulative_borrow_cost.to_string(),
+            ),
+            (
+                "cumulative_idle_margin_interest",
+                self.cumulative_idle_margin_interest.to_string(),
+            ),
+            ("cumulative_slippage", self.cumulative_slippage.to_string()),
+            ("num_bankruptcies", self.num_bankruptcies.to_string()),
+            (
+                "cumulative_bankruptcy_deficit",
+                self.cumulative_bankruptcy_deficit.to_string(),
+            ),
+            ("num_trading_days", self.num_trading_days.to_string()),
+            (
+                "pnl_price_moves",
+                self.pnl_attribution.price_pnl.to_string(),
+            ),
+            (
+                "pnl_fees",
+                (self.pnl_attribution.fees_received - self.pnl_attribution.fees_paid).to_string(),
+            ),
+            (
+                "pnl_funding",
+                (self.pnl_attribution.funding_received - self.pnl_attribution.funding_paid)
+                    .to_string(),
+            ),
+            (
+                "pnl_slippage",
+                self.pnl_attribution.slippage.into_negative().to_string(),
+            ),
+            ("long_num_trades", self.long_stats.num_trades.to_string()),
+            (
+                "long_win_ratio",
+                format!("{:.4}", self.long_stats.win_ratio()),
+            ),
+            ("long_net_pnl", self.long_stats.net_pnl.to_string()),
+            ("short_num_trades", self.short_stats.num_trades.to_string()),
+            (
+                "short_win_ratio",
+                format!("{:.4}", self.short_stats.win_ratio()),
+            ),
+            ("short_net_pnl", self.short_stats.net_pnl.to_string()),
+            ("kelly_fraction", optional(&self.kelly_fraction)),
+            ("risk_of_ruin", optional(&self.risk_of_ruin)),
+        ];
+        let name_width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        let value_width = rows.iter().map(|(_, value)| value.len()).max().unwrap_or(0);
+        let separator = "-".repeat(name_width + value_width + 3);
+        writeln!(f, "{separator}")?;
+        for (name, value) in rows.iter() {
+            writeln!(f, "{name:<name_width$} | {value:>value_width$}")?;
+        }
+        write!(f, "{separator}")
+    }
+}
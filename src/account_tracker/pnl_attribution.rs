@@ -0,0 +1,93 @@
+use crate::types::{Currency, MarginCurrency};
+
+/// The profit and loss split into its components, denoted in the margin currency,
+/// as attributed by the `FullAccountTracker` to the whole run or to a single trade.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct PnlAttribution<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The profit and loss due to price moves, valued at the best bid and ask,
+    /// so before fees, funding and slippage.
+    pub price_pnl: M,
+    /// The fees paid to the exchange.
+    pub fees_paid: M,
+    /// The rebates received from the exchange due to negative fees.
+    pub fees_received: M,
+    /// The funding, e.g. the borrow cost of leveraged positions, paid.
+    pub funding_paid: M,
+    /// The funding received.
+    pub funding_received: M,
+    /// The cost of market orders filling worse than the best bid or ask.
+    pub slippage: M,
+}
+
+impl<M> PnlAttribution<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The profit and loss net of all costs, which is the sum of the components.
+    pub fn net_pnl(&self) -> M {
+        self.price_pnl - self.fees_paid + self.fees_received - self.funding_paid
+            + self.funding_received
+            - self.slippage
+    }
+
+    /// Add all components of `other`.
+    pub(crate) fn merge(&mut self, other: &Self) {
+        self.price_pnl += other.price_pnl;
+        self.fees_paid += other.fees_paid;
+        self.fees_received += other.fees_received;
+        self.funding_paid += other.funding_paid;
+        self.funding_received += other.funding_received;
+        self.slippage += other.slippage;
+    }
+
+    /// Add a fee, which is a rebate if negative.
+    pub(crate) fn add_fee(&mut self, fee: M) {
+        if fee >= M::new_zero() {
+            self.fees_paid += fee;
+        } else {
+            self.fees_received += fee.abs();
+        }
+    }
+
+    /// Add a funding payment, which is received if negative.
+    pub(crate) fn add_funding(&mut self, funding: M) {
+        if funding >= M::new_zero() {
+            self.funding_paid += funding;
+        } else {
+            self.funding_received += funding.abs();
+        }
+    }
+
+    /// Add slippage, which the `price_pnl` excludes although the fill prices include it.
+    pub(crate) fn add_slippage(&mut self, slippage: M) {
+        self.slippage += slippage;
+        self.price_pnl += slippage;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn pnl_attribution_net_pnl() {
+        let mut attribution = PnlAttribution::<QuoteCurrency> {
+            price_pnl: quote!(10),
+            ..Default::default()
+        };
+        attribution.add_fee(quote!(1));
+        attribution.add_fee(quote!(-0.5));
+        attribution.add_funding(quote!(2));
+        attribution.add_funding(quote!(-0.25));
+        attribution.add_slippage(quote!(3));
+        assert_eq!(attribution.price_pnl, quote!(13));
+        assert_eq!(attribution.fees_received, quote!(0.5));
+        assert_eq!(attribution.funding_received, quote!(0.25));
+        assert_eq!(attribution.net_pnl(), quote!(7.75));
+    }
+}
@@ -0,0 +1,148 @@
+use alloc::{vec, vec::Vec};
+
+/// The empirical tail risk of a return distribution at some confidence level.
+/// Both values are losses, so positive values denote negative returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TailRisk {
+    /// The value at risk, which is the loss not exceeded with the given confidence.
+    pub value_at_risk: f64,
+    /// The conditional value at risk (expected shortfall),
+    /// which is the average loss in the cases where the value at risk is reached or exceeded.
+    pub conditional_value_at_risk: f64,
+}
+
+impl TailRisk {
+    /// Compute the empirical tail risk from the observed `returns`.
+    ///
+    /// # Arguments:
+    /// `returns`: The observed returns, e.g. ln returns.
+    /// `confidence`: in range (0.0, 1.0), usually something like 0.95 or 0.99.
+    pub fn empirical(returns: &[f64], confidence: f64) -> Self {
+        if returns.is_empty() {
+            return Self {
+                value_at_risk: 0.0,
+                conditional_value_at_risk: 0.0,
+            };
+        }
+        let mut sorted = returns.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let idx = ((sorted.len() as f64 * (1.0 - confidence)) as usize).min(sorted.len() - 1);
+        let tail = &sorted[..=idx];
+
+        Self {
+            value_at_risk: -sorted[idx],
+            conditional_value_at_risk: -tail.iter().sum::<f64>() / tail.len() as f64,
+        }
+    }
+}
+
+/// A histogram of returns with bins of equal width.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Histogram {
+    min: f64,
+    bin_width: f64,
+    counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Count the `values` in `num_bins` bins spanning from the smallest to the largest value.
+    pub fn new(values: &[f64], num_bins: usize) -> Self {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if values.is_empty() || num_bins == 0 {
+            return Self {
+                min: 0.0,
+                bin_width: 0.0,
+                counts: vec![0; num_bins],
+            };
+        }
+        let bin_width = (max - min) / num_bins as f64;
+        let mut counts = vec![0; num_bins];
+        for value in values {
+            let idx = if bin_width > 0.0 {
+                (((value - min) / bin_width) as usize).min(num_bins - 1)
+            } else {
+                0
+            };
+            counts[idx] += 1;
+        }
+
+        Self {
+            min,
+            bin_width,
+            counts,
+        }
+    }
+
+    /// The width of each bin.
+    #[inline(always)]
+    pub fn bin_width(&self) -> f64 {
+        self.bin_width
+    }
+
+    /// The number of values in each bin, from the lowest to the highest bin.
+    #[inline(always)]
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// The lower edge, upper edge and count of each bin.
+    /// The upper edge of the last bin is inclusive.
+    pub fn bins(&self) -> impl Iterator<Item = (f64, f64, usize)> + '_ {
+        self.counts.iter().enumerate().map(|(i, count)| {
+            let lower = self.min + i as f64 * self.bin_width;
+            (lower, lower + self.bin_width, *count)
+        })
+    }
+
+    /// Write the bins as CSV with a header row.
+    #[cfg(feature = "std")]
+    pub fn write_csv<W>(&self, mut writer: W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(writer, "lower,upper,count")?;
+        for (lower, upper, count) in self.bins() {
+            writeln!(writer, "{lower},{upper},{count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::tests::round;
+
+    #[test]
+    fn tail_risk_empirical() {
+        let returns: Vec<f64> = (1..=100).map(|i| (i as f64 - 50.0) / 100.0).collect();
+        let tail_risk = TailRisk::empirical(&returns, 0.95);
+        // The 5% quantile is -0.44 and the returns up to it are -0.49 ..= -0.44
+        assert_eq!(round(tail_risk.value_at_risk, 6), 0.44);
+        assert_eq!(round(tail_risk.conditional_value_at_risk, 6), 0.465);
+        assert_eq!(TailRisk::empirical(&[], 0.95).value_at_risk, 0.0);
+    }
+
+    #[test]
+    fn histogram() {
+        let histogram = Histogram::new(&[0.0, 0.1, 0.25, 0.4, 1.0], 4);
+        assert_eq!(histogram.counts(), &[2, 2, 0, 1]);
+        assert_eq!(histogram.bin_width(), 0.25);
+
+        #[cfg(feature = "std")]
+        {
+            let mut csv = Vec::new();
+            histogram.write_csv(&mut csv).unwrap();
+            assert_eq!(
+                String::from_utf8(csv).unwrap(),
+                "lower,upper,count\n0,0.25,2\n0.25,0.5,2\n0.5,0.75,0\n0.75,1,1\n"
+            );
+        }
+
+        assert_eq!(Histogram::new(&[0.5, 0.5], 2).counts(), &[2, 0]);
+        assert_eq!(Histogram::new(&[], 2).counts(), &[0, 0]);
+    }
+}
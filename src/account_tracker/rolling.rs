@@ -0,0 +1,99 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use num_traits::Float;
+
+use super::statistical_moments;
+
+/// The size of the window over which rolling statistics are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RollingWindow {
+    /// The most recent number of samples.
+    Samples(usize),
+    /// The samples within the most recent nanoseconds of simulated time.
+    Duration(u64),
+}
+
+/// The index ranges of the samples within each window, one for every sample once the history is long enough.
+///
+/// # Arguments:
+/// `timestamps`: The ascending timestamps of the samples in nanoseconds.
+/// `window`: The size of the window.
+pub(crate) fn window_ranges(timestamps: &[u64], window: RollingWindow) -> Vec<Range<usize>> {
+    match window {
+        RollingWindow::Samples(0) => vec![],
+        RollingWindow::Samples(n) => (n..=timestamps.len()).map(|end| end - n..end).collect(),
+        RollingWindow::Duration(duration_ns) => {
+            let Some(first_ts) = timestamps.first() else {
+                return vec![];
+            };
+            let mut start = 0;
+            let mut ranges = Vec::new();
+            for (end, ts) in timestamps.iter().enumerate() {
+                if ts - first_ts < duration_ns {
+                    continue;
+                }
+                while ts - timestamps[start] >= duration_ns {
+                    start += 1;
+                }
+                ranges.push(start..end + 1);
+            }
+            ranges
+        }
+    }
+}
+
+/// The annualized Sharpe ratio of logarithmic returns in excess of the `risk_free_ln_return` per period.
+pub(crate) fn ln_sharpe(
+    ln_returns: &[f64],
+    periods_per_year: f64,
+    risk_free_ln_return: f64,
+) -> f64 {
+    if ln_returns.is_empty() {
+        return 0.0;
+    }
+    let stats = statistical_moments(ln_returns);
+    if stats.std_dev == 0.0 {
+        return 0.0;
+    }
+
+    (stats.mean - risk_free_ln_return) / stats.std_dev * Float::sqrt(periods_per_year)
+}
+
+/// The annualized standard deviation of logarithmic returns.
+pub(crate) fn ln_volatility(ln_returns: &[f64], periods_per_year: f64) -> f64 {
+    if ln_returns.is_empty() {
+        return 0.0;
+    }
+
+    statistical_moments(ln_returns).std_dev * Float::sqrt(periods_per_year)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_window_ranges() {
+        let ts = [0, 10, 20, 30, 50];
+        assert_eq!(
+            window_ranges(&ts, RollingWindow::Samples(2)),
+            vec![0..2, 1..3, 2..4, 3..5]
+        );
+        assert!(window_ranges(&ts, RollingWindow::Samples(6)).is_empty());
+        assert!(window_ranges(&ts, RollingWindow::Samples(0)).is_empty());
+        // Each window covers the samples within (ts - 25, ts].
+        assert_eq!(
+            window_ranges(&ts, RollingWindow::Duration(25)),
+            vec![1..4, 3..5]
+        );
+        assert!(window_ranges(&[], RollingWindow::Duration(25)).is_empty());
+    }
+
+    #[test]
+    fn rolling_volatility() {
+        assert_eq!(ln_volatility(&[0.01, -0.01], 4.0), 0.02);
+        assert_eq!(ln_sharpe(&[0.01, 0.01], 4.0, 0.0), 0.0);
+    }
+}
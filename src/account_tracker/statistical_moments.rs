@@ -1,3 +1,5 @@
+use num_traits::Float;
+
 /// The statistical moments of a distribution
 #[derive(Debug, Clone)]
 pub struct StatisticalMoments {
@@ -16,14 +18,14 @@ pub fn statistical_moments(vals: &[f64]) -> StatisticalMoments {
     let mean = vals.iter().sum::<f64>() / vals.len() as f64;
     let n = vals.len() as f64;
 
-    let variance = vals.iter().map(|v| (*v - mean).powi(2)).sum::<f64>() / n;
-    let std_dev = variance.sqrt();
+    let variance = vals.iter().map(|v| Float::powi(*v - mean, 2)).sum::<f64>() / n;
+    let std_dev = Float::sqrt(variance);
 
-    let skew =
-        ((1.0 / n) * vals.iter().map(|v| (*v - mean).powi(3)).sum::<f64>()) / variance.powf(1.5);
+    let skew = ((1.0 / n) * vals.iter().map(|v| Float::powi(*v - mean, 3)).sum::<f64>())
+        / Float::powf(variance, 1.5);
 
-    let kurtosis = ((1.0 / n) * vals.iter().map(|v| (*v - mean).powi(4)).sum::<f64>())
-        / variance.powi(2)
+    let kurtosis = ((1.0 / n) * vals.iter().map(|v| Float::powi(*v - mean, 4)).sum::<f64>())
+        / Float::powi(variance, 2)
         - 3.0;
 
     StatisticalMoments {
@@ -34,7 +36,7 @@ pub fn statistical_moments(vals: &[f64]) -> StatisticalMoments {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use rand::{thread_rng, Rng};
     use rand_distr::StandardNormal;
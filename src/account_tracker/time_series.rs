@@ -0,0 +1,341 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    account_tracker::AccountTracker,
+    prelude::{Account, MarketState},
+    risk_engine::RiskError,
+    types::{Currency, Error, MarginCurrency, Mon, QuoteCurrency, Result, Side},
+};
+
+/// A field of the account state sampled by the `TimeSeriesRecorder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecordedField {
+    /// The wallet balance including the unrealized profit and loss.
+    Equity,
+    /// The net position size, negative when short.
+    PositionSize,
+    /// The maintenance margin over the margin balance of the position, see `Account::margin_ratio`.
+    MarginRatio,
+    /// The number of open limit orders.
+    NumOpenOrders,
+}
+
+impl RecordedField {
+    /// The name of the column.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecordedField::Equity => "equity",
+            RecordedField::PositionSize => "position_size",
+            RecordedField::MarginRatio => "margin_ratio",
+            RecordedField::NumOpenOrders => "num_open_orders",
+        }
+    }
+}
+
+/// Samples selected fields of the account state every `interval_ns` of simulated time
+/// into an in-memory columnar store, which can be exported as CSV
+/// or as an Arrow `RecordBatch` with the `parquet` feature.
+/// Independent of the `FullAccountTracker`, so it may be used on its own
+/// or combined with other trackers, e.g. `(FullAccountTracker<M>, TimeSeriesRecorder<M>)`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct TimeSeriesRecorder<M>
+where
+    M: Currency + MarginCurrency,
+{
+    interval_ns: u64,
+    maintenance_margin: M::Num,
+    next_sample_ns: u64,
+    timestamps_ns: Vec<u64>,
+    fields: Vec<RecordedField>,
+    // One column per field, each as long as `timestamps_ns`.
+    columns: Vec<Vec<M::Num>>,
+}
+
+impl<M> TimeSeriesRecorder<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// Create a new `TimeSeriesRecorder`.
+    ///
+    /// # Arguments:
+    /// `interval_ns`: The simulated time between samples in nanoseconds.
+    /// The first update is sampled, then the first update at or after each multiple of `interval_ns`.
+    /// `fields`: The fields to sample, one column each.
+    /// `maintenance_margin`: The maintenance margin fraction of the contract, for `RecordedField::MarginRatio`.
+    ///
+    /// # Returns:
+    /// Either a valid `TimeSeriesRecorder` or an Error if `interval_ns` is zero or `fields` are empty or duplicated.
+    pub fn new(
+        interval_ns: u64,
+        fields: &[RecordedField],
+        maintenance_margin: M::Num,
+    ) -> Result<Self> {
+        if interval_ns == 0 {
+            return Err(Error::InvalidSamplingInterval);
+        }
+        if fields.is_empty()
+            || fields
+                .iter()
+                .enumerate()
+                .any(|(i, field)| fields[..i].contains(field))
+        {
+            return Err(Error::InvalidRecordedFields);
+        }
+        Ok(Self {
+            interval_ns,
+            maintenance_margin,
+            next_sample_ns: 0,
+            timestamps_ns: Vec::new(),
+            fields: fields.to_vec(),
+            columns: vec![Vec::new(); fields.len()],
+        })
+    }
+
+    /// The sampled fields, in column order.
+    #[inline(always)]
+    pub fn fields(&self) -> &[RecordedField] {
+        &self.fields
+    }
+
+    /// The timestamps in nanoseconds of the samples.
+    #[inline(always)]
+    pub fn timestamps_ns(&self) -> &[u64] {
+        &self.timestamps_ns
+    }
+
+    /// The sampled values of `field`, if it is recorded.
+    pub fn column(&self, field: RecordedField) -> Option<&[M::Num]> {
+        self.fields
+            .iter()
+            .position(|f| *f == field)
+            .map(|i| self.columns[i].as_slice())
+    }
+
+    /// The number of samples.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.timestamps_ns.len()
+    }
+
+    /// Whether nothing has been sampled yet.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.timestamps_ns.is_empty()
+    }
+
+    /// Write the samples as CSV with a header row, starting with the `timestamp_ns` column.
+    #[cfg(feature = "std")]
+    pub fn write_csv<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        write!(writer, "timestamp_ns")?;
+        for field in self.fields.iter() {
+            write!(writer, ",{}", field.name())?;
+        }
+        writeln!(writer)?;
+        for (row, timestamp_ns) in self.timestamps_ns.iter().enumerate() {
+            write!(writer, "{timestamp_ns}")?;
+            for column in self.columns.iter() {
+                write!(writer, ",{}", column[row])?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// The samples as an Arrow `RecordBatch` with a `UInt64` column `timestamp_ns`
+    /// followed by a `Float64` column per field.
+    #[cfg(feature = "parquet")]
+    pub fn to_record_batch(&self) -> arrow_array::RecordBatch {
+        use alloc::sync::Arc;
+
+        use arrow_array::{ArrayRef, Float64Array, RecordBatch, UInt64Array};
+        use arrow_schema::{DataType, Field, Schema};
+
+        let schema = Schema::new(Vec::from_iter(
+            core::iter::once(Field::new("timestamp_ns", DataType::UInt64, false)).chain(
+                self.fields
+                    .iter()
+                    .map(|field| Field::new(field.name(), DataType::Float64, false)),
+            ),
+        ));
+        let arrays = Vec::from_iter(
+            core::iter::once(Arc::new(UInt64Array::from(self.timestamps_ns.clone())) as ArrayRef)
+                .chain(self.columns.iter().map(|column| {
+                    Arc::new(Float64Array::from_iter_values(
+                        column.iter().map(|value| value.to_f64()),
+                    )) as ArrayRef
+                })),
+        );
+        RecordBatch::try_new(Arc::new(schema), arrays)
+            .expect("The columns match the schema and have equal length; qed")
+    }
+
+    fn sample(
+        &self,
+        field: RecordedField,
+        market_state: &MarketState<M::Num>,
+        account: &Account<M>,
+    ) -> M::Num {
+        match field {
+            RecordedField::Equity => (account.wallet_balance()
+                + account.unrealized_pnl(market_state.bid(), market_state.ask()))
+            .inner(),
+            RecordedField::PositionSize => {
+                (account.position().size() + account.short_position().size()).inner()
+            }
+            RecordedField::MarginRatio => {
+                account.margin_ratio(market_state, self.maintenance_margin)
+            }
+            RecordedField::NumOpenOrders => {
+                M::Num::from_u64(account.active_limit_orders().len() as u64)
+            }
+        }
+    }
+}
+
+impl<M> AccountTracker<M> for TimeSeriesRecorder<M>
+where
+    M: Currency + MarginCurrency,
+{
+    fn update(
+        &mut self,
+        timestamp_ns: u64,
+        market_state: &MarketState<M::Num>,
+        account: &Account<M>,
+    ) {
+        if timestamp_ns < self.next_sample_ns {
+            return;
+        }
+        self.next_sample_ns = timestamp_ns - timestamp_ns % self.interval_ns + self.interval_ns;
+        self.timestamps_ns.push(timestamp_ns);
+        for (i, field) in self.fields.iter().enumerate() {
+            let value = self.sample(*field, market_state, account);
+            self.columns[i].push(value);
+        }
+    }
+
+    fn log_rpnl(&mut self, _net_rpnl: M, _position_side: Side, _ts_ns: i64) {}
+
+    fn log_fee(&mut self, _fee_in_margin: M) {}
+
+    fn log_limit_order_submission(&mut self) {}
+
+    fn log_limit_order_cancellation(&mut self) {}
+
+    fn log_limit_order_expiry(&mut self) {}
+
+    fn log_limit_order_fill(&mut self) {}
+
+    fn log_market_order_fill(&mut self) {}
+
+    fn log_trade(
+        &mut self,
+        _side: Side,
+        _price: QuoteCurrency<M::Num>,
+        _quantity: M::PairedCurrency,
+    ) {
+    }
+
+    fn log_risk_violation(&mut self, _risk_error: &RiskError) {}
+
+    fn log_borrow_cost(&mut self, _borrow_cost: M) {}
+
+    fn log_idle_margin_interest(&mut self, _interest: M) {}
+
+    fn log_slippage(&mut self, _slippage: M) {}
+
+    fn log_bankruptcy(&mut self, _deficit: M, _covered_by_insurance_fund: bool, _ts_ns: i64) {}
+
+    fn log_cash_flow(&mut self, _amount: M, _ts_ns: i64) {}
+
+    fn log_settlement(&mut self, _settlement_price: QuoteCurrency<M::Num>, _ts_ns: i64) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock_exchange_base, prelude::*};
+
+    #[test]
+    fn time_series_recorder_new() {
+        assert_eq!(
+            TimeSeriesRecorder::<QuoteCurrency>::new(0, &[RecordedField::Equity], Dec!(0.01))
+                .map(|_| ()),
+            Err(Error::InvalidSamplingInterval)
+        );
+        assert_eq!(
+            TimeSeriesRecorder::<QuoteCurrency>::new(1, &[], Dec!(0.01)).map(|_| ()),
+            Err(Error::InvalidRecordedFields)
+        );
+        assert_eq!(
+            TimeSeriesRecorder::<QuoteCurrency>::new(
+                1,
+                &[RecordedField::Equity, RecordedField::Equity],
+                Dec!(0.01)
+            )
+            .map(|_| ()),
+            Err(Error::InvalidRecordedFields)
+        );
+    }
+
+    #[test]
+    fn time_series_recorder_samples_every_interval() {
+        let recorder = TimeSeriesRecorder::<QuoteCurrency>::new(
+            10,
+            &[
+                RecordedField::Equity,
+                RecordedField::PositionSize,
+                RecordedField::NumOpenOrders,
+            ],
+            Dec!(0.01),
+        )
+        .unwrap();
+        let mut exchange =
+            Exchange::<_, BaseCurrency>::new(recorder, mock_exchange_base().config().clone());
+        for ts in [0, 5, 10, 12, 25] {
+            exchange
+                .update_state(ts, bba!(quote!(100), quote!(101)))
+                .unwrap();
+            if ts == 5 {
+                exchange
+                    .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+                    .unwrap();
+            }
+        }
+        let recorder = exchange.account_tracker();
+        assert_eq!(recorder.timestamps_ns(), &[0, 10, 25]);
+        assert_eq!(
+            recorder.column(RecordedField::NumOpenOrders).unwrap(),
+            &[Dec!(0), Dec!(1), Dec!(1)]
+        );
+        assert!(recorder.column(RecordedField::MarginRatio).is_none());
+
+        #[cfg(feature = "std")]
+        {
+            let mut buf = Vec::new();
+            recorder.write_csv(&mut buf).unwrap();
+            assert_eq!(
+                String::from_utf8(buf).unwrap(),
+                "timestamp_ns,equity,position_size,num_open_orders\n0,1000,0,0\n10,1000,0,1\n25,1000,0,1\n"
+            );
+        }
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn time_series_recorder_record_batch() {
+        let mut recorder = TimeSeriesRecorder::<QuoteCurrency>::new(
+            10,
+            &[RecordedField::Equity, RecordedField::MarginRatio],
+            Dec!(0.01),
+        )
+        .unwrap();
+        let exchange = mock_exchange_base();
+        recorder.update(0, exchange.market_state(), exchange.account());
+        let batch = recorder.to_record_batch();
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(batch.num_columns(), 3);
+        assert_eq!(batch.schema().field(1).name(), "equity");
+    }
+}
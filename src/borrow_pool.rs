@@ -0,0 +1,89 @@
+//! The borrowable amount of the traded asset, which caps the size of short positions.
+
+use crate::types::{Currency, Error, Mon, QuoteCurrency, Result};
+
+/// The amount of the traded asset the venue lends for short selling, like the borrow pool of a spot margin venue.
+/// Orders which would increase the short position beyond `max_borrow` are rejected,
+/// and the borrowed amount accrues interest every hour, valued at the mark price.
+/// Without a `BorrowPool` the asset is assumed to be available in unlimited supply.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct BorrowPool<M>
+where
+    M: Currency,
+{
+    max_borrow: M::PairedCurrency,
+    hourly_rate: M::Num,
+}
+
+impl<M> BorrowPool<M>
+where
+    M: Currency,
+{
+    /// Create a new `BorrowPool`.
+    ///
+    /// # Arguments:
+    /// `max_borrow`: The maximum size of the short position, zero if the asset cannot be borrowed at all.
+    /// `hourly_rate`: The interest rate per hour on the borrowed amount.
+    ///
+    /// # Returns:
+    /// Either a valid `BorrowPool` or an Error if an argument is negative.
+    pub fn new(max_borrow: M::PairedCurrency, hourly_rate: M::Num) -> Result<Self> {
+        if max_borrow < M::PairedCurrency::new_zero() || hourly_rate < M::Num::ZERO {
+            return Err(Error::InvalidBorrowPool);
+        }
+        Ok(Self {
+            max_borrow,
+            hourly_rate,
+        })
+    }
+
+    /// The maximum size of the short position.
+    #[inline(always)]
+    pub fn max_borrow(&self) -> M::PairedCurrency {
+        self.max_borrow
+    }
+
+    /// The interest rate per hour on the borrowed amount.
+    #[inline(always)]
+    pub fn hourly_rate(&self) -> M::Num {
+        self.hourly_rate
+    }
+
+    /// The interest on `borrowed` over `elapsed_ns`, valued at the `price`.
+    pub(crate) fn interest(
+        &self,
+        borrowed: M::PairedCurrency,
+        price: QuoteCurrency<M::Num>,
+        elapsed_ns: i64,
+    ) -> M {
+        const HOUR_NS: i64 = 3_600_000_000_000;
+
+        borrowed.convert(price) * self.hourly_rate * M::Num::from_i64(elapsed_ns)
+            / M::Num::from_i64(HOUR_NS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn borrow_pool() {
+        assert_eq!(
+            BorrowPool::<QuoteCurrency>::new(base!(-1), Dec!(0.001)),
+            Err(Error::InvalidBorrowPool)
+        );
+        assert_eq!(
+            BorrowPool::<QuoteCurrency>::new(base!(1), Dec!(-0.001)),
+            Err(Error::InvalidBorrowPool)
+        );
+        let pool = BorrowPool::<QuoteCurrency>::new(base!(10), Dec!(0.001)).unwrap();
+        assert_eq!(pool.max_borrow(), base!(10));
+        assert_eq!(
+            pool.interest(base!(5), quote!(100), 7_200_000_000_000),
+            quote!(1)
+        );
+    }
+}
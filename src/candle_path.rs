@@ -0,0 +1,179 @@
+//! Assumptions about the price path within a candle, which decide the order of fills.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::types::{Currency, Mon, QuoteCurrency, Side};
+
+/// The assumed price path within a candle, which goes from the previous mid price (open)
+/// through the high and low to the mid price of the candle (close).
+/// The set of filled orders only depends on the high and low,
+/// but the path decides which of them are filled first,
+/// e.g. when one order of a one-cancels-other group would cancel the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CandlePath {
+    /// The extreme closer to the open is visited first, which is the common OHLC convention.
+    #[default]
+    Nearest,
+    /// The high is always visited before the low.
+    HighFirst,
+    /// The low is always visited before the high.
+    LowFirst,
+    /// The extreme which is adverse to the open position is visited first,
+    /// e.g. the low for a long position. A flat account falls back to `Nearest`.
+    WorstCase,
+    /// The price follows a driftless brownian motion between the open and close,
+    /// which hits the high before the low with probability `(open - low) / (high - low)`.
+    /// Deterministic for a given `seed`.
+    Brownian {
+        /// The seed of the random number generator.
+        seed: u64,
+    },
+}
+
+/// Draws the price path within candles according to the `CandlePath`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct CandlePathSampler {
+    path: CandlePath,
+    rng: ChaCha12Rng,
+}
+
+impl CandlePathSampler {
+    pub(crate) fn new(path: CandlePath) -> Self {
+        let seed = match path {
+            CandlePath::Brownian { seed } => seed,
+            _ => 0,
+        };
+        Self {
+            path,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// The prices visited in order: open, the first and second extreme, close.
+    ///
+    /// # Arguments:
+    /// `open`, `low`, `high`, `close`: The prices of the candle.
+    /// `position_side`: The side of the open position, if any.
+    pub(crate) fn waypoints<T: Mon>(
+        &mut self,
+        open: QuoteCurrency<T>,
+        low: QuoteCurrency<T>,
+        high: QuoteCurrency<T>,
+        close: QuoteCurrency<T>,
+        position_side: Option<Side>,
+    ) -> [QuoteCurrency<T>; 4] {
+        let nearest_high_first = high - open <= open - low;
+        let high_first = match self.path {
+            CandlePath::Nearest => nearest_high_first,
+            CandlePath::HighFirst => true,
+            CandlePath::LowFirst => false,
+            CandlePath::WorstCase => match position_side {
+                Some(Side::Buy) => false,
+                Some(Side::Sell) => true,
+                None => nearest_high_first,
+            },
+            CandlePath::Brownian { .. } => {
+                let range = high - low;
+                if range <= QuoteCurrency::new_zero() {
+                    true
+                } else {
+                    let p = ((open - low) / range).inner().to_f64().clamp(0.0, 1.0);
+                    self.rng.gen_bool(p)
+                }
+            }
+        };
+        if high_first {
+            [open, high, low, close]
+        } else {
+            [open, low, high, close]
+        }
+    }
+}
+
+/// The distance travelled along the `waypoints` until a limit order at `limit_price` is reached,
+/// or `None` if the path never reaches it.
+pub(crate) fn crossing_distance<T: Mon>(
+    waypoints: &[QuoteCurrency<T>; 4],
+    side: Side,
+    limit_price: QuoteCurrency<T>,
+) -> Option<QuoteCurrency<T>> {
+    let reached = |price: QuoteCurrency<T>| match side {
+        Side::Buy => price <= limit_price,
+        Side::Sell => price >= limit_price,
+    };
+    if reached(waypoints[0]) {
+        return Some(QuoteCurrency::new_zero());
+    }
+    let mut distance = QuoteCurrency::new_zero();
+    for segment in waypoints.windows(2) {
+        if reached(segment[1]) {
+            return Some(distance + (limit_price - segment[0]).abs());
+        }
+        distance += (segment[1] - segment[0]).abs();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn candle_path_waypoints() {
+        let (open, low, high, close) = (quote!(100), quote!(95), quote!(110), quote!(105));
+        let mut nearest = CandlePathSampler::new(CandlePath::Nearest);
+        assert_eq!(
+            nearest.waypoints(open, low, high, close, None),
+            [open, low, high, close]
+        );
+        let mut worst_case = CandlePathSampler::new(CandlePath::WorstCase);
+        assert_eq!(
+            worst_case.waypoints(open, low, high, close, Some(Side::Sell)),
+            [open, high, low, close]
+        );
+        assert_eq!(
+            CandlePathSampler::new(CandlePath::HighFirst).waypoints(open, low, high, close, None),
+            [open, high, low, close]
+        );
+    }
+
+    #[test]
+    fn candle_path_brownian_deterministic() {
+        let (open, low, high, close) = (quote!(100), quote!(95), quote!(110), quote!(105));
+        let path = CandlePath::Brownian { seed: 7 };
+        let mut a = CandlePathSampler::new(path);
+        let mut b = CandlePathSampler::new(path);
+        let mut num_high_first = 0;
+        for _ in 0..1000 {
+            let waypoints = a.waypoints(open, low, high, close, None);
+            assert_eq!(waypoints, b.waypoints(open, low, high, close, None));
+            if waypoints[1] == high {
+                num_high_first += 1;
+            }
+        }
+        // The high is hit first with a probability of 1/3.
+        assert!((280..390).contains(&num_high_first));
+    }
+
+    #[test]
+    fn candle_path_crossing_distance() {
+        let waypoints = [quote!(100), quote!(110), quote!(95), quote!(105)];
+        assert_eq!(
+            crossing_distance(&waypoints, Side::Sell, quote!(105)),
+            Some(quote!(5))
+        );
+        assert_eq!(
+            crossing_distance(&waypoints, Side::Buy, quote!(98)),
+            Some(quote!(22))
+        );
+        assert_eq!(crossing_distance(&waypoints, Side::Buy, quote!(90)), None);
+        assert_eq!(
+            crossing_distance(&waypoints, Side::Buy, quote!(100)),
+            Some(quote!(0))
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Volatility halts triggered by large moves of the mark price.
+
+use crate::types::{Currency, Error, Mon, QuoteCurrency, Result};
+
+/// Halts trading for `halt_duration_ns` when a market update moves the mark price
+/// by more than `max_move` relative to the previous mark price, like the volatility halts of real venues.
+/// During a halt only cancellations are accepted and resting orders are neither triggered nor matched.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct CircuitBreaker<T>
+where
+    T: Mon,
+{
+    max_move: T,
+    halt_duration_ns: i64,
+}
+
+impl<T> CircuitBreaker<T>
+where
+    T: Mon,
+{
+    /// Create a new `CircuitBreaker`.
+    ///
+    /// # Arguments:
+    /// `max_move`: The largest relative move of the mark price which does not halt trading, e.g. 0.1 for 10%.
+    /// `halt_duration_ns`: The duration of a halt in nanoseconds of simulated time.
+    ///
+    /// # Returns:
+    /// Either a valid `CircuitBreaker` or an Error if an argument is not positive.
+    pub fn new(max_move: T, halt_duration_ns: i64) -> Result<Self> {
+        if max_move <= T::ZERO || halt_duration_ns <= 0 {
+            return Err(Error::InvalidCircuitBreaker);
+        }
+        Ok(Self {
+            max_move,
+            halt_duration_ns,
+        })
+    }
+
+    /// The largest relative move of the mark price which does not halt trading.
+    #[inline(always)]
+    pub fn max_move(&self) -> T {
+        self.max_move
+    }
+
+    /// The duration of a halt in nanoseconds.
+    #[inline(always)]
+    pub fn halt_duration_ns(&self) -> i64 {
+        self.halt_duration_ns
+    }
+
+    /// Whether the move from `prev_mark_price` to `mark_price` halts trading.
+    pub(crate) fn is_tripped(
+        &self,
+        prev_mark_price: QuoteCurrency<T>,
+        mark_price: QuoteCurrency<T>,
+    ) -> bool {
+        if prev_mark_price <= QuoteCurrency::new_zero() {
+            return false;
+        }
+        (mark_price - prev_mark_price).abs().inner() > self.max_move * prev_mark_price.inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn circuit_breaker() {
+        assert_eq!(
+            CircuitBreaker::new(Dec!(0), 1),
+            Err(Error::InvalidCircuitBreaker)
+        );
+        assert_eq!(
+            CircuitBreaker::new(Dec!(0.1), 0),
+            Err(Error::InvalidCircuitBreaker)
+        );
+        let breaker = CircuitBreaker::new(Dec!(0.1), 1_000).unwrap();
+        assert!(!breaker.is_tripped(quote!(100), quote!(110)));
+        assert!(!breaker.is_tripped(quote!(100), quote!(90)));
+        assert!(breaker.is_tripped(quote!(100), quote!(110.5)));
+        assert!(breaker.is_tripped(quote!(100), quote!(89)));
+    }
+}
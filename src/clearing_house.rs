@@ -1,10 +1,13 @@
 //! A clearinghouse clears and settles all trades and collects margin
 
-use fpdec::Decimal;
-
 use crate::{
+    borrow_pool::BorrowPool,
+    ledger::LedgerEntryKind,
+    options::OptionPosition,
     prelude::{Account, AccountTracker},
-    types::{Currency, Fee, MarginCurrency, QuoteCurrency, Side},
+    types::{
+        BankruptcyMode, Currency, Fee, MarginCurrency, Mon, PositionMode, QuoteCurrency, Side,
+    },
 };
 
 /// A clearing house acts as an intermediary in futures transactions.
@@ -17,8 +20,9 @@ use crate::{
 /// house. If there has been a gain on the transactions, the account receives
 /// variation margin from the clearing house.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct ClearingHouse<A, M> {
-    _margin_curr: std::marker::PhantomData<(A, M)>,
+    _margin_curr: core::marker::PhantomData<(A, M)>,
 }
 
 impl<A, M> ClearingHouse<A, M>
@@ -40,10 +44,266 @@ where
     /// Else its the otherway around.
     /// TODO: not used but may be in the future.
     #[allow(unused)]
-    pub(crate) fn settle_funding_period(&mut self, _mark_value: M, _funding_rate: Decimal) {
+    pub(crate) fn settle_funding_period(&mut self, _mark_value: M, _funding_rate: M::Num) {
         todo!("Support `settle_funding_period`")
     }
 
+    /// Debit the interest on the borrowed notional of the positions from the wallet balance.
+    /// The borrowed notional is the position value at the entry price minus the position margin.
+    ///
+    /// # Arguments:
+    /// `annualized_rate`: The interest rate per 365 days.
+    /// `elapsed_ns`: The time in nanoseconds over which the interest accrued.
+    ///
+    /// # Returns:
+    /// The interest charged.
+    pub(crate) fn settle_borrow_cost(
+        &mut self,
+        account: &mut Account<M>,
+        account_tracker: &mut A,
+        annualized_rate: M::Num,
+        elapsed_ns: i64,
+        ts_ns: i64,
+    ) -> M {
+        const YEAR_NS: i64 = 365 * 86_400_000_000_000;
+
+        let borrowed = [&account.position, &account.short_position]
+            .into_iter()
+            .fold(M::new_zero(), |acc, position| {
+                acc + position.size().abs().convert(position.entry_price())
+                    - position.position_margin()
+            });
+        if borrowed <= M::new_zero() {
+            return M::new_zero();
+        }
+        let borrow_cost =
+            borrowed * annualized_rate * M::Num::from_i64(elapsed_ns) / M::Num::from_i64(YEAR_NS);
+        account.change_balance(
+            borrow_cost.into_negative(),
+            LedgerEntryKind::BorrowCost,
+            ts_ns,
+        );
+        account_tracker.log_borrow_cost(borrow_cost);
+        borrow_cost
+    }
+
+    /// Debit the interest on the short position, which borrows the asset from the `BorrowPool`,
+    /// from the wallet balance.
+    ///
+    /// # Arguments:
+    /// `mark_price`: The price at which the borrowed amount is valued.
+    /// `elapsed_ns`: The time in nanoseconds over which the interest accrued.
+    ///
+    /// # Returns:
+    /// The interest paid.
+    pub(crate) fn settle_short_borrow_interest(
+        &mut self,
+        account: &mut Account<M>,
+        account_tracker: &mut A,
+        borrow_pool: &BorrowPool<M>,
+        mark_price: QuoteCurrency<M::Num>,
+        elapsed_ns: i64,
+        ts_ns: i64,
+    ) -> M {
+        let borrowed = [&account.position, &account.short_position]
+            .into_iter()
+            .map(|position| position.size())
+            .filter(|size| *size < M::PairedCurrency::new_zero())
+            .fold(M::PairedCurrency::new_zero(), |acc, size| acc + size.abs());
+        if borrowed == M::PairedCurrency::new_zero() {
+            return M::new_zero();
+        }
+        let interest = borrow_pool.interest(borrowed, mark_price, elapsed_ns);
+        account.change_balance(interest.into_negative(), LedgerEntryKind::BorrowCost, ts_ns);
+        account_tracker.log_borrow_cost(interest);
+        interest
+    }
+
+    /// Credit the interest on the available balance, which is the margin not used by positions and orders,
+    /// to the wallet balance.
+    ///
+    /// # Arguments:
+    /// `annualized_rate`: The interest rate per 365 days.
+    /// `elapsed_ns`: The time in nanoseconds over which the interest accrued.
+    ///
+    /// # Returns:
+    /// The interest paid.
+    pub(crate) fn settle_idle_margin_interest(
+        &mut self,
+        account: &mut Account<M>,
+        account_tracker: &mut A,
+        annualized_rate: M::Num,
+        elapsed_ns: i64,
+        ts_ns: i64,
+    ) -> M {
+        const YEAR_NS: i64 = 365 * 86_400_000_000_000;
+
+        let idle = account.available_balance();
+        if idle <= M::new_zero() {
+            return M::new_zero();
+        }
+        let interest =
+            idle * annualized_rate * M::Num::from_i64(elapsed_ns) / M::Num::from_i64(YEAR_NS);
+        account.change_balance(interest, LedgerEntryKind::Interest, ts_ns);
+        account_tracker.log_idle_margin_interest(interest);
+        interest
+    }
+
+    /// Cash settle all open positions of an expired dated futures contract
+    /// at the `settlement_price`, without any fees.
+    pub(crate) fn settle_expiry(
+        &mut self,
+        account: &mut Account<M>,
+        account_tracker: &mut A,
+        settlement_price: QuoteCurrency<M::Num>,
+        ts_ns: i64,
+    ) {
+        self.close_positions(
+            account,
+            account_tracker,
+            settlement_price,
+            settlement_price,
+            Fee::new(M::Num::ZERO),
+            LedgerEntryKind::TakerFee,
+            ts_ns,
+        );
+        account_tracker.log_settlement(settlement_price, ts_ns);
+    }
+
+    /// Close all open positions, selling longs at the `bid` and buying back shorts at the `ask`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn close_positions(
+        &mut self,
+        account: &mut Account<M>,
+        account_tracker: &mut A,
+        bid: QuoteCurrency<M::Num>,
+        ask: QuoteCurrency<M::Num>,
+        fee: Fee<M::Num>,
+        fee_kind: LedgerEntryKind,
+        ts_ns: i64,
+    ) {
+        let liquidation = fee_kind == LedgerEntryKind::LiquidationFee;
+        let long_size = account.position.size();
+        if matches!(account.position_mode(), PositionMode::Hedge) {
+            let short_size = account.short_position.size();
+            if long_size != M::PairedCurrency::new_zero() {
+                let before = account.snapshot_positions();
+                self.settle_hedged_order(
+                    account,
+                    account_tracker,
+                    long_size.into_negative(),
+                    bid,
+                    fee,
+                    fee_kind,
+                    true,
+                    ts_ns,
+                );
+                account.record_position_changes(before, bid, liquidation, ts_ns);
+            }
+            if short_size != M::PairedCurrency::new_zero() {
+                let before = account.snapshot_positions();
+                self.settle_hedged_order(
+                    account,
+                    account_tracker,
+                    short_size.abs(),
+                    ask,
+                    fee,
+                    fee_kind,
+                    true,
+                    ts_ns,
+                );
+                account.record_position_changes(before, ask, liquidation, ts_ns);
+            }
+        } else if long_size > M::PairedCurrency::new_zero() {
+            let before = account.snapshot_positions();
+            self.settle_sell_order(
+                account,
+                account_tracker,
+                long_size,
+                bid,
+                fee,
+                fee_kind,
+                ts_ns,
+            );
+            account.record_position_changes(before, bid, liquidation, ts_ns);
+        } else if long_size < M::PairedCurrency::new_zero() {
+            let before = account.snapshot_positions();
+            self.settle_buy_order(
+                account,
+                account_tracker,
+                long_size.abs(),
+                ask,
+                fee,
+                fee_kind,
+                ts_ns,
+            );
+            account.record_position_changes(before, ask, liquidation, ts_ns);
+        }
+    }
+
+    /// Handle a wallet balance which became negative, first by selling collateral,
+    /// and then according to the `BankruptcyMode` if the collateral does not cover the deficit.
+    ///
+    /// # Returns:
+    /// The deficit covered by the insurance fund.
+    pub(crate) fn settle_bankruptcy(
+        &mut self,
+        account: &mut Account<M>,
+        account_tracker: &mut A,
+        bankruptcy_mode: BankruptcyMode,
+        ts_ns: i64,
+    ) -> M {
+        if account.wallet_balance >= M::new_zero() {
+            return M::new_zero();
+        }
+        let converted = account.convert_collateral(account.wallet_balance.abs(), ts_ns);
+        if converted > M::new_zero() {
+            // From the perspective of the wallet balance, the sold collateral is a deposit.
+            account_tracker.log_cash_flow(converted, ts_ns);
+        }
+        if account.wallet_balance >= M::new_zero() {
+            return M::new_zero();
+        }
+        let deficit = account.wallet_balance.abs();
+        match bankruptcy_mode {
+            BankruptcyMode::NegativeEquity => {
+                account_tracker.log_bankruptcy(deficit, false, ts_ns);
+                M::new_zero()
+            }
+            BankruptcyMode::NegativeBalanceProtection => {
+                account.change_balance(deficit, LedgerEntryKind::InsuranceFundPayout, ts_ns);
+                account_tracker.log_bankruptcy(deficit, true, ts_ns);
+                deficit
+            }
+        }
+    }
+
+    /// Exercise an expired European option against the `settlement_price` of the underlying.
+    /// The premium has already been transferred when the option was traded,
+    /// so only the payoff is settled, while the realized profit and loss includes the premium.
+    pub(crate) fn settle_option(
+        &mut self,
+        account: &mut Account<M>,
+        account_tracker: &mut A,
+        option_position: &OptionPosition<M>,
+        settlement_price: QuoteCurrency<M::Num>,
+        ts_ns: i64,
+    ) {
+        let payoff = option_position
+            .contract
+            .intrinsic_value::<M>(settlement_price, option_position.quantity);
+        let payoff = match option_position.side {
+            Side::Buy => payoff,
+            Side::Sell => payoff.into_negative(),
+        };
+        account.change_balance(payoff, LedgerEntryKind::OptionPayoff, ts_ns);
+        account_tracker.log_rpnl(
+            option_position.settlement_pnl(settlement_price),
+            option_position.side,
+            ts_ns,
+        );
+    }
+
     /// Settlement referes to the actual transfer of funds or assets between the buyer and seller to fulfill the trade.
     /// As the `ClearingHouse` is the central counterparty to every trade,
     /// it is the buyer of every sell order,
@@ -53,16 +313,24 @@ where
     /// `quantity`: The number of contract traded, where a negative number indicates a sell.
     /// `fill_price`: The execution price of the trade
     /// `fee`: The fee fraction for this type of order settlement.
+    /// `fee_kind`: How the fee is recorded in the ledger.
+    /// `reduce_only`: Whether the order may only reduce a position,
+    ///     which selects the opposite position in `PositionMode::Hedge`.
     ///
+    /// # Returns:
+    /// The paid fee, denoted in the margin currency, which is negative for rebates.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn settle_filled_order(
         &mut self,
         account: &mut Account<M>,
         account_tracker: &mut A,
         quantity: M::PairedCurrency,
-        fill_price: QuoteCurrency,
-        fee: Fee,
+        fill_price: QuoteCurrency<M::Num>,
+        fee: Fee<M::Num>,
+        fee_kind: LedgerEntryKind,
+        reduce_only: bool,
         ts_ns: i64,
-    ) {
+    ) -> M {
         let side = if quantity > M::PairedCurrency::new_zero() {
             Side::Buy
         } else {
@@ -70,8 +338,28 @@ where
         };
         account_tracker.log_trade(side, fill_price, quantity);
 
-        if quantity > M::PairedCurrency::new_zero() {
-            self.settle_buy_order(account, account_tracker, quantity, fill_price, fee, ts_ns);
+        let before = account.snapshot_positions();
+        let fee = if matches!(account.position_mode(), PositionMode::Hedge) {
+            self.settle_hedged_order(
+                account,
+                account_tracker,
+                quantity,
+                fill_price,
+                fee,
+                fee_kind,
+                reduce_only,
+                ts_ns,
+            )
+        } else if quantity > M::PairedCurrency::new_zero() {
+            self.settle_buy_order(
+                account,
+                account_tracker,
+                quantity,
+                fill_price,
+                fee,
+                fee_kind,
+                ts_ns,
+            )
         } else {
             self.settle_sell_order(
                 account,
@@ -79,23 +367,70 @@ where
                 quantity.abs(),
                 fill_price,
                 fee,
+                fee_kind,
                 ts_ns,
-            );
+            )
+        };
+        account.record_position_changes(
+            before,
+            fill_price,
+            fee_kind == LedgerEntryKind::LiquidationFee,
+            ts_ns,
+        );
+        fee
+    }
+
+    /// Settle an order in `PositionMode::Hedge`,
+    /// where the long and short positions never net against each other.
+    #[allow(clippy::too_many_arguments)]
+    fn settle_hedged_order(
+        &mut self,
+        account: &mut Account<M>,
+        account_tracker: &mut A,
+        quantity: M::PairedCurrency,
+        fill_price: QuoteCurrency<M::Num>,
+        fee: Fee<M::Num>,
+        fee_kind: LedgerEntryKind,
+        reduce_only: bool,
+        ts_ns: i64,
+    ) -> M {
+        let abs_qty = quantity.abs();
+        let fee = abs_qty.convert(fill_price) * fee;
+        let fee = account.pay_fee(fee, fee_kind, ts_ns);
+        account_tracker.log_fee(fee);
+
+        let is_buy = quantity > M::PairedCurrency::new_zero();
+        match (is_buy, reduce_only) {
+            (true, false) => account.position.increase_long(abs_qty, fill_price),
+            (false, false) => account.short_position.increase_short(abs_qty, fill_price),
+            (true, true) => {
+                let rpnl = account.short_position.decrease_short(abs_qty, fill_price);
+                account.change_balance(rpnl, LedgerEntryKind::RealizedPnl, ts_ns);
+                account_tracker.log_rpnl(rpnl - fee, Side::Sell, ts_ns);
+            }
+            (false, true) => {
+                let rpnl = account.position.decrease_long(abs_qty, fill_price);
+                account.change_balance(rpnl, LedgerEntryKind::RealizedPnl, ts_ns);
+                account_tracker.log_rpnl(rpnl - fee, Side::Buy, ts_ns);
+            }
         }
+        fee
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn settle_buy_order(
         &mut self,
         account: &mut Account<M>,
         account_tracker: &mut A,
         quantity: M::PairedCurrency,
-        fill_price: QuoteCurrency,
-        fee: Fee,
+        fill_price: QuoteCurrency<M::Num>,
+        fee: Fee<M::Num>,
+        fee_kind: LedgerEntryKind,
         ts_ns: i64,
-    ) {
+    ) -> M {
         let notional_value = quantity.convert(fill_price);
         let fee = notional_value * fee;
-        account.wallet_balance -= fee;
+        let fee = account.pay_fee(fee, fee_kind, ts_ns);
         account_tracker.log_fee(fee);
 
         if account.position.size() >= M::PairedCurrency::new_zero() {
@@ -105,63 +440,50 @@ where
             if quantity.into_negative() >= account.position.size {
                 // Strictly decrease the short position
                 let rpnl = account.position.decrease_short(quantity, fill_price);
-                account.wallet_balance += rpnl;
-                account_tracker.log_rpnl(rpnl - fee, ts_ns);
+                account.change_balance(rpnl, LedgerEntryKind::RealizedPnl, ts_ns);
+                account_tracker.log_rpnl(rpnl - fee, Side::Sell, ts_ns);
             } else {
-                let new_long_size = quantity - account.position.size().abs();
-
-                // decrease the short first
-                let rpnl = account
-                    .position
-                    .decrease_short(account.position.size().abs(), fill_price);
-                account.wallet_balance += rpnl;
-                account_tracker.log_rpnl(rpnl - fee, ts_ns);
-
-                // also open a long
-                account.position.open_position(new_long_size, fill_price);
+                // Close the short and open a long with the remainder.
+                let rpnl = account.position.flip(quantity, fill_price);
+                account.change_balance(rpnl, LedgerEntryKind::RealizedPnl, ts_ns);
+                account_tracker.log_rpnl(rpnl - fee, Side::Sell, ts_ns);
             }
         }
+        fee
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn settle_sell_order(
         &mut self,
         account: &mut Account<M>,
         account_tracker: &mut A,
         quantity: M::PairedCurrency,
-        fill_price: QuoteCurrency,
-        fee: Fee,
+        fill_price: QuoteCurrency<M::Num>,
+        fee: Fee<M::Num>,
+        fee_kind: LedgerEntryKind,
         ts_ns: i64,
-    ) {
+    ) -> M {
         let notional_value = quantity.convert(fill_price);
         let fee = notional_value * fee;
-        account.wallet_balance -= fee;
+        let fee = account.pay_fee(fee, fee_kind, ts_ns);
         account_tracker.log_fee(fee);
 
         if account.position.size() > M::PairedCurrency::new_zero() {
             if quantity <= account.position.size() {
                 // Decrease the long only
                 let rpnl = account.position.decrease_long(quantity, fill_price);
-                account.wallet_balance += rpnl;
-                account_tracker.log_rpnl(rpnl - fee, ts_ns);
+                account.change_balance(rpnl, LedgerEntryKind::RealizedPnl, ts_ns);
+                account_tracker.log_rpnl(rpnl - fee, Side::Buy, ts_ns);
             } else {
-                let new_short_size = quantity - account.position.size();
-
-                // Close the long
-                let rpnl = account
-                    .position
-                    .decrease_long(account.position.size(), fill_price);
-
-                account.wallet_balance += rpnl;
-                account_tracker.log_rpnl(rpnl - fee, ts_ns);
-
-                // Open a short as well
-                account
-                    .position
-                    .open_position(new_short_size.into_negative(), fill_price);
+                // Close the long and open a short with the remainder.
+                let rpnl = account.position.flip(quantity, fill_price);
+                account.change_balance(rpnl, LedgerEntryKind::RealizedPnl, ts_ns);
+                account_tracker.log_rpnl(rpnl - fee, Side::Buy, ts_ns);
             }
         } else {
             // Increase short position
             account.position.increase_short(quantity, fill_price);
         }
+        fee
     }
 }
@@ -0,0 +1,151 @@
+//! Assets other than the margin currency posted as collateral, like the multi-asset mode of real venues.
+
+use alloc::string::String;
+
+use crate::types::{Currency, Mon};
+
+/// An asset which may be posted as collateral in addition to the margin currency,
+/// configured with `Config::with_collateral_asset`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct CollateralAsset<T>
+where
+    T: Mon,
+{
+    /// The ticker of the asset, e.g. "BTC".
+    pub ticker: String,
+    /// The fraction of the value which does not count towards the margin balance, in range [0, 1).
+    pub haircut: T,
+}
+
+/// The quantity of a `CollateralAsset` held by the account and its last conversion price.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct CollateralBalance<M>
+where
+    M: Currency,
+{
+    asset: CollateralAsset<M::Num>,
+    quantity: M::Num,
+    price: Option<M>,
+}
+
+impl<M> CollateralBalance<M>
+where
+    M: Currency,
+{
+    pub(crate) fn new(asset: CollateralAsset<M::Num>) -> Self {
+        Self {
+            asset,
+            quantity: M::Num::ZERO,
+            price: None,
+        }
+    }
+
+    /// The posted asset.
+    #[inline(always)]
+    pub fn asset(&self) -> &CollateralAsset<M::Num> {
+        &self.asset
+    }
+
+    /// The held quantity of the asset.
+    #[inline(always)]
+    pub fn quantity(&self) -> M::Num {
+        self.quantity
+    }
+
+    /// The last price of one unit of the asset, denoted in the margin currency.
+    /// The collateral is worth nothing until the first price arrives.
+    #[inline(always)]
+    pub fn price(&self) -> Option<M> {
+        self.price
+    }
+
+    /// The value of the held quantity at the last price, denoted in the margin currency.
+    pub fn market_value(&self) -> M {
+        self.price
+            .map_or(M::new_zero(), |price| price * self.quantity)
+    }
+
+    /// The value after the haircut, which counts towards the margin balance.
+    pub fn value(&self) -> M {
+        self.market_value() * (M::Num::ONE - self.asset.haircut)
+    }
+
+    /// The counted value of `quantity` units of the asset.
+    pub(crate) fn value_of(&self, quantity: M::Num) -> M {
+        self.price.map_or(M::new_zero(), |price| {
+            price * quantity * (M::Num::ONE - self.asset.haircut)
+        })
+    }
+
+    pub(crate) fn deposit(&mut self, quantity: M::Num) {
+        self.quantity += quantity;
+    }
+
+    pub(crate) fn withdraw(&mut self, quantity: M::Num) {
+        debug_assert!(quantity <= self.quantity);
+        self.quantity -= quantity;
+    }
+
+    pub(crate) fn set_price(&mut self, price: M) {
+        self.price = Some(price);
+    }
+
+    /// Sell as much of the asset at its market price as is needed to raise `amount`.
+    ///
+    /// # Returns:
+    /// The raised amount, which falls short of `amount` if the collateral is exhausted.
+    pub(crate) fn convert(&mut self, amount: M) -> M {
+        let Some(price) = self.price else {
+            return M::new_zero();
+        };
+        let quantity = (amount / price).inner();
+        if quantity >= self.quantity {
+            let raised = price * self.quantity;
+            self.quantity = M::Num::ZERO;
+            raised
+        } else {
+            self.quantity -= quantity;
+            amount
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{quote, Dec, Decimal, QuoteCurrency};
+
+    fn btc() -> CollateralBalance<QuoteCurrency> {
+        let mut balance = CollateralBalance::new(CollateralAsset {
+            ticker: "BTC".to_string(),
+            haircut: Dec!(0.05),
+        });
+        balance.deposit(Dec!(2));
+        balance
+    }
+
+    #[test]
+    fn collateral_balance_value() {
+        let mut balance = btc();
+        assert_eq!(balance.value(), quote!(0));
+
+        balance.set_price(quote!(100));
+        assert_eq!(balance.market_value(), quote!(200));
+        assert_eq!(balance.value(), quote!(190));
+        assert_eq!(balance.value_of(Dec!(1)), quote!(95));
+    }
+
+    #[test]
+    fn collateral_balance_convert() {
+        let mut balance = btc();
+        assert_eq!(balance.convert(quote!(50)), quote!(0));
+
+        balance.set_price(quote!(100));
+        assert_eq!(balance.convert(quote!(50)), quote!(50));
+        assert_eq!(balance.quantity(), Dec!(1.5));
+        assert_eq!(balance.convert(quote!(500)), quote!(150));
+        assert_eq!(balance.quantity(), Dec!(0));
+    }
+}
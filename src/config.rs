@@ -1,10 +1,31 @@
+use alloc::{string::String, sync::Arc, vec::Vec};
+
 use crate::{
+    borrow_pool::BorrowPool,
+    candle_path::CandlePath,
+    circuit_breaker::CircuitBreaker,
+    collateral::CollateralAsset,
     contract_specification::ContractSpecification,
-    types::{Currency, Error, Leverage, Result},
+    fault_injection::FaultInjection,
+    fee_token::FeeToken,
+    latency::LatencyModel,
+    maker_program::MakerProgram,
+    mark_price::{MarkPriceSource, MidPrice},
+    order_margin::{MaxOfSides, OrderMarginModel},
+    position_limit::PositionLimit,
+    rate_limit::RateLimit,
+    risk_engine::RiskEngine,
+    session_calendar::SessionCalendar,
+    slippage::{NoSlippage, SlippageModel},
+    types::{
+        BankruptcyMode, Currency, DustHandling, Error, Leverage, Mon, PositionMode,
+        PriceImprovement, Result, SelfTradePrevention,
+    },
 };
 
 #[derive(Debug, Clone)]
 /// Define the Exchange configuration
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct Config<M>
 where
     M: Currency,
@@ -21,6 +42,84 @@ where
     initial_leverage: Leverage,
     /// The contract specification.
     contract_specification: ContractSpecification<M::PairedCurrency>,
+    /// The maximum fraction of the wallet balance that a single order may put at risk.
+    max_risk_per_trade: Option<M::Num>,
+    /// Defines when `TimeInForce::Day` orders expire.
+    session_calendar: SessionCalendar,
+    /// Defines when orders are accepted.
+    /// The liquidity program of the venue, which adjusts the fees based on the maker volume share.
+    maker_program: Option<MakerProgram<M::Num>>,
+    /// Whether opposite fills net into a single position or are held as separate long and short positions.
+    position_mode: PositionMode,
+    /// The annualized interest rate charged on the borrowed notional of leveraged positions.
+    borrow_rate: Option<M::Num>,
+    /// The annualized interest rate earned on the available balance not used as margin.
+    idle_margin_rate: Option<M::Num>,
+    /// The timestamp in nanoseconds at which a dated futures contract expires.
+    expiry_ts_ns: Option<i64>,
+    /// The simulated latency of order submissions and cancellations.
+    latency_model: LatencyModel,
+    /// The seed from which random latencies are drawn.
+    latency_seed: u64,
+    /// Determines the fill price of market orders.
+    /// Not serializable, so it falls back to `NoSlippage` when resuming from a checkpoint.
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_slippage_model"))]
+    slippage_model: Arc<dyn SlippageModel<M::Num>>,
+    /// Derives the mark price at which positions are valued for liquidations.
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_mark_price_source"))]
+    mark_price_source: Arc<dyn MarkPriceSource<M::Num>>,
+    /// Combines the margin of the resting buy and sell orders.
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_order_margin_model"))]
+    order_margin_model: Arc<dyn OrderMarginModel<M>>,
+    /// The custom pre-trade checks, run after the built-in margin checks.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    risk_engines: Vec<Arc<dyn RiskEngine<M>>>,
+    /// The assumed price path within candles.
+    candle_path: CandlePath,
+    /// What happens when a loss exceeds the wallet balance.
+    bankruptcy_mode: BankruptcyMode,
+    /// What happens to residuals below the minimum quantity or notional.
+    dust_handling: DustHandling,
+    price_improvement: PriceImprovement,
+    /// Whether an order larger than the opposite position may close it and open a new one.
+    position_flip: bool,
+    /// Caps the notional value of positions.
+    position_limit: PositionLimit<M>,
+    borrow_pool: Option<BorrowPool<M>>,
+    /// What happens when an aggressive order would trade against a resting order of the account.
+    self_trade_prevention: SelfTradePrevention,
+    /// The assets which may be posted as collateral in addition to the margin currency.
+    collateral_assets: Vec<CollateralAsset<M::Num>>,
+    /// The asset in which fees are paid at a discount, if any.
+    fee_token: Option<FeeToken<M::Num>>,
+    /// The `[start, end)` timestamps in nanoseconds of simulated exchange outages.
+    downtime_windows: Vec<(i64, i64)>,
+    circuit_breaker: Option<CircuitBreaker<M::Num>>,
+    /// Limits the rate of order submissions and cancellations.
+    rate_limit: Option<RateLimit>,
+    /// The maximum number of `Fill`s kept in the trade history of the `Exchange`.
+    trade_history_capacity: usize,
+    /// The maximum number of closed orders whose `OrderStatus` the `Exchange` remembers, if bounded.
+    closed_order_capacity: Option<usize>,
+    /// Randomly rejects or delays order submissions and cancellations.
+    fault_injection: Option<FaultInjection>,
+    /// The seed from which the injected faults are drawn.
+    fault_injection_seed: u64,
+}
+
+#[cfg(feature = "serde")]
+fn default_slippage_model<T: Mon>() -> Arc<dyn SlippageModel<T>> {
+    Arc::new(NoSlippage)
+}
+
+#[cfg(feature = "serde")]
+fn default_order_margin_model<M: Currency>() -> Arc<dyn OrderMarginModel<M>> {
+    Arc::new(MaxOfSides)
+}
+
+#[cfg(feature = "serde")]
+fn default_mark_price_source<T: Mon>() -> Arc<dyn MarkPriceSource<T>> {
+    Arc::new(MidPrice)
 }
 
 impl<M> Config<M>
@@ -60,9 +159,57 @@ where
             max_num_open_orders,
             initial_leverage,
             contract_specification,
+            max_risk_per_trade: None,
+            session_calendar: SessionCalendar::default(),
+            maker_program: None,
+            position_mode: PositionMode::default(),
+            borrow_rate: None,
+            idle_margin_rate: None,
+            expiry_ts_ns: None,
+            latency_model: LatencyModel::None,
+            latency_seed: 0,
+            slippage_model: Arc::new(NoSlippage),
+            mark_price_source: Arc::new(MidPrice),
+            order_margin_model: Arc::new(MaxOfSides),
+            risk_engines: Vec::new(),
+            candle_path: CandlePath::default(),
+            bankruptcy_mode: BankruptcyMode::default(),
+            dust_handling: DustHandling::default(),
+            price_improvement: PriceImprovement::default(),
+            position_flip: true,
+            position_limit: PositionLimit::default(),
+            borrow_pool: None,
+            self_trade_prevention: SelfTradePrevention::default(),
+            collateral_assets: Vec::new(),
+            fee_token: None,
+            downtime_windows: Vec::new(),
+            circuit_breaker: None,
+            rate_limit: None,
+            trade_history_capacity: 0,
+            closed_order_capacity: None,
+            fault_injection: None,
+            fault_injection_seed: 0,
         })
     }
 
+    /// Limit the worst-case loss of any single order to a fraction of the equity,
+    /// i.e. the wallet balance plus the unrealized profit and loss at the mark price.
+    /// The worst-case loss is measured to the orders protective stop if it has one,
+    /// or to the liquidation price of the added exposure otherwise.
+    ///
+    /// # Arguments:
+    /// `max_risk_per_trade`: The fraction in range (0, 1], e.g.: 0.02 for 2% of the equity.
+    ///
+    /// # Returns:
+    /// Either the modified Config or an Error if the fraction is out of range.
+    pub fn with_max_risk_per_trade(mut self, max_risk_per_trade: M::Num) -> Result<Self> {
+        if max_risk_per_trade <= M::Num::ZERO || max_risk_per_trade > M::Num::ONE {
+            return Err(Error::InvalidMaxRiskPerTrade);
+        }
+        self.max_risk_per_trade = Some(max_risk_per_trade);
+        Ok(self)
+    }
+
     /// Return the starting wallet balance of this Config
     #[inline(always)]
     pub fn starting_balance(&self) -> M {
@@ -81,6 +228,501 @@ where
         &self.contract_specification
     }
 
+    /// Use a custom `SessionCalendar`, which defines when orders are accepted
+    /// and when `TimeInForce::Day` orders expire,
+    /// instead of being open around the clock with sessions ending at midnight UTC.
+    pub fn with_session_calendar(mut self, session_calendar: SessionCalendar) -> Self {
+        self.session_calendar = session_calendar;
+        self
+    }
+
+    /// Return the `SessionCalendar`, which defines when orders are accepted and `TimeInForce::Day` orders expire.
+    #[inline(always)]
+    pub fn session_calendar(&self) -> &SessionCalendar {
+        &self.session_calendar
+    }
+
+    /// Enroll in a venue liquidity program,
+    /// where the fees of the `FeeSchedule` are replaced by the fees of the qualifying `MakerProgramTier`.
+    pub fn with_maker_program(mut self, maker_program: MakerProgram<M::Num>) -> Self {
+        self.maker_program = Some(maker_program);
+        self
+    }
+
+    /// Return the `MakerProgram` of the venue, if any.
+    #[inline(always)]
+    pub fn maker_program(&self) -> Option<&MakerProgram<M::Num>> {
+        self.maker_program.as_ref()
+    }
+
+    /// Set the `PositionMode`, which defaults to `PositionMode::Netting`.
+    pub fn with_position_mode(mut self, position_mode: PositionMode) -> Self {
+        self.position_mode = position_mode;
+        self
+    }
+
+    /// Return the `PositionMode` of the account.
+    #[inline(always)]
+    pub fn position_mode(&self) -> PositionMode {
+        self.position_mode
+    }
+
+    /// Charge interest on the borrowed notional of leveraged positions,
+    /// which is the part of the position value not backed by position margin.
+    /// The interest accrues with the elapsed time between market updates
+    /// and is debited from the wallet balance.
+    ///
+    /// # Arguments:
+    /// `annualized_rate`: The interest rate per 365 days, e.g.: 0.05 for 5%.
+    ///
+    /// # Returns:
+    /// Either the modified Config or an Error if the rate is negative.
+    pub fn with_borrow_rate(mut self, annualized_rate: M::Num) -> Result<Self> {
+        if annualized_rate < M::Num::ZERO {
+            return Err(Error::InvalidBorrowRate);
+        }
+        self.borrow_rate = Some(annualized_rate);
+        Ok(self)
+    }
+
+    /// Return the annualized interest rate charged on borrowed notional, if any.
+    #[inline(always)]
+    pub fn borrow_rate(&self) -> Option<M::Num> {
+        self.borrow_rate
+    }
+
+    /// Pay interest on the available balance, which is the margin not used by positions and orders,
+    /// as the cash return of idle margin, e.g. the risk-free rate of a USD-margined account.
+    /// The interest accrues with the elapsed time between market updates
+    /// and is credited to the wallet balance.
+    ///
+    /// # Arguments:
+    /// `annualized_rate`: The interest rate per 365 days, e.g.: 0.05 for 5%.
+    ///
+    /// # Returns:
+    /// Either the modified Config or an Error if the rate is negative.
+    pub fn with_idle_margin_rate(mut self, annualized_rate: M::Num) -> Result<Self> {
+        if annualized_rate < M::Num::ZERO {
+            return Err(Error::InvalidIdleMarginRate);
+        }
+        self.idle_margin_rate = Some(annualized_rate);
+        Ok(self)
+    }
+
+    /// Return the annualized interest rate earned on the available balance, if any.
+    #[inline(always)]
+    pub fn idle_margin_rate(&self) -> Option<M::Num> {
+        self.idle_margin_rate
+    }
+
+    /// Make the futures contract a dated one, which expires at `expiry_ts_ns`.
+    /// During the first `update_state` at or after the expiry, all resting orders are cancelled
+    /// and open positions are cash settled at the mid price, without any fees.
+    /// Afterwards no more orders are accepted.
+    ///
+    /// # Arguments:
+    /// `expiry_ts_ns`: The expiry timestamp in nanoseconds, must be positive.
+    ///
+    /// # Returns:
+    /// Either the modified Config or an Error.
+    pub fn with_expiry(mut self, expiry_ts_ns: i64) -> Result<Self> {
+        if expiry_ts_ns <= 0 {
+            return Err(Error::InvalidExpiry);
+        }
+        self.expiry_ts_ns = Some(expiry_ts_ns);
+        Ok(self)
+    }
+
+    /// Return the expiry timestamp in nanoseconds of a dated futures contract, if any.
+    #[inline(always)]
+    pub fn expiry_ts_ns(&self) -> Option<i64> {
+        self.expiry_ts_ns
+    }
+
+    /// Delay order submissions and cancellations by a simulated latency,
+    /// so they only take effect during the first `update_state` at or after their arrival.
+    /// Orders in flight don't reserve any margin and may miss the prices at which they were submitted.
+    ///
+    /// # Arguments:
+    /// `latency_model`: The distribution of latencies.
+    /// `seed`: Makes random latencies deterministic.
+    ///
+    /// # Returns:
+    /// Either the modified Config or an Error if the latencies could be negative.
+    pub fn with_latency(mut self, latency_model: LatencyModel, seed: u64) -> Result<Self> {
+        latency_model.validate()?;
+        self.latency_model = latency_model;
+        self.latency_seed = seed;
+        Ok(self)
+    }
+
+    /// Return the simulated latency of order submissions and cancellations.
+    #[inline(always)]
+    pub fn latency_model(&self) -> &LatencyModel {
+        &self.latency_model
+    }
+
+    /// Return the seed from which random latencies are drawn.
+    #[inline(always)]
+    pub fn latency_seed(&self) -> u64 {
+        self.latency_seed
+    }
+
+    /// Fill market orders at a price away from the best bid or ask, as determined by the `slippage_model`.
+    /// By default market orders fill at the best bid or ask.
+    pub fn with_slippage_model(mut self, slippage_model: Arc<dyn SlippageModel<M::Num>>) -> Self {
+        self.slippage_model = slippage_model;
+        self
+    }
+
+    /// Return the model which determines the fill price of market orders.
+    #[inline(always)]
+    pub fn slippage_model(&self) -> &dyn SlippageModel<M::Num> {
+        self.slippage_model.as_ref()
+    }
+
+    /// Derive the mark price with the `mark_price_source`, at which positions are valued
+    /// for their maintenance margin and liquidation. By default the mark price is the mid price.
+    /// Each `Exchange` starts from a clone of the `mark_price_source`,
+    /// which is reset to the `MidPrice` when resuming from a checkpoint.
+    pub fn with_mark_price_source(
+        mut self,
+        mark_price_source: Arc<dyn MarkPriceSource<M::Num>>,
+    ) -> Self {
+        self.mark_price_source = mark_price_source;
+        self
+    }
+
+    /// Return the source from which the mark price is derived.
+    #[inline(always)]
+    pub fn mark_price_source(&self) -> &dyn MarkPriceSource<M::Num> {
+        self.mark_price_source.as_ref()
+    }
+
+    /// Combine the margin requirements of the resting buy and sell orders with the `order_margin_model`,
+    /// e.g. `SumOfSides` to margin both sides like Binance. By default only the larger side counts.
+    /// The `order_margin_model` is reset to `MaxOfSides` when resuming from a checkpoint.
+    pub fn with_order_margin_model(
+        mut self,
+        order_margin_model: Arc<dyn OrderMarginModel<M>>,
+    ) -> Self {
+        self.order_margin_model = order_margin_model;
+        self
+    }
+
+    /// Return the model which combines the margin of the resting buy and sell orders.
+    #[inline(always)]
+    pub fn order_margin_model(&self) -> &Arc<dyn OrderMarginModel<M>> {
+        &self.order_margin_model
+    }
+
+    /// Add a custom `RiskEngine`, e.g. to limit the order rate, reject fat-finger prices or restrict sides.
+    /// It runs after the built-in margin checks and the previously added risk engines,
+    /// and rejects orders with its `RiskError`, or liquidates if it fails the maintenance margin check.
+    /// Each `Exchange` starts from a clone of the `risk_engine`,
+    /// which is dropped when resuming from a checkpoint.
+    pub fn with_risk_engine(mut self, risk_engine: Arc<dyn RiskEngine<M>>) -> Self {
+        self.risk_engines.push(risk_engine);
+        self
+    }
+
+    /// Return the custom risk engines in the order in which they run.
+    #[inline(always)]
+    pub fn risk_engines(&self) -> &[Arc<dyn RiskEngine<M>>] {
+        &self.risk_engines
+    }
+
+    /// Set the assumed price path within candles,
+    /// which decides the order in which resting orders are filled by a `MarketUpdate::Candle`.
+    pub fn with_candle_path(mut self, candle_path: CandlePath) -> Self {
+        self.candle_path = candle_path;
+        self
+    }
+
+    /// Return the assumed price path within candles.
+    #[inline(always)]
+    pub fn candle_path(&self) -> CandlePath {
+        self.candle_path
+    }
+
+    /// Set what happens when a loss exceeds the wallet balance,
+    /// e.g. because the price gapped through the liquidation price.
+    pub fn with_bankruptcy_mode(mut self, bankruptcy_mode: BankruptcyMode) -> Self {
+        self.bankruptcy_mode = bankruptcy_mode;
+        self
+    }
+
+    /// Return what happens when a loss exceeds the wallet balance.
+    #[inline(always)]
+    pub fn bankruptcy_mode(&self) -> BankruptcyMode {
+        self.bankruptcy_mode
+    }
+
+    /// Set what happens to positions and order remainders which fall below the minimum quantity
+    /// or notional value of the `ContractSpecification` after partial fills.
+    pub fn with_dust_handling(mut self, dust_handling: DustHandling) -> Self {
+        self.dust_handling = dust_handling;
+        self
+    }
+
+    /// Return what happens to residuals below the minimum quantity or notional.
+    #[inline(always)]
+    pub fn dust_handling(&self) -> DustHandling {
+        self.dust_handling
+    }
+
+    /// Set the fill price of aggressive limit orders which cross the spread.
+    /// Defaults to `PriceImprovement::BestQuote`.
+    pub fn with_price_improvement(mut self, price_improvement: PriceImprovement) -> Self {
+        self.price_improvement = price_improvement;
+        self
+    }
+
+    /// Return the fill price of aggressive limit orders which cross the spread.
+    #[inline(always)]
+    pub fn price_improvement(&self) -> PriceImprovement {
+        self.price_improvement
+    }
+
+    /// Set whether an order larger than the opposite position in `PositionMode::Netting`
+    /// closes the position and opens one on the other side with the remainder,
+    /// realizing the profit and loss and re-allocating the margin in one step.
+    /// Enabled by default. If disabled, such orders are rejected with `RiskError::PositionFlipNotAllowed`.
+    pub fn with_position_flip(mut self, position_flip: bool) -> Self {
+        self.position_flip = position_flip;
+        self
+    }
+
+    /// Return whether an order may flip the position.
+    #[inline(always)]
+    pub fn position_flip(&self) -> bool {
+        self.position_flip
+    }
+
+    /// Reject orders which would increase the absolute notional value of a position
+    /// beyond the `position_limit`, e.g. as given by the leverage brackets of a venue.
+    pub fn with_position_limit(mut self, position_limit: PositionLimit<M>) -> Self {
+        self.position_limit = position_limit;
+        self
+    }
+
+    /// Return the limit on the notional value of positions.
+    #[inline(always)]
+    pub fn position_limit(&self) -> &PositionLimit<M> {
+        &self.position_limit
+    }
+
+    /// Limit the size of short positions to the amount of the asset the venue lends
+    /// and charge hourly interest on it, to avoid backtesting shorts which could not be borrowed.
+    pub fn with_borrow_pool(mut self, borrow_pool: BorrowPool<M>) -> Self {
+        self.borrow_pool = Some(borrow_pool);
+        self
+    }
+
+    /// Return the pool which short positions borrow from, if any.
+    #[inline(always)]
+    pub fn borrow_pool(&self) -> Option<&BorrowPool<M>> {
+        self.borrow_pool.as_ref()
+    }
+
+    /// Set what happens when an aggressive order would trade against a resting order of the account,
+    /// which is the case if the resting order is priced at or better than the fill price of the aggressive order.
+    /// Defaults to `SelfTradePrevention::Off`.
+    pub fn with_self_trade_prevention(
+        mut self,
+        self_trade_prevention: SelfTradePrevention,
+    ) -> Self {
+        self.self_trade_prevention = self_trade_prevention;
+        self
+    }
+
+    /// Return what happens when an aggressive order would trade against a resting order of the account.
+    #[inline(always)]
+    pub fn self_trade_prevention(&self) -> SelfTradePrevention {
+        self.self_trade_prevention
+    }
+
+    /// Accept an asset other than the margin currency as collateral, like the multi-asset mode of real venues.
+    /// Its value at the conversion price of `Exchange::update_collateral_price`, reduced by the `haircut`,
+    /// adds to the balance available for margin.
+    /// A negative wallet balance is covered by selling collateral before it counts as a bankruptcy.
+    ///
+    /// # Arguments:
+    /// `ticker`: The ticker of the asset, e.g. "BTC".
+    /// `haircut`: The fraction of the value which does not count towards the margin, in range [0, 1).
+    ///
+    /// # Returns:
+    /// Either the modified Config or an Error if the haircut is out of range or the ticker is already configured.
+    pub fn with_collateral_asset(
+        mut self,
+        ticker: impl Into<String>,
+        haircut: M::Num,
+    ) -> Result<Self> {
+        let ticker = ticker.into();
+        if haircut < M::Num::ZERO
+            || haircut >= M::Num::ONE
+            || self
+                .collateral_assets
+                .iter()
+                .any(|asset| asset.ticker == ticker)
+        {
+            return Err(Error::InvalidCollateralAsset);
+        }
+        self.collateral_assets
+            .push(CollateralAsset { ticker, haircut });
+        Ok(self)
+    }
+
+    /// The assets which may be posted as collateral in addition to the margin currency.
+    #[inline(always)]
+    pub fn collateral_assets(&self) -> &[CollateralAsset<M::Num>] {
+        &self.collateral_assets
+    }
+
+    /// Pay fees in a separate token at a `discount`, like the BNB fee discount of real venues.
+    /// The token is deposited with `Exchange::deposit_fee_token` and valued at the conversion price
+    /// of `Exchange::update_fee_token_price`.
+    /// Fees are paid in the token whenever the held quantity covers the discounted fee,
+    /// and in the margin currency otherwise. Rebates are always credited in the margin currency.
+    ///
+    /// # Arguments:
+    /// `ticker`: The ticker of the token, e.g. "BNB".
+    /// `discount`: The fraction by which fees paid in the token are reduced, in range [0, 1).
+    ///
+    /// # Returns:
+    /// Either the modified Config or an Error if the discount is out of range.
+    pub fn with_fee_token(mut self, ticker: impl Into<String>, discount: M::Num) -> Result<Self> {
+        if discount < M::Num::ZERO || discount >= M::Num::ONE {
+            return Err(Error::InvalidFeeToken);
+        }
+        self.fee_token = Some(FeeToken {
+            ticker: ticker.into(),
+            discount,
+        });
+        Ok(self)
+    }
+
+    /// The asset in which fees are paid at a discount, if any.
+    #[inline(always)]
+    pub fn fee_token(&self) -> Option<&FeeToken<M::Num>> {
+        self.fee_token.as_ref()
+    }
+
+    /// Schedule an exchange outage, during which order submissions and cancellations
+    /// fail with `Error::ExchangeUnavailable` while the market keeps moving,
+    /// to test the robustness of a strategy. May be called once per outage.
+    ///
+    /// # Arguments:
+    /// `start_ns`: The timestamp in nanoseconds at which the outage starts.
+    /// `end_ns`: The timestamp in nanoseconds at which the exchange is available again.
+    ///
+    /// # Returns:
+    /// Either the modified Config or an Error if the outage is empty.
+    pub fn with_downtime(mut self, start_ns: i64, end_ns: i64) -> Result<Self> {
+        if start_ns >= end_ns {
+            return Err(Error::InvalidDowntime);
+        }
+        self.downtime_windows.push((start_ns, end_ns));
+        Ok(self)
+    }
+
+    /// The `[start, end)` timestamps in nanoseconds of the scheduled outages.
+    #[inline(always)]
+    pub fn downtime_windows(&self) -> &[(i64, i64)] {
+        &self.downtime_windows
+    }
+
+    /// Halt trading when a market update moves the mark price too far from the previous one,
+    /// during which order submissions fail with `OrderError::TradingHalted` and only cancellations are accepted.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker<M::Num>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Return the circuit breaker halting trading on large price moves, if any.
+    #[inline(always)]
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker<M::Num>> {
+        self.circuit_breaker.as_ref()
+    }
+
+    /// Limit the rate of order submissions and cancellations,
+    /// which fail with `Error::RateLimited` once the `RateLimit` is exhausted.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// The `RateLimit` of order submissions and cancellations, if any.
+    #[inline(always)]
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit
+    }
+
+    /// Keep the latest `capacity` `Fill`s in the trade history of the `Exchange`,
+    /// where the oldest ones are dropped once it is full. Disabled if 0, which is the default.
+    pub fn with_trade_history(mut self, capacity: usize) -> Self {
+        self.trade_history_capacity = capacity;
+        self
+    }
+
+    /// The maximum number of `Fill`s kept in the trade history of the `Exchange`.
+    #[inline(always)]
+    pub fn trade_history_capacity(&self) -> usize {
+        self.trade_history_capacity
+    }
+
+    /// Remember the final `OrderStatus` of only the latest `capacity` closed orders for `Exchange::order_status`,
+    /// where the oldest ones are forgotten once it is full.
+    /// By default every closed order is remembered, so the memory grows with every order of a long backtest.
+    pub fn with_closed_order_capacity(mut self, capacity: usize) -> Self {
+        self.closed_order_capacity = Some(capacity);
+        self
+    }
+
+    /// The maximum number of closed orders whose `OrderStatus` the `Exchange` remembers, if bounded.
+    #[inline(always)]
+    pub fn closed_order_capacity(&self) -> Option<usize> {
+        self.closed_order_capacity
+    }
+
+    /// Randomly reject or delay a fraction of the order submissions and cancellations,
+    /// to test whether a strategy handles transient failures by retrying or replacing its orders.
+    /// Delayed requests take effect like with a `LatencyModel`.
+    ///
+    /// # Arguments:
+    /// `fault_injection`: The probabilities of rejections and delays.
+    /// `seed`: Makes the injected faults deterministic.
+    pub fn with_fault_injection(mut self, fault_injection: FaultInjection, seed: u64) -> Self {
+        self.fault_injection = Some(fault_injection);
+        self.fault_injection_seed = seed;
+        self
+    }
+
+    /// The `FaultInjection` of order submissions and cancellations, if any.
+    #[inline(always)]
+    pub fn fault_injection(&self) -> Option<FaultInjection> {
+        self.fault_injection
+    }
+
+    /// Return the seed from which the injected faults are drawn.
+    #[inline(always)]
+    pub fn fault_injection_seed(&self) -> u64 {
+        self.fault_injection_seed
+    }
+
+    /// Whether the exchange is unavailable due to a scheduled outage at `ts_ns`.
+    pub fn is_down(&self, ts_ns: i64) -> bool {
+        self.downtime_windows
+            .iter()
+            .any(|(start_ns, end_ns)| *start_ns <= ts_ns && ts_ns < *end_ns)
+    }
+
+    /// Return the maximum fraction of the wallet balance a single order may put at risk, if any.
+    #[inline(always)]
+    pub fn max_risk_per_trade(&self) -> Option<M::Num> {
+        self.max_risk_per_trade
+    }
+
     /// Return the configured initial leverage
     #[inline(always)]
     pub fn initial_leverage(&self) -> Leverage {
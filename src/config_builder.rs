@@ -0,0 +1,507 @@
+//! A builder for the `Config` with named setters and defaults.
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+
+use crate::{
+    borrow_pool::BorrowPool,
+    candle_path::CandlePath,
+    circuit_breaker::CircuitBreaker,
+    config::Config,
+    contract_specification::ContractSpecification,
+    fault_injection::FaultInjection,
+    latency::LatencyModel,
+    leverage,
+    maker_program::MakerProgram,
+    mark_price::MarkPriceSource,
+    order_margin::OrderMarginModel,
+    position_limit::PositionLimit,
+    rate_limit::RateLimit,
+    risk_engine::RiskEngine,
+    session_calendar::SessionCalendar,
+    slippage::SlippageModel,
+    types::{
+        BankruptcyMode, Currency, DustHandling, Error, Leverage, Mon, PositionMode,
+        PriceImprovement, Result, SelfTradePrevention,
+    },
+};
+
+/// Builds a `Config` with named setters.
+/// Everything except the `starting_balance` and the `contract_specification` has a default,
+/// and all settings are validated in `build`.
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder<M>
+where
+    M: Currency,
+{
+    starting_balance: M,
+    max_num_open_orders: usize,
+    initial_leverage: Leverage,
+    contract_specification: Option<ContractSpecification<M::PairedCurrency>>,
+    max_risk_per_trade: Option<M::Num>,
+    session_calendar: SessionCalendar,
+    maker_program: Option<MakerProgram<M::Num>>,
+    position_mode: PositionMode,
+    borrow_rate: Option<M::Num>,
+    idle_margin_rate: Option<M::Num>,
+    expiry_ts_ns: Option<i64>,
+    latency: Option<(LatencyModel, u64)>,
+    slippage_model: Option<Arc<dyn SlippageModel<M::Num>>>,
+    mark_price_source: Option<Arc<dyn MarkPriceSource<M::Num>>>,
+    order_margin_model: Option<Arc<dyn OrderMarginModel<M>>>,
+    risk_engines: Vec<Arc<dyn RiskEngine<M>>>,
+    candle_path: CandlePath,
+    bankruptcy_mode: BankruptcyMode,
+    dust_handling: DustHandling,
+    price_improvement: PriceImprovement,
+    position_flip: bool,
+    position_limit: PositionLimit<M>,
+    borrow_pool: Option<BorrowPool<M>>,
+    self_trade_prevention: SelfTradePrevention,
+    collateral_assets: Vec<(String, M::Num)>,
+    fee_token: Option<(String, M::Num)>,
+    downtime_windows: Vec<(i64, i64)>,
+    circuit_breaker: Option<CircuitBreaker<M::Num>>,
+    rate_limit: Option<RateLimit>,
+    trade_history_capacity: usize,
+    closed_order_capacity: Option<usize>,
+    fault_injection: Option<(FaultInjection, u64)>,
+}
+
+impl<M> Default for ConfigBuilder<M>
+where
+    M: Currency,
+{
+    fn default() -> Self {
+        Self {
+            starting_balance: M::new_zero(),
+            max_num_open_orders: 200,
+            initial_leverage: leverage!(1),
+            contract_specification: None,
+            max_risk_per_trade: None,
+            session_calendar: SessionCalendar::default(),
+            maker_program: None,
+            position_mode: PositionMode::default(),
+            borrow_rate: None,
+            idle_margin_rate: None,
+            expiry_ts_ns: None,
+            latency: None,
+            slippage_model: None,
+            mark_price_source: None,
+            order_margin_model: None,
+            risk_engines: Vec::new(),
+            candle_path: CandlePath::default(),
+            bankruptcy_mode: BankruptcyMode::default(),
+            dust_handling: DustHandling::default(),
+            price_improvement: PriceImprovement::default(),
+            position_flip: true,
+            position_limit: PositionLimit::default(),
+            borrow_pool: None,
+            self_trade_prevention: SelfTradePrevention::default(),
+            collateral_assets: Vec::new(),
+            fee_token: None,
+            downtime_windows: Vec::new(),
+            circuit_breaker: None,
+            rate_limit: None,
+            trade_history_capacity: 0,
+            closed_order_capacity: None,
+            fault_injection: None,
+        }
+    }
+}
+
+impl<M> ConfigBuilder<M>
+where
+    M: Currency,
+{
+    /// Create a new builder with the defaults:
+    /// 200 open orders, a leverage of 1 and no optional settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The starting wallet balance, denoted in the margin currency. Required.
+    pub fn starting_balance(mut self, starting_balance: M) -> Self {
+        self.starting_balance = starting_balance;
+        self
+    }
+
+    /// The maximum number of open orders the user can have at any given time. Defaults to 200.
+    pub fn max_num_open_orders(mut self, max_num_open_orders: usize) -> Self {
+        self.max_num_open_orders = max_num_open_orders;
+        self
+    }
+
+    /// The initial leverage of positions. Defaults to 1.
+    pub fn initial_leverage(mut self, initial_leverage: Leverage) -> Self {
+        self.initial_leverage = initial_leverage;
+        self
+    }
+
+    /// The specification of the traded contract. Required.
+    pub fn contract_specification(
+        mut self,
+        contract_specification: ContractSpecification<M::PairedCurrency>,
+    ) -> Self {
+        self.contract_specification = Some(contract_specification);
+        self
+    }
+
+    /// See `Config::with_max_risk_per_trade`.
+    pub fn max_risk_per_trade(mut self, max_risk_per_trade: M::Num) -> Self {
+        self.max_risk_per_trade = Some(max_risk_per_trade);
+        self
+    }
+
+    /// See `Config::with_session_calendar`.
+    pub fn session_calendar(mut self, session_calendar: SessionCalendar) -> Self {
+        self.session_calendar = session_calendar;
+        self
+    }
+
+    /// See `Config::with_maker_program`.
+    pub fn maker_program(mut self, maker_program: MakerProgram<M::Num>) -> Self {
+        self.maker_program = Some(maker_program);
+        self
+    }
+
+    /// See `Config::with_position_mode`.
+    pub fn position_mode(mut self, position_mode: PositionMode) -> Self {
+        self.position_mode = position_mode;
+        self
+    }
+
+    /// See `Config::with_borrow_rate`.
+    pub fn borrow_rate(mut self, annualized_rate: M::Num) -> Self {
+        self.borrow_rate = Some(annualized_rate);
+        self
+    }
+
+    /// See `Config::with_idle_margin_rate`.
+    pub fn idle_margin_rate(mut self, annualized_rate: M::Num) -> Self {
+        self.idle_margin_rate = Some(annualized_rate);
+        self
+    }
+
+    /// See `Config::with_expiry`.
+    pub fn expiry(mut self, expiry_ts_ns: i64) -> Self {
+        self.expiry_ts_ns = Some(expiry_ts_ns);
+        self
+    }
+
+    /// See `Config::with_latency`.
+    pub fn latency(mut self, latency_model: LatencyModel, seed: u64) -> Self {
+        self.latency = Some((latency_model, seed));
+        self
+    }
+
+    /// See `Config::with_slippage_model`.
+    pub fn slippage_model(mut self, slippage_model: Arc<dyn SlippageModel<M::Num>>) -> Self {
+        self.slippage_model = Some(slippage_model);
+        self
+    }
+
+    /// See `Config::with_mark_price_source`.
+    pub fn mark_price_source(
+        mut self,
+        mark_price_source: Arc<dyn MarkPriceSource<M::Num>>,
+    ) -> Self {
+        self.mark_price_source = Some(mark_price_source);
+        self
+    }
+
+    /// See `Config::with_order_margin_model`.
+    pub fn order_margin_model(mut self, order_margin_model: Arc<dyn OrderMarginModel<M>>) -> Self {
+        self.order_margin_model = Some(order_margin_model);
+        self
+    }
+
+    /// See `Config::with_risk_engine`.
+    pub fn risk_engine(mut self, risk_engine: Arc<dyn RiskEngine<M>>) -> Self {
+        self.risk_engines.push(risk_engine);
+        self
+    }
+
+    /// See `Config::with_candle_path`.
+    pub fn candle_path(mut self, candle_path: CandlePath) -> Self {
+        self.candle_path = candle_path;
+        self
+    }
+
+    /// See `Config::with_bankruptcy_mode`.
+    pub fn bankruptcy_mode(mut self, bankruptcy_mode: BankruptcyMode) -> Self {
+        self.bankruptcy_mode = bankruptcy_mode;
+        self
+    }
+
+    /// See `Config::with_dust_handling`.
+    pub fn dust_handling(mut self, dust_handling: DustHandling) -> Self {
+        self.dust_handling = dust_handling;
+        self
+    }
+
+    /// See `Config::with_price_improvement`.
+    pub fn price_improvement(mut self, price_improvement: PriceImprovement) -> Self {
+        self.price_improvement = price_improvement;
+        self
+    }
+
+    /// See `Config::with_position_flip`.
+    pub fn position_flip(mut self, position_flip: bool) -> Self {
+        self.position_flip = position_flip;
+        self
+    }
+
+    /// See `Config::with_position_limit`.
+    pub fn position_limit(mut self, position_limit: PositionLimit<M>) -> Self {
+        self.position_limit = position_limit;
+        self
+    }
+
+    /// See `Config::with_borrow_pool`.
+    pub fn borrow_pool(mut self, borrow_pool: BorrowPool<M>) -> Self {
+        self.borrow_pool = Some(borrow_pool);
+        self
+    }
+
+    /// See `Config::with_self_trade_prevention`.
+    pub fn self_trade_prevention(mut self, self_trade_prevention: SelfTradePrevention) -> Self {
+        self.self_trade_prevention = self_trade_prevention;
+        self
+    }
+
+    /// See `Config::with_collateral_asset`, may be called once per asset.
+    pub fn collateral_asset(mut self, ticker: impl Into<String>, haircut: M::Num) -> Self {
+        self.collateral_assets.push((ticker.into(), haircut));
+        self
+    }
+
+    /// See `Config::with_fee_token`.
+    pub fn fee_token(mut self, ticker: impl Into<String>, discount: M::Num) -> Self {
+        self.fee_token = Some((ticker.into(), discount));
+        self
+    }
+
+    /// See `Config::with_downtime`, may be called once per outage.
+    pub fn downtime(mut self, start_ns: i64, end_ns: i64) -> Self {
+        self.downtime_windows.push((start_ns, end_ns));
+        self
+    }
+
+    /// See `Config::with_circuit_breaker`.
+    pub fn circuit_breaker(mut self, circuit_breaker: CircuitBreaker<M::Num>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// See `Config::with_rate_limit`.
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// See `Config::with_trade_history`.
+    pub fn trade_history(mut self, capacity: usize) -> Self {
+        self.trade_history_capacity = capacity;
+        self
+    }
+
+    /// See `Config::with_closed_order_capacity`.
+    pub fn closed_order_capacity(mut self, capacity: usize) -> Self {
+        self.closed_order_capacity = Some(capacity);
+        self
+    }
+
+    /// See `Config::with_fault_injection`.
+    pub fn fault_injection(mut self, fault_injection: FaultInjection, seed: u64) -> Self {
+        self.fault_injection = Some((fault_injection, seed));
+        self
+    }
+
+    /// Validate all settings and build the `Config`.
+    /// Suspicious but valid settings, like a taker fee below the maker fee, are logged as warnings.
+    ///
+    /// # Returns:
+    /// Either a valid `Config` or the Error of the first invalid setting.
+    pub fn build(self) -> Result<Config<M>> {
+        let contract_specification = self
+            .contract_specification
+            .ok_or(Error::MissingContractSpecification)?;
+        let valid_margin = |margin: M::Num| margin > M::Num::ZERO && margin <= M::Num::ONE;
+        if !valid_margin(contract_specification.initial_margin)
+            || !valid_margin(contract_specification.maintenance_margin)
+        {
+            return Err(Error::InvalidMarginRequirement);
+        }
+        for tier in contract_specification.fee_schedule.tiers() {
+            if tier.fee_taker.inner() < tier.fee_maker.inner() {
+                warn!(
+                    "The taker fee {} is below the maker fee {} of the fee tier at volume {}",
+                    tier.fee_taker, tier.fee_maker, tier.min_volume
+                );
+            }
+        }
+
+        let mut config = Config::new(
+            self.starting_balance,
+            self.max_num_open_orders,
+            self.initial_leverage,
+            contract_specification,
+        )?
+        .with_session_calendar(self.session_calendar)
+        .with_position_mode(self.position_mode)
+        .with_candle_path(self.candle_path)
+        .with_bankruptcy_mode(self.bankruptcy_mode)
+        .with_dust_handling(self.dust_handling)
+        .with_price_improvement(self.price_improvement)
+        .with_position_flip(self.position_flip)
+        .with_position_limit(self.position_limit)
+        .with_self_trade_prevention(self.self_trade_prevention);
+        if let Some(max_risk_per_trade) = self.max_risk_per_trade {
+            config = config.with_max_risk_per_trade(max_risk_per_trade)?;
+        }
+        if let Some(maker_program) = self.maker_program {
+            config = config.with_maker_program(maker_program);
+        }
+        if let Some(borrow_pool) = self.borrow_pool {
+            config = config.with_borrow_pool(borrow_pool);
+        }
+        if let Some(borrow_rate) = self.borrow_rate {
+            config = config.with_borrow_rate(borrow_rate)?;
+        }
+        if let Some(idle_margin_rate) = self.idle_margin_rate {
+            config = config.with_idle_margin_rate(idle_margin_rate)?;
+        }
+        if let Some(expiry_ts_ns) = self.expiry_ts_ns {
+            config = config.with_expiry(expiry_ts_ns)?;
+        }
+        if let Some((latency_model, seed)) = self.latency {
+            config = config.with_latency(latency_model, seed)?;
+        }
+        if let Some(slippage_model) = self.slippage_model {
+            config = config.with_slippage_model(slippage_model);
+        }
+        if let Some(mark_price_source) = self.mark_price_source {
+            config = config.with_mark_price_source(mark_price_source);
+        }
+        if let Some(order_margin_model) = self.order_margin_model {
+            config = config.with_order_margin_model(order_margin_model);
+        }
+        for risk_engine in self.risk_engines {
+            config = config.with_risk_engine(risk_engine);
+        }
+        for (ticker, haircut) in self.collateral_assets {
+            config = config.with_collateral_asset(ticker, haircut)?;
+        }
+        if let Some((ticker, discount)) = self.fee_token {
+            config = config.with_fee_token(ticker, discount)?;
+        }
+        for (start_ns, end_ns) in self.downtime_windows {
+            config = config.with_downtime(start_ns, end_ns)?;
+        }
+        if let Some(circuit_breaker) = self.circuit_breaker {
+            config = config.with_circuit_breaker(circuit_breaker);
+        }
+        if let Some(rate_limit) = self.rate_limit {
+            config = config.with_rate_limit(rate_limit);
+        }
+        config = config.with_trade_history(self.trade_history_capacity);
+        if let Some(capacity) = self.closed_order_capacity {
+            config = config.with_closed_order_capacity(capacity);
+        }
+        if let Some((fault_injection, seed)) = self.fault_injection {
+            config = config.with_fault_injection(fault_injection, seed);
+        }
+
+        Ok(config)
+    }
+}
+
+impl<M> Config<M>
+where
+    M: Currency,
+{
+    /// Start building a `Config` with named setters.
+    pub fn builder() -> ConfigBuilder<M> {
+        ConfigBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn contract_spec() -> ContractSpecification<BaseCurrency> {
+        ContractSpecification {
+            ticker: "TESTUSD".to_string(),
+            initial_margin: Dec!(0.1),
+            maintenance_margin: Dec!(0.05),
+            mark_method: MarkMethod::MidPrice,
+            price_filter: PriceFilter::default(),
+            quantity_filter: QuantityFilter::default(),
+            notional_filter: NotionalFilter::default(),
+            max_num_orders_filter: MaxNumOrdersFilter::default(),
+            fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+        }
+    }
+
+    #[test]
+    fn config_builder() {
+        let config = Config::builder()
+            .starting_balance(quote!(1000))
+            .contract_specification(contract_spec())
+            .initial_leverage(leverage!(5))
+            .borrow_rate(Dec!(0.05))
+            .idle_margin_rate(Dec!(0.04))
+            .position_mode(PositionMode::Hedge)
+            .build()
+            .unwrap();
+        assert_eq!(config.starting_balance(), quote!(1000));
+        assert_eq!(config.max_num_open_orders(), 200);
+        assert_eq!(config.initial_leverage(), leverage!(5));
+        assert_eq!(config.borrow_rate(), Some(Dec!(0.05)));
+        assert_eq!(config.idle_margin_rate(), Some(Dec!(0.04)));
+        assert_eq!(config.position_mode(), PositionMode::Hedge);
+        assert_eq!(config.expiry_ts_ns(), None);
+    }
+
+    #[test]
+    fn config_builder_validation() {
+        let builder = ConfigBuilder::<QuoteCurrency>::new().contract_specification(contract_spec());
+        assert_eq!(
+            builder.clone().build().unwrap_err(),
+            Error::InvalidStartingBalance
+        );
+        assert_eq!(
+            ConfigBuilder::new()
+                .starting_balance(quote!(1000))
+                .build()
+                .unwrap_err(),
+            Error::MissingContractSpecification
+        );
+
+        let builder = builder.starting_balance(quote!(1000));
+        assert_eq!(
+            builder.clone().max_num_open_orders(0).build().unwrap_err(),
+            Error::InvalidMaxNumOpenOrders
+        );
+        assert_eq!(
+            builder.clone().expiry(-1).build().unwrap_err(),
+            Error::InvalidExpiry
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .max_risk_per_trade(Dec!(1.5))
+                .build()
+                .unwrap_err(),
+            Error::InvalidMaxRiskPerTrade
+        );
+        let mut spec = contract_spec();
+        spec.initial_margin = Dec!(0);
+        assert_eq!(
+            builder.contract_specification(spec).build().unwrap_err(),
+            Error::InvalidMarginRequirement
+        );
+    }
+}
@@ -1,12 +1,13 @@
-use fpdec::Decimal;
+use alloc::string::String;
 
 use crate::{
-    prelude::{Currency, PriceFilter, QuantityFilter},
-    types::Fee,
+    fee_schedule::FeeSchedule,
+    prelude::{Currency, MaxNumOrdersFilter, NotionalFilter, PriceFilter, QuantityFilter},
 };
 
 /// Specifies the details of the futures contract
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct ContractSpecification<S>
 where
     S: Currency,
@@ -14,23 +15,28 @@ where
     /// Identifying ticker symbol
     pub ticker: String,
     /// The initial margin as a fraction.
-    pub initial_margin: Decimal,
+    pub initial_margin: S::Num,
     /// The required maintenance margin as a fraction.
-    pub maintenance_margin: Decimal,
+    pub maintenance_margin: S::Num,
     /// The methods for computing `mark-to-market`
     pub mark_method: MarkMethod,
     /// Pricing rules
-    pub price_filter: PriceFilter,
+    pub price_filter: PriceFilter<S::Num>,
     /// Quantity rules
     pub quantity_filter: QuantityFilter<S>,
-    /// The maker fee as a fraction. e.g.: 2.5 basis points rebate -> -0.00025
-    pub fee_maker: Fee,
-    /// The taker fee as a fraction. e.g.: 10 basis points -> 0.0010
-    pub fee_taker: Fee,
+    /// Notional value rules, denoted in the margin currency
+    pub notional_filter: NotionalFilter<S::PairedCurrency>,
+    /// Limits on the number of resting limit orders
+    pub max_num_orders_filter: MaxNumOrdersFilter,
+    /// The maker and taker fees, which may depend on the rolling traded volume.
+    /// Use `FeeSchedule::flat` for static fees.
+    pub fee_schedule: FeeSchedule<S::Num>,
 }
 
 /// Which price to use in `mark-to-market` calculations
+/// Only descriptive, the mark price is derived by the `MarkPriceSource` of the `Config`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MarkMethod {
     /// Take the last mid price of the market.
     MidPrice,
@@ -1,6 +1,8 @@
+use num_traits::Float;
+
 use crate::{
     account_tracker::statistical_moments,
-    types::{Currency, LnReturns},
+    types::{Currency, LnReturns, Mon},
     Result,
 };
 
@@ -18,7 +20,7 @@ pub struct CornishFisherOutput<M> {
 /// - 'log_returns': natural logarithmic return series: (p1 / p0).ln()
 /// - `asset_value`: Serves as the base from which the `asset_value_at_risk` is computed.
 /// - `confidence_interval`: in range [0.0, 1.0], usually something like 0.01 or
-/// 0.05.
+///   0.05.
 ///
 pub(crate) fn cornish_fisher_value_at_risk<C>(
     ln_returns: &LnReturns<'_, f64>,
@@ -32,11 +34,11 @@ where
 
     // Its a bit expensive to compute, so only warn if user opts-in
     #[cfg(feature = "cornish_fisher_domain_warning")]
-    if stats.skew <= 6.0 * (2_f64.sqrt() - 1.0)
+    if stats.skew <= 6.0 * (core::f64::consts::SQRT_2 - 1.0)
         && 27.0 * stats.excess_kurtosis
-            - (216.0 + 66.0 * stats.skew.powi(2)) * stats.excess_kurtosis
-            + 40.0 * stats.skew.powi(4)
-            + 336.0 * stats.skew.powi(2)
+            - (216.0 + 66.0 * Float::powi(stats.skew, 2)) * stats.excess_kurtosis
+            + 40.0 * Float::powi(stats.skew, 4)
+            + 336.0 * Float::powi(stats.skew, 2)
             <= 0.0
     {
         // See <https://portfoliooptimizer.io/blog/corrected-cornish-fisher-expansion-improving-the-accuracy-of-modified-value-at-risk/>
@@ -46,16 +48,16 @@ where
     let quantile = distrs::Normal::ppf(confidence_interval, 0.0, 1.0);
 
     let exp = quantile
-        + (quantile.powi(2) - 1.0) * stats.skew / 6.0
-        + (quantile.powi(3) - 3.0 * quantile) * stats.excess_kurtosis / 24.0
-        - (2.0 * quantile.powi(3) - 5.0 * quantile) * stats.skew.powi(2) / 36.0;
+        + (Float::powi(quantile, 2) - 1.0) * stats.skew / 6.0
+        + (Float::powi(quantile, 3) - 3.0 * quantile) * stats.excess_kurtosis / 24.0
+        - (2.0 * Float::powi(quantile, 3) - 5.0 * quantile) * Float::powi(stats.skew, 2) / 36.0;
 
     let var = stats.mean + stats.std_dev * exp;
 
     // If these were percent returns we'd use the commented out one.
     // But here we use ln returns, so we take the latter one.
     // let asset_value_at_risk = asset_value * C::new((1.0 + var).try_into()?);
-    let asset_value_at_risk = asset_value * C::new(var.exp().try_into()?);
+    let asset_value_at_risk = asset_value * C::new(C::Num::from_f64(Float::exp(var))?);
 
     Ok(CornishFisherOutput {
         var,
@@ -0,0 +1,182 @@
+//! Coordinates several `Exchange`s for cross-exchange strategies like basis or arbitrage trades.
+
+use alloc::vec::Vec;
+
+use crate::{
+    account_tracker::AccountTracker,
+    exchange::Exchange,
+    types::{Currency, Error, MarginCurrency, MarketUpdate, Order, Result},
+};
+
+/// Funds withdrawn from one venue which are credited to another once they arrive.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct PendingTransfer<M>
+where
+    M: Currency,
+{
+    /// The index of the venue receiving the funds.
+    pub to: usize,
+    /// The transferred amount, denoted in the margin currency.
+    pub amount: M,
+    /// The timestamp in nanoseconds at which the funds arrive.
+    pub arrival_ts_ns: u64,
+}
+
+/// Runs several `Exchange` venues, each with its own `Config`, e.g. different fees or latencies,
+/// against correlated `MarketUpdate` streams.
+/// It tracks the combined equity and transfers funds between the venues,
+/// which take `transfer_delay_ns` to arrive.
+#[derive(Debug, Clone)]
+pub struct CrossExchange<A, S>
+where
+    S: Currency,
+    S::PairedCurrency: MarginCurrency,
+{
+    venues: Vec<Exchange<A, S>>,
+    transfer_delay_ns: u64,
+    pending_transfers: Vec<PendingTransfer<S::PairedCurrency>>,
+    /// The latest timestamp of any market update.
+    current_ts_ns: u64,
+}
+
+impl<A, S> CrossExchange<A, S>
+where
+    A: AccountTracker<S::PairedCurrency>,
+    S: Currency,
+    S::PairedCurrency: MarginCurrency,
+{
+    /// Create a new `CrossExchange`, where transfers arrive instantly.
+    pub fn new(venues: Vec<Exchange<A, S>>) -> Self {
+        Self {
+            venues,
+            transfer_delay_ns: 0,
+            pending_transfers: Vec::new(),
+            current_ts_ns: 0,
+        }
+    }
+
+    /// Let transfers arrive `transfer_delay_ns` after the latest market update,
+    /// like on-chain or banking transfers between real venues.
+    pub fn with_transfer_delay(mut self, transfer_delay_ns: u64) -> Self {
+        self.transfer_delay_ns = transfer_delay_ns;
+        self
+    }
+
+    /// Add another venue.
+    ///
+    /// # Returns:
+    /// The index of the new venue.
+    pub fn add_venue(&mut self, exchange: Exchange<A, S>) -> usize {
+        self.venues.push(exchange);
+        self.venues.len() - 1
+    }
+
+    /// Return all venues in the order they were added.
+    #[inline(always)]
+    pub fn venues(&self) -> &[Exchange<A, S>] {
+        &self.venues
+    }
+
+    /// Return the venue at index `venue`.
+    ///
+    /// # Panics:
+    /// If there is no such venue.
+    #[inline(always)]
+    pub fn venue(&self, venue: usize) -> &Exchange<A, S> {
+        &self.venues[venue]
+    }
+
+    /// Return the venue at index `venue` mutably, e.g. to submit orders.
+    ///
+    /// # Panics:
+    /// If there is no such venue.
+    #[inline(always)]
+    pub fn venue_mut(&mut self, venue: usize) -> &mut Exchange<A, S> {
+        &mut self.venues[venue]
+    }
+
+    /// Return the transfers which have not arrived yet.
+    #[inline(always)]
+    pub fn pending_transfers(&self) -> &[PendingTransfer<S::PairedCurrency>] {
+        &self.pending_transfers
+    }
+
+    /// Update the state of one venue with a new market update,
+    /// after crediting all transfers which arrived by `timestamp_ns`.
+    ///
+    /// # Returns:
+    /// The executed orders of the venue, see `Exchange::update_state`,
+    /// or `Error::UnknownVenue` without any effect if there is no venue at index `venue`.
+    pub fn update_state(
+        &mut self,
+        venue: usize,
+        timestamp_ns: u64,
+        market_update: MarketUpdate<S>,
+    ) -> Result<Vec<Order<S>>> {
+        if venue >= self.venues.len() {
+            return Err(Error::UnknownVenue);
+        }
+        self.current_ts_ns = self.current_ts_ns.max(timestamp_ns);
+        self.settle_transfers()?;
+        self.venues[venue].update_state(timestamp_ns, market_update)
+    }
+
+    /// Withdraw the `amount` from the venue `from` and deposit it into the venue `to`,
+    /// once it arrives after the transfer delay.
+    ///
+    /// # Returns:
+    /// If Err, nothing was transferred, because the venues are invalid
+    /// or the available balance of `from` cannot cover the `amount`.
+    pub fn transfer(&mut self, from: usize, to: usize, amount: S::PairedCurrency) -> Result<()> {
+        if from == to || from >= self.venues.len() || to >= self.venues.len() {
+            return Err(Error::InvalidTransfer);
+        }
+        self.venues[from].withdraw(amount)?;
+        self.pending_transfers.push(PendingTransfer {
+            to,
+            amount,
+            arrival_ts_ns: self.current_ts_ns + self.transfer_delay_ns,
+        });
+        self.settle_transfers()
+    }
+
+    /// Deposit the transfers which arrived by the latest market update.
+    fn settle_transfers(&mut self) -> Result<()> {
+        let current_ts_ns = self.current_ts_ns;
+        let (arrived, pending): (Vec<_>, Vec<_>) = self
+            .pending_transfers
+            .drain(..)
+            .partition(|transfer| transfer.arrival_ts_ns <= current_ts_ns);
+        self.pending_transfers = pending;
+        for transfer in arrived {
+            self.venues[transfer.to].deposit(transfer.amount)?;
+        }
+        Ok(())
+    }
+
+    /// The equity of the venue at index `venue`,
+    /// which is its effective balance plus the unrealized profit and loss at its best bid and ask.
+    ///
+    /// # Panics:
+    /// If there is no such venue.
+    pub fn venue_equity(&self, venue: usize) -> S::PairedCurrency {
+        let exchange = &self.venues[venue];
+        let market_state = exchange.market_state();
+        exchange.account().effective_balance()
+            + exchange
+                .account()
+                .unrealized_pnl(market_state.bid(), market_state.ask())
+    }
+
+    /// The equity of all venues plus the funds in transit between them.
+    pub fn combined_equity(&self) -> S::PairedCurrency {
+        let in_transit = self
+            .pending_transfers
+            .iter()
+            .fold(S::PairedCurrency::new_zero(), |acc, transfer| {
+                acc + transfer.amount
+            });
+        (0..self.venues.len()).fold(in_transit, |acc, venue| acc + self.venue_equity(venue))
+    }
+}
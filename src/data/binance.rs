@@ -0,0 +1,388 @@
+//! Reads the aggTrades and bookTicker dumps of Binance, the most common source of market data.
+
+use std::{fs::File, io::Read, marker::PhantomData, path::Path};
+
+use csv::StringRecord;
+
+use crate::types::{Currency, Error, MarketUpdate, Mon, QuoteCurrency, Result, Side};
+
+/// The kind of a Binance market data dump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinanceFormat {
+    /// Aggregated trades with the columns
+    /// `agg_trade_id, price, quantity, first_trade_id, last_trade_id, transact_time, is_buyer_maker`,
+    /// followed by `is_best_match` in spot dumps.
+    AggTrades,
+    /// Best bid and ask updates with the columns
+    /// `update_id, best_bid_price, best_bid_qty, best_ask_price, best_ask_qty, transaction_time, event_time`.
+    BookTicker,
+}
+
+/// Convert a Binance timestamp to nanoseconds.
+/// The dumps use milliseconds, except for spot data since 2025 which uses microseconds,
+/// so the unit is inferred from the magnitude.
+pub fn binance_timestamp_ns(timestamp: u64) -> u64 {
+    if timestamp < 100_000_000_000_000 {
+        timestamp * 1_000_000
+    } else if timestamp < 100_000_000_000_000_000 {
+        timestamp * 1_000
+    } else {
+        timestamp
+    }
+}
+
+fn number<T: Mon>(value: &str) -> Result<T> {
+    value
+        .parse::<T>()
+        .map_err(|_| Error::InvalidMarketData(format!("not a number: {value:?}")))
+}
+
+fn timestamp(value: &str) -> Result<u64> {
+    value
+        .parse::<u64>()
+        .map(binance_timestamp_ns)
+        .map_err(|_| Error::InvalidMarketData(format!("not a timestamp: {value:?}")))
+}
+
+/// The aggressor of a trade, which is the seller if the buyer is the maker.
+fn aggressor(is_buyer_maker: &str) -> Result<Side> {
+    match is_buyer_maker.to_ascii_lowercase().as_str() {
+        "true" => Ok(Side::Sell),
+        "false" => Ok(Side::Buy),
+        _ => Err(Error::InvalidMarketData(format!(
+            "not a boolean: {is_buyer_maker:?}"
+        ))),
+    }
+}
+
+fn agg_trade<S: Currency>(
+    price: &str,
+    quantity: &str,
+    is_buyer_maker: &str,
+) -> Result<MarketUpdate<S>> {
+    Ok(MarketUpdate::Trade {
+        price: QuoteCurrency::new(number(price)?),
+        quantity: S::new(number(quantity)?),
+        side: aggressor(is_buyer_maker)?,
+    })
+}
+
+fn book_ticker<S: Currency>(bid: &str, ask: &str) -> Result<MarketUpdate<S>> {
+    Ok(MarketUpdate::Bba {
+        bid: QuoteCurrency::new(number(bid)?),
+        ask: QuoteCurrency::new(number(ask)?),
+    })
+}
+
+/// Streams `(timestamp_ns, MarketUpdate)` items from a Binance CSV dump, e.g. from data.binance.vision,
+/// one per row, with or without the header row.
+#[derive(Debug)]
+pub struct BinanceCsvMarketData<R, S>
+where
+    S: Currency,
+{
+    reader: csv::Reader<R>,
+    record: StringRecord,
+    format: BinanceFormat,
+    _currency: PhantomData<S>,
+}
+
+impl<S> BinanceCsvMarketData<File, S>
+where
+    S: Currency,
+{
+    /// Open the Binance CSV dump at `path`.
+    ///
+    /// # Returns:
+    /// An Error if the file can not be opened.
+    pub fn from_path<P: AsRef<Path>>(path: P, format: BinanceFormat) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|e| Error::InvalidMarketData(format!("{}: {e}", path.display())))?;
+        Ok(Self::from_reader(file, format))
+    }
+}
+
+impl<R, S> BinanceCsvMarketData<R, S>
+where
+    R: Read,
+    S: Currency,
+{
+    /// Read a Binance CSV dump from any `reader`.
+    pub fn from_reader(reader: R, format: BinanceFormat) -> Self {
+        Self {
+            reader: csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .trim(csv::Trim::All)
+                .from_reader(reader),
+            record: StringRecord::new(),
+            format,
+            _currency: PhantomData,
+        }
+    }
+
+    /// Parse the current `record` into a market update.
+    fn parse_record(&self) -> Result<(u64, MarketUpdate<S>)> {
+        let field = |index: usize| {
+            self.record
+                .get(index)
+                .ok_or_else(|| Error::InvalidMarketData(format!("missing value in column {index}")))
+        };
+        match self.format {
+            BinanceFormat::AggTrades => Ok((
+                timestamp(field(5)?)?,
+                agg_trade(field(1)?, field(2)?, field(6)?)?,
+            )),
+            BinanceFormat::BookTicker => {
+                Ok((timestamp(field(5)?)?, book_ticker(field(1)?, field(3)?)?))
+            }
+        }
+    }
+}
+
+impl<R, S> Iterator for BinanceCsvMarketData<R, S>
+where
+    R: Read,
+    S: Currency,
+{
+    type Item = Result<(u64, MarketUpdate<S>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.reader.read_record(&mut self.record) {
+                Ok(true) => {
+                    let line = self.record.position().map_or(0, |pos| pos.line());
+                    // The newer dumps start with a header row.
+                    if line == 1
+                        && self
+                            .record
+                            .get(0)
+                            .is_some_and(|id| id.parse::<u64>().is_err())
+                    {
+                        continue;
+                    }
+                    return Some(self.parse_record().map_err(|e| match e {
+                        Error::InvalidMarketData(reason) => {
+                            Error::InvalidMarketData(format!("line {line}: {reason}"))
+                        }
+                        e => e,
+                    }));
+                }
+                Ok(false) => return None,
+                Err(e) => return Some(Err(Error::InvalidMarketData(e.to_string()))),
+            }
+        }
+    }
+}
+
+/// Streams `(timestamp_ns, MarketUpdate)` items from recorded Binance websocket messages,
+/// one JSON object per line, behind the `serde` feature.
+/// Both `aggTrade` and `bookTicker` messages are understood, also when wrapped by a combined stream.
+/// A `bookTicker` message requires its transaction time `T` or event time `E`,
+/// which the spot streams do not carry.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub struct BinanceJsonMarketData<R, S>
+where
+    S: Currency,
+{
+    lines: std::io::Lines<R>,
+    line: usize,
+    _currency: PhantomData<S>,
+}
+
+#[cfg(feature = "serde")]
+impl<R, S> BinanceJsonMarketData<R, S>
+where
+    R: std::io::BufRead,
+    S: Currency,
+{
+    /// Read the messages from any buffered `reader`.
+    pub fn from_reader(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line: 0,
+            _currency: PhantomData,
+        }
+    }
+
+    fn parse_message(message: &str) -> Result<(u64, MarketUpdate<S>)> {
+        let invalid = |reason: &str| Error::InvalidMarketData(reason.to_string());
+        let value: serde_json::Value =
+            serde_json::from_str(message).map_err(|e| invalid(&e.to_string()))?;
+        let data = value.get("data").unwrap_or(&value);
+        let text = |key: &str| {
+            data.get(key)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| invalid(&format!("missing field {key}")))
+        };
+        let time = |key: &str| data.get(key).and_then(|v| v.as_u64());
+
+        if data.get("e").and_then(|v| v.as_str()) == Some("aggTrade") {
+            let ts = time("T").ok_or_else(|| invalid("missing field T"))?;
+            let is_buyer_maker = data
+                .get("m")
+                .and_then(|v| v.as_bool())
+                .ok_or_else(|| invalid("missing field m"))?;
+            let update = agg_trade(text("p")?, text("q")?, &is_buyer_maker.to_string())?;
+            Ok((binance_timestamp_ns(ts), update))
+        } else if data.get("b").is_some() && data.get("a").is_some() {
+            let ts = time("T")
+                .or_else(|| time("E"))
+                .ok_or_else(|| invalid("bookTicker without a timestamp"))?;
+            Ok((
+                binance_timestamp_ns(ts),
+                book_ticker(text("b")?, text("a")?)?,
+            ))
+        } else {
+            Err(invalid("neither an aggTrade nor a bookTicker message"))
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<R, S> Iterator for BinanceJsonMarketData<R, S>
+where
+    R: std::io::BufRead,
+    S: Currency,
+{
+    type Item = Result<(u64, MarketUpdate<S>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(Error::InvalidMarketData(e.to_string()))),
+            };
+            self.line += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Some(Self::parse_message(&line).map_err(|e| match e {
+                Error::InvalidMarketData(reason) => {
+                    Error::InvalidMarketData(format!("line {}: {reason}", self.line))
+                }
+                e => e,
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, trade};
+
+    #[test]
+    fn binance_timestamps() {
+        assert_eq!(
+            binance_timestamp_ns(1_700_000_000_123),
+            1_700_000_000_123_000_000
+        );
+        assert_eq!(
+            binance_timestamp_ns(1_700_000_000_123_456),
+            1_700_000_000_123_456_000
+        );
+        assert_eq!(
+            binance_timestamp_ns(1_700_000_000_123_456_789),
+            1_700_000_000_123_456_789
+        );
+    }
+
+    #[test]
+    fn binance_agg_trades_csv() {
+        let data = "agg_trade_id,price,quantity,first_trade_id,last_trade_id,transact_time,is_buyer_maker\n\
+            1,42000.5,0.010,10,11,1700000000000,true\n\
+            2,42001.0,0.500,12,12,1700000000001,false\n";
+        let updates =
+            Result::<Vec<_>>::from_iter(BinanceCsvMarketData::<_, BaseCurrency>::from_reader(
+                data.as_bytes(),
+                BinanceFormat::AggTrades,
+            ))
+            .unwrap();
+        assert_eq!(
+            updates,
+            vec![
+                (
+                    1_700_000_000_000_000_000,
+                    trade!(quote!(42000.5), base!(0.01), Side::Sell)
+                ),
+                (
+                    1_700_000_000_001_000_000,
+                    trade!(quote!(42001), base!(0.5), Side::Buy)
+                ),
+            ]
+        );
+
+        // Spot dumps come without a header, with microseconds and an additional column.
+        let data = "1,42000.5,0.010,10,11,1735689600000000,True,True\n";
+        let updates =
+            Result::<Vec<_>>::from_iter(BinanceCsvMarketData::<_, BaseCurrency>::from_reader(
+                data.as_bytes(),
+                BinanceFormat::AggTrades,
+            ))
+            .unwrap();
+        assert_eq!(updates[0].0, 1_735_689_600_000_000_000);
+    }
+
+    #[test]
+    fn binance_book_ticker_csv() {
+        let data = "update_id,best_bid_price,best_bid_qty,best_ask_price,best_ask_qty,transaction_time,event_time\n\
+            7,42000.1,1.5,42000.2,0.3,1700000000000,1700000000002\n\
+            8,42000.1,x,42000.2,0.3,1700000000001,1700000000003\n\
+            9,42000.0,1.5,oops,0.3,1700000000002,1700000000004\n";
+        let mut updates = BinanceCsvMarketData::<_, BaseCurrency>::from_reader(
+            data.as_bytes(),
+            BinanceFormat::BookTicker,
+        );
+        assert_eq!(
+            updates.next().unwrap().unwrap(),
+            (
+                1_700_000_000_000_000_000,
+                bba!(quote!(42000.1), quote!(42000.2))
+            )
+        );
+        // The quantities are not used.
+        assert!(updates.next().unwrap().is_ok());
+        assert_eq!(
+            updates.next().unwrap(),
+            Err(Error::InvalidMarketData(
+                "line 4: not a number: \"oops\"".to_string()
+            ))
+        );
+        assert!(updates.next().is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn binance_json_messages() {
+        let data = r#"{"e":"aggTrade","E":1700000000005,"s":"BTCUSDT","a":5,"p":"42000.5","q":"0.010","f":10,"l":11,"T":1700000000004,"m":false}
+
+{"stream":"btcusdt@bookTicker","data":{"e":"bookTicker","u":7,"s":"BTCUSDT","b":"42000.1","B":"1.5","a":"42000.2","A":"0.3","T":1700000000006,"E":1700000000007}}
+{"u":8,"s":"BTCUSDT","b":"42000.1","B":"1.5","a":"42000.2","A":"0.3"}
+"#;
+        let mut updates = BinanceJsonMarketData::<_, BaseCurrency>::from_reader(data.as_bytes());
+        assert_eq!(
+            updates.next().unwrap().unwrap(),
+            (
+                1_700_000_000_004_000_000,
+                trade!(quote!(42000.5), base!(0.01), Side::Buy)
+            )
+        );
+        assert_eq!(
+            updates.next().unwrap().unwrap(),
+            (
+                1_700_000_000_006_000_000,
+                bba!(quote!(42000.1), quote!(42000.2))
+            )
+        );
+        assert_eq!(
+            updates.next().unwrap(),
+            Err(Error::InvalidMarketData(
+                "line 4: bookTicker without a timestamp".to_string()
+            ))
+        );
+        assert!(updates.next().is_none());
+    }
+}
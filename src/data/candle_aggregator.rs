@@ -0,0 +1,334 @@
+//! Aggregates a stream of trades into candles.
+
+use crate::types::{Currency, Error, MarketUpdate, QuoteCurrency, Result};
+
+/// When a `CandleAggregator` completes a candle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CandleInterval<S>
+where
+    S: Currency,
+{
+    /// Candles of a fixed duration in nanoseconds, aligned to multiples of it since the unix epoch.
+    /// A candle completes with the first trade of a later interval, so intervals without trades are skipped.
+    Time(u64),
+    /// Candles of a fixed number of trades.
+    Ticks(usize),
+    /// Candles of at least this traded quantity, completed by the trade which reaches it.
+    Volume(S),
+}
+
+/// The candle which is currently being aggregated.
+#[derive(Debug, Clone)]
+struct OpenCandle<S>
+where
+    S: Currency,
+{
+    /// The index of the time interval, for `CandleInterval::Time`.
+    bucket: u64,
+    last_ts_ns: u64,
+    low: QuoteCurrency<S::Num>,
+    high: QuoteCurrency<S::Num>,
+    close: QuoteCurrency<S::Num>,
+    num_trades: usize,
+    volume: S,
+}
+
+/// Consumes `MarketUpdate::Trade`s and emits a `MarketUpdate::Candle` whenever the `CandleInterval` completes,
+/// so raw trade data can drive candle based simulations.
+/// As trades carry no quotes, the bid of a candle is its close price and the ask is the close price plus the `spread`.
+/// The range of a candle includes its closing quotes, so the low is always below the high.
+#[derive(Debug, Clone)]
+pub struct CandleAggregator<S>
+where
+    S: Currency,
+{
+    interval: CandleInterval<S>,
+    spread: QuoteCurrency<S::Num>,
+    current: Option<OpenCandle<S>>,
+}
+
+impl<S> CandleAggregator<S>
+where
+    S: Currency,
+{
+    /// Create a new aggregator.
+    ///
+    /// # Arguments:
+    /// `interval`: When a candle completes.
+    /// `spread`: The assumed spread at the close of a candle, e.g. the tick size.
+    ///
+    /// # Returns:
+    /// An Error if the `interval` or the `spread` is not positive.
+    pub fn new(interval: CandleInterval<S>, spread: QuoteCurrency<S::Num>) -> Result<Self> {
+        let valid_interval = match interval {
+            CandleInterval::Time(duration_ns) => duration_ns > 0,
+            CandleInterval::Ticks(num_trades) => num_trades > 0,
+            CandleInterval::Volume(volume) => volume > S::new_zero(),
+        };
+        if !valid_interval || spread <= QuoteCurrency::new_zero() {
+            return Err(Error::NonPositive);
+        }
+        Ok(Self {
+            interval,
+            spread,
+            current: None,
+        })
+    }
+
+    /// Return the interval of the candles.
+    #[inline(always)]
+    pub fn interval(&self) -> CandleInterval<S> {
+        self.interval
+    }
+
+    /// Add a market update to the current candle. Updates other than trades are ignored.
+    ///
+    /// # Returns:
+    /// The timestamp in nanoseconds and the candle, if one got completed.
+    /// Time based candles are timestamped at the end of their interval, all others at their last trade.
+    pub fn update(
+        &mut self,
+        timestamp_ns: u64,
+        market_update: &MarketUpdate<S>,
+    ) -> Option<(u64, MarketUpdate<S>)> {
+        let MarketUpdate::Trade {
+            price, quantity, ..
+        } = *market_update
+        else {
+            return None;
+        };
+        let bucket = match self.interval {
+            CandleInterval::Time(duration_ns) => timestamp_ns / duration_ns,
+            CandleInterval::Ticks(_) | CandleInterval::Volume(_) => 0,
+        };
+        let mut completed = None;
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|candle| candle.bucket != bucket)
+        {
+            completed = self.flush();
+        }
+        let candle = self.current.get_or_insert(OpenCandle {
+            bucket,
+            last_ts_ns: timestamp_ns,
+            low: price,
+            high: price,
+            close: price,
+            num_trades: 0,
+            volume: S::new_zero(),
+        });
+        candle.last_ts_ns = timestamp_ns;
+        if price < candle.low {
+            candle.low = price;
+        }
+        if price > candle.high {
+            candle.high = price;
+        }
+        candle.close = price;
+        candle.num_trades += 1;
+        candle.volume += quantity.abs();
+
+        let complete = match self.interval {
+            CandleInterval::Time(_) => false,
+            CandleInterval::Ticks(num_trades) => candle.num_trades >= num_trades,
+            CandleInterval::Volume(volume) => candle.volume >= volume,
+        };
+        if complete {
+            debug_assert!(completed.is_none());
+            completed = self.flush();
+        }
+        completed
+    }
+
+    /// Complete the current candle early, e.g. at the end of the trade data.
+    ///
+    /// # Returns:
+    /// The timestamp in nanoseconds and the candle, if any trades were aggregated since the last candle.
+    pub fn flush(&mut self) -> Option<(u64, MarketUpdate<S>)> {
+        let candle = self.current.take()?;
+        let timestamp_ns = match self.interval {
+            CandleInterval::Time(duration_ns) => (candle.bucket + 1) * duration_ns,
+            CandleInterval::Ticks(_) | CandleInterval::Volume(_) => candle.last_ts_ns,
+        };
+        let bid = candle.close;
+        let ask = candle.close + self.spread;
+        let low = if candle.low < bid { candle.low } else { bid };
+        let high = if candle.high > ask { candle.high } else { ask };
+        Some((
+            timestamp_ns,
+            MarketUpdate::Candle {
+                bid,
+                ask,
+                low,
+                high,
+            },
+        ))
+    }
+
+    /// Turn a stream of market updates into a stream of candles, including the incomplete last one.
+    pub fn aggregate<I>(self, market_updates: I) -> Candles<I::IntoIter, S>
+    where
+        I: IntoIterator<Item = (u64, MarketUpdate<S>)>,
+    {
+        Candles {
+            market_updates: market_updates.into_iter(),
+            aggregator: self,
+            done: false,
+        }
+    }
+}
+
+/// An iterator of candles aggregated from market updates, created by `CandleAggregator::aggregate`.
+#[derive(Debug, Clone)]
+pub struct Candles<I, S>
+where
+    S: Currency,
+{
+    market_updates: I,
+    aggregator: CandleAggregator<S>,
+    done: bool,
+}
+
+impl<I, S> Iterator for Candles<I, S>
+where
+    I: Iterator<Item = (u64, MarketUpdate<S>)>,
+    S: Currency,
+{
+    type Item = (u64, MarketUpdate<S>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        for (timestamp_ns, market_update) in self.market_updates.by_ref() {
+            if let Some(candle) = self.aggregator.update(timestamp_ns, &market_update) {
+                return Some(candle);
+            }
+        }
+        self.done = true;
+        self.aggregator.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{candle, prelude::*, trade};
+
+    fn trades() -> Vec<(u64, MarketUpdate<BaseCurrency>)> {
+        vec![
+            (1_000, trade!(quote!(100), base!(1), Side::Buy)),
+            (1_500, trade!(quote!(102), base!(2), Side::Buy)),
+            (1_900, trade!(quote!(99), base!(1), Side::Sell)),
+            (2_100, bba!(quote!(99), quote!(100))),
+            (2_200, trade!(quote!(101), base!(3), Side::Sell)),
+            (4_500, trade!(quote!(101), base!(1), Side::Buy)),
+        ]
+    }
+
+    #[test]
+    fn candle_aggregator_validation() {
+        assert_eq!(
+            CandleAggregator::<BaseCurrency>::new(CandleInterval::Time(0), quote!(1)).unwrap_err(),
+            Error::NonPositive
+        );
+        assert!(
+            CandleAggregator::<BaseCurrency>::new(CandleInterval::Ticks(0), quote!(1)).is_err()
+        );
+        assert!(
+            CandleAggregator::<BaseCurrency>::new(CandleInterval::Volume(base!(0)), quote!(1))
+                .is_err()
+        );
+        assert!(
+            CandleAggregator::<BaseCurrency>::new(CandleInterval::Ticks(1), quote!(0)).is_err()
+        );
+    }
+
+    #[test]
+    fn candle_aggregator_time() {
+        let aggregator = CandleAggregator::new(CandleInterval::Time(1_000), quote!(1)).unwrap();
+        assert_eq!(
+            Vec::from_iter(aggregator.aggregate(trades())),
+            vec![
+                (
+                    2_000,
+                    candle!(quote!(99), quote!(100), quote!(99), quote!(102))
+                ),
+                (
+                    3_000,
+                    candle!(quote!(101), quote!(102), quote!(101), quote!(102))
+                ),
+                // The interval from 3_000 to 4_000 has no trades.
+                (
+                    5_000,
+                    candle!(quote!(101), quote!(102), quote!(101), quote!(102))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn candle_aggregator_ticks() {
+        let mut aggregator = CandleAggregator::new(CandleInterval::Ticks(2), quote!(0.5)).unwrap();
+        let mut candles = Vec::new();
+        for (ts, update) in trades() {
+            candles.extend(aggregator.update(ts, &update));
+        }
+        assert_eq!(
+            candles,
+            vec![
+                (
+                    1_500,
+                    candle!(quote!(102), quote!(102.5), quote!(100), quote!(102.5))
+                ),
+                (
+                    2_200,
+                    candle!(quote!(101), quote!(101.5), quote!(99), quote!(101.5))
+                ),
+            ]
+        );
+        assert_eq!(
+            aggregator.flush(),
+            Some((
+                4_500,
+                candle!(quote!(101), quote!(101.5), quote!(101), quote!(101.5))
+            ))
+        );
+        assert_eq!(aggregator.flush(), None);
+    }
+
+    #[test]
+    fn candle_aggregator_volume() {
+        let aggregator =
+            CandleAggregator::new(CandleInterval::Volume(base!(3)), quote!(1)).unwrap();
+        assert_eq!(
+            Vec::from_iter(aggregator.aggregate(trades())),
+            vec![
+                (
+                    1_500,
+                    candle!(quote!(102), quote!(103), quote!(100), quote!(103))
+                ),
+                (
+                    2_200,
+                    candle!(quote!(101), quote!(102), quote!(99), quote!(102))
+                ),
+                (
+                    4_500,
+                    candle!(quote!(101), quote!(102), quote!(101), quote!(102))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn candle_aggregator_drives_exchange() {
+        let aggregator = CandleAggregator::new(CandleInterval::Ticks(2), quote!(1)).unwrap();
+        let mut exchange = crate::mock_exchange_base();
+        for (ts, candle) in aggregator.aggregate(trades()) {
+            exchange.update_state(ts, candle).unwrap();
+        }
+        assert_eq!(exchange.market_state().bid(), quote!(101));
+        assert_eq!(exchange.market_state().ask(), quote!(102));
+    }
+}
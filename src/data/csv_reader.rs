@@ -0,0 +1,292 @@
+//! Reads market data from CSV files with a configurable mapping of the header columns.
+
+use std::{fs::File, io::Read, marker::PhantomData, path::Path};
+
+use csv::StringRecord;
+use fpdec::Decimal;
+
+use super::{Columns, MarketDataFormat, TimestampUnit};
+use crate::types::{Currency, Error, MarketUpdate, Mon, Result};
+
+/// Configures how a CSV file is read into `MarketUpdate`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvConfig<T = Decimal>
+where
+    T: Mon,
+{
+    format: MarketDataFormat<T>,
+    timestamp_column: String,
+    timestamp_unit: TimestampUnit,
+    delimiter: u8,
+}
+
+impl<T> CsvConfig<T>
+where
+    T: Mon,
+{
+    /// Create a new config for the `format`,
+    /// with nanosecond timestamps in the `timestamp` column and comma separated values.
+    pub fn new(format: MarketDataFormat<T>) -> Self {
+        Self {
+            format,
+            timestamp_column: "timestamp".to_string(),
+            timestamp_unit: TimestampUnit::default(),
+            delimiter: b',',
+        }
+    }
+
+    /// Read the timestamps from the column with this name.
+    pub fn with_timestamp_column<C: Into<String>>(mut self, timestamp_column: C) -> Self {
+        self.timestamp_column = timestamp_column.into();
+        self
+    }
+
+    /// Interpret the timestamps in this unit.
+    pub fn with_timestamp_unit(mut self, timestamp_unit: TimestampUnit) -> Self {
+        self.timestamp_unit = timestamp_unit;
+        self
+    }
+
+    /// Separate the values by this delimiter, e.g. `b';'` or `b'\t'`.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// The kind of market data and the names of its columns.
+    #[inline(always)]
+    pub fn format(&self) -> &MarketDataFormat<T> {
+        &self.format
+    }
+
+    /// The name of the timestamp column.
+    #[inline(always)]
+    pub fn timestamp_column(&self) -> &str {
+        &self.timestamp_column
+    }
+
+    /// The unit of the timestamps.
+    #[inline(always)]
+    pub fn timestamp_unit(&self) -> TimestampUnit {
+        self.timestamp_unit
+    }
+
+    /// The delimiter of the values.
+    #[inline(always)]
+    pub fn delimiter(&self) -> u8 {
+        self.delimiter
+    }
+}
+
+/// Streams `(timestamp_ns, MarketUpdate)` items from CSV data, one per row,
+/// without loading the whole file into memory.
+#[derive(Debug)]
+pub struct CsvMarketData<R, S>
+where
+    S: Currency,
+{
+    reader: csv::Reader<R>,
+    record: StringRecord,
+    timestamp: usize,
+    timestamp_unit: TimestampUnit,
+    columns: Columns<S::Num>,
+    _currency: PhantomData<S>,
+}
+
+impl<S> CsvMarketData<File, S>
+where
+    S: Currency,
+{
+    /// Open the CSV file at `path`.
+    ///
+    /// # Returns:
+    /// An Error if the file can not be opened or lacks a configured column.
+    pub fn from_path<P: AsRef<Path>>(path: P, config: CsvConfig<S::Num>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|e| Error::InvalidMarketData(format!("{}: {e}", path.display())))?;
+        Self::from_reader(file, config)
+    }
+}
+
+impl<R, S> CsvMarketData<R, S>
+where
+    R: Read,
+    S: Currency,
+{
+    /// Read CSV data with a header row from any `reader`.
+    ///
+    /// # Returns:
+    /// An Error if the header can not be read or lacks a configured column.
+    pub fn from_reader(reader: R, config: CsvConfig<S::Num>) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(config.delimiter)
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let headers = reader
+            .headers()
+            .map_err(|e| Error::InvalidMarketData(e.to_string()))?
+            .clone();
+        let index = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or_else(|| Error::InvalidMarketData(format!("missing column {name}")))
+        };
+        let columns = Columns::resolve(&config.format, index)?;
+
+        Ok(Self {
+            timestamp: index(&config.timestamp_column)?,
+            timestamp_unit: config.timestamp_unit,
+            columns,
+            reader,
+            record: StringRecord::new(),
+            _currency: PhantomData,
+        })
+    }
+
+    /// Parse the current `record` into a market update.
+    fn parse_record(&self) -> Result<(u64, MarketUpdate<S>)> {
+        let field = |index: usize| {
+            self.record
+                .get(index)
+                .ok_or_else(|| Error::InvalidMarketData(format!("missing value in column {index}")))
+        };
+        let timestamp = field(self.timestamp)?.parse::<u64>().map_err(|_| {
+            Error::InvalidMarketData(format!(
+                "timestamp is not an unsigned integer: {:?}",
+                &self.record[self.timestamp]
+            ))
+        })?;
+        let timestamp_ns = self.timestamp_unit.to_nanos(timestamp)?;
+        Ok((timestamp_ns, self.columns.market_update(field)?))
+    }
+}
+
+impl<R, S> Iterator for CsvMarketData<R, S>
+where
+    R: Read,
+    S: Currency,
+{
+    type Item = Result<(u64, MarketUpdate<S>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.reader.read_record(&mut self.record) {
+            Ok(true) => Some(self.parse_record().map_err(|e| match e {
+                Error::InvalidMarketData(reason) => {
+                    let line = self.record.position().map_or(0, |pos| pos.line());
+                    Error::InvalidMarketData(format!("line {line}: {reason}"))
+                }
+                e => e,
+            })),
+            Ok(false) => None,
+            Err(e) => Some(Err(Error::InvalidMarketData(e.to_string()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{candle, prelude::*, trade};
+
+    fn read<S: Currency>(
+        data: &str,
+        config: CsvConfig<S::Num>,
+    ) -> Result<Vec<(u64, MarketUpdate<S>)>> {
+        CsvMarketData::from_reader(data.as_bytes(), config)?.collect()
+    }
+
+    #[test]
+    fn csv_trades() {
+        let data = "timestamp,price,quantity,side\n1,100.5,0.5,buy\n2,100,1.5,Sell\n";
+        assert_eq!(
+            read::<BaseCurrency>(data, CsvConfig::new(MarketDataFormat::trades())).unwrap(),
+            vec![
+                (1, trade!(quote!(100.5), base!(0.5), Side::Buy)),
+                (2, trade!(quote!(100), base!(1.5), Side::Sell)),
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_trades_signed_quantity() {
+        // The layout of the bundled BitMEX trades.
+        let data = "timestamp,price,size\n1514764802610,13873,10\n1514764806057,13872.5,-3000\n";
+        let config = CsvConfig::new(MarketDataFormat::Trades {
+            price: "price".to_string(),
+            quantity: "size".to_string(),
+            side: None,
+        })
+        .with_timestamp_unit(TimestampUnit::Milliseconds);
+        assert_eq!(
+            read::<QuoteCurrency>(data, config).unwrap(),
+            vec![
+                (
+                    1_514_764_802_610_000_000,
+                    trade!(quote!(13873), quote!(10), Side::Buy)
+                ),
+                (
+                    1_514_764_806_057_000_000,
+                    trade!(quote!(13872.5), quote!(3000), Side::Sell)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_candles() {
+        let data = "time;open;high;low;close;volume\n60;100;102;99;101;1000\n";
+        let config = CsvConfig::new(MarketDataFormat::candles(quote!(0.5)))
+            .with_timestamp_column("time")
+            .with_timestamp_unit(TimestampUnit::Seconds)
+            .with_delimiter(b';');
+        assert_eq!(
+            read::<BaseCurrency>(data, config).unwrap(),
+            vec![(
+                60_000_000_000,
+                candle!(quote!(101), quote!(101.5), quote!(99), quote!(102))
+            )]
+        );
+    }
+
+    #[test]
+    fn csv_quotes() {
+        let data = "timestamp, bid, ask\n5, 99.5, 100\n";
+        let updates =
+            read::<BaseCurrency<f64>>(data, CsvConfig::new(MarketDataFormat::quotes())).unwrap();
+        assert_eq!(
+            updates,
+            vec![(5, bba!(QuoteCurrency::new(99.5), QuoteCurrency::new(100.0)))]
+        );
+    }
+
+    #[test]
+    fn csv_errors() {
+        let config = CsvConfig::new(MarketDataFormat::quotes());
+        assert_eq!(
+            read::<BaseCurrency>("timestamp,bid\n1,100\n", config.clone()),
+            Err(Error::InvalidMarketData("missing column ask".to_string()))
+        );
+        assert!(matches!(
+            read::<BaseCurrency>("timestamp,bid,ask\n1,100,abc\n", config.clone()),
+            Err(Error::InvalidMarketData(_))
+        ));
+        assert!(matches!(
+            read::<BaseCurrency>("timestamp,bid,ask\n-1,100,101\n", config),
+            Err(Error::InvalidMarketData(_))
+        ));
+        assert!(matches!(
+            read::<BaseCurrency>(
+                "timestamp,price,quantity,side\n1,100,1,long\n",
+                CsvConfig::new(MarketDataFormat::trades())
+            ),
+            Err(Error::InvalidMarketData(_))
+        ));
+        assert!(CsvMarketData::<File, BaseCurrency>::from_path(
+            "does_not_exist.csv",
+            CsvConfig::new(MarketDataFormat::quotes())
+        )
+        .is_err());
+    }
+}
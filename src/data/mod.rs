@@ -0,0 +1,277 @@
+//! Readers of historical market data, which yield `(timestamp_ns, MarketUpdate)` items
+//! to be fed into `Exchange::update_state`.
+
+#[cfg(feature = "std")]
+mod binance;
+mod candle_aggregator;
+#[cfg(feature = "std")]
+mod csv_reader;
+#[cfg(feature = "parquet")]
+mod parquet_reader;
+mod synthetic;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+#[cfg(feature = "serde")]
+pub use binance::BinanceJsonMarketData;
+#[cfg(feature = "std")]
+pub use binance::{binance_timestamp_ns, BinanceCsvMarketData, BinanceFormat};
+pub use candle_aggregator::{CandleAggregator, CandleInterval, Candles};
+#[cfg(feature = "std")]
+pub use csv_reader::{CsvConfig, CsvMarketData};
+use fpdec::Decimal;
+#[cfg(feature = "parquet")]
+pub use parquet_reader::{ParquetConfig, ParquetMarketData};
+pub use synthetic::{PriceProcess, SyntheticMarketData, SyntheticUpdate};
+
+#[cfg(feature = "std")]
+use crate::types::{Currency, MarketUpdate, Side};
+use crate::types::{Error, Mon, QuoteCurrency, Result};
+
+/// The unit of the timestamps in the market data, which get converted to nanoseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampUnit {
+    /// Seconds since the unix epoch.
+    Seconds,
+    /// Milliseconds since the unix epoch.
+    Milliseconds,
+    /// Microseconds since the unix epoch.
+    Microseconds,
+    /// Nanoseconds since the unix epoch.
+    #[default]
+    Nanoseconds,
+}
+
+impl TimestampUnit {
+    /// Convert a timestamp in this unit to nanoseconds.
+    ///
+    /// # Returns:
+    /// An Error if the timestamp overflows in nanoseconds.
+    pub fn to_nanos(&self, timestamp: u64) -> Result<u64> {
+        let factor = match self {
+            TimestampUnit::Seconds => 1_000_000_000,
+            TimestampUnit::Milliseconds => 1_000_000,
+            TimestampUnit::Microseconds => 1_000,
+            TimestampUnit::Nanoseconds => 1,
+        };
+        timestamp.checked_mul(factor).ok_or_else(|| {
+            Error::InvalidMarketData(format!("timestamp {timestamp} overflows in nanoseconds"))
+        })
+    }
+}
+
+/// The kind of market data and the names of its columns.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketDataFormat<T = Decimal>
+where
+    T: Mon,
+{
+    /// One `MarketUpdate::Trade` per row.
+    /// Without a `side` column, a negative quantity denotes a sell and its absolute value is used.
+    Trades {
+        /// The column of the trade price.
+        price: String,
+        /// The column of the traded quantity.
+        quantity: String,
+        /// The optional column of the taker side, see `Side::from_str`.
+        side: Option<String>,
+    },
+    /// One `MarketUpdate::Candle` per row, e.g. of OHLCV data.
+    /// As candles carry no quotes, the bid is the close price and the ask is the close price plus the `spread`.
+    Candles {
+        /// The column of the high price.
+        high: String,
+        /// The column of the low price.
+        low: String,
+        /// The column of the close price.
+        close: String,
+        /// The assumed spread at the close, e.g. the tick size.
+        spread: QuoteCurrency<T>,
+    },
+    /// One `MarketUpdate::Bba` per row.
+    Quotes {
+        /// The column of the best bid.
+        bid: String,
+        /// The column of the best ask.
+        ask: String,
+    },
+}
+
+impl<T> MarketDataFormat<T>
+where
+    T: Mon,
+{
+    /// Trades with the columns `price`, `quantity` and `side`.
+    pub fn trades() -> Self {
+        Self::Trades {
+            price: "price".to_string(),
+            quantity: "quantity".to_string(),
+            side: Some("side".to_string()),
+        }
+    }
+
+    /// Candles with the columns `high`, `low` and `close`, where any other columns like `open` and `volume` are ignored.
+    pub fn candles(spread: QuoteCurrency<T>) -> Self {
+        Self::Candles {
+            high: "high".to_string(),
+            low: "low".to_string(),
+            close: "close".to_string(),
+            spread,
+        }
+    }
+
+    /// Quotes with the columns `bid` and `ask`.
+    pub fn quotes() -> Self {
+        Self::Quotes {
+            bid: "bid".to_string(),
+            ask: "ask".to_string(),
+        }
+    }
+}
+
+/// The `MarketDataFormat` with its column names resolved to indices.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub(crate) enum Columns<T>
+where
+    T: Mon,
+{
+    Trades {
+        price: usize,
+        quantity: usize,
+        side: Option<usize>,
+    },
+    Candles {
+        high: usize,
+        low: usize,
+        close: usize,
+        spread: QuoteCurrency<T>,
+    },
+    Quotes {
+        bid: usize,
+        ask: usize,
+    },
+}
+
+#[cfg(feature = "std")]
+impl<T> Columns<T>
+where
+    T: Mon,
+{
+    /// Resolve the column names of the `format` with the `index` lookup.
+    pub(crate) fn resolve<F>(format: &MarketDataFormat<T>, index: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Result<usize>,
+    {
+        Ok(match format {
+            MarketDataFormat::Trades {
+                price,
+                quantity,
+                side,
+            } => Columns::Trades {
+                price: index(price)?,
+                quantity: index(quantity)?,
+                side: side.as_deref().map(&index).transpose()?,
+            },
+            MarketDataFormat::Candles {
+                high,
+                low,
+                close,
+                spread,
+            } => Columns::Candles {
+                high: index(high)?,
+                low: index(low)?,
+                close: index(close)?,
+                spread: *spread,
+            },
+            MarketDataFormat::Quotes { bid, ask } => Columns::Quotes {
+                bid: index(bid)?,
+                ask: index(ask)?,
+            },
+        })
+    }
+
+    /// The indices of all columns.
+    #[cfg(feature = "parquet")]
+    pub(crate) fn indices(&self) -> Vec<usize> {
+        match *self {
+            Columns::Trades {
+                price,
+                quantity,
+                side,
+            } => Vec::from_iter([price, quantity].into_iter().chain(side)),
+            Columns::Candles {
+                high, low, close, ..
+            } => vec![high, low, close],
+            Columns::Quotes { bid, ask } => vec![bid, ask],
+        }
+    }
+
+    /// Parse a market update from the textual values of a row, which `field` returns by column index.
+    pub(crate) fn market_update<'a, S, F>(&self, field: F) -> Result<MarketUpdate<S>>
+    where
+        S: Currency<Num = T>,
+        F: Fn(usize) -> Result<&'a str>,
+    {
+        let number = |index: usize| {
+            let value = field(index)?;
+            value.parse::<T>().map_err(|_| {
+                Error::InvalidMarketData(format!("column {index} is not a number: {value:?}"))
+            })
+        };
+        Ok(match *self {
+            Columns::Trades {
+                price,
+                quantity,
+                side,
+            } => {
+                let quantity = number(quantity)?;
+                let side = match side {
+                    Some(side) => field(side)?.parse::<Side>()?,
+                    None if quantity < T::ZERO => Side::Sell,
+                    None => Side::Buy,
+                };
+                MarketUpdate::Trade {
+                    price: QuoteCurrency::new(number(price)?),
+                    quantity: S::new(quantity.abs()),
+                    side,
+                }
+            }
+            Columns::Candles {
+                high,
+                low,
+                close,
+                spread,
+            } => {
+                let close = QuoteCurrency::new(number(close)?);
+                MarketUpdate::Candle {
+                    bid: close,
+                    ask: close + spread,
+                    low: QuoteCurrency::new(number(low)?),
+                    high: QuoteCurrency::new(number(high)?),
+                }
+            }
+            Columns::Quotes { bid, ask } => MarketUpdate::Bba {
+                bid: QuoteCurrency::new(number(bid)?),
+                ask: QuoteCurrency::new(number(ask)?),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_unit_to_nanos() {
+        assert_eq!(TimestampUnit::Seconds.to_nanos(2).unwrap(), 2_000_000_000);
+        assert_eq!(TimestampUnit::Milliseconds.to_nanos(2).unwrap(), 2_000_000);
+        assert_eq!(TimestampUnit::Microseconds.to_nanos(2).unwrap(), 2_000);
+        assert_eq!(TimestampUnit::Nanoseconds.to_nanos(2).unwrap(), 2);
+        assert!(TimestampUnit::Seconds.to_nanos(u64::MAX).is_err());
+    }
+}
@@ -0,0 +1,407 @@
+//! Streams market data from Parquet files in record batches, behind the `parquet` feature.
+
+use std::{fs::File, marker::PhantomData, path::Path};
+
+use arrow_array::{cast::AsArray, types::Int64Type, Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, TimeUnit};
+use fpdec::Decimal;
+use parquet::arrow::{
+    arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder},
+    ProjectionMask,
+};
+
+use super::{Columns, MarketDataFormat, TimestampUnit};
+use crate::types::{Currency, Error, MarketUpdate, Mon, Result};
+
+/// Configures how a Parquet file is read into `MarketUpdate`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParquetConfig<T = Decimal>
+where
+    T: Mon,
+{
+    format: MarketDataFormat<T>,
+    timestamp_column: String,
+    timestamp_unit: TimestampUnit,
+    batch_size: usize,
+}
+
+impl<T> ParquetConfig<T>
+where
+    T: Mon,
+{
+    /// Create a new config for the `format`,
+    /// with nanosecond timestamps in the `timestamp` column and batches of 8192 rows.
+    pub fn new(format: MarketDataFormat<T>) -> Self {
+        Self {
+            format,
+            timestamp_column: "timestamp".to_string(),
+            timestamp_unit: TimestampUnit::default(),
+            batch_size: 8192,
+        }
+    }
+
+    /// Read the timestamps from the column with this name.
+    pub fn with_timestamp_column<C: Into<String>>(mut self, timestamp_column: C) -> Self {
+        self.timestamp_column = timestamp_column.into();
+        self
+    }
+
+    /// Interpret integer timestamps in this unit.
+    /// Columns of the Arrow `Timestamp` type carry their own unit, which takes precedence.
+    pub fn with_timestamp_unit(mut self, timestamp_unit: TimestampUnit) -> Self {
+        self.timestamp_unit = timestamp_unit;
+        self
+    }
+
+    /// Decode this many rows at once, trading memory for throughput.
+    ///
+    /// # Returns:
+    /// An Error if the `batch_size` is zero.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Result<Self> {
+        if batch_size == 0 {
+            return Err(Error::InvalidMarketData(
+                "the batch size must be positive".to_string(),
+            ));
+        }
+        self.batch_size = batch_size;
+        Ok(self)
+    }
+
+    /// The kind of market data and the names of its columns.
+    #[inline(always)]
+    pub fn format(&self) -> &MarketDataFormat<T> {
+        &self.format
+    }
+
+    /// The name of the timestamp column.
+    #[inline(always)]
+    pub fn timestamp_column(&self) -> &str {
+        &self.timestamp_column
+    }
+
+    /// The unit of integer timestamps.
+    #[inline(always)]
+    pub fn timestamp_unit(&self) -> TimestampUnit {
+        self.timestamp_unit
+    }
+
+    /// The number of rows decoded at once.
+    #[inline(always)]
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+/// A record batch with the timestamps as integers and all other columns as text,
+/// which is parsed into the numeric type of the currency without losing precision.
+struct DecodedBatch {
+    timestamps: Int64Array,
+    /// Indexed like the projected columns, with `None` in place of the timestamp column.
+    values: Vec<Option<StringArray>>,
+}
+
+/// Streams `(timestamp_ns, MarketUpdate)` items from a Parquet file, one per row,
+/// decoding only the configured columns one record batch at a time.
+/// Integer, floating point, decimal and string columns are supported for prices and quantities.
+pub struct ParquetMarketData<S>
+where
+    S: Currency,
+{
+    reader: ParquetRecordBatchReader,
+    timestamp: usize,
+    timestamp_unit: TimestampUnit,
+    columns: Columns<S::Num>,
+    batch: Option<DecodedBatch>,
+    row: usize,
+    _currency: PhantomData<S>,
+}
+
+impl<S> std::fmt::Debug for ParquetMarketData<S>
+where
+    S: Currency,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParquetMarketData")
+            .field("timestamp_unit", &self.timestamp_unit)
+            .field("row", &self.row)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> ParquetMarketData<S>
+where
+    S: Currency,
+{
+    /// Open the Parquet file at `path`.
+    ///
+    /// # Returns:
+    /// An Error if the file can not be read or lacks a configured column.
+    pub fn from_path<P: AsRef<Path>>(path: P, config: ParquetConfig<S::Num>) -> Result<Self> {
+        let path = path.as_ref();
+        let invalid = |e: &dyn std::fmt::Display| {
+            Error::InvalidMarketData(format!("{}: {e}", path.display()))
+        };
+        let file = File::open(path).map_err(|e| invalid(&e))?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file).map_err(|e| invalid(&e))?;
+
+        let schema = builder.schema().clone();
+        let root_index = |name: &str| {
+            schema
+                .index_of(name)
+                .map_err(|_| Error::InvalidMarketData(format!("missing column {name}")))
+        };
+        // Only the configured columns get decoded, in the order of the file schema.
+        let mut roots = Columns::resolve(&config.format, root_index)?.indices();
+        roots.push(root_index(&config.timestamp_column)?);
+        roots.sort_unstable();
+        roots.dedup();
+        let index = |name: &str| {
+            let root = root_index(name)?;
+            Ok(roots.binary_search(&root).expect("Root is projected; qed"))
+        };
+        let timestamp = index(&config.timestamp_column)?;
+        let columns = Columns::resolve(&config.format, index)?;
+
+        let timestamp_unit = match schema.field(roots[timestamp]).data_type() {
+            DataType::Timestamp(unit, _) => match unit {
+                TimeUnit::Second => TimestampUnit::Seconds,
+                TimeUnit::Millisecond => TimestampUnit::Milliseconds,
+                TimeUnit::Microsecond => TimestampUnit::Microseconds,
+                TimeUnit::Nanosecond => TimestampUnit::Nanoseconds,
+            },
+            _ => config.timestamp_unit,
+        };
+        let projection = ProjectionMask::roots(builder.parquet_schema(), roots.iter().copied());
+        let reader = builder
+            .with_projection(projection)
+            .with_batch_size(config.batch_size)
+            .build()
+            .map_err(|e| invalid(&e))?;
+
+        Ok(Self {
+            reader,
+            timestamp,
+            timestamp_unit,
+            columns,
+            batch: None,
+            row: 0,
+            _currency: PhantomData,
+        })
+    }
+
+    /// Cast the columns of a record batch into integer timestamps and textual values.
+    fn decode(&self, batch: RecordBatch) -> Result<DecodedBatch> {
+        let invalid = |e: arrow_schema::ArrowError| Error::InvalidMarketData(e.to_string());
+        let mut timestamps = None;
+        let mut values = Vec::with_capacity(batch.num_columns());
+        for (i, column) in batch.columns().iter().enumerate() {
+            if i == self.timestamp {
+                let column = arrow_cast::cast(column, &DataType::Int64).map_err(invalid)?;
+                timestamps = Some(column.as_primitive::<Int64Type>().clone());
+                values.push(None);
+            } else {
+                let column = arrow_cast::cast(column, &DataType::Utf8).map_err(invalid)?;
+                values.push(Some(column.as_string::<i32>().clone()));
+            }
+        }
+        Ok(DecodedBatch {
+            timestamps: timestamps.expect("The timestamp column is projected; qed"),
+            values,
+        })
+    }
+
+    /// Parse a row of the current batch into a market update.
+    fn parse_row(&self, batch: &DecodedBatch, row: usize) -> Result<(u64, MarketUpdate<S>)> {
+        if batch.timestamps.is_null(row) {
+            return Err(Error::InvalidMarketData(format!(
+                "row {row}: missing timestamp"
+            )));
+        }
+        let timestamp = u64::try_from(batch.timestamps.value(row))
+            .map_err(|_| Error::InvalidMarketData(format!("row {row}: negative timestamp")))?;
+        let timestamp_ns = self.timestamp_unit.to_nanos(timestamp)?;
+        let market_update =
+            self.columns
+                .market_update(|index| match batch.values[index].as_ref() {
+                    Some(values) if !values.is_null(row) => Ok(values.value(row)),
+                    _ => Err(Error::InvalidMarketData(format!(
+                        "row {row}: missing value in column {index}"
+                    ))),
+                })?;
+        Ok((timestamp_ns, market_update))
+    }
+}
+
+impl<S> Iterator for ParquetMarketData<S>
+where
+    S: Currency,
+{
+    type Item = Result<(u64, MarketUpdate<S>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(batch) = self.batch.as_ref() {
+                if self.row < batch.timestamps.len() {
+                    let item = self.parse_row(batch, self.row);
+                    self.row += 1;
+                    return Some(item);
+                }
+            }
+            let batch = match self.reader.next()? {
+                Ok(batch) => batch,
+                Err(e) => return Some(Err(Error::InvalidMarketData(e.to_string()))),
+            };
+            match self.decode(batch) {
+                Ok(batch) => {
+                    self.batch = Some(batch);
+                    self.row = 0;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Float64Array, StringArray, TimestampMillisecondArray, UInt64Array};
+    use arrow_schema::{Field, Schema};
+    use parquet::arrow::ArrowWriter;
+
+    use super::*;
+    use crate::{candle, prelude::*, trade};
+
+    /// Write the `batch` to a temporary Parquet file and return its path.
+    fn write_parquet(name: &str, batch: RecordBatch) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("lfest_{name}_{}.parquet", std::process::id()));
+        let file = File::create(&path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        path
+    }
+
+    #[test]
+    fn parquet_trades() {
+        let schema = Schema::new(vec![
+            Field::new(
+                "timestamp",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new("id", DataType::UInt64, false),
+            Field::new("side", DataType::Utf8, false),
+            Field::new("price", DataType::Float64, false),
+            Field::new("quantity", DataType::Float64, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(TimestampMillisecondArray::from(vec![1, 2, 3])),
+                Arc::new(UInt64Array::from(vec![10, 11, 12])),
+                Arc::new(StringArray::from(vec!["buy", "sell", "buy"])),
+                Arc::new(Float64Array::from(vec![100.1, 100.0, 100.2])),
+                Arc::new(Float64Array::from(vec![0.5, 1.0, 0.1])),
+            ],
+        )
+        .unwrap();
+        let path = write_parquet("trades", batch);
+
+        let config = ParquetConfig::new(MarketDataFormat::trades())
+            .with_batch_size(2)
+            .unwrap();
+        let updates = Result::<Vec<_>>::from_iter(
+            ParquetMarketData::<BaseCurrency>::from_path(&path, config).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            updates,
+            vec![
+                (1_000_000, trade!(quote!(100.1), base!(0.5), Side::Buy)),
+                (2_000_000, trade!(quote!(100), base!(1), Side::Sell)),
+                (3_000_000, trade!(quote!(100.2), base!(0.1), Side::Buy)),
+            ]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parquet_candles() {
+        let schema = Schema::new(vec![
+            Field::new("time", DataType::Int64, false),
+            Field::new("open", DataType::Float64, false),
+            Field::new("high", DataType::Float64, false),
+            Field::new("low", DataType::Float64, false),
+            Field::new("close", DataType::Float64, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![60, 120])),
+                Arc::new(Float64Array::from(vec![100.0, 101.0])),
+                Arc::new(Float64Array::from(vec![102.0, 103.5])),
+                Arc::new(Float64Array::from(vec![99.0, 100.5])),
+                Arc::new(Float64Array::from(vec![Some(101.0), None])),
+            ],
+        )
+        .unwrap();
+        let path = write_parquet("candles", batch);
+
+        let config = ParquetConfig::new(MarketDataFormat::candles(QuoteCurrency::new(0.5)))
+            .with_timestamp_column("time")
+            .with_timestamp_unit(TimestampUnit::Seconds);
+        let mut data = ParquetMarketData::<BaseCurrency<f64>>::from_path(&path, config).unwrap();
+        assert_eq!(
+            data.next().unwrap().unwrap(),
+            (
+                60_000_000_000,
+                candle!(
+                    QuoteCurrency::new(101.0),
+                    QuoteCurrency::new(101.5),
+                    QuoteCurrency::new(99.0),
+                    QuoteCurrency::new(102.0)
+                )
+            )
+        );
+        // The close of the second candle is missing.
+        assert!(matches!(
+            data.next(),
+            Some(Err(Error::InvalidMarketData(_)))
+        ));
+        assert!(data.next().is_none());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parquet_errors() {
+        let config = ParquetConfig::<Decimal>::new(MarketDataFormat::quotes());
+        assert!(config.clone().with_batch_size(0).is_err());
+        assert!(ParquetMarketData::<BaseCurrency>::from_path(
+            "does_not_exist.parquet",
+            config.clone()
+        )
+        .is_err());
+
+        let schema = Schema::new(vec![
+            Field::new("timestamp", DataType::Int64, false),
+            Field::new("bid", DataType::Float64, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(Float64Array::from(vec![100.0])),
+            ],
+        )
+        .unwrap();
+        let path = write_parquet("quotes", batch);
+        assert_eq!(
+            ParquetMarketData::<BaseCurrency>::from_path(&path, config).unwrap_err(),
+            Error::InvalidMarketData("missing column ask".to_string())
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,477 @@
+//! Synthetic market data from seeded stochastic price processes,
+//! to test strategies and the exchange without shipping datasets.
+
+use alloc::format;
+
+use num_traits::Float;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::types::{Currency, Error, MarketUpdate, Mon, QuoteCurrency, Result, Side};
+
+/// The number of nanoseconds in a year of 365 days, the unit of time of the `PriceProcess` parameters.
+const YEAR_NS: f64 = 365.0 * 86_400_000_000_000.0;
+
+/// The stochastic process of the mid price, with annualized parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriceProcess {
+    /// Geometric Brownian motion, with log-normally distributed returns.
+    Gbm {
+        /// The expected return per year.
+        drift: f64,
+        /// The standard deviation of the log returns per year.
+        volatility: f64,
+    },
+    /// An Ornstein-Uhlenbeck process, which reverts the price to its `mean`.
+    OrnsteinUhlenbeck {
+        /// The price to which the process reverts.
+        mean: f64,
+        /// The speed of the mean reversion per year.
+        reversion: f64,
+        /// The standard deviation of the price changes per year, denoted in the quote currency.
+        volatility: f64,
+    },
+    /// Merton's jump diffusion, a geometric Brownian motion with Poisson distributed jumps of log-normal size.
+    JumpDiffusion {
+        /// The expected return per year of the diffusion.
+        drift: f64,
+        /// The standard deviation of the log returns per year of the diffusion.
+        volatility: f64,
+        /// The expected number of jumps per year.
+        jump_intensity: f64,
+        /// The mean of the log jump sizes.
+        jump_mean: f64,
+        /// The standard deviation of the log jump sizes.
+        jump_volatility: f64,
+    },
+}
+
+impl PriceProcess {
+    fn validate(&self) -> Result<()> {
+        let valid = match *self {
+            PriceProcess::Gbm { drift, volatility } => drift.is_finite() && volatility >= 0.0,
+            PriceProcess::OrnsteinUhlenbeck {
+                mean,
+                reversion,
+                volatility,
+            } => mean > 0.0 && reversion >= 0.0 && volatility >= 0.0,
+            PriceProcess::JumpDiffusion {
+                drift,
+                volatility,
+                jump_intensity,
+                jump_mean,
+                jump_volatility,
+            } => {
+                drift.is_finite()
+                    && volatility >= 0.0
+                    && jump_intensity >= 0.0
+                    && jump_mean.is_finite()
+                    && jump_volatility >= 0.0
+            }
+        };
+        // Comparisons with NaN are false, so NaN parameters are invalid.
+        if !valid {
+            return Err(Error::InvalidMarketData(format!(
+                "invalid price process {self:?}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Advance the `price` by `dt` years.
+    fn step<R: Rng>(&self, rng: &mut R, price: f64, dt: f64) -> f64 {
+        match *self {
+            PriceProcess::Gbm { drift, volatility } => {
+                price * Float::exp(gbm_return(rng, drift, volatility, dt))
+            }
+            PriceProcess::OrnsteinUhlenbeck {
+                mean,
+                reversion,
+                volatility,
+            } => {
+                price
+                    + reversion * (mean - price) * dt
+                    + volatility * Float::sqrt(dt) * standard_normal(rng)
+            }
+            PriceProcess::JumpDiffusion {
+                drift,
+                volatility,
+                jump_intensity,
+                jump_mean,
+                jump_volatility,
+            } => {
+                let mut log_return = gbm_return(rng, drift, volatility, dt);
+                for _ in 0..poisson(rng, jump_intensity * dt) {
+                    log_return += jump_mean + jump_volatility * standard_normal(rng);
+                }
+                price * Float::exp(log_return)
+            }
+        }
+    }
+}
+
+/// The log return of a geometric Brownian motion over `dt` years.
+fn gbm_return<R: Rng>(rng: &mut R, drift: f64, volatility: f64, dt: f64) -> f64 {
+    (drift - volatility * volatility / 2.0) * dt
+        + volatility * Float::sqrt(dt) * standard_normal(rng)
+}
+
+/// Draw from the standard normal distribution with the Box-Muller transform.
+fn standard_normal<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    Float::sqrt(-2.0 * Float::ln(u1)) * Float::cos(2.0 * core::f64::consts::PI * u2)
+}
+
+/// Draw from the Poisson distribution with Knuth's algorithm, which is fast for the small `lambda` of a single step.
+fn poisson<R: Rng>(rng: &mut R, lambda: f64) -> u64 {
+    let threshold = Float::exp(-lambda);
+    let mut num_events = 0;
+    let mut product: f64 = rng.gen();
+    while product > threshold {
+        num_events += 1;
+        product *= rng.gen::<f64>();
+    }
+    num_events
+}
+
+/// The kind of `MarketUpdate`s a `SyntheticMarketData` generator emits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyntheticUpdate<S>
+where
+    S: Currency,
+{
+    /// The best bid and ask around the mid price.
+    Bba,
+    /// A trade of the `quantity` at the best bid or ask, with a random taker side.
+    Trade {
+        /// The quantity of every trade.
+        quantity: S,
+    },
+    /// A candle spanning this many steps of the price process,
+    /// whose low and high are the lowest bid and highest ask of the steps.
+    Candle {
+        /// The number of price process steps per candle.
+        num_steps: usize,
+    },
+}
+
+/// An endless, seeded stream of `(timestamp_ns, MarketUpdate)` items driven by a `PriceProcess`.
+/// The mid price is rounded down to the tick size for the bid, and the ask is the bid plus the spread.
+/// The same seed always yields the same stream.
+#[derive(Debug, Clone)]
+pub struct SyntheticMarketData<S>
+where
+    S: Currency,
+{
+    process: PriceProcess,
+    rng: ChaCha12Rng,
+    mid_price: f64,
+    timestamp_ns: u64,
+    step_ns: u64,
+    tick_size: QuoteCurrency<S::Num>,
+    tick_size_f64: f64,
+    spread_ticks: u64,
+    update: SyntheticUpdate<S>,
+}
+
+impl<S> SyntheticMarketData<S>
+where
+    S: Currency,
+{
+    /// Create a new generator of `Bba` updates one second apart, with a spread of one tick.
+    ///
+    /// # Arguments:
+    /// `process`: The stochastic process of the mid price.
+    /// `start_price`: The initial mid price.
+    /// `tick_size`: The tick size of the quotes, which must match the `PriceFilter` of the exchange.
+    /// `seed`: The seed of the random number generator.
+    ///
+    /// # Returns:
+    /// An Error if the `process` parameters are invalid or the prices are not positive.
+    pub fn new(
+        process: PriceProcess,
+        start_price: QuoteCurrency<S::Num>,
+        tick_size: QuoteCurrency<S::Num>,
+        seed: u64,
+    ) -> Result<Self> {
+        process.validate()?;
+        if start_price <= QuoteCurrency::new_zero() || tick_size <= QuoteCurrency::new_zero() {
+            return Err(Error::NonPositive);
+        }
+        Ok(Self {
+            process,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+            mid_price: start_price.inner().to_f64(),
+            timestamp_ns: 0,
+            step_ns: 1_000_000_000,
+            tick_size,
+            tick_size_f64: tick_size.inner().to_f64(),
+            spread_ticks: 1,
+            update: SyntheticUpdate::Bba,
+        })
+    }
+
+    /// Emit this kind of `MarketUpdate`.
+    ///
+    /// # Returns:
+    /// An Error if the trade quantity or the number of candle steps is not positive.
+    pub fn with_update(mut self, update: SyntheticUpdate<S>) -> Result<Self> {
+        let valid = match update {
+            SyntheticUpdate::Bba => true,
+            SyntheticUpdate::Trade { quantity } => quantity > S::new_zero(),
+            SyntheticUpdate::Candle { num_steps } => num_steps > 0,
+        };
+        if !valid {
+            return Err(Error::NonPositive);
+        }
+        self.update = update;
+        Ok(self)
+    }
+
+    /// Quote a spread of this many ticks.
+    ///
+    /// # Returns:
+    /// An Error if the `spread_ticks` is zero.
+    pub fn with_spread_ticks(mut self, spread_ticks: u64) -> Result<Self> {
+        if spread_ticks == 0 {
+            return Err(Error::NonPositive);
+        }
+        self.spread_ticks = spread_ticks;
+        Ok(self)
+    }
+
+    /// Advance the price process by `step_ns` nanoseconds per step, starting at `start_ts_ns`.
+    ///
+    /// # Returns:
+    /// An Error if the `step_ns` is zero.
+    pub fn with_timing(mut self, start_ts_ns: u64, step_ns: u64) -> Result<Self> {
+        if step_ns == 0 {
+            return Err(Error::NonPositive);
+        }
+        self.timestamp_ns = start_ts_ns;
+        self.step_ns = step_ns;
+        Ok(self)
+    }
+
+    /// The current mid price of the process, before the rounding to the tick size.
+    #[inline(always)]
+    pub fn mid_price(&self) -> f64 {
+        self.mid_price
+    }
+
+    /// Advance the price process by one step.
+    ///
+    /// # Returns:
+    /// The best bid and ask after the step.
+    fn step(&mut self) -> (QuoteCurrency<S::Num>, QuoteCurrency<S::Num>) {
+        self.timestamp_ns += self.step_ns;
+        self.mid_price =
+            self.process
+                .step(&mut self.rng, self.mid_price, self.step_ns as f64 / YEAR_NS);
+        // The bid can not fall below one tick.
+        let half_spread = self.spread_ticks as f64 * self.tick_size_f64 / 2.0;
+        let bid_ticks =
+            Float::floor((self.mid_price - half_spread) / self.tick_size_f64).max(1.0) as u64;
+        let bid = self.tick_size * S::Num::from_u64(bid_ticks);
+        let ask = self.tick_size * S::Num::from_u64(bid_ticks + self.spread_ticks);
+        (bid, ask)
+    }
+}
+
+impl<S> Iterator for SyntheticMarketData<S>
+where
+    S: Currency,
+{
+    type Item = (u64, MarketUpdate<S>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let market_update = match self.update {
+            SyntheticUpdate::Bba => {
+                let (bid, ask) = self.step();
+                MarketUpdate::Bba { bid, ask }
+            }
+            SyntheticUpdate::Trade { quantity } => {
+                let (bid, ask) = self.step();
+                if self.rng.gen_bool(0.5) {
+                    MarketUpdate::Trade {
+                        price: ask,
+                        quantity,
+                        side: Side::Buy,
+                    }
+                } else {
+                    MarketUpdate::Trade {
+                        price: bid,
+                        quantity,
+                        side: Side::Sell,
+                    }
+                }
+            }
+            SyntheticUpdate::Candle { num_steps } => {
+                let (mut bid, mut ask) = self.step();
+                let (mut low, mut high) = (bid, ask);
+                for _ in 1..num_steps {
+                    (bid, ask) = self.step();
+                    if bid < low {
+                        low = bid;
+                    }
+                    if ask > high {
+                        high = ask;
+                    }
+                }
+                MarketUpdate::Candle {
+                    bid,
+                    ask,
+                    low,
+                    high,
+                }
+            }
+        };
+        Some((self.timestamp_ns, market_update))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    const GBM: PriceProcess = PriceProcess::Gbm {
+        drift: 0.0,
+        volatility: 0.8,
+    };
+
+    #[test]
+    fn synthetic_validation() {
+        assert!(SyntheticMarketData::<BaseCurrency>::new(
+            PriceProcess::Gbm {
+                drift: 0.0,
+                volatility: -0.1
+            },
+            quote!(100),
+            quote!(0.1),
+            0
+        )
+        .is_err());
+        assert!(SyntheticMarketData::<BaseCurrency>::new(
+            PriceProcess::OrnsteinUhlenbeck {
+                mean: 0.0,
+                reversion: 1.0,
+                volatility: 1.0
+            },
+            quote!(100),
+            quote!(0.1),
+            0
+        )
+        .is_err());
+        assert_eq!(
+            SyntheticMarketData::<BaseCurrency>::new(GBM, quote!(100), quote!(0), 0).unwrap_err(),
+            Error::NonPositive
+        );
+        let data =
+            SyntheticMarketData::<BaseCurrency>::new(GBM, quote!(100), quote!(0.1), 0).unwrap();
+        assert!(data.clone().with_spread_ticks(0).is_err());
+        assert!(data.clone().with_timing(0, 0).is_err());
+        assert!(data
+            .clone()
+            .with_update(SyntheticUpdate::Trade { quantity: base!(0) })
+            .is_err());
+        assert!(data
+            .with_update(SyntheticUpdate::Candle { num_steps: 0 })
+            .is_err());
+    }
+
+    #[test]
+    fn synthetic_deterministic() {
+        let data = SyntheticMarketData::<BaseCurrency>::new(GBM, quote!(100), quote!(0.1), 42)
+            .unwrap()
+            .with_spread_ticks(2)
+            .unwrap();
+        let a = Vec::from_iter(data.clone().take(100));
+        let b = Vec::from_iter(data.take(100));
+        assert_eq!(a, b);
+        for (i, (ts, update)) in a.iter().enumerate() {
+            assert_eq!(*ts, (i as u64 + 1) * 1_000_000_000);
+            let MarketUpdate::Bba { bid, ask } = *update else {
+                panic!("Expected a Bba update");
+            };
+            assert_eq!(ask - bid, quote!(0.2));
+            assert!(bid.inner().is_multiple_of(Dec!(0.1)));
+        }
+
+        let other = Vec::from_iter(
+            SyntheticMarketData::<BaseCurrency>::new(GBM, quote!(100), quote!(0.1), 43)
+                .unwrap()
+                .take(100),
+        );
+        assert_ne!(a, other);
+    }
+
+    #[test]
+    fn synthetic_ornstein_uhlenbeck_reverts() {
+        let process = PriceProcess::OrnsteinUhlenbeck {
+            mean: 100.0,
+            reversion: 50.0,
+            volatility: 5.0,
+        };
+        let mut data = SyntheticMarketData::<BaseCurrency<f64>>::new(
+            process,
+            QuoteCurrency::new(150.0),
+            QuoteCurrency::new(0.5),
+            1,
+        )
+        .unwrap()
+        .with_timing(0, 86_400_000_000_000)
+        .unwrap();
+        // After a year of daily steps, the price has reverted close to the mean.
+        for _ in 0..365 {
+            data.next();
+        }
+        assert!(
+            (data.mid_price() - 100.0).abs() < 5.0,
+            "{}",
+            data.mid_price()
+        );
+    }
+
+    #[test]
+    fn synthetic_jump_diffusion_jumps() {
+        let process = PriceProcess::JumpDiffusion {
+            drift: 0.0,
+            volatility: 0.0,
+            jump_intensity: 365.0 * 24.0,
+            jump_mean: 0.0,
+            jump_volatility: 0.05,
+        };
+        let data = SyntheticMarketData::<BaseCurrency>::new(process, quote!(100), quote!(0.01), 7)
+            .unwrap()
+            .with_timing(0, 3_600_000_000_000)
+            .unwrap();
+        // Without diffusion, the price only moves by jumps, which occur about once per hourly step.
+        let mids = Vec::from_iter(data.take(100).map(|(_, update)| match update {
+            MarketUpdate::Bba { bid, .. } => bid,
+            _ => unreachable!(),
+        }));
+        let num_moves = mids.windows(2).filter(|w| w[0] != w[1]).count();
+        assert!((40..90).contains(&num_moves), "{num_moves}");
+    }
+
+    #[test]
+    fn synthetic_drives_exchange() {
+        let mut exchange = crate::mock_exchange_base();
+        let trades = SyntheticMarketData::<BaseCurrency>::new(GBM, quote!(100), quote!(1), 3)
+            .unwrap()
+            .with_update(SyntheticUpdate::Trade { quantity: base!(1) })
+            .unwrap();
+        let candles = SyntheticMarketData::<BaseCurrency>::new(GBM, quote!(100), quote!(1), 3)
+            .unwrap()
+            .with_timing(1_000_000_000_000, 60_000_000_000)
+            .unwrap()
+            .with_update(SyntheticUpdate::Candle { num_steps: 10 })
+            .unwrap();
+        for (ts, update) in trades.take(100).chain(candles.take(100)) {
+            exchange.update_state(ts, update).unwrap();
+        }
+        assert_eq!(exchange.market_state().step(), 200);
+    }
+}
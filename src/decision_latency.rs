@@ -0,0 +1,130 @@
+//! Delays strategy decisions by a fixed amount of simulated time before they reach the exchange.
+
+use alloc::{collections::VecDeque, vec::Vec};
+
+use crate::{
+    account_tracker::AccountTracker,
+    exchange::Exchange,
+    types::{Currency, MarginCurrency, Order, Result},
+};
+
+/// Models the time it takes a strategy to compute a decision,
+/// which is separate from the time it takes the order to travel to the exchange.
+/// Orders decided at `decision_ts_ns` only reach the exchange once the simulated time
+/// is at or beyond `decision_ts_ns + latency_ns`.
+/// This makes it possible to separate alpha decay from execution latency effects in research.
+#[derive(Debug, Clone)]
+pub struct DecisionLatency<S>
+where
+    S: Currency,
+{
+    latency_ns: u64,
+    /// Orders that await release, sorted by their release timestamp.
+    pending: VecDeque<(i64, Order<S>)>,
+}
+
+impl<S> DecisionLatency<S>
+where
+    S: Currency,
+{
+    /// Create a new instance with a fixed decision latency in nanoseconds.
+    pub fn new(latency_ns: u64) -> Self {
+        Self {
+            latency_ns,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Return the configured decision latency in nanoseconds.
+    #[inline(always)]
+    pub fn latency_ns(&self) -> u64 {
+        self.latency_ns
+    }
+
+    /// Return the number of orders that have been decided but not yet released.
+    #[inline(always)]
+    pub fn num_pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Record an order the strategy decided upon at `decision_ts_ns`.
+    pub fn decide(&mut self, decision_ts_ns: i64, order: Order<S>) {
+        let release_ts_ns = decision_ts_ns + self.latency_ns as i64;
+        let idx = self
+            .pending
+            .partition_point(|(ts_ns, _)| *ts_ns <= release_ts_ns);
+        self.pending.insert(idx, (release_ts_ns, order));
+    }
+
+    /// Remove and return all orders whose decision latency has elapsed at `current_ts_ns`,
+    /// in the order of their release.
+    pub fn release(&mut self, current_ts_ns: i64) -> Vec<Order<S>> {
+        let n = self
+            .pending
+            .partition_point(|(ts_ns, _)| *ts_ns <= current_ts_ns);
+        Vec::from_iter(self.pending.drain(..n).map(|(_, order)| order))
+    }
+
+    /// Submit all orders whose decision latency has elapsed at the current exchange time.
+    ///
+    /// # Returns:
+    /// The result of each submission.
+    pub fn submit_due<A>(&mut self, exchange: &mut Exchange<A, S>) -> Vec<Result<Order<S>>>
+    where
+        A: AccountTracker<S::PairedCurrency>,
+        S::PairedCurrency: MarginCurrency,
+    {
+        let ts_ns = exchange.market_state().current_timestamp_ns();
+        Vec::from_iter(
+            self.release(ts_ns)
+                .into_iter()
+                .map(|order| exchange.submit_order(order)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock_exchange_base, prelude::*};
+
+    #[test]
+    fn decision_latency_release() {
+        let mut dl = DecisionLatency::new(10);
+        let o0 = Order::market(Side::Buy, base!(1)).unwrap();
+        let o1 = Order::market(Side::Sell, base!(2)).unwrap();
+        dl.decide(5, o0.clone());
+        dl.decide(0, o1.clone());
+        assert_eq!(dl.num_pending(), 2);
+
+        assert_eq!(dl.release(9), vec![]);
+        assert_eq!(dl.release(10), vec![o1]);
+        assert_eq!(dl.release(20), vec![o0]);
+        assert_eq!(dl.num_pending(), 0);
+    }
+
+    #[test]
+    fn decision_latency_submit_due() {
+        let mut exchange = mock_exchange_base();
+        exchange
+            .update_state(0, bba!(quote!(99), quote!(100)))
+            .unwrap();
+
+        let mut dl = DecisionLatency::new(100);
+        dl.decide(0, Order::market(Side::Buy, base!(1)).unwrap());
+        assert!(dl.submit_due(&mut exchange).is_empty());
+
+        // The price moved while the strategy was computing its decision.
+        exchange
+            .update_state(100, bba!(quote!(104), quote!(105)))
+            .unwrap();
+        let results = dl.submit_due(&mut exchange);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].as_ref().unwrap().filled(),
+            Filled::Yes {
+                fill_price: quote!(105)
+            }
+        );
+    }
+}
@@ -1,31 +1,127 @@
+use alloc::{boxed::Box, collections::VecDeque, string::ToString, vec::Vec};
+
+use hashbrown::HashMap;
+
 use crate::{
     account::Account,
-    account_tracker::AccountTracker,
+    account_tracker::{AccountTracker, NoAccountTracker},
+    candle_path::{crossing_distance, CandlePathSampler},
     clearing_house::ClearingHouse,
     config::Config,
+    fault_injection::FaultInjector,
+    fee_schedule::RollingVolume,
+    journal::{Journal, JournalEntry},
+    latency::LatencySampler,
+    ledger::LedgerEntryKind,
+    maker_program::{MakerProgramState, MakerProgramTier},
     market_state::MarketState,
-    risk_engine::{IsolatedMarginRiskEngine, RiskEngine},
+    options::{OptionContract, OptionPosition},
+    position_tpsl::{PositionTpSl, TrailingTakeProfit},
+    rate_limit::RateLimiter,
+    risk_engine::{IsolatedMarginRiskEngine, RiskEngine, RiskError},
+    snapshot::ExchangeSnapshot,
     types::{
-        Currency, Error, MarginCurrency, MarketUpdate, Order, OrderError, OrderType, Result, Side,
+        Currency, DustHandling, Error, EventSubscribers, ExchangeEvent, ExchangeStats, Fee, Fill,
+        Leverage, MarginCurrency, MarginShock, MarketUpdate, Mon, Order, OrderError, OrderStatus,
+        OrderType, PositionMode, PriceImprovement, QuoteCurrency, Result, SelfTradePrevention,
+        Side, TimeInForce, TriggerSource, UserOrderId,
     },
+    utils::max,
 };
 
 pub(crate) const EXPECT_LIMIT_PRICE: &str = "A limit price must be present for a limit order; qed";
 
+/// The lowest and highest price during a market update.
+type PriceRange<T> = (QuoteCurrency<T>, QuoteCurrency<T>);
+
+/// A request from the user which is in flight to the exchange.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+enum Request<S>
+where
+    S: Currency,
+{
+    Submission(Box<Order<S>>),
+    Cancellation(u64),
+}
+
+/// The changes of the `SelfTradePrevention` to an aggressive order and the resting orders it crosses.
+#[derive(Debug)]
+struct SelfTrade<S>
+where
+    S: Currency,
+{
+    /// The quantity by which the aggressive order is decremented.
+    aggressor_decrement: S,
+    /// The ids of the resting orders to cancel.
+    cancel: Vec<u64>,
+    /// The id of a resting order that is decremented by the quantity, without being cancelled.
+    decrement: Option<(u64, S)>,
+}
+
 #[derive(Debug, Clone)]
 /// The main leveraged futures exchange for simulated trading
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(bound(
+        serialize = "A: serde::Serialize",
+        deserialize = "A: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Exchange<A, S>
 where
     S: Currency,
     S::PairedCurrency: MarginCurrency,
 {
     config: Config<S::PairedCurrency>,
-    market_state: MarketState,
+    market_state: MarketState<S::Num>,
     account: Account<S::PairedCurrency>,
     account_tracker: A,
     risk_engine: IsolatedMarginRiskEngine<S::PairedCurrency>,
+    /// The custom risk engines of the `Config`, which are dropped when deserialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    custom_risk_engines: Vec<Box<dyn RiskEngine<S::PairedCurrency>>>,
     clearing_house: ClearingHouse<A, S::PairedCurrency>,
+    maker_program: Option<MakerProgramState<S::Num>>,
+    rolling_volume: RollingVolume<S::Num>,
+    fee_maker: Fee<S::Num>,
+    fee_taker: Fee<S::Num>,
+    stats: ExchangeStats,
+    insurance_fund_payout: S::PairedCurrency,
+    /// The cumulative quantity of dust positions written off at their entry price due to `DustHandling::Track`.
+    dust: S,
+    /// Callbacks can not be serialized, so they must be subscribed again after resuming.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    subscribers: EventSubscribers<S>,
+    first_ts_ns: Option<i64>,
+    settled: bool,
+    latency: LatencySampler,
+    rate_limiter: RateLimiter,
+    fault_injector: FaultInjector,
+    candle_path: CandlePathSampler,
+    /// Requests that have not reached the exchange yet, sorted by their arrival timestamp.
+    in_flight: VecDeque<(i64, Request<S>)>,
     next_order_id: u64,
+    /// The final status of the orders which are no longer active, up to `Config::closed_order_capacity`.
+    closed_orders: HashMap<u64, OrderStatus>,
+    /// The ids of the `closed_orders`, oldest first.
+    closed_order_ids: VecDeque<u64>,
+    /// The resting orders triggered by the current market update, kept to reuse the allocation.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    triggered_orders: Vec<Order<S>>,
+    /// The fills of the latest call to `update_state` or `submit_order`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fills: Vec<Fill<S>>,
+    /// The latest fills, up to `Config::trade_history_capacity`.
+    trade_history: VecDeque<Fill<S>>,
+    next_trade_id: u64,
+    /// The timestamp in nanoseconds at which the latest halt of the `CircuitBreaker` ends.
+    halted_until_ns: Option<i64>,
+    /// The stop orders which have not been triggered yet, in the order of their submission.
+    stop_orders: Vec<Order<S>>,
+    position_tpsl: Option<PositionTpSl<S::Num>>,
+    trailing_take_profit: Option<TrailingTakeProfit<S::Num>>,
 }
 
 impl<A, S> Exchange<A, S>
@@ -37,25 +133,78 @@ where
     /// Create a new Exchange with the desired config and whether to use candles
     /// as infomation source
     pub fn new(account_tracker: A, config: Config<S::PairedCurrency>) -> Self {
-        let market_state = MarketState::new(config.contract_specification().price_filter.clone());
-        let account = Account::new(
+        let market_state = MarketState::new(
+            config.contract_specification().price_filter.clone(),
+            config.mark_price_source().box_clone(),
+        );
+        let fee_schedule = &config.contract_specification().fee_schedule;
+        let base_tier = fee_schedule.tier(S::Num::ZERO);
+        let (fee_maker, fee_taker) = (base_tier.fee_maker, base_tier.fee_taker);
+        let rolling_volume = RollingVolume::new(fee_schedule.window_ns());
+        let mut account = Account::new(
             config.starting_balance(),
             config.initial_leverage(),
-            config.contract_specification().fee_maker,
+            fee_maker,
+            config.position_mode(),
         );
+        account.set_collateral_assets(config.collateral_assets());
+        account.set_fee_token(config.fee_token());
+        account.set_order_margin_model(config.order_margin_model().clone());
         let risk_engine = IsolatedMarginRiskEngine::<S::PairedCurrency>::new(
             config.contract_specification().clone(),
+            config.max_risk_per_trade(),
+            config.position_limit().clone(),
+            config.borrow_pool().cloned(),
+            config.position_flip(),
+        );
+        let custom_risk_engines = Vec::from_iter(
+            config
+                .risk_engines()
+                .iter()
+                .map(|risk_engine| risk_engine.box_clone()),
         );
         let clearing_house = ClearingHouse::new();
+        let maker_program = config.maker_program().cloned().map(MakerProgramState::new);
+        let latency = LatencySampler::new(config.latency_model().clone(), config.latency_seed());
+        let rate_limiter = RateLimiter::new(config.rate_limit());
+        let fault_injector =
+            FaultInjector::new(config.fault_injection(), config.fault_injection_seed());
+        let candle_path = CandlePathSampler::new(config.candle_path());
 
         Self {
             config,
             market_state,
             clearing_house,
             risk_engine,
+            custom_risk_engines,
             account,
             account_tracker,
+            maker_program,
+            rolling_volume,
+            fee_maker,
+            fee_taker,
+            stats: ExchangeStats::default(),
+            insurance_fund_payout: S::PairedCurrency::new_zero(),
+            dust: S::new_zero(),
+            subscribers: EventSubscribers::default(),
+            first_ts_ns: None,
+            settled: false,
+            latency,
+            rate_limiter,
+            fault_injector,
+            candle_path,
+            in_flight: VecDeque::new(),
             next_order_id: 0,
+            closed_orders: HashMap::new(),
+            closed_order_ids: VecDeque::new(),
+            triggered_orders: Vec::new(),
+            fills: Vec::new(),
+            trade_history: VecDeque::new(),
+            next_trade_id: 0,
+            halted_until_ns: None,
+            stop_orders: Vec::new(),
+            position_tpsl: None,
+            trailing_take_profit: None,
         }
     }
 
@@ -85,10 +234,110 @@ where
 
     /// Return a reference to the currency `MarketState`
     #[inline(always)]
-    pub fn market_state(&self) -> &MarketState {
+    pub fn market_state(&self) -> &MarketState<S::Num> {
         &self.market_state
     }
 
+    /// Take an `ExchangeSnapshot` of the account, margin, position, open orders and market state,
+    /// e.g. to `diff` it against a snapshot of another run.
+    pub fn snapshot(&self) -> ExchangeSnapshot<S::PairedCurrency> {
+        ExchangeSnapshot::new(&self.market_state, &self.account)
+    }
+
+    /// Return the counters of the events processed so far,
+    /// e.g. to log the progress of a simulation.
+    pub fn stats(&self) -> ExchangeStats {
+        ExchangeStats {
+            step: self.market_state.step(),
+            elapsed_ns: self.first_ts_ns.map_or(0, |first_ts_ns| {
+                self.market_state.current_timestamp_ns() - first_ts_ns
+            }),
+            ..self.stats.clone()
+        }
+    }
+
+    /// The currently active tier of the `MakerProgram`, if any.
+    #[inline]
+    pub fn maker_program_tier(&self) -> Option<&MakerProgramTier<S::Num>> {
+        self.maker_program.as_ref().and_then(|p| p.tier())
+    }
+
+    /// The traded notional volume within the rolling window of the `FeeSchedule`,
+    /// denoted in the margin currency.
+    #[inline(always)]
+    pub fn rolling_volume(&self) -> S::Num {
+        self.rolling_volume.volume()
+    }
+
+    /// The maker fee that currently applies to resting orders.
+    #[inline(always)]
+    pub fn fee_maker(&self) -> Fee<S::Num> {
+        self.fee_maker
+    }
+
+    /// The taker fee that currently applies to orders executed immediately.
+    #[inline(always)]
+    pub fn fee_taker(&self) -> Fee<S::Num> {
+        self.fee_taker
+    }
+
+    /// The margin required to keep all positions open at the current mid price,
+    /// using the maintenance margin of the `ContractSpecification`.
+    pub fn maintenance_margin(&self) -> S::PairedCurrency {
+        self.account.maintenance_margin(
+            &self.market_state,
+            self.config.contract_specification().maintenance_margin,
+        )
+    }
+
+    /// How close the positions are to liquidation, using the maintenance margin of the `ContractSpecification`.
+    /// A value above one means they get liquidated on the next update.
+    /// See [`Account::margin_ratio`] for details.
+    pub fn margin_ratio(&self) -> S::Num {
+        self.account.margin_ratio(
+            &self.market_state,
+            self.config.contract_specification().maintenance_margin,
+        )
+    }
+
+    /// Stress test the current position against an instantaneous relative price move,
+    /// using the maintenance margin of the `ContractSpecification`.
+    /// See [`Account::margin_after_shock`] for details.
+    pub fn margin_after_shock(&self, pct_move: S::Num) -> MarginShock<S::PairedCurrency> {
+        self.account.margin_after_shock(
+            &self.market_state,
+            self.config.contract_specification().maintenance_margin,
+            pct_move,
+        )
+    }
+
+    /// Check the internal consistency of the exchange, e.g. after every step in a test of a strategy.
+    /// This includes that the wallet balance covers the position, order and option margin,
+    /// that no margin is negative, that the ledger adds up to the wallet balance
+    /// and that the incrementally tracked order margin matches the active orders.
+    ///
+    /// # Panics:
+    /// With a description of the first violated invariant.
+    pub fn assert_invariants(&self) {
+        self.account
+            .assert_invariants(self.config.starting_balance());
+        for id in self.account.active_limit_orders().keys() {
+            assert!(
+                !self.closed_orders.contains_key(id),
+                "order {id} is both active and closed"
+            );
+        }
+        for (group_id, ids) in self.account.order_groups.iter() {
+            for id in ids {
+                assert!(
+                    self.account.active_limit_orders().contains_key(id)
+                        || self.stop_orders.iter().any(|order| order.id() == *id),
+                    "order group {group_id} contains the inactive order {id}"
+                );
+            }
+        }
+    }
+
     /// Update the exchange state with new information
     ///
     /// ### Parameters:
@@ -98,201 +347,2296 @@ where
     ///
     /// ### Returns:
     /// If Ok, the executed orders,
+    /// including partially executed iceberg orders which remain active with their `filled_quantity` updated.
     /// Some Error otherwise
     pub fn update_state(
         &mut self,
         timestamp_ns: u64,
         market_update: MarketUpdate<S>,
     ) -> Result<Vec<Order<S>>> {
+        let mut executed = Vec::new();
+        self.update_state_into(timestamp_ns, &market_update, &mut executed)?;
+        Ok(executed)
+    }
+
+    /// Update the exchange state with a batch of market updates, in the order of the slice.
+    /// Each update is matched and settled exactly like in `update_state`, including the journaling and `ExchangeEvent`s,
+    /// but the `AccountTracker` is only sampled once at the end of the batch, e.g. at the close of a candle,
+    /// instead of after every update. Use `update_state` to sample every update.
+    ///
+    /// ### Parameters:
+    /// `updates`: The timestamps in nanoseconds and the market updates.
+    ///
+    /// ### Returns:
+    /// If Ok, the executed orders of all updates, in the order of their execution,
+    /// and the fills of the whole batch are available in `Exchange::fills`.
+    /// The Error of the first failing update otherwise, e.g. a liquidation,
+    /// in which case the updates before it remain applied and the ones after it are not processed.
+    /// The `AccountTracker` is then sampled at the failing update for a liquidation
+    /// and at the last applied update otherwise.
+    pub fn update_state_batch(
+        &mut self,
+        updates: &[(u64, MarketUpdate<S>)],
+    ) -> Result<Vec<Order<S>>> {
+        let mut executed = Vec::new();
+        self.fills.clear();
+        let last = updates.len().saturating_sub(1);
+        for (i, (timestamp_ns, market_update)) in updates.iter().enumerate() {
+            match self.process_market_update(*timestamp_ns, market_update, &mut executed, i == last)
+            {
+                Ok(()) => {}
+                // A failed maintenance margin check, for which the tracker is sampled before liquidating.
+                Err(e @ Error::RiskError(_)) => return Err(e),
+                Err(e) => {
+                    if i > 0 {
+                        self.account_tracker.update(
+                            self.market_state.current_timestamp_ns() as u64,
+                            &self.market_state,
+                            &self.account,
+                        );
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(executed)
+    }
+
+    /// Update the exchange state like `update_state`, but append the executed orders to the caller provided `executed` buffer.
+    /// Reusing the buffer across calls avoids any allocation for the returned orders,
+    /// e.g. in tick level backtests over billions of updates.
+    ///
+    /// ### Parameters:
+    /// `timestamp_ns`: Is used in the AccountTracker `A`
+    ///     and if setting order timestamps is enabled in the config.
+    /// `market_update`: Newest market information
+    /// `executed`: The executed orders get appended to it. It is not cleared beforehand.
+    ///
+    /// ### Returns:
+    /// Some Error if the update failed, in which case the executed orders appended so far remain in `executed`.
+    pub fn update_state_into(
+        &mut self,
+        timestamp_ns: u64,
+        market_update: &MarketUpdate<S>,
+        executed: &mut Vec<Order<S>>,
+    ) -> Result<()> {
+        self.fills.clear();
+        self.process_market_update(timestamp_ns, market_update, executed, true)
+    }
+
+    /// Process a single market update, sampling the `AccountTracker` if `sample_tracker`
+    /// or if the account gets liquidated.
+    fn process_market_update(
+        &mut self,
+        timestamp_ns: u64,
+        market_update: &MarketUpdate<S>,
+        executed: &mut Vec<Order<S>>,
+        sample_tracker: bool,
+    ) -> Result<()> {
+        self.subscribers.record(|| JournalEntry::MarketUpdate {
+            timestamp_ns,
+            update: market_update.clone(),
+        });
+        self.stats.num_updates += 1;
+        let prev_ts_ns = self.market_state.current_timestamp_ns();
+        let prev_mid_price = (self.market_state.step() > 0).then(|| self.market_state.mid_price());
+        let prev_mark_price =
+            (self.market_state.step() > 0).then(|| self.market_state.mark_price());
         self.market_state
-            .update_state(timestamp_ns, &market_update)?;
-        self.account_tracker
-            .update(timestamp_ns, &self.market_state, &self.account);
-        if let Err(e) = self
-            .risk_engine
-            .check_maintenance_margin(&self.market_state, &self.account)
-        {
-            // TODO: liquidate position properly
+            .update_state(timestamp_ns, market_update)?;
+        let ts_ns = self.market_state.current_timestamp_ns();
+        self.check_circuit_breaker(prev_mark_price);
+        if let Some(borrow_rate) = self.config.borrow_rate() {
+            if self.first_ts_ns.is_some() && ts_ns > prev_ts_ns {
+                let borrow_cost = self.clearing_house.settle_borrow_cost(
+                    &mut self.account,
+                    &mut self.account_tracker,
+                    borrow_rate,
+                    ts_ns - prev_ts_ns,
+                    ts_ns,
+                );
+                if borrow_cost != S::PairedCurrency::new_zero() {
+                    self.subscribers
+                        .emit(ts_ns, || ExchangeEvent::BorrowCost(borrow_cost));
+                }
+            }
+        }
+        if let Some(borrow_pool) = self.config.borrow_pool() {
+            if self.first_ts_ns.is_some() && ts_ns > prev_ts_ns {
+                let interest = self.clearing_house.settle_short_borrow_interest(
+                    &mut self.account,
+                    &mut self.account_tracker,
+                    borrow_pool,
+                    self.market_state.mark_price(),
+                    ts_ns - prev_ts_ns,
+                    ts_ns,
+                );
+                if interest != S::PairedCurrency::new_zero() {
+                    self.subscribers
+                        .emit(ts_ns, || ExchangeEvent::BorrowCost(interest));
+                }
+            }
+        }
+        if let Some(idle_margin_rate) = self.config.idle_margin_rate() {
+            if self.first_ts_ns.is_some() && ts_ns > prev_ts_ns {
+                let interest = self.clearing_house.settle_idle_margin_interest(
+                    &mut self.account,
+                    &mut self.account_tracker,
+                    idle_margin_rate,
+                    ts_ns - prev_ts_ns,
+                    ts_ns,
+                );
+                if interest != S::PairedCurrency::new_zero() {
+                    self.subscribers
+                        .emit(ts_ns, || ExchangeEvent::Interest(interest));
+                }
+            }
+        }
+        self.first_ts_ns.get_or_insert(ts_ns);
+        self.rolling_volume.update_timestamp(ts_ns);
+        if let Some(maker_program) = self.maker_program.as_mut() {
+            maker_program.update_timestamp(ts_ns);
+        }
+        self.update_fees();
+        if !self.settled && self.is_expired() {
+            self.settle_expiry();
+            self.settle_bankruptcy();
+        }
+        let maintenance_margin = self.check_maintenance_margin();
+        if sample_tracker || maintenance_margin.is_err() {
+            self.account_tracker
+                .update(timestamp_ns, &self.market_state, &self.account);
+        }
+        if let Err(e) = maintenance_margin {
+            self.liquidate();
             return Err(e.into());
         };
 
-        let mut to_be_exec = self.check_resting_orders(&market_update);
-        for order in to_be_exec.iter_mut() {
+        self.expire_orders();
+        self.settle_expired_options();
+        self.settle_bankruptcy();
+        self.process_arrived_requests(executed);
+
+        if self.is_halted() {
+            // Orders are neither triggered nor matched until trading resumes.
+            return Ok(());
+        }
+        self.trigger_stop_orders(market_update, executed);
+        self.trigger_position_tpsl(market_update, executed);
+        self.trail_take_profit(market_update, executed);
+        let mut to_be_exec = core::mem::take(&mut self.triggered_orders);
+        self.check_resting_orders(market_update, prev_mid_price, &mut to_be_exec);
+        executed.reserve(to_be_exec.len());
+        for mut order in to_be_exec.drain(..) {
+            if !self.account.active_limit_orders.contains_key(&order.id()) {
+                // A one-cancels-other sibling got executed first.
+                continue;
+            }
+            // Only the visible quantity of an iceberg order gets executed at once.
+            let fill_qty = order.visible_quantity();
+            if order.reduce_only() && fill_qty > self.account.reducible_quantity(order.side()) {
+                // The position got reduced in the meantime.
+                debug!(
+                    "cancelling reduce-only order exceeding the position: {:?}",
+                    order
+                );
+                self.try_cancel_order(order.id())?;
+                continue;
+            }
             let qty = match order.side() {
-                Side::Buy => order.quantity(),
-                Side::Sell => order.quantity().into_negative(),
+                Side::Buy => fill_qty,
+                Side::Sell => fill_qty.into_negative(),
             };
             let l_price = order.limit_price().expect(EXPECT_LIMIT_PRICE);
-            self.clearing_house.settle_filled_order(
+            let fee_maker = self.fee_maker;
+            let fee = self.clearing_house.settle_filled_order(
                 &mut self.account,
                 &mut self.account_tracker,
                 qty,
                 l_price,
-                self.config.contract_specification().fee_maker,
+                fee_maker,
+                LedgerEntryKind::MakerFee,
+                order.reduce_only(),
                 self.market_state.current_timestamp_ns(),
             );
-            self.account.remove_executed_order_from_active(order.id());
-            self.account_tracker.log_limit_order_fill();
-            order.mark_filled(l_price);
+            self.log_fill_volume(fill_qty.convert(l_price).inner(), true);
+            self.stats.num_fills += 1;
+            self.record_fill(&order, fill_qty, l_price, fee, true);
+            order.add_filled_quantity(fill_qty);
+            if order.remaining_quantity() == S::new_zero() {
+                self.account.remove_executed_order_from_active(order.id());
+                self.account_tracker.log_limit_order_fill();
+                order.mark_filled(l_price);
+                self.close_order(&mut order, OrderStatus::Filled);
+                self.subscribers.emit(ts_ns, || ExchangeEvent::Fill {
+                    order: order.clone(),
+                    quantity: fill_qty,
+                    price: l_price,
+                    is_maker: true,
+                });
+            } else {
+                // The iceberg order is refilled and loses its time priority.
+                order.set_timestamp(self.market_state.current_timestamp_ns());
+                order.set_status(
+                    OrderStatus::PartiallyFilled,
+                    self.market_state.current_timestamp_ns(),
+                );
+                self.account.update_partially_filled_order(order.clone());
+                self.subscribers.emit(ts_ns, || ExchangeEvent::PartialFill {
+                    order: order.clone(),
+                    quantity: fill_qty,
+                    price: l_price,
+                });
+                if self.is_dust_order(&order) {
+                    self.try_cancel_order(order.id())?;
+                }
+            }
+            self.cancel_order_group_siblings(&order);
+            executed.push(order);
         }
+        self.triggered_orders = to_be_exec;
+        self.handle_dust_positions();
 
-        Ok(to_be_exec)
-    }
+        // A resting order fills at its limit price, which may be far from the market after a gap.
+        if let Err(e) = self.check_maintenance_margin() {
+            self.liquidate();
+            return Err(e.into());
+        };
 
-    /// Check if any resting orders have been executed
-    fn check_resting_orders(&mut self, market_update: &MarketUpdate<S>) -> Vec<Order<S>> {
-        Vec::from_iter(
-            self.account
-                .active_limit_orders
-                .values()
-                .filter(|order| self.check_limit_order_execution(order, market_update))
-                .cloned(),
-        )
+        Ok(())
     }
 
-    /// Check an individual resting order if it has been executed.
-    ///
-    /// # Returns:
-    /// If `Some`, The order is filled and needs to be settled.
-    fn check_limit_order_execution(
-        &self,
-        limit_order: &Order<S>,
-        market_update: &MarketUpdate<S>,
-    ) -> bool {
-        let limit_price = limit_order.limit_price().expect(EXPECT_LIMIT_PRICE);
+    /// Log the notional value of a fill for the volume based fees.
+    fn log_fill_volume(&mut self, notional_value: S::Num, is_maker: bool) {
+        self.rolling_volume
+            .log_fill(self.market_state.current_timestamp_ns(), notional_value);
+        if let Some(maker_program) = self.maker_program.as_mut() {
+            maker_program.log_fill(notional_value, is_maker);
+        }
+        self.update_fees();
+    }
 
-        match market_update {
-            MarketUpdate::Bba { .. } => {
-                // Updates to the best bid and ask prices do not trigger limit orders for simulation purposes.
-                false
-            }
-            MarketUpdate::Trade {
-                price,
-                quantity: _,
-                side,
-            } => {
-                // For now we ignore the filled quantity, which will change in future versions.
-                match limit_order.side() {
-                    Side::Buy => *price <= limit_price && matches!(side, Side::Sell),
-                    Side::Sell => *price >= limit_price && matches!(side, Side::Buy),
-                }
+    /// Apply the fees of the active `MakerProgramTier`,
+    /// or of the `FeeTier` for the rolling volume otherwise.
+    fn update_fees(&mut self) {
+        let (fee_maker, fee_taker) = match self.maker_program_tier() {
+            Some(tier) => (tier.fee_maker, tier.fee_taker),
+            None => {
+                let tier = self
+                    .config
+                    .contract_specification()
+                    .fee_schedule
+                    .tier(self.rolling_volume.volume());
+                (tier.fee_maker, tier.fee_taker)
             }
-            MarketUpdate::Candle {
-                bid: _,
-                ask: _,
-                low,
-                high,
-            } => match limit_order.side() {
-                Side::Buy => *low < limit_price,
-                Side::Sell => *high > limit_price,
-            },
+        };
+        if (fee_maker, fee_taker) == (self.fee_maker, self.fee_taker) {
+            return;
         }
+        debug!("new fees: maker {fee_maker}, taker {fee_taker}");
+        self.fee_maker = fee_maker;
+        self.fee_taker = fee_taker;
+        self.account.set_maker_fee(fee_maker);
+        self.risk_engine.set_fees(fee_maker, fee_taker);
     }
 
-    /// Submit a new order to the exchange.
-    ///
-    /// # Arguments:
-    /// `order`: The order that is being submitted.
-    ///
-    /// # Returns:
-    /// If Ok, the order with timestamp and id filled in.
-    /// Else its an error.
-    pub fn submit_order(&mut self, mut order: Order<S>) -> Result<Order<S>> {
-        trace!("submit_order: {:?}", order);
-
-        // Basic checks
-        self.config
-            .contract_specification()
-            .quantity_filter
-            .validate_order(&order)?;
+    /// Whether the exchange is unavailable due to a scheduled downtime, see `Config::with_downtime`.
+    pub fn is_down(&self) -> bool {
         self.config
-            .contract_specification()
-            .price_filter
-            .validate_order(&order, self.market_state.mid_price())?;
+            .is_down(self.market_state.current_timestamp_ns())
+    }
 
-        order.set_timestamp(self.market_state.current_timestamp_ns());
-        order.set_id(self.next_order_id());
+    /// Fail with `Error::ExchangeUnavailable` during a scheduled downtime.
+    fn check_available(&self) -> Result<()> {
+        if self.is_down() {
+            return Err(Error::ExchangeUnavailable);
+        }
+        Ok(())
+    }
 
-        match order.order_type() {
-            OrderType::Market => {
-                let fill_price = match order.side() {
-                    Side::Buy => self.market_state.ask(),
-                    Side::Sell => self.market_state.bid(),
-                };
-                self.risk_engine
-                    .check_market_order(&self.account, &order, fill_price)?;
-                let quantity = match order.side() {
-                    Side::Buy => order.quantity(),
-                    Side::Sell => order.quantity().into_negative(),
-                };
-                // From here on, everything is infallible
-                self.clearing_house.settle_filled_order(
-                    &mut self.account,
-                    &mut self.account_tracker,
-                    quantity,
-                    fill_price,
-                    self.config.contract_specification().fee_taker,
-                    self.market_state.current_timestamp_ns(),
-                );
-                order.mark_filled(fill_price);
-                self.account_tracker.log_market_order_fill();
-            }
-            OrderType::Limit => {
-                let l_price = order.limit_price().expect(EXPECT_LIMIT_PRICE);
-                match order.side() {
-                    Side::Buy => {
-                        if l_price >= self.market_state.ask() {
-                            return Err(Error::OrderError(OrderError::LimitPriceAboveAsk));
-                        }
-                    }
-                    Side::Sell => {
-                        if l_price <= self.market_state.bid() {
-                            return Err(Error::OrderError(OrderError::LimitPriceBelowBid));
-                        }
-                    }
+    /// Whether trading is halted by the `CircuitBreaker`, see `Config::with_circuit_breaker`.
+    pub fn is_halted(&self) -> bool {
+        self.halted_until_ns
+            .is_some_and(|until_ts_ns| self.market_state.current_timestamp_ns() < until_ts_ns)
+    }
+
+    /// Halt trading if the latest market update moved the mark price beyond the `CircuitBreaker` band.
+    /// A move during a halt does not extend it.
+    fn check_circuit_breaker(&mut self, prev_mark_price: Option<QuoteCurrency<S::Num>>) {
+        let (Some(circuit_breaker), Some(prev_mark_price)) =
+            (self.config.circuit_breaker(), prev_mark_price)
+        else {
+            return;
+        };
+        if self.is_halted()
+            || !circuit_breaker.is_tripped(prev_mark_price, self.market_state.mark_price())
+        {
+            return;
+        }
+        let ts_ns = self.market_state.current_timestamp_ns();
+        let until_ts_ns = ts_ns + circuit_breaker.halt_duration_ns();
+        debug!("circuit breaker halts trading until {until_ts_ns}");
+        self.halted_until_ns = Some(until_ts_ns);
+        self.subscribers
+            .emit(ts_ns, || ExchangeEvent::TradingHalted { until_ts_ns });
+    }
+
+    /// Whether the dated futures contract has reached its expiry.
+    pub fn is_expired(&self) -> bool {
+        self.config
+            .expiry_ts_ns()
+            .is_some_and(|expiry_ts_ns| self.market_state.current_timestamp_ns() >= expiry_ts_ns)
+    }
+
+    /// Cancel all resting orders and cash settle the open positions at the mid price.
+    fn settle_expiry(&mut self) {
+        self.cancel_all_resting_orders();
+        let settlement_price = self.market_state.mid_price();
+        self.clearing_house.settle_expiry(
+            &mut self.account,
+            &mut self.account_tracker,
+            settlement_price,
+            self.market_state.current_timestamp_ns(),
+        );
+        self.subscribers
+            .emit(self.market_state.current_timestamp_ns(), || {
+                ExchangeEvent::Settlement {
+                    price: settlement_price,
                 }
-                self.risk_engine.check_limit_order(&self.account, &order)?;
-                self.account.append_limit_order(order.clone());
-                self.account_tracker.log_limit_order_submission();
+            });
+        self.settled = true;
+    }
+
+    fn cancel_all_resting_orders(&mut self) {
+        for mut order in core::mem::take(&mut self.stop_orders) {
+            self.account.leave_order_group(&order);
+            self.close_order(&mut order, OrderStatus::Cancelled);
+            self.stats.num_cancellations += 1;
+            self.subscribers
+                .emit(self.market_state.current_timestamp_ns(), || {
+                    ExchangeEvent::Cancel(order)
+                });
+        }
+        let order_ids = Vec::from_iter(self.account.active_limit_orders.keys().copied());
+        for order_id in order_ids {
+            let mut order = self
+                .account
+                .cancel_order(order_id, &mut self.account_tracker)
+                .expect("Is an active order");
+            self.close_order(&mut order, OrderStatus::Cancelled);
+            self.stats.num_cancellations += 1;
+            self.subscribers
+                .emit(self.market_state.current_timestamp_ns(), || {
+                    ExchangeEvent::Cancel(order)
+                });
+        }
+    }
+
+    /// Set the final `status` of an `order` which is no longer active
+    /// and remember it for `order_status`.
+    fn close_order(&mut self, order: &mut Order<S>, status: OrderStatus) {
+        order.set_status(status, self.market_state.current_timestamp_ns());
+        self.remember_closed_order(order.id(), status);
+    }
+
+    /// Remember the final `status` of a closed order,
+    /// forgetting the oldest one beyond the `Config::closed_order_capacity`.
+    fn remember_closed_order(&mut self, order_id: u64, status: OrderStatus) {
+        if self.closed_orders.insert(order_id, status).is_some() {
+            return;
+        }
+        self.closed_order_ids.push_back(order_id);
+        if let Some(capacity) = self.config.closed_order_capacity() {
+            while self.closed_order_ids.len() > capacity {
+                let oldest = self
+                    .closed_order_ids
+                    .pop_front()
+                    .expect("Exceeds the capacity; qed");
+                self.closed_orders.remove(&oldest);
             }
         }
+    }
 
-        Ok(order)
+    /// Return the `OrderStatus` of the order with the `order_id` assigned by the exchange,
+    /// or `None` if no such order was submitted or its status was forgotten due to the `Config::closed_order_capacity`.
+    /// Submissions which have not reached the exchange yet due to the `LatencyModel` are `OrderStatus::New`.
+    pub fn order_status(&self, order_id: u64) -> Option<OrderStatus> {
+        if let Some(order) = self.account.active_limit_orders.get(&order_id) {
+            return Some(order.status());
+        }
+        if self.stop_orders.iter().any(|order| order.id() == order_id) {
+            return Some(OrderStatus::New);
+        }
+        if self
+            .submissions_in_flight()
+            .any(|order| order.id() == order_id)
+        {
+            return Some(OrderStatus::New);
+        }
+        self.closed_orders.get(&order_id).copied()
+    }
+
+    /// Cancel the other orders of the one-cancels-other group of `order`,
+    /// which are active limit orders or untriggered stop orders.
+    fn cancel_order_group_siblings(&mut self, order: &Order<S>) {
+        for sibling_id in self.account.take_order_group_siblings(order) {
+            debug!("cancelling one-cancels-other sibling: {}", sibling_id);
+            // A stop order triggered by the same market update is neither, which `trigger_stop_orders` handles.
+            let Ok(mut sibling) = self.remove_resting_order(sibling_id) else {
+                continue;
+            };
+            self.stats.num_cancellations += 1;
+            self.close_order(&mut sibling, OrderStatus::Cancelled);
+            self.subscribers
+                .emit(self.market_state.current_timestamp_ns(), || {
+                    ExchangeEvent::Cancel(sibling)
+                });
+        }
+    }
+
+    /// Cancel all resting orders and close the positions at the best bid and ask as taker,
+    /// once the maintenance margin is violated.
+    /// If the price gapped through the liquidation price,
+    /// the loss exceeds the wallet balance, which is handled according to the `BankruptcyMode`.
+    fn liquidate(&mut self) {
+        debug!("liquidating positions");
+        self.cancel_all_resting_orders();
+        let fee_taker = self.fee_taker;
+        self.clearing_house.close_positions(
+            &mut self.account,
+            &mut self.account_tracker,
+            self.market_state.bid(),
+            self.market_state.ask(),
+            fee_taker,
+            LedgerEntryKind::LiquidationFee,
+            self.market_state.current_timestamp_ns(),
+        );
+        self.stats.num_liquidations += 1;
+        self.subscribers
+            .emit(self.market_state.current_timestamp_ns(), || {
+                ExchangeEvent::Liquidation {
+                    bid: self.market_state.bid(),
+                    ask: self.market_state.ask(),
+                }
+            });
+        self.settle_bankruptcy();
+    }
+
+    /// Handle a negative wallet balance according to the `BankruptcyMode`.
+    fn settle_bankruptcy(&mut self) {
+        self.insurance_fund_payout += self.clearing_house.settle_bankruptcy(
+            &mut self.account,
+            &mut self.account_tracker,
+            self.config.bankruptcy_mode(),
+            self.market_state.current_timestamp_ns(),
+        );
     }
 
+    /// The cumulative deficit of the account that was covered by the insurance fund of the exchange,
+    /// due to `BankruptcyMode::NegativeBalanceProtection`.
     #[inline(always)]
-    fn next_order_id(&mut self) -> u64 {
-        self.next_order_id += 1;
-        self.next_order_id - 1
+    pub fn insurance_fund_payout(&self) -> S::PairedCurrency {
+        self.insurance_fund_payout
     }
 
-    /// Cancel an active order based on the user_order_id of an Order
-    ///
-    /// # Arguments:
-    /// `user_order_id`: The `user_order_id` of the order to cancel.
-    ///
-    /// # Returns:
-    /// the cancelled order if successfull, error when the `user_order_id` is
-    /// not found
-    pub fn cancel_order_by_user_id(&mut self, user_order_id: u64) -> Result<Order<S>> {
-        self.account
-            .cancel_order_by_user_id(user_order_id, &mut self.account_tracker)
+    /// Exercise all options which expired against the current mid price of the underlying.
+    fn settle_expired_options(&mut self) {
+        let ts_ns = self.market_state.current_timestamp_ns();
+        if !self
+            .account
+            .option_positions
+            .iter()
+            .any(|p| p.contract.expiry_ts_ns <= ts_ns)
+        {
+            return;
+        }
+        let (expired, open) = core::mem::take(&mut self.account.option_positions)
+            .into_iter()
+            .partition::<Vec<_>, _>(|p| p.contract.expiry_ts_ns <= ts_ns);
+        self.account.option_positions = open;
+        for option_position in expired {
+            self.clearing_house.settle_option(
+                &mut self.account,
+                &mut self.account_tracker,
+                &option_position,
+                self.market_state.mid_price(),
+                ts_ns,
+            );
+        }
     }
 
-    /// Cancel an active order.
-    ///
-    /// # Arguments:
-    /// `order_id`: The `id` (assigned by the exchange) of the order to cancel.
-    ///
-    /// # Returns:
+    /// Cancel all resting orders whose time in force has expired.
+    fn expire_orders(&mut self) {
+        let ts_ns = self.market_state.current_timestamp_ns();
+        while let Some(order_id) = self.account.pop_expired(ts_ns) {
+            debug_assert!(self.account.active_limit_orders[&order_id]
+                .is_expired(ts_ns, self.config.session_calendar()));
+            let mut order = self
+                .account
+                .expire_order(order_id, &mut self.account_tracker);
+            self.close_order(&mut order, OrderStatus::Expired);
+            self.stats.num_expirations += 1;
+            self.subscribers
+                .emit(ts_ns, || ExchangeEvent::Cancel(order));
+        }
+    }
+
+    /// The stop orders which have not been triggered yet, in the order of their submission.
+    #[inline(always)]
+    pub fn stop_orders(&self) -> &[Order<S>] {
+        &self.stop_orders
+    }
+
+    /// Submit the stop orders whose `TriggerSource` reached their stop price during the `market_update`,
+    /// in the order of their submission.
+    /// Triggered orders which are rejected, e.g. by the risk checks, are counted in the `ExchangeStats`.
+    fn trigger_stop_orders(
+        &mut self,
+        market_update: &MarketUpdate<S>,
+        executed: &mut Vec<Order<S>>,
+    ) {
+        if self.stop_orders.is_empty() {
+            return;
+        }
+        let (triggered, untriggered) = core::mem::take(&mut self.stop_orders)
+            .into_iter()
+            .partition::<Vec<_>, _>(|order| self.is_stop_triggered(order, market_update));
+        self.stop_orders = untriggered;
+        // The groups whose other orders got cancelled by a triggered order.
+        let mut cancelled_groups = Vec::new();
+        for mut order in triggered {
+            if order
+                .group_id()
+                .is_some_and(|group_id| cancelled_groups.contains(&group_id))
+            {
+                self.stats.num_cancellations += 1;
+                self.close_order(&mut order, OrderStatus::Cancelled);
+                self.subscribers
+                    .emit(self.market_state.current_timestamp_ns(), || {
+                        ExchangeEvent::Cancel(order)
+                    });
+                continue;
+            }
+            // Triggering a stop order cancels its one-cancels-other siblings, just like a fill does.
+            if let Some(group_id) = order.group_id() {
+                self.cancel_order_group_siblings(&order);
+                cancelled_groups.push(group_id);
+            }
+            order.clear_stop_price();
+            if let Some(l_price) = order.limit_price() {
+                let crosses_spread = match order.side() {
+                    Side::Buy => l_price >= self.market_state.ask(),
+                    Side::Sell => l_price <= self.market_state.bid(),
+                };
+                if crosses_spread {
+                    order.set_time_in_force(TimeInForce::ImmediateOrCancel);
+                }
+            }
+            self.subscribers
+                .emit(self.market_state.current_timestamp_ns(), || {
+                    ExchangeEvent::StopTriggered(order.clone())
+                });
+            match self.process_submission(order) {
+                Ok(order) => {
+                    if order.remaining_quantity() == S::new_zero() {
+                        executed.push(order);
+                    }
+                }
+                Err(e) => debug!("triggered stop order rejected: {}", e),
+            }
+        }
+    }
+
+    /// Whether the `TriggerSource` of the stop `order` reached its stop price during the `market_update`.
+    fn is_stop_triggered(&self, order: &Order<S>, market_update: &MarketUpdate<S>) -> bool {
+        let stop_price = order.stop_price().expect("Only stop orders are held; qed");
+        let Some((low, high)) = self.trigger_price_range(order.trigger_source(), market_update)
+        else {
+            return false;
+        };
+        match order.side() {
+            Side::Buy => high >= stop_price,
+            Side::Sell => low <= stop_price,
+        }
+    }
+
+    /// The lowest and highest price of the `trigger_source` during the `market_update`,
+    /// or `None` if the `market_update` does not provide it.
+    fn trigger_price_range(
+        &self,
+        trigger_source: TriggerSource,
+        market_update: &MarketUpdate<S>,
+    ) -> Option<PriceRange<S::Num>> {
+        let price = match trigger_source {
+            TriggerSource::LastPrice => match market_update {
+                MarketUpdate::Trade { price, .. } => *price,
+                MarketUpdate::Candle { low, high, .. } => return Some((*low, *high)),
+                MarketUpdate::Bba { .. } | MarketUpdate::Depth { .. } => {
+                    self.market_state.mid_price()
+                }
+                MarketUpdate::Index { .. } => return None,
+            },
+            TriggerSource::MarkPrice => self.market_state.mark_price(),
+            TriggerSource::IndexPrice => self.market_state.index_price()?,
+        };
+        Some((price, price))
+    }
+
+    /// Attach a take-profit and a stop-loss price to the open position,
+    /// which close the whole position with a reduce-only market order once the last price reaches either of them.
+    /// They are removed once triggered or when the position is closed or flipped otherwise.
+    /// In `PositionMode::Hedge` only one of the positions may be open.
+    ///
+    /// # Arguments:
+    /// `take_profit`: The price at which to close the position in profit, if any.
+    /// `stop_loss`: The price at which to close the position at a loss, if any.
+    /// Without either price the current take-profit and stop-loss are removed.
+    ///
+    /// # Returns:
+    /// If Err, no single position is open, or a price is on the wrong side of the mark price.
+    pub fn set_position_tpsl(
+        &mut self,
+        take_profit: Option<QuoteCurrency<S::Num>>,
+        stop_loss: Option<QuoteCurrency<S::Num>>,
+    ) -> Result<()> {
+        self.subscribers.record(|| JournalEntry::SetPositionTpSl {
+            take_profit,
+            stop_loss,
+        });
+        if take_profit.is_none() && stop_loss.is_none() {
+            self.position_tpsl = None;
+            return Ok(());
+        }
+        let side = self
+            .single_position_side()
+            .ok_or(Error::InvalidPositionTpSl)?;
+        let position_tpsl = PositionTpSl::new(side, take_profit, stop_loss);
+        if !position_tpsl.is_valid(self.market_state.mark_price()) {
+            return Err(Error::InvalidPositionTpSl);
+        }
+        self.position_tpsl = Some(position_tpsl);
+        Ok(())
+    }
+
+    /// The side of the open position, or `None` if no position or both hedged positions are open.
+    fn single_position_side(&self) -> Option<Side> {
+        let is_long = self.protected_position_size(Side::Buy) > S::new_zero();
+        let is_short = self.protected_position_size(Side::Sell) < S::new_zero();
+        match (is_long, is_short) {
+            (true, false) => Some(Side::Buy),
+            (false, true) => Some(Side::Sell),
+            _ => None,
+        }
+    }
+
+    /// Whether the signed position `size` is still open on the `side`.
+    fn is_open_on(size: S, side: Side) -> bool {
+        match side {
+            Side::Buy => size > S::new_zero(),
+            Side::Sell => size < S::new_zero(),
+        }
+    }
+
+    /// The signed size of the position on the `side`, which is the net position in `PositionMode::Netting`.
+    fn protected_position_size(&self, side: Side) -> S {
+        match (self.account.position_mode(), side) {
+            (PositionMode::Hedge, Side::Sell) => self.account.short_position().size(),
+            _ => self.account.position().size(),
+        }
+    }
+
+    /// The take-profit and stop-loss attached to the open position, if any.
+    #[inline(always)]
+    pub fn position_tpsl(&self) -> Option<&PositionTpSl<S::Num>> {
+        self.position_tpsl.as_ref()
+    }
+
+    /// Close the whole position protected by the `PositionTpSl` if the last price reached it during the `market_update`.
+    fn trigger_position_tpsl(
+        &mut self,
+        market_update: &MarketUpdate<S>,
+        executed: &mut Vec<Order<S>>,
+    ) {
+        let Some(position_tpsl) = self.position_tpsl.as_ref() else {
+            return;
+        };
+        let side = position_tpsl.side();
+        let size = self.protected_position_size(side);
+        if !Self::is_open_on(size, side) {
+            // The position was closed or flipped in the meantime.
+            self.position_tpsl = None;
+            return;
+        }
+        let Some((low, high)) = self.trigger_price_range(TriggerSource::LastPrice, market_update)
+        else {
+            return;
+        };
+        if !position_tpsl.is_triggered(low, high) {
+            return;
+        }
+        self.position_tpsl = None;
+        self.close_protected_position(side, size, executed);
+    }
+
+    /// Close the position of the signed `size` on the `side` with a reduce-only market order.
+    fn close_protected_position(&mut self, side: Side, size: S, executed: &mut Vec<Order<S>>) {
+        let mut order =
+            Order::market(side.inverted(), size.abs()).expect("The position is open; qed");
+        order.set_reduce_only(true);
+        order.set_id(self.next_order_id());
+        order.set_created_ts_ns(self.market_state.current_timestamp_ns());
+        match self.process_submission(order) {
+            Ok(order) => executed.push(order),
+            Err(e) => debug!("closing the protected position failed: {}", e),
+        }
+    }
+
+    /// Attach a trailing take-profit to the open position, which activates once the last price
+    /// moved `activation_profit` beyond the current entry price in favour of the position.
+    /// From then on it trails the best price by the `offset` and closes the whole position
+    /// with a reduce-only market order once the last price retraces to it.
+    /// It replaces any previous trailing take-profit and is removed once triggered
+    /// or when the position is closed or flipped otherwise.
+    /// In `PositionMode::Hedge` only one of the positions may be open.
+    ///
+    /// # Returns:
+    /// If Err, no single position is open, the `activation_profit` is negative or the `offset` is not positive.
+    pub fn set_trailing_take_profit(
+        &mut self,
+        activation_profit: QuoteCurrency<S::Num>,
+        offset: QuoteCurrency<S::Num>,
+    ) -> Result<()> {
+        self.subscribers
+            .record(|| JournalEntry::SetTrailingTakeProfit {
+                activation_profit,
+                offset,
+            });
+        if activation_profit < QuoteCurrency::new_zero() || offset <= QuoteCurrency::new_zero() {
+            return Err(Error::InvalidTrailingTakeProfit);
+        }
+        let side = self
+            .single_position_side()
+            .ok_or(Error::InvalidTrailingTakeProfit)?;
+        let entry_price = match (self.account.position_mode(), side) {
+            (PositionMode::Hedge, Side::Sell) => self.account.short_position().entry_price(),
+            _ => self.account.position().entry_price(),
+        };
+        let activation_price = match side {
+            Side::Buy => entry_price + activation_profit,
+            Side::Sell => entry_price - activation_profit,
+        };
+        self.trailing_take_profit = Some(TrailingTakeProfit::new(side, activation_price, offset));
+        Ok(())
+    }
+
+    /// Remove the trailing take-profit of the position.
+    pub fn cancel_trailing_take_profit(&mut self) {
+        self.subscribers
+            .record(|| JournalEntry::CancelTrailingTakeProfit);
+        self.trailing_take_profit = None;
+    }
+
+    /// The trailing take-profit attached to the open position, if any.
+    #[inline(always)]
+    pub fn trailing_take_profit(&self) -> Option<&TrailingTakeProfit<S::Num>> {
+        self.trailing_take_profit.as_ref()
+    }
+
+    /// Follow the last price of the `market_update` with the `TrailingTakeProfit`
+    /// and close the whole position once it retraced by the offset.
+    fn trail_take_profit(&mut self, market_update: &MarketUpdate<S>, executed: &mut Vec<Order<S>>) {
+        let Some(side) = self
+            .trailing_take_profit
+            .as_ref()
+            .map(|trailing| trailing.side())
+        else {
+            return;
+        };
+        let size = self.protected_position_size(side);
+        if !Self::is_open_on(size, side) {
+            // The position was closed or flipped in the meantime.
+            self.trailing_take_profit = None;
+            return;
+        }
+        let Some((low, high)) = self.trigger_price_range(TriggerSource::LastPrice, market_update)
+        else {
+            return;
+        };
+        let trailing = self
+            .trailing_take_profit
+            .as_mut()
+            .expect("Checked above; qed");
+        if !trailing.update(low, high) {
+            return;
+        }
+        self.trailing_take_profit = None;
+        self.close_protected_position(side, size, executed);
+    }
+
+    /// Check if any resting orders have been executed and write them into `orders`.
+    /// Only the price levels of the `OrderBook` crossed by the `market_update` are visited.
+    /// The orders are in price-time priority, so the best priced orders at the front of their queue get executed first.
+    /// For candles, the orders reached first along the assumed `CandlePath` get executed first.
+    fn check_resting_orders(
+        &mut self,
+        market_update: &MarketUpdate<S>,
+        prev_mid_price: Option<QuoteCurrency<S::Num>>,
+        orders: &mut Vec<Order<S>>,
+    ) {
+        orders.clear();
+        let book = &self.account.order_book;
+        let crossed: Box<dyn Iterator<Item = u64>> = match *market_update {
+            // Updates to the best bid and ask prices do not trigger limit orders for simulation purposes.
+            MarketUpdate::Bba { .. } | MarketUpdate::Index { .. } | MarketUpdate::Depth { .. } => {
+                return
+            }
+            MarketUpdate::Trade {
+                price,
+                side: Side::Sell,
+                ..
+            } => Box::new(book.bids_from(price, true)),
+            MarketUpdate::Trade {
+                price,
+                side: Side::Buy,
+                ..
+            } => Box::new(book.asks_to(price, true)),
+            MarketUpdate::Candle { low, high, .. } => {
+                Box::new(book.bids_from(low, false).chain(book.asks_to(high, false)))
+            }
+        };
+        orders.extend(crossed.map(|id| {
+            let order = &self.account.active_limit_orders[&id];
+            debug_assert!(self.check_limit_order_execution(order, market_update));
+            order.clone()
+        }));
+        match market_update {
+            MarketUpdate::Candle { low, high, .. } if orders.len() > 1 => {
+                let close = self.market_state.mid_price();
+                let net_size = self.account.position.size() + self.account.short_position.size();
+                let position_side = if net_size > S::new_zero() {
+                    Some(Side::Buy)
+                } else if net_size < S::new_zero() {
+                    Some(Side::Sell)
+                } else {
+                    None
+                };
+                let waypoints = self.candle_path.waypoints(
+                    prev_mid_price.unwrap_or(close),
+                    *low,
+                    *high,
+                    close,
+                    position_side,
+                );
+                orders.sort_by_cached_key(|order| {
+                    let distance = crossing_distance(
+                        &waypoints,
+                        order.side(),
+                        order.limit_price().expect(EXPECT_LIMIT_PRICE),
+                    );
+                    (distance.is_none(), distance, order.timestamp(), order.id())
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Check an individual resting order if it has been executed.
+    ///
+    /// # Returns:
+    /// If `Some`, The order is filled and needs to be settled.
+    fn check_limit_order_execution(
+        &self,
+        limit_order: &Order<S>,
+        market_update: &MarketUpdate<S>,
+    ) -> bool {
+        let limit_price = limit_order.limit_price().expect(EXPECT_LIMIT_PRICE);
+
+        match market_update {
+            MarketUpdate::Bba { .. } | MarketUpdate::Index { .. } | MarketUpdate::Depth { .. } => {
+                // Updates to the best bid and ask prices do not trigger limit orders for simulation purposes.
+                false
+            }
+            MarketUpdate::Trade {
+                price,
+                quantity: _,
+                side,
+            } => {
+                // For now we ignore the filled quantity, which will change in future versions.
+                match limit_order.side() {
+                    Side::Buy => *price <= limit_price && matches!(side, Side::Sell),
+                    Side::Sell => *price >= limit_price && matches!(side, Side::Buy),
+                }
+            }
+            MarketUpdate::Candle {
+                bid: _,
+                ask: _,
+                low,
+                high,
+            } => match limit_order.side() {
+                Side::Buy => *low < limit_price,
+                Side::Sell => *high > limit_price,
+            },
+        }
+    }
+
+    /// Submit a new order to the exchange.
+    /// With a `LatencyModel` configured, the order only reaches the exchange
+    /// during the first `update_state` at or after its arrival and is processed there.
+    /// Orders rejected upon arrival are counted in the `ExchangeStats`.
+    /// With a `FaultInjection` configured, the submission may be delayed the same way
+    /// or fail with `Error::ExchangeOverloaded` or `Error::RequestTimeout` without any effect.
+    ///
+    /// # Arguments:
+    /// `order`: The order that is being submitted.
+    ///
+    /// # Returns:
+    /// If Ok, the order with timestamp and id filled in.
+    /// Else its an error.
+    pub fn submit_order(&mut self, order: Order<S>) -> Result<Order<S>> {
+        self.subscribers
+            .record(|| JournalEntry::SubmitOrder(order.clone()));
+        self.fills.clear();
+        self.acquire_rate_limit()?;
+        let delay_ns = self.inject_fault()?;
+        self.send_submission(order, delay_ns)
+    }
+
+    /// Take a token of the `RateLimit` for an order submission or cancellation.
+    fn acquire_rate_limit(&mut self) -> Result<()> {
+        self.rate_limiter
+            .acquire(self.market_state.current_timestamp_ns())
+            .inspect_err(|_| self.stats.num_rate_limited += 1)
+    }
+
+    /// Draw the `FaultInjection` of an order submission or cancellation.
+    ///
+    /// # Returns:
+    /// The additional delay in nanoseconds or an Error if the request is rejected.
+    fn inject_fault(&mut self) -> Result<i64> {
+        let fault = self.fault_injector.sample();
+        if !matches!(fault, Ok(0)) {
+            self.stats.num_injected_faults += 1;
+        }
+        fault
+    }
+
+    /// Send an order submission, which takes effect immediately
+    /// unless there is a `LatencyModel` or an injected `delay_ns`.
+    fn send_submission(&mut self, mut order: Order<S>, delay_ns: i64) -> Result<Order<S>> {
+        order.set_id(self.next_order_id());
+        order.set_created_ts_ns(self.market_state.current_timestamp_ns());
+        if self.latency.is_enabled() || delay_ns > 0 {
+            order.set_timestamp(self.market_state.current_timestamp_ns());
+            self.send_request(Request::Submission(Box::new(order.clone())), delay_ns);
+            return Ok(order);
+        }
+        self.process_submission(order)
+    }
+
+    /// Subscribe to all `ExchangeEvent`s.
+    /// The callback receives the timestamp in nanoseconds of the event and the event itself.
+    /// Events are only created while there is at least one subscriber.
+    pub fn subscribe<F>(&mut self, subscriber: F)
+    where
+        F: FnMut(i64, &ExchangeEvent<S>) + Send + 'static,
+    {
+        self.subscribers.push(subscriber);
+    }
+
+    /// Start recording all inputs and events into a `Journal`, discarding any current recording.
+    /// Changes made through `account_mut` are not recorded.
+    pub fn start_recording(&mut self) {
+        self.subscribers.start_journal();
+    }
+
+    /// Stop recording.
+    ///
+    /// # Returns:
+    /// The recorded `Journal`, if recording was started.
+    pub fn stop_recording(&mut self) -> Option<Journal<S>> {
+        self.subscribers.take_journal()
+    }
+
+    /// Apply all inputs of the `journal` again and verify that the same events are emitted.
+    /// The exchange must be in the state in which recording started,
+    /// e.g. newly created with the same `Config` or resumed from a checkpoint.
+    /// Errors returned by the individual inputs are part of the recording and are not propagated.
+    /// Any current recording is discarded.
+    ///
+    /// # Returns:
+    /// An Error if the replay diverged from the `journal`.
+    pub fn replay(&mut self, journal: &Journal<S>) -> Result<()> {
+        self.start_recording();
+        for entry in journal.entries() {
+            // The errors are recorded as events, which get compared below.
+            let _ = match entry.clone() {
+                JournalEntry::MarketUpdate {
+                    timestamp_ns,
+                    update,
+                } => self.update_state(timestamp_ns, update).map(|_| ()),
+                JournalEntry::SubmitOrder(order) => self.submit_order(order).map(|_| ()),
+                JournalEntry::SubmitOcoOrders(order_a, order_b) => {
+                    self.submit_oco_orders(order_a, order_b).map(|_| ())
+                }
+                JournalEntry::SubmitBasketOrders(orders) => {
+                    self.submit_basket_orders(orders).map(|_| ())
+                }
+                JournalEntry::CancelOrder(order_id) => self.cancel_order(order_id).map(|_| ()),
+                JournalEntry::SetLeverage(leverage) => self.set_leverage(leverage),
+                JournalEntry::SetPositionTpSl {
+                    take_profit,
+                    stop_loss,
+                } => self.set_position_tpsl(take_profit, stop_loss),
+                JournalEntry::SetTrailingTakeProfit {
+                    activation_profit,
+                    offset,
+                } => self.set_trailing_take_profit(activation_profit, offset),
+                JournalEntry::CancelTrailingTakeProfit => {
+                    self.cancel_trailing_take_profit();
+                    Ok(())
+                }
+                JournalEntry::Deposit(amount) => self.deposit(amount),
+                JournalEntry::Withdraw(amount) => self.withdraw(amount),
+                JournalEntry::DepositPositionMargin { side, amount } => {
+                    self.deposit_position_margin(side, amount)
+                }
+                JournalEntry::WithdrawPositionMargin { side, amount } => {
+                    self.withdraw_position_margin(side, amount)
+                }
+                JournalEntry::DepositCollateral { ticker, quantity } => {
+                    self.deposit_collateral(&ticker, quantity)
+                }
+                JournalEntry::WithdrawCollateral { ticker, quantity } => {
+                    self.withdraw_collateral(&ticker, quantity)
+                }
+                JournalEntry::CollateralPrice { ticker, price } => {
+                    self.update_collateral_price(&ticker, price)
+                }
+                JournalEntry::DepositFeeToken(quantity) => self.deposit_fee_token(quantity),
+                JournalEntry::WithdrawFeeToken(quantity) => self.withdraw_fee_token(quantity),
+                JournalEntry::FeeTokenPrice(price) => self.update_fee_token_price(price),
+                JournalEntry::CancelOrderByUserId(user_order_id) => {
+                    self.cancel_order_by_user_id(user_order_id).map(|_| ())
+                }
+                JournalEntry::TradeOption {
+                    contract,
+                    side,
+                    quantity,
+                    premium,
+                } => self
+                    .trade_option(contract, side, quantity, premium)
+                    .map(|_| ()),
+                JournalEntry::Event { .. } => Ok(()),
+            };
+        }
+        let replayed = self.stop_recording().expect("Recording was started; qed");
+        if replayed != *journal {
+            return Err(Error::ReplayDiverged);
+        }
+        Ok(())
+    }
+
+    /// Process an order submission which reached the exchange.
+    fn process_submission(&mut self, order: Order<S>) -> Result<Order<S>> {
+        let ts_ns = self.market_state.current_timestamp_ns();
+        let order_id = order.id();
+        let submitted = self.subscribers_active().then(|| order.clone());
+        let order = self.try_submit_order(order).inspect_err(|error| {
+            self.stats.num_rejections += 1;
+            self.remember_closed_order(order_id, OrderStatus::Rejected);
+            if let Some(mut order) = submitted {
+                order.set_status(OrderStatus::Rejected, ts_ns);
+                self.subscribers
+                    .emit(ts_ns, || ExchangeEvent::OrderRejected {
+                        order,
+                        error: error.clone(),
+                    });
+            }
+        })?;
+        self.handle_dust_positions();
+        Ok(order)
+    }
+
+    /// Whether the absolute `quantity` is dust at the `price`,
+    /// i.e. below the minimum quantity or notional value of the `ContractSpecification`.
+    fn is_dust(&self, quantity: S, price: QuoteCurrency<S::Num>) -> bool {
+        let spec = self.config.contract_specification();
+        let min_quantity = spec.quantity_filter.min_quantity;
+        let min_notional = spec.notional_filter.min_notional;
+        quantity > S::new_zero()
+            && ((min_quantity != S::new_zero() && quantity < min_quantity)
+                || (min_notional != S::PairedCurrency::new_zero()
+                    && quantity.convert(price) < min_notional))
+    }
+
+    /// Whether the remainder of a partially filled order is dust, which is cancelled unless `DustHandling::Keep`.
+    fn is_dust_order(&self, order: &Order<S>) -> bool {
+        !matches!(self.config.dust_handling(), DustHandling::Keep)
+            && self.is_dust(
+                order.remaining_quantity(),
+                order.limit_price().expect(EXPECT_LIMIT_PRICE),
+            )
+    }
+
+    /// Close or write off the positions which are dust at the mid price, according to the `DustHandling`.
+    fn handle_dust_positions(&mut self) {
+        let dust_handling = self.config.dust_handling();
+        if matches!(dust_handling, DustHandling::Keep) {
+            return;
+        }
+        let mid_price = self.market_state.mid_price();
+        for (is_short_position, size) in [
+            (false, self.account.position.size()),
+            (true, self.account.short_position.size()),
+        ] {
+            if !self.is_dust(size.abs(), mid_price) {
+                continue;
+            }
+            let price = match dust_handling {
+                DustHandling::Keep => unreachable!("Handled above"),
+                DustHandling::AutoClose if size > S::new_zero() => self.market_state.bid(),
+                DustHandling::AutoClose => self.market_state.ask(),
+                DustHandling::Track => {
+                    self.write_off_dust(is_short_position, size);
+                    continue;
+                }
+            };
+            debug!("closing dust position of {} at {}", size, price);
+            self.clearing_house.settle_filled_order(
+                &mut self.account,
+                &mut self.account_tracker,
+                size.into_negative(),
+                price,
+                self.fee_taker,
+                LedgerEntryKind::TakerFee,
+                true,
+                self.market_state.current_timestamp_ns(),
+            );
+        }
+    }
+
+    /// Take the dust `size` out of its position at the entry price and accumulate it in `Exchange::dust`.
+    /// No trade is recorded, so neither profit and loss nor fees are realized for the residual.
+    fn write_off_dust(&mut self, is_short_position: bool, size: S) {
+        debug!("writing off dust position of {}", size);
+        let before = self.account.snapshot_positions();
+        let position = if is_short_position {
+            &mut self.account.short_position
+        } else {
+            &mut self.account.position
+        };
+        let entry_price = position.entry_price();
+        let rpnl = if size > S::new_zero() {
+            position.decrease_long(size, entry_price)
+        } else {
+            position.decrease_short(size.abs(), entry_price)
+        };
+        debug_assert!(rpnl.is_zero(), "Written off at the entry price; qed");
+        self.account.record_position_changes(
+            before,
+            entry_price,
+            false,
+            self.market_state.current_timestamp_ns(),
+        );
+        self.dust += size.abs();
+    }
+
+    /// Assign the next trade id to the execution of `quantity` of the `order`
+    /// and record it in the `fills` of the current call and the trade history.
+    fn record_fill(
+        &mut self,
+        order: &Order<S>,
+        quantity: S,
+        price: QuoteCurrency<S::Num>,
+        fee: S::PairedCurrency,
+        is_maker: bool,
+    ) {
+        let fill = Fill {
+            trade_id: self.next_trade_id,
+            order_id: order.id(),
+            side: order.side(),
+            price,
+            quantity,
+            fee,
+            is_maker,
+            timestamp_ns: self.market_state.current_timestamp_ns(),
+        };
+        self.next_trade_id += 1;
+        let capacity = self.config.trade_history_capacity();
+        if capacity > 0 {
+            if self.trade_history.len() == capacity {
+                self.trade_history.pop_front();
+            }
+            self.trade_history.push_back(fill.clone());
+        }
+        self.fills.push(fill);
+    }
+
+    /// The fills of the latest call to `update_state`, `update_state_batch`, `update_state_into`,
+    /// `submit_order` or `submit_oco_orders`, in the order of their execution.
+    /// Orders delayed by the `LatencyModel` are filled during the `update_state` in which they arrive.
+    #[inline(always)]
+    pub fn fills(&self) -> &[Fill<S>] {
+        &self.fills
+    }
+
+    /// The latest fills up to `Config::trade_history_capacity`, oldest first.
+    #[inline(always)]
+    pub fn trade_history(&self) -> &VecDeque<Fill<S>> {
+        &self.trade_history
+    }
+
+    /// The cumulative quantity of dust positions written off at their entry price due to `DustHandling::Track`,
+    /// which is no longer part of the position.
+    #[inline(always)]
+    pub fn dust(&self) -> S {
+        self.dust
+    }
+
+    /// Whether any callbacks are subscribed to the `ExchangeEvent`s.
+    #[inline(always)]
+    fn subscribers_active(&self) -> bool {
+        !self.subscribers.is_empty()
+    }
+
+    /// Queue a request until it reaches the exchange after the simulated latency and the injected `delay_ns`.
+    fn send_request(&mut self, request: Request<S>, delay_ns: i64) {
+        let arrival_ts_ns =
+            self.market_state.current_timestamp_ns() + self.latency.sample() + delay_ns;
+        let idx = self
+            .in_flight
+            .partition_point(|(ts_ns, _)| *ts_ns <= arrival_ts_ns);
+        self.in_flight.insert(idx, (arrival_ts_ns, request));
+    }
+
+    /// Process all requests which have reached the exchange by now, in the order of their arrival.
+    ///
+    /// The orders which got executed immediately upon arrival are appended to `executed`.
+    fn process_arrived_requests(&mut self, executed: &mut Vec<Order<S>>) {
+        let ts_ns = self.market_state.current_timestamp_ns();
+        let n = self
+            .in_flight
+            .partition_point(|(arrival_ts_ns, _)| *arrival_ts_ns <= ts_ns);
+        let arrived = Vec::from_iter(self.in_flight.drain(..n).map(|(_, request)| request));
+        for request in arrived {
+            match request {
+                Request::Submission(order) => match self.process_submission(*order) {
+                    Ok(order) => {
+                        if order.remaining_quantity() == S::new_zero() {
+                            executed.push(order);
+                        }
+                    }
+                    Err(e) => debug!("order rejected upon arrival: {}", e),
+                },
+                Request::Cancellation(order_id) => {
+                    if let Err(e) = self
+                        .check_available()
+                        .and_then(|_| self.try_cancel_order(order_id))
+                    {
+                        // The order got filled or cancelled while the request was in flight.
+                        debug!(
+                            "cancellation of order {} failed upon arrival: {}",
+                            order_id, e
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return the number of order submissions and cancellations that have not reached the exchange yet.
+    #[inline(always)]
+    pub fn num_in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    fn try_submit_order(&mut self, mut order: Order<S>) -> Result<Order<S>> {
+        trace!("submit_order: {:?}", order);
+
+        if self.is_expired() {
+            return Err(Error::ContractExpired);
+        }
+        self.check_available()?;
+        if !self
+            .config
+            .session_calendar()
+            .is_open(self.market_state.current_timestamp_ns())
+        {
+            return Err(Error::OrderError(OrderError::MarketClosed));
+        }
+        if self.is_halted() {
+            return Err(Error::OrderError(OrderError::TradingHalted));
+        }
+        if let Some(user_order_id) = order.user_order_id() {
+            if self
+                .account
+                .lookup_order_nonce_from_user_order_id
+                .contains_key(user_order_id)
+                || self
+                    .stop_orders
+                    .iter()
+                    .any(|stop_order| stop_order.user_order_id().as_ref() == Some(user_order_id))
+            {
+                return Err(Error::OrderError(OrderError::DuplicateUserOrderId));
+            }
+        }
+
+        // Basic checks
+        self.config
+            .contract_specification()
+            .quantity_filter
+            .validate_order(&order)?;
+        if order.stop_price().is_some() {
+            // The price checks apply once the stop order is triggered.
+            order.set_timestamp(self.market_state.current_timestamp_ns());
+            self.account.join_order_group(&order);
+            self.stop_orders.push(order.clone());
+            self.subscribers
+                .emit(self.market_state.current_timestamp_ns(), || {
+                    ExchangeEvent::OrderAccepted(order.clone())
+                });
+            return Ok(order);
+        }
+        self.config
+            .contract_specification()
+            .price_filter
+            .validate_order(&order, self.market_state.mid_price())?;
+        self.config
+            .contract_specification()
+            .notional_filter
+            .validate_order(&order, self.market_state.mid_price())?;
+
+        order.set_timestamp(self.market_state.current_timestamp_ns());
+
+        match order.order_type() {
+            OrderType::Market => {
+                let best_price = match order.side() {
+                    Side::Buy => self.market_state.ask(),
+                    Side::Sell => self.market_state.bid(),
+                };
+                let fill_price = self.config.slippage_model().market_fill_price(
+                    order.side(),
+                    order.quantity().inner(),
+                    &self.market_state,
+                );
+                self.execute_taker_order(&mut order, fill_price)?;
+                self.account_tracker.log_market_order_fill();
+                self.emit_taker_fill(&order, fill_price);
+                if fill_price != best_price {
+                    let slippage = (order.quantity().convert(fill_price)
+                        - order.quantity().convert(best_price))
+                    .abs();
+                    self.account_tracker.log_slippage(slippage);
+                }
+            }
+            OrderType::Limit => {
+                let l_price = order.limit_price().expect(EXPECT_LIMIT_PRICE);
+                let crosses_spread = match order.side() {
+                    Side::Buy => l_price >= self.market_state.ask(),
+                    Side::Sell => l_price <= self.market_state.bid(),
+                };
+                match order.time_in_force() {
+                    TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill => {
+                        if !crosses_spread {
+                            return Err(Error::OrderError(OrderError::NotImmediatelyExecutable));
+                        }
+                        if let Some(quoted) = self.quoted_quantity(order.side(), l_price) {
+                            if quoted < order.remaining_quantity() {
+                                if matches!(order.time_in_force(), TimeInForce::FillOrKill) {
+                                    return Err(Error::OrderError(
+                                        OrderError::NotImmediatelyExecutable,
+                                    ));
+                                }
+                                // The part of the order exceeding the quoted quantity is cancelled.
+                                order.decrement_quantity(order.remaining_quantity() - quoted);
+                            }
+                        }
+                        let fill_price = match (self.config.price_improvement(), order.side()) {
+                            (PriceImprovement::BestQuote, Side::Buy) => self.market_state.ask(),
+                            (PriceImprovement::BestQuote, Side::Sell) => self.market_state.bid(),
+                            (PriceImprovement::Off, _) => l_price,
+                        };
+                        self.execute_taker_order(&mut order, fill_price)?;
+                        self.account_tracker.log_limit_order_submission();
+                        self.account_tracker.log_limit_order_fill();
+                        self.emit_taker_fill(&order, fill_price);
+                    }
+                    TimeInForce::GoodTilCancel | TimeInForce::GoodTilDate(_) | TimeInForce::Day => {
+                        if order.is_expired(
+                            self.market_state.current_timestamp_ns(),
+                            self.config.session_calendar(),
+                        ) {
+                            return Err(Error::OrderError(OrderError::GoodTilDateInPast));
+                        }
+                        if crosses_spread {
+                            return Err(Error::OrderError(match order.side() {
+                                Side::Buy => OrderError::LimitPriceAboveAsk,
+                                Side::Sell => OrderError::LimitPriceBelowBid,
+                            }));
+                        }
+                        self.config
+                            .contract_specification()
+                            .max_num_orders_filter
+                            .validate_order(&order, self.account.active_limit_orders())?;
+                        self.check_limit_order(&order)
+                            .inspect_err(|e| self.account_tracker.log_risk_violation(e))?;
+                        self.account.append_limit_order(order.clone());
+                        if let Some(expires_at) = order.expires_at(self.config.session_calendar()) {
+                            self.account.index_expiry(order.id(), expires_at);
+                        }
+                        self.account_tracker.log_limit_order_submission();
+                        self.subscribers
+                            .emit(self.market_state.current_timestamp_ns(), || {
+                                ExchangeEvent::OrderAccepted(order.clone())
+                            });
+                    }
+                }
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Determine the `SelfTradePrevention` of an aggressive `order` executing at `fill_price`,
+    /// if it crosses resting orders of the account on the opposite side.
+    /// Nothing is changed yet, so the `RiskEngine` can approve the order before any resting order is touched.
+    fn plan_self_trade(
+        &self,
+        order: &Order<S>,
+        fill_price: QuoteCurrency<S::Num>,
+    ) -> Result<SelfTrade<S>> {
+        let mut self_trade = SelfTrade {
+            aggressor_decrement: S::new_zero(),
+            cancel: Vec::new(),
+            decrement: None,
+        };
+        let mode = self.config.self_trade_prevention();
+        if matches!(mode, SelfTradePrevention::Off) {
+            return Ok(self_trade);
+        }
+        // The aggressive order would meet the best priced resting orders first.
+        let book = &self.account.order_book;
+        let crossed = match order.side() {
+            Side::Buy => Vec::from_iter(book.asks_to(fill_price, true)),
+            Side::Sell => Vec::from_iter(book.bids_from(fill_price, true)),
+        };
+        if crossed.is_empty() {
+            return Ok(self_trade);
+        }
+
+        match mode {
+            SelfTradePrevention::Off => unreachable!("Handled above"),
+            SelfTradePrevention::CancelAggressor => {
+                return Err(Error::OrderError(OrderError::SelfTradePrevented));
+            }
+            SelfTradePrevention::CancelResting => self_trade.cancel = crossed,
+            SelfTradePrevention::DecrementBoth => {
+                // Cancelling an order also cancels its one-cancels-other siblings.
+                let mut cancelled_groups = Vec::new();
+                for order_id in crossed {
+                    let remaining = order.remaining_quantity() - self_trade.aggressor_decrement;
+                    if remaining == S::new_zero() {
+                        break;
+                    }
+                    let Some(resting) = self.account.active_limit_orders.get(&order_id) else {
+                        continue;
+                    };
+                    if resting
+                        .group_id()
+                        .is_some_and(|group_id| cancelled_groups.contains(&group_id))
+                    {
+                        continue;
+                    }
+                    let mut decremented = resting.clone();
+                    decremented.decrement_quantity(resting.remaining_quantity().min(remaining));
+                    self_trade.aggressor_decrement += resting.remaining_quantity().min(remaining);
+                    if decremented.remaining_quantity() == S::new_zero()
+                        || self.is_dust_order(&decremented)
+                    {
+                        cancelled_groups.extend(resting.group_id());
+                        self_trade.cancel.push(order_id);
+                    } else {
+                        self_trade.decrement = Some((order_id, remaining));
+                    }
+                }
+            }
+        }
+        Ok(self_trade)
+    }
+
+    /// Apply the `SelfTrade` to the aggressive `order` and the crossed resting orders.
+    ///
+    /// # Returns:
+    /// `OrderError::SelfTradePrevented` if nothing of the aggressive order remains.
+    fn apply_self_trade(&mut self, order: &mut Order<S>, self_trade: SelfTrade<S>) -> Result<()> {
+        order.decrement_quantity(self_trade.aggressor_decrement);
+        for order_id in self_trade.cancel {
+            // A sibling of a cancelled one-cancels-other group may be gone already.
+            if self.account.active_limit_orders.contains_key(&order_id) {
+                self.try_cancel_order(order_id)?;
+            }
+        }
+        if let Some((order_id, quantity)) = self_trade.decrement {
+            if let Some(mut resting) = self.account.active_limit_orders.get(&order_id).cloned() {
+                resting.decrement_quantity(quantity);
+                self.account.update_partially_filled_order(resting);
+            }
+        }
+        if order.remaining_quantity() == S::new_zero() {
+            return Err(Error::OrderError(OrderError::SelfTradePrevented));
+        }
+        Ok(())
+    }
+
+    /// Emit the acceptance and the fill of an order executed immediately as taker.
+    fn emit_taker_fill(&self, order: &Order<S>, fill_price: QuoteCurrency<S::Num>) {
+        let ts_ns = self.market_state.current_timestamp_ns();
+        self.subscribers
+            .emit(ts_ns, || ExchangeEvent::OrderAccepted(order.clone()));
+        self.subscribers.emit(ts_ns, || ExchangeEvent::Fill {
+            order: order.clone(),
+            quantity: order.quantity(),
+            price: fill_price,
+            is_maker: false,
+        });
+    }
+
+    /// Check a market order with the built-in and then the custom risk engines.
+    fn check_market_order(
+        &mut self,
+        order: &Order<S>,
+        fill_price: QuoteCurrency<S::Num>,
+    ) -> core::result::Result<(), RiskError> {
+        self.risk_engine.check_market_order(
+            &self.market_state,
+            &self.account,
+            order,
+            fill_price,
+        )?;
+        for risk_engine in self.custom_risk_engines.iter_mut() {
+            risk_engine.check_market_order(&self.market_state, &self.account, order, fill_price)?;
+        }
+        Ok(())
+    }
+
+    /// Check a limit order with the built-in and then the custom risk engines.
+    fn check_limit_order(&mut self, order: &Order<S>) -> core::result::Result<(), RiskError> {
+        self.risk_engine
+            .check_limit_order(&self.market_state, &self.account, order)?;
+        for risk_engine in self.custom_risk_engines.iter_mut() {
+            risk_engine.check_limit_order(&self.market_state, &self.account, order)?;
+        }
+        Ok(())
+    }
+
+    /// Check the maintenance margin with the built-in and then the custom risk engines.
+    fn check_maintenance_margin(&self) -> core::result::Result<(), RiskError> {
+        self.risk_engine
+            .check_maintenance_margin(&self.market_state, &self.account)?;
+        for risk_engine in self.custom_risk_engines.iter() {
+            risk_engine.check_maintenance_margin(&self.market_state, &self.account)?;
+        }
+        Ok(())
+    }
+
+    /// The quantity quoted in the latest depth on the opposite side of an order at prices up to its `limit_price`.
+    /// `None` if the depth is not known, in which case the best bid and ask are assumed to have unbounded size.
+    fn quoted_quantity(&self, side: Side, limit_price: QuoteCurrency<S::Num>) -> Option<S> {
+        let levels = match side {
+            Side::Buy => self.market_state.ask_depth(),
+            Side::Sell => self.market_state.bid_depth(),
+        };
+        if levels.is_empty() {
+            return None;
+        }
+        let quoted = levels
+            .iter()
+            .filter(|(price, _)| match side {
+                Side::Buy => *price <= limit_price,
+                Side::Sell => *price >= limit_price,
+            })
+            .fold(S::new_zero(), |quoted, (_, quantity)| {
+                quoted + S::new(*quantity)
+            });
+        Some(quoted)
+    }
+
+    /// Execute an order immediately as taker at the `fill_price`,
+    /// if the `RiskEngine` approves it.
+    /// The `SelfTradePrevention` only changes the resting orders once the order is approved.
+    fn execute_taker_order(
+        &mut self,
+        order: &mut Order<S>,
+        fill_price: QuoteCurrency<S::Num>,
+    ) -> Result<()> {
+        let self_trade = self.plan_self_trade(order, fill_price)?;
+        if self_trade.aggressor_decrement < order.remaining_quantity() {
+            let mut approved = order.clone();
+            approved.decrement_quantity(self_trade.aggressor_decrement);
+            self.check_market_order(&approved, fill_price)
+                .inspect_err(|e| self.account_tracker.log_risk_violation(e))?;
+        }
+        self.apply_self_trade(order, self_trade)?;
+        let quantity = match order.side() {
+            Side::Buy => order.quantity(),
+            Side::Sell => order.quantity().into_negative(),
+        };
+        // From here on, everything is infallible
+        let fee_taker = self.fee_taker;
+        let fee = self.clearing_house.settle_filled_order(
+            &mut self.account,
+            &mut self.account_tracker,
+            quantity,
+            fill_price,
+            fee_taker,
+            LedgerEntryKind::TakerFee,
+            order.reduce_only(),
+            self.market_state.current_timestamp_ns(),
+        );
+        self.log_fill_volume(quantity.convert(fill_price).inner(), false);
+        self.stats.num_fills += 1;
+        self.record_fill(order, order.quantity(), fill_price, fee, false);
+        order.mark_filled(fill_price);
+        self.close_order(order, OrderStatus::Filled);
+
+        Ok(())
+    }
+
+    /// Buy or sell a European option on the underlying futures contract.
+    /// The premium is transferred immediately and the option is exercised
+    /// against the mid price of the underlying during the first `update_state` at or after its expiry.
+    /// Selling an option reserves margin of its intrinsic value plus the initial margin
+    /// fraction of the `ContractSpecification` times the notional value of the underlying.
+    ///
+    /// # Arguments:
+    /// `contract`: The option to trade.
+    /// `side`: `Buy` to pay the premium, `Sell` to receive it.
+    /// `quantity`: The number of contracts of the underlying.
+    /// `premium`: The total premium of the trade, denoted in the margin currency.
+    ///
+    /// # Returns:
+    /// If Ok, the new `OptionPosition`, else an Error.
+    pub fn trade_option(
+        &mut self,
+        contract: OptionContract<S::Num>,
+        side: Side,
+        quantity: S,
+        premium: S::PairedCurrency,
+    ) -> Result<OptionPosition<S::PairedCurrency>> {
+        self.subscribers.record(|| JournalEntry::TradeOption {
+            contract,
+            side,
+            quantity,
+            premium,
+        });
+        if quantity <= S::new_zero() {
+            return Err(Error::OrderError(OrderError::OrderSizeMustBePositive));
+        }
+        if premium < S::PairedCurrency::new_zero() {
+            return Err(Error::InvalidAmount);
+        }
+        if contract.expiry_ts_ns <= self.market_state.current_timestamp_ns() {
+            return Err(Error::OptionExpired);
+        }
+        let available_balance = self.account.available_balance();
+        let margin = match side {
+            Side::Buy => {
+                if premium > available_balance {
+                    return Err(RiskError::NotEnoughAvailableBalance.into());
+                }
+                self.account.change_balance(
+                    premium.into_negative(),
+                    LedgerEntryKind::OptionPremium,
+                    self.market_state.current_timestamp_ns(),
+                );
+                S::PairedCurrency::new_zero()
+            }
+            Side::Sell => {
+                let margin = contract.short_margin::<S::PairedCurrency>(
+                    self.market_state.mid_price(),
+                    quantity,
+                    self.config.contract_specification().initial_margin,
+                );
+                if margin > available_balance + premium {
+                    return Err(RiskError::NotEnoughAvailableBalance.into());
+                }
+                self.account.change_balance(
+                    premium,
+                    LedgerEntryKind::OptionPremium,
+                    self.market_state.current_timestamp_ns(),
+                );
+                margin
+            }
+        };
+        let option_position = OptionPosition {
+            contract,
+            side,
+            quantity,
+            premium,
+            margin,
+        };
+        self.account.option_positions.push(option_position.clone());
+
+        Ok(option_position)
+    }
+
+    #[inline(always)]
+    fn next_order_id(&mut self) -> u64 {
+        self.next_order_id += 1;
+        self.next_order_id - 1
+    }
+
+    /// Submit two linked resting limit or stop orders, where the execution of one automatically cancels the other
+    /// (one-cancels-other), e.g.: a take-profit limit and a stop-loss stop order.
+    /// Triggering a stop order or cancelling one of the orders cancels the other as well.
+    /// Either both orders are accepted or none of them.
+    ///
+    /// # Returns:
+    /// If Ok, both orders with their timestamp, id and `group_id` filled in.
+    /// Else its an error.
+    pub fn submit_oco_orders(
+        &mut self,
+        mut order_a: Order<S>,
+        mut order_b: Order<S>,
+    ) -> Result<(Order<S>, Order<S>)> {
+        self.subscribers
+            .record(|| JournalEntry::SubmitOcoOrders(order_a.clone(), order_b.clone()));
+        self.fills.clear();
+        self.acquire_rate_limit()?;
+        for order in [&order_a, &order_b] {
+            let is_resting = order.stop_price().is_some()
+                || (matches!(order.order_type(), OrderType::Limit)
+                    && !order.time_in_force().is_immediate());
+            if !is_resting {
+                self.stats.num_rejections += 1;
+                return Err(Error::OrderError(OrderError::InvalidOcoOrder));
+            }
+        }
+        // The group is identified by the `id` of its first order.
+        let group_id = self.next_order_id;
+        order_a.set_group_id(group_id);
+        order_b.set_group_id(group_id);
+
+        // Both orders are validated before either is submitted,
+        // as with a `LatencyModel` the first one would already be in flight.
+        self.validate_submissions(&[order_a.clone(), order_b.clone()])
+            .inspect_err(|_| self.stats.num_rejections += 1)?;
+
+        // Both orders share the fault of the request.
+        let delay_ns = self.inject_fault()?;
+        let order_a = self.send_submission(order_a, delay_ns)?;
+        match self.send_submission(order_b, delay_ns) {
+            Ok(order_b) => Ok((order_a, order_b)),
+            Err(e) => {
+                self.withdraw_submission(order_a.id());
+                Err(e)
+            }
+        }
+    }
+
+    /// Cancel a submitted order, which is dropped from the requests in flight if it has not reached the exchange yet.
+    fn withdraw_submission(&mut self, order_id: u64) {
+        let in_flight = self.in_flight.iter().position(
+            |(_, request)| matches!(request, Request::Submission(order) if order.id() == order_id),
+        );
+        let Some(idx) = in_flight else {
+            if let Err(e) = self.try_cancel_order(order_id) {
+                debug!("withdrawing order {} failed: {}", order_id, e);
+            }
+            return;
+        };
+        let Some((_, Request::Submission(order))) = self.in_flight.remove(idx) else {
+            unreachable!("Found above");
+        };
+        let mut order = *order;
+        self.close_order(&mut order, OrderStatus::Cancelled);
+        self.on_cancelled(&order);
+    }
+
+    /// Submit a basket of orders atomically, e.g. the legs of a spread or pairs trade.
+    /// All orders are first validated in sequence against a copy of the exchange,
+    /// so either all of them pass the filters and margin checks and are submitted, or none of them.
+    /// The basket counts as a single request for the `RateLimit` and the `FaultInjection`.
+    ///
+    /// # Returns:
+    /// If Ok, the orders with their timestamp and id filled in.
+    /// Else the error of the first order which failed, without any effect on the exchange.
+    pub fn submit_basket_orders(&mut self, orders: Vec<Order<S>>) -> Result<Vec<Order<S>>> {
+        self.subscribers
+            .record(|| JournalEntry::SubmitBasketOrders(orders.clone()));
+        self.fills.clear();
+        self.acquire_rate_limit()?;
+        self.validate_submissions(&orders)
+            .inspect_err(|_| self.stats.num_rejections += 1)?;
+
+        // All orders share the fault of the request.
+        let delay_ns = self.inject_fault()?;
+        orders
+            .into_iter()
+            .map(|order| self.send_submission(order, delay_ns))
+            .collect()
+    }
+
+    /// Check that the `orders` would all be accepted when submitted in sequence,
+    /// by submitting them to a copy of the exchange.
+    fn validate_submissions(&self, orders: &[Order<S>]) -> Result<()> {
+        if orders.is_empty() {
+            return Ok(());
+        }
+        let mut dry_run = self.dry_run_copy();
+        for order in orders.iter() {
+            let mut order = order.clone();
+            order.set_id(dry_run.next_order_id());
+            order.set_created_ts_ns(dry_run.market_state.current_timestamp_ns());
+            dry_run.process_submission(order)?;
+        }
+        Ok(())
+    }
+
+    /// A copy of the exchange without `AccountTracker`, subscribers, pending requests and history,
+    /// on which requests can be tried without any effect on this exchange.
+    /// Leaving out the history keeps the cost of the copy independent of the length of the run.
+    fn dry_run_copy(&self) -> Exchange<NoAccountTracker, S> {
+        Exchange {
+            config: self.config.clone(),
+            market_state: self.market_state.clone(),
+            account: self.account.clone_without_history(),
+            account_tracker: NoAccountTracker,
+            risk_engine: self.risk_engine.clone(),
+            custom_risk_engines: self.custom_risk_engines.clone(),
+            clearing_house: ClearingHouse::new(),
+            maker_program: self.maker_program.clone(),
+            rolling_volume: self.rolling_volume.clone(),
+            fee_maker: self.fee_maker,
+            fee_taker: self.fee_taker,
+            stats: ExchangeStats::default(),
+            insurance_fund_payout: self.insurance_fund_payout,
+            dust: self.dust,
+            subscribers: EventSubscribers::default(),
+            first_ts_ns: self.first_ts_ns,
+            settled: self.settled,
+            latency: self.latency.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            fault_injector: self.fault_injector.clone(),
+            candle_path: self.candle_path.clone(),
+            in_flight: VecDeque::new(),
+            next_order_id: self.next_order_id,
+            closed_orders: HashMap::new(),
+            closed_order_ids: VecDeque::new(),
+            triggered_orders: Vec::new(),
+            fills: Vec::new(),
+            trade_history: VecDeque::new(),
+            next_trade_id: self.next_trade_id,
+            halted_until_ns: self.halted_until_ns,
+            stop_orders: self.stop_orders.clone(),
+            position_tpsl: self.position_tpsl.clone(),
+            trailing_take_profit: self.trailing_take_profit.clone(),
+        }
+    }
+
+    /// Change the leverage of the positions, which also applies to the resting orders.
+    /// The position margin is transferred from or to the available balance accordingly.
+    ///
+    /// # Arguments:
+    /// `leverage`: The new leverage.
+    ///
+    /// # Returns:
+    /// If Err, the leverage remains unchanged, because either
+    /// the positions would exceed the `PositionLimit` for the new leverage,
+    /// the available balance cannot cover the additional margin,
+    /// or the positions would fall below their maintenance margin.
+    pub fn set_leverage(&mut self, leverage: Leverage) -> Result<()> {
+        self.subscribers
+            .record(|| JournalEntry::SetLeverage(leverage));
+        if let Some(max_notional) = self.config.position_limit().max_notional(leverage) {
+            let mid_price = self.market_state.mid_price();
+            for position in [self.account.position(), self.account.short_position()] {
+                if position.size().abs().convert(mid_price) > max_notional {
+                    return Err(Error::RiskError(RiskError::MaxPositionNotionalExceeded));
+                }
+            }
+        }
+        let previous = self.account.position().leverage();
+        self.account.update_desired_leverage(leverage)?;
+        if let Err(err) = self.check_maintenance_margin() {
+            self.account
+                .update_desired_leverage(previous)
+                .expect("The previous leverage was valid; qed");
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    /// Deposit external funds into the wallet balance, e.g. to simulate periodic capital injections.
+    /// The `AccountTracker` logs it as a cash flow, which is not part of the trading profit and loss.
+    ///
+    /// # Arguments:
+    /// `amount`: The positive amount to deposit, denoted in the margin currency.
+    pub fn deposit(&mut self, amount: S::PairedCurrency) -> Result<()> {
+        self.subscribers.record(|| JournalEntry::Deposit(amount));
+        if amount <= S::PairedCurrency::new_zero() {
+            return Err(Error::InvalidAmount);
+        }
+        self.account.change_balance(
+            amount,
+            LedgerEntryKind::Deposit,
+            self.market_state.current_timestamp_ns(),
+        );
+        self.account_tracker
+            .log_cash_flow(amount, self.market_state.current_timestamp_ns());
+        Ok(())
+    }
+
+    /// Withdraw funds from the wallet balance, e.g. to simulate profit-taking.
+    /// The `AccountTracker` logs it as a cash flow, which is not part of the trading profit and loss.
+    ///
+    /// # Arguments:
+    /// `amount`: The positive amount to withdraw, denoted in the margin currency.
+    ///
+    /// # Returns:
+    /// If Err, the available balance cannot cover the `amount`.
+    pub fn withdraw(&mut self, amount: S::PairedCurrency) -> Result<()> {
+        self.subscribers.record(|| JournalEntry::Withdraw(amount));
+        if amount <= S::PairedCurrency::new_zero() {
+            return Err(Error::InvalidAmount);
+        }
+        // The collateral counts towards the available balance, but can't be withdrawn in the margin currency.
+        if amount > self.account.available_balance() || amount > self.account.wallet_balance {
+            return Err(Error::RiskError(RiskError::NotEnoughAvailableBalance));
+        }
+        self.account.change_balance(
+            amount.into_negative(),
+            LedgerEntryKind::Withdrawal,
+            self.market_state.current_timestamp_ns(),
+        );
+        self.account_tracker.log_cash_flow(
+            amount.into_negative(),
+            self.market_state.current_timestamp_ns(),
+        );
+        Ok(())
+    }
+
+    /// Deposit additional margin from the available balance into a position,
+    /// which moves its liquidation price further away.
+    ///
+    /// # Arguments:
+    /// `side`: The side of the position, which only matters in `PositionMode::Hedge`,
+    /// where `Buy` selects the long and `Sell` the short position.
+    /// `amount`: The positive amount to deposit, denoted in the margin currency.
+    ///
+    /// # Returns:
+    /// If Err, there is no such position or the available balance cannot cover the `amount`.
+    pub fn deposit_position_margin(&mut self, side: Side, amount: S::PairedCurrency) -> Result<()> {
+        self.subscribers
+            .record(|| JournalEntry::DepositPositionMargin { side, amount });
+        self.account.deposit_position_margin(side, amount)
+    }
+
+    /// Withdraw previously deposited margin from a position back into the available balance.
+    /// The margin required by the leverage of the position can not be withdrawn.
+    ///
+    /// # Arguments:
+    /// `side`: The side of the position, which only matters in `PositionMode::Hedge`,
+    /// where `Buy` selects the long and `Sell` the short position.
+    /// `amount`: The positive amount to withdraw, denoted in the margin currency.
+    ///
+    /// # Returns:
+    /// If Err, the margin remains unchanged, because either
+    /// there is no such position, the `amount` exceeds the deposited margin,
+    /// or the position would fall below its maintenance margin.
+    pub fn withdraw_position_margin(
+        &mut self,
+        side: Side,
+        amount: S::PairedCurrency,
+    ) -> Result<()> {
+        self.subscribers
+            .record(|| JournalEntry::WithdrawPositionMargin { side, amount });
+        self.account.withdraw_position_margin(side, amount)?;
+        if let Err(err) = self.check_maintenance_margin() {
+            self.account
+                .deposit_position_margin(side, amount)
+                .expect("The amount was just withdrawn; qed");
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    /// Post collateral in an asset other than the margin currency,
+    /// which counts towards the available balance once it has a price.
+    ///
+    /// # Arguments:
+    /// `ticker`: The ticker of an asset of `Config::with_collateral_asset`.
+    /// `quantity`: The positive quantity of the asset to deposit.
+    ///
+    /// # Returns:
+    /// If Err, the asset is not configured as collateral or the `quantity` is not positive.
+    pub fn deposit_collateral(&mut self, ticker: &str, quantity: S::Num) -> Result<()> {
+        self.subscribers.record(|| JournalEntry::DepositCollateral {
+            ticker: ticker.to_string(),
+            quantity,
+        });
+        if quantity <= S::Num::ZERO {
+            return Err(Error::InvalidAmount);
+        }
+        self.account.collateral_mut(ticker)?.deposit(quantity);
+        Ok(())
+    }
+
+    /// Withdraw previously posted collateral.
+    ///
+    /// # Arguments:
+    /// `ticker`: The ticker of an asset of `Config::with_collateral_asset`.
+    /// `quantity`: The positive quantity of the asset to withdraw.
+    ///
+    /// # Returns:
+    /// If Err, the collateral remains unchanged, because either the asset is not configured as collateral,
+    /// the `quantity` exceeds the held quantity, or the available balance cannot cover its value.
+    pub fn withdraw_collateral(&mut self, ticker: &str, quantity: S::Num) -> Result<()> {
+        self.subscribers
+            .record(|| JournalEntry::WithdrawCollateral {
+                ticker: ticker.to_string(),
+                quantity,
+            });
+        if quantity <= S::Num::ZERO {
+            return Err(Error::InvalidAmount);
+        }
+        let available_balance = self.account.available_balance();
+        let collateral = self.account.collateral_mut(ticker)?;
+        if quantity > collateral.quantity() || collateral.value_of(quantity) > available_balance {
+            return Err(Error::RiskError(RiskError::NotEnoughAvailableBalance));
+        }
+        collateral.withdraw(quantity);
+        Ok(())
+    }
+
+    /// Update the conversion price of a collateral asset, which determines its value.
+    /// If the effective balance no longer covers the margin afterwards, the account is liquidated.
+    ///
+    /// # Arguments:
+    /// `ticker`: The ticker of an asset of `Config::with_collateral_asset`.
+    /// `price`: The positive price of one unit of the asset, denoted in the margin currency.
+    ///
+    /// # Returns:
+    /// If Err, either the price was not applied, or it was and `RiskError::Liquidate` reports the liquidation.
+    pub fn update_collateral_price(
+        &mut self,
+        ticker: &str,
+        price: S::PairedCurrency,
+    ) -> Result<()> {
+        self.subscribers.record(|| JournalEntry::CollateralPrice {
+            ticker: ticker.to_string(),
+            price,
+        });
+        if price <= S::PairedCurrency::new_zero() {
+            return Err(Error::InvalidPrice);
+        }
+        self.account.collateral_mut(ticker)?.set_price(price);
+        if self.account.required_margin()
+            > max(
+                self.account.effective_balance(),
+                S::PairedCurrency::new_zero(),
+            )
+        {
+            self.liquidate();
+            return Err(RiskError::Liquidate.into());
+        }
+        Ok(())
+    }
+
+    /// Deposit the token of `Config::with_fee_token`, in which fees are then paid at its discount.
+    ///
+    /// # Arguments:
+    /// `quantity`: The positive quantity of the token to deposit.
+    ///
+    /// # Returns:
+    /// If Err, no fee token is configured or the `quantity` is not positive.
+    pub fn deposit_fee_token(&mut self, quantity: S::Num) -> Result<()> {
+        self.subscribers
+            .record(|| JournalEntry::DepositFeeToken(quantity));
+        if quantity <= S::Num::ZERO {
+            return Err(Error::InvalidAmount);
+        }
+        self.account.fee_token_mut()?.deposit(quantity);
+        Ok(())
+    }
+
+    /// Withdraw previously deposited fee tokens.
+    ///
+    /// # Arguments:
+    /// `quantity`: The positive quantity of the token to withdraw.
+    ///
+    /// # Returns:
+    /// If Err, the balance remains unchanged, because either no fee token is configured,
+    /// the `quantity` is not positive, or it exceeds the held quantity.
+    pub fn withdraw_fee_token(&mut self, quantity: S::Num) -> Result<()> {
+        self.subscribers
+            .record(|| JournalEntry::WithdrawFeeToken(quantity));
+        if quantity <= S::Num::ZERO {
+            return Err(Error::InvalidAmount);
+        }
+        let balance = self.account.fee_token_mut()?;
+        if quantity > balance.quantity() {
+            return Err(Error::RiskError(RiskError::NotEnoughAvailableBalance));
+        }
+        balance.withdraw(quantity);
+        Ok(())
+    }
+
+    /// Update the conversion price of the fee token, which determines the quantity paid per fee.
+    ///
+    /// # Arguments:
+    /// `price`: The positive price of one unit of the token, denoted in the margin currency.
+    ///
+    /// # Returns:
+    /// If Err, the price was not applied, because no fee token is configured or the price is not positive.
+    pub fn update_fee_token_price(&mut self, price: S::PairedCurrency) -> Result<()> {
+        self.subscribers
+            .record(|| JournalEntry::FeeTokenPrice(price));
+        if price <= S::PairedCurrency::new_zero() {
+            return Err(Error::InvalidPrice);
+        }
+        self.account.fee_token_mut()?.set_price(price);
+        Ok(())
+    }
+
+    /// Cancel an active order based on the user_order_id of an Order
+    ///
+    /// # Arguments:
+    /// `user_order_id`: The `user_order_id` of the order to cancel, either a `u64` or a string.
+    ///
+    /// # Returns:
+    /// the cancelled order if successfull, error when the `user_order_id` is
+    /// not found
+    pub fn cancel_order_by_user_id<I: Into<UserOrderId>>(
+        &mut self,
+        user_order_id: I,
+    ) -> Result<Order<S>> {
+        let user_order_id = user_order_id.into();
+        self.subscribers
+            .record(|| JournalEntry::CancelOrderByUserId(user_order_id.clone()));
+        self.acquire_rate_limit()?;
+        let delay_ns = self.inject_fault()?;
+        if self.latency.is_enabled() || delay_ns > 0 {
+            let order_id = self
+                .account
+                .lookup_order_nonce_from_user_order_id
+                .get(&user_order_id)
+                .copied()
+                .or_else(|| self.stop_order_id_from_user_order_id(&user_order_id))
+                .or_else(|| {
+                    self.submissions_in_flight()
+                        .find(|order| order.user_order_id().as_ref() == Some(&user_order_id))
+                        .map(|order| order.id())
+                })
+                .ok_or(Error::UserOrderIdNotFound)?;
+            return self.send_cancellation(order_id, delay_ns);
+        }
+        self.check_available()?;
+        if let Some(order_id) = self.stop_order_id_from_user_order_id(&user_order_id) {
+            return self.try_cancel_order(order_id);
+        }
+        let mut order = self
+            .account
+            .cancel_order_by_user_id(&user_order_id, &mut self.account_tracker)?;
+        self.close_order(&mut order, OrderStatus::Cancelled);
+        self.on_cancelled(&order);
+        Ok(order)
+    }
+
+    /// Cancel an active order.
+    /// If the order is part of a one-cancels-other group, the other orders are cancelled as well.
+    /// With a `LatencyModel` configured, the cancellation only takes effect once it reaches the exchange,
+    /// so the order may still get filled in the meantime.
+    ///
+    /// # Arguments:
+    /// `order_id`: The `id` (assigned by the exchange) of the order to cancel.
+    ///
+    /// # Returns:
     /// An order if successful with the given order_id.
     pub fn cancel_order(&mut self, order_id: u64) -> Result<Order<S>> {
-        self.account
-            .cancel_order(order_id, &mut self.account_tracker)
+        self.subscribers
+            .record(|| JournalEntry::CancelOrder(order_id));
+        self.acquire_rate_limit()?;
+        let delay_ns = self.inject_fault()?;
+        self.send_cancellation(order_id, delay_ns)
+    }
+
+    /// Send an order cancellation, which takes effect immediately
+    /// unless there is a `LatencyModel` or an injected `delay_ns`.
+    fn send_cancellation(&mut self, order_id: u64, delay_ns: i64) -> Result<Order<S>> {
+        if self.latency.is_enabled() || delay_ns > 0 {
+            let order = self
+                .account
+                .active_limit_orders
+                .get(&order_id)
+                .or_else(|| self.stop_orders.iter().find(|order| order.id() == order_id))
+                .or_else(|| {
+                    self.submissions_in_flight()
+                        .find(|order| order.id() == order_id)
+                })
+                .cloned()
+                .ok_or(Error::OrderIdNotFound)?;
+            self.send_request(Request::Cancellation(order_id), delay_ns);
+            return Ok(order);
+        }
+        self.check_available()?;
+        self.try_cancel_order(order_id)
+    }
+
+    /// The order submissions which have not reached the exchange yet.
+    fn submissions_in_flight(&self) -> impl Iterator<Item = &Order<S>> {
+        self.in_flight
+            .iter()
+            .filter_map(|(_, request)| match request {
+                Request::Submission(order) => Some(order.as_ref()),
+                Request::Cancellation(_) => None,
+            })
+    }
+
+    /// The id of the untriggered stop order with the `user_order_id`, if any.
+    fn stop_order_id_from_user_order_id(&self, user_order_id: &UserOrderId) -> Option<u64> {
+        self.stop_orders
+            .iter()
+            .find(|order| order.user_order_id().as_ref() == Some(user_order_id))
+            .map(|order| order.id())
+    }
+
+    fn try_cancel_order(&mut self, order_id: u64) -> Result<Order<S>> {
+        let mut order = self.remove_resting_order(order_id)?;
+        self.close_order(&mut order, OrderStatus::Cancelled);
+        self.on_cancelled(&order);
+        Ok(order)
+    }
+
+    /// Remove an untriggered stop order or an active limit order.
+    fn remove_resting_order(&mut self, order_id: u64) -> Result<Order<S>> {
+        match self
+            .stop_orders
+            .iter()
+            .position(|order| order.id() == order_id)
+        {
+            Some(i) => Ok(self.stop_orders.remove(i)),
+            None => self
+                .account
+                .cancel_order(order_id, &mut self.account_tracker),
+        }
+    }
+
+    /// Account for an order cancelled by the user, which also cancels its one-cancels-other siblings.
+    fn on_cancelled(&mut self, order: &Order<S>) {
+        self.stats.num_cancellations += 1;
+        self.subscribers
+            .emit(self.market_state.current_timestamp_ns(), || {
+                ExchangeEvent::Cancel(order.clone())
+            });
+        self.cancel_order_group_siblings(order);
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test {
     use fpdec::Decimal;
 
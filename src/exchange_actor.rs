@@ -0,0 +1,173 @@
+//! An async adapter of the `Exchange`, to paper trade against live market data feeds.
+
+use std::pin::pin;
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio_stream::{Stream, StreamExt};
+
+use crate::{
+    account_tracker::AccountTracker,
+    exchange::Exchange,
+    risk_engine::RiskError,
+    types::{
+        Currency, Error, ExchangeEvent, MarginCurrency, MarketUpdate, Order, Result, UserOrderId,
+    },
+};
+
+/// An order command sent to an `ExchangeActor`.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum ExchangeCommand<S>
+where
+    S: Currency,
+{
+    /// Submit the order, see `Exchange::submit_order`.
+    SubmitOrder(Order<S>),
+    /// Submit the two orders as a one-cancels-other group, see `Exchange::submit_oco_orders`.
+    SubmitOcoOrders(Order<S>, Order<S>),
+    /// Cancel the order with the id, see `Exchange::cancel_order`.
+    CancelOrder(u64),
+    /// Cancel the order with the user id, see `Exchange::cancel_order_by_user_id`.
+    CancelOrderByUserId(UserOrderId),
+}
+
+/// Something emitted by an `ExchangeActor` on its output channel.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum ActorEvent<S>
+where
+    S: Currency,
+{
+    /// An `ExchangeEvent` with the timestamp in nanoseconds at which it happened.
+    Exchange {
+        /// The timestamp in nanoseconds of the event.
+        ts_ns: i64,
+        /// The event.
+        event: ExchangeEvent<S>,
+    },
+    /// The `Exchange` returned an Error for the command,
+    /// which includes rejected orders that are also emitted as `ExchangeEvent::OrderRejected`.
+    CommandFailed {
+        /// The failed command.
+        command: ExchangeCommand<S>,
+        /// The reason of the failure.
+        error: Error,
+    },
+}
+
+/// Drives an `Exchange` with a stream of `MarketUpdate`s and a channel of `ExchangeCommand`s,
+/// emitting `ActorEvent`s on an output channel.
+/// This reuses the same matching and accounting as a backtest for paper trading against live websocket feeds.
+///
+/// Pending commands are applied before the next market update,
+/// so orders are processed in the market state in which they were sent.
+/// Both channels are unbounded, as the events are emitted synchronously from within the `Exchange`.
+#[derive(Debug)]
+pub struct ExchangeActor<A, S>
+where
+    S: Currency,
+    S::PairedCurrency: MarginCurrency,
+{
+    exchange: Exchange<A, S>,
+    commands: UnboundedReceiver<ExchangeCommand<S>>,
+    events: UnboundedSender<ActorEvent<S>>,
+}
+
+impl<A, S> ExchangeActor<A, S>
+where
+    A: AccountTracker<S::PairedCurrency>,
+    S: Currency + Send + 'static,
+    S::PairedCurrency: MarginCurrency,
+{
+    /// Create a new actor of the `exchange`, subscribing to all of its `ExchangeEvent`s.
+    ///
+    /// # Returns:
+    /// The actor, the sender of its commands and the receiver of its events.
+    pub fn new(
+        mut exchange: Exchange<A, S>,
+    ) -> (
+        Self,
+        UnboundedSender<ExchangeCommand<S>>,
+        UnboundedReceiver<ActorEvent<S>>,
+    ) {
+        let (command_tx, commands) = unbounded_channel();
+        let (events, event_rx) = unbounded_channel();
+        let subscriber = events.clone();
+        exchange.subscribe(move |ts_ns, event| {
+            // A dropped receiver just means that nobody listens to the events anymore.
+            let _ = subscriber.send(ActorEvent::Exchange {
+                ts_ns,
+                event: event.clone(),
+            });
+        });
+        let actor = Self {
+            exchange,
+            commands,
+            events,
+        };
+        (actor, command_tx, event_rx)
+    }
+
+    /// Return a reference to the `Exchange`.
+    #[inline(always)]
+    pub fn exchange(&self) -> &Exchange<A, S> {
+        &self.exchange
+    }
+
+    /// Run until the `market_updates`, which are pairs of a timestamp in nanoseconds and a `MarketUpdate`, end.
+    /// Commands keep being processed after all command senders are dropped, as long as market updates arrive.
+    /// Liquidations are emitted as `ExchangeEvent::Liquidation` and do not stop the actor.
+    ///
+    /// # Returns:
+    /// If Ok, the `Exchange` in its final state.
+    /// Otherwise the first Error of a market update other than a liquidation, e.g. an invalid market update.
+    pub async fn run<U>(mut self, market_updates: U) -> Result<Exchange<A, S>>
+    where
+        U: Stream<Item = (u64, MarketUpdate<S>)>,
+    {
+        let mut market_updates = pin!(market_updates);
+        let mut executed = Vec::new();
+        let mut commands_open = true;
+        loop {
+            tokio::select! {
+                biased;
+                command = self.commands.recv(), if commands_open => match command {
+                    Some(command) => self.handle_command(command),
+                    None => commands_open = false,
+                },
+                update = market_updates.next() => match update {
+                    Some((timestamp_ns, market_update)) => {
+                        executed.clear();
+                        match self.exchange.update_state_into(timestamp_ns, &market_update, &mut executed) {
+                            Ok(()) | Err(Error::RiskError(RiskError::Liquidate)) => {}
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    None => return Ok(self.exchange),
+                },
+            }
+        }
+    }
+
+    fn handle_command(&mut self, command: ExchangeCommand<S>) {
+        let result = match command.clone() {
+            ExchangeCommand::SubmitOrder(order) => self.exchange.submit_order(order).map(|_| ()),
+            ExchangeCommand::SubmitOcoOrders(order_a, order_b) => self
+                .exchange
+                .submit_oco_orders(order_a, order_b)
+                .map(|_| ()),
+            ExchangeCommand::CancelOrder(order_id) => {
+                self.exchange.cancel_order(order_id).map(|_| ())
+            }
+            ExchangeCommand::CancelOrderByUserId(user_order_id) => self
+                .exchange
+                .cancel_order_by_user_id(user_order_id)
+                .map(|_| ()),
+        };
+        if let Err(error) = result {
+            let _ = self
+                .events
+                .send(ActorEvent::CommandFailed { command, error });
+        }
+    }
+}
@@ -0,0 +1,420 @@
+//! Per-symbol exchange specifications, which can be loaded from TOML or JSON files
+//! instead of hard-coding the `Config`.
+
+use std::{any::TypeId, path::Path};
+
+use fpdec::Decimal;
+
+use crate::{
+    config::Config,
+    contract_specification::{ContractSpecification, MarkMethod},
+    fee_schedule::{FeeSchedule, FeeTier, THIRTY_DAYS_NS},
+    order_filters::{MaxNumOrdersFilter, NotionalFilter, PriceFilter, QuantityFilter},
+    quanto::Quanto,
+    types::{Currency, Error, Fee, Leverage, QuoteCurrency, Result},
+};
+
+/// Whether the contract is margined in the quote, the base or a third currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractType {
+    /// Margined and settled in `QuoteCurrency`.
+    Linear,
+    /// Margined and settled in `BaseCurrency`.
+    Inverse,
+    /// Margined and settled in a third currency at the fixed `quanto_multiplier`,
+    /// simulated as a linear contract, see `Quanto`.
+    Quanto,
+}
+
+/// The file format of an `ExchangeSpec`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecFormat {
+    /// Tom's Obvious Minimal Language.
+    Toml,
+    /// JavaScript Object Notation.
+    Json,
+}
+
+/// The fees of an `ExchangeSpec`, either flat or with rolling volume tiers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FeeSpec {
+    /// The same fees regardless of the traded volume.
+    Flat {
+        /// The maker fee as a fraction.
+        maker: Fee,
+        /// The taker fee as a fraction.
+        taker: Fee,
+    },
+    /// See `FeeSchedule::new`.
+    Tiered {
+        /// The length of the rolling volume window in nanoseconds.
+        #[serde(default = "default_fee_window_ns")]
+        window_ns: i64,
+        /// The volume tiers.
+        tiers: Vec<FeeTier>,
+    },
+}
+
+/// The quantity rules of an `ExchangeSpec`, see `QuantityFilter`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuantityFilterSpec {
+    /// The minimum quantity of any order, disabled if 0.
+    pub min_quantity: Decimal,
+    /// The maximum quantity of any order, disabled if 0.
+    pub max_quantity: Decimal,
+    /// The maximum quantity of market orders, disabled if 0.
+    pub max_market_quantity: Decimal,
+    /// The intervals in which the quantity may change.
+    pub step_size: Decimal,
+}
+
+/// The notional value rules of an `ExchangeSpec`, denoted in the margin currency, see `NotionalFilter`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotionalFilterSpec {
+    /// The minimum notional value of any order, disabled if 0.
+    pub min_notional: Decimal,
+    /// The maximum notional value of any order, disabled if 0.
+    pub max_notional: Decimal,
+}
+
+impl Default for QuantityFilterSpec {
+    fn default() -> Self {
+        Self {
+            min_quantity: Decimal::ZERO,
+            max_quantity: Decimal::ZERO,
+            max_market_quantity: Decimal::ZERO,
+            step_size: Decimal::ONE,
+        }
+    }
+}
+
+/// Describes the exchange and the contract of a single symbol,
+/// e.g. as scraped from the exchange info of a venue.
+/// All decimal values are written as strings to preserve their precision.
+///
+/// A minimal TOML spec:
+/// ```toml
+/// ticker = "BTCUSDT"
+/// contract_type = "linear"
+/// starting_balance = "1000"
+/// leverage = 5
+/// initial_margin = "0.05"
+/// maintenance_margin = "0.025"
+/// fees = { maker = "0.0002", taker = "0.0004" }
+///
+/// [price_filter]
+/// tick_size = "0.1"
+///
+/// [quantity_filter]
+/// step_size = "0.001"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeSpec {
+    /// Identifying ticker symbol.
+    pub ticker: String,
+    /// Whether the contract is linear, inverse or quanto.
+    pub contract_type: ContractType,
+    /// The value of a price move of one unit of the quote currency for one contract,
+    /// denoted in the settlement currency. Required for and only allowed with `ContractType::Quanto`.
+    #[serde(default)]
+    pub quanto_multiplier: Option<Decimal>,
+    /// The starting wallet balance, denoted in the margin currency.
+    pub starting_balance: Decimal,
+    /// The maximum number of open orders. Defaults to 200.
+    #[serde(default = "default_max_num_open_orders")]
+    pub max_num_open_orders: usize,
+    /// The initial leverage of positions. Defaults to 1.
+    #[serde(default = "default_leverage")]
+    pub leverage: u8,
+    /// The initial margin as a fraction.
+    pub initial_margin: Decimal,
+    /// The maintenance margin as a fraction.
+    pub maintenance_margin: Decimal,
+    /// The method for computing `mark-to-market`. Defaults to `MidPrice`.
+    #[serde(default = "default_mark_method")]
+    pub mark_method: MarkMethod,
+    /// The maker and taker fees.
+    pub fees: FeeSpec,
+    /// The price rules, with unspecified fields taken from `PriceFilter::default`.
+    #[serde(default)]
+    pub price_filter: PriceFilter,
+    /// The quantity rules.
+    #[serde(default)]
+    pub quantity_filter: QuantityFilterSpec,
+    /// The notional value rules.
+    #[serde(default)]
+    pub notional_filter: NotionalFilterSpec,
+    /// The limits on the number of resting limit orders.
+    #[serde(default)]
+    pub max_num_orders_filter: MaxNumOrdersFilter,
+}
+
+fn default_fee_window_ns() -> i64 {
+    THIRTY_DAYS_NS
+}
+
+fn default_max_num_open_orders() -> usize {
+    200
+}
+
+fn default_leverage() -> u8 {
+    1
+}
+
+fn default_mark_method() -> MarkMethod {
+    MarkMethod::MidPrice
+}
+
+impl ExchangeSpec {
+    /// Parse a spec in the given `format`.
+    pub fn from_str(s: &str, format: SpecFormat) -> Result<Self> {
+        match format {
+            SpecFormat::Toml => {
+                toml::from_str(s).map_err(|e| Error::InvalidExchangeSpec(e.to_string()))
+            }
+            SpecFormat::Json => {
+                serde_json::from_str(s).map_err(|e| Error::InvalidExchangeSpec(e.to_string()))
+            }
+        }
+    }
+
+    /// Read a spec from a file, whose format is inferred from its `.toml` or `.json` extension.
+    pub fn from_file<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let format = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => SpecFormat::Toml,
+            Some("json") => SpecFormat::Json,
+            _ => {
+                return Err(Error::InvalidExchangeSpec(format!(
+                    "unknown file extension of {}",
+                    path.display()
+                )))
+            }
+        };
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::InvalidExchangeSpec(format!("{}: {e}", path.display())))?;
+        Self::from_str(&contents, format)
+    }
+
+    /// Build a validated `Config` from the spec.
+    /// The `contract_type` must match the margin currency `M`, which uses the `Decimal` backend.
+    /// A quanto contract uses `QuoteCurrency` as its settlement currency,
+    /// with the absolute prices of the `price_filter` scaled by the `quanto_multiplier`.
+    pub fn into_config<M>(self) -> Result<Config<M>>
+    where
+        M: Currency<Num = Decimal> + 'static,
+    {
+        let contract_type = if TypeId::of::<M>() == TypeId::of::<QuoteCurrency>() {
+            ContractType::Linear
+        } else {
+            ContractType::Inverse
+        };
+        let settled_as = match self.contract_type {
+            ContractType::Quanto => ContractType::Linear,
+            contract_type => contract_type,
+        };
+        if contract_type != settled_as {
+            return Err(Error::InvalidExchangeSpec(format!(
+                "the spec of {} describes a {:?} contract, but the margin currency is for a {contract_type:?} one",
+                self.ticker, self.contract_type
+            )));
+        }
+        let price_filter = match (self.contract_type, self.quanto_multiplier) {
+            (ContractType::Quanto, Some(multiplier)) => {
+                Quanto::new(multiplier)?.price_filter(self.price_filter)
+            }
+            (ContractType::Quanto, None) => {
+                return Err(Error::InvalidExchangeSpec(format!(
+                    "the quanto contract {} requires a quanto_multiplier",
+                    self.ticker
+                )))
+            }
+            (_, Some(_)) => {
+                return Err(Error::InvalidExchangeSpec(format!(
+                    "the quanto_multiplier of {} requires contract_type = \"quanto\"",
+                    self.ticker
+                )))
+            }
+            (_, None) => self.price_filter,
+        };
+
+        let fee_schedule = match self.fees {
+            FeeSpec::Flat { maker, taker } => FeeSchedule::flat(maker, taker),
+            FeeSpec::Tiered { window_ns, tiers } => FeeSchedule::new(window_ns, tiers)?,
+        };
+        let contract_specification = ContractSpecification {
+            ticker: self.ticker,
+            initial_margin: self.initial_margin,
+            maintenance_margin: self.maintenance_margin,
+            mark_method: self.mark_method,
+            price_filter,
+            quantity_filter: QuantityFilter {
+                min_quantity: M::PairedCurrency::new(self.quantity_filter.min_quantity),
+                max_quantity: M::PairedCurrency::new(self.quantity_filter.max_quantity),
+                max_market_quantity: M::PairedCurrency::new(
+                    self.quantity_filter.max_market_quantity,
+                ),
+                step_size: M::PairedCurrency::new(self.quantity_filter.step_size),
+            },
+            notional_filter: NotionalFilter {
+                min_notional: M::new(self.notional_filter.min_notional),
+                max_notional: M::new(self.notional_filter.max_notional),
+            },
+            max_num_orders_filter: self.max_num_orders_filter,
+            fee_schedule,
+        };
+
+        Config::builder()
+            .starting_balance(M::new(self.starting_balance))
+            .max_num_open_orders(self.max_num_open_orders)
+            .initial_leverage(Leverage::new(self.leverage)?)
+            .contract_specification(contract_specification)
+            .build()
+    }
+}
+
+impl<M> Config<M>
+where
+    M: Currency<Num = Decimal> + 'static,
+{
+    /// Load the `Config` from an `ExchangeSpec` in the given `format`.
+    pub fn from_str(s: &str, format: SpecFormat) -> Result<Self> {
+        ExchangeSpec::from_str(s, format)?.into_config()
+    }
+
+    /// Load the `Config` from an `ExchangeSpec` file with a `.toml` or `.json` extension.
+    pub fn from_file<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        ExchangeSpec::from_file(path)?.into_config()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    const SPEC_TOML: &str = r#"
+ticker = "BTCUSDT"
+contract_type = "linear"
+starting_balance = "1000"
+leverage = 5
+initial_margin = "0.05"
+maintenance_margin = "0.025"
+fees = { maker = "0.0002", taker = "0.0004" }
+
+[price_filter]
+tick_size = "0.1"
+
+[quantity_filter]
+min_quantity = "0.001"
+step_size = "0.001"
+
+[notional_filter]
+min_notional = "100"
+
+[max_num_orders_filter]
+max_num_orders = 200
+"#;
+
+    #[test]
+    fn config_from_toml() {
+        let config = Config::<QuoteCurrency>::from_str(SPEC_TOML, SpecFormat::Toml).unwrap();
+        assert_eq!(config.starting_balance(), quote!(1000));
+        assert_eq!(config.max_num_open_orders(), 200);
+        assert_eq!(config.initial_leverage(), leverage!(5));
+        let spec = config.contract_specification();
+        assert_eq!(spec.ticker, "BTCUSDT");
+        assert_eq!(spec.price_filter.tick_size, quote!(0.1));
+        assert_eq!(spec.price_filter.multiplier_up, Dec!(2));
+        assert_eq!(spec.quantity_filter.step_size, base!(0.001));
+        assert_eq!(spec.notional_filter.min_notional, quote!(100));
+        assert_eq!(spec.max_num_orders_filter.max_num_orders, 200);
+        assert_eq!(spec.fee_schedule.tiers()[0].fee_taker, fee!(0.0004));
+
+        assert!(matches!(
+            Config::<BaseCurrency>::from_str(SPEC_TOML, SpecFormat::Toml),
+            Err(Error::InvalidExchangeSpec(_))
+        ));
+    }
+
+    #[test]
+    fn config_from_json() {
+        let json = r#"{
+            "ticker": "XBTUSD",
+            "contract_type": "inverse",
+            "starting_balance": "1",
+            "initial_margin": "0.01",
+            "maintenance_margin": "0.005",
+            "mark_method": "FairPrice",
+            "fees": {
+                "window_ns": 1000,
+                "tiers": [
+                    { "min_volume": "0", "fee_maker": "-0.00025", "fee_taker": "0.00075" },
+                    { "min_volume": "100000", "fee_maker": "-0.0003", "fee_taker": "0.0005" }
+                ]
+            }
+        }"#;
+        let config = Config::<BaseCurrency>::from_str(json, SpecFormat::Json).unwrap();
+        assert_eq!(config.starting_balance(), base!(1));
+        let spec = config.contract_specification();
+        assert_eq!(spec.fee_schedule.window_ns(), 1000);
+        assert_eq!(spec.fee_schedule.tiers().len(), 2);
+        assert_eq!(spec.quantity_filter.step_size, quote!(1));
+
+        assert!(matches!(
+            Config::<BaseCurrency>::from_str("{}", SpecFormat::Json),
+            Err(Error::InvalidExchangeSpec(_))
+        ));
+    }
+
+    #[test]
+    fn config_from_quanto_spec() {
+        let spec = SPEC_TOML.replace(
+            r#"contract_type = "linear""#,
+            "contract_type = \"quanto\"\nquanto_multiplier = \"0.000001\"",
+        );
+        let config = Config::<QuoteCurrency>::from_str(&spec, SpecFormat::Toml).unwrap();
+        let price_filter = &config.contract_specification().price_filter;
+        assert_eq!(price_filter.tick_size, quote!(0.0000001));
+        assert_eq!(price_filter.multiplier_up, Dec!(2));
+        assert!(matches!(
+            Config::<BaseCurrency>::from_str(&spec, SpecFormat::Toml),
+            Err(Error::InvalidExchangeSpec(_))
+        ));
+
+        let without_multiplier = SPEC_TOML.replace("linear", "quanto");
+        assert!(matches!(
+            Config::<QuoteCurrency>::from_str(&without_multiplier, SpecFormat::Toml),
+            Err(Error::InvalidExchangeSpec(_))
+        ));
+        let linear_with_multiplier = SPEC_TOML.replace(
+            r#"contract_type = "linear""#,
+            "contract_type = \"linear\"\nquanto_multiplier = \"0.000001\"",
+        );
+        assert!(matches!(
+            Config::<QuoteCurrency>::from_str(&linear_with_multiplier, SpecFormat::Toml),
+            Err(Error::InvalidExchangeSpec(_))
+        ));
+    }
+
+    #[test]
+    fn config_from_file() {
+        let path = std::env::temp_dir().join("lfest_exchange_spec.toml");
+        std::fs::write(&path, SPEC_TOML).unwrap();
+        let config = Config::<QuoteCurrency>::from_file(&path).unwrap();
+        assert_eq!(config.contract_specification().ticker, "BTCUSDT");
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(Config::<QuoteCurrency>::from_file("spec.yaml").is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Randomly rejects or delays order actions, to test the retry and replace handling of strategies.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::types::{Error, Result};
+
+/// Defines the fraction of order submissions and cancellations which are rejected or delayed,
+/// like the transient failures of real venues under load.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FaultInjection {
+    rejection_probability: f64,
+    delay_probability: f64,
+    delay_ns: i64,
+}
+
+impl FaultInjection {
+    /// Create a new `FaultInjection`.
+    ///
+    /// # Arguments:
+    /// `rejection_probability`: The probability of rejecting an order action with either
+    /// `Error::ExchangeOverloaded` or `Error::RequestTimeout`, in which case it has no effect.
+    /// `delay_probability`: The probability of delaying an order action which is not rejected.
+    /// `delay_ns`: The additional time in nanoseconds until a delayed order action reaches the exchange.
+    ///
+    /// # Returns:
+    /// Either a valid `FaultInjection` or an Error if a probability is outside `[0, 1]` or `delay_ns` is negative.
+    pub fn new(rejection_probability: f64, delay_probability: f64, delay_ns: i64) -> Result<Self> {
+        let valid_probability = |p: f64| (0.0..=1.0).contains(&p);
+        if !valid_probability(rejection_probability)
+            || !valid_probability(delay_probability)
+            || delay_ns < 0
+        {
+            return Err(Error::InvalidFaultInjection);
+        }
+        Ok(Self {
+            rejection_probability,
+            delay_probability,
+            delay_ns,
+        })
+    }
+
+    /// The probability of rejecting an order action.
+    #[inline(always)]
+    pub fn rejection_probability(&self) -> f64 {
+        self.rejection_probability
+    }
+
+    /// The probability of delaying an order action which is not rejected.
+    #[inline(always)]
+    pub fn delay_probability(&self) -> f64 {
+        self.delay_probability
+    }
+
+    /// The additional time in nanoseconds until a delayed order action reaches the exchange.
+    #[inline(always)]
+    pub fn delay_ns(&self) -> i64 {
+        self.delay_ns
+    }
+}
+
+/// Draws the faults of a `FaultInjection` deterministically from a seed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct FaultInjector {
+    fault_injection: Option<FaultInjection>,
+    rng: ChaCha12Rng,
+}
+
+impl FaultInjector {
+    pub(crate) fn new(fault_injection: Option<FaultInjection>, seed: u64) -> Self {
+        Self {
+            fault_injection,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Draw the fault of the next order action.
+    ///
+    /// # Returns:
+    /// The additional delay in nanoseconds, which is zero if the action is not delayed,
+    /// or an Error if it is rejected.
+    pub(crate) fn sample(&mut self) -> Result<i64> {
+        let Some(fault_injection) = self.fault_injection else {
+            return Ok(0);
+        };
+        if self.rng.gen_bool(fault_injection.rejection_probability) {
+            return Err(if self.rng.gen_bool(0.5) {
+                Error::ExchangeOverloaded
+            } else {
+                Error::RequestTimeout
+            });
+        }
+        if self.rng.gen_bool(fault_injection.delay_probability) {
+            return Ok(fault_injection.delay_ns);
+        }
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fault_injection_new() {
+        assert_eq!(
+            FaultInjection::new(-0.1, 0.0, 0),
+            Err(Error::InvalidFaultInjection)
+        );
+        assert_eq!(
+            FaultInjection::new(0.0, 1.1, 0),
+            Err(Error::InvalidFaultInjection)
+        );
+        assert_eq!(
+            FaultInjection::new(0.0, 0.0, -1),
+            Err(Error::InvalidFaultInjection)
+        );
+        assert_eq!(
+            FaultInjection::new(f64::NAN, 0.0, 0),
+            Err(Error::InvalidFaultInjection)
+        );
+    }
+
+    #[test]
+    fn fault_injector_is_deterministic() {
+        let fault_injection = FaultInjection::new(0.2, 0.3, 1_000).unwrap();
+        let draw = |seed| {
+            let mut injector = FaultInjector::new(Some(fault_injection), seed);
+            Vec::from_iter((0..1_000).map(|_| injector.sample()))
+        };
+        let faults = draw(0);
+        assert_eq!(faults, draw(0));
+        assert_ne!(faults, draw(1));
+
+        let rejections = faults.iter().filter(|fault| fault.is_err()).count();
+        let delays = faults.iter().filter(|fault| **fault == Ok(1_000)).count();
+        assert!((150..250).contains(&rejections), "{rejections}");
+        assert!((180..300).contains(&delays), "{delays}");
+        assert!(faults.contains(&Err(Error::ExchangeOverloaded)));
+        assert!(faults.contains(&Err(Error::RequestTimeout)));
+
+        let mut disabled = FaultInjector::new(None, 0);
+        assert!((0..100).all(|_| disabled.sample() == Ok(0)));
+    }
+}
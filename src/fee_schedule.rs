@@ -0,0 +1,212 @@
+//! Maker and taker fees which depend on the rolling traded volume.
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+use fpdec::Decimal;
+
+use crate::types::{Error, Fee, Mon, Result};
+
+/// The default rolling window of 30 days in nanoseconds.
+pub const THIRTY_DAYS_NS: i64 = 30 * 86_400_000_000_000;
+
+/// A volume tier of the `FeeSchedule`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct FeeTier<T = Decimal>
+where
+    T: Mon,
+{
+    /// The minimum rolling traded notional volume, denoted in the margin currency.
+    pub min_volume: T,
+    /// The maker fee as a fraction. e.g.: 2.5 basis points rebate -> -0.00025
+    pub fee_maker: Fee<T>,
+    /// The taker fee as a fraction. e.g.: 10 basis points -> 0.0010
+    pub fee_taker: Fee<T>,
+}
+
+/// Selects the maker and taker fees based on the traded notional volume over a rolling window.
+/// The tier with the highest `min_volume` not exceeding the rolling volume applies.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct FeeSchedule<T = Decimal>
+where
+    T: Mon,
+{
+    window_ns: i64,
+    tiers: Vec<FeeTier<T>>,
+}
+
+impl<T> FeeSchedule<T>
+where
+    T: Mon,
+{
+    /// Create a new `FeeSchedule`.
+    ///
+    /// # Arguments:
+    /// `window_ns`: The length of the rolling volume window in nanoseconds, e.g. `THIRTY_DAYS_NS`.
+    /// `tiers`: The volume tiers in any order. One of them must have a `min_volume` of zero.
+    ///
+    /// # Returns:
+    /// Either a valid `FeeSchedule` or an Error.
+    pub fn new(window_ns: i64, mut tiers: Vec<FeeTier<T>>) -> Result<Self> {
+        tiers.sort_by(|a, b| {
+            a.min_volume
+                .partial_cmp(&b.min_volume)
+                .expect("Volumes are comparable")
+        });
+        if window_ns <= 0 || tiers.first().map(|t| t.min_volume) != Some(T::ZERO) {
+            return Err(Error::InvalidFeeSchedule);
+        }
+
+        Ok(Self { window_ns, tiers })
+    }
+
+    /// A `FeeSchedule` with the same fees regardless of the traded volume.
+    pub fn flat(fee_maker: Fee<T>, fee_taker: Fee<T>) -> Self {
+        Self {
+            window_ns: THIRTY_DAYS_NS,
+            tiers: vec![FeeTier {
+                min_volume: T::ZERO,
+                fee_maker,
+                fee_taker,
+            }],
+        }
+    }
+
+    /// The length of the rolling volume window in nanoseconds.
+    #[inline(always)]
+    pub fn window_ns(&self) -> i64 {
+        self.window_ns
+    }
+
+    /// The volume tiers sorted by ascending `min_volume`.
+    #[inline(always)]
+    pub fn tiers(&self) -> &[FeeTier<T>] {
+        &self.tiers
+    }
+
+    /// The tier which applies to the `rolling_volume`.
+    pub fn tier(&self, rolling_volume: T) -> &FeeTier<T> {
+        let idx = self
+            .tiers
+            .partition_point(|t| t.min_volume <= rolling_volume);
+        &self.tiers[idx.saturating_sub(1)]
+    }
+}
+
+/// Tracks the traded notional volume over a rolling window.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub(crate) struct RollingVolume<T>
+where
+    T: Mon,
+{
+    window_ns: i64,
+    fills: VecDeque<(i64, T)>,
+    volume: T,
+}
+
+impl<T> RollingVolume<T>
+where
+    T: Mon,
+{
+    pub(crate) fn new(window_ns: i64) -> Self {
+        Self {
+            window_ns,
+            fills: VecDeque::new(),
+            volume: T::ZERO,
+        }
+    }
+
+    /// The traded notional volume within the window.
+    #[inline(always)]
+    pub(crate) fn volume(&self) -> T {
+        self.volume
+    }
+
+    /// Log the notional value of a fill at `ts_ns`.
+    pub(crate) fn log_fill(&mut self, ts_ns: i64, notional_value: T) {
+        let notional_value = notional_value.abs();
+        self.fills.push_back((ts_ns, notional_value));
+        self.volume += notional_value;
+    }
+
+    /// Drop all fills which are older than the window at `ts_ns`.
+    pub(crate) fn update_timestamp(&mut self, ts_ns: i64) {
+        while let Some((fill_ts_ns, notional_value)) = self.fills.front() {
+            if *fill_ts_ns > ts_ns - self.window_ns {
+                break;
+            }
+            self.volume -= *notional_value;
+            self.fills.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn schedule() -> FeeSchedule {
+        FeeSchedule::new(
+            100,
+            vec![
+                FeeTier {
+                    min_volume: Dec!(1000),
+                    fee_maker: fee!(0.0001),
+                    fee_taker: fee!(0.0004),
+                },
+                FeeTier {
+                    min_volume: Dec!(0),
+                    fee_maker: fee!(0.0002),
+                    fee_taker: fee!(0.0006),
+                },
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn fee_schedule_new() {
+        assert_eq!(
+            FeeSchedule::<Decimal>::new(100, vec![]),
+            Err(Error::InvalidFeeSchedule)
+        );
+        assert_eq!(
+            FeeSchedule::new(
+                0,
+                vec![FeeTier {
+                    min_volume: Dec!(0),
+                    fee_maker: fee!(0.0002),
+                    fee_taker: fee!(0.0006),
+                }]
+            ),
+            Err(Error::InvalidFeeSchedule)
+        );
+    }
+
+    #[test]
+    fn fee_schedule_tier() {
+        let schedule = schedule();
+        assert_eq!(schedule.tier(Dec!(0)).fee_maker, fee!(0.0002));
+        assert_eq!(schedule.tier(Dec!(999)).fee_maker, fee!(0.0002));
+        assert_eq!(schedule.tier(Dec!(1000)).fee_maker, fee!(0.0001));
+        assert_eq!(schedule.tier(Dec!(5000)).fee_taker, fee!(0.0004));
+    }
+
+    #[test]
+    fn rolling_volume() {
+        let mut volume = RollingVolume::new(100);
+        volume.log_fill(0, Dec!(500));
+        volume.log_fill(50, Dec!(-600));
+        assert_eq!(volume.volume(), Dec!(1100));
+
+        volume.update_timestamp(99);
+        assert_eq!(volume.volume(), Dec!(1100));
+        volume.update_timestamp(100);
+        assert_eq!(volume.volume(), Dec!(600));
+        volume.update_timestamp(150);
+        assert_eq!(volume.volume(), Dec!(0));
+    }
+}
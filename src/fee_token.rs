@@ -0,0 +1,122 @@
+//! Paying fees in a separate asset at a discount, like the BNB fee discount of real venues.
+
+use alloc::string::String;
+
+use crate::types::{Currency, Mon};
+
+/// An asset in which fees are paid at a discount, configured with `Config::with_fee_token`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct FeeToken<T>
+where
+    T: Mon,
+{
+    /// The ticker of the asset, e.g. "BNB".
+    pub ticker: String,
+    /// The fraction by which fees paid in the token are reduced, in range [0, 1), e.g. 0.1 for 10%.
+    pub discount: T,
+}
+
+/// The quantity of the `FeeToken` held by the account and its last conversion price.
+/// It is held apart from the wallet balance, so it does not back any margin.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct FeeTokenBalance<M>
+where
+    M: Currency,
+{
+    token: FeeToken<M::Num>,
+    quantity: M::Num,
+    price: Option<M>,
+}
+
+impl<M> FeeTokenBalance<M>
+where
+    M: Currency,
+{
+    pub(crate) fn new(token: FeeToken<M::Num>) -> Self {
+        Self {
+            token,
+            quantity: M::Num::ZERO,
+            price: None,
+        }
+    }
+
+    /// The configured token.
+    #[inline(always)]
+    pub fn token(&self) -> &FeeToken<M::Num> {
+        &self.token
+    }
+
+    /// The held quantity of the token.
+    #[inline(always)]
+    pub fn quantity(&self) -> M::Num {
+        self.quantity
+    }
+
+    /// The last price of one unit of the token, denoted in the margin currency.
+    /// Fees are paid in the margin currency until the first price arrives.
+    #[inline(always)]
+    pub fn price(&self) -> Option<M> {
+        self.price
+    }
+
+    /// The value of the held quantity at the last price, denoted in the margin currency.
+    pub fn market_value(&self) -> M {
+        self.price
+            .map_or(M::new_zero(), |price| price * self.quantity)
+    }
+
+    pub(crate) fn deposit(&mut self, quantity: M::Num) {
+        self.quantity += quantity;
+    }
+
+    pub(crate) fn withdraw(&mut self, quantity: M::Num) {
+        debug_assert!(quantity <= self.quantity);
+        self.quantity -= quantity;
+    }
+
+    pub(crate) fn set_price(&mut self, price: M) {
+        self.price = Some(price);
+    }
+
+    /// Pay the positive `fee` in the token at its discount.
+    ///
+    /// # Returns:
+    /// The discounted fee denoted in the margin currency,
+    /// or `None` if the held quantity does not cover it, in which case nothing is paid.
+    pub(crate) fn pay(&mut self, fee: M) -> Option<M> {
+        let price = self.price?;
+        let discounted = fee * (M::Num::ONE - self.token.discount);
+        let quantity = (discounted / price).inner();
+        if quantity > self.quantity {
+            return None;
+        }
+        self.quantity -= quantity;
+        Some(discounted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{quote, Dec, Decimal, QuoteCurrency};
+
+    #[test]
+    fn fee_token_balance_pay() {
+        let mut balance = FeeTokenBalance::<QuoteCurrency>::new(FeeToken {
+            ticker: "BNB".to_string(),
+            discount: Dec!(0.1),
+        });
+        balance.deposit(Dec!(2));
+        assert_eq!(balance.pay(quote!(10)), None);
+
+        balance.set_price(quote!(5));
+        assert_eq!(balance.market_value(), quote!(10));
+        assert_eq!(balance.pay(quote!(5)), Some(quote!(4.5)));
+        assert_eq!(balance.quantity(), Dec!(1.1));
+        // Not enough left to cover the discounted fee of 9.
+        assert_eq!(balance.pay(quote!(10)), None);
+        assert_eq!(balance.quantity(), Dec!(1.1));
+    }
+}
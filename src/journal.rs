@@ -0,0 +1,198 @@
+//! A journal of all inputs to and events of an `Exchange`, from which its state can be replayed.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    options::OptionContract,
+    types::{
+        Currency, ExchangeEvent, Leverage, MarketUpdate, Order, QuoteCurrency, Side, UserOrderId,
+    },
+};
+
+/// An input to the `Exchange` or an event it emitted.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+#[allow(clippy::large_enum_variant)]
+pub enum JournalEntry<S>
+where
+    S: Currency,
+{
+    /// A call to `Exchange::update_state`.
+    MarketUpdate {
+        /// The timestamp in nanoseconds of the update.
+        timestamp_ns: u64,
+        /// The market information.
+        update: MarketUpdate<S>,
+    },
+    /// A call to `Exchange::submit_order` with the order as it was submitted.
+    SubmitOrder(Order<S>),
+    /// A call to `Exchange::submit_oco_orders`.
+    SubmitOcoOrders(Order<S>, Order<S>),
+    /// A call to `Exchange::submit_basket_orders`.
+    SubmitBasketOrders(Vec<Order<S>>),
+    /// A call to `Exchange::cancel_order` with the id of the order.
+    CancelOrder(u64),
+    /// A call to `Exchange::cancel_order_by_user_id` with the user id of the order.
+    CancelOrderByUserId(UserOrderId),
+    /// A call to `Exchange::set_leverage`.
+    SetLeverage(Leverage),
+    /// A call to `Exchange::set_position_tpsl`.
+    SetPositionTpSl {
+        /// The take-profit price, if any.
+        take_profit: Option<QuoteCurrency<S::Num>>,
+        /// The stop-loss price, if any.
+        stop_loss: Option<QuoteCurrency<S::Num>>,
+    },
+    /// A call to `Exchange::set_trailing_take_profit`.
+    SetTrailingTakeProfit {
+        /// The profit per unit at which the trailing starts.
+        activation_profit: QuoteCurrency<S::Num>,
+        /// The distance by which the stop trails the peak.
+        offset: QuoteCurrency<S::Num>,
+    },
+    /// A call to `Exchange::cancel_trailing_take_profit`.
+    CancelTrailingTakeProfit,
+    /// A call to `Exchange::deposit` with the amount in the margin currency.
+    Deposit(S::PairedCurrency),
+    /// A call to `Exchange::withdraw` with the amount in the margin currency.
+    Withdraw(S::PairedCurrency),
+    /// A call to `Exchange::deposit_position_margin`.
+    DepositPositionMargin {
+        /// The side of the position.
+        side: Side,
+        /// The deposited amount, denoted in the margin currency.
+        amount: S::PairedCurrency,
+    },
+    /// A call to `Exchange::withdraw_position_margin`.
+    WithdrawPositionMargin {
+        /// The side of the position.
+        side: Side,
+        /// The withdrawn amount, denoted in the margin currency.
+        amount: S::PairedCurrency,
+    },
+    /// A call to `Exchange::deposit_collateral`.
+    DepositCollateral {
+        /// The ticker of the collateral asset.
+        ticker: String,
+        /// The deposited quantity of the asset.
+        quantity: S::Num,
+    },
+    /// A call to `Exchange::withdraw_collateral`.
+    WithdrawCollateral {
+        /// The ticker of the collateral asset.
+        ticker: String,
+        /// The withdrawn quantity of the asset.
+        quantity: S::Num,
+    },
+    /// A call to `Exchange::update_collateral_price`.
+    CollateralPrice {
+        /// The ticker of the collateral asset.
+        ticker: String,
+        /// The price of one unit of the asset, denoted in the margin currency.
+        price: S::PairedCurrency,
+    },
+    /// A call to `Exchange::deposit_fee_token` with the quantity of the token.
+    DepositFeeToken(S::Num),
+    /// A call to `Exchange::withdraw_fee_token` with the quantity of the token.
+    WithdrawFeeToken(S::Num),
+    /// A call to `Exchange::update_fee_token_price` with the price denoted in the margin currency.
+    FeeTokenPrice(S::PairedCurrency),
+    /// A call to `Exchange::trade_option`.
+    TradeOption {
+        /// The option traded.
+        contract: OptionContract<S::Num>,
+        /// `Buy` or `Sell`.
+        side: Side,
+        /// The absolute number of contracts of the underlying.
+        quantity: S,
+        /// The premium paid or received.
+        premium: S::PairedCurrency,
+    },
+    /// An event emitted by the `Exchange`.
+    Event {
+        /// The timestamp in nanoseconds of the event.
+        ts_ns: i64,
+        /// The event itself.
+        event: ExchangeEvent<S>,
+    },
+}
+
+impl<S> JournalEntry<S>
+where
+    S: Currency,
+{
+    /// Whether the entry is an input to the `Exchange`, as opposed to an event it emitted.
+    #[inline(always)]
+    pub fn is_input(&self) -> bool {
+        !matches!(self, JournalEntry::Event { .. })
+    }
+}
+
+/// All inputs to and events of an `Exchange` in the order they happened,
+/// recorded between [`Exchange::start_recording`](crate::prelude::Exchange::start_recording)
+/// and [`Exchange::stop_recording`](crate::prelude::Exchange::stop_recording).
+/// Its inputs can be applied again with [`Exchange::replay`](crate::prelude::Exchange::replay).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Journal<S>
+where
+    S: Currency,
+{
+    entries: Vec<JournalEntry<S>>,
+}
+
+impl<S> Default for Journal<S>
+where
+    S: Currency,
+{
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<S> Journal<S>
+where
+    S: Currency,
+{
+    /// The recorded entries in the order they happened.
+    #[inline(always)]
+    pub fn entries(&self) -> &[JournalEntry<S>] {
+        &self.entries
+    }
+
+    pub(crate) fn push(&mut self, entry: JournalEntry<S>) {
+        self.entries.push(entry);
+    }
+
+    /// Write the journal with one JSON object per entry and line.
+    #[cfg(feature = "serde")]
+    pub fn write_json_lines<W>(&self, mut writer: W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        for entry in self.entries.iter() {
+            serde_json::to_writer(&mut writer, entry)?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Read a journal written with `write_json_lines`.
+    #[cfg(feature = "serde")]
+    pub fn read_json_lines<R>(reader: R) -> std::io::Result<Self>
+    where
+        R: std::io::BufRead,
+    {
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(serde_json::from_str(&line)?);
+        }
+        Ok(Self { entries })
+    }
+}
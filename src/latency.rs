@@ -0,0 +1,140 @@
+//! Simulated latency between the user and the exchange.
+
+use alloc::sync::Arc;
+use core::fmt::Debug;
+
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::types::{Error, Result};
+
+/// A user-supplied distribution of latencies.
+pub trait LatencyDistribution: Debug + Send + Sync {
+    /// Draw a latency in nanoseconds, which must not be negative.
+    fn sample(&self, rng: &mut dyn RngCore) -> i64;
+}
+
+/// Defines how long it takes for an order submission or cancellation to reach the exchange.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LatencyModel {
+    /// Requests take effect immediately.
+    #[default]
+    None,
+    /// Every request takes the same number of nanoseconds.
+    Fixed(i64),
+    /// The latency is drawn uniformly from the inclusive range in nanoseconds.
+    Uniform {
+        /// The smallest possible latency.
+        min_ns: i64,
+        /// The largest possible latency.
+        max_ns: i64,
+    },
+    /// The latency is drawn from a user-supplied distribution.
+    /// Not serializable, so it must be configured again after resuming from a checkpoint.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Arc<dyn LatencyDistribution>),
+}
+
+impl LatencyModel {
+    /// Make sure the latencies can never be negative.
+    pub(crate) fn validate(&self) -> Result<()> {
+        let valid = match self {
+            LatencyModel::None | LatencyModel::Custom(_) => true,
+            LatencyModel::Fixed(latency_ns) => *latency_ns >= 0,
+            LatencyModel::Uniform { min_ns, max_ns } => *min_ns >= 0 && min_ns <= max_ns,
+        };
+        if !valid {
+            return Err(Error::InvalidLatencyModel);
+        }
+        Ok(())
+    }
+}
+
+/// Draws the latencies of a `LatencyModel` deterministically from a seed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct LatencySampler {
+    model: LatencyModel,
+    rng: ChaCha12Rng,
+}
+
+impl LatencySampler {
+    pub(crate) fn new(model: LatencyModel, seed: u64) -> Self {
+        Self {
+            model,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Whether requests are delayed at all.
+    #[inline(always)]
+    pub(crate) fn is_enabled(&self) -> bool {
+        !matches!(self.model, LatencyModel::None)
+    }
+
+    /// Draw the latency of the next request in nanoseconds.
+    pub(crate) fn sample(&mut self) -> i64 {
+        match &self.model {
+            LatencyModel::None => 0,
+            LatencyModel::Fixed(latency_ns) => *latency_ns,
+            LatencyModel::Uniform { min_ns, max_ns } => self.rng.gen_range(*min_ns..=*max_ns),
+            LatencyModel::Custom(distribution) => distribution.sample(&mut self.rng).max(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Alternating;
+
+    impl LatencyDistribution for Alternating {
+        fn sample(&self, rng: &mut dyn RngCore) -> i64 {
+            if rng.gen_bool(0.5) {
+                10
+            } else {
+                -10
+            }
+        }
+    }
+
+    #[test]
+    fn latency_model_validate() {
+        assert!(LatencyModel::Fixed(0).validate().is_ok());
+        assert_eq!(
+            LatencyModel::Fixed(-1).validate(),
+            Err(Error::InvalidLatencyModel)
+        );
+        assert_eq!(
+            LatencyModel::Uniform {
+                min_ns: 10,
+                max_ns: 5
+            }
+            .validate(),
+            Err(Error::InvalidLatencyModel)
+        );
+    }
+
+    #[test]
+    fn latency_sampler_deterministic() {
+        let model = LatencyModel::Uniform {
+            min_ns: 100,
+            max_ns: 200,
+        };
+        let mut a = LatencySampler::new(model.clone(), 42);
+        let mut b = LatencySampler::new(model, 42);
+        for _ in 0..100 {
+            let latency = a.sample();
+            assert!((100..=200).contains(&latency));
+            assert_eq!(latency, b.sample());
+        }
+
+        let mut custom = LatencySampler::new(LatencyModel::Custom(Arc::new(Alternating)), 0);
+        for _ in 0..10 {
+            assert!([0, 10].contains(&custom.sample()));
+        }
+    }
+}
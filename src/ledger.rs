@@ -0,0 +1,136 @@
+//! A ledger of every change of the wallet balance, to reconcile it exactly.
+
+use alloc::vec::Vec;
+
+use crate::types::Currency;
+
+/// The reason of a change of the wallet balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LedgerEntryKind {
+    /// The profit or loss realized by reducing a position, excluding fees.
+    RealizedPnl,
+    /// The fee of a passively filled limit order, negative for rebates.
+    MakerFee,
+    /// The fee of an order executed immediately as taker.
+    TakerFee,
+    /// The fee of closing the positions in a liquidation.
+    LiquidationFee,
+    /// The interest on the borrowed notional of leveraged positions or the borrowed quantity of short positions,
+    /// as emitted in `ExchangeEvent::BorrowCost`.
+    BorrowCost,
+    /// The interest earned on idle margin, as emitted in `ExchangeEvent::Interest`.
+    Interest,
+    /// A deficit of a bankruptcy covered by the insurance fund.
+    InsuranceFundPayout,
+    /// The premium paid or received for trading an option.
+    OptionPremium,
+    /// The payoff of an option exercised at its expiry.
+    OptionPayoff,
+    /// An external deposit.
+    Deposit,
+    /// An external withdrawal.
+    Withdrawal,
+    /// Collateral sold to cover a negative wallet balance.
+    CollateralConversion,
+}
+
+/// A single change of the wallet balance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct LedgerEntry<M>
+where
+    M: Currency,
+{
+    /// The timestamp in nanoseconds of the change.
+    pub ts_ns: i64,
+    /// The reason of the change.
+    pub kind: LedgerEntryKind,
+    /// The signed change of the wallet balance, denoted in the margin currency.
+    pub amount: M,
+    /// The wallet balance after the change.
+    pub balance: M,
+}
+
+/// Records every change of the wallet balance of an `Account`,
+/// such that the starting balance plus the sum of all amounts equals the wallet balance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Ledger<M>
+where
+    M: Currency,
+{
+    entries: Vec<LedgerEntry<M>>,
+}
+
+impl<M> Default for Ledger<M>
+where
+    M: Currency,
+{
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<M> Ledger<M>
+where
+    M: Currency,
+{
+    /// Append an entry, unless the `amount` is zero.
+    pub(crate) fn record(&mut self, ts_ns: i64, kind: LedgerEntryKind, amount: M, balance: M) {
+        if amount.is_zero() {
+            return;
+        }
+        self.entries.push(LedgerEntry {
+            ts_ns,
+            kind,
+            amount,
+            balance,
+        });
+    }
+
+    /// All entries in chronological order.
+    #[inline(always)]
+    pub fn entries(&self) -> &[LedgerEntry<M>] {
+        &self.entries
+    }
+
+    /// The entries of a `kind` in chronological order.
+    pub fn entries_of(&self, kind: LedgerEntryKind) -> impl Iterator<Item = &LedgerEntry<M>> {
+        self.entries.iter().filter(move |entry| entry.kind == kind)
+    }
+
+    /// The sum of the amounts of a `kind`.
+    pub fn total(&self, kind: LedgerEntryKind) -> M {
+        self.entries_of(kind)
+            .fold(M::new_zero(), |acc, entry| acc + entry.amount)
+    }
+
+    /// The sum of all amounts, which is the change of the wallet balance since the start.
+    pub fn net_change(&self) -> M {
+        self.entries
+            .iter()
+            .fold(M::new_zero(), |acc, entry| acc + entry.amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn ledger() {
+        let mut ledger = Ledger::default();
+        ledger.record(0, LedgerEntryKind::Deposit, quote!(100), quote!(1100));
+        ledger.record(1, LedgerEntryKind::TakerFee, quote!(-0.5), quote!(1099.5));
+        ledger.record(1, LedgerEntryKind::RealizedPnl, quote!(0), quote!(1099.5));
+        ledger.record(2, LedgerEntryKind::TakerFee, quote!(-0.25), quote!(1099.25));
+        assert_eq!(ledger.entries().len(), 3);
+        assert_eq!(ledger.total(LedgerEntryKind::TakerFee), quote!(-0.75));
+        assert_eq!(ledger.total(LedgerEntryKind::MakerFee), quote!(0));
+        assert_eq!(ledger.net_change(), quote!(99.25));
+    }
+}
@@ -1,9 +1,12 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(missing_docs, rustdoc::missing_crate_level_docs, unused_imports)]
 #![warn(clippy::all)]
 #![doc = include_str!("../README.md")]
 
 //! lfest - leveraged futures exchange for simulated trading
 
+extern crate alloc;
+
 #[macro_use]
 extern crate log;
 #[macro_use]
@@ -11,17 +14,51 @@ extern crate serde;
 
 mod account;
 pub mod account_tracker;
+mod borrow_pool;
+mod candle_path;
+mod circuit_breaker;
 mod clearing_house;
+mod collateral;
 mod config;
+mod config_builder;
 mod contract_specification;
 mod cornish_fisher;
+mod cross_exchange;
+pub mod data;
+mod decision_latency;
 mod exchange;
+#[cfg(feature = "tokio")]
+mod exchange_actor;
+#[cfg(feature = "serde")]
+mod exchange_spec;
+mod fault_injection;
+mod fee_schedule;
+mod fee_token;
+mod journal;
+mod latency;
+mod ledger;
+mod lockstep;
+mod maker_program;
+mod mark_price;
 mod market_state;
 mod mock_exchange;
+mod options;
+mod order_book;
 mod order_filters;
 mod order_margin;
 mod position;
+mod position_history;
+mod position_limit;
+mod position_tpsl;
+pub mod presets;
+mod quanto;
+mod rate_limit;
 mod risk_engine;
+mod session_calendar;
+mod slippage;
+mod snapshot;
+mod strategy;
+mod sub_accounts;
 #[cfg(test)]
 mod test_helpers;
 #[cfg(test)]
@@ -29,7 +66,9 @@ mod tests;
 mod types;
 mod utils;
 
-pub use mock_exchange::{mock_exchange_base, mock_exchange_quote};
+pub use mock_exchange::{
+    mock_config_base, mock_contract_specification_base, mock_exchange_base, mock_exchange_quote,
+};
 pub use types::Result;
 
 /// Exports common types
@@ -37,19 +76,61 @@ pub mod prelude {
     // To make the macros work
     pub use fpdec::{self, Dec, Decimal};
 
+    #[cfg(feature = "tokio")]
+    pub use crate::exchange_actor::{ActorEvent, ExchangeActor, ExchangeCommand};
+    #[cfg(feature = "serde")]
+    pub use crate::exchange_spec::{
+        ContractType, ExchangeSpec, FeeSpec, NotionalFilterSpec, QuantityFilterSpec, SpecFormat,
+    };
     pub use crate::{
         account::Account,
         account_tracker::AccountTracker,
         base, bba,
+        borrow_pool::BorrowPool,
+        candle_path::CandlePath,
+        circuit_breaker::CircuitBreaker,
+        collateral::{CollateralAsset, CollateralBalance},
         config::Config,
+        config_builder::ConfigBuilder,
         contract_specification::*,
+        cross_exchange::{CrossExchange, PendingTransfer},
+        decision_latency::DecisionLatency,
         exchange::Exchange,
-        fee, leverage,
+        fault_injection::FaultInjection,
+        fee,
+        fee_schedule::{FeeSchedule, FeeTier, THIRTY_DAYS_NS},
+        fee_token::{FeeToken, FeeTokenBalance},
+        journal::{Journal, JournalEntry},
+        latency::{LatencyDistribution, LatencyModel},
+        ledger::{Ledger, LedgerEntry, LedgerEntryKind},
+        leverage,
+        lockstep::{Divergence, Lockstep},
+        maker_program::{MakerProgram, MakerProgramTier},
+        mark_price::{
+            EmaOfMid, IndexSeries, LastIndexPrice, MarkPriceSource, MedianOfTrades, MidPrice,
+        },
         market_state::MarketState,
-        order_filters::{PriceFilter, QuantityFilter},
+        options::{OptionContract, OptionKind, OptionPosition},
+        order_filters::{
+            MaxNumOrdersFilter, NotionalFilter, PriceFilter, QuantityFilter, SideMultipliers,
+        },
+        order_margin::{MaxOfSides, OrderMarginModel, SumOfSides},
         position::Position,
+        position_history::{PositionChange, PositionChangeKind, PositionHistory},
+        position_limit::{LeverageBracket, PositionLimit},
+        position_tpsl::{PositionTpSl, TrailingTakeProfit},
+        quanto::Quanto,
         quote,
-        risk_engine::RiskError,
+        rate_limit::RateLimit,
+        risk_engine::{RiskEngine, RiskError},
+        session_calendar::SessionCalendar,
+        slippage::{
+            DepthSlippage, FixedBpsSlippage, NoSlippage, SlippageModel, SquareRootImpact,
+            WalkTheBook,
+        },
+        snapshot::ExchangeSnapshot,
+        strategy::{Backtest, BacktestSummary, Strategy},
+        sub_accounts::SubAccounts,
         types::*,
     };
 }
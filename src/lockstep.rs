@@ -0,0 +1,257 @@
+//! Runs the exact `Decimal` and the fast `f64` backend in lockstep, to quantify the accuracy cost of the latter.
+
+use alloc::vec::Vec;
+
+use fpdec::Decimal;
+
+use crate::{
+    account_tracker::NoAccountTracker,
+    exchange::Exchange,
+    types::{Currency, MarginCurrency, MarketUpdate, Mon, Order, QuoteCurrency},
+};
+
+/// The absolute differences between the `f64` and the `Decimal` exchange after a step of a `Lockstep` run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Divergence {
+    /// The index of the step, counting both market updates and order submissions.
+    pub step: usize,
+    /// The timestamp in nanoseconds of the latest market update.
+    pub timestamp_ns: u64,
+    /// Whether the backends disagree on the outcome of the step,
+    /// e.g. only one of them rejected an order or the number of executed orders differs.
+    pub outcome_differs: bool,
+    /// The difference of the wallet balances, denoted in the margin currency.
+    pub wallet_balance: f64,
+    /// The larger difference of the long and the short position sizes.
+    pub position_size: f64,
+    /// The larger difference of the long and the short position entry prices.
+    pub entry_price: f64,
+    /// The difference of the order margins, denoted in the margin currency.
+    pub order_margin: f64,
+}
+
+impl Divergence {
+    /// The largest of the differences.
+    pub fn max(&self) -> f64 {
+        self.wallet_balance
+            .max(self.position_size)
+            .max(self.entry_price)
+            .max(self.order_margin)
+    }
+
+    /// Whether the outcome differs or any difference exceeds the absolute `tolerance`.
+    pub fn exceeds(&self, tolerance: f64) -> bool {
+        self.outcome_differs || self.max() > tolerance
+    }
+}
+
+/// Runs an `Exchange` with the `Decimal` backend and one with the `f64` backend in lockstep,
+/// feeding both the same market updates and orders and recording their `Divergence` after every step.
+/// The inputs are given for the `Decimal` backend and converted for the `f64` one,
+/// so the divergence includes the rounding of the inputs themselves.
+/// The two exchanges must be created with equivalent `Config`s.
+#[derive(Debug, Clone)]
+pub struct Lockstep<S, F>
+where
+    S: Currency<Num = Decimal>,
+    S::PairedCurrency: MarginCurrency,
+    F: Currency<Num = f64>,
+    F::PairedCurrency: MarginCurrency,
+{
+    decimal: Exchange<NoAccountTracker, S>,
+    float: Exchange<NoAccountTracker, F>,
+    timestamp_ns: u64,
+    divergences: Vec<Divergence>,
+}
+
+impl<S, F> Lockstep<S, F>
+where
+    S: Currency<Num = Decimal>,
+    S::PairedCurrency: MarginCurrency,
+    F: Currency<Num = f64>,
+    F::PairedCurrency: MarginCurrency,
+{
+    /// Create a new `Lockstep` run of the `decimal` and the `float` exchange.
+    pub fn new(
+        decimal: Exchange<NoAccountTracker, S>,
+        float: Exchange<NoAccountTracker, F>,
+    ) -> Self {
+        Self {
+            decimal,
+            float,
+            timestamp_ns: 0,
+            divergences: Vec::new(),
+        }
+    }
+
+    /// Return the exchange with the `Decimal` backend.
+    #[inline(always)]
+    pub fn decimal(&self) -> &Exchange<NoAccountTracker, S> {
+        &self.decimal
+    }
+
+    /// Return the exchange with the `f64` backend.
+    #[inline(always)]
+    pub fn float(&self) -> &Exchange<NoAccountTracker, F> {
+        &self.float
+    }
+
+    /// Apply the `market_update` to both exchanges, see `Exchange::update_state`.
+    ///
+    /// # Returns:
+    /// The `Divergence` after the update.
+    pub fn update_state(
+        &mut self,
+        timestamp_ns: u64,
+        market_update: MarketUpdate<S>,
+    ) -> Divergence {
+        self.timestamp_ns = timestamp_ns;
+        let float_update = convert_market_update(&market_update);
+        let decimal = self.decimal.update_state(timestamp_ns, market_update);
+        let float = self.float.update_state(timestamp_ns, float_update);
+        self.record(decimal.as_ref().map(Vec::len) != float.as_ref().map(Vec::len))
+    }
+
+    /// Submit the `order` to both exchanges, see `Exchange::submit_order`.
+    ///
+    /// # Returns:
+    /// The `Divergence` after the submission.
+    pub fn submit_order(&mut self, order: Order<S>) -> Divergence {
+        let float_order = convert_order(&order);
+        let decimal = self.decimal.submit_order(order);
+        let float = self.float.submit_order(float_order);
+        self.record(decimal.as_ref().err() != float.as_ref().err())
+    }
+
+    /// The `Divergence` after every step so far, in the order of the steps.
+    #[inline(always)]
+    pub fn divergences(&self) -> &[Divergence] {
+        &self.divergences
+    }
+
+    /// The first step whose `Divergence` exceeds the absolute `tolerance`, if any.
+    pub fn first_divergence(&self, tolerance: f64) -> Option<&Divergence> {
+        self.divergences.iter().find(|d| d.exceeds(tolerance))
+    }
+
+    fn record(&mut self, outcome_differs: bool) -> Divergence {
+        let decimal = self.decimal.account();
+        let float = self.float.account();
+        let diff = |a: Decimal, b: f64| (a.to_f64() - b).abs();
+        let positions = [
+            (decimal.position(), float.position()),
+            (decimal.short_position(), float.short_position()),
+        ];
+        let divergence = Divergence {
+            step: self.divergences.len(),
+            timestamp_ns: self.timestamp_ns,
+            outcome_differs,
+            wallet_balance: diff(
+                decimal.wallet_balance().inner(),
+                float.wallet_balance().inner(),
+            ),
+            position_size: positions.iter().fold(0.0, |acc, (d, f)| {
+                acc.max(diff(d.size().inner(), f.size().inner()))
+            }),
+            entry_price: positions.iter().fold(0.0, |acc, (d, f)| {
+                acc.max(diff(d.entry_price().inner(), f.entry_price().inner()))
+            }),
+            order_margin: diff(decimal.order_margin().inner(), float.order_margin().inner()),
+        };
+        self.divergences.push(divergence);
+        divergence
+    }
+}
+
+fn convert_price(price: QuoteCurrency<Decimal>) -> QuoteCurrency<f64> {
+    QuoteCurrency::new(price.inner().to_f64())
+}
+
+fn convert_quantity<S, F>(quantity: S) -> F
+where
+    S: Currency<Num = Decimal>,
+    F: Currency<Num = f64>,
+{
+    F::new(quantity.inner().to_f64())
+}
+
+fn convert_market_update<S, F>(market_update: &MarketUpdate<S>) -> MarketUpdate<F>
+where
+    S: Currency<Num = Decimal>,
+    F: Currency<Num = f64>,
+{
+    let convert_levels = |levels: &[(QuoteCurrency<Decimal>, S)]| {
+        Vec::from_iter(
+            levels
+                .iter()
+                .map(|(price, quantity)| (convert_price(*price), convert_quantity(*quantity))),
+        )
+    };
+    match market_update {
+        MarketUpdate::Bba { bid, ask } => MarketUpdate::Bba {
+            bid: convert_price(*bid),
+            ask: convert_price(*ask),
+        },
+        MarketUpdate::Trade {
+            price,
+            quantity,
+            side,
+        } => MarketUpdate::Trade {
+            price: convert_price(*price),
+            quantity: convert_quantity(*quantity),
+            side: *side,
+        },
+        MarketUpdate::Candle {
+            bid,
+            ask,
+            low,
+            high,
+        } => MarketUpdate::Candle {
+            bid: convert_price(*bid),
+            ask: convert_price(*ask),
+            low: convert_price(*low),
+            high: convert_price(*high),
+        },
+        MarketUpdate::Index { price } => MarketUpdate::Index {
+            price: convert_price(*price),
+        },
+        MarketUpdate::Depth { bids, asks } => MarketUpdate::Depth {
+            bids: convert_levels(bids),
+            asks: convert_levels(asks),
+        },
+    }
+}
+
+fn convert_order<S, F>(order: &Order<S>) -> Order<F>
+where
+    S: Currency<Num = Decimal>,
+    F: Currency<Num = f64>,
+{
+    const VALID: &str = "The positive values of a valid order remain positive; qed";
+    let quantity = convert_quantity(order.quantity());
+    let mut converted = match order.limit_price() {
+        Some(limit_price) => Order::limit(order.side(), convert_price(limit_price), quantity),
+        None => Order::market(order.side(), quantity),
+    }
+    .expect(VALID);
+    converted.set_time_in_force(order.time_in_force());
+    converted.set_reduce_only(order.reduce_only());
+    converted.set_trigger_source(order.trigger_source());
+    if let Some(user_order_id) = order.user_order_id() {
+        converted.set_user_order_id(user_order_id.clone());
+    }
+    if let Some(display_quantity) = order.display_quantity() {
+        converted
+            .set_display_quantity(convert_quantity(display_quantity))
+            .expect(VALID);
+    }
+    if let Some(protective_stop) = order.protective_stop() {
+        converted.set_protective_stop(convert_price(protective_stop));
+    }
+    if let Some(stop_price) = order.stop_price() {
+        converted
+            .set_stop_price(convert_price(stop_price))
+            .expect(VALID);
+    }
+    converted
+}
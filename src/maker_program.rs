@@ -0,0 +1,228 @@
+//! Venue liquidity programs, where providing a minimum share of maker volume earns improved fees.
+
+use alloc::vec::Vec;
+
+use fpdec::Decimal;
+
+use crate::types::{Error, Fee, Mon, Result};
+
+/// A fee level of a `MakerProgram`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct MakerProgramTier<T = Decimal>
+where
+    T: Mon,
+{
+    /// The minimum fraction of the traded notional volume that must have been
+    /// provided as maker during the previous period, in range [0, 1].
+    pub min_maker_share: T,
+    /// The maker fee which applies while the tier is active.
+    pub fee_maker: Fee<T>,
+    /// The taker fee which applies while the tier is active.
+    pub fee_taker: Fee<T>,
+}
+
+/// A liquidity program of the venue.
+/// At the end of each period the maker share of the traded notional volume is evaluated
+/// and the fees of the highest qualifying tier apply during the next period.
+/// If no tier qualifies, the fees of the `FeeSchedule` apply.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct MakerProgram<T = Decimal>
+where
+    T: Mon,
+{
+    period_ns: i64,
+    tiers: Vec<MakerProgramTier<T>>,
+}
+
+impl<T> MakerProgram<T>
+where
+    T: Mon,
+{
+    /// Create a new `MakerProgram`.
+    ///
+    /// # Arguments:
+    /// `period_ns`: The length of an evaluation period in nanoseconds.
+    /// Periods are aligned to the unix epoch.
+    /// `tiers`: The fee levels of the program, in any order.
+    ///
+    /// # Returns:
+    /// Either a valid `MakerProgram` or an Error.
+    pub fn new(period_ns: i64, mut tiers: Vec<MakerProgramTier<T>>) -> Result<Self> {
+        if period_ns <= 0
+            || tiers
+                .iter()
+                .any(|t| t.min_maker_share < T::ZERO || t.min_maker_share > T::ONE)
+        {
+            return Err(Error::InvalidMakerProgram);
+        }
+        tiers.sort_by(|a, b| {
+            a.min_maker_share
+                .partial_cmp(&b.min_maker_share)
+                .expect("Shares are comparable")
+        });
+
+        Ok(Self { period_ns, tiers })
+    }
+
+    /// The length of an evaluation period in nanoseconds.
+    #[inline(always)]
+    pub fn period_ns(&self) -> i64 {
+        self.period_ns
+    }
+
+    /// The fee levels of the program, sorted by ascending `min_maker_share`.
+    #[inline(always)]
+    pub fn tiers(&self) -> &[MakerProgramTier<T>] {
+        &self.tiers
+    }
+}
+
+/// Tracks the maker volume of the current period and the resulting tier of a `MakerProgram`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub(crate) struct MakerProgramState<T>
+where
+    T: Mon,
+{
+    program: MakerProgram<T>,
+    period: Option<i64>,
+    maker_volume: T,
+    total_volume: T,
+    tier: Option<usize>,
+}
+
+impl<T> MakerProgramState<T>
+where
+    T: Mon,
+{
+    pub(crate) fn new(program: MakerProgram<T>) -> Self {
+        Self {
+            program,
+            period: None,
+            maker_volume: T::ZERO,
+            total_volume: T::ZERO,
+            tier: None,
+        }
+    }
+
+    /// Evaluate the maker share once a period has ended.
+    ///
+    /// # Returns:
+    /// true if the active tier changed.
+    pub(crate) fn update_timestamp(&mut self, ts_ns: i64) -> bool {
+        let period = ts_ns.div_euclid(self.program.period_ns);
+        let Some(prev_period) = self.period.replace(period) else {
+            return false;
+        };
+        if period == prev_period {
+            return false;
+        }
+        // Skipped periods without any volume do not qualify for any tier.
+        let maker_share = if period == prev_period + 1 && self.total_volume > T::ZERO {
+            self.maker_volume / self.total_volume
+        } else {
+            T::ZERO
+        };
+        self.maker_volume = T::ZERO;
+        self.total_volume = T::ZERO;
+
+        let tier = self
+            .program
+            .tiers
+            .iter()
+            .rposition(|t| maker_share >= t.min_maker_share);
+        let changed = tier != self.tier;
+        self.tier = tier;
+        changed
+    }
+
+    /// Log the notional value of a fill for the current period.
+    pub(crate) fn log_fill(&mut self, notional_value: T, is_maker: bool) {
+        let notional_value = notional_value.abs();
+        self.total_volume += notional_value;
+        if is_maker {
+            self.maker_volume += notional_value;
+        }
+    }
+
+    /// The currently active tier, if any.
+    pub(crate) fn tier(&self) -> Option<&MakerProgramTier<T>> {
+        self.tier.map(|idx| &self.program.tiers[idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn program() -> MakerProgram {
+        MakerProgram::new(
+            100,
+            vec![
+                MakerProgramTier {
+                    min_maker_share: Dec!(0.8),
+                    fee_maker: fee!(-0.0001),
+                    fee_taker: fee!(0.0004),
+                },
+                MakerProgramTier {
+                    min_maker_share: Dec!(0.5),
+                    fee_maker: fee!(0.0),
+                    fee_taker: fee!(0.0005),
+                },
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn maker_program_new() {
+        let program = program();
+        assert_eq!(program.tiers()[0].min_maker_share, Dec!(0.5));
+        assert_eq!(
+            MakerProgram::<Decimal>::new(0, vec![]),
+            Err(Error::InvalidMakerProgram)
+        );
+        assert_eq!(
+            MakerProgram::new(
+                100,
+                vec![MakerProgramTier {
+                    min_maker_share: Dec!(1.1),
+                    fee_maker: fee!(0.0),
+                    fee_taker: fee!(0.0),
+                }]
+            ),
+            Err(Error::InvalidMakerProgram)
+        );
+    }
+
+    #[test]
+    fn maker_program_state() {
+        let mut state = MakerProgramState::new(program());
+        assert!(!state.update_timestamp(0));
+        state.log_fill(Dec!(60), true);
+        state.log_fill(Dec!(-40), false);
+        assert!(!state.update_timestamp(99));
+        assert_eq!(state.tier(), None);
+
+        // 60% maker share qualifies for the lower tier.
+        assert!(state.update_timestamp(100));
+        assert_eq!(state.tier(), Some(&program().tiers()[0]));
+
+        state.log_fill(Dec!(90), true);
+        state.log_fill(Dec!(10), false);
+        assert!(state.update_timestamp(200));
+        assert_eq!(state.tier(), Some(&program().tiers()[1]));
+
+        // A period without any volume loses the tier.
+        assert!(state.update_timestamp(300));
+        assert_eq!(state.tier(), None);
+
+        // Skipping periods does not qualify either.
+        state.log_fill(Dec!(100), true);
+        assert!(!state.update_timestamp(500));
+        assert_eq!(state.tier(), None);
+    }
+}
@@ -0,0 +1,370 @@
+//! Derives the mark price at which positions are valued for liquidations.
+
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
+use core::fmt::Debug;
+
+use fpdec::Decimal;
+
+use crate::types::{Currency, Error, Mon, QuoteCurrency, Result};
+
+/// Derives the mark price from the market updates, as venues differ in how they do it.
+/// The mark price determines the maintenance margin and thus when positions get liquidated,
+/// while orders keep executing at the best bid and ask.
+/// The generic `T` is the numeric type of the prices.
+pub trait MarkPriceSource<T = Decimal>: Debug + Send + Sync
+where
+    T: Mon,
+{
+    /// Update with the latest market information.
+    ///
+    /// # Arguments:
+    /// `timestamp_ns`: The timestamp of the market update in nanoseconds.
+    /// `bid`: The current best bid.
+    /// `ask`: The current best ask.
+    /// `trade_price`: The price of the trade, if the market update is a `MarketUpdate::Trade`.
+    /// `index_price`: The latest price of the external index from a `MarketUpdate::Index`, if any.
+    ///
+    /// # Returns:
+    /// The new mark price.
+    fn update(
+        &mut self,
+        timestamp_ns: i64,
+        bid: QuoteCurrency<T>,
+        ask: QuoteCurrency<T>,
+        trade_price: Option<QuoteCurrency<T>>,
+        index_price: Option<QuoteCurrency<T>>,
+    ) -> QuoteCurrency<T>;
+
+    /// Clone into a new box, so every `Exchange` gets its own state.
+    fn box_clone(&self) -> Box<dyn MarkPriceSource<T>>;
+}
+
+impl<T> Clone for Box<dyn MarkPriceSource<T>>
+where
+    T: Mon,
+{
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+fn mid<T: Mon>(bid: QuoteCurrency<T>, ask: QuoteCurrency<T>) -> QuoteCurrency<T> {
+    (bid + ask) / T::TWO
+}
+
+/// The mark price is the mid price, which is the default.
+#[derive(Debug, Clone, Default)]
+pub struct MidPrice;
+
+impl<T> MarkPriceSource<T> for MidPrice
+where
+    T: Mon,
+{
+    fn update(
+        &mut self,
+        _timestamp_ns: i64,
+        bid: QuoteCurrency<T>,
+        ask: QuoteCurrency<T>,
+        _trade_price: Option<QuoteCurrency<T>>,
+        _index_price: Option<QuoteCurrency<T>>,
+    ) -> QuoteCurrency<T> {
+        mid(bid, ask)
+    }
+
+    fn box_clone(&self) -> Box<dyn MarkPriceSource<T>> {
+        Box::new(self.clone())
+    }
+}
+
+/// The mark price is the latest price of the external index from a `MarketUpdate::Index`,
+/// falling back to the mid price before the first one.
+#[derive(Debug, Clone, Default)]
+pub struct LastIndexPrice;
+
+impl<T> MarkPriceSource<T> for LastIndexPrice
+where
+    T: Mon,
+{
+    fn update(
+        &mut self,
+        _timestamp_ns: i64,
+        bid: QuoteCurrency<T>,
+        ask: QuoteCurrency<T>,
+        _trade_price: Option<QuoteCurrency<T>>,
+        index_price: Option<QuoteCurrency<T>>,
+    ) -> QuoteCurrency<T> {
+        index_price.unwrap_or_else(|| mid(bid, ask))
+    }
+
+    fn box_clone(&self) -> Box<dyn MarkPriceSource<T>> {
+        Box::new(self.clone())
+    }
+}
+
+/// The mark price is an exponential moving average of the mid price over the market updates,
+/// which smoothes out short spikes.
+#[derive(Debug, Clone)]
+pub struct EmaOfMid<T = Decimal>
+where
+    T: Mon,
+{
+    alpha: T,
+    ema: Option<QuoteCurrency<T>>,
+}
+
+impl<T> EmaOfMid<T>
+where
+    T: Mon,
+{
+    /// Create a new `EmaOfMid`.
+    ///
+    /// # Arguments:
+    /// `alpha`: The weight of the latest mid price, in `(0, 1]`.
+    ///
+    /// # Returns:
+    /// Either a valid `EmaOfMid` or an Error if `alpha` is out of range.
+    pub fn new(alpha: T) -> Result<Self> {
+        if alpha <= T::ZERO || alpha > T::ONE {
+            return Err(Error::InvalidMarkPriceSource);
+        }
+        Ok(Self { alpha, ema: None })
+    }
+}
+
+impl<T> MarkPriceSource<T> for EmaOfMid<T>
+where
+    T: Mon + 'static,
+{
+    fn update(
+        &mut self,
+        _timestamp_ns: i64,
+        bid: QuoteCurrency<T>,
+        ask: QuoteCurrency<T>,
+        _trade_price: Option<QuoteCurrency<T>>,
+        _index_price: Option<QuoteCurrency<T>>,
+    ) -> QuoteCurrency<T> {
+        let mid = mid(bid, ask);
+        let ema = match self.ema {
+            Some(ema) => ema + (mid - ema) * self.alpha,
+            None => mid,
+        };
+        self.ema = Some(ema);
+        ema
+    }
+
+    fn box_clone(&self) -> Box<dyn MarkPriceSource<T>> {
+        Box::new(self.clone())
+    }
+}
+
+/// The mark price is the median of the last `n` trade prices,
+/// which makes it robust to single outlier prints.
+/// Falls back to the mid price before the first trade.
+#[derive(Debug, Clone)]
+pub struct MedianOfTrades<T = Decimal>
+where
+    T: Mon,
+{
+    n: usize,
+    prices: VecDeque<QuoteCurrency<T>>,
+}
+
+impl<T> MedianOfTrades<T>
+where
+    T: Mon,
+{
+    /// Create a new `MedianOfTrades` over the last `n` trades.
+    ///
+    /// # Returns:
+    /// Either a valid `MedianOfTrades` or an Error if `n` is zero.
+    pub fn new(n: usize) -> Result<Self> {
+        if n == 0 {
+            return Err(Error::InvalidMarkPriceSource);
+        }
+        Ok(Self {
+            n,
+            prices: VecDeque::with_capacity(n),
+        })
+    }
+}
+
+impl<T> MarkPriceSource<T> for MedianOfTrades<T>
+where
+    T: Mon + 'static,
+{
+    fn update(
+        &mut self,
+        _timestamp_ns: i64,
+        bid: QuoteCurrency<T>,
+        ask: QuoteCurrency<T>,
+        trade_price: Option<QuoteCurrency<T>>,
+        _index_price: Option<QuoteCurrency<T>>,
+    ) -> QuoteCurrency<T> {
+        if let Some(price) = trade_price {
+            if self.prices.len() == self.n {
+                self.prices.pop_front();
+            }
+            self.prices.push_back(price);
+        }
+        if self.prices.is_empty() {
+            return mid(bid, ask);
+        }
+        let mut sorted = Vec::from_iter(self.prices.iter().copied());
+        sorted.sort_by(|a, b| a.partial_cmp(b).expect("Prices are comparable; qed"));
+        let m = sorted.len() / 2;
+        if sorted.len() % 2 == 1 {
+            sorted[m]
+        } else {
+            mid(sorted[m - 1], sorted[m])
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn MarkPriceSource<T>> {
+        Box::new(self.clone())
+    }
+}
+
+/// The mark price follows a precomputed external index series, e.g. the spot index a perpetual references.
+/// Use `LastIndexPrice` to feed the index as `MarketUpdate::Index` instead.
+/// The latest index price at or before the market update is used,
+/// falling back to the mid price before the first one.
+#[derive(Debug, Clone)]
+pub struct IndexSeries<T = Decimal>
+where
+    T: Mon,
+{
+    series: Vec<(i64, QuoteCurrency<T>)>,
+    // The number of index prices at or before the last market update.
+    pos: usize,
+}
+
+impl<T> IndexSeries<T>
+where
+    T: Mon,
+{
+    /// Create a new `IndexSeries`.
+    ///
+    /// # Arguments:
+    /// `series`: The timestamps in nanoseconds and index prices.
+    ///
+    /// # Returns:
+    /// Either a valid `IndexSeries` or an Error if the timestamps are not ascending
+    /// or a price is not positive.
+    pub fn new(series: Vec<(i64, QuoteCurrency<T>)>) -> Result<Self> {
+        if series.windows(2).any(|w| w[0].0 > w[1].0)
+            || series
+                .iter()
+                .any(|(_, price)| *price <= QuoteCurrency::new_zero())
+        {
+            return Err(Error::InvalidMarkPriceSource);
+        }
+        Ok(Self { series, pos: 0 })
+    }
+}
+
+impl<T> MarkPriceSource<T> for IndexSeries<T>
+where
+    T: Mon + 'static,
+{
+    fn update(
+        &mut self,
+        timestamp_ns: i64,
+        bid: QuoteCurrency<T>,
+        ask: QuoteCurrency<T>,
+        _trade_price: Option<QuoteCurrency<T>>,
+        _index_price: Option<QuoteCurrency<T>>,
+    ) -> QuoteCurrency<T> {
+        self.pos += self.series[self.pos..].partition_point(|(ts_ns, _)| *ts_ns <= timestamp_ns);
+        match self.pos {
+            0 => mid(bid, ask),
+            pos => self.series[pos - 1].1,
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn MarkPriceSource<T>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{quote, Dec};
+
+    #[test]
+    fn mark_price_ema_of_mid() {
+        assert_eq!(
+            EmaOfMid::new(Dec!(0)).map(|_| ()),
+            Err(Error::InvalidMarkPriceSource)
+        );
+        let mut source = EmaOfMid::new(Dec!(0.5)).unwrap();
+        assert_eq!(
+            source.update(0, quote!(99), quote!(101), None, None),
+            quote!(100)
+        );
+        assert_eq!(
+            source.update(1, quote!(109), quote!(111), None, None),
+            quote!(105)
+        );
+        assert_eq!(
+            source.update(2, quote!(109), quote!(111), None, None),
+            quote!(107.5)
+        );
+    }
+
+    #[test]
+    fn mark_price_median_of_trades() {
+        assert_eq!(
+            MedianOfTrades::<Decimal>::new(0).map(|_| ()),
+            Err(Error::InvalidMarkPriceSource)
+        );
+        let mut source = MedianOfTrades::new(3).unwrap();
+        assert_eq!(
+            source.update(0, quote!(99), quote!(101), None, None),
+            quote!(100)
+        );
+        assert_eq!(
+            source.update(1, quote!(99), quote!(101), Some(quote!(102)), None),
+            quote!(102)
+        );
+        assert_eq!(
+            source.update(2, quote!(99), quote!(101), Some(quote!(98)), None),
+            quote!(100)
+        );
+        // An outlier print doesn't move the median.
+        assert_eq!(
+            source.update(3, quote!(99), quote!(101), Some(quote!(50)), None),
+            quote!(98)
+        );
+        // The oldest trade drops out of the window.
+        assert_eq!(
+            source.update(4, quote!(99), quote!(101), Some(quote!(101)), None),
+            quote!(98)
+        );
+    }
+
+    #[test]
+    fn mark_price_index_series() {
+        assert_eq!(
+            IndexSeries::new(vec![(2, quote!(100)), (1, quote!(101))]).map(|_| ()),
+            Err(Error::InvalidMarkPriceSource)
+        );
+        let mut source = IndexSeries::new(vec![(10, quote!(105)), (20, quote!(95))]).unwrap();
+        assert_eq!(
+            source.update(0, quote!(99), quote!(101), None, None),
+            quote!(100)
+        );
+        assert_eq!(
+            source.update(10, quote!(99), quote!(101), None, None),
+            quote!(105)
+        );
+        assert_eq!(
+            source.update(19, quote!(99), quote!(101), None, None),
+            quote!(105)
+        );
+        assert_eq!(
+            source.update(25, quote!(99), quote!(101), None, None),
+            quote!(95)
+        );
+    }
+}
@@ -1,33 +1,67 @@
+use alloc::{boxed::Box, vec::Vec};
+
 use fpdec::Decimal;
 
 use crate::{
+    mark_price::MarkPriceSource,
     prelude::PriceFilter,
-    quote,
-    types::{Currency, MarketUpdate, QuoteCurrency, Result},
+    types::{Currency, MarketUpdate, Mon, QuoteCurrency, Result},
 };
 
 /// Some information regarding the state of the market.
 #[derive(Debug, Clone)]
-pub struct MarketState {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct MarketState<T = Decimal>
+where
+    T: Mon,
+{
     /// Used to validate states
     // TODO: remove here and pass through were needed
-    price_filter: PriceFilter,
+    price_filter: PriceFilter<T>,
     /// The current bid
-    bid: QuoteCurrency,
+    bid: QuoteCurrency<T>,
     /// The current ask
-    ask: QuoteCurrency,
+    ask: QuoteCurrency<T>,
+    /// The bid levels of the latest `MarketUpdate::Depth` as price and quantity, best first.
+    /// Cleared by updates which move the best bid and ask without depth.
+    bid_depth: Vec<(QuoteCurrency<T>, T)>,
+    /// The ask levels of the latest `MarketUpdate::Depth` as price and quantity, best first.
+    ask_depth: Vec<(QuoteCurrency<T>, T)>,
+    /// The latest price of the external index, if any was received.
+    index_price: Option<QuoteCurrency<T>>,
+    /// The current mark price, at which positions are valued for liquidations.
+    mark_price: QuoteCurrency<T>,
+    /// Derives the mark price, which is reset to the `MidPrice` when deserialized.
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_mark_price_source"))]
+    mark_price_source: Box<dyn MarkPriceSource<T>>,
     /// The current timestamp in nanoseconds
     current_ts_ns: i64,
     /// Used for synchronizing orders
     step: u64,
 }
 
-impl MarketState {
-    pub(crate) fn new(price_filter: PriceFilter) -> Self {
+#[cfg(feature = "serde")]
+fn default_mark_price_source<T: Mon>() -> Box<dyn MarkPriceSource<T>> {
+    Box::new(crate::mark_price::MidPrice)
+}
+
+impl<T> MarketState<T>
+where
+    T: Mon,
+{
+    pub(crate) fn new(
+        price_filter: PriceFilter<T>,
+        mark_price_source: Box<dyn MarkPriceSource<T>>,
+    ) -> Self {
         Self {
             price_filter,
-            bid: quote!(0),
-            ask: quote!(0),
+            bid: QuoteCurrency::new_zero(),
+            ask: QuoteCurrency::new_zero(),
+            bid_depth: Vec::new(),
+            ask_depth: Vec::new(),
+            index_price: None,
+            mark_price: QuoteCurrency::new_zero(),
+            mark_price_source,
             current_ts_ns: 0,
             step: 0,
         }
@@ -46,31 +80,81 @@ impl MarketState {
         market_update: &MarketUpdate<S>,
     ) -> Result<()>
     where
-        S: Currency,
+        S: Currency<Num = T>,
     {
         self.price_filter.validate_market_update(market_update)?;
 
+        let mut trade_price = None;
         match market_update {
             MarketUpdate::Bba { bid, ask } => {
                 self.bid = *bid;
                 self.ask = *ask;
+                self.clear_depth();
             }
-            MarketUpdate::Trade { .. } => {}
+            MarketUpdate::Trade { price, .. } => trade_price = Some(*price),
             MarketUpdate::Candle { bid, ask, .. } => {
                 self.bid = *bid;
                 self.ask = *ask;
+                self.clear_depth();
+            }
+            MarketUpdate::Index { price } => self.index_price = Some(*price),
+            MarketUpdate::Depth { bids, asks } => {
+                // The `PriceFilter` ensures both sides have a level.
+                self.bid = bids[0].0;
+                self.ask = asks[0].0;
+                self.bid_depth.clear();
+                self.bid_depth.extend(
+                    bids.iter()
+                        .map(|(price, quantity)| (*price, quantity.inner())),
+                );
+                self.ask_depth.clear();
+                self.ask_depth.extend(
+                    asks.iter()
+                        .map(|(price, quantity)| (*price, quantity.inner())),
+                );
             }
         }
         self.current_ts_ns = timestamp_ns as i64;
+        self.mark_price = self.mark_price_source.update(
+            self.current_ts_ns,
+            self.bid,
+            self.ask,
+            trade_price,
+            self.index_price,
+        );
         self.step += 1;
 
         Ok(())
     }
 
+    fn clear_depth(&mut self) {
+        self.bid_depth.clear();
+        self.ask_depth.clear();
+    }
+
     /// Get the mid price
     #[inline]
-    pub fn mid_price(&self) -> QuoteCurrency {
-        (self.bid + self.ask) / quote!(2)
+    pub fn mid_price(&self) -> QuoteCurrency<T> {
+        (self.bid + self.ask) / T::TWO
+    }
+
+    /// Get the mark price, as derived by the `MarkPriceSource` of the `Config`.
+    /// Positions are valued at the mark price for their maintenance margin and liquidation.
+    #[inline]
+    pub fn mark_price(&self) -> QuoteCurrency<T> {
+        self.mark_price
+    }
+
+    /// Get the latest price of the external index from a `MarketUpdate::Index`, if any was received.
+    #[inline]
+    pub fn index_price(&self) -> Option<QuoteCurrency<T>> {
+        self.index_price
+    }
+
+    /// Get the number of valid market updates processed so far.
+    #[inline]
+    pub fn step(&self) -> u64 {
+        self.step
     }
 
     /// Get the last observed timestamp in nanoseconts
@@ -81,21 +165,71 @@ impl MarketState {
 
     /// Get the last observed bid price.
     #[inline]
-    pub fn bid(&self) -> QuoteCurrency {
+    pub fn bid(&self) -> QuoteCurrency<T> {
         self.bid
     }
 
     /// Get the last observed ask price.
     #[inline]
-    pub fn ask(&self) -> QuoteCurrency {
+    pub fn ask(&self) -> QuoteCurrency<T> {
         self.ask
     }
 
+    /// Get the bid levels of the latest `MarketUpdate::Depth` as price and quantity, best first.
+    /// Empty if the best bid and ask have moved since without depth.
+    #[inline]
+    pub fn bid_depth(&self) -> &[(QuoteCurrency<T>, T)] {
+        &self.bid_depth
+    }
+
+    /// Get the ask levels of the latest `MarketUpdate::Depth` as price and quantity, best first.
+    /// Empty if the best bid and ask have moved since without depth.
+    #[inline]
+    pub fn ask_depth(&self) -> &[(QuoteCurrency<T>, T)] {
+        &self.ask_depth
+    }
+
+    /// Get the order book imbalance over the best `levels` of each side,
+    /// which is `(bid_quantity - ask_quantity) / (bid_quantity + ask_quantity)` in `[-1, 1]`.
+    /// Positive values mean more resting bids than asks, which tends to precede upward moves.
+    ///
+    /// # Returns:
+    /// `None` if there is no depth or `levels` is zero.
+    pub fn book_imbalance(&self, levels: usize) -> Option<T> {
+        let bid_quantity = self
+            .bid_depth
+            .iter()
+            .take(levels)
+            .fold(T::ZERO, |acc, (_, quantity)| acc + *quantity);
+        let ask_quantity = self
+            .ask_depth
+            .iter()
+            .take(levels)
+            .fold(T::ZERO, |acc, (_, quantity)| acc + *quantity);
+        let total = bid_quantity + ask_quantity;
+        if total <= T::ZERO {
+            return None;
+        }
+        Some((bid_quantity - ask_quantity) / total)
+    }
+
+    /// Get the microprice, which is the mid price weighted towards the side with less resting quantity
+    /// at the best levels: `(bid * ask_quantity + ask * bid_quantity) / (bid_quantity + ask_quantity)`.
+    ///
+    /// # Returns:
+    /// `None` if there is no depth.
+    pub fn microprice(&self) -> Option<QuoteCurrency<T>> {
+        let (bid, bid_quantity) = *self.bid_depth.first()?;
+        let (ask, ask_quantity) = *self.ask_depth.first()?;
+        Some((bid * ask_quantity + ask * bid_quantity) / (bid_quantity + ask_quantity))
+    }
+
+    /// Create a new instance from its components, used in tests.
     #[cfg(test)]
     pub fn from_components(
-        price_filter: PriceFilter,
-        bid: QuoteCurrency,
-        ask: QuoteCurrency,
+        price_filter: PriceFilter<T>,
+        bid: QuoteCurrency<T>,
+        ask: QuoteCurrency<T>,
         current_ts_ns: i64,
         step: u64,
     ) -> Self {
@@ -103,6 +237,11 @@ impl MarketState {
             price_filter,
             bid,
             ask,
+            bid_depth: Vec::new(),
+            ask_depth: Vec::new(),
+            index_price: None,
+            mark_price: (bid + ask) / T::TWO,
+            mark_price_source: Box::new(crate::mark_price::MidPrice),
             current_ts_ns,
             step,
         }
@@ -1,5 +1,7 @@
 //! Convenince function used in tests.
 
+use alloc::string::ToString;
+
 use fpdec::{Dec, Decimal};
 
 use crate::{account_tracker::NoAccountTracker, prelude::*};
@@ -18,10 +20,12 @@ pub fn mock_exchange_base() -> Exchange<NoAccountTracker, BaseCurrency> {
         quantity_filter: QuantityFilter {
             min_quantity: base!(0),
             max_quantity: base!(0),
+            max_market_quantity: base!(0),
             step_size: base!(0.01),
         },
-        fee_maker: fee!(0.0002),
-        fee_taker: fee!(0.0006),
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
     };
     let config = Config::new(quote!(1000), 200, leverage!(1), contract_specification).unwrap();
     Exchange::new(acc_tracker, config)
@@ -39,9 +43,36 @@ pub fn mock_exchange_quote(
         mark_method: MarkMethod::MidPrice,
         price_filter: PriceFilter::default(),
         quantity_filter: QuantityFilter::default(),
-        fee_maker: fee!(0.0002),
-        fee_taker: fee!(0.0006),
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
     };
     let config = Config::new(starting_balance, 200, leverage!(1), contract_specification).unwrap();
     Exchange::new(acc_tracker, config)
 }
+
+/// The `ContractSpecification` of a linear future for testing,
+/// with an initial margin of 10%, a maintenance margin of 2% and neither filters nor fees.
+pub fn mock_contract_specification_base() -> ContractSpecification<BaseCurrency> {
+    ContractSpecification {
+        ticker: "TESTUSD".to_string(),
+        initial_margin: Dec!(0.1),
+        maintenance_margin: Dec!(0.02),
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter::default(),
+        quantity_filter: QuantityFilter::default(),
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(fee!(0), fee!(0)),
+    }
+}
+
+/// A `ConfigBuilder` of a linear futures exchange for testing,
+/// with a starting balance of 1000, a leverage of 10 and the `mock_contract_specification_base`.
+/// Tests override only the settings they exercise.
+pub fn mock_config_base() -> ConfigBuilder<QuoteCurrency> {
+    Config::builder()
+        .starting_balance(quote!(1000))
+        .initial_leverage(leverage!(10))
+        .contract_specification(mock_contract_specification_base())
+}
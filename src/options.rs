@@ -0,0 +1,183 @@
+//! European options on the underlying futures contract.
+
+use fpdec::Decimal;
+
+use crate::{
+    types::{Currency, MarginCurrency, Mon, QuoteCurrency, Side},
+    utils::max,
+};
+
+/// Whether the option gives the right to buy or to sell the underlying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OptionKind {
+    /// The right to buy the underlying at the strike price.
+    Call,
+    /// The right to sell the underlying at the strike price.
+    Put,
+}
+
+/// A European option, which can only be exercised at its expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct OptionContract<T = Decimal>
+where
+    T: Mon,
+{
+    /// Call or put.
+    pub kind: OptionKind,
+    /// The price at which the underlying can be bought or sold.
+    pub strike: QuoteCurrency<T>,
+    /// The timestamp in nanoseconds at which the option is exercised.
+    pub expiry_ts_ns: i64,
+}
+
+impl<T> OptionContract<T>
+where
+    T: Mon,
+{
+    /// The value of the option if it were exercised at the `underlying_price`.
+    ///
+    /// # Arguments:
+    /// `underlying_price`: The price of the underlying futures contract.
+    /// `quantity`: The absolute number of contracts of the underlying.
+    pub fn intrinsic_value<M>(
+        &self,
+        underlying_price: QuoteCurrency<T>,
+        quantity: M::PairedCurrency,
+    ) -> M
+    where
+        M: Currency<Num = T> + MarginCurrency,
+    {
+        let signed_qty = match self.kind {
+            OptionKind::Call => quantity,
+            OptionKind::Put => quantity.into_negative(),
+        };
+        max(
+            M::pnl(self.strike, underlying_price, signed_qty),
+            M::new_zero(),
+        )
+    }
+
+    /// The margin required to sell the option,
+    /// which is its intrinsic value plus a fraction of the notional value of the underlying.
+    ///
+    /// # Arguments:
+    /// `underlying_price`: The price of the underlying futures contract.
+    /// `quantity`: The absolute number of contracts of the underlying.
+    /// `margin_fraction`: The fraction of the notional value required as margin.
+    pub fn short_margin<M>(
+        &self,
+        underlying_price: QuoteCurrency<T>,
+        quantity: M::PairedCurrency,
+        margin_fraction: T,
+    ) -> M
+    where
+        M: Currency<Num = T> + MarginCurrency,
+    {
+        self.intrinsic_value::<M>(underlying_price, quantity)
+            + quantity.abs().convert(underlying_price) * margin_fraction
+    }
+}
+
+/// An open option position.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct OptionPosition<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The option held.
+    pub contract: OptionContract<M::Num>,
+    /// `Buy` for a long option, `Sell` for a short option.
+    pub side: Side,
+    /// The absolute number of contracts of the underlying.
+    pub quantity: M::PairedCurrency,
+    /// The premium paid for a long or received for a short option.
+    pub premium: M,
+    /// The margin reserved for a short option, zero for long options.
+    pub margin: M,
+}
+
+impl<M> OptionPosition<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The realized profit and loss when the option is exercised at the `settlement_price`,
+    /// including the premium.
+    pub fn settlement_pnl(&self, settlement_price: QuoteCurrency<M::Num>) -> M {
+        let payoff = self
+            .contract
+            .intrinsic_value::<M>(settlement_price, self.quantity);
+        match self.side {
+            Side::Buy => payoff - self.premium,
+            Side::Sell => self.premium - payoff,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn option_intrinsic_value_linear() {
+        let call = OptionContract {
+            kind: OptionKind::Call,
+            strike: quote!(100),
+            expiry_ts_ns: 0,
+        };
+        assert_eq!(
+            call.intrinsic_value::<QuoteCurrency>(quote!(110), base!(2)),
+            quote!(20)
+        );
+        assert_eq!(
+            call.intrinsic_value::<QuoteCurrency>(quote!(90), base!(2)),
+            quote!(0)
+        );
+
+        let put = OptionContract {
+            kind: OptionKind::Put,
+            ..call
+        };
+        assert_eq!(
+            put.intrinsic_value::<QuoteCurrency>(quote!(90), base!(2)),
+            quote!(20)
+        );
+        assert_eq!(
+            put.short_margin::<QuoteCurrency>(quote!(90), base!(2), Dec!(0.1)),
+            quote!(38)
+        );
+    }
+
+    #[test]
+    fn option_intrinsic_value_inverse() {
+        let call = OptionContract {
+            kind: OptionKind::Call,
+            strike: quote!(1000),
+            expiry_ts_ns: 0,
+        };
+        assert_eq!(
+            call.intrinsic_value::<BaseCurrency>(quote!(2000), quote!(1000)),
+            base!(0.5)
+        );
+    }
+
+    #[test]
+    fn option_settlement_pnl() {
+        let position = OptionPosition::<QuoteCurrency> {
+            contract: OptionContract {
+                kind: OptionKind::Call,
+                strike: quote!(100),
+                expiry_ts_ns: 0,
+            },
+            side: Side::Sell,
+            quantity: base!(1),
+            premium: quote!(5),
+            margin: quote!(10),
+        };
+        assert_eq!(position.settlement_pnl(quote!(90)), quote!(5));
+        assert_eq!(position.settlement_pnl(quote!(110)), quote!(-5));
+    }
+}
@@ -0,0 +1,202 @@
+//! The resting limit orders of the account by side and price level, in price-time priority.
+
+use alloc::collections::{BTreeMap, VecDeque};
+use core::ops::Bound;
+
+use crate::types::{Mon, QuoteCurrency, Side};
+
+/// The ids of the resting limit orders of the account, by side and price level,
+/// while the orders themselves live in `Account::active_limit_orders`.
+/// Each price level is a FIFO queue, so iterating a side from its best price
+/// yields the orders in price-time priority,
+/// and matching a market update only visits the price levels it crosses.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub(crate) struct OrderBook<T>
+where
+    T: Mon,
+{
+    #[cfg_attr(feature = "serde", serde(with = "levels"))]
+    bids: BTreeMap<QuoteCurrency<T>, VecDeque<u64>>,
+    #[cfg_attr(feature = "serde", serde(with = "levels"))]
+    asks: BTreeMap<QuoteCurrency<T>, VecDeque<u64>>,
+}
+
+impl<T> Default for OrderBook<T>
+where
+    T: Mon,
+{
+    fn default() -> Self {
+        Self {
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> OrderBook<T>
+where
+    T: Mon,
+{
+    fn side(&self, side: Side) -> &BTreeMap<QuoteCurrency<T>, VecDeque<u64>> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    fn side_mut(&mut self, side: Side) -> &mut BTreeMap<QuoteCurrency<T>, VecDeque<u64>> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    /// Queue the order `id` at the back of its price level.
+    pub(crate) fn insert(&mut self, side: Side, price: QuoteCurrency<T>, id: u64) {
+        self.side_mut(side).entry(price).or_default().push_back(id);
+    }
+
+    /// Remove the order `id` from its price level.
+    pub(crate) fn remove(&mut self, side: Side, price: QuoteCurrency<T>, id: u64) {
+        let levels = self.side_mut(side);
+        let Some(level) = levels.get_mut(&price) else {
+            debug_assert!(false, "The price level of order {id} must exist");
+            return;
+        };
+        let Some(pos) = level.iter().position(|queued| *queued == id) else {
+            debug_assert!(false, "The order {id} must be queued");
+            return;
+        };
+        level.remove(pos);
+        if level.is_empty() {
+            levels.remove(&price);
+        }
+    }
+
+    /// Move the order `id` to the back of its price level, as it lost its time priority.
+    pub(crate) fn requeue(&mut self, side: Side, price: QuoteCurrency<T>, id: u64) {
+        self.remove(side, price, id);
+        self.insert(side, price, id);
+    }
+
+    /// The ids of the buy orders priced at or above `price`, or strictly above if not `inclusive`,
+    /// in price-time priority.
+    pub(crate) fn bids_from(
+        &self,
+        price: QuoteCurrency<T>,
+        inclusive: bool,
+    ) -> impl Iterator<Item = u64> + '_ {
+        let bound = if inclusive {
+            Bound::Included(price)
+        } else {
+            Bound::Excluded(price)
+        };
+        self.bids
+            .range((bound, Bound::Unbounded))
+            .rev()
+            .flat_map(|(_, level)| level.iter().copied())
+    }
+
+    /// The ids of the sell orders priced at or below `price`, or strictly below if not `inclusive`,
+    /// in price-time priority.
+    pub(crate) fn asks_to(
+        &self,
+        price: QuoteCurrency<T>,
+        inclusive: bool,
+    ) -> impl Iterator<Item = u64> + '_ {
+        let bound = if inclusive {
+            Bound::Included(price)
+        } else {
+            Bound::Excluded(price)
+        };
+        self.asks
+            .range((Bound::Unbounded, bound))
+            .flat_map(|(_, level)| level.iter().copied())
+    }
+
+    /// The queued ids and prices of one side, for consistency checks.
+    pub(crate) fn orders(&self, side: Side) -> impl Iterator<Item = (QuoteCurrency<T>, u64)> + '_ {
+        self.side(side)
+            .iter()
+            .flat_map(|(price, level)| level.iter().map(move |id| (*price, *id)))
+    }
+}
+
+/// JSON only allows string keys, so the price levels are written as a sequence of pairs.
+#[cfg(feature = "serde")]
+mod levels {
+    use alloc::collections::{BTreeMap, VecDeque};
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::types::{Mon, QuoteCurrency};
+
+    pub(super) fn serialize<T, S>(
+        levels: &BTreeMap<QuoteCurrency<T>, VecDeque<u64>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: Mon,
+        S: Serializer,
+    {
+        serializer.collect_seq(levels.iter())
+    }
+
+    pub(super) fn deserialize<'de, T, D>(
+        deserializer: D,
+    ) -> Result<BTreeMap<QuoteCurrency<T>, VecDeque<u64>>, D::Error>
+    where
+        T: Mon,
+        D: Deserializer<'de>,
+    {
+        Ok(BTreeMap::from_iter(
+            Vec::<(QuoteCurrency<T>, VecDeque<u64>)>::deserialize(deserializer)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{quote, Currency, Decimal};
+
+    #[test]
+    fn order_book_price_time_priority() {
+        let mut book = OrderBook::<Decimal>::default();
+        book.insert(Side::Buy, quote!(99), 0);
+        book.insert(Side::Buy, quote!(100), 1);
+        book.insert(Side::Buy, quote!(99), 2);
+        book.insert(Side::Buy, quote!(98), 3);
+        book.insert(Side::Sell, quote!(102), 4);
+        book.insert(Side::Sell, quote!(101), 5);
+        book.insert(Side::Sell, quote!(102), 6);
+
+        assert_eq!(
+            Vec::from_iter(book.bids_from(quote!(99), true)),
+            vec![1, 0, 2]
+        );
+        assert_eq!(Vec::from_iter(book.bids_from(quote!(99), false)), vec![1]);
+        assert_eq!(
+            Vec::from_iter(book.asks_to(quote!(102), true)),
+            vec![5, 4, 6]
+        );
+        assert_eq!(Vec::from_iter(book.asks_to(quote!(102), false)), vec![5]);
+        assert_eq!(book.asks_to(quote!(100), true).count(), 0);
+
+        book.requeue(Side::Buy, quote!(99), 0);
+        assert_eq!(
+            Vec::from_iter(book.bids_from(quote!(99), true)),
+            vec![1, 2, 0]
+        );
+        book.remove(Side::Buy, quote!(100), 1);
+        assert_eq!(
+            Vec::from_iter(book.bids_from(quote!(0), true)),
+            vec![2, 0, 3]
+        );
+        assert_eq!(
+            Vec::from_iter(book.orders(Side::Buy)),
+            vec![(quote!(98), 3), (quote!(99), 2), (quote!(99), 0)]
+        );
+    }
+}
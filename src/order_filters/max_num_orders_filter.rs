@@ -0,0 +1,83 @@
+use hashbrown::HashMap;
+
+use crate::{
+    prelude::OrderError,
+    types::{Currency, Order},
+};
+
+/// The `MaxNumOrdersFilter` limits the number of resting limit orders,
+/// like the `MAX_NUM_ORDERS` filter of real exchanges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct MaxNumOrdersFilter {
+    /// Defines the maximum number of resting limit orders
+    /// Disabled if 0
+    pub max_num_orders: usize,
+
+    /// Defines the maximum number of resting limit orders on each side
+    /// Disabled if 0
+    pub max_num_orders_per_side: usize,
+}
+
+impl MaxNumOrdersFilter {
+    /// Check if the `order` may rest in addition to the `active_limit_orders`.
+    pub(crate) fn validate_order<S>(
+        &self,
+        order: &Order<S>,
+        active_limit_orders: &HashMap<u64, Order<S>>,
+    ) -> Result<(), OrderError>
+    where
+        S: Currency,
+    {
+        if active_limit_orders.len() >= self.max_num_orders && self.max_num_orders != 0 {
+            return Err(OrderError::MaxNumOrders);
+        }
+        if self.max_num_orders_per_side != 0
+            && active_limit_orders
+                .values()
+                .filter(|o| o.side() == order.side())
+                .count()
+                >= self.max_num_orders_per_side
+        {
+            return Err(OrderError::MaxNumOrdersPerSide);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn max_num_orders_filter() {
+        let filter = MaxNumOrdersFilter {
+            max_num_orders: 3,
+            max_num_orders_per_side: 2,
+        };
+        let buy = Order::limit(Side::Buy, quote!(100), base!(1)).unwrap();
+        let sell = Order::limit(Side::Sell, quote!(101), base!(1)).unwrap();
+        let mut active = HashMap::new();
+        filter.validate_order(&buy, &active).unwrap();
+
+        active.insert(0, buy.clone());
+        active.insert(1, buy.clone());
+        assert_eq!(
+            filter.validate_order(&buy, &active),
+            Err(OrderError::MaxNumOrdersPerSide)
+        );
+        filter.validate_order(&sell, &active).unwrap();
+
+        active.insert(2, sell.clone());
+        assert_eq!(
+            filter.validate_order(&sell, &active),
+            Err(OrderError::MaxNumOrders)
+        );
+
+        // Disabled
+        MaxNumOrdersFilter::default()
+            .validate_order(&sell, &active)
+            .unwrap();
+    }
+}
@@ -1,7 +1,11 @@
 //! Contains order filter related code
 
+mod max_num_orders_filter;
+mod notional_filter;
 mod price_filter;
 mod quantity_filter;
 
-pub use price_filter::PriceFilter;
+pub use max_num_orders_filter::MaxNumOrdersFilter;
+pub use notional_filter::NotionalFilter;
+pub use price_filter::{PriceFilter, SideMultipliers};
 pub use quantity_filter::QuantityFilter;
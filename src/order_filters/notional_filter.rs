@@ -0,0 +1,104 @@
+use crate::{
+    prelude::OrderError,
+    types::{Currency, Order, QuoteCurrency},
+};
+
+/// The `NotionalFilter` defines the rules for the notional value of each order,
+/// which is its quantity times its limit price, or times the mark price for market orders.
+/// The generic currency `M` is the margin currency, in which the notional value is denoted.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct NotionalFilter<M>
+where
+    M: Currency,
+{
+    /// Defines the minimum notional value of any order
+    /// Disabled if 0
+    pub min_notional: M,
+
+    /// Defines the maximum notional value of any order
+    /// Disabled if 0
+    pub max_notional: M,
+}
+
+impl<M> Default for NotionalFilter<M>
+where
+    M: Currency,
+{
+    fn default() -> Self {
+        Self {
+            min_notional: M::new_zero(),
+            max_notional: M::new_zero(),
+        }
+    }
+}
+
+impl<M> NotionalFilter<M>
+where
+    M: Currency,
+{
+    pub(crate) fn validate_order<S>(
+        &self,
+        order: &Order<S>,
+        mark_price: QuoteCurrency<S::Num>,
+    ) -> Result<(), OrderError>
+    where
+        S: Currency<PairedCurrency = M>,
+    {
+        let price = order.limit_price().unwrap_or(mark_price);
+        let notional = order.quantity().convert(price);
+        if notional < self.min_notional && self.min_notional != M::new_zero() {
+            return Err(OrderError::NotionalTooLow);
+        }
+        if notional > self.max_notional && self.max_notional != M::new_zero() {
+            return Err(OrderError::NotionalTooHigh);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn notional_filter() {
+        let filter = NotionalFilter {
+            min_notional: quote!(5),
+            max_notional: quote!(1000),
+        };
+        let mark_price = quote!(100);
+
+        let order = Order::market(Side::Buy, base!(0.05)).unwrap();
+        filter.validate_order(&order, mark_price).unwrap();
+        let order = Order::market(Side::Sell, base!(0.04)).unwrap();
+        assert_eq!(
+            filter.validate_order(&order, mark_price),
+            Err(OrderError::NotionalTooLow)
+        );
+
+        // The limit price takes precedence over the mark price.
+        let order = Order::limit(Side::Buy, quote!(200), base!(5)).unwrap();
+        filter.validate_order(&order, mark_price).unwrap();
+        let order = Order::limit(Side::Sell, quote!(201), base!(5)).unwrap();
+        assert_eq!(
+            filter.validate_order(&order, mark_price),
+            Err(OrderError::NotionalTooHigh)
+        );
+    }
+
+    #[test]
+    fn notional_filter_inverse() {
+        let filter = NotionalFilter {
+            min_notional: base!(0.001),
+            max_notional: base!(0),
+        };
+        let order = Order::market(Side::Buy, quote!(100)).unwrap();
+        filter.validate_order(&order, quote!(50000)).unwrap();
+        assert_eq!(
+            filter.validate_order(&order, quote!(200000)),
+            Err(OrderError::NotionalTooLow)
+        );
+    }
+}
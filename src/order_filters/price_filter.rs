@@ -2,59 +2,128 @@ use fpdec::Decimal;
 
 use crate::{
     prelude::{Error, OrderError},
-    quote,
-    types::{Currency, MarketUpdate, Order, QuoteCurrency},
+    types::{Currency, MarketUpdate, Mon, Order, QuoteCurrency, Side},
 };
 
+/// Price bands relative to the mark price which depend on the side of the order,
+/// like the `PERCENT_PRICE_BY_SIDE` filter of Binance.
+/// Each multiplier is disabled if 0.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(default, bound = "")
+)]
+pub struct SideMultipliers<T = Decimal>
+where
+    T: Mon,
+{
+    /// To pass this filter, a buy order.limit_price <= mark_price * bid_multiplier_up
+    pub bid_multiplier_up: T,
+    /// To pass this filter, a buy order.limit_price >= mark_price * bid_multiplier_down
+    pub bid_multiplier_down: T,
+    /// To pass this filter, a sell order.limit_price <= mark_price * ask_multiplier_up
+    pub ask_multiplier_up: T,
+    /// To pass this filter, a sell order.limit_price >= mark_price * ask_multiplier_down
+    pub ask_multiplier_down: T,
+}
+
+impl<T> Default for SideMultipliers<T>
+where
+    T: Mon,
+{
+    fn default() -> Self {
+        Self {
+            bid_multiplier_up: T::ZERO,
+            bid_multiplier_down: T::ZERO,
+            ask_multiplier_up: T::ZERO,
+            ask_multiplier_down: T::ZERO,
+        }
+    }
+}
+
+impl<T> SideMultipliers<T>
+where
+    T: Mon,
+{
+    /// Only bound buy prices from above and sell prices from below,
+    /// like the `PERCENT_PRICE` filter of Binance futures.
+    pub fn buy_up_sell_down(multiplier_up: T, multiplier_down: T) -> Self {
+        Self {
+            bid_multiplier_up: multiplier_up,
+            ask_multiplier_down: multiplier_down,
+            ..Default::default()
+        }
+    }
+}
+
 /// The `PriceFilter` defines the price rules for a symbol
 #[derive(Debug, Clone)]
-pub struct PriceFilter {
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(default, bound = "")
+)]
+pub struct PriceFilter<T = Decimal>
+where
+    T: Mon,
+{
     /// Defines the minimum price allowed.
     /// Disabled if `min_price` == 0
-    pub min_price: QuoteCurrency,
+    pub min_price: QuoteCurrency<T>,
 
     /// Defines the maximum price allowed.
     /// Disabled if `max_price` == 0
-    pub max_price: QuoteCurrency,
+    pub max_price: QuoteCurrency<T>,
 
     /// Defines the intervals that a price can be increased / decreased by.
     /// For the filter to pass,
     /// (order.limit_price - min_price) % tick_size == 0
-    pub tick_size: QuoteCurrency,
+    pub tick_size: QuoteCurrency<T>,
 
     /// Defines valid ranges for the order price relative to the mark price
     /// To pass this filter,
     /// order.limit_price <= mark_price * multiplier_up
-    pub multiplier_up: Decimal,
+    pub multiplier_up: T,
 
     /// Defines valid ranges for the order price relative to the mark price
     /// To pass this filter,
     /// order.limit_price >= mark_price * multiplier_down
-    pub multiplier_down: Decimal,
+    pub multiplier_down: T,
+
+    /// Replaces `multiplier_up` and `multiplier_down` with price bands depending on the order side.
+    pub side_multipliers: Option<SideMultipliers<T>>,
 }
 
-impl Default for PriceFilter {
+impl<T> Default for PriceFilter<T>
+where
+    T: Mon,
+{
     fn default() -> Self {
         Self {
-            min_price: quote!(0),
+            min_price: QuoteCurrency::new_zero(),
             // disabled
-            max_price: quote!(0),
-            tick_size: quote!(1),
-            multiplier_up: Decimal::TWO,
-            multiplier_down: Decimal::ZERO,
+            max_price: QuoteCurrency::new_zero(),
+            tick_size: QuoteCurrency::new(T::ONE),
+            multiplier_up: T::TWO,
+            multiplier_down: T::ZERO,
+            side_multipliers: None,
         }
     }
 }
 
-impl PriceFilter {
+impl<T> PriceFilter<T>
+where
+    T: Mon,
+{
     /// check if an `Order` is valid
     pub(crate) fn validate_order<S>(
         &self,
         order: &Order<S>,
-        mark_price: QuoteCurrency,
+        mark_price: QuoteCurrency<T>,
     ) -> Result<(), OrderError>
     where
-        S: Currency,
+        S: Currency<Num = T>,
     {
         match order.limit_price() {
             Some(limit_price) => {
@@ -64,17 +133,22 @@ impl PriceFilter {
                 if limit_price > self.max_price && self.max_price != QuoteCurrency::new_zero() {
                     return Err(OrderError::LimitPriceAboveMax);
                 }
-                if ((limit_price - self.min_price) % self.tick_size) != QuoteCurrency::new_zero() {
+                if !(limit_price - self.min_price)
+                    .inner()
+                    .is_multiple_of(self.tick_size.inner())
+                {
                     return Err(OrderError::InvalidOrderPriceStepSize);
                 }
-                if limit_price > mark_price * self.multiplier_up
-                    && self.multiplier_up != Decimal::ZERO
+                let (multiplier_up, multiplier_down) = match (&self.side_multipliers, order.side())
                 {
+                    (Some(m), Side::Buy) => (m.bid_multiplier_up, m.bid_multiplier_down),
+                    (Some(m), Side::Sell) => (m.ask_multiplier_up, m.ask_multiplier_down),
+                    (None, _) => (self.multiplier_up, self.multiplier_down),
+                };
+                if limit_price > mark_price * multiplier_up && multiplier_up != T::ZERO {
                     return Err(OrderError::LimitPriceAboveMultiple);
                 }
-                if limit_price < mark_price * self.multiplier_down
-                    && self.multiplier_down != Decimal::ZERO
-                {
+                if limit_price < mark_price * multiplier_down && multiplier_down != T::ZERO {
                     return Err(OrderError::LimitPriceBelowMultiple);
                 }
                 Ok(())
@@ -89,7 +163,7 @@ impl PriceFilter {
         market_update: &MarketUpdate<S>,
     ) -> Result<(), Error>
     where
-        S: Currency,
+        S: Currency<Num = T>,
     {
         match market_update {
             MarketUpdate::Bba { bid, ask } => {
@@ -128,6 +202,32 @@ impl PriceFilter {
                 enforce_bid_ask_spread(*bid, *ask)?;
                 enforce_bid_ask_spread(*low, *high)?;
             }
+            // The index does not trade, so only the positivity of its price is enforced.
+            MarketUpdate::Index { price } => {
+                if *price <= QuoteCurrency::new_zero() {
+                    return Err(Error::InvalidMarketUpdatePrice);
+                }
+            }
+            MarketUpdate::Depth { bids, asks } => {
+                let (Some((best_bid, _)), Some((best_ask, _))) = (bids.first(), asks.first())
+                else {
+                    return Err(Error::InvalidMarketUpdateDepth);
+                };
+                for (price, quantity) in bids.iter().chain(asks.iter()) {
+                    enforce_min_price(self.min_price, *price)?;
+                    enforce_max_price(self.max_price, *price)?;
+                    enforce_step_size(self.tick_size, *price)?;
+                    if *quantity <= S::new_zero() {
+                        return Err(Error::InvalidMarketUpdateDepth);
+                    }
+                }
+                if bids.windows(2).any(|w| w[0].0 <= w[1].0)
+                    || asks.windows(2).any(|w| w[0].0 >= w[1].0)
+                {
+                    return Err(Error::InvalidMarketUpdateDepth);
+                }
+                enforce_bid_ask_spread(*best_bid, *best_ask)?;
+            }
         }
         Ok(())
     }
@@ -135,7 +235,10 @@ impl PriceFilter {
 
 /// Errors if there is no bid-ask spread
 #[inline]
-fn enforce_bid_ask_spread(bid: QuoteCurrency, ask: QuoteCurrency) -> Result<(), Error> {
+fn enforce_bid_ask_spread<T: Mon>(
+    bid: QuoteCurrency<T>,
+    ask: QuoteCurrency<T>,
+) -> Result<(), Error> {
     if bid >= ask {
         return Err(Error::InvalidMarketUpdateBidAskSpread);
     }
@@ -145,8 +248,11 @@ fn enforce_bid_ask_spread(bid: QuoteCurrency, ask: QuoteCurrency) -> Result<(),
 /// Make sure the price is not too low
 /// Disabled if `min_price` == 0
 #[inline]
-fn enforce_min_price(min_price: QuoteCurrency, price: QuoteCurrency) -> Result<(), Error> {
-    if price < min_price && min_price != quote!(0) {
+fn enforce_min_price<T: Mon>(
+    min_price: QuoteCurrency<T>,
+    price: QuoteCurrency<T>,
+) -> Result<(), Error> {
+    if price < min_price && !min_price.is_zero() {
         return Err(Error::MarketUpdatePriceTooLow);
     }
     Ok(())
@@ -155,8 +261,11 @@ fn enforce_min_price(min_price: QuoteCurrency, price: QuoteCurrency) -> Result<(
 /// Make sure the price is not too high
 /// Disabled if `max_price` == 0
 #[inline]
-fn enforce_max_price(max_price: QuoteCurrency, price: QuoteCurrency) -> Result<(), Error> {
-    if price > max_price && max_price != quote!(0) {
+fn enforce_max_price<T: Mon>(
+    max_price: QuoteCurrency<T>,
+    price: QuoteCurrency<T>,
+) -> Result<(), Error> {
+    if price > max_price && !max_price.is_zero() {
         return Err(Error::MarketUpdatePriceTooHigh);
     }
     Ok(())
@@ -164,8 +273,11 @@ fn enforce_max_price(max_price: QuoteCurrency, price: QuoteCurrency) -> Result<(
 
 /// Make sure the price conforms to the step size
 #[inline]
-fn enforce_step_size(step_size: QuoteCurrency, price: QuoteCurrency) -> Result<(), Error> {
-    if (price % step_size) != QuoteCurrency::new_zero() {
+fn enforce_step_size<T: Mon>(
+    step_size: QuoteCurrency<T>,
+    price: QuoteCurrency<T>,
+) -> Result<(), Error> {
+    if !price.inner().is_multiple_of(step_size.inner()) {
         return Err(Error::MarketUpdatePriceStepSize);
     }
     Ok(())
@@ -186,6 +298,7 @@ mod tests {
             tick_size: quote!(0.1),
             multiplier_up: Dec!(1.2),
             multiplier_down: Dec!(0.8),
+            side_multipliers: None,
         };
         let mark_price = quote!(100.0);
 
@@ -239,4 +352,35 @@ mod tests {
             Err(OrderError::InvalidOrderPriceStepSize)
         );
     }
+
+    #[test]
+    fn price_filter_side_multipliers() {
+        let filter = PriceFilter {
+            side_multipliers: Some(SideMultipliers::buy_up_sell_down(Dec!(1.05), Dec!(0.95))),
+            ..Default::default()
+        };
+        let mark_price = quote!(100);
+
+        let order = Order::limit(Side::Buy, quote!(105), base!(0.1)).unwrap();
+        filter.validate_order(&order, mark_price).unwrap();
+        let order = Order::limit(Side::Buy, quote!(106), base!(0.1)).unwrap();
+        assert_eq!(
+            filter.validate_order(&order, mark_price),
+            Err(OrderError::LimitPriceAboveMultiple)
+        );
+        // Buy prices are not bound from below.
+        let order = Order::limit(Side::Buy, quote!(50), base!(0.1)).unwrap();
+        filter.validate_order(&order, mark_price).unwrap();
+
+        let order = Order::limit(Side::Sell, quote!(95), base!(0.1)).unwrap();
+        filter.validate_order(&order, mark_price).unwrap();
+        let order = Order::limit(Side::Sell, quote!(94), base!(0.1)).unwrap();
+        assert_eq!(
+            filter.validate_order(&order, mark_price),
+            Err(OrderError::LimitPriceBelowMultiple)
+        );
+        // Sell prices are not bound from above, even beyond the symmetric `multiplier_up`.
+        let order = Order::limit(Side::Sell, quote!(300), base!(0.1)).unwrap();
+        filter.validate_order(&order, mark_price).unwrap();
+    }
 }
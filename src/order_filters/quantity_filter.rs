@@ -1,16 +1,15 @@
 //! This module contains order filtering related code
 
-use fpdec::{Dec, Decimal};
-
 use crate::{
     prelude::OrderError,
-    types::{Currency, Order},
+    types::{Currency, Mon, Order, OrderType},
 };
 
 /// The `SizeFilter` defines the quantity rules that each order needs to follow
 /// The generic currency `S` is always the `PairedCurrency` of the margin
 /// currency
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct QuantityFilter<S>
 where
     S: Currency,
@@ -23,6 +22,11 @@ where
     /// Disabled if 0
     pub max_quantity: S,
 
+    /// Defines the maximum `quantity` of market orders, like the `MARKET_LOT_SIZE` filter of Binance,
+    /// which is usually lower than the `max_quantity`.
+    /// Disabled if 0
+    pub max_market_quantity: S,
+
     /// Defines the intervals that a `quantity` can be increased / decreased by.
     /// For the filter to pass,
     /// (quantity - min_qty) % step_size == 0
@@ -37,7 +41,8 @@ where
         Self {
             min_quantity: S::new_zero(),
             max_quantity: S::new_zero(),
-            step_size: S::new(Dec!(1)),
+            max_market_quantity: S::new_zero(),
+            step_size: S::new(S::Num::ONE),
         }
     }
 }
@@ -53,11 +58,75 @@ where
         if order.quantity() > self.max_quantity && self.max_quantity != S::new_zero() {
             return Err(OrderError::QuantityTooHigh);
         }
-        if ((order.quantity() - self.min_quantity) % self.step_size) != S::new_zero() {
+        if matches!(order.order_type(), OrderType::Market)
+            && order.quantity() > self.max_market_quantity
+            && self.max_market_quantity != S::new_zero()
+        {
+            return Err(OrderError::MarketQuantityTooHigh);
+        }
+        if !(order.quantity() - self.min_quantity)
+            .inner()
+            .is_multiple_of(self.step_size.inner())
+        {
             return Err(OrderError::InvalidQuantityStepSize);
         }
+        if let Some(display_quantity) = order.display_quantity() {
+            if display_quantity < self.min_quantity && self.min_quantity != S::new_zero() {
+                return Err(OrderError::QuantityTooLow);
+            }
+            if !(display_quantity - self.min_quantity)
+                .inner()
+                .is_multiple_of(self.step_size.inner())
+            {
+                return Err(OrderError::InvalidQuantityStepSize);
+            }
+        }
         Ok(())
     }
+
+    /// Round the `quantity` down onto the steps of `step_size` starting at the `min_quantity`,
+    /// so it passes the step size check.
+    /// Quantities below the `min_quantity` round to zero.
+    pub fn round_to_step(&self, quantity: S) -> S {
+        if quantity < self.min_quantity || quantity <= S::new_zero() {
+            return S::new_zero();
+        }
+        let steps = ((quantity - self.min_quantity).inner() / self.step_size.inner()).trunc();
+        self.min_quantity + S::new(steps * self.step_size.inner())
+    }
+
+    /// Clamp the `quantity` of a limit order into the range of `min_quantity` and `max_quantity`
+    /// and round it down onto the steps, so it passes all checks of the filter.
+    /// A non-positive `quantity` stays zero, as there is nothing to trade.
+    pub fn clamp_to_filter(&self, quantity: S) -> S {
+        self.clamp(quantity, self.max_quantity)
+    }
+
+    /// Like `clamp_to_filter`, but for market orders, which are also capped at the `max_market_quantity`.
+    pub fn clamp_market_to_filter(&self, quantity: S) -> S {
+        let max_quantity = if self.max_market_quantity == S::new_zero()
+            || (self.max_quantity != S::new_zero() && self.max_quantity < self.max_market_quantity)
+        {
+            self.max_quantity
+        } else {
+            self.max_market_quantity
+        };
+        self.clamp(quantity, max_quantity)
+    }
+
+    fn clamp(&self, quantity: S, max_quantity: S) -> S {
+        if quantity <= S::new_zero() {
+            return S::new_zero();
+        }
+        let mut quantity = quantity;
+        if max_quantity != S::new_zero() && quantity > max_quantity {
+            quantity = max_quantity;
+        }
+        if quantity < self.min_quantity {
+            quantity = self.min_quantity;
+        }
+        self.round_to_step(quantity)
+    }
 }
 
 #[cfg(test)]
@@ -70,6 +139,7 @@ mod tests {
         let filter = QuantityFilter {
             min_quantity: quote!(10),
             max_quantity: quote!(1000),
+            max_market_quantity: quote!(0),
             step_size: quote!(1),
         };
 
@@ -94,4 +164,51 @@ mod tests {
             Err(OrderError::InvalidQuantityStepSize)
         );
     }
+
+    #[test]
+    fn quantity_filter_max_market_quantity() {
+        let filter = QuantityFilter {
+            min_quantity: base!(0.1),
+            max_quantity: base!(1000),
+            max_market_quantity: base!(100),
+            step_size: base!(0.05),
+        };
+
+        let order = Order::market(Side::Buy, base!(200)).unwrap();
+        assert_eq!(
+            filter.validate_order(&order),
+            Err(OrderError::MarketQuantityTooHigh)
+        );
+        let order = Order::limit(Side::Buy, quote!(100), base!(200)).unwrap();
+        filter.validate_order(&order).unwrap();
+    }
+
+    #[test]
+    fn quantity_filter_round_and_clamp() {
+        let filter = QuantityFilter {
+            min_quantity: base!(0.1),
+            max_quantity: base!(1000),
+            max_market_quantity: base!(100),
+            step_size: base!(0.05),
+        };
+        assert_eq!(filter.round_to_step(base!(0.27)), base!(0.25));
+        assert_eq!(filter.round_to_step(base!(0.25)), base!(0.25));
+        assert_eq!(filter.round_to_step(base!(0.05)), base!(0));
+
+        assert_eq!(filter.clamp_to_filter(base!(0.05)), base!(0.1));
+        assert_eq!(filter.clamp_to_filter(base!(0)), base!(0));
+        assert_eq!(filter.clamp_to_filter(base!(1500)), base!(1000));
+        assert_eq!(filter.clamp_to_filter(base!(12.34)), base!(12.3));
+        assert_eq!(filter.clamp_market_to_filter(base!(1500)), base!(100));
+        assert_eq!(filter.clamp_market_to_filter(base!(12.34)), base!(12.3));
+
+        // The clamped quantities pass the filter.
+        for quantity in [base!(0.05), base!(1500), base!(12.34)] {
+            let order =
+                Order::limit(Side::Buy, quote!(100), filter.clamp_to_filter(quantity)).unwrap();
+            filter.validate_order(&order).unwrap();
+            let order = Order::market(Side::Buy, filter.clamp_market_to_filter(quantity)).unwrap();
+            filter.validate_order(&order).unwrap();
+        }
+    }
 }
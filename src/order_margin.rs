@@ -1,80 +1,365 @@
-use hashbrown::HashMap;
+use alloc::{sync::Arc, vec::Vec};
+use core::fmt::Debug;
 
 use crate::{
     exchange::EXPECT_LIMIT_PRICE,
     prelude::Position,
-    types::{Currency, Fee, MarginCurrency, Order, Side},
+    types::{Currency, Fee, Leverage, MarginCurrency, Order, PositionMode, QuoteCurrency, Side},
     utils::{max, min},
 };
 
-/// Compute the current order margin requirement.
-pub(crate) fn compute_order_margin<M>(
-    position: &Position<M>,
-    active_limit_orders: &HashMap<u64, Order<M::PairedCurrency>>,
-    fee: Fee,
-) -> M
+/// Combines the margin requirements of the resting buy and sell orders in `PositionMode::Netting`,
+/// as venues differ in how they margin overlapping orders of both sides.
+/// In `PositionMode::Hedge` the orders of both sides always require margin.
+pub trait OrderMarginModel<M>: Debug + Send + Sync
+where
+    M: Currency,
+{
+    /// Compute the order margin requirement.
+    ///
+    /// # Arguments:
+    /// `buy_margin`: The margin of the buy orders, including their fees,
+    ///     of which the part offset by a short position does not require margin.
+    /// `sell_margin`: The margin of the sell orders, of which the part offset by a long position does not require margin.
+    fn order_margin(&self, buy_margin: M, sell_margin: M) -> M;
+}
+
+/// Only the side with the larger margin requirement counts,
+/// as at most one side can add to the position once the other is filled.
+/// This is the BitMEX-style margining of orders, and the default.
+#[derive(Debug, Clone, Default)]
+pub struct MaxOfSides;
+
+impl<M> OrderMarginModel<M> for MaxOfSides
+where
+    M: Currency,
+{
+    fn order_margin(&self, buy_margin: M, sell_margin: M) -> M {
+        max(buy_margin, sell_margin)
+    }
+}
+
+/// Both sides require margin, which is more conservative when the orders of both sides may fill.
+/// This is the Binance-style margining of orders.
+#[derive(Debug, Clone, Default)]
+pub struct SumOfSides;
+
+impl<M> OrderMarginModel<M> for SumOfSides
+where
+    M: Currency,
+{
+    fn order_margin(&self, buy_margin: M, sell_margin: M) -> M {
+        buy_margin + sell_margin
+    }
+}
+
+fn default_order_margin_model<M: Currency>() -> Arc<dyn OrderMarginModel<M>> {
+    Arc::new(MaxOfSides)
+}
+
+/// The resting limit orders of one price, aggregated.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+struct PriceLevel<M>
+where
+    M: Currency,
+{
+    price: QuoteCurrency<M::Num>,
+    num_orders: usize,
+    /// The remaining quantity of all orders.
+    quantity: M::PairedCurrency,
+    /// The notional value of the remaining quantity of all orders.
+    notional: M,
+}
+
+/// The resting limit orders of one side, aggregated by price.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+struct SideAggregate<M>
+where
+    M: Currency,
+{
+    /// Sorted by ascending price.
+    levels: Vec<PriceLevel<M>>,
+    notional: M,
+    reduce_only_notional: M,
+}
+
+impl<M> Default for SideAggregate<M>
+where
+    M: Currency,
+{
+    fn default() -> Self {
+        Self {
+            levels: Vec::new(),
+            notional: M::new_zero(),
+            reduce_only_notional: M::new_zero(),
+        }
+    }
+}
+
+impl<M> SideAggregate<M>
+where
+    M: Currency + MarginCurrency,
+{
+    fn insert(&mut self, order: &Order<M::PairedCurrency>) {
+        let price = order.limit_price().expect(EXPECT_LIMIT_PRICE);
+        let quantity = order.remaining_quantity();
+        let notional = quantity.convert(price);
+        match self.levels.binary_search_by(|level| {
+            level
+                .price
+                .partial_cmp(&price)
+                .expect("Prices are comparable")
+        }) {
+            Ok(idx) => {
+                let level = &mut self.levels[idx];
+                level.num_orders += 1;
+                level.quantity += quantity;
+                level.notional += notional;
+            }
+            Err(idx) => self.levels.insert(
+                idx,
+                PriceLevel {
+                    price,
+                    num_orders: 1,
+                    quantity,
+                    notional,
+                },
+            ),
+        }
+        self.notional += notional;
+        if order.reduce_only() {
+            self.reduce_only_notional += notional;
+        }
+    }
+
+    fn remove(&mut self, order: &Order<M::PairedCurrency>) {
+        let price = order.limit_price().expect(EXPECT_LIMIT_PRICE);
+        let quantity = order.remaining_quantity();
+        let notional = quantity.convert(price);
+        let Ok(idx) = self.levels.binary_search_by(|level| {
+            level
+                .price
+                .partial_cmp(&price)
+                .expect("Prices are comparable")
+        }) else {
+            debug_assert!(false, "The order must have been inserted");
+            return;
+        };
+        let level = &mut self.levels[idx];
+        level.num_orders -= 1;
+        level.quantity -= quantity;
+        level.notional -= notional;
+        if level.num_orders == 0 {
+            self.levels.remove(idx);
+        }
+        self.notional -= notional;
+        if order.reduce_only() {
+            self.reduce_only_notional -= notional;
+        }
+        if self.levels.is_empty() {
+            // Don't let floating point backends accumulate rounding errors.
+            self.notional = M::new_zero();
+            self.reduce_only_notional = M::new_zero();
+        }
+    }
+
+    /// The notional value of all orders, including the `additional` one,
+    /// which is not offset by a position of size `offset`.
+    /// The orders with the lowest price are offset first.
+    fn unoffset_notional(
+        &self,
+        mut offset: M::PairedCurrency,
+        additional: Option<(QuoteCurrency<M::Num>, M::PairedCurrency)>,
+    ) -> M {
+        let mut notional = self.notional;
+        if let Some((price, quantity)) = additional {
+            notional += quantity.convert(price);
+        }
+        let mut additional = additional;
+        let mut levels = self.levels.iter().peekable();
+        while offset > M::PairedCurrency::new_zero() {
+            let (price, quantity, level_notional) = match (additional, levels.peek().copied()) {
+                (Some((price, quantity)), Some(level)) if price < level.price => {
+                    additional = None;
+                    (price, quantity, quantity.convert(price))
+                }
+                (_, Some(level)) => {
+                    levels.next();
+                    (level.price, level.quantity, level.notional)
+                }
+                (Some((price, quantity)), None) => {
+                    additional = None;
+                    (price, quantity, quantity.convert(price))
+                }
+                (None, None) => break,
+            };
+            if quantity <= offset {
+                notional -= level_notional;
+                offset -= quantity;
+            } else {
+                notional -= offset.convert(price);
+                break;
+            }
+        }
+        notional
+    }
+}
+
+/// Tracks the order margin requirement incrementally,
+/// by aggregating the resting limit orders of each side by price as they are inserted and removed.
+/// This avoids iterating over all active orders, which matters for strategies with many resting orders.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub(crate) struct OrderMargin<M>
+where
+    M: Currency,
+{
+    buys: SideAggregate<M>,
+    sells: SideAggregate<M>,
+    /// Reset to `MaxOfSides` when deserialized.
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_order_margin_model"))]
+    model: Arc<dyn OrderMarginModel<M>>,
+}
+
+impl<M> Default for OrderMargin<M>
+where
+    M: Currency,
+{
+    fn default() -> Self {
+        Self::with_model(default_order_margin_model())
+    }
+}
+
+impl<M> OrderMargin<M>
+where
+    M: Currency,
+{
+    /// Create an empty `OrderMargin` which combines the sides with the `model`.
+    pub(crate) fn with_model(model: Arc<dyn OrderMarginModel<M>>) -> Self {
+        Self {
+            buys: SideAggregate::default(),
+            sells: SideAggregate::default(),
+            model,
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn model(&self) -> &Arc<dyn OrderMarginModel<M>> {
+        &self.model
+    }
+
+    pub(crate) fn set_model(&mut self, model: Arc<dyn OrderMarginModel<M>>) {
+        self.model = model;
+    }
+}
+
+impl<M> OrderMargin<M>
 where
     M: Currency + MarginCurrency,
 {
-    // New Algo:
-    // 1: Orders are split into buys and sells
-    // 2: They are sorted by ascending price
-    // 3: Each side is handled separately
-    // 4: For buys:
-    //  - An existing short position offsets the order size closest to the positions entry price
-    //  - Anything that cannot be offset: notional value is accumulated
-    // 5: For sells its the same but reversed.
-
-    let mut buys = Vec::from_iter(
-        active_limit_orders
-            .values()
-            .filter(|order| matches!(order.side(), Side::Buy)),
-    );
-    buys.sort_by_key(|order| order.limit_price().expect(EXPECT_LIMIT_PRICE));
-    debug!("buys: {:?}", buys);
-
-    let mut sells = Vec::from_iter(
-        active_limit_orders
-            .values()
-            .filter(|order| matches!(order.side(), Side::Sell)),
-    );
-    sells.sort_by_key(|order| order.limit_price().expect(EXPECT_LIMIT_PRICE));
-    debug!("sells: {:?}", sells);
-
-    // accumulate notional value (+fee) of buy orders which are not offset by the position
-    let mut buy_margin_req = M::new_zero();
-    let mut remaining_short_size = min(position.size(), M::PairedCurrency::new_zero()).abs();
-    for b in &buys {
-        let mut order_qty = b.quantity();
-        if remaining_short_size > M::PairedCurrency::new_zero() {
-            // offset the order qty by as much as possible
-            let offset = max(order_qty, remaining_short_size);
-            order_qty -= offset;
-            remaining_short_size -= offset;
+    fn side_mut(&mut self, side: Side) -> &mut SideAggregate<M> {
+        match side {
+            Side::Buy => &mut self.buys,
+            Side::Sell => &mut self.sells,
         }
-        let order_value = order_qty.convert(b.limit_price().expect(EXPECT_LIMIT_PRICE));
-        let margin_req = order_value / position.leverage;
-        let fee = order_value * fee;
-        buy_margin_req = buy_margin_req + margin_req + fee;
-    }
-
-    let mut sell_margin_req = M::new_zero();
-    let mut remaining_long_size = max(position.size(), M::PairedCurrency::new_zero());
-    for s in &sells {
-        let mut order_qty = s.quantity();
-        if remaining_long_size > M::PairedCurrency::new_zero() {
-            // offset the order qty by as much as possible
-            let offset = max(order_qty, remaining_long_size);
-            order_qty -= offset;
-            remaining_long_size -= offset;
+    }
+
+    /// Add a resting limit order.
+    pub(crate) fn insert(&mut self, order: &Order<M::PairedCurrency>) {
+        self.side_mut(order.side()).insert(order);
+    }
+
+    /// Remove a resting limit order, which must have been inserted with the same remaining quantity.
+    pub(crate) fn remove(&mut self, order: &Order<M::PairedCurrency>) {
+        self.side_mut(order.side()).remove(order);
+    }
+
+    /// Compute the order margin requirement, as if the `additional` order was resting as well.
+    ///
+    /// In `PositionMode::Netting`, the orders of each side are offset by an opposite position,
+    /// starting with the lowest price, and the sides are combined by the `OrderMarginModel`.
+    /// In `PositionMode::Hedge`, both the long and short position can be held at the same time,
+    /// so the orders of both sides require margin for their notional value.
+    /// Reduce-only orders only require their fee.
+    pub(crate) fn order_margin(
+        &self,
+        position_mode: PositionMode,
+        position: &Position<M>,
+        fee: Fee<M::Num>,
+        additional: Option<&Order<M::PairedCurrency>>,
+    ) -> M {
+        let leverage = position.leverage;
+        let additional_of = |side: Side| {
+            additional
+                .filter(|order| order.side() == side)
+                .map(|order| {
+                    (
+                        order.limit_price().expect(EXPECT_LIMIT_PRICE),
+                        order.remaining_quantity(),
+                    )
+                })
+        };
+        match position_mode {
+            PositionMode::Netting => {
+                let zero = M::PairedCurrency::new_zero();
+                let buy_notional = self
+                    .buys
+                    .unoffset_notional(min(position.size(), zero).abs(), additional_of(Side::Buy));
+                let sell_notional = self
+                    .sells
+                    .unoffset_notional(max(position.size(), zero), additional_of(Side::Sell));
+                self.model.order_margin(
+                    notional_margin(buy_notional, leverage, fee),
+                    notional_margin(sell_notional, leverage, fee),
+                )
+            }
+            PositionMode::Hedge => {
+                [Side::Buy, Side::Sell]
+                    .into_iter()
+                    .fold(M::new_zero(), |acc, side| {
+                        let aggregate = match side {
+                            Side::Buy => &self.buys,
+                            Side::Sell => &self.sells,
+                        };
+                        let mut notional = aggregate.notional;
+                        let mut reduce_only_notional = aggregate.reduce_only_notional;
+                        if let Some(order) = additional.filter(|order| order.side() == side) {
+                            let order_value = order
+                                .remaining_quantity()
+                                .convert(order.limit_price().expect(EXPECT_LIMIT_PRICE));
+                            notional += order_value;
+                            if order.reduce_only() {
+                                reduce_only_notional += order_value;
+                            }
+                        }
+                        acc + (notional - reduce_only_notional) / leverage
+                            + fee_margin(notional, fee)
+                    })
+            }
         }
-        let order_value = order_qty.convert(s.limit_price().expect(EXPECT_LIMIT_PRICE));
-        let margin_req = order_value / position.leverage;
-        let fee = order_value * fee;
-        sell_margin_req = sell_margin_req + margin_req + fee;
     }
+}
+
+/// The margin required for orders of the `notional` value, including their fee.
+#[inline]
+fn notional_margin<M>(notional: M, leverage: Leverage, fee: Fee<M::Num>) -> M
+where
+    M: Currency,
+{
+    notional / leverage + fee_margin(notional, fee)
+}
 
-    max(buy_margin_req, sell_margin_req)
+/// The margin required for the fee of an order.
+/// Rebates (negative fees) are only credited once the order is filled,
+/// so they do not reduce the margin requirement.
+#[inline]
+pub(crate) fn fee_margin<M>(order_value: M, fee: Fee<M::Num>) -> M
+where
+    M: Currency,
+{
+    max(order_value * fee, M::new_zero())
 }
 
 #[cfg(test)]
@@ -85,36 +370,48 @@ mod tests {
     #[test]
     fn order_margin_no_position() {
         let fee = fee!(0.0002);
-        let mut account = Account::new(quote!(1000), leverage!(1), fee);
+        let mut account = Account::new(quote!(1000), leverage!(1), fee, PositionMode::Netting);
 
-        assert_eq!(
-            compute_order_margin(account.position(), &account.active_limit_orders, fee),
-            quote!(0)
-        );
+        assert_eq!(account.order_margin(), quote!(0));
 
         let mut order = Order::limit(Side::Buy, quote!(90), base!(1)).unwrap();
         order.set_id(0);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(90) + quote!(0.018)
-        );
+        assert_eq!(account.order_margin(), quote!(90) + quote!(0.018));
 
         let mut order = Order::limit(Side::Sell, quote!(100), base!(1)).unwrap();
         order.set_id(1);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(100) + quote!(0.02)
-        );
+        assert_eq!(account.order_margin(), quote!(100) + quote!(0.02));
 
         let mut order = Order::limit(Side::Sell, quote!(120), base!(1)).unwrap();
         order.set_id(2);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(220) + quote!(0.044)
-        );
+        assert_eq!(account.order_margin(), quote!(220) + quote!(0.044));
+    }
+
+    #[test]
+    fn order_margin_sum_of_sides() {
+        let fee = fee!(0.0002);
+        let mut account = Account::new(quote!(1000), leverage!(1), fee, PositionMode::Netting);
+        account.set_order_margin_model(Arc::new(SumOfSides));
+
+        let mut order = Order::limit(Side::Buy, quote!(90), base!(1)).unwrap();
+        order.set_id(0);
+        account.append_limit_order(order);
+        let mut order = Order::limit(Side::Sell, quote!(100), base!(1)).unwrap();
+        order.set_id(1);
+        account.append_limit_order(order);
+        assert_eq!(account.order_margin(), quote!(190) + quote!(0.038));
+
+        // The sell offset by a long position doesn't require margin.
+        account.position = Position {
+            size: base!(1),
+            entry_price: quote!(100),
+            position_margin: quote!(100),
+            leverage: leverage!(1),
+        };
+        assert_eq!(account.order_margin(), quote!(90) + quote!(0.018));
     }
 
     #[test]
@@ -122,49 +419,34 @@ mod tests {
         let _ = pretty_env_logger::try_init();
 
         let fee = fee!(0.0002);
-        let mut account = Account::new(quote!(1000), leverage!(1), fee);
+        let mut account = Account::new(quote!(1000), leverage!(1), fee, PositionMode::Netting);
         account.position = Position {
             size: base!(1),
             entry_price: quote!(100),
             position_margin: quote!(100),
             leverage: leverage!(1),
         };
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(0)
-        );
+        assert_eq!(account.order_margin(), quote!(0));
 
         let mut order = Order::limit(Side::Buy, quote!(90), base!(1)).unwrap();
         order.set_id(0);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(90) + quote!(0.018)
-        );
+        assert_eq!(account.order_margin(), quote!(90) + quote!(0.018));
 
         let mut order = Order::limit(Side::Sell, quote!(100), base!(1)).unwrap();
         order.set_id(1);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(90) + quote!(0.018)
-        );
+        assert_eq!(account.order_margin(), quote!(90) + quote!(0.018));
 
         let mut order = Order::limit(Side::Sell, quote!(120), base!(1)).unwrap();
         order.set_id(2);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(120) + quote!(0.024)
-        );
+        assert_eq!(account.order_margin(), quote!(120) + quote!(0.024));
 
         let mut order = Order::limit(Side::Buy, quote!(95), base!(1)).unwrap();
         order.set_id(3);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(185) + quote!(0.037)
-        );
+        assert_eq!(account.order_margin(), quote!(185) + quote!(0.037));
     }
 
     #[test]
@@ -172,48 +454,97 @@ mod tests {
         let _ = pretty_env_logger::try_init();
 
         let fee = fee!(0.0002);
-        let mut account = Account::new(quote!(1000), leverage!(1), fee);
+        let mut account = Account::new(quote!(1000), leverage!(1), fee, PositionMode::Netting);
         account.position = Position {
             size: base!(-1),
             entry_price: quote!(100),
             position_margin: quote!(100),
             leverage: leverage!(1),
         };
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(0)
-        );
+        assert_eq!(account.order_margin(), quote!(0));
 
         let mut order = Order::limit(Side::Buy, quote!(90), base!(1)).unwrap();
         order.set_id(0);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(0)
-        );
+        assert_eq!(account.order_margin(), quote!(0));
 
         let mut order = Order::limit(Side::Sell, quote!(100), base!(1)).unwrap();
         order.set_id(1);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(100) + quote!(0.02)
-        );
+        assert_eq!(account.order_margin(), quote!(100) + quote!(0.02));
 
         let mut order = Order::limit(Side::Sell, quote!(120), base!(1)).unwrap();
         order.set_id(2);
         account.append_limit_order(order);
-        assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(220) + quote!(0.044)
-        );
+        assert_eq!(account.order_margin(), quote!(220) + quote!(0.044));
 
         let mut order = Order::limit(Side::Buy, quote!(95), base!(1)).unwrap();
         order.set_id(3);
         account.append_limit_order(order);
+        assert_eq!(account.order_margin(), quote!(220) + quote!(0.044));
+    }
+
+    #[test]
+    fn order_margin_partial_offset() {
+        let fee = fee!(0.0002);
+        let position = Position {
+            size: base!(1.5),
+            entry_price: quote!(100),
+            position_margin: quote!(150),
+            leverage: leverage!(1),
+        };
+        let mut order_margin = OrderMargin::default();
+        for (id, price) in [(0, quote!(110)), (1, quote!(120))] {
+            let mut order = Order::limit(Side::Sell, price, base!(1)).unwrap();
+            order.set_id(id);
+            order_margin.insert(&order);
+        }
+        // The order at 110 is offset fully and the one at 120 by half.
+        assert_eq!(
+            order_margin.order_margin(PositionMode::Netting, &position, fee, None),
+            quote!(60) + quote!(0.012)
+        );
+
+        let mut order = Order::limit(Side::Sell, quote!(105), base!(1)).unwrap();
+        order.set_id(2);
+        assert_eq!(
+            order_margin.order_margin(PositionMode::Netting, &position, fee, Some(&order)),
+            quote!(175) + quote!(0.035)
+        );
+        order_margin.insert(&order);
+        assert_eq!(
+            order_margin.order_margin(PositionMode::Netting, &position, fee, None),
+            quote!(175) + quote!(0.035)
+        );
+        order_margin.remove(&order);
+        assert_eq!(
+            order_margin.order_margin(PositionMode::Netting, &position, fee, None),
+            quote!(60) + quote!(0.012)
+        );
+    }
+
+    #[test]
+    fn order_margin_hedge() {
+        let fee = fee!(0.0002);
+        let position = Position::<QuoteCurrency>::new(leverage!(2));
+        let mut order_margin = OrderMargin::default();
+        let mut buy = Order::limit(Side::Buy, quote!(100), base!(1)).unwrap();
+        buy.set_id(0);
+        order_margin.insert(&buy);
+        let mut sell = Order::limit(Side::Sell, quote!(110), base!(1)).unwrap();
+        sell.set_id(1);
+        order_margin.insert(&sell);
+        assert_eq!(
+            order_margin.order_margin(PositionMode::Hedge, &position, fee, None),
+            quote!(105) + quote!(0.042)
+        );
+
+        let mut reduce_only = Order::limit(Side::Sell, quote!(120), base!(1)).unwrap();
+        reduce_only.set_id(2);
+        reduce_only.set_reduce_only(true);
         assert_eq!(
-            compute_order_margin(&account.position, &account.active_limit_orders, fee),
-            quote!(220) + quote!(0.044)
+            order_margin.order_margin(PositionMode::Hedge, &position, fee, Some(&reduce_only)),
+            quote!(105) + quote!(0.066)
         );
     }
 }
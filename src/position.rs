@@ -1,13 +1,9 @@
-use fpdec::Decimal;
-
-use crate::{
-    quote,
-    types::{Currency, Leverage, MarginCurrency, QuoteCurrency},
-};
+use crate::types::{Currency, Error, Leverage, MarginCurrency, Mon, QuoteCurrency, Result};
 
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 /// Describes the position information of the account.
 /// It assumes isolated margining mechanism, because the margin is directly associated with the position.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct Position<M>
 where
     M: Currency + MarginCurrency,
@@ -17,7 +13,7 @@ where
     /// e.g.: XBTUSD has a contract size of 1 USD, so `M::PairedCurrency` is USD.
     pub(crate) size: M::PairedCurrency,
     /// The entry price of the position
-    pub(crate) entry_price: QuoteCurrency,
+    pub(crate) entry_price: QuoteCurrency<M::Num>,
     /// The position margin of account, same denotation as wallet_balance
     pub(crate) position_margin: M,
     /// The position leverage,
@@ -43,7 +39,7 @@ where
 
     /// Return the entry price of the position
     #[inline(always)]
-    pub fn entry_price(&self) -> QuoteCurrency {
+    pub fn entry_price(&self) -> QuoteCurrency<M::Num> {
         self.entry_price
     }
 
@@ -59,10 +55,72 @@ where
         self.leverage
     }
 
+    /// Return the position margin deposited in excess of the margin required at the set leverage.
+    #[inline]
+    pub fn variation_margin(&self) -> M {
+        self.position_margin - self.required_margin()
+    }
+
+    /// The margin required for the position at the set leverage.
+    #[inline]
+    fn required_margin(&self) -> M {
+        if self.size.is_zero() {
+            // The entry price of an empty position may be zero, which inverse contracts can't convert.
+            return M::new_zero();
+        }
+        self.size.abs().convert(self.entry_price) / self.leverage
+    }
+
+    /// Set the leverage and recompute the position margin for it, keeping the variation margin.
+    pub(crate) fn set_leverage(&mut self, leverage: Leverage) {
+        let variation_margin = self.variation_margin();
+        self.leverage = leverage;
+        self.position_margin = self.required_margin() + variation_margin;
+    }
+
+    /// Deposit additional margin into the position, which moves the liquidation price further away.
+    /// The available balance must have been checked by the caller.
+    pub(crate) fn deposit_variation_margin(&mut self, amount: M) -> Result<()> {
+        if amount <= M::new_zero() {
+            return Err(Error::InvalidAmount);
+        }
+        if self.size.is_zero() {
+            return Err(Error::NoOpenPosition);
+        }
+        self.position_margin += amount;
+        Ok(())
+    }
+
+    /// Release previously deposited margin from the position.
+    /// The position margin never falls below the margin required at the set leverage.
+    pub(crate) fn release_variation_margin(&mut self, amount: M) -> Result<()> {
+        if amount <= M::new_zero() {
+            return Err(Error::InvalidAmount);
+        }
+        if self.size.is_zero() {
+            return Err(Error::NoOpenPosition);
+        }
+        if amount > self.variation_margin() {
+            return Err(Error::NotEnoughPositionMargin);
+        }
+        self.position_margin -= amount;
+        Ok(())
+    }
+
+    /// The variation margin which remains after reducing the position by `quantity`,
+    /// as it is released in proportion to the reduction.
+    fn released_variation_margin(&self, quantity: M::PairedCurrency) -> M {
+        let variation_margin = self.variation_margin();
+        if variation_margin.is_zero() {
+            return variation_margin;
+        }
+        variation_margin * ((self.size.abs() - quantity) / self.size.abs()).inner()
+    }
+
     /// Returns the implied leverage of the position based on the position value and the collateral backing it.
     /// It is computed by dividing the total value of the position by the amount of margin required to hold that position.
     #[inline]
-    pub fn implied_leverage(&self, price: QuoteCurrency) -> Decimal {
+    pub fn implied_leverage(&self, price: QuoteCurrency<M::Num>) -> M::Num {
         let value = self.size.convert(price);
         value.inner() / self.position_margin.inner()
     }
@@ -70,7 +128,7 @@ where
     /// Return the positions unrealized profit and loss
     /// denoted in QUOTE when using linear futures,
     /// denoted in BASE when using inverse futures
-    pub fn unrealized_pnl(&self, bid: QuoteCurrency, ask: QuoteCurrency) -> M {
+    pub fn unrealized_pnl(&self, bid: QuoteCurrency<M::Num>, ask: QuoteCurrency<M::Num>) -> M {
         // The upnl is based on the possible fill price, not the mid-price, which is more conservative
         if self.size > M::PairedCurrency::new_zero() {
             M::pnl(self.entry_price, bid, self.size)
@@ -79,6 +137,60 @@ where
         }
     }
 
+    /// Return the price at which the position gets liquidated with isolated margin,
+    /// where only its `position_margin` backs it.
+    ///
+    /// # Arguments:
+    /// `maintenance_margin`: The maintenance margin requirement as a fraction,
+    /// e.g. of the `ContractSpecification`.
+    ///
+    /// # Returns:
+    /// `None` if there is no position or it can never reach its maintenance margin.
+    #[inline]
+    pub fn liquidation_price(&self, maintenance_margin: M::Num) -> Option<QuoteCurrency<M::Num>> {
+        self.liquidation_price_with_collateral(self.position_margin, maintenance_margin)
+    }
+
+    /// Return the price at which the position would get liquidated if the `collateral` backed it,
+    /// e.g. the position margin plus the available balance with cross margin.
+    /// Works for linear and inverse futures alike, see [`MarginCurrency::liquidation_price`].
+    #[inline]
+    pub fn liquidation_price_with_collateral(
+        &self,
+        collateral: M,
+        maintenance_margin: M::Num,
+    ) -> Option<QuoteCurrency<M::Num>> {
+        M::liquidation_price(self.entry_price, self.size, collateral, maintenance_margin)
+    }
+
+    /// Return the price at which the position margin plus the unrealized profit and loss reaches zero,
+    /// which is the liquidation price without any maintenance margin.
+    ///
+    /// # Returns:
+    /// `None` if there is no position or it can never lose its whole position margin,
+    /// e.g. a short of an inverse future without leverage.
+    #[inline]
+    pub fn bankruptcy_price(&self) -> Option<QuoteCurrency<M::Num>> {
+        self.liquidation_price(M::Num::ZERO)
+    }
+
+    /// The margin required to keep the position open at the `mark_price`.
+    ///
+    /// # Arguments:
+    /// `mark_price`: The price at which the position is valued.
+    /// `maintenance_margin`: The maintenance margin requirement as a fraction.
+    #[inline]
+    pub fn maintenance_margin(
+        &self,
+        mark_price: QuoteCurrency<M::Num>,
+        maintenance_margin: M::Num,
+    ) -> M {
+        if self.size.is_zero() {
+            return M::new_zero();
+        }
+        self.size.abs().convert(mark_price) * maintenance_margin
+    }
+
     /// Create a new position with all fields custom.
     ///
     /// # Arguments:
@@ -86,12 +198,12 @@ where
     ///     The `size` must have been approved by the `RiskEngine`.
     /// `entry_price`: The price at which the position was entered.
     ///
-    pub(crate) fn open_position(&mut self, size: M::PairedCurrency, price: QuoteCurrency) {
-        debug_assert!(price > quote!(0));
+    pub(crate) fn open_position(&mut self, size: M::PairedCurrency, price: QuoteCurrency<M::Num>) {
+        debug_assert!(price > QuoteCurrency::new_zero());
 
         self.size = size;
         self.entry_price = price;
-        self.position_margin = self.size.abs().convert(self.entry_price) / self.leverage;
+        self.position_margin = self.required_margin();
     }
 
     /// Increase a long (or neutral) position.
@@ -101,13 +213,18 @@ where
     ///     The `amount` must have been approved by the `RiskEngine`.
     /// `price`: The price at which it is sold.
     ///
-    pub(crate) fn increase_long(&mut self, quantity: M::PairedCurrency, price: QuoteCurrency) {
+    pub(crate) fn increase_long(
+        &mut self,
+        quantity: M::PairedCurrency,
+        price: QuoteCurrency<M::Num>,
+    ) {
         debug_assert!(
             quantity > M::PairedCurrency::new_zero(),
             "`amount` must be positive"
         );
         debug_assert!(self.size >= M::PairedCurrency::new_zero(), "Short is open");
 
+        let variation_margin = self.variation_margin();
         let new_size = self.size + quantity;
         self.entry_price = QuoteCurrency::new(
             (self.entry_price * self.size.inner() + price * quantity.inner()).inner()
@@ -115,7 +232,7 @@ where
         );
 
         self.size = new_size;
-        self.position_margin = self.size.abs().convert(self.entry_price) / self.leverage;
+        self.position_margin = self.required_margin() + variation_margin;
     }
 
     /// Reduce a long position.
@@ -127,7 +244,11 @@ where
     /// # Returns:
     /// If Ok, the net realized profit and loss for that specific futures contract.
     #[must_use]
-    pub(crate) fn decrease_long(&mut self, quantity: M::PairedCurrency, price: QuoteCurrency) -> M {
+    pub(crate) fn decrease_long(
+        &mut self,
+        quantity: M::PairedCurrency,
+        price: QuoteCurrency<M::Num>,
+    ) -> M {
         debug_assert!(
             self.size > M::PairedCurrency::new_zero(),
             "Open short or no position"
@@ -135,8 +256,9 @@ where
         debug_assert!(quantity > M::PairedCurrency::new_zero());
         debug_assert!(quantity <= self.size, "Quantity larger than position size");
 
+        let variation_margin = self.released_variation_margin(quantity);
         self.size -= quantity;
-        self.position_margin = self.size.abs().convert(self.entry_price) / self.leverage;
+        self.position_margin = self.required_margin() + variation_margin;
 
         M::pnl(self.entry_price, price, quantity)
     }
@@ -148,7 +270,11 @@ where
     ///     The `amount` must have been approved by the `RiskEngine`.
     /// `price`: The entry price.
     ///
-    pub(crate) fn increase_short(&mut self, quantity: M::PairedCurrency, price: QuoteCurrency) {
+    pub(crate) fn increase_short(
+        &mut self,
+        quantity: M::PairedCurrency,
+        price: QuoteCurrency<M::Num>,
+    ) {
         debug_assert!(
             quantity > M::PairedCurrency::new_zero(),
             "Amount must be positive; qed"
@@ -158,13 +284,14 @@ where
             "Position must not be long; qed"
         );
 
+        let variation_margin = self.variation_margin();
         let new_size = self.size - quantity;
         self.entry_price = QuoteCurrency::new(
             (self.entry_price.inner() * self.size.inner().abs() + price.inner() * quantity.inner())
                 / new_size.inner().abs(),
         );
         self.size = new_size;
-        self.position_margin = self.size.abs().convert(self.entry_price) / self.leverage;
+        self.position_margin = self.required_margin() + variation_margin;
     }
 
     /// Reduce a short position
@@ -179,7 +306,7 @@ where
     pub(crate) fn decrease_short(
         &mut self,
         quantity: M::PairedCurrency,
-        price: QuoteCurrency,
+        price: QuoteCurrency<M::Num>,
     ) -> M {
         debug_assert!(
             quantity > M::PairedCurrency::new_zero(),
@@ -194,9 +321,44 @@ where
             "Amount must be smaller than short position; qed"
         );
 
+        let variation_margin = self.released_variation_margin(quantity);
         self.size += quantity;
-        self.position_margin = self.size.abs().convert(self.entry_price) / self.leverage;
+        self.position_margin = self.required_margin() + variation_margin;
 
         M::pnl(self.entry_price, price, quantity.into_negative())
     }
+
+    /// Close the position and open one on the opposite side with the remainder, both at `price`,
+    /// which releases the whole position margin and allocates the margin of the new position.
+    ///
+    /// # Arguments:
+    /// `quantity`: The absolute quantity of the order, which must exceed the absolute position size.
+    ///     The `quantity` must have been approved by the `RiskEngine`.
+    /// `price`: The fill price.
+    ///
+    /// # Returns:
+    /// The realized profit and loss of the closed position.
+    #[must_use]
+    pub(crate) fn flip(&mut self, quantity: M::PairedCurrency, price: QuoteCurrency<M::Num>) -> M {
+        debug_assert!(
+            self.size != M::PairedCurrency::new_zero(),
+            "No position to flip; qed"
+        );
+        debug_assert!(
+            quantity > self.size.abs(),
+            "Quantity must exceed the position size; qed"
+        );
+
+        let size = self.size;
+        let remainder = quantity - size.abs();
+        if size > M::PairedCurrency::new_zero() {
+            let rpnl = self.decrease_long(size, price);
+            self.open_position(remainder.into_negative(), price);
+            rpnl
+        } else {
+            let rpnl = self.decrease_short(size.abs(), price);
+            self.open_position(remainder, price);
+            rpnl
+        }
+    }
 }
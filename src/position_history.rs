@@ -0,0 +1,143 @@
+use alloc::vec::Vec;
+
+use crate::{
+    position::Position,
+    types::{Currency, MarginCurrency, QuoteCurrency},
+};
+
+/// The kind of a `PositionChange`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PositionChangeKind {
+    /// A position was opened from a neutral position.
+    Open,
+    /// The position was increased, which moves the entry price to the weighted average.
+    Increase,
+    /// The position was partially reduced, which keeps the entry price.
+    Decrease,
+    /// The position was fully closed.
+    Close,
+    /// The position was closed and reopened on the other side at the fill price.
+    Flip,
+    /// The position was closed in a liquidation.
+    Liquidation,
+}
+
+/// A single mutation of a position with its state before and after,
+/// to audit the weighted average entry price and margin accounting.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct PositionChange<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The timestamp in nanoseconds of the change.
+    pub ts_ns: i64,
+    /// The kind of the change.
+    pub kind: PositionChangeKind,
+    /// The price at which the change was filled.
+    pub fill_price: QuoteCurrency<M::Num>,
+    /// The position size before the change, negative denoting a short.
+    pub size_before: M::PairedCurrency,
+    /// The position size after the change.
+    pub size_after: M::PairedCurrency,
+    /// The entry price before the change.
+    pub entry_price_before: QuoteCurrency<M::Num>,
+    /// The entry price after the change.
+    pub entry_price_after: QuoteCurrency<M::Num>,
+    /// The position margin before the change.
+    pub position_margin_before: M,
+    /// The position margin after the change.
+    pub position_margin_after: M,
+}
+
+impl<M> PositionChange<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The signed quantity by which the position size changed.
+    #[inline(always)]
+    pub fn quantity(&self) -> M::PairedCurrency {
+        self.size_after - self.size_before
+    }
+}
+
+/// Records every mutation of the positions of an `Account` in chronological order.
+/// In `PositionMode::Hedge` the changes of the long and short position are interleaved
+/// and distinguishable by the sign of their sizes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct PositionHistory<M>
+where
+    M: Currency + MarginCurrency,
+{
+    changes: Vec<PositionChange<M>>,
+}
+
+impl<M> Default for PositionHistory<M>
+where
+    M: Currency + MarginCurrency,
+{
+    fn default() -> Self {
+        Self {
+            changes: Vec::new(),
+        }
+    }
+}
+
+impl<M> PositionHistory<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// Append the change from `before` to `after`, unless the size did not change.
+    pub(crate) fn record(
+        &mut self,
+        ts_ns: i64,
+        before: &Position<M>,
+        after: &Position<M>,
+        fill_price: QuoteCurrency<M::Num>,
+        liquidation: bool,
+    ) {
+        if before.size == after.size {
+            return;
+        }
+        let zero = M::PairedCurrency::new_zero();
+        let kind = if liquidation {
+            PositionChangeKind::Liquidation
+        } else if before.size == zero {
+            PositionChangeKind::Open
+        } else if after.size == zero {
+            PositionChangeKind::Close
+        } else if (before.size > zero) != (after.size > zero) {
+            PositionChangeKind::Flip
+        } else if after.size.abs() > before.size.abs() {
+            PositionChangeKind::Increase
+        } else {
+            PositionChangeKind::Decrease
+        };
+        self.changes.push(PositionChange {
+            ts_ns,
+            kind,
+            fill_price,
+            size_before: before.size,
+            size_after: after.size,
+            entry_price_before: before.entry_price,
+            entry_price_after: after.entry_price,
+            position_margin_before: before.position_margin,
+            position_margin_after: after.position_margin,
+        });
+    }
+
+    /// All changes in chronological order.
+    #[inline(always)]
+    pub fn changes(&self) -> &[PositionChange<M>] {
+        &self.changes
+    }
+
+    /// The changes of a `kind` in chronological order.
+    pub fn changes_of(&self, kind: PositionChangeKind) -> impl Iterator<Item = &PositionChange<M>> {
+        self.changes
+            .iter()
+            .filter(move |change| change.kind == kind)
+    }
+}
@@ -0,0 +1,146 @@
+//! Caps on the notional value of positions, like the leverage brackets of real venues.
+
+use alloc::vec::Vec;
+
+use crate::{
+    types::{Currency, Error, Leverage, Result},
+    utils::min,
+};
+
+/// A tier of the leverage brackets of a venue:
+/// Positions up to `max_notional` may use a leverage of up to `max_leverage`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct LeverageBracket<M>
+where
+    M: Currency,
+{
+    /// The maximum absolute notional value of a position in this bracket, denoted in the margin currency.
+    pub max_notional: M,
+    /// The maximum leverage allowed in this bracket.
+    pub max_leverage: Leverage,
+}
+
+/// Limits the absolute notional value of positions, denoted in the margin currency.
+/// Orders which would increase a position beyond the limit are rejected.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct PositionLimit<M>
+where
+    M: Currency,
+{
+    max_notional: Option<M>,
+    brackets: Vec<LeverageBracket<M>>,
+}
+
+impl<M> Default for PositionLimit<M>
+where
+    M: Currency,
+{
+    fn default() -> Self {
+        Self {
+            max_notional: None,
+            brackets: Vec::new(),
+        }
+    }
+}
+
+impl<M> PositionLimit<M>
+where
+    M: Currency,
+{
+    /// Create a new `PositionLimit`.
+    ///
+    /// # Arguments:
+    /// `max_notional`: A cap on the notional value regardless of the leverage, if any.
+    /// `brackets`: The leverage brackets in any order, may be empty.
+    /// With brackets, the notional value is capped by the largest bracket which allows the leverage of the position,
+    /// and positions with a leverage above every bracket are rejected.
+    ///
+    /// # Returns:
+    /// Either a valid `PositionLimit` or an Error if some notional value is not positive.
+    pub fn new(max_notional: Option<M>, mut brackets: Vec<LeverageBracket<M>>) -> Result<Self> {
+        if max_notional.is_some_and(|n| n <= M::new_zero())
+            || brackets.iter().any(|b| b.max_notional <= M::new_zero())
+        {
+            return Err(Error::InvalidPositionLimit);
+        }
+        brackets.sort_by(|a, b| {
+            a.max_notional
+                .partial_cmp(&b.max_notional)
+                .expect("Notional values are comparable")
+        });
+
+        Ok(Self {
+            max_notional,
+            brackets,
+        })
+    }
+
+    /// The leverage brackets sorted by ascending `max_notional`.
+    #[inline(always)]
+    pub fn brackets(&self) -> &[LeverageBracket<M>] {
+        &self.brackets
+    }
+
+    /// The maximum absolute notional value of a position with the given `leverage`,
+    /// or `None` if it is unlimited.
+    pub fn max_notional(&self, leverage: Leverage) -> Option<M> {
+        let bracket_limit = if self.brackets.is_empty() {
+            None
+        } else {
+            Some(
+                self.brackets
+                    .iter()
+                    .rev()
+                    .find(|b| b.max_leverage.inner() >= leverage.inner())
+                    .map_or(M::new_zero(), |b| b.max_notional),
+            )
+        };
+        match (self.max_notional, bracket_limit) {
+            (Some(a), Some(b)) => Some(min(a, b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn position_limit() {
+        assert_eq!(
+            PositionLimit::<QuoteCurrency>::default().max_notional(leverage!(100)),
+            None
+        );
+        assert_eq!(
+            PositionLimit::new(Some(quote!(0)), vec![]),
+            Err(Error::InvalidPositionLimit)
+        );
+
+        let limit = PositionLimit::new(
+            None,
+            vec![
+                LeverageBracket {
+                    max_notional: quote!(250000),
+                    max_leverage: leverage!(50),
+                },
+                LeverageBracket {
+                    max_notional: quote!(50000),
+                    max_leverage: leverage!(125),
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(limit.brackets()[0].max_notional, quote!(50000));
+        assert_eq!(limit.max_notional(leverage!(125)), Some(quote!(50000)));
+        assert_eq!(limit.max_notional(leverage!(51)), Some(quote!(50000)));
+        assert_eq!(limit.max_notional(leverage!(10)), Some(quote!(250000)));
+        let limit = PositionLimit::new(Some(quote!(100000)), limit.brackets().to_vec()).unwrap();
+        assert_eq!(limit.max_notional(leverage!(10)), Some(quote!(100000)));
+        assert_eq!(limit.max_notional(leverage!(100)), Some(quote!(50000)));
+        assert_eq!(limit.max_notional(leverage!(126)), Some(quote!(0)));
+    }
+}
@@ -0,0 +1,205 @@
+//! Take-profit and stop-loss prices attached to a position instead of discrete orders.
+
+use crate::{
+    types::{Mon, QuoteCurrency, Side},
+    utils::{max, min},
+};
+
+/// The take-profit and stop-loss prices of a position, see `Exchange::set_position_tpsl`.
+/// Once the last price reaches either of them, the whole position is closed with a market order,
+/// regardless of how its size changed in the meantime.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct PositionTpSl<T>
+where
+    T: Mon,
+{
+    side: Side,
+    take_profit: Option<QuoteCurrency<T>>,
+    stop_loss: Option<QuoteCurrency<T>>,
+}
+
+impl<T> PositionTpSl<T>
+where
+    T: Mon,
+{
+    pub(crate) fn new(
+        side: Side,
+        take_profit: Option<QuoteCurrency<T>>,
+        stop_loss: Option<QuoteCurrency<T>>,
+    ) -> Self {
+        Self {
+            side,
+            take_profit,
+            stop_loss,
+        }
+    }
+
+    /// The side of the protected position, `Side::Buy` for a long position.
+    #[inline(always)]
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// The price at which the position is closed in profit, if any.
+    #[inline(always)]
+    pub fn take_profit(&self) -> Option<QuoteCurrency<T>> {
+        self.take_profit
+    }
+
+    /// The price at which the position is closed at a loss, if any.
+    #[inline(always)]
+    pub fn stop_loss(&self) -> Option<QuoteCurrency<T>> {
+        self.stop_loss
+    }
+
+    /// Whether the prices are on the profitable and losing side of the `price` respectively.
+    pub(crate) fn is_valid(&self, price: QuoteCurrency<T>) -> bool {
+        let (above, below) = match self.side {
+            Side::Buy => (self.take_profit, self.stop_loss),
+            Side::Sell => (self.stop_loss, self.take_profit),
+        };
+        above.is_none_or(|above| above > price) && below.is_none_or(|below| below < price)
+    }
+
+    /// Whether a price in the range from `low` to `high` reached the take-profit or the stop-loss.
+    pub(crate) fn is_triggered(&self, low: QuoteCurrency<T>, high: QuoteCurrency<T>) -> bool {
+        let (above, below) = match self.side {
+            Side::Buy => (self.take_profit, self.stop_loss),
+            Side::Sell => (self.stop_loss, self.take_profit),
+        };
+        above.is_some_and(|above| high >= above) || below.is_some_and(|below| low <= below)
+    }
+}
+
+/// A take-profit of a position which trails its best price, see `Exchange::set_trailing_take_profit`.
+/// It activates once the last price reaches the `activation_price`,
+/// then follows the peak of a long position (or the trough of a short position)
+/// and closes the whole position with a market order once the last price retraces by the `offset`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct TrailingTakeProfit<T>
+where
+    T: Mon,
+{
+    side: Side,
+    activation_price: QuoteCurrency<T>,
+    offset: QuoteCurrency<T>,
+    peak: Option<QuoteCurrency<T>>,
+}
+
+impl<T> TrailingTakeProfit<T>
+where
+    T: Mon,
+{
+    pub(crate) fn new(
+        side: Side,
+        activation_price: QuoteCurrency<T>,
+        offset: QuoteCurrency<T>,
+    ) -> Self {
+        Self {
+            side,
+            activation_price,
+            offset,
+            peak: None,
+        }
+    }
+
+    /// The side of the protected position, `Side::Buy` for a long position.
+    #[inline(always)]
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// The price at which the trailing starts.
+    #[inline(always)]
+    pub fn activation_price(&self) -> QuoteCurrency<T> {
+        self.activation_price
+    }
+
+    /// The distance by which the stop trails the peak.
+    #[inline(always)]
+    pub fn offset(&self) -> QuoteCurrency<T> {
+        self.offset
+    }
+
+    /// The best price since the activation, or `None` if not activated yet.
+    #[inline(always)]
+    pub fn peak(&self) -> Option<QuoteCurrency<T>> {
+        self.peak
+    }
+
+    /// The price at which the position is closed, or `None` if not activated yet.
+    pub fn stop_price(&self) -> Option<QuoteCurrency<T>> {
+        self.peak.map(|peak| match self.side {
+            Side::Buy => peak - self.offset,
+            Side::Sell => peak + self.offset,
+        })
+    }
+
+    /// Follow the price in the range from `low` to `high`.
+    /// As the order of the extremes within the range is unknown,
+    /// the retracement is checked against the peak before the range, which moves the peak afterwards.
+    ///
+    /// # Returns:
+    /// Whether the position is to be closed.
+    pub(crate) fn update(&mut self, low: QuoteCurrency<T>, high: QuoteCurrency<T>) -> bool {
+        let triggered = self.stop_price().is_some_and(|stop_price| match self.side {
+            Side::Buy => low <= stop_price,
+            Side::Sell => high >= stop_price,
+        });
+        if triggered {
+            return true;
+        }
+        let (best, is_reached) = match self.side {
+            Side::Buy => (high, high >= self.activation_price),
+            Side::Sell => (low, low <= self.activation_price),
+        };
+        self.peak = match (self.peak, self.side) {
+            (Some(peak), Side::Buy) => Some(max(peak, best)),
+            (Some(peak), Side::Sell) => Some(min(peak, best)),
+            (None, _) if is_reached => Some(best),
+            (None, _) => None,
+        };
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn position_tpsl() {
+        let long = PositionTpSl::new(Side::Buy, Some(quote!(110)), Some(quote!(95)));
+        assert!(long.is_valid(quote!(100)));
+        assert!(!long.is_valid(quote!(111)));
+        assert!(!long.is_triggered(quote!(96), quote!(109)));
+        assert!(long.is_triggered(quote!(96), quote!(110)));
+        assert!(long.is_triggered(quote!(95), quote!(100)));
+
+        let short = PositionTpSl::new(Side::Sell, Some(quote!(90)), None);
+        assert!(short.is_valid(quote!(100)));
+        assert!(!short.is_valid(quote!(89)));
+        assert!(!short.is_triggered(quote!(91), quote!(200)));
+        assert!(short.is_triggered(quote!(90), quote!(100)));
+    }
+
+    #[test]
+    fn trailing_take_profit() {
+        let mut long = TrailingTakeProfit::new(Side::Buy, quote!(110), quote!(5));
+        assert!(!long.update(quote!(100), quote!(109)));
+        assert_eq!(long.peak(), None);
+        assert!(!long.update(quote!(100), quote!(112)));
+        assert_eq!(long.stop_price(), Some(quote!(107)));
+        assert!(!long.update(quote!(108), quote!(120)));
+        assert_eq!(long.stop_price(), Some(quote!(115)));
+        assert!(long.update(quote!(115), quote!(118)));
+
+        let mut short = TrailingTakeProfit::new(Side::Sell, quote!(90), quote!(2));
+        assert!(!short.update(quote!(85), quote!(95)));
+        assert_eq!(short.stop_price(), Some(quote!(87)));
+        assert!(short.update(quote!(86), quote!(87)));
+    }
+}
@@ -0,0 +1,240 @@
+//! Ready-made contract specifications of popular venues and symbols.
+//!
+//! The values reflect the base tier of each venue at the time of writing:
+//! the lowest fee tier without any discounts and the maintenance margin of the smallest risk limit.
+//! Venues change their rules from time to time, so verify them against the venue before relying on them.
+//!
+//! Linear contracts are margined in `QuoteCurrency` and specified in `BaseCurrency`,
+//! inverse contracts are margined in `BaseCurrency` and specified in `QuoteCurrency`:
+//! ```rust
+//! use lfest::{account_tracker::NoAccountTracker, prelude::*, presets};
+//!
+//! let config = Config::new(quote!(1000), 200, leverage!(5), presets::binance_btcusdt()).unwrap();
+//! let exchange = Exchange::<NoAccountTracker, BaseCurrency>::new(NoAccountTracker, config);
+//! ```
+
+use alloc::string::ToString;
+
+use fpdec::{Dec, Decimal};
+
+use crate::{
+    base,
+    contract_specification::{ContractSpecification, MarkMethod},
+    fee,
+    fee_schedule::FeeSchedule,
+    order_filters::{
+        MaxNumOrdersFilter, NotionalFilter, PriceFilter, QuantityFilter, SideMultipliers,
+    },
+    quote,
+    types::{BaseCurrency, Currency, Fee, QuoteCurrency},
+};
+
+/// The `BTCUSDT` perpetual of Binance USD-M futures.
+pub fn binance_btcusdt() -> ContractSpecification<BaseCurrency> {
+    ContractSpecification {
+        ticker: "BTCUSDT".to_string(),
+        initial_margin: Dec!(0.008),
+        maintenance_margin: Dec!(0.004),
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter {
+            min_price: quote!(556.8),
+            max_price: quote!(4529764),
+            tick_size: quote!(0.1),
+            multiplier_up: Dec!(0),
+            multiplier_down: Dec!(0),
+            side_multipliers: Some(SideMultipliers::buy_up_sell_down(Dec!(1.05), Dec!(0.95))),
+        },
+        quantity_filter: QuantityFilter {
+            min_quantity: base!(0.001),
+            max_quantity: base!(1000),
+            max_market_quantity: base!(0),
+            step_size: base!(0.001),
+        },
+        notional_filter: NotionalFilter {
+            min_notional: quote!(100),
+            max_notional: quote!(0),
+        },
+        max_num_orders_filter: MaxNumOrdersFilter {
+            max_num_orders: 200,
+            max_num_orders_per_side: 0,
+        },
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0005)),
+    }
+}
+
+/// The `ETHUSDT` perpetual of Binance USD-M futures.
+pub fn binance_ethusdt() -> ContractSpecification<BaseCurrency> {
+    ContractSpecification {
+        ticker: "ETHUSDT".to_string(),
+        initial_margin: Dec!(0.008),
+        maintenance_margin: Dec!(0.005),
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter {
+            min_price: quote!(39.86),
+            max_price: quote!(306177),
+            tick_size: quote!(0.01),
+            multiplier_up: Dec!(0),
+            multiplier_down: Dec!(0),
+            side_multipliers: Some(SideMultipliers::buy_up_sell_down(Dec!(1.05), Dec!(0.95))),
+        },
+        quantity_filter: QuantityFilter {
+            min_quantity: base!(0.001),
+            max_quantity: base!(10000),
+            max_market_quantity: base!(0),
+            step_size: base!(0.001),
+        },
+        notional_filter: NotionalFilter {
+            min_notional: quote!(20),
+            max_notional: quote!(0),
+        },
+        max_num_orders_filter: MaxNumOrdersFilter {
+            max_num_orders: 200,
+            max_num_orders_per_side: 0,
+        },
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0005)),
+    }
+}
+
+/// The `BTCUSDT` linear perpetual of Bybit.
+pub fn bybit_btcusdt() -> ContractSpecification<BaseCurrency> {
+    ContractSpecification {
+        ticker: "BTCUSDT".to_string(),
+        initial_margin: Dec!(0.01),
+        maintenance_margin: Dec!(0.005),
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter {
+            min_price: quote!(0.1),
+            max_price: quote!(1999999.8),
+            tick_size: quote!(0.1),
+            multiplier_up: Dec!(1.03),
+            multiplier_down: Dec!(0.97),
+            side_multipliers: None,
+        },
+        quantity_filter: QuantityFilter {
+            min_quantity: base!(0.001),
+            max_quantity: base!(1190),
+            max_market_quantity: base!(0),
+            step_size: base!(0.001),
+        },
+        notional_filter: NotionalFilter {
+            min_notional: quote!(5),
+            max_notional: quote!(0),
+        },
+        max_num_orders_filter: MaxNumOrdersFilter {
+            max_num_orders: 500,
+            max_num_orders_per_side: 0,
+        },
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.00055)),
+    }
+}
+
+/// The `XBTUSD` inverse perpetual of BitMEX, with contracts of 1 USD traded in lots of 100.
+pub fn bitmex_xbtusd() -> ContractSpecification<QuoteCurrency> {
+    ContractSpecification {
+        ticker: "XBTUSD".to_string(),
+        initial_margin: Dec!(0.01),
+        maintenance_margin: Dec!(0.0035),
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter {
+            min_price: quote!(0),
+            max_price: quote!(1000000),
+            tick_size: quote!(0.5),
+            multiplier_up: Dec!(2),
+            multiplier_down: Dec!(0),
+            side_multipliers: None,
+        },
+        quantity_filter: QuantityFilter {
+            min_quantity: quote!(100),
+            max_quantity: quote!(10000000),
+            max_market_quantity: quote!(0),
+            step_size: quote!(100),
+        },
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.00075)),
+    }
+}
+
+/// The `BTC-PERPETUAL` inverse perpetual of Deribit, with contracts of 10 USD.
+pub fn deribit_btc_perpetual() -> ContractSpecification<QuoteCurrency> {
+    ContractSpecification {
+        ticker: "BTC-PERPETUAL".to_string(),
+        initial_margin: Dec!(0.02),
+        maintenance_margin: Dec!(0.01),
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter {
+            min_price: quote!(0),
+            max_price: quote!(0),
+            tick_size: quote!(0.5),
+            multiplier_up: Dec!(2),
+            multiplier_down: Dec!(0),
+            side_multipliers: None,
+        },
+        quantity_filter: QuantityFilter {
+            min_quantity: quote!(10),
+            max_quantity: quote!(0),
+            max_market_quantity: quote!(0),
+            step_size: quote!(10),
+        },
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(fee!(0.0), fee!(0.0005)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+    #[test]
+    fn presets_linear() {
+        for spec in [binance_btcusdt(), binance_ethusdt(), bybit_btcusdt()] {
+            let config = Config::builder()
+                .starting_balance(quote!(1000))
+                .initial_leverage(leverage!(10))
+                .contract_specification(spec)
+                .build()
+                .unwrap();
+            let mut exchange =
+                Exchange::<NoAccountTracker, BaseCurrency>::new(NoAccountTracker, config);
+            exchange
+                .update_state(0, bba!(quote!(1000), quote!(1000.1)))
+                .unwrap();
+            exchange
+                .submit_order(Order::market(Side::Buy, base!(0.1)).unwrap())
+                .unwrap();
+            assert_eq!(
+                exchange
+                    .submit_order(Order::market(Side::Buy, base!(0.0001)).unwrap())
+                    .unwrap_err(),
+                Error::OrderError(OrderError::QuantityTooLow)
+            );
+        }
+    }
+
+    #[test]
+    fn presets_inverse() {
+        for spec in [bitmex_xbtusd(), deribit_btc_perpetual()] {
+            let config = Config::builder()
+                .starting_balance(base!(1))
+                .contract_specification(spec)
+                .build()
+                .unwrap();
+            let mut exchange =
+                Exchange::<NoAccountTracker, QuoteCurrency>::new(NoAccountTracker, config);
+            exchange
+                .update_state(0, bba!(quote!(50000), quote!(50000.5)))
+                .unwrap();
+            exchange
+                .submit_order(Order::market(Side::Sell, quote!(1000)).unwrap())
+                .unwrap();
+            assert_eq!(
+                exchange
+                    .submit_order(Order::market(Side::Sell, quote!(5)).unwrap())
+                    .unwrap_err(),
+                Error::OrderError(OrderError::QuantityTooLow)
+            );
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Quanto futures, which are quoted in one currency but settled in another at a fixed multiplier.
+
+use alloc::vec::Vec;
+
+use crate::{
+    order_filters::PriceFilter,
+    types::{
+        BaseCurrency, Currency, Error, MarginCurrency, MarketUpdate, Mon, QuoteCurrency, Result,
+    },
+};
+
+/// Converts between the quoted prices of a quanto futures contract and the prices of the linear contract simulating it.
+///
+/// A quanto contract, e.g. ETHUSD settled in BTC, pays `(exit_price - entry_price) * quantity * multiplier`
+/// in the settlement currency, with the prices in the quote currency and a fixed `multiplier`,
+/// e.g. 0.000001 BTC per USD and contract.
+/// As this is linear in the price, a linear `Exchange` simulates the contract exactly,
+/// when its `QuoteCurrency` denotes the settlement currency and all prices are scaled by the `multiplier`.
+/// So market updates go through [`Quanto::market_update`], limit and trigger prices through [`Quanto::settlement_price`],
+/// and the fill prices of the `Exchange` come back through [`Quanto::quote_price`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Quanto<T>
+where
+    T: Mon,
+{
+    multiplier: T,
+}
+
+impl<T> Quanto<T>
+where
+    T: Mon,
+{
+    /// Create a new `Quanto` conversion.
+    ///
+    /// # Arguments:
+    /// `multiplier`: The value of a price move of one unit of the quote currency for one contract,
+    /// denoted in the settlement currency.
+    ///
+    /// # Returns:
+    /// Either a valid `Quanto` or an Error if the `multiplier` is not positive.
+    pub fn new(multiplier: T) -> Result<Self> {
+        if multiplier <= T::ZERO {
+            return Err(Error::InvalidQuantoMultiplier);
+        }
+        Ok(Self { multiplier })
+    }
+
+    /// The value of a price move of one unit of the quote currency for one contract,
+    /// denoted in the settlement currency.
+    #[inline(always)]
+    pub fn multiplier(&self) -> T {
+        self.multiplier
+    }
+
+    /// The price of the simulating linear contract, denoted in the settlement currency.
+    #[inline(always)]
+    pub fn settlement_price(&self, price: QuoteCurrency<T>) -> QuoteCurrency<T> {
+        price * self.multiplier
+    }
+
+    /// The quoted price of the quanto contract, from a price of the simulating linear contract.
+    #[inline(always)]
+    pub fn quote_price(&self, settlement_price: QuoteCurrency<T>) -> QuoteCurrency<T> {
+        settlement_price / self.multiplier
+    }
+
+    /// The profit and loss of `quantity` contracts, denoted in the settlement currency.
+    ///
+    /// # Arguments:
+    /// `entry_price`: The quoted price at which the position was entered.
+    /// `exit_price`: The quoted price at which the position was exited.
+    /// `quantity`: The number of contracts, negative denoting a short position.
+    pub fn pnl(
+        &self,
+        entry_price: QuoteCurrency<T>,
+        exit_price: QuoteCurrency<T>,
+        quantity: BaseCurrency<T>,
+    ) -> QuoteCurrency<T> {
+        QuoteCurrency::pnl(
+            self.settlement_price(entry_price),
+            self.settlement_price(exit_price),
+            quantity,
+        )
+    }
+
+    /// The notional value of `quantity` contracts at the quoted `price`, denoted in the settlement currency,
+    /// of which the initial and maintenance margin are fractions.
+    pub fn notional(&self, price: QuoteCurrency<T>, quantity: BaseCurrency<T>) -> QuoteCurrency<T> {
+        quantity.abs().convert(self.settlement_price(price))
+    }
+
+    /// Scale all prices of a `MarketUpdate` of the quanto contract into settlement prices.
+    pub fn market_update(
+        &self,
+        update: MarketUpdate<BaseCurrency<T>>,
+    ) -> MarketUpdate<BaseCurrency<T>> {
+        match update {
+            MarketUpdate::Bba { bid, ask } => MarketUpdate::Bba {
+                bid: self.settlement_price(bid),
+                ask: self.settlement_price(ask),
+            },
+            MarketUpdate::Trade {
+                price,
+                quantity,
+                side,
+            } => MarketUpdate::Trade {
+                price: self.settlement_price(price),
+                quantity,
+                side,
+            },
+            MarketUpdate::Candle {
+                bid,
+                ask,
+                low,
+                high,
+            } => MarketUpdate::Candle {
+                bid: self.settlement_price(bid),
+                ask: self.settlement_price(ask),
+                low: self.settlement_price(low),
+                high: self.settlement_price(high),
+            },
+            MarketUpdate::Index { price } => MarketUpdate::Index {
+                price: self.settlement_price(price),
+            },
+            MarketUpdate::Depth { bids, asks } => MarketUpdate::Depth {
+                bids: Vec::from_iter(
+                    bids.into_iter()
+                        .map(|(price, quantity)| (self.settlement_price(price), quantity)),
+                ),
+                asks: Vec::from_iter(
+                    asks.into_iter()
+                        .map(|(price, quantity)| (self.settlement_price(price), quantity)),
+                ),
+            },
+        }
+    }
+
+    /// Scale the absolute prices of a `PriceFilter` of the quanto contract into settlement prices.
+    /// The relative multipliers remain unchanged.
+    pub fn price_filter(&self, price_filter: PriceFilter<T>) -> PriceFilter<T> {
+        PriceFilter {
+            min_price: self.settlement_price(price_filter.min_price),
+            max_price: self.settlement_price(price_filter.max_price),
+            tick_size: self.settlement_price(price_filter.tick_size),
+            ..price_filter
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{account_tracker::NoAccountTracker, candle, prelude::*};
+
+    #[test]
+    fn quanto_conversion() {
+        assert_eq!(Quanto::new(Dec!(0)), Err(Error::InvalidQuantoMultiplier));
+
+        // ETHUSD settled in BTC.
+        let quanto = Quanto::new(Dec!(0.000001)).unwrap();
+        assert_eq!(quanto.settlement_price(quote!(2000)), quote!(0.002));
+        assert_eq!(quanto.quote_price(quote!(0.002)), quote!(2000));
+        assert_eq!(
+            quanto.pnl(quote!(2000), quote!(2100), base!(10)),
+            quote!(0.001)
+        );
+        assert_eq!(
+            quanto.pnl(quote!(2000), quote!(2100), base!(-10)),
+            quote!(-0.001)
+        );
+        assert_eq!(quanto.notional(quote!(2000), base!(-10)), quote!(0.02));
+        assert_eq!(
+            quanto.market_update(candle!(
+                quote!(2000),
+                quote!(2001),
+                quote!(1990),
+                quote!(2010)
+            )),
+            candle!(
+                quote!(0.002),
+                quote!(0.002001),
+                quote!(0.00199),
+                quote!(0.00201)
+            )
+        );
+    }
+
+    #[test]
+    fn quanto_exchange() {
+        let quanto = Quanto::new(Dec!(0.000001)).unwrap();
+        let contract_spec = ContractSpecification {
+            ticker: "ETHUSD".to_string(),
+            initial_margin: Dec!(0.1),
+            maintenance_margin: Dec!(0.05),
+            mark_method: MarkMethod::MidPrice,
+            price_filter: quanto.price_filter(PriceFilter {
+                tick_size: quote!(0.05),
+                ..Default::default()
+            }),
+            quantity_filter: QuantityFilter::default(),
+            notional_filter: NotionalFilter::default(),
+            max_num_orders_filter: MaxNumOrdersFilter::default(),
+            fee_schedule: FeeSchedule::flat(fee!(0.0), fee!(0.0)),
+        };
+        // The wallet balance is denoted in BTC.
+        let config = Config::new(quote!(1), 200, leverage!(10), contract_spec).unwrap();
+        let mut exchange =
+            Exchange::<NoAccountTracker, BaseCurrency>::new(NoAccountTracker, config);
+        exchange
+            .update_state(0, quanto.market_update(bba!(quote!(2000), quote!(2000.05))))
+            .unwrap();
+        exchange
+            .submit_order(Order::market(Side::Buy, base!(100)).unwrap())
+            .unwrap();
+        assert_eq!(
+            exchange.account().position().entry_price(),
+            quanto.settlement_price(quote!(2000.05))
+        );
+        // 100 contracts worth 0.200005 BTC at 10x leverage.
+        assert_eq!(
+            exchange.account().position_margin(),
+            quanto.notional(quote!(2000.05), base!(100)) / Dec!(10)
+        );
+
+        exchange
+            .update_state(1, quanto.market_update(bba!(quote!(2100), quote!(2100.05))))
+            .unwrap();
+        exchange
+            .submit_order(Order::market(Side::Sell, base!(100)).unwrap())
+            .unwrap();
+        assert_eq!(
+            exchange.account().wallet_balance(),
+            quote!(1) + quanto.pnl(quote!(2000.05), quote!(2100), base!(100))
+        );
+        assert_eq!(exchange.account().wallet_balance(), quote!(1.009995));
+    }
+}
@@ -0,0 +1,117 @@
+//! Limits the rate of order actions like the API limits of real venues.
+
+use crate::types::{Error, Result};
+
+/// Allows up to `max_requests` order submissions and cancellations per `interval_ns` of simulated time,
+/// as a token bucket which holds `max_requests` tokens and refills continuously.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RateLimit {
+    max_requests: u32,
+    interval_ns: i64,
+}
+
+impl RateLimit {
+    /// Create a new `RateLimit`, e.g. `RateLimit::new(10, 1_000_000_000)` for 10 requests per second.
+    ///
+    /// # Returns:
+    /// Either a valid `RateLimit` or an Error if `max_requests` or `interval_ns` is zero.
+    pub fn new(max_requests: u32, interval_ns: i64) -> Result<Self> {
+        if max_requests == 0 || interval_ns <= 0 {
+            return Err(Error::InvalidRateLimit);
+        }
+        Ok(Self {
+            max_requests,
+            interval_ns,
+        })
+    }
+
+    /// The number of requests allowed per `interval_ns`, which is also the maximum burst.
+    #[inline(always)]
+    pub fn max_requests(&self) -> u32 {
+        self.max_requests
+    }
+
+    /// The interval in nanoseconds over which `max_requests` are allowed.
+    #[inline(always)]
+    pub fn interval_ns(&self) -> i64 {
+        self.interval_ns
+    }
+}
+
+/// The token bucket state of a `RateLimit`.
+/// The level is scaled by `interval_ns`, so a request costs `interval_ns`
+/// and every elapsed nanosecond refills `max_requests`, which keeps the arithmetic exact.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct RateLimiter {
+    limit: Option<RateLimit>,
+    level: i128,
+    last_ts_ns: i64,
+}
+
+impl RateLimiter {
+    /// Starts with a full bucket.
+    pub(crate) fn new(limit: Option<RateLimit>) -> Self {
+        Self {
+            limit,
+            level: limit.map_or(0, |limit| Self::capacity(&limit)),
+            last_ts_ns: i64::MIN,
+        }
+    }
+
+    fn capacity(limit: &RateLimit) -> i128 {
+        limit.max_requests as i128 * limit.interval_ns as i128
+    }
+
+    /// Take a token for a request at `ts_ns`.
+    ///
+    /// # Returns:
+    /// `Error::RateLimited` if the bucket is empty.
+    pub(crate) fn acquire(&mut self, ts_ns: i64) -> Result<()> {
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+        if ts_ns > self.last_ts_ns {
+            let elapsed = ts_ns as i128 - self.last_ts_ns as i128;
+            self.level =
+                (self.level + elapsed * limit.max_requests as i128).min(Self::capacity(&limit));
+            self.last_ts_ns = ts_ns;
+        }
+        if self.level < limit.interval_ns as i128 {
+            return Err(Error::RateLimited);
+        }
+        self.level -= limit.interval_ns as i128;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_new() {
+        assert_eq!(RateLimit::new(0, 1), Err(Error::InvalidRateLimit));
+        assert_eq!(RateLimit::new(1, 0), Err(Error::InvalidRateLimit));
+    }
+
+    #[test]
+    fn rate_limiter_token_bucket() {
+        let mut limiter = RateLimiter::new(Some(RateLimit::new(2, 1_000).unwrap()));
+        assert_eq!(limiter.acquire(0), Ok(()));
+        assert_eq!(limiter.acquire(0), Ok(()));
+        assert_eq!(limiter.acquire(0), Err(Error::RateLimited));
+        // One token refills every 500ns.
+        assert_eq!(limiter.acquire(499), Err(Error::RateLimited));
+        assert_eq!(limiter.acquire(500), Ok(()));
+        assert_eq!(limiter.acquire(500), Err(Error::RateLimited));
+        // The bucket holds at most two tokens.
+        assert_eq!(limiter.acquire(10_000), Ok(()));
+        assert_eq!(limiter.acquire(10_000), Ok(()));
+        assert_eq!(limiter.acquire(10_000), Err(Error::RateLimited));
+
+        let mut unlimited = RateLimiter::new(None);
+        assert!((0..100).all(|_| unlimited.acquire(0).is_ok()));
+    }
+}
@@ -1,26 +1,64 @@
+use alloc::boxed::Box;
+
 use super::{risk_engine_trait::RiskError, RiskEngine};
 use crate::{
+    borrow_pool::BorrowPool,
     contract_specification::ContractSpecification,
+    exchange::EXPECT_LIMIT_PRICE,
     market_state::MarketState,
-    order_margin::compute_order_margin,
+    order_margin::fee_margin,
+    position::Position,
+    position_limit::PositionLimit,
     prelude::Account,
-    types::{Currency, MarginCurrency, Order, OrderType, QuoteCurrency, Side},
+    types::{
+        Currency, Fee, MarginCurrency, Mon, Order, OrderType, PositionMode, QuoteCurrency, Side,
+    },
+    utils::max,
 };
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub(crate) struct IsolatedMarginRiskEngine<M>
 where
     M: Currency + MarginCurrency,
 {
     contract_spec: ContractSpecification<M::PairedCurrency>,
+    max_risk_per_trade: Option<M::Num>,
+    position_limit: PositionLimit<M>,
+    borrow_pool: Option<BorrowPool<M>>,
+    fee_maker: Fee<M::Num>,
+    fee_taker: Fee<M::Num>,
+    allow_position_flip: bool,
 }
 
 impl<M> IsolatedMarginRiskEngine<M>
 where
     M: Currency + MarginCurrency,
 {
-    pub(crate) fn new(contract_spec: ContractSpecification<M::PairedCurrency>) -> Self {
-        Self { contract_spec }
+    pub(crate) fn new(
+        contract_spec: ContractSpecification<M::PairedCurrency>,
+        max_risk_per_trade: Option<M::Num>,
+        position_limit: PositionLimit<M>,
+        borrow_pool: Option<BorrowPool<M>>,
+        allow_position_flip: bool,
+    ) -> Self {
+        let base_tier = contract_spec.fee_schedule.tier(M::Num::ZERO);
+        Self {
+            fee_maker: base_tier.fee_maker,
+            fee_taker: base_tier.fee_taker,
+            contract_spec,
+            max_risk_per_trade,
+            position_limit,
+            borrow_pool,
+            allow_position_flip,
+        }
+    }
+
+    /// Update the fees used in the margin calculations,
+    /// e.g. when a new `FeeTier` or `MakerProgramTier` applies.
+    pub(crate) fn set_fees(&mut self, fee_maker: Fee<M::Num>, fee_taker: Fee<M::Num>) {
+        self.fee_maker = fee_maker;
+        self.fee_taker = fee_taker;
     }
 }
 
@@ -29,34 +67,52 @@ where
     M: Currency + MarginCurrency,
 {
     fn check_market_order(
-        &self,
+        &mut self,
+        market_state: &MarketState<M::Num>,
         account: &Account<M>,
         order: &Order<M::PairedCurrency>,
-        fill_price: QuoteCurrency,
+        fill_price: QuoteCurrency<M::Num>,
     ) -> Result<(), RiskError> {
-        debug_assert!(matches!(order.order_type(), OrderType::Market));
-        match order.side() {
-            Side::Buy => self.handle_market_buy_order(account, order, fill_price),
-            Side::Sell => self.handle_market_sell_order(account, order, fill_price),
+        debug_assert!(
+            matches!(order.order_type(), OrderType::Market) || order.time_in_force().is_immediate()
+        );
+        if order.reduce_only() {
+            Self::check_reduce_only(account, order)?;
         }
+        self.check_position_flip(account, order)?;
+        let fill = Fill::simulate(account, order, fill_price);
+        self.check_margin_after_fill(account, order, fill_price, &fill)?;
+        if fill.adds_exposure {
+            for position in [&fill.long, &fill.short] {
+                if self.is_liquidatable(position, market_state.mark_price()) {
+                    return Err(RiskError::Liquidate);
+                }
+            }
+        }
+        self.check_position_limit(account, order, fill_price)?;
+        self.check_borrow_pool(account, order)?;
+        self.check_max_risk_per_trade(market_state, account, order, fill_price)
     }
 
     fn check_limit_order(
-        &self,
+        &mut self,
+        market_state: &MarketState<M::Num>,
         account: &Account<M>,
         order: &Order<<M as Currency>::PairedCurrency>,
     ) -> Result<(), RiskError> {
         debug_assert!(matches!(order.order_type(), OrderType::Limit));
 
-        let mut orders = account.active_limit_orders.clone();
-        orders.insert(order.id(), order.clone());
-        let new_order_margin =
-            compute_order_margin(&account.position, &orders, self.contract_spec.fee_maker);
+        if order.reduce_only() {
+            Self::check_reduce_only(account, order)?;
+        }
+        self.check_position_flip(account, order)?;
+        let new_order_margin = account.order_margin_with(Some(order), self.fee_maker);
 
         // TODO: this calculation does not allow a fully loaded long (or short) position
         // to be reversed into the opposite position of the same size,
         // which should be possible and requires a slightly modified calculation that
-        let available_balance = account.wallet_balance - account.position.position_margin;
+        let available_balance =
+            account.effective_balance() - account.position_margin() - account.option_margin();
         debug!(
             "new_order_margin: {}, available_balance: {}",
             new_order_margin, available_balance
@@ -65,110 +121,301 @@ where
             return Err(RiskError::NotEnoughAvailableBalance);
         }
 
-        Ok(())
+        let limit_price = order.limit_price().expect(EXPECT_LIMIT_PRICE);
+        self.check_position_limit(account, order, limit_price)?;
+        self.check_borrow_pool(account, order)?;
+        self.check_max_risk_per_trade(market_state, account, order, limit_price)
     }
 
     fn check_maintenance_margin(
         &self,
-        market_state: &MarketState,
+        market_state: &MarketState<M::Num>,
         account: &Account<M>,
     ) -> Result<(), RiskError> {
-        if account.position.size() == M::PairedCurrency::new_zero() {
-            return Ok(());
-        }
-        let pos_value = account
-            .position()
-            .size()
-            .abs()
-            .convert(market_state.mid_price());
-        let maint_margin = account
-            .position()
-            .size()
-            .convert(account.position.entry_price)
-            * self.contract_spec.maintenance_margin;
-        if pos_value < maint_margin {
-            return Err(RiskError::Liquidate);
+        for position in [&account.position, &account.short_position] {
+            if self.is_liquidatable(position, market_state.mark_price()) {
+                return Err(RiskError::Liquidate);
+            }
         }
 
         Ok(())
     }
+
+    fn box_clone(&self) -> Box<dyn RiskEngine<M>> {
+        Box::new(self.clone())
+    }
 }
 
 impl<M> IsolatedMarginRiskEngine<M>
 where
     M: Currency + MarginCurrency,
 {
-    fn handle_market_buy_order(
+    /// A reduce-only order must not exceed the position it reduces.
+    fn check_reduce_only(
+        account: &Account<M>,
+        order: &Order<M::PairedCurrency>,
+    ) -> Result<(), RiskError> {
+        if order.remaining_quantity() > account.reducible_quantity(order.side()) {
+            return Err(RiskError::ReduceOnlyExceedsPosition);
+        }
+        Ok(())
+    }
+
+    /// Unless flipping is allowed, an order in `PositionMode::Netting` must not exceed
+    /// the opposite position, which it would close and reopen on the other side.
+    /// Resting limit orders are checked against the position at submission.
+    fn check_position_flip(
         &self,
         account: &Account<M>,
         order: &Order<M::PairedCurrency>,
-        fill_price: QuoteCurrency,
     ) -> Result<(), RiskError> {
-        debug_assert!(matches!(order.order_type(), OrderType::Market));
-        debug_assert!(matches!(order.side(), Side::Buy));
-
-        if account.position.size() >= M::PairedCurrency::new_zero() {
-            // A long position increases in size.
-            let notional_value = order.quantity().convert(fill_price);
-            let margin_req = notional_value / account.position.leverage;
-            let fee = notional_value * self.contract_spec.fee_taker;
-            if margin_req + fee > account.available_balance() {
-                return Err(RiskError::NotEnoughAvailableBalance);
-            }
+        if self.allow_position_flip || !matches!(account.position_mode(), PositionMode::Netting) {
             return Ok(());
         }
-        // Else its a short position which needs to be reduced
-        if order.quantity() <= account.position.size().abs() {
-            // The order strictly reduces the position, so no additional margin is required.
-            return Ok(());
+        let opposite = account.reducible_quantity(order.side());
+        if opposite > M::PairedCurrency::new_zero() && order.remaining_quantity() > opposite {
+            return Err(RiskError::PositionFlipNotAllowed);
         }
-        // The order reduces the short and puts on a long
-        let released_from_old_pos = account.position.position_margin;
-
-        let new_long_size = order.quantity() - account.position.size.abs();
-        let new_notional_value = new_long_size.convert(fill_price);
-        let new_margin_req = new_notional_value / account.position.leverage;
+        Ok(())
+    }
 
-        if new_margin_req > account.available_balance() + released_from_old_pos {
+    /// The wallet balance after the fill, which realizes the profit and loss of any reduced position
+    /// and pays the fee, must cover the margin of the positions and the active limit orders.
+    /// Even an order which strictly reduces a position can increase the order margin,
+    /// as the smaller position offsets fewer of the resting orders on the same side.
+    fn check_margin_after_fill(
+        &self,
+        account: &Account<M>,
+        order: &Order<M::PairedCurrency>,
+        fill_price: QuoteCurrency<M::Num>,
+        fill: &Fill<M>,
+    ) -> Result<(), RiskError> {
+        let fee = fee_margin(order.quantity().convert(fill_price), self.fee_taker);
+        let wallet_balance = account.effective_balance() + fill.realized_pnl - fee;
+        let required_margin = fill.long.position_margin()
+            + fill.short.position_margin()
+            + account.order_margin_for(&fill.long)
+            + account.option_margin();
+        if required_margin > wallet_balance {
             return Err(RiskError::NotEnoughAvailableBalance);
         }
-
         Ok(())
     }
 
-    fn handle_market_sell_order(
+    /// A position is liquidated once its equity falls below the maintenance margin,
+    /// which happens at the liquidation price.
+    fn is_liquidatable(&self, position: &Position<M>, mark_price: QuoteCurrency<M::Num>) -> bool {
+        if position.size().is_zero() {
+            return false;
+        }
+        let equity = position.position_margin() + position.unrealized_pnl(mark_price, mark_price);
+        equity < position.maintenance_margin(mark_price, self.contract_spec.maintenance_margin)
+    }
+
+    /// Make sure the order does not increase the absolute notional value of the position it trades into
+    /// beyond the `PositionLimit`. Orders which reduce the position are always allowed.
+    fn check_position_limit(
         &self,
         account: &Account<M>,
         order: &Order<M::PairedCurrency>,
-        fill_price: QuoteCurrency,
+        price: QuoteCurrency<M::Num>,
     ) -> Result<(), RiskError> {
-        debug_assert!(matches!(order.order_type(), OrderType::Market));
-        debug_assert!(matches!(order.side(), Side::Sell));
-
-        if account.position.size() <= M::PairedCurrency::new_zero() {
-            let notional_value = order.quantity().convert(fill_price);
-            let margin_req = notional_value / account.position.leverage;
-            let fee = notional_value * self.contract_spec.fee_taker;
-            if margin_req + fee > account.available_balance() {
-                return Err(RiskError::NotEnoughAvailableBalance);
-            }
+        if order.reduce_only() {
             return Ok(());
         }
-        // Else its a long position which needs to be reduced
-        if order.quantity() <= account.position.size() {
-            // The order strictly reduces the position, so no additional margin is required.
+        let position = match (account.position_mode(), order.side()) {
+            (PositionMode::Hedge, Side::Sell) => &account.short_position,
+            _ => &account.position,
+        };
+        let Some(max_notional) = self.position_limit.max_notional(position.leverage) else {
             return Ok(());
+        };
+        let signed_qty = match order.side() {
+            Side::Buy => order.quantity(),
+            Side::Sell => order.quantity().into_negative(),
+        };
+        let new_size = match account.position_mode() {
+            PositionMode::Netting => position.size() + signed_qty,
+            PositionMode::Hedge => position.size().abs() + order.quantity(),
+        };
+        if new_size.abs() > position.size().abs() && new_size.abs().convert(price) > max_notional {
+            return Err(RiskError::MaxPositionNotionalExceeded);
         }
-        // The order reduces the long position and opens a short.
-        let released_from_old_pos = account.position.position_margin;
 
-        let new_short_size = order.quantity() - account.position.size();
-        let new_margin_req = new_short_size.convert(fill_price) / account.position.leverage;
+        Ok(())
+    }
 
-        if new_margin_req > account.available_balance() + released_from_old_pos {
-            return Err(RiskError::NotEnoughAvailableBalance);
+    /// Make sure the order does not increase the short position beyond the amount the `BorrowPool` lends.
+    /// Orders which reduce the short position are always allowed.
+    fn check_borrow_pool(
+        &self,
+        account: &Account<M>,
+        order: &Order<M::PairedCurrency>,
+    ) -> Result<(), RiskError> {
+        let Some(borrow_pool) = self.borrow_pool.as_ref() else {
+            return Ok(());
+        };
+        if order.reduce_only() || matches!(order.side(), Side::Buy) {
+            return Ok(());
+        }
+        let short_size = match account.position_mode() {
+            PositionMode::Netting => account.position.size() - order.quantity(),
+            PositionMode::Hedge => account.short_position.size() - order.quantity(),
+        };
+        if short_size < M::PairedCurrency::new_zero() && short_size.abs() > borrow_pool.max_borrow()
+        {
+            return Err(RiskError::BorrowPoolExhausted);
         }
 
         Ok(())
     }
+
+    /// Make sure the worst-case loss of the exposure added by the order
+    /// does not exceed the configured fraction of the equity,
+    /// i.e. the wallet balance plus the unrealized profit and loss at the mark price.
+    /// The worst-case loss is measured to the protective stop of the order if any,
+    /// otherwise to the liquidation price of the added exposure.
+    fn check_max_risk_per_trade(
+        &self,
+        market_state: &MarketState<M::Num>,
+        account: &Account<M>,
+        order: &Order<M::PairedCurrency>,
+        fill_price: QuoteCurrency<M::Num>,
+    ) -> Result<(), RiskError> {
+        let Some(max_risk_per_trade) = self.max_risk_per_trade else {
+            return Ok(());
+        };
+        // Only the part of the order which is not offset by the current position adds exposure.
+        let pos_size = account.position.size();
+        let added_qty = match order.side() {
+            _ if order.reduce_only() => M::PairedCurrency::new_zero(),
+            _ if matches!(account.position_mode(), PositionMode::Hedge) => order.quantity(),
+            Side::Buy => {
+                if pos_size >= M::PairedCurrency::new_zero() {
+                    order.quantity()
+                } else {
+                    max(
+                        order.quantity() - pos_size.abs(),
+                        M::PairedCurrency::new_zero(),
+                    )
+                }
+            }
+            Side::Sell => {
+                if pos_size <= M::PairedCurrency::new_zero() {
+                    order.quantity()
+                } else {
+                    max(order.quantity() - pos_size, M::PairedCurrency::new_zero())
+                }
+            }
+        };
+        if added_qty.is_zero() {
+            return Ok(());
+        }
+        let signed_qty = match order.side() {
+            Side::Buy => added_qty,
+            Side::Sell => added_qty.into_negative(),
+        };
+
+        let margin = added_qty.convert(fill_price) / account.position.leverage;
+        let exit_price = order.protective_stop().or_else(|| {
+            M::liquidation_price(
+                fill_price,
+                signed_qty,
+                margin,
+                self.contract_spec.maintenance_margin,
+            )
+        });
+        let worst_case_loss = match exit_price {
+            Some(exit_price) => max(
+                M::pnl(fill_price, exit_price, signed_qty).into_negative(),
+                M::new_zero(),
+            ),
+            // The exposure can never be liquidated, so the whole margin is at risk.
+            None => margin,
+        };
+        let mark_price = market_state.mark_price();
+        let equity = account.wallet_balance + account.unrealized_pnl(mark_price, mark_price);
+        if worst_case_loss > equity * max_risk_per_trade {
+            return Err(RiskError::MaxRiskPerTradeExceeded);
+        }
+
+        Ok(())
+    }
+}
+
+/// The positions of an `Account` after a market order is filled,
+/// settled the same way as the `ClearingHouse` does.
+struct Fill<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The long position in `PositionMode::Hedge`, the only position otherwise.
+    long: Position<M>,
+    short: Position<M>,
+    realized_pnl: M,
+    /// Whether the order increases a position or opens a new one, instead of strictly reducing a position.
+    adds_exposure: bool,
+}
+
+impl<M> Fill<M>
+where
+    M: Currency + MarginCurrency,
+{
+    fn simulate(
+        account: &Account<M>,
+        order: &Order<M::PairedCurrency>,
+        fill_price: QuoteCurrency<M::Num>,
+    ) -> Self {
+        let mut long = account.position.clone();
+        let mut short = account.short_position.clone();
+        let mut realized_pnl = M::new_zero();
+        let quantity = order.quantity();
+        let size = long.size();
+        let zero = M::PairedCurrency::new_zero();
+        let adds_exposure = match (account.position_mode(), order.side()) {
+            (PositionMode::Hedge, Side::Buy) if order.reduce_only() => {
+                realized_pnl = short.decrease_short(quantity, fill_price);
+                false
+            }
+            (PositionMode::Hedge, Side::Sell) if order.reduce_only() => {
+                realized_pnl = long.decrease_long(quantity, fill_price);
+                false
+            }
+            (PositionMode::Hedge, Side::Buy) => {
+                long.increase_long(quantity, fill_price);
+                true
+            }
+            (PositionMode::Hedge, Side::Sell) => {
+                short.increase_short(quantity, fill_price);
+                true
+            }
+            (PositionMode::Netting, Side::Buy) if size >= zero => {
+                long.increase_long(quantity, fill_price);
+                true
+            }
+            (PositionMode::Netting, Side::Sell) if size <= zero => {
+                long.increase_short(quantity, fill_price);
+                true
+            }
+            (PositionMode::Netting, Side::Buy) if quantity <= size.abs() => {
+                realized_pnl = long.decrease_short(quantity, fill_price);
+                false
+            }
+            (PositionMode::Netting, Side::Sell) if quantity <= size => {
+                realized_pnl = long.decrease_long(quantity, fill_price);
+                false
+            }
+            // The order closes the position and opens the opposite one.
+            (PositionMode::Netting, _) => {
+                realized_pnl = long.flip(quantity, fill_price);
+                true
+            }
+        };
+        Self {
+            long,
+            short,
+            realized_pnl,
+            adds_exposure,
+        }
+    }
 }
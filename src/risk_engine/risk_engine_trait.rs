@@ -1,3 +1,6 @@
+use alloc::{boxed::Box, string::String};
+use core::fmt::Debug;
+
 use crate::{
     market_state::MarketState,
     prelude::Account,
@@ -6,6 +9,7 @@ use crate::{
 
 /// The error that the `RiskEngine` outputs, if any.
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[allow(missing_docs)]
 pub enum RiskError {
     #[error("The `Trader` does not have enough balance.")]
@@ -13,13 +17,37 @@ pub enum RiskError {
 
     #[error("The position will be liquidated!")]
     Liquidate,
+
+    #[error("The worst-case loss of the order exceeds the maximum risk per trade.")]
+    MaxRiskPerTradeExceeded,
+
+    #[error("The reduce-only order exceeds the position it reduces.")]
+    ReduceOnlyExceedsPosition,
+
+    #[error("The position would exceed its maximum notional value.")]
+    MaxPositionNotionalExceeded,
+
+    #[error("The order would flip the position, which is not allowed.")]
+    PositionFlipNotAllowed,
+
+    #[error("The short position would exceed the borrowable amount of the asset.")]
+    BorrowPoolExhausted,
+
+    #[error("The order was rejected by a custom risk check: {0}")]
+    Rejected(String),
 }
 
-pub(crate) trait RiskEngine<M>
+/// Validates orders before they are accepted and the margin of the account afterwards.
+/// The `Exchange` always runs the built-in isolated margin checks,
+/// followed by the custom risk engines added with `Config::with_risk_engine`,
+/// e.g. to limit the order rate, reject fat-finger prices or restrict sides.
+/// Every check defaults to approving, so a custom risk engine only implements the checks it needs.
+pub trait RiskEngine<M>: Debug
 where
     M: Currency + MarginCurrency,
 {
-    /// Checks if the account it able to satisfy the margin requirements for a new market order.
+    /// Checks if the account it able to satisfy the margin requirements for a new market order,
+    /// or a limit order that is executed immediately due to its `TimeInForce`.
     ///
     /// When a trader submits an order to increase their position,
     /// the risk engine will typically calculate the margin requirements as if the new order is executed and added to their existing positions.
@@ -31,21 +59,30 @@ where
     /// The risk engine will consider the notional value of the order, the current market price,
     /// and the leverage used to determine the new required margin for the remaining position.
     ///
+    /// An order which increases a position is also rejected if the position would be liquidated right away
+    /// at the current mid price, e.g. because the spread exceeds its margin above the maintenance margin.
+    ///
     /// # Returns:
     /// If Err, the account cannot satisfy the margin requirements.
     fn check_market_order(
-        &self,
-        account: &Account<M>,
-        order: &Order<M::PairedCurrency>,
-        fill_price: QuoteCurrency,
-    ) -> Result<(), RiskError>;
+        &mut self,
+        _market_state: &MarketState<M::Num>,
+        _account: &Account<M>,
+        _order: &Order<M::PairedCurrency>,
+        _fill_price: QuoteCurrency<M::Num>,
+    ) -> Result<(), RiskError> {
+        Ok(())
+    }
 
     /// Checks if the account it able to satisfy the margin requirements for a new limit order.
     fn check_limit_order(
-        &self,
-        account: &Account<M>,
-        order: &Order<M::PairedCurrency>,
-    ) -> Result<(), RiskError>;
+        &mut self,
+        _market_state: &MarketState<M::Num>,
+        _account: &Account<M>,
+        _order: &Order<M::PairedCurrency>,
+    ) -> Result<(), RiskError> {
+        Ok(())
+    }
 
     /// Ensure the account has enough maintenance margin, to keep the position open.
     /// The maintenance margin is the minimum amount of funds that must be maintained in a trader's account
@@ -59,7 +96,21 @@ where
     /// If Err, the account must be liquidated.
     fn check_maintenance_margin(
         &self,
-        market_state: &MarketState,
-        account: &Account<M>,
-    ) -> Result<(), RiskError>;
+        _market_state: &MarketState<M::Num>,
+        _account: &Account<M>,
+    ) -> Result<(), RiskError> {
+        Ok(())
+    }
+
+    /// Clone into a new box, so every `Exchange` gets its own state.
+    fn box_clone(&self) -> Box<dyn RiskEngine<M>>;
+}
+
+impl<M> Clone for Box<dyn RiskEngine<M>>
+where
+    M: Currency + MarginCurrency,
+{
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
 }
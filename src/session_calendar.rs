@@ -0,0 +1,210 @@
+//! Defines when the market is open and when trading sessions end,
+//! e.g. to expire `TimeInForce::Day` orders or for venues with sessions, weekends and holidays.
+
+use alloc::vec::Vec;
+
+use crate::types::{Error, Result};
+
+const DAILY_NS: i64 = 86_400_000_000_000;
+
+/// Defines the trading sessions by daily sessions in UTC, weekends, holidays or custom session ends.
+/// The default is always open with sessions ending at midnight UTC, as for crypto venues.
+/// Scheduled maintenance is simulated with `Config::with_downtime`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionCalendar {
+    // The open and close of the daily session in nanoseconds since midnight UTC.
+    session: Option<(i64, i64)>,
+    closed_on_weekends: bool,
+    // The days since the unix epoch on which the market is closed, sorted ascending.
+    holidays: Vec<i64>,
+    // The timestamps in nanoseconds at which sessions end, sorted ascending, if not daily.
+    session_ends: Option<Vec<i64>>,
+}
+
+impl SessionCalendar {
+    /// Only open during a daily session, e.g. from 14:30 to 21:00 UTC,
+    /// which ends at its close.
+    ///
+    /// # Arguments:
+    /// `open_ns`: The open of the session in nanoseconds since midnight UTC.
+    /// `close_ns`: The close of the session in nanoseconds since midnight UTC, exclusive.
+    ///
+    /// # Returns:
+    /// An Error unless `0 <= open_ns < close_ns <= 24h`.
+    pub fn with_session(mut self, open_ns: i64, close_ns: i64) -> Result<Self> {
+        if open_ns < 0 || open_ns >= close_ns || close_ns > DAILY_NS {
+            return Err(Error::InvalidSessionCalendar);
+        }
+        self.session = Some((open_ns, close_ns));
+        Ok(self)
+    }
+
+    /// Closed on Saturdays and Sundays in UTC.
+    pub fn closed_on_weekends(mut self) -> Self {
+        self.closed_on_weekends = true;
+        self
+    }
+
+    /// Closed during the whole UTC day which contains `ts_ns`.
+    pub fn with_holiday(mut self, ts_ns: i64) -> Self {
+        let day = ts_ns.div_euclid(DAILY_NS);
+        if let Err(idx) = self.holidays.binary_search(&day) {
+            self.holidays.insert(idx, day);
+        }
+        self
+    }
+
+    /// Sessions end at the provided timestamps in nanoseconds instead of daily.
+    ///
+    /// # Returns:
+    /// An Error unless the `session_ends` are sorted ascending.
+    pub fn with_session_ends(mut self, session_ends: Vec<i64>) -> Result<Self> {
+        if !session_ends.is_sorted() {
+            return Err(Error::InvalidSessionCalendar);
+        }
+        self.session_ends = Some(session_ends);
+        Ok(self)
+    }
+
+    /// Return the end of the session which contains `ts_ns`.
+    /// `None` if no session end is known after `ts_ns`.
+    pub fn session_end(&self, ts_ns: i64) -> Option<i64> {
+        if let Some(session_ends) = &self.session_ends {
+            let idx = session_ends.partition_point(|end| *end <= ts_ns);
+            return session_ends.get(idx).copied();
+        }
+        let close_ns = self.session.map_or(DAILY_NS, |(_, close_ns)| close_ns);
+        let day = ts_ns.div_euclid(DAILY_NS);
+        if ts_ns.rem_euclid(DAILY_NS) < close_ns {
+            Some(day * DAILY_NS + close_ns)
+        } else {
+            Some((day + 1) * DAILY_NS + close_ns)
+        }
+    }
+
+    /// Whether the UTC day which contains `ts_ns` is a trading day,
+    /// so neither a weekend day, if closed on weekends, nor a holiday.
+    pub fn is_trading_day(&self, ts_ns: i64) -> bool {
+        self.is_trading_day_index(ts_ns.div_euclid(DAILY_NS))
+    }
+
+    fn is_trading_day_index(&self, day: i64) -> bool {
+        // The unix epoch was a Thursday, so Saturday and Sunday are 5 and 6 with Monday being 0.
+        let weekday = (day + 3).rem_euclid(7);
+        if self.closed_on_weekends && weekday >= 5 {
+            return false;
+        }
+        self.holidays.binary_search(&day).is_err()
+    }
+
+    /// Whether the market is open at `ts_ns`, so orders are accepted.
+    pub fn is_open(&self, ts_ns: i64) -> bool {
+        if !self.is_trading_day(ts_ns) {
+            return false;
+        }
+        match self.session {
+            Some((open_ns, close_ns)) => {
+                let time_of_day = ts_ns.rem_euclid(DAILY_NS);
+                open_ns <= time_of_day && time_of_day < close_ns
+            }
+            None => true,
+        }
+    }
+
+    /// The number of trading days from the day which contains `from_ns`
+    /// up to the day which contains `to_ns`, exclusive.
+    pub fn num_trading_days(&self, from_ns: i64, to_ns: i64) -> u64 {
+        let from_day = from_ns.div_euclid(DAILY_NS);
+        let to_day = to_ns.div_euclid(DAILY_NS);
+        (from_day..to_day)
+            .filter(|day| self.is_trading_day_index(*day))
+            .count() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_NS: i64 = 3_600_000_000_000;
+    // Monday, 2024-01-01 00:00 UTC.
+    const MONDAY_NS: i64 = 1_704_067_200_000_000_000;
+
+    #[test]
+    fn session_end_utc_day() {
+        let calendar = SessionCalendar::default();
+        assert_eq!(calendar.session_end(0), Some(DAILY_NS));
+        assert_eq!(calendar.session_end(DAILY_NS - 1), Some(DAILY_NS));
+        assert_eq!(calendar.session_end(DAILY_NS), Some(2 * DAILY_NS));
+    }
+
+    #[test]
+    fn session_end_daily_session() {
+        let calendar = SessionCalendar::default()
+            .with_session(9 * HOUR_NS, 17 * HOUR_NS)
+            .unwrap();
+        assert_eq!(
+            calendar.session_end(MONDAY_NS + 10 * HOUR_NS),
+            Some(MONDAY_NS + 17 * HOUR_NS)
+        );
+        assert_eq!(
+            calendar.session_end(MONDAY_NS + 17 * HOUR_NS),
+            Some(MONDAY_NS + DAILY_NS + 17 * HOUR_NS)
+        );
+    }
+
+    #[test]
+    fn session_end_custom() {
+        assert_eq!(
+            SessionCalendar::default().with_session_ends(vec![20, 10]),
+            Err(Error::InvalidSessionCalendar)
+        );
+        let calendar = SessionCalendar::default()
+            .with_session_ends(vec![10, 20, 30])
+            .unwrap();
+        assert_eq!(calendar.session_end(0), Some(10));
+        assert_eq!(calendar.session_end(10), Some(20));
+        assert_eq!(calendar.session_end(25), Some(30));
+        assert_eq!(calendar.session_end(30), None);
+    }
+
+    #[test]
+    fn session_calendar_always_open() {
+        let calendar = SessionCalendar::default();
+        assert!(calendar.is_open(0));
+        assert!(calendar.is_open(MONDAY_NS + 5 * DAILY_NS));
+        assert_eq!(
+            calendar.num_trading_days(MONDAY_NS, MONDAY_NS + 7 * DAILY_NS),
+            7
+        );
+    }
+
+    #[test]
+    fn session_calendar_sessions() {
+        assert_eq!(
+            SessionCalendar::default().with_session(2 * HOUR_NS, HOUR_NS),
+            Err(Error::InvalidSessionCalendar)
+        );
+
+        let calendar = SessionCalendar::default()
+            .with_session(14 * HOUR_NS + HOUR_NS / 2, 21 * HOUR_NS)
+            .unwrap()
+            .closed_on_weekends()
+            .with_holiday(MONDAY_NS + HOUR_NS);
+        let tuesday = MONDAY_NS + DAILY_NS;
+        assert!(!calendar.is_open(MONDAY_NS + 15 * HOUR_NS));
+        assert!(!calendar.is_open(tuesday + 14 * HOUR_NS));
+        assert!(calendar.is_open(tuesday + 14 * HOUR_NS + HOUR_NS / 2));
+        assert!(calendar.is_open(tuesday + 15 * HOUR_NS));
+        assert!(!calendar.is_open(tuesday + 21 * HOUR_NS));
+        // Saturday
+        assert!(!calendar.is_open(MONDAY_NS + 5 * DAILY_NS + 15 * HOUR_NS));
+
+        // Tuesday to Friday of the first week, after the holiday, and the whole second week.
+        assert_eq!(
+            calendar.num_trading_days(MONDAY_NS, MONDAY_NS + 14 * DAILY_NS),
+            9
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Models the price impact of market orders.
+
+use core::fmt::Debug;
+
+use fpdec::Decimal;
+
+use crate::{
+    market_state::MarketState,
+    types::{Currency, Mon, QuoteCurrency, Side},
+};
+
+/// Determines the average fill price of a market order, which walks the book beyond the best price.
+/// The generic `T` is the numeric type of the prices.
+pub trait SlippageModel<T = Decimal>: Debug + Send + Sync
+where
+    T: Mon,
+{
+    /// Compute the average fill price of a market order.
+    ///
+    /// # Arguments:
+    /// `side`: The side of the market order.
+    /// `best_price`: The best ask for buys or the best bid for sells.
+    /// `quantity`: The absolute order quantity, denoted in the size currency.
+    ///
+    /// # Returns:
+    /// The absolute price difference between the fill price and the `best_price`.
+    fn price_impact(
+        &self,
+        side: Side,
+        best_price: QuoteCurrency<T>,
+        quantity: T,
+    ) -> QuoteCurrency<T>;
+
+    /// The average fill price of a market order, which is always worse than the `best_price`.
+    fn fill_price(
+        &self,
+        side: Side,
+        best_price: QuoteCurrency<T>,
+        quantity: T,
+    ) -> QuoteCurrency<T> {
+        let impact = self.price_impact(side, best_price, quantity).abs();
+        match side {
+            Side::Buy => best_price + impact,
+            Side::Sell => best_price - impact,
+        }
+    }
+
+    /// The average fill price of a market order given the whole `MarketState`, which the `Exchange` uses.
+    /// Override it to consume the L2 depth or the microstructure features like `MarketState::book_imbalance`,
+    /// by default it applies `fill_price` to the best bid or ask.
+    fn market_fill_price(
+        &self,
+        side: Side,
+        quantity: T,
+        market_state: &MarketState<T>,
+    ) -> QuoteCurrency<T> {
+        let best_price = match side {
+            Side::Buy => market_state.ask(),
+            Side::Sell => market_state.bid(),
+        };
+        self.fill_price(side, best_price, quantity)
+    }
+}
+
+/// Market orders fill at the best bid or ask.
+#[derive(Debug, Clone, Default)]
+pub struct NoSlippage;
+
+impl<T> SlippageModel<T> for NoSlippage
+where
+    T: Mon,
+{
+    fn price_impact(
+        &self,
+        _side: Side,
+        _best_price: QuoteCurrency<T>,
+        _quantity: T,
+    ) -> QuoteCurrency<T> {
+        QuoteCurrency::new_zero()
+    }
+}
+
+/// Market orders fill a fixed number of basis points away from the best price, regardless of their size.
+#[derive(Debug, Clone)]
+pub struct FixedBpsSlippage<T = Decimal> {
+    /// The slippage in basis points, e.g.: 2 -> 0.02%.
+    pub bps: T,
+}
+
+impl<T> SlippageModel<T> for FixedBpsSlippage<T>
+where
+    T: Mon,
+{
+    fn price_impact(
+        &self,
+        _side: Side,
+        best_price: QuoteCurrency<T>,
+        _quantity: T,
+    ) -> QuoteCurrency<T> {
+        best_price * self.bps / T::from_u64(10_000)
+    }
+}
+
+/// The square-root market impact model,
+/// where the relative price impact is `coefficient * sqrt(quantity / average_daily_volume)`.
+#[derive(Debug, Clone)]
+pub struct SquareRootImpact<T = Decimal> {
+    /// Scales the impact, usually in the order of the daily volatility.
+    pub coefficient: T,
+    /// The average daily traded volume, denoted in the size currency.
+    pub average_daily_volume: T,
+}
+
+impl<T> SlippageModel<T> for SquareRootImpact<T>
+where
+    T: Mon,
+{
+    fn price_impact(
+        &self,
+        _side: Side,
+        best_price: QuoteCurrency<T>,
+        quantity: T,
+    ) -> QuoteCurrency<T> {
+        if self.average_daily_volume <= T::ZERO {
+            return QuoteCurrency::new_zero();
+        }
+        best_price * self.coefficient * (quantity / self.average_daily_volume).sqrt()
+    }
+}
+
+/// Models a book with the same quantity resting at each price level,
+/// so the market order consumes the levels one after the other.
+#[derive(Debug, Clone)]
+pub struct DepthSlippage<T = Decimal> {
+    /// The quantity available at each price level, denoted in the size currency.
+    pub quantity_per_level: T,
+    /// The price distance between neighbouring levels.
+    pub level_spacing: QuoteCurrency<T>,
+}
+
+impl<T> SlippageModel<T> for DepthSlippage<T>
+where
+    T: Mon,
+{
+    fn price_impact(
+        &self,
+        _side: Side,
+        _best_price: QuoteCurrency<T>,
+        quantity: T,
+    ) -> QuoteCurrency<T> {
+        if self.quantity_per_level <= T::ZERO || quantity <= T::ZERO {
+            return QuoteCurrency::new_zero();
+        }
+        let full_levels = (quantity / self.quantity_per_level).trunc();
+        let remainder = quantity - full_levels * self.quantity_per_level;
+        // The levels `0..full_levels` are fully consumed, the remainder fills at level `full_levels`.
+        let level_sum = self.quantity_per_level * full_levels * (full_levels - T::ONE) / T::TWO
+            + remainder * full_levels;
+        self.level_spacing * level_sum / quantity
+    }
+}
+
+/// Walks the levels of the latest `MarketUpdate::Depth`, so market orders consume the observed resting quantity
+/// one level after the other.
+/// The quantity exceeding the observed depth fills at further levels, which `beyond_depth` extrapolates
+/// `level_spacing` apart from the worst observed level with `quantity_per_level` each.
+/// Without depth, e.g. on `Bba` data, the whole order fills at the extrapolated levels starting at the best price.
+#[derive(Debug, Clone)]
+pub struct WalkTheBook<T = Decimal> {
+    /// Extrapolates the book beyond the observed depth.
+    pub beyond_depth: DepthSlippage<T>,
+}
+
+impl<T> SlippageModel<T> for WalkTheBook<T>
+where
+    T: Mon,
+{
+    fn price_impact(
+        &self,
+        side: Side,
+        best_price: QuoteCurrency<T>,
+        quantity: T,
+    ) -> QuoteCurrency<T> {
+        self.beyond_depth.price_impact(side, best_price, quantity)
+    }
+
+    fn market_fill_price(
+        &self,
+        side: Side,
+        quantity: T,
+        market_state: &MarketState<T>,
+    ) -> QuoteCurrency<T> {
+        let levels = match side {
+            Side::Buy => market_state.ask_depth(),
+            Side::Sell => market_state.bid_depth(),
+        };
+        let Some((worst_price, _)) = levels.last() else {
+            return self
+                .beyond_depth
+                .market_fill_price(side, quantity, market_state);
+        };
+        if quantity <= T::ZERO {
+            return levels[0].0;
+        }
+        let mut remaining = quantity;
+        let mut cost = QuoteCurrency::new_zero();
+        for (price, level_quantity) in levels.iter() {
+            let filled = if remaining < *level_quantity {
+                remaining
+            } else {
+                *level_quantity
+            };
+            cost += *price * filled;
+            remaining -= filled;
+            if remaining <= T::ZERO {
+                return cost / quantity;
+            }
+        }
+        let next_price = match side {
+            Side::Buy => *worst_price + self.beyond_depth.level_spacing,
+            Side::Sell => *worst_price - self.beyond_depth.level_spacing,
+        };
+        let beyond_price = self.beyond_depth.fill_price(side, next_price, remaining);
+        (cost + beyond_price * remaining) / quantity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn fixed_bps_slippage() {
+        let model = FixedBpsSlippage { bps: Dec!(10) };
+        assert_eq!(
+            model.fill_price(Side::Buy, quote!(1000), Dec!(1)),
+            quote!(1001)
+        );
+        assert_eq!(
+            model.fill_price(Side::Sell, quote!(1000), Dec!(1)),
+            quote!(999)
+        );
+    }
+
+    #[test]
+    fn square_root_impact() {
+        let model = SquareRootImpact {
+            coefficient: Dec!(0.1),
+            average_daily_volume: Dec!(10000),
+        };
+        assert_eq!(
+            model.fill_price(Side::Buy, quote!(100), Dec!(100)),
+            quote!(101)
+        );
+        assert_eq!(
+            model.fill_price(Side::Sell, quote!(100), Dec!(400)),
+            quote!(98)
+        );
+    }
+
+    #[test]
+    fn depth_slippage() {
+        let model = DepthSlippage {
+            quantity_per_level: Dec!(2),
+            level_spacing: quote!(0.5),
+        };
+        assert_eq!(
+            model.fill_price(Side::Buy, quote!(100), Dec!(1)),
+            quote!(100)
+        );
+        // 2 @ 100, 2 @ 100.5, 1 @ 101
+        assert_eq!(
+            model.fill_price(Side::Buy, quote!(100), Dec!(5)),
+            quote!(100.4)
+        );
+        assert_eq!(
+            model.fill_price(Side::Sell, quote!(100), Dec!(4)),
+            quote!(99.75)
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Point in time copies of the exchange state, which can be compared to find where two runs diverge.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use crate::{
+    account::Account,
+    market_state::MarketState,
+    types::{Currency, MarginCurrency, Order, QuoteCurrency},
+};
+
+/// A copy of the account, margin, position, open orders and market state of an `Exchange`,
+/// taken with `Exchange::snapshot`.
+/// Comparing snapshots of two runs with [`ExchangeSnapshot::diff`] helps to bisect
+/// where a backtest diverges between versions or configurations.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct ExchangeSnapshot<M>
+where
+    M: Currency + MarginCurrency,
+{
+    /// The timestamp of the last market update in nanoseconds.
+    pub timestamp_ns: i64,
+    /// The number of processed market updates.
+    pub step: u64,
+    /// The best bid price.
+    pub bid: QuoteCurrency<M::Num>,
+    /// The best ask price.
+    pub ask: QuoteCurrency<M::Num>,
+    /// The wallet balance, denoted in the margin currency.
+    pub wallet_balance: M,
+    /// The margin locked by the position.
+    pub position_margin: M,
+    /// The margin locked by the open orders.
+    pub order_margin: M,
+    /// The balance available for new orders.
+    pub available_balance: M,
+    /// The size of the position, negative denoting a short.
+    pub position_size: M::PairedCurrency,
+    /// The entry price of the position.
+    pub entry_price: QuoteCurrency<M::Num>,
+    /// The open limit orders, sorted by their `id`.
+    pub open_orders: Vec<Order<M::PairedCurrency>>,
+}
+
+impl<M> ExchangeSnapshot<M>
+where
+    M: Currency + MarginCurrency,
+{
+    pub(crate) fn new(market_state: &MarketState<M::Num>, account: &Account<M>) -> Self {
+        let position = account.position();
+        let mut open_orders = Vec::from_iter(account.active_limit_orders().values().cloned());
+        open_orders.sort_by_key(|order| order.id());
+        Self {
+            timestamp_ns: market_state.current_timestamp_ns(),
+            step: market_state.step(),
+            bid: market_state.bid(),
+            ask: market_state.ask(),
+            wallet_balance: account.wallet_balance(),
+            position_margin: account.position_margin(),
+            order_margin: account.order_margin(),
+            available_balance: account.available_balance(),
+            position_size: position.size(),
+            entry_price: position.entry_price(),
+            open_orders,
+        }
+    }
+
+    /// The human-readable changes from `self` to `other`, one line each,
+    /// e.g. "wallet_balance: 1000 -> 999.8".
+    /// Empty if both snapshots are equal.
+    pub fn diff(&self, other: &Self) -> Vec<String> {
+        let mut changes = Vec::new();
+        push_change(
+            &mut changes,
+            "timestamp_ns",
+            self.timestamp_ns,
+            other.timestamp_ns,
+        );
+        push_change(&mut changes, "step", self.step, other.step);
+        push_change(&mut changes, "bid", self.bid, other.bid);
+        push_change(&mut changes, "ask", self.ask, other.ask);
+        push_change(
+            &mut changes,
+            "wallet_balance",
+            self.wallet_balance,
+            other.wallet_balance,
+        );
+        push_change(
+            &mut changes,
+            "position_margin",
+            self.position_margin,
+            other.position_margin,
+        );
+        push_change(
+            &mut changes,
+            "order_margin",
+            self.order_margin,
+            other.order_margin,
+        );
+        push_change(
+            &mut changes,
+            "available_balance",
+            self.available_balance,
+            other.available_balance,
+        );
+        push_change(
+            &mut changes,
+            "position_size",
+            self.position_size,
+            other.position_size,
+        );
+        push_change(
+            &mut changes,
+            "entry_price",
+            self.entry_price,
+            other.entry_price,
+        );
+
+        for order in self.open_orders.iter() {
+            match other.open_orders.iter().find(|o| o.id() == order.id()) {
+                None => changes.push(format!("order {}: removed", order.id())),
+                Some(o) if o != order => changes.push(format!(
+                    "order {}: {} -> {}",
+                    order.id(),
+                    describe_order(order),
+                    describe_order(o)
+                )),
+                Some(_) => {}
+            }
+        }
+        for order in other.open_orders.iter() {
+            if !self.open_orders.iter().any(|o| o.id() == order.id()) {
+                changes.push(format!(
+                    "order {}: added {}",
+                    order.id(),
+                    describe_order(order)
+                ));
+            }
+        }
+        changes
+    }
+}
+
+fn push_change<T: PartialEq + Display>(changes: &mut Vec<String>, name: &str, from: T, to: T) {
+    if from != to {
+        changes.push(format!("{name}: {from} -> {to}"));
+    }
+}
+
+fn describe_order<S: Currency>(order: &Order<S>) -> String {
+    let price = order
+        .limit_price()
+        .map_or_else(|| "market".to_string(), |price| price.to_string());
+    format!(
+        "{} {} of {} @ {}",
+        order.side(),
+        order.remaining_quantity(),
+        order.quantity(),
+        price
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock_exchange_base, prelude::*};
+
+    #[test]
+    fn exchange_snapshot_diff() {
+        let mut exchange = mock_exchange_base();
+        exchange
+            .update_state(0, bba!(quote!(100), quote!(101)))
+            .unwrap();
+        let before = exchange.snapshot();
+        assert!(before.diff(&before).is_empty());
+
+        exchange
+            .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+            .unwrap();
+        let order = exchange
+            .submit_order(Order::limit(Side::Buy, quote!(99), base!(2)).unwrap())
+            .unwrap();
+        let after = exchange.snapshot();
+        assert_eq!(after.open_orders.len(), 1);
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                "wallet_balance: 1000 -> 999.9394".to_string(),
+                "position_margin: 0 -> 101".to_string(),
+                "order_margin: 0 -> 198.0396".to_string(),
+                "available_balance: 1000 -> 700.8998".to_string(),
+                "position_size: 0 -> 1".to_string(),
+                "entry_price: 0 -> 101".to_string(),
+                format!("order {}: added Buy 2 of 2 @ 99", order.id()),
+            ]
+        );
+        assert_eq!(
+            after.diff(&before).last().unwrap(),
+            &format!("order {}: removed", order.id())
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! A canonical event-driven backtest loop, which wires market data, the `Exchange` and a `Strategy` together.
+
+use alloc::vec::Vec;
+
+use crate::{
+    account_tracker::AccountTracker,
+    exchange::Exchange,
+    risk_engine::RiskError,
+    types::{Currency, Error, MarginCurrency, MarketUpdate, Order, Result},
+};
+
+/// A trading strategy, driven by the events of a `Backtest`.
+/// Orders are submitted directly to the `Exchange` passed into the callbacks.
+pub trait Strategy<A, S>
+where
+    S: Currency,
+    S::PairedCurrency: MarginCurrency,
+{
+    /// Called after the exchange processed the `market_update`, including its fills and liquidations,
+    /// to decide upon new orders.
+    fn on_market_update(
+        &mut self,
+        exchange: &mut Exchange<A, S>,
+        timestamp_ns: u64,
+        market_update: &MarketUpdate<S>,
+    );
+
+    /// Called for every resting order executed by a market update, before `on_market_update`.
+    /// Partially executed iceberg orders are included with their `filled_quantity` updated.
+    /// Orders executed immediately upon submission are returned by `Exchange::submit_order` instead.
+    #[allow(unused_variables)]
+    fn on_fill(&mut self, exchange: &mut Exchange<A, S>, order: &Order<S>) {}
+
+    /// Called after the positions got liquidated by a market update, before `on_market_update`.
+    #[allow(unused_variables)]
+    fn on_liquidation(&mut self, exchange: &mut Exchange<A, S>) {}
+}
+
+/// Counts the events of a `Backtest` run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BacktestSummary {
+    /// The number of processed market updates.
+    pub num_updates: usize,
+    /// The number of orders passed to `Strategy::on_fill`.
+    pub num_fills: usize,
+    /// The number of liquidations.
+    pub num_liquidations: usize,
+}
+
+/// Drives a `Strategy` with market data through an `Exchange`.
+/// For every market update, the exchange state is updated first,
+/// then `on_fill` is called for each executed order, `on_liquidation` after a liquidation
+/// and finally `on_market_update`.
+#[derive(Debug, Clone)]
+pub struct Backtest<A, S, St>
+where
+    S: Currency,
+    S::PairedCurrency: MarginCurrency,
+{
+    exchange: Exchange<A, S>,
+    strategy: St,
+    /// Reused across market updates to avoid allocations.
+    executed: Vec<Order<S>>,
+}
+
+impl<A, S, St> Backtest<A, S, St>
+where
+    A: AccountTracker<S::PairedCurrency>,
+    S: Currency,
+    S::PairedCurrency: MarginCurrency,
+    St: Strategy<A, S>,
+{
+    /// Create a new backtest of the `strategy` on the `exchange`.
+    pub fn new(exchange: Exchange<A, S>, strategy: St) -> Self {
+        Self {
+            exchange,
+            strategy,
+            executed: Vec::new(),
+        }
+    }
+
+    /// Return a reference to the `Exchange`.
+    #[inline(always)]
+    pub fn exchange(&self) -> &Exchange<A, S> {
+        &self.exchange
+    }
+
+    /// Return a reference to the `Strategy`.
+    #[inline(always)]
+    pub fn strategy(&self) -> &St {
+        &self.strategy
+    }
+
+    /// Consume the backtest and return the `Exchange` and `Strategy`.
+    pub fn into_parts(self) -> (Exchange<A, S>, St) {
+        (self.exchange, self.strategy)
+    }
+
+    /// Run the strategy over all `updates`, which are pairs of a timestamp in nanoseconds and a `MarketUpdate`.
+    /// Can be called repeatedly to continue with the next chunk of market data.
+    ///
+    /// # Returns:
+    /// If Ok, the counted events of this run.
+    /// Otherwise the first Error of the exchange other than a liquidation, e.g. an invalid market update.
+    pub fn run<I>(&mut self, updates: I) -> Result<BacktestSummary>
+    where
+        I: IntoIterator<Item = (u64, MarketUpdate<S>)>,
+    {
+        let mut summary = BacktestSummary::default();
+        for (timestamp_ns, market_update) in updates {
+            self.executed.clear();
+            let liquidated = match self.exchange.update_state_into(
+                timestamp_ns,
+                &market_update,
+                &mut self.executed,
+            ) {
+                Ok(()) => false,
+                Err(Error::RiskError(RiskError::Liquidate)) => true,
+                Err(e) => return Err(e),
+            };
+            summary.num_updates += 1;
+            for order in self.executed.iter() {
+                self.strategy.on_fill(&mut self.exchange, order);
+            }
+            summary.num_fills += self.executed.len();
+            if liquidated {
+                summary.num_liquidations += 1;
+                self.strategy.on_liquidation(&mut self.exchange);
+            }
+            self.strategy
+                .on_market_update(&mut self.exchange, timestamp_ns, &market_update);
+        }
+        Ok(summary)
+    }
+}
@@ -0,0 +1,148 @@
+//! Isolates the risk of several books in sub-accounts under one umbrella.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    account_tracker::AccountTracker,
+    cross_exchange::CrossExchange,
+    exchange::Exchange,
+    types::{Currency, Error, MarginCurrency, MarketUpdate, Order, Result},
+};
+
+/// Named sub-accounts trading the same market, each with its own margin, positions and orders,
+/// so a liquidation of one book does not affect the others.
+/// Every market update is applied to all sub-accounts,
+/// and funds are transferred between them instantly or after a delay.
+#[derive(Debug, Clone)]
+pub struct SubAccounts<A, S>
+where
+    S: Currency,
+    S::PairedCurrency: MarginCurrency,
+{
+    names: Vec<String>,
+    accounts: CrossExchange<A, S>,
+}
+
+impl<A, S> Default for SubAccounts<A, S>
+where
+    A: AccountTracker<S::PairedCurrency>,
+    S: Currency,
+    S::PairedCurrency: MarginCurrency,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A, S> SubAccounts<A, S>
+where
+    A: AccountTracker<S::PairedCurrency>,
+    S: Currency,
+    S::PairedCurrency: MarginCurrency,
+{
+    /// Create an umbrella without sub-accounts, where transfers arrive instantly.
+    pub fn new() -> Self {
+        Self {
+            names: Vec::new(),
+            accounts: CrossExchange::new(Vec::new()),
+        }
+    }
+
+    /// Let transfers arrive `transfer_delay_ns` after the latest market update.
+    pub fn with_transfer_delay(mut self, transfer_delay_ns: u64) -> Self {
+        self.accounts = self.accounts.with_transfer_delay(transfer_delay_ns);
+        self
+    }
+
+    /// Add a sub-account, whose `Exchange` is usually created from the same `Config`
+    /// with the starting balance allocated to the book.
+    ///
+    /// # Returns:
+    /// If Err, the `name` is already taken.
+    pub fn add(&mut self, name: &str, exchange: Exchange<A, S>) -> Result<()> {
+        if self.names.iter().any(|existing| existing == name) {
+            return Err(Error::DuplicateSubAccount);
+        }
+        self.names.push(name.to_string());
+        self.accounts.add_venue(exchange);
+        Ok(())
+    }
+
+    fn index_of(&self, name: &str) -> Result<usize> {
+        self.names
+            .iter()
+            .position(|existing| existing == name)
+            .ok_or(Error::UnknownSubAccount)
+    }
+
+    /// Return the names of the sub-accounts in the order they were added.
+    #[inline(always)]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Return the sub-account of `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&Exchange<A, S>> {
+        let index = self.index_of(name).ok()?;
+        Some(self.accounts.venue(index))
+    }
+
+    /// Return the sub-account of `name` mutably, e.g. to submit orders, if any.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Exchange<A, S>> {
+        let index = self.index_of(name).ok()?;
+        Some(self.accounts.venue_mut(index))
+    }
+
+    /// Apply a market update to all sub-accounts,
+    /// after crediting all transfers which arrived by `timestamp_ns`.
+    ///
+    /// # Returns:
+    /// The result of `Exchange::update_state` of each sub-account, in the order of the `names`,
+    /// so an error like a liquidation of one sub-account does not stop the others.
+    pub fn update_state(
+        &mut self,
+        timestamp_ns: u64,
+        market_update: &MarketUpdate<S>,
+    ) -> Vec<Result<Vec<Order<S>>>> {
+        Vec::from_iter((0..self.names.len()).map(|index| {
+            self.accounts
+                .update_state(index, timestamp_ns, market_update.clone())
+        }))
+    }
+
+    /// Transfer the `amount` from the sub-account `from` to `to`, see `CrossExchange::transfer`.
+    pub fn transfer(&mut self, from: &str, to: &str, amount: S::PairedCurrency) -> Result<()> {
+        let from = self.index_of(from)?;
+        let to = self.index_of(to)?;
+        self.accounts.transfer(from, to, amount)
+    }
+
+    /// The equity of all sub-accounts plus the funds in transit between them.
+    pub fn combined_equity(&self) -> S::PairedCurrency {
+        self.accounts.combined_equity()
+    }
+
+    /// The sum of the wallet balances of all sub-accounts, excluding the funds in transit.
+    pub fn total_wallet_balance(&self) -> S::PairedCurrency {
+        self.accounts
+            .venues()
+            .iter()
+            .fold(S::PairedCurrency::new_zero(), |acc, exchange| {
+                acc + exchange.account().wallet_balance()
+            })
+    }
+
+    /// The net position of all sub-accounts, where a negative size denotes a net short.
+    pub fn net_position_size(&self) -> S {
+        self.accounts
+            .venues()
+            .iter()
+            .fold(S::new_zero(), |acc, exchange| {
+                acc + exchange.account().position().size()
+                    + exchange.account().short_position().size()
+            })
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+fn exchange(
+    bankruptcy_mode: BankruptcyMode,
+) -> Exchange<FullAccountTracker<QuoteCurrency>, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .bankruptcy_mode(bankruptcy_mode)
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::new(FullAccountTracker::new(quote!(1000)), config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(50)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(90), base!(1)).unwrap())
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn liquidation_without_bankruptcy() {
+    let mut exchange = exchange(BankruptcyMode::NegativeEquity);
+    assert_eq!(
+        exchange.update_state(1, bba!(quote!(92), quote!(93))),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account().wallet_balance(), quote!(550));
+    assert_eq!(exchange.stats().num_liquidations, 1);
+    assert_eq!(exchange.account_tracker().num_bankruptcies(), 0);
+}
+
+#[test]
+fn gap_through_liquidation_price_negative_equity() {
+    let mut exchange = exchange(BankruptcyMode::NegativeEquity);
+    assert!(exchange
+        .update_state(1, bba!(quote!(95), quote!(96)))
+        .is_ok());
+    assert_eq!(
+        exchange.update_state(2, bba!(quote!(80), quote!(81))),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.account().wallet_balance(), quote!(-50));
+    assert_eq!(exchange.account_tracker().num_bankruptcies(), 1);
+    assert_eq!(
+        exchange.account_tracker().cumulative_bankruptcy_deficit(),
+        quote!(50)
+    );
+    assert_eq!(
+        exchange
+            .account_tracker()
+            .cumulative_insurance_fund_payout(),
+        quote!(0)
+    );
+    assert_eq!(exchange.insurance_fund_payout(), quote!(0));
+}
+
+#[test]
+fn gap_through_liquidation_price_negative_balance_protection() {
+    let mut exchange = exchange(BankruptcyMode::NegativeBalanceProtection);
+    assert_eq!(
+        exchange.update_state(1, bba!(quote!(80), quote!(81))),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+    assert_eq!(exchange.account().wallet_balance(), quote!(0));
+    assert_eq!(exchange.insurance_fund_payout(), quote!(50));
+    assert_eq!(exchange.account_tracker().num_bankruptcies(), 1);
+    assert_eq!(
+        exchange
+            .account_tracker()
+            .cumulative_insurance_fund_payout(),
+        quote!(50)
+    );
+}
+
+#[test]
+fn negative_equity_available_balance() {
+    let mut exchange = exchange(BankruptcyMode::NegativeEquity);
+    assert!(exchange
+        .update_state(1, bba!(quote!(80), quote!(81)))
+        .is_err());
+    assert_eq!(exchange.account().available_balance(), quote!(-50));
+    assert_eq!(
+        exchange.withdraw(quote!(1)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(1)).unwrap()),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange.deposit(quote!(100)).unwrap();
+    assert_eq!(exchange.account().available_balance(), quote!(50));
+    exchange.assert_invariants();
+}
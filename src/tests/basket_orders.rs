@@ -0,0 +1,88 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn exchange() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base().build().unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn basket_orders_accepted() {
+    let mut exchange = exchange();
+    let orders = exchange
+        .submit_basket_orders(vec![
+            Order::market(Side::Buy, base!(2)).unwrap(),
+            Order::limit(Side::Sell, quote!(110), base!(1)).unwrap(),
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+        ])
+        .unwrap();
+    assert_eq!(
+        orders.iter().map(|order| order.id()).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    assert_eq!(exchange.account().position().size(), base!(2));
+    assert_eq!(exchange.account().active_limit_orders().len(), 2);
+    assert_eq!(exchange.submit_basket_orders(vec![]), Ok(vec![]));
+}
+
+#[test]
+fn basket_orders_all_or_nothing() {
+    let mut exchange = exchange();
+    // The last leg exceeds the available balance, so none of the legs are accepted.
+    assert_eq!(
+        exchange.submit_basket_orders(vec![
+            Order::market(Side::Buy, base!(2)).unwrap(),
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::limit(Side::Buy, quote!(90), base!(200)).unwrap(),
+        ]),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account().wallet_balance(), quote!(1000));
+    assert_eq!(exchange.stats().num_rejections, 1);
+
+    // A leg failing the price filter rejects the whole basket as well.
+    assert!(exchange
+        .submit_basket_orders(vec![
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(105.5), base!(1)).unwrap(),
+        ])
+        .is_err());
+    assert!(exchange.account().active_limit_orders().is_empty());
+
+    // The ids of the rejected legs are not used up.
+    let orders = exchange
+        .submit_basket_orders(vec![Order::market(Side::Buy, base!(1)).unwrap()])
+        .unwrap();
+    assert_eq!(orders[0].id(), 0);
+}
+
+#[test]
+fn basket_orders_replay() {
+    let mut exchange = exchange();
+    exchange.start_recording();
+    exchange
+        .submit_basket_orders(vec![
+            Order::market(Side::Buy, base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(105), base!(1)).unwrap(),
+        ])
+        .unwrap();
+    assert!(exchange
+        .submit_basket_orders(vec![
+            Order::limit(Side::Buy, quote!(90), base!(500)).unwrap()
+        ])
+        .is_err());
+    let journal = exchange.stop_recording().unwrap();
+
+    let mut replayed = self::exchange();
+    replayed.replay(&journal).unwrap();
+    assert_eq!(replayed.account().position(), exchange.account().position());
+    assert_eq!(
+        replayed.account().active_limit_orders(),
+        exchange.account().active_limit_orders()
+    );
+}
@@ -0,0 +1,68 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+const YEAR_NS: u64 = 365 * 86_400_000_000_000;
+
+#[test]
+fn borrow_cost_of_leveraged_position() {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(2))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.01),
+            ..crate::mock_contract_specification_base()
+        })
+        .borrow_rate(Dec!(0.1))
+        .build()
+        .unwrap();
+    let mut exchange =
+        Exchange::<_, BaseCurrency>::new(FullAccountTracker::new(quote!(1000)), config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().position_margin(), quote!(101));
+
+    // Half of the 202 notional is borrowed.
+    exchange
+        .update_state(YEAR_NS / 2, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(994.95));
+    exchange
+        .update_state(YEAR_NS, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(989.9));
+    assert_eq!(
+        exchange.account_tracker().cumulative_borrow_cost(),
+        quote!(10.1)
+    );
+}
+
+#[test]
+fn borrow_cost_without_leverage() {
+    let mut exchange = crate::mock_exchange_base();
+    assert_eq!(
+        Config::new(
+            quote!(1000),
+            200,
+            leverage!(1),
+            exchange.config().contract_specification().clone()
+        )
+        .unwrap()
+        .with_borrow_rate(Dec!(-0.1))
+        .unwrap_err(),
+        Error::InvalidBorrowRate
+    );
+
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    let wallet_balance = exchange.account().wallet_balance();
+    exchange
+        .update_state(YEAR_NS, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), wallet_balance);
+}
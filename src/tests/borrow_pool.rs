@@ -0,0 +1,95 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn exchange(position_mode: PositionMode) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .position_mode(position_mode)
+        .borrow_pool(BorrowPool::new(base!(5), Dec!(0.001)).unwrap())
+        .build()
+        .unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+#[test]
+fn borrow_pool_rejects_shorts_beyond_the_pool() {
+    let mut exchange = exchange(PositionMode::Netting);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Sell, base!(6)).unwrap()),
+        Err(Error::RiskError(RiskError::BorrowPoolExhausted))
+    );
+    assert_eq!(
+        exchange.submit_order(Order::limit(Side::Sell, quote!(102), base!(6)).unwrap()),
+        Err(Error::RiskError(RiskError::BorrowPoolExhausted))
+    );
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(5)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(-5));
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Sell, base!(1)).unwrap()),
+        Err(Error::RiskError(RiskError::BorrowPoolExhausted))
+    );
+
+    // Flipping a long into a short only borrows the short part.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(8)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(3));
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(8)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(-5));
+}
+
+#[test]
+fn borrow_pool_hedge_mode() {
+    let mut exchange = exchange(PositionMode::Hedge);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(8)).unwrap())
+        .unwrap();
+    // In hedge mode the long position does not offset the borrowed short.
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Sell, base!(6)).unwrap()),
+        Err(Error::RiskError(RiskError::BorrowPoolExhausted))
+    );
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(5)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().short_position().size(), base!(-5));
+}
+
+#[test]
+fn borrow_pool_hourly_interest() {
+    const HOUR_NS: u64 = 3_600_000_000_000;
+
+    let mut exchange = exchange(PositionMode::Netting);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(4)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(1000));
+
+    // 4 borrowed at a mark price of 100.5 for two hours at 0.1% per hour.
+    exchange
+        .update_state(2 * HOUR_NS, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(999.196));
+
+    // A long position does not borrow.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(6)).unwrap())
+        .unwrap();
+    let balance = exchange.account().wallet_balance();
+    exchange
+        .update_state(3 * HOUR_NS, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), balance);
+}
@@ -0,0 +1,75 @@
+use crate::{account_tracker::NoAccountTracker, candle, prelude::*};
+
+/// Submit a bracket of a buy and a sell limit order around the price
+/// and return the side which got filled by a candle covering both.
+fn filled_side(candle_path: CandlePath) -> Side {
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_candle_path(candle_path);
+    let mut exchange = Exchange::<_, BaseCurrency>::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_oco_orders(
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(110), base!(1)).unwrap(),
+        )
+        .unwrap();
+
+    let executed = exchange
+        .update_state(
+            1,
+            candle!(quote!(104), quote!(105), quote!(94), quote!(111)),
+        )
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert!(exchange.account().active_limit_orders().is_empty());
+    executed[0].side()
+}
+
+#[test]
+fn candle_path_decides_fill_order() {
+    // The low is closer to the open of 100.5.
+    assert_eq!(filled_side(CandlePath::Nearest), Side::Buy);
+    assert_eq!(filled_side(CandlePath::HighFirst), Side::Sell);
+    assert_eq!(filled_side(CandlePath::LowFirst), Side::Buy);
+    assert_eq!(filled_side(CandlePath::WorstCase), Side::Buy);
+    let seeded = filled_side(CandlePath::Brownian { seed: 3 });
+    for _ in 0..5 {
+        assert_eq!(filled_side(CandlePath::Brownian { seed: 3 }), seeded);
+    }
+}
+
+#[test]
+fn candle_path_orders_fills_along_the_path() {
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_candle_path(CandlePath::HighFirst);
+    let mut exchange = Exchange::<_, BaseCurrency>::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    let lower = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(95), base!(1)).unwrap())
+        .unwrap();
+    let upper = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(98), base!(1)).unwrap())
+        .unwrap();
+    let sell = exchange
+        .submit_order(Order::limit(Side::Sell, quote!(105), base!(1)).unwrap())
+        .unwrap();
+
+    let executed = exchange
+        .update_state(
+            1,
+            candle!(quote!(100), quote!(101), quote!(94), quote!(106)),
+        )
+        .unwrap();
+    assert_eq!(
+        Vec::from_iter(executed.iter().map(|order| order.id())),
+        vec![sell.id(), upper.id(), lower.id()]
+    );
+}
@@ -0,0 +1,72 @@
+use crate::{
+    account_tracker::{FullAccountTracker, ReturnsSource},
+    prelude::*,
+    risk_engine::RiskError,
+};
+
+const DAY_NS: u64 = 86_400_000_000_000;
+
+fn mock_exchange_tracked() -> Exchange<FullAccountTracker<QuoteCurrency>, BaseCurrency> {
+    let config = crate::mock_exchange_base().config().clone();
+    Exchange::new(FullAccountTracker::new(quote!(1000)), config)
+}
+
+#[test]
+fn deposit_and_withdraw() {
+    let mut exchange = mock_exchange_tracked();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.deposit(quote!(0)), Err(Error::InvalidAmount));
+    exchange.deposit(quote!(500)).unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(1500));
+
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(100), base!(10)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.withdraw(quote!(600)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange.withdraw(quote!(400)).unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(1100));
+    assert_eq!(exchange.account_tracker().net_cash_flow(), quote!(100));
+
+    // Cash flows are not drawdowns.
+    exchange
+        .update_state(1, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.account_tracker().drawdown().max_pct(), Dec!(0));
+    assert_eq!(exchange.account_tracker().total_rpnl(), quote!(0));
+}
+
+#[test]
+fn time_weighted_return() {
+    let mut exchange = mock_exchange_tracked();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    // The unrealized profit is 45 at the close of the first day.
+    exchange
+        .update_state(DAY_NS, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    exchange.deposit(quote!(1000)).unwrap();
+    // And 95 at the close of the second day, with twice the capital.
+    exchange
+        .update_state(2 * DAY_NS, bba!(quote!(120), quote!(121)))
+        .unwrap();
+    exchange
+        .update_state(3 * DAY_NS, bba!(quote!(120), quote!(121)))
+        .unwrap();
+
+    let tracker = exchange.account_tracker();
+    let ln_returns = tracker.ln_returns(&ReturnsSource::Daily);
+    assert_eq!(ln_returns.len(), 3);
+    assert!((ln_returns[1] - (1045.0_f64 / 1000.0).ln()).abs() < 1e-9);
+    assert!((ln_returns[2] - (2095.0_f64 / 2045.0).ln()).abs() < 1e-9);
+    let twr = 1045.0 / 1000.0 * 2095.0 / 2045.0 - 1.0;
+    assert!((tracker.time_weighted_return(ReturnsSource::Daily) - twr).abs() < 1e-9);
+}
@@ -0,0 +1,35 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*, trade};
+
+type TestExchange = Exchange<NoAccountTracker, BaseCurrency>;
+
+#[test]
+fn checkpoint_roundtrip() {
+    let mut exchange = crate::mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(105), base!(1)).unwrap())
+        .unwrap();
+
+    let checkpoint = serde_json::to_string(&exchange).unwrap();
+    let mut resumed: TestExchange = serde_json::from_str(&checkpoint).unwrap();
+    assert_eq!(serde_json::to_string(&resumed).unwrap(), checkpoint);
+    assert_eq!(resumed.account().position(), exchange.account().position());
+    assert_eq!(resumed.account().active_limit_orders().len(), 1);
+
+    // Both continue identically after resuming.
+    let update = trade!(quote!(106), base!(1), Side::Buy);
+    assert_eq!(
+        resumed.update_state(1, update.clone()).unwrap(),
+        exchange.update_state(1, update).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_string(&resumed).unwrap(),
+        serde_json::to_string(&exchange).unwrap()
+    );
+    assert_eq!(resumed.account().position().size(), base!(1));
+}
@@ -0,0 +1,75 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{account_tracker::NoAccountTracker, prelude::*, trade};
+
+#[test]
+fn circuit_breaker_halts_trading() {
+    let config = crate::mock_config_base()
+        .circuit_breaker(CircuitBreaker::new(Dec!(0.1), 1_000).unwrap())
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    exchange.subscribe(move |ts_ns, event| {
+        if let ExchangeEvent::TradingHalted { until_ts_ns } = event {
+            sink.lock().unwrap().push((ts_ns, *until_ts_ns));
+        }
+    });
+
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    let first = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(95), base!(1)).unwrap())
+        .unwrap();
+    let second = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(94), base!(1)).unwrap())
+        .unwrap();
+
+    // A move of 10% does not trip the circuit breaker.
+    exchange
+        .update_state(10, bba!(quote!(96), quote!(97)))
+        .unwrap();
+    assert!(!exchange.is_halted());
+
+    // The mark price drops by more than 10% from 96.5 to 86.5.
+    let executed = exchange
+        .update_state(20, bba!(quote!(86), quote!(87)))
+        .unwrap();
+    assert!(exchange.is_halted());
+    assert_eq!(*events.lock().unwrap(), vec![(20, 1_020)]);
+    assert!(executed.is_empty());
+    // The resting orders are not matched during the halt.
+    let executed = exchange
+        .update_state(30, trade!(quote!(86), base!(2), Side::Sell))
+        .unwrap();
+    assert!(executed.is_empty());
+    assert_eq!(exchange.account().active_limit_orders().len(), 2);
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(1)).unwrap()),
+        Err(Error::OrderError(OrderError::TradingHalted))
+    );
+    // Cancellations are still accepted.
+    exchange.cancel_order(first.id()).unwrap();
+
+    // Further moves during the halt do not extend it.
+    exchange
+        .update_state(500, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    assert_eq!(*events.lock().unwrap(), vec![(20, 1_020)]);
+
+    exchange
+        .update_state(1_020, bba!(quote!(92), quote!(93)))
+        .unwrap();
+    assert!(!exchange.is_halted());
+    let executed = exchange
+        .update_state(1_030, trade!(quote!(92), base!(2), Side::Sell))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].id(), second.id());
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+}
@@ -0,0 +1,158 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn exchange() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .starting_balance(quote!(100))
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(1),
+            ..crate::mock_contract_specification_base()
+        })
+        .collateral_asset("BTC", Dec!(0.05))
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn collateral_counts_towards_available_balance() {
+    let mut exchange = exchange();
+    exchange.deposit_collateral("BTC", Dec!(1)).unwrap();
+    // Worthless until the first conversion price arrives.
+    assert_eq!(exchange.account().available_balance(), quote!(100));
+
+    exchange
+        .update_collateral_price("BTC", quote!(1000))
+        .unwrap();
+    assert_eq!(exchange.account().collateral_value(), quote!(950));
+    assert_eq!(exchange.account().effective_balance(), quote!(1050));
+    assert_eq!(exchange.account().available_balance(), quote!(1050));
+
+    // The position margin of 505 exceeds the wallet balance.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(5));
+    assert_eq!(exchange.account().available_balance(), quote!(545));
+    exchange.assert_invariants();
+
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(6)).unwrap()),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+}
+
+#[test]
+fn collateral_errors() {
+    let mut exchange = exchange();
+    assert_eq!(
+        exchange.deposit_collateral("ETH", Dec!(1)),
+        Err(Error::UnknownCollateralAsset)
+    );
+    assert_eq!(
+        exchange.deposit_collateral("BTC", Dec!(0)),
+        Err(Error::InvalidAmount)
+    );
+    assert_eq!(
+        exchange.update_collateral_price("BTC", quote!(0)),
+        Err(Error::InvalidPrice)
+    );
+    assert_eq!(
+        Config::new(
+            quote!(100),
+            200,
+            leverage!(1),
+            exchange.config().contract_specification().clone()
+        )
+        .unwrap()
+        .with_collateral_asset("BTC", Dec!(1))
+        .map(|_| ()),
+        Err(Error::InvalidCollateralAsset)
+    );
+
+    exchange.deposit_collateral("BTC", Dec!(1)).unwrap();
+    exchange
+        .update_collateral_price("BTC", quote!(1000))
+        .unwrap();
+    assert_eq!(
+        exchange.withdraw_collateral("BTC", Dec!(2)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    // The collateral can't be withdrawn in the margin currency.
+    assert_eq!(
+        exchange.withdraw(quote!(101)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    // The collateral backs the position.
+    assert_eq!(
+        exchange.withdraw_collateral("BTC", Dec!(1)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange.withdraw_collateral("BTC", Dec!(0.5)).unwrap();
+    assert_eq!(exchange.account().collateral()[0].quantity(), Dec!(0.5));
+    exchange.assert_invariants();
+}
+
+#[test]
+fn collateral_price_drop_liquidates() {
+    let mut exchange = exchange();
+    exchange.deposit_collateral("BTC", Dec!(1)).unwrap();
+    exchange
+        .update_collateral_price("BTC", quote!(1000))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        exchange.update_collateral_price("BTC", quote!(100)),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+    // Bought at the ask of 101 and sold at the bid of 100.
+    assert_eq!(exchange.account().wallet_balance(), quote!(95));
+    assert_eq!(exchange.account().collateral()[0].quantity(), Dec!(1));
+    assert_eq!(exchange.stats().num_liquidations, 1);
+    exchange.assert_invariants();
+}
+
+#[test]
+fn collateral_covers_negative_wallet_balance() {
+    let mut exchange = exchange();
+    exchange.deposit_collateral("BTC", Dec!(1)).unwrap();
+    exchange
+        .update_collateral_price("BTC", quote!(1000))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(1, bba!(quote!(80), quote!(81)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(5)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(-5));
+    exchange.assert_invariants();
+
+    // The deficit is covered by selling collateral at its market price on the next update.
+    exchange
+        .update_state(2, bba!(quote!(80), quote!(81)))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(0));
+    assert_eq!(exchange.account().collateral()[0].quantity(), Dec!(0.995));
+    assert_eq!(
+        exchange.account().ledger().entries().last().unwrap().kind,
+        LedgerEntryKind::CollateralConversion
+    );
+    assert_eq!(exchange.insurance_fund_payout(), quote!(0));
+    exchange.assert_invariants();
+}
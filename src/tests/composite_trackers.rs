@@ -0,0 +1,52 @@
+use crate::{
+    account_tracker::{FullAccountTracker, NoAccountTracker},
+    prelude::*,
+};
+
+fn trade<A>(account_tracker: A) -> Exchange<A, BaseCurrency>
+where
+    A: AccountTracker<QuoteCurrency>,
+{
+    let config = crate::mock_exchange_base().config().clone();
+    let mut exchange = Exchange::new(account_tracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn composite_trackers_tuple() {
+    let exchange = trade((
+        FullAccountTracker::new(quote!(1000)),
+        NoAccountTracker,
+        FullAccountTracker::new(quote!(1000)),
+    ));
+    let (first, _, second) = exchange.account_tracker();
+    assert_eq!(first.num_trades(), 1);
+    assert_eq!(first.cumulative_fees(), quote!(0.0606));
+    assert_eq!(second.num_trades(), 1);
+    assert_eq!(second.cumulative_fees(), quote!(0.0606));
+}
+
+#[test]
+fn composite_trackers_vec() {
+    let exchange = trade(vec![
+        FullAccountTracker::new(quote!(1000)),
+        FullAccountTracker::new(quote!(1000)),
+    ]);
+    assert!(exchange
+        .account_tracker()
+        .iter()
+        .all(|tracker| tracker.num_trades() == 1));
+
+    let boxed: Vec<Box<dyn AccountTracker<QuoteCurrency>>> = vec![
+        Box::new(NoAccountTracker),
+        Box::new(FullAccountTracker::new(quote!(1000))),
+    ];
+    let exchange = trade(boxed);
+    assert_eq!(exchange.account().position().size(), base!(1));
+}
@@ -0,0 +1,85 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn venue(fee_taker: Fee<Decimal>) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .contract_specification(ContractSpecification {
+            fee_schedule: FeeSchedule::flat(fee!(0), fee_taker),
+            ..crate::mock_contract_specification_base()
+        })
+        .build()
+        .unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+#[test]
+fn cross_exchange_arbitrage() {
+    let mut cross =
+        CrossExchange::new(vec![venue(fee!(0)), venue(fee!(0.001))]).with_transfer_delay(10);
+    cross
+        .update_state(0, 0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    cross
+        .update_state(1, 0, bba!(quote!(110), quote!(111)))
+        .unwrap();
+
+    // Buy the cheap venue and sell the expensive one.
+    cross
+        .venue_mut(0)
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    cross
+        .venue_mut(1)
+        .submit_order(Order::market(Side::Sell, base!(5)).unwrap())
+        .unwrap();
+    // Venue 0 is marked at its bid and venue 1 at its ask, minus the taker fee of 0.55.
+    assert_eq!(cross.venue_equity(0), quote!(995));
+    assert_eq!(cross.venue_equity(1), quote!(994.45));
+    assert_eq!(cross.combined_equity(), quote!(1989.45));
+
+    // The prices converge.
+    cross
+        .update_state(0, 5, bba!(quote!(105), quote!(106)))
+        .unwrap();
+    cross
+        .update_state(1, 5, bba!(quote!(105), quote!(106)))
+        .unwrap();
+    assert_eq!(cross.combined_equity(), quote!(2039.45));
+
+    cross.transfer(1, 0, quote!(100)).unwrap();
+    assert_eq!(cross.pending_transfers().len(), 1);
+    assert_eq!(cross.venue(1).account().wallet_balance(), quote!(899.45));
+    // Funds in transit still count towards the combined equity.
+    assert_eq!(cross.combined_equity(), quote!(2039.45));
+
+    cross
+        .update_state(0, 14, bba!(quote!(105), quote!(106)))
+        .unwrap();
+    assert_eq!(cross.pending_transfers().len(), 1);
+    cross
+        .update_state(0, 15, bba!(quote!(105), quote!(106)))
+        .unwrap();
+    assert!(cross.pending_transfers().is_empty());
+    assert_eq!(cross.venue(0).account().wallet_balance(), quote!(1100));
+    assert_eq!(cross.combined_equity(), quote!(2039.45));
+}
+
+#[test]
+fn cross_exchange_invalid_transfer() {
+    let mut cross = CrossExchange::new(vec![venue(fee!(0)), venue(fee!(0))]);
+    assert_eq!(cross.transfer(0, 0, quote!(1)), Err(Error::InvalidTransfer));
+    assert_eq!(cross.transfer(0, 2, quote!(1)), Err(Error::InvalidTransfer));
+    cross.transfer(0, 1, quote!(1)).unwrap();
+    assert_eq!(cross.venue(1).account().wallet_balance(), quote!(1001));
+}
+
+#[test]
+fn cross_exchange_unknown_venue() {
+    let mut cross = CrossExchange::new(vec![venue(fee!(0)), venue(fee!(0))]);
+    assert_eq!(
+        cross.update_state(2, 0, bba!(quote!(100), quote!(101))),
+        Err(Error::UnknownVenue)
+    );
+    cross
+        .update_state(1, 0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+}
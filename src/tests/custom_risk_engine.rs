@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*};
+
+/// Rejects limit orders priced more than 5% away from the mid price.
+#[derive(Debug, Clone)]
+struct PriceBand;
+
+impl RiskEngine<QuoteCurrency> for PriceBand {
+    fn check_limit_order(
+        &mut self,
+        _market_state: &MarketState,
+        _account: &Account<QuoteCurrency>,
+        order: &Order<BaseCurrency>,
+    ) -> std::result::Result<(), RiskError> {
+        let limit_price = order.limit_price().unwrap();
+        if limit_price < quote!(95) || limit_price > quote!(105) {
+            return Err(RiskError::Rejected("outside of the price band".to_string()));
+        }
+        Ok(())
+    }
+
+    fn box_clone(&self) -> Box<dyn RiskEngine<QuoteCurrency>> {
+        Box::new(self.clone())
+    }
+}
+
+/// Allows at most `max` market orders.
+#[derive(Debug, Clone)]
+struct MaxMarketOrders {
+    max: usize,
+    count: usize,
+}
+
+impl RiskEngine<QuoteCurrency> for MaxMarketOrders {
+    fn check_market_order(
+        &mut self,
+        _market_state: &MarketState,
+        _account: &Account<QuoteCurrency>,
+        _order: &Order<BaseCurrency>,
+        _fill_price: QuoteCurrency,
+    ) -> std::result::Result<(), RiskError> {
+        if self.count == self.max {
+            return Err(RiskError::Rejected("too many market orders".to_string()));
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    fn box_clone(&self) -> Box<dyn RiskEngine<QuoteCurrency>> {
+        Box::new(self.clone())
+    }
+}
+
+#[test]
+fn custom_risk_engines() {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_risk_engine(Arc::new(PriceBand))
+        .with_risk_engine(Arc::new(MaxMarketOrders { max: 1, count: 0 }));
+    let mut exchange = Exchange::new(NoAccountTracker, config.clone());
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    assert_eq!(
+        exchange.submit_order(Order::limit(Side::Buy, quote!(90), base!(1)).unwrap()),
+        Err(Error::RiskError(RiskError::Rejected(
+            "outside of the price band".to_string()
+        )))
+    );
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+        .unwrap();
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(1)).unwrap()),
+        Err(Error::RiskError(RiskError::Rejected(
+            "too many market orders".to_string()
+        )))
+    );
+
+    // Every `Exchange` starts from a fresh clone of the risk engines.
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+}
+
+#[test]
+fn built_in_checks_run_first() {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_risk_engine(Arc::new(MaxMarketOrders { max: 1, count: 0 }));
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    // Rejected by the margin check, so it doesn't count against the custom limit.
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(100)).unwrap()),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+}
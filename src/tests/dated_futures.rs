@@ -0,0 +1,92 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+fn exchange(
+    position_mode: PositionMode,
+) -> Exchange<FullAccountTracker<QuoteCurrency>, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.01),
+            ..crate::mock_contract_specification_base()
+        })
+        .position_mode(position_mode)
+        .expiry(100)
+        .build()
+        .unwrap();
+    Exchange::new(FullAccountTracker::new(quote!(1000)), config)
+}
+
+#[test]
+fn dated_futures_settlement() {
+    let mut exchange = exchange(PositionMode::Netting);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(90), base!(1)).unwrap())
+        .unwrap();
+    assert!(!exchange.is_expired());
+
+    exchange
+        .update_state(99, bba!(quote!(105), quote!(106)))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(2));
+    assert_eq!(exchange.account_tracker().settlement(), None);
+
+    // Settled at the mid price of 110.5, while the limit order would have been filled.
+    assert_eq!(
+        exchange.update_state(100, bba!(quote!(85), quote!(136))),
+        Ok(vec![])
+    );
+    assert!(exchange.is_expired());
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account().wallet_balance(), quote!(1019));
+    assert_eq!(exchange.account().available_balance(), quote!(1019));
+    assert_eq!(exchange.account_tracker().total_rpnl(), quote!(19));
+    assert_eq!(
+        exchange.account_tracker().settlement(),
+        Some((100, quote!(110.5)))
+    );
+    assert_eq!(exchange.stats().num_cancellations, 1);
+
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(1)).unwrap()),
+        Err(Error::ContractExpired)
+    );
+    exchange
+        .update_state(200, bba!(quote!(120), quote!(121)))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(1019));
+}
+
+#[test]
+fn dated_futures_settlement_hedge_mode() {
+    let mut exchange = exchange(PositionMode::Hedge);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+
+    exchange
+        .update_state(150, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.account().short_position().size(), base!(0));
+    // Long: 2 * (110.5 - 101), short: 1 * (100 - 110.5)
+    assert_eq!(exchange.account().wallet_balance(), quote!(1008.5));
+}
+
+#[test]
+fn dated_futures_invalid_expiry() {
+    let config = crate::mock_exchange_base().config().clone();
+    assert_eq!(config.with_expiry(0).unwrap_err(), Error::InvalidExpiry);
+}
@@ -0,0 +1,62 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+#[test]
+fn direction_stats_long_and_short() {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(2))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.5),
+            ..crate::mock_contract_specification_base()
+        })
+        .build()
+        .unwrap();
+    let mut exchange =
+        Exchange::<_, BaseCurrency>::new(FullAccountTracker::new(quote!(1000)), config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(10, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .update_state(20, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(40, bba!(quote!(95), quote!(96)))
+        .unwrap();
+    // Closes the remaining long and opens a short.
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(50, bba!(quote!(95), quote!(96)))
+        .unwrap();
+    exchange
+        .update_state(80, bba!(quote!(90), quote!(91)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+
+    let tracker = exchange.account_tracker();
+    let long = tracker.long_stats();
+    assert_eq!(long.num_trades, 2);
+    assert_eq!(long.num_wins, 1);
+    assert_eq!(long.net_pnl, quote!(3));
+    assert_eq!(long.win_ratio(), 0.5);
+    // Measured from the update at 10 at which the long was first open.
+    assert_eq!(long.avg_duration_ns(), 20);
+
+    let short = tracker.short_stats();
+    assert_eq!(short.num_trades, 1);
+    assert_eq!(short.num_wins, 1);
+    assert_eq!(short.net_pnl, quote!(4));
+    assert_eq!(short.avg_duration_ns(), 30);
+    assert_eq!(long.net_pnl + short.net_pnl, tracker.total_rpnl());
+}
@@ -0,0 +1,52 @@
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*, trade};
+
+#[test]
+fn requests_fail_during_downtime_while_the_market_moves() {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_downtime(10, 20)
+        .unwrap();
+    assert_eq!(
+        config.clone().with_downtime(30, 30).map(|_| ()),
+        Err(Error::InvalidDowntime)
+    );
+    let mut exchange = Exchange::<_, BaseCurrency>::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    let order = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+        .unwrap();
+    let other = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(98), base!(1)).unwrap())
+        .unwrap();
+
+    exchange
+        .update_state(10, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(exchange.is_down());
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(1)).unwrap()),
+        Err(Error::ExchangeUnavailable)
+    );
+    assert_eq!(
+        exchange.cancel_order(order.id()),
+        Err(Error::ExchangeUnavailable)
+    );
+    assert_eq!(exchange.stats().num_rejections, 1);
+
+    // Resting orders still get filled by the market.
+    let executed = exchange
+        .update_state(15, trade!(quote!(99), base!(1), Side::Sell))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(exchange.account().position().size(), base!(1));
+
+    exchange
+        .update_state(20, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(!exchange.is_down());
+    exchange.cancel_order(other.id()).unwrap();
+    exchange.assert_invariants();
+}
@@ -0,0 +1,101 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*, trade};
+
+fn exchange(dust_handling: DustHandling) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(1),
+            quantity_filter: QuantityFilter {
+                min_quantity: base!(1),
+                max_quantity: base!(0),
+                max_market_quantity: base!(0),
+                step_size: base!(0.5),
+            },
+            fee_schedule: FeeSchedule::flat(fee!(0), fee!(0.001)),
+            ..crate::mock_contract_specification_base()
+        })
+        .dust_handling(dust_handling)
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(102)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1.5)).unwrap())
+        .unwrap();
+    exchange
+}
+
+fn reduce_to_dust(exchange: &mut Exchange<NoAccountTracker, BaseCurrency>) {
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+}
+
+#[test]
+fn dust_handling_keep() {
+    let mut exchange = exchange(DustHandling::Keep);
+    reduce_to_dust(&mut exchange);
+    // The remaining 0.5 can't be closed with a valid order.
+    assert_eq!(exchange.account().position().size(), base!(0.5));
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Sell, base!(0.5)).unwrap()),
+        Err(Error::OrderError(OrderError::QuantityTooLow))
+    );
+}
+
+#[test]
+fn dust_handling_auto_close() {
+    let mut exchange = exchange(DustHandling::AutoClose);
+    let wallet_balance = exchange.account().wallet_balance;
+    reduce_to_dust(&mut exchange);
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.dust(), base!(0));
+    // Bought 1.5 @ 102 and sold 1.5 @ 100 as taker, with the fee of the buy paid already.
+    assert_eq!(
+        exchange.account().wallet_balance,
+        wallet_balance - quote!(3) - quote!(0.15)
+    );
+}
+
+#[test]
+fn dust_handling_track() {
+    let mut exchange = exchange(DustHandling::Track);
+    let wallet_balance = exchange.account().wallet_balance;
+    reduce_to_dust(&mut exchange);
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.dust(), base!(0.5));
+    // Only the sell of 1 @ 100 is settled, the dust is written off at the entry price of 102.
+    assert_eq!(
+        exchange.account().wallet_balance,
+        wallet_balance - quote!(2) - quote!(0.1)
+    );
+    assert_eq!(exchange.account().position_margin(), quote!(0));
+}
+
+#[test]
+fn dust_order_remainder_cancelled() {
+    let mut exchange = exchange(DustHandling::AutoClose);
+    let mut order = Order::limit(Side::Sell, quote!(105), base!(2)).unwrap();
+    order.set_display_quantity(base!(1.5)).unwrap();
+    let order = exchange.submit_order(order).unwrap();
+
+    exchange
+        .update_state(1, trade!(quote!(106), base!(5), Side::Buy))
+        .unwrap();
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(
+        exchange.order_status(order.id()),
+        Some(OrderStatus::Cancelled)
+    );
+
+    let mut exchange = self::exchange(DustHandling::Keep);
+    let mut order = Order::limit(Side::Sell, quote!(105), base!(2)).unwrap();
+    order.set_display_quantity(base!(1.5)).unwrap();
+    exchange.submit_order(order).unwrap();
+    exchange
+        .update_state(1, trade!(quote!(106), base!(5), Side::Buy))
+        .unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+}
@@ -0,0 +1,78 @@
+use tokio::sync::mpsc::unbounded_channel;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use crate::{prelude::*, trade};
+
+#[tokio::test]
+async fn exchange_actor_paper_trading() {
+    let (actor, commands, mut events) = ExchangeActor::new(crate::mock_exchange_base());
+    let (market_tx, market_rx) = unbounded_channel();
+    // Queued before any command, as pending commands take precedence over market updates.
+    market_tx.send((0, bba!(quote!(100), quote!(101)))).unwrap();
+
+    let driver = async move {
+        let order = Order::limit(Side::Buy, quote!(99), base!(1)).unwrap();
+        commands
+            .send(ExchangeCommand::SubmitOrder(order.clone()))
+            .unwrap();
+        let Some(ActorEvent::Exchange {
+            ts_ns: 0,
+            event: ExchangeEvent::OrderAccepted(accepted),
+        }) = events.recv().await
+        else {
+            panic!("The order must be accepted");
+        };
+        assert_eq!(accepted.limit_price(), order.limit_price());
+
+        commands.send(ExchangeCommand::CancelOrder(42)).unwrap();
+        assert_eq!(
+            events.recv().await,
+            Some(ActorEvent::CommandFailed {
+                command: ExchangeCommand::CancelOrder(42),
+                error: Error::OrderIdNotFound,
+            })
+        );
+
+        market_tx
+            .send((1, trade!(quote!(98), base!(2), Side::Sell)))
+            .unwrap();
+        let Some(ActorEvent::Exchange {
+            ts_ns: 1,
+            event:
+                ExchangeEvent::Fill {
+                    order: filled,
+                    quantity,
+                    price,
+                    is_maker: true,
+                },
+        }) = events.recv().await
+        else {
+            panic!("The order must be filled");
+        };
+        assert_eq!(filled.id(), accepted.id());
+        assert_eq!(quantity, base!(1));
+        assert_eq!(price, quote!(99));
+        // Ends the market data feed and thereby the actor.
+        drop(market_tx);
+    };
+
+    let (exchange, ()) = tokio::join!(actor.run(UnboundedReceiverStream::new(market_rx)), driver);
+    let exchange = exchange.unwrap();
+    assert_eq!(exchange.account().position().size(), base!(1));
+    assert_eq!(exchange.account().active_limit_orders().len(), 0);
+}
+
+#[tokio::test]
+async fn exchange_actor_invalid_market_update() {
+    let (actor, _commands, mut events) = ExchangeActor::new(crate::mock_exchange_base());
+    let updates = tokio_stream::iter([
+        (0, bba!(quote!(100), quote!(101))),
+        (1, bba!(quote!(101), quote!(100))),
+        (2, bba!(quote!(100), quote!(101))),
+    ]);
+    assert_eq!(
+        actor.run(updates).await.unwrap_err(),
+        Error::InvalidMarketUpdateBidAskSpread
+    );
+    assert!(events.try_recv().is_err());
+}
@@ -0,0 +1,164 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{account_tracker::NoAccountTracker, prelude::*, trade};
+
+type Events = Arc<Mutex<Vec<(i64, ExchangeEvent<BaseCurrency>)>>>;
+
+fn subscribe(exchange: &mut Exchange<NoAccountTracker, BaseCurrency>) -> Events {
+    let events = Events::default();
+    let sink = Arc::clone(&events);
+    exchange.subscribe(move |ts_ns, event| sink.lock().unwrap().push((ts_ns, event.clone())));
+    events
+}
+
+#[test]
+fn exchange_events_orders() {
+    let mut exchange = crate::mock_exchange_base();
+    let events = subscribe(&mut exchange);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let market = exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    let limit = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+        .unwrap();
+    let rejected = Order::limit(Side::Buy, quote!(102), base!(1)).unwrap();
+    assert!(exchange.submit_order(rejected.clone()).is_err());
+    let cancelled = exchange
+        .submit_order(Order::limit(Side::Sell, quote!(110), base!(1)).unwrap())
+        .unwrap();
+    let cancel = exchange.cancel_order(cancelled.id()).unwrap();
+
+    let executed = exchange
+        .update_state(1, trade!(quote!(98), base!(2), Side::Sell))
+        .unwrap();
+
+    let events = events.lock().unwrap();
+    let mut rejected = rejected;
+    rejected.set_id(2);
+    rejected.set_status(OrderStatus::Rejected, 0);
+    assert_eq!(
+        *events,
+        vec![
+            (0, ExchangeEvent::OrderAccepted(market.clone())),
+            (
+                0,
+                ExchangeEvent::Fill {
+                    order: market,
+                    quantity: base!(1),
+                    price: quote!(101),
+                    is_maker: false,
+                }
+            ),
+            (0, ExchangeEvent::OrderAccepted(limit.clone())),
+            (
+                0,
+                ExchangeEvent::OrderRejected {
+                    order: rejected,
+                    error: Error::OrderError(OrderError::LimitPriceAboveAsk),
+                }
+            ),
+            (0, ExchangeEvent::OrderAccepted(cancelled.clone())),
+            (0, ExchangeEvent::Cancel(cancel)),
+            (
+                1,
+                ExchangeEvent::Fill {
+                    order: executed[0].clone(),
+                    quantity: base!(1),
+                    price: quote!(99),
+                    is_maker: true,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn exchange_events_liquidation_and_settlement() {
+    let contract_spec = crate::mock_exchange_base()
+        .config()
+        .contract_specification()
+        .clone();
+    let config = Config::new(quote!(1000), 200, leverage!(1), contract_spec)
+        .unwrap()
+        .with_expiry(10)
+        .unwrap()
+        .with_borrow_rate(Dec!(0.1))
+        .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    let events = subscribe(&mut exchange);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .update_state(10, bba!(quote!(100), quote!(102)))
+        .unwrap();
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![(10, ExchangeEvent::Settlement { price: quote!(101) })]
+    );
+
+    let mut exchange = crate::mock_exchange_base();
+    let events = subscribe(&mut exchange);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(9)).unwrap())
+        .unwrap();
+    assert!(exchange
+        .update_state(1, bba!(quote!(200), quote!(201)))
+        .is_err());
+    assert_eq!(
+        events.lock().unwrap().last(),
+        Some(&(
+            1,
+            ExchangeEvent::Liquidation {
+                bid: quote!(200),
+                ask: quote!(201)
+            }
+        ))
+    );
+}
+
+#[test]
+fn exchange_events_borrow_cost_and_interest() {
+    const YEAR_NS: u64 = 365 * 86_400_000_000_000;
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(2))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.5),
+            ..crate::mock_contract_specification_base()
+        })
+        .borrow_rate(Dec!(0.1))
+        .idle_margin_rate(Dec!(0.05))
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    let events = subscribe(&mut exchange);
+    exchange
+        .update_state(YEAR_NS / 2, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let ledger = exchange.account().ledger();
+    assert_eq!(ledger.total(LedgerEntryKind::BorrowCost), quote!(-5.05));
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            (YEAR_NS as i64 / 2, ExchangeEvent::BorrowCost(quote!(5.05))),
+            (
+                YEAR_NS as i64 / 2,
+                ExchangeEvent::Interest(ledger.total(LedgerEntryKind::Interest))
+            ),
+        ]
+    );
+}
@@ -0,0 +1,56 @@
+use crate::{mock_exchange_base, prelude::*, trade};
+
+#[test]
+fn exchange_stats() {
+    let mut exchange = mock_exchange_base();
+    assert_eq!(exchange.stats(), ExchangeStats::default());
+
+    exchange
+        .update_state(1_000, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    // Invalid market update.
+    assert!(exchange
+        .update_state(1_500, bba!(quote!(102), quote!(101)))
+        .is_err());
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    assert!(exchange
+        .submit_order(Order::market(Side::Buy, base!(100)).unwrap())
+        .is_err());
+
+    let order = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(90), base!(1)).unwrap())
+        .unwrap();
+    exchange.cancel_order(order.id()).unwrap();
+    exchange
+        .submit_oco_orders(
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(105), base!(1)).unwrap(),
+        )
+        .unwrap();
+    let mut order = Order::limit(Side::Buy, quote!(90), base!(1)).unwrap();
+    order.set_time_in_force(TimeInForce::GoodTilDate(2_500));
+    exchange.submit_order(order).unwrap();
+
+    exchange
+        .update_state(3_000, trade!(quote!(94), base!(1), Side::Sell))
+        .unwrap();
+
+    assert_eq!(
+        exchange.stats(),
+        ExchangeStats {
+            num_updates: 3,
+            num_fills: 2,
+            num_cancellations: 2,
+            num_expirations: 1,
+            num_rejections: 1,
+            num_rate_limited: 0,
+            num_injected_faults: 0,
+            num_liquidations: 0,
+            step: 2,
+            elapsed_ns: 2_000,
+        }
+    );
+}
@@ -0,0 +1,98 @@
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*};
+
+fn mock_exchange_f64() -> Exchange<NoAccountTracker, BaseCurrency<f64>> {
+    let contract_specification = ContractSpecification {
+        ticker: "TESTUSD".to_string(),
+        initial_margin: 0.01,
+        maintenance_margin: 0.02,
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter::default(),
+        quantity_filter: QuantityFilter {
+            min_quantity: BaseCurrency::new(0.0),
+            max_quantity: BaseCurrency::new(0.0),
+            max_market_quantity: BaseCurrency::new(0.0),
+            step_size: BaseCurrency::new(0.01),
+        },
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(Fee::new(0.0002), Fee::new(0.0006)),
+    };
+    let config = Config::new(
+        QuoteCurrency::new(1000.0),
+        200,
+        leverage!(1),
+        contract_specification,
+    )
+    .unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{a} != {b}");
+}
+
+#[test]
+fn f64_backend_market_orders() {
+    let mut exchange = mock_exchange_f64();
+    exchange
+        .update_state(
+            0,
+            bba!(QuoteCurrency::new(100.0), QuoteCurrency::new(101.0)),
+        )
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, BaseCurrency::new(5.0)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position.size, BaseCurrency::new(5.0));
+    assert_eq!(
+        exchange.account().position.entry_price,
+        QuoteCurrency::new(101.0)
+    );
+    assert_close(exchange.account().available_balance().inner(), 494.697);
+
+    exchange
+        .update_state(
+            1,
+            bba!(QuoteCurrency::new(110.0), QuoteCurrency::new(111.0)),
+        )
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, BaseCurrency::new(5.0)).unwrap())
+        .unwrap();
+    assert!(exchange.account().position.size.is_zero());
+    // 45 profit minus the fees of 0.303 and 0.33.
+    assert_close(exchange.account().wallet_balance().inner(), 1044.367);
+}
+
+#[test]
+fn f64_backend_matches_decimal() {
+    let mut exchange_f64 = mock_exchange_f64();
+    let mut exchange_dec = mock_exchange_base();
+    let prices = [(100.0, 101.0), (98.0, 99.0), (103.0, 104.0), (101.0, 102.0)];
+    for (i, (bid, ask)) in prices.into_iter().enumerate() {
+        let ts = i as u64;
+        exchange_f64
+            .update_state(ts, bba!(QuoteCurrency::new(bid), QuoteCurrency::new(ask)))
+            .unwrap();
+        exchange_dec
+            .update_state(
+                ts,
+                bba!(
+                    QuoteCurrency::new(Decimal::from_f64(bid).unwrap()),
+                    QuoteCurrency::new(Decimal::from_f64(ask).unwrap())
+                ),
+            )
+            .unwrap();
+        let side = if i % 2 == 0 { Side::Buy } else { Side::Sell };
+        exchange_f64
+            .submit_order(Order::market(side, BaseCurrency::new(2.5)).unwrap())
+            .unwrap();
+        exchange_dec
+            .submit_order(Order::market(side, base!(2.5)).unwrap())
+            .unwrap();
+    }
+    assert_close(
+        exchange_f64.account().wallet_balance().inner(),
+        exchange_dec.account().wallet_balance().inner().to_f64(),
+    );
+}
@@ -0,0 +1,76 @@
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*};
+
+fn exchange(fault_injection: FaultInjection) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_fault_injection(fault_injection, 0);
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn rejected_order_actions_have_no_effect() {
+    let mut exchange = exchange(FaultInjection::new(1.0, 0.0, 0).unwrap());
+    let order = Order::limit(Side::Buy, quote!(99), base!(1)).unwrap();
+    let err = exchange.submit_order(order.clone()).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::ExchangeOverloaded | Error::RequestTimeout
+    ));
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert!(exchange.cancel_order(0).is_err());
+    assert_eq!(exchange.stats().num_injected_faults, 2);
+    assert_eq!(exchange.stats().num_rejections, 0);
+    exchange.assert_invariants();
+}
+
+#[test]
+fn delayed_order_actions_arrive_later() {
+    let mut exchange = exchange(FaultInjection::new(0.0, 1.0, 10).unwrap());
+    let order = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+        .unwrap();
+    assert!(exchange.account().active_limit_orders().is_empty());
+
+    exchange
+        .update_state(10, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(exchange
+        .account()
+        .active_limit_orders()
+        .contains_key(&order.id()));
+
+    exchange.cancel_order(order.id()).unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+    exchange
+        .update_state(20, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.stats().num_injected_faults, 2);
+    exchange.assert_invariants();
+}
+
+#[test]
+fn retries_eventually_succeed() {
+    let mut exchange = exchange(FaultInjection::new(0.5, 0.0, 0).unwrap());
+    let mut attempts = 0;
+    let order = loop {
+        attempts += 1;
+        match exchange.submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap()) {
+            Ok(order) => break order,
+            Err(Error::ExchangeOverloaded | Error::RequestTimeout) => continue,
+            Err(e) => panic!("unexpected error {e}"),
+        }
+    };
+    assert_eq!(exchange.stats().num_injected_faults, attempts - 1);
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+    assert!(exchange
+        .account()
+        .active_limit_orders()
+        .contains_key(&order.id()));
+    exchange.assert_invariants();
+}
@@ -0,0 +1,56 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+#[test]
+fn fee_schedule_rolling_volume_tiers() {
+    let fee_schedule = FeeSchedule::new(
+        1_000,
+        vec![
+            FeeTier {
+                min_volume: Dec!(0),
+                fee_maker: fee!(0.0002),
+                fee_taker: fee!(0.0006),
+            },
+            FeeTier {
+                min_volume: Dec!(150),
+                fee_maker: fee!(0.0001),
+                fee_taker: fee!(0.0004),
+            },
+        ],
+    )
+    .unwrap();
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.01),
+            fee_schedule,
+            ..crate::mock_contract_specification_base()
+        })
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::<NoAccountTracker, BaseCurrency>::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.fee_taker(), fee!(0.0006));
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.rolling_volume(), Dec!(202));
+    assert_eq!(exchange.fee_maker(), fee!(0.0001));
+    assert_eq!(exchange.fee_taker(), fee!(0.0004));
+    assert_eq!(exchange.account().wallet_balance(), quote!(999.8788));
+
+    // The next fill is charged the fee of the new tier.
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(997.7988));
+
+    // The volume drops out of the rolling window.
+    exchange
+        .update_state(1_000, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.rolling_volume(), Dec!(0));
+    assert_eq!(exchange.fee_taker(), fee!(0.0006));
+}
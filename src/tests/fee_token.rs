@@ -0,0 +1,96 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+fn config() -> ConfigBuilder<QuoteCurrency> {
+    crate::mock_config_base()
+        .starting_balance(quote!(10000))
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(1),
+            fee_schedule: FeeSchedule::flat(fee!(0), fee!(0.001)),
+            ..crate::mock_contract_specification_base()
+        })
+}
+
+#[test]
+fn fee_token_config() {
+    assert_eq!(
+        config().fee_token("BNB", Dec!(1)).build().map(|_| ()),
+        Err(Error::InvalidFeeToken)
+    );
+    assert_eq!(
+        config().fee_token("BNB", Dec!(-0.1)).build().map(|_| ()),
+        Err(Error::InvalidFeeToken)
+    );
+
+    let mut exchange = Exchange::<_, BaseCurrency>::new(
+        FullAccountTracker::new(quote!(10000)),
+        config().build().unwrap(),
+    );
+    assert!(exchange.account().fee_token().is_none());
+    assert_eq!(
+        exchange.deposit_fee_token(Dec!(1)),
+        Err(Error::FeeTokenNotConfigured)
+    );
+    assert_eq!(
+        exchange.update_fee_token_price(quote!(2)),
+        Err(Error::FeeTokenNotConfigured)
+    );
+}
+
+#[test]
+fn fees_paid_in_fee_token_at_a_discount() {
+    let config = config().fee_token("BNB", Dec!(0.1)).build().unwrap();
+    let mut exchange =
+        Exchange::<_, BaseCurrency>::new(FullAccountTracker::new(quote!(10000)), config);
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    exchange.deposit_fee_token(Dec!(1)).unwrap();
+    assert_eq!(
+        exchange.deposit_fee_token(Dec!(0)),
+        Err(Error::InvalidAmount)
+    );
+
+    // Without a conversion price the fee is paid in the margin currency.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(10)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance, quote!(9999));
+    assert_eq!(exchange.account().fee_token().unwrap().quantity(), Dec!(1));
+
+    // The fee of 1 is discounted to 0.9 and paid with 0.45 tokens.
+    exchange.update_fee_token_price(quote!(2)).unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(10)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance, quote!(9999));
+    assert_eq!(
+        exchange.account().fee_token().unwrap().quantity(),
+        Dec!(0.55)
+    );
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(10)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.account().fee_token().unwrap().quantity(),
+        Dec!(0.1)
+    );
+
+    // The remaining tokens don't cover the next fee, which falls back to the margin currency.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(10)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance, quote!(9998));
+    assert_eq!(
+        exchange.account().fee_token().unwrap().quantity(),
+        Dec!(0.1)
+    );
+    assert_eq!(exchange.account_tracker().cumulative_fees(), quote!(3.8));
+
+    assert_eq!(
+        exchange.withdraw_fee_token(Dec!(0.2)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange.withdraw_fee_token(Dec!(0.1)).unwrap();
+    assert_eq!(exchange.account().fee_token().unwrap().quantity(), Dec!(0));
+}
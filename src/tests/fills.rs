@@ -0,0 +1,79 @@
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*, trade};
+
+#[test]
+fn fills_of_latest_call() {
+    let config = mock_exchange_base().config().clone().with_trade_history(2);
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(exchange.fills().is_empty());
+
+    let order = exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.fills(),
+        &[Fill {
+            trade_id: 0,
+            order_id: order.id(),
+            side: Side::Buy,
+            price: quote!(101),
+            quantity: base!(2),
+            fee: quote!(0.1212),
+            is_maker: false,
+            timestamp_ns: 0,
+        }]
+    );
+
+    let order = exchange
+        .submit_order(Order::limit(Side::Sell, quote!(102), base!(3)).unwrap())
+        .unwrap();
+    // Resting orders don't fill on submission.
+    assert!(exchange.fills().is_empty());
+
+    exchange
+        .update_state(1, trade!(quote!(103), base!(3), Side::Buy))
+        .unwrap();
+    assert_eq!(
+        exchange.fills(),
+        &[Fill {
+            trade_id: 1,
+            order_id: order.id(),
+            side: Side::Sell,
+            price: quote!(102),
+            quantity: base!(3),
+            fee: quote!(0.0612),
+            is_maker: true,
+            timestamp_ns: 1,
+        }]
+    );
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    // The trade history is bounded, so the first fill dropped out.
+    assert_eq!(
+        Vec::from_iter(exchange.trade_history().iter().map(|fill| fill.trade_id)),
+        vec![1, 2]
+    );
+    assert_eq!(exchange.trade_history()[1], exchange.fills()[0]);
+
+    exchange
+        .update_state(2, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(exchange.fills().is_empty());
+}
+
+#[test]
+fn trade_history_disabled_by_default() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.fills().len(), 1);
+    assert!(exchange.trade_history().is_empty());
+}
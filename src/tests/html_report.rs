@@ -0,0 +1,41 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+const DAILY_NS: u64 = 86_400_000_000_000;
+
+#[test]
+fn html_report_is_self_contained() {
+    let mut exchange = Exchange::<_, BaseCurrency>::new(
+        FullAccountTracker::new(quote!(1000)),
+        crate::mock_exchange_base().config().clone(),
+    );
+    for day in 0..60 {
+        let price = quote!(100) + QuoteCurrency::new(Decimal::from(day % 7));
+        exchange
+            .update_state(day * DAILY_NS, bba!(price, price + quote!(1)))
+            .unwrap();
+        let side = if day % 2 == 0 { Side::Buy } else { Side::Sell };
+        exchange
+            .submit_order(Order::market(side, base!(1)).unwrap())
+            .unwrap();
+    }
+
+    let mut html = Vec::new();
+    exchange
+        .account_tracker()
+        .write_html_report(&mut html)
+        .unwrap();
+    let html = String::from_utf8(html).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.trim_end().ends_with("</html>"));
+    assert_eq!(html.matches("<svg").count(), 4);
+    for title in [
+        "Equity curve",
+        "Drawdown",
+        "Monthly returns",
+        "Realized trades",
+    ] {
+        assert!(html.contains(title));
+    }
+    // Only the inline charts, no external assets.
+    assert!(!html.contains("src="));
+}
@@ -0,0 +1,77 @@
+use crate::{mock_exchange_base, prelude::*, trade};
+
+#[test]
+fn iceberg_order_refills_after_each_execution() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let mut order = Order::limit(Side::Buy, quote!(100), base!(3)).unwrap();
+    order.set_display_quantity(base!(1)).unwrap();
+    let order = exchange.submit_order(order).unwrap();
+    // The hidden quantity requires order margin as well.
+    assert_eq!(exchange.account().order_margin(), quote!(300.06));
+
+    let executed = exchange
+        .update_state(1, trade!(quote!(99), base!(5), Side::Sell))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].filled_quantity(), base!(1));
+    assert_eq!(executed[0].filled(), Filled::No);
+    assert_eq!(exchange.account().position().size(), base!(1));
+    assert_eq!(exchange.account().wallet_balance(), quote!(999.98));
+    assert_eq!(exchange.account().order_margin(), quote!(200.04));
+    let active = &exchange.account().active_limit_orders()[&order.id()];
+    assert_eq!(active.remaining_quantity(), base!(2));
+    assert_eq!(active.visible_quantity(), base!(1));
+    // Refilled at the back of the queue.
+    assert_eq!(active.timestamp(), 1);
+
+    exchange
+        .update_state(2, trade!(quote!(99), base!(5), Side::Sell))
+        .unwrap();
+    let executed = exchange
+        .update_state(3, trade!(quote!(99), base!(5), Side::Sell))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(
+        executed[0].filled(),
+        Filled::Yes {
+            fill_price: quote!(100)
+        }
+    );
+    assert_eq!(executed[0].filled_quantity(), base!(3));
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account().order_margin(), quote!(0));
+    assert_eq!(exchange.account().position().size(), base!(3));
+    assert_eq!(exchange.account().position().entry_price(), quote!(100));
+    // Every slice is charged the maker fee.
+    assert_eq!(exchange.account().wallet_balance(), quote!(999.94));
+}
+
+#[test]
+fn iceberg_order_display_quantity_filter() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let mut order = Order::limit(Side::Sell, quote!(102), base!(3)).unwrap();
+    order.set_display_quantity(base!(0.005)).unwrap();
+    assert_eq!(
+        exchange.submit_order(order),
+        Err(Error::OrderError(OrderError::InvalidQuantityStepSize))
+    );
+
+    let mut order = Order::limit(Side::Sell, quote!(102), base!(3)).unwrap();
+    assert_eq!(
+        order.set_display_quantity(base!(4)),
+        Err(OrderError::InvalidDisplayQuantity)
+    );
+    let mut order = Order::market(Side::Sell, base!(3)).unwrap();
+    assert_eq!(
+        order.set_display_quantity(base!(1)),
+        Err(OrderError::InvalidDisplayQuantity)
+    );
+}
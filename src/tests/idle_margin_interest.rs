@@ -0,0 +1,47 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+const YEAR_NS: u64 = 365 * 86_400_000_000_000;
+
+fn config() -> ConfigBuilder<QuoteCurrency> {
+    crate::mock_config_base()
+        .initial_leverage(leverage!(2))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.5),
+            ..crate::mock_contract_specification_base()
+        })
+}
+
+#[test]
+fn idle_margin_interest_on_available_balance() {
+    let config = config().idle_margin_rate(Dec!(0.05)).build().unwrap();
+    let mut exchange =
+        Exchange::<_, BaseCurrency>::new(FullAccountTracker::new(quote!(1000)), config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().available_balance(), quote!(899));
+
+    // Only the 899 not used as position margin earn interest.
+    exchange
+        .update_state(YEAR_NS / 2, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(1022.475));
+    assert_eq!(
+        exchange.account().ledger().total(LedgerEntryKind::Interest),
+        quote!(22.475)
+    );
+    let tracker = exchange.account_tracker();
+    assert_eq!(tracker.cumulative_idle_margin_interest(), quote!(22.475));
+    assert_eq!(tracker.pnl_attribution().funding_received, quote!(22.475));
+}
+
+#[test]
+fn idle_margin_rate_must_not_be_negative() {
+    assert_eq!(
+        config().idle_margin_rate(Dec!(-0.01)).build().map(|_| ()),
+        Err(Error::InvalidIdleMarginRate)
+    );
+}
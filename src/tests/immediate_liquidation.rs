@@ -0,0 +1,51 @@
+use crate::{account_tracker::NoAccountTracker, candle, prelude::*};
+
+fn exchange() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base().build().unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+#[test]
+fn resting_order_filled_beyond_liquidation_price() {
+    let mut exchange = exchange();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(50)).unwrap())
+        .unwrap();
+
+    // The candle gaps through the limit price, so the new long is liquidated within the same update.
+    assert_eq!(
+        exchange.update_state(1, candle!(quote!(80), quote!(81), quote!(79), quote!(101))),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.account().wallet_balance(), quote!(50));
+    assert_eq!(exchange.stats().num_liquidations, 1);
+    exchange.assert_invariants();
+}
+
+#[test]
+fn market_order_liquidated_by_the_spread() {
+    let mut exchange = exchange();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(120)))
+        .unwrap();
+
+    // Buying at the ask loses half the spread against the mid price, which exceeds the margin above maintenance.
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(10)).unwrap()),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+
+    exchange
+        .update_state(1, bba!(quote!(100), quote!(110)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(10)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(10));
+    exchange.assert_invariants();
+}
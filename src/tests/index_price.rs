@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use crate::{account_tracker::NoAccountTracker, index, mock_exchange_base, prelude::*};
+
+#[test]
+fn index_updates_leave_the_book_untouched() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+        .unwrap();
+
+    exchange.update_state(1, index!(quote!(90.25))).unwrap();
+    assert_eq!(exchange.market_state().index_price(), Some(quote!(90.25)));
+    assert_eq!(exchange.market_state().bid(), quote!(100));
+    assert_eq!(exchange.market_state().ask(), quote!(101));
+    // The default `MidPrice` ignores the index.
+    assert_eq!(exchange.market_state().mark_price(), quote!(100.5));
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+
+    assert_eq!(
+        exchange.update_state(2, index!(quote!(0))),
+        Err(Error::InvalidMarketUpdatePrice)
+    );
+    exchange.assert_invariants();
+}
+
+#[test]
+fn index_drives_the_mark_price() {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_mark_price_source(Arc::new(LastIndexPrice));
+    let mut exchange = Exchange::<_, BaseCurrency>::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.market_state().mark_price(), quote!(100.5));
+
+    exchange.update_state(1, index!(quote!(99.8))).unwrap();
+    assert_eq!(exchange.market_state().mark_price(), quote!(99.8));
+    exchange
+        .update_state(2, bba!(quote!(102), quote!(103)))
+        .unwrap();
+    assert_eq!(exchange.market_state().mark_price(), quote!(99.8));
+    exchange.assert_invariants();
+}
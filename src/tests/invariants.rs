@@ -0,0 +1,222 @@
+//! Property based tests, which drive the exchange with random sequences of orders and market updates
+//! and check `Exchange::assert_invariants` after every step.
+
+use proptest::{prelude::*, strategy::Strategy};
+
+use crate::{account_tracker::NoAccountTracker, candle, mock_exchange_base, prelude::*, trade};
+
+/// A step applied to the exchange, with prices in ticks and quantities in steps of 0.01.
+#[derive(Debug, Clone)]
+enum Action {
+    Quote {
+        bid: u64,
+    },
+    Trade {
+        price: u64,
+        quantity: u64,
+        side: Side,
+    },
+    Candle {
+        bid: u64,
+        low: u64,
+        high: u64,
+    },
+    Market {
+        side: Side,
+        quantity: u64,
+        reduce_only: bool,
+    },
+    Limit {
+        side: Side,
+        price: u64,
+        quantity: u64,
+        reduce_only: bool,
+    },
+    Cancel {
+        index: usize,
+    },
+}
+
+fn side() -> impl Strategy<Value = Side> {
+    prop_oneof![Just(Side::Buy), Just(Side::Sell)]
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        (80_u64..120).prop_map(|bid| Action::Quote { bid }),
+        (80_u64..120, 1_u64..500, side()).prop_map(|(price, quantity, side)| Action::Trade {
+            price,
+            quantity,
+            side
+        }),
+        (80_u64..120, 0_u64..10, 0_u64..10).prop_map(|(bid, down, up)| Action::Candle {
+            bid,
+            low: bid - down - 1,
+            high: bid + up + 2,
+        }),
+        (side(), 1_u64..500, any::<bool>()).prop_map(|(side, quantity, reduce_only)| {
+            Action::Market {
+                side,
+                quantity,
+                reduce_only,
+            }
+        }),
+        (side(), 80_u64..120, 1_u64..500, any::<bool>()).prop_map(
+            |(side, price, quantity, reduce_only)| Action::Limit {
+                side,
+                price,
+                quantity,
+                reduce_only
+            }
+        ),
+        any::<usize>().prop_map(|index| Action::Cancel { index }),
+    ]
+}
+
+fn price(ticks: u64) -> QuoteCurrency {
+    QuoteCurrency::new(Decimal::from_u64(ticks))
+}
+
+fn quantity(steps: u64) -> BaseCurrency {
+    BaseCurrency::new(Decimal::from_u64(steps) * Dec!(0.01))
+}
+
+fn exchange(
+    leverage: Leverage,
+    position_mode: PositionMode,
+) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage)
+        .contract_specification(ContractSpecification {
+            fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+            ..crate::mock_contract_specification_base()
+        })
+        .position_mode(position_mode)
+        .build()
+        .unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+/// Apply the `actions` one by one, ignoring rejected orders and liquidations,
+/// and check the invariants after every step.
+fn run(mut exchange: Exchange<NoAccountTracker, BaseCurrency>, actions: Vec<Action>) {
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange.assert_invariants();
+    for (i, action) in actions.into_iter().enumerate() {
+        let ts = i as u64 + 1;
+        let _ = match action {
+            Action::Quote { bid } => exchange
+                .update_state(ts, bba!(price(bid), price(bid + 1)))
+                .map(drop),
+            Action::Trade {
+                price: p,
+                quantity: q,
+                side,
+            } => exchange
+                .update_state(ts, trade!(price(p), quantity(q), side))
+                .map(drop),
+            Action::Candle { bid, low, high } => exchange
+                .update_state(
+                    ts,
+                    candle!(price(bid), price(bid + 1), price(low), price(high)),
+                )
+                .map(drop),
+            Action::Market {
+                side,
+                quantity: q,
+                reduce_only,
+            } => Order::market(side, quantity(q))
+                .map_err(Error::from)
+                .and_then(|mut order| {
+                    order.set_reduce_only(reduce_only);
+                    exchange.submit_order(order)
+                })
+                .map(drop),
+            Action::Limit {
+                side,
+                price: p,
+                quantity: q,
+                reduce_only,
+            } => Order::limit(side, price(p), quantity(q))
+                .map_err(Error::from)
+                .and_then(|mut order| {
+                    order.set_reduce_only(reduce_only);
+                    exchange.submit_order(order)
+                })
+                .map(drop),
+            Action::Cancel { index } => {
+                let mut ids =
+                    Vec::from_iter(exchange.account().active_limit_orders().keys().copied());
+                ids.sort_unstable();
+                match ids.get(index.checked_rem(ids.len()).unwrap_or(0)) {
+                    Some(&id) => exchange.cancel_order(id).map(drop),
+                    None => Ok(()),
+                }
+            }
+        };
+        exchange.assert_invariants();
+    }
+}
+
+proptest! {
+    #[test]
+    fn invariants_netting(actions in prop::collection::vec(action(), 1..64)) {
+        run(mock_exchange_base(), actions);
+    }
+
+    #[test]
+    fn invariants_netting_leveraged(actions in prop::collection::vec(action(), 1..64)) {
+        run(exchange(leverage!(10), PositionMode::Netting), actions);
+    }
+
+    #[test]
+    fn invariants_hedge(actions in prop::collection::vec(action(), 1..64)) {
+        run(exchange(leverage!(5), PositionMode::Hedge), actions);
+    }
+
+    /// Flipping a position with a single order is the same as closing it and opening the opposite one.
+    #[test]
+    fn position_flip_conserves_value(
+        side in side(),
+        open in 1_u64..300,
+        flip in 1_u64..300,
+        entry in 90_u64..110,
+        exit in 90_u64..110,
+    ) {
+        let opposite = side.inverted();
+        let mut single = mock_exchange_base();
+        let mut split = mock_exchange_base();
+        for exchange in [&mut single, &mut split] {
+            exchange.update_state(0, bba!(price(entry), price(entry + 1))).unwrap();
+            exchange.submit_order(Order::market(side, quantity(open)).unwrap()).unwrap();
+            exchange.update_state(1, bba!(price(exit), price(exit + 1))).unwrap();
+        }
+        let equity = |exchange: &Exchange<NoAccountTracker, BaseCurrency>| {
+            exchange.account().wallet_balance()
+                + exchange.account().unrealized_pnl(price(exit), price(exit + 1))
+        };
+        let equity_before = equity(&single);
+
+        let flipped = single.submit_order(Order::market(opposite, quantity(open + flip)).unwrap());
+        let closed = split.submit_order(Order::market(opposite, quantity(open)).unwrap());
+        prop_assume!(flipped.is_ok() && closed.is_ok());
+        let opened = split.submit_order(Order::market(opposite, quantity(flip)).unwrap());
+        prop_assert!(opened.is_ok());
+        single.assert_invariants();
+        split.assert_invariants();
+
+        prop_assert_eq!(single.account().wallet_balance(), split.account().wallet_balance());
+        prop_assert_eq!(single.account().position(), split.account().position());
+
+        // The flip only costs the fees and the spread of the new position.
+        let fill_price = match opposite {
+            Side::Buy => price(exit + 1),
+            Side::Sell => price(exit),
+        };
+        let fee = quantity(open + flip).convert(fill_price) * single.fee_taker();
+        let spread_cost = quantity(flip).convert(quote!(1));
+        prop_assert_eq!(equity(&single), equity_before - fee - spread_cost);
+    }
+}
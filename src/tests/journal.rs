@@ -0,0 +1,90 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*, trade};
+
+fn record_session() -> (
+    Exchange<NoAccountTracker, BaseCurrency>,
+    Journal<BaseCurrency>,
+) {
+    let mut exchange = crate::mock_exchange_base();
+    exchange.start_recording();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    assert!(exchange
+        .submit_order(Order::limit(Side::Buy, quote!(102), base!(1)).unwrap())
+        .is_err());
+    exchange
+        .submit_oco_orders(
+            Order::limit(Side::Sell, quote!(105), base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(110), base!(1)).unwrap(),
+        )
+        .unwrap();
+    exchange
+        .update_state(1, trade!(quote!(106), base!(1), Side::Buy))
+        .unwrap();
+    let journal = exchange.stop_recording().unwrap();
+    (exchange, journal)
+}
+
+#[test]
+fn journal_records_inputs_and_events() {
+    let (_, journal) = record_session();
+    let inputs = journal.entries().iter().filter(|e| e.is_input()).count();
+    assert_eq!(inputs, 5);
+    assert!(journal.entries().iter().any(|e| matches!(
+        e,
+        JournalEntry::Event {
+            event: ExchangeEvent::OrderRejected { .. },
+            ..
+        }
+    )));
+    assert!(matches!(
+        journal.entries().last(),
+        Some(JournalEntry::Event {
+            ts_ns: 1,
+            event: ExchangeEvent::Cancel(_)
+        })
+    ));
+}
+
+#[test]
+fn journal_replay() {
+    let (recorded, journal) = record_session();
+
+    let mut exchange = crate::mock_exchange_base();
+    exchange.replay(&journal).unwrap();
+    assert_eq!(exchange.account().position(), recorded.account().position());
+    assert_eq!(
+        exchange.account().wallet_balance(),
+        recorded.account().wallet_balance()
+    );
+    assert_eq!(exchange.stats(), recorded.stats());
+    assert!(exchange.stop_recording().is_none());
+
+    // A different starting balance can not afford the orders, so the replay diverges.
+    let config = Config::new(
+        quote!(10),
+        200,
+        leverage!(1),
+        recorded.config().contract_specification().clone(),
+    )
+    .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    assert_eq!(exchange.replay(&journal), Err(Error::ReplayDiverged));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn journal_json_lines() {
+    let (_, journal) = record_session();
+    let mut buf = Vec::new();
+    journal.write_json_lines(&mut buf).unwrap();
+    assert_eq!(
+        buf.iter().filter(|b| **b == b'\n').count(),
+        journal.entries().len()
+    );
+    let read = Journal::read_json_lines(buf.as_slice()).unwrap();
+    assert_eq!(read, journal);
+}
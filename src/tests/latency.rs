@@ -0,0 +1,149 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*, trade};
+
+fn exchange(latency_model: LatencyModel) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_latency(latency_model, 0)
+        .unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+#[test]
+fn market_order_fills_at_arrival_price() {
+    let mut exchange = exchange(LatencyModel::Fixed(10));
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let order = exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.num_in_flight(), 1);
+    assert_eq!(exchange.account().position().size(), base!(0));
+
+    exchange
+        .update_state(9, bba!(quote!(102), quote!(103)))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+
+    let executed = exchange
+        .update_state(10, bba!(quote!(104), quote!(105)))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].id(), order.id());
+    assert_eq!(exchange.num_in_flight(), 0);
+    assert_eq!(exchange.account().position().size(), base!(1));
+    assert_eq!(exchange.account().position().entry_price(), quote!(105));
+}
+
+#[test]
+fn limit_order_misses_price_level() {
+    let mut exchange = exchange(LatencyModel::Fixed(10));
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(100), base!(1)).unwrap())
+        .unwrap();
+
+    // The price moved through the limit price, so the order gets rejected on arrival.
+    exchange
+        .update_state(10, bba!(quote!(98), quote!(99)))
+        .unwrap();
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.stats().num_rejections, 1);
+}
+
+#[test]
+fn order_fills_while_cancellation_in_flight() {
+    let mut exchange = exchange(LatencyModel::Fixed(10));
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    let order = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(100), base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(5, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.cancel_order(order.id()).unwrap().id(), order.id());
+    exchange
+        .update_state(10, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+
+    // Filled before the cancellation arrives.
+    let executed = exchange
+        .update_state(12, trade!(quote!(99), base!(1), Side::Sell))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    exchange
+        .update_state(15, bba!(quote!(98), quote!(99)))
+        .unwrap();
+    assert_eq!(exchange.num_in_flight(), 0);
+    assert_eq!(exchange.account().position().size(), base!(1));
+    assert_eq!(exchange.stats().num_cancellations, 0);
+    assert_eq!(
+        exchange.cancel_order(order.id()),
+        Err(Error::OrderIdNotFound)
+    );
+}
+
+#[test]
+fn cancellation_arrives_before_fill() {
+    let mut exchange = exchange(LatencyModel::Fixed(10));
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    let order = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(100), base!(1)).unwrap())
+        .unwrap();
+    exchange.cancel_order(order.id()).unwrap();
+    exchange
+        .update_state(10, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.stats().num_cancellations, 1);
+}
+
+#[test]
+fn uniform_latency_is_deterministic() {
+    let model = LatencyModel::Uniform {
+        min_ns: 1,
+        max_ns: 100,
+    };
+    let arrivals = |model: LatencyModel| {
+        let mut exchange = exchange(model);
+        exchange
+            .update_state(0, bba!(quote!(100), quote!(101)))
+            .unwrap();
+        for _ in 0..5 {
+            exchange
+                .submit_order(Order::limit(Side::Buy, quote!(90), base!(0.1)).unwrap())
+                .unwrap();
+        }
+        Vec::from_iter((1..=100).map(|ts| {
+            exchange
+                .update_state(ts, bba!(quote!(100), quote!(101)))
+                .unwrap();
+            exchange.num_in_flight()
+        }))
+    };
+    assert_eq!(arrivals(model.clone()), arrivals(model));
+    assert_eq!(
+        Config::new(
+            quote!(1000),
+            200,
+            leverage!(1),
+            crate::mock_exchange_base()
+                .config()
+                .contract_specification()
+                .clone()
+        )
+        .unwrap()
+        .with_latency(LatencyModel::Fixed(-1), 0)
+        .unwrap_err(),
+        Error::InvalidLatencyModel
+    );
+}
@@ -0,0 +1,43 @@
+use crate::{prelude::*, trade};
+
+#[test]
+fn ledger_reconciles_wallet_balance() {
+    let mut exchange = crate::mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange.deposit(quote!(500)).unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(110), base!(5)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(1, trade!(quote!(111), base!(5), Side::Buy))
+        .unwrap();
+    exchange.withdraw(quote!(100)).unwrap();
+
+    let ledger = exchange.account().ledger();
+    assert_eq!(ledger.total(LedgerEntryKind::Deposit), quote!(500));
+    assert_eq!(ledger.total(LedgerEntryKind::Withdrawal), quote!(-100));
+    assert_eq!(ledger.total(LedgerEntryKind::TakerFee), quote!(-0.303));
+    assert_eq!(ledger.total(LedgerEntryKind::MakerFee), quote!(-0.11));
+    assert_eq!(ledger.total(LedgerEntryKind::RealizedPnl), quote!(45));
+    let kinds = Vec::from_iter(ledger.entries().iter().map(|entry| entry.kind));
+    assert_eq!(
+        kinds,
+        vec![
+            LedgerEntryKind::Deposit,
+            LedgerEntryKind::TakerFee,
+            LedgerEntryKind::MakerFee,
+            LedgerEntryKind::RealizedPnl,
+            LedgerEntryKind::Withdrawal,
+        ]
+    );
+    assert_eq!(ledger.entries()[3].ts_ns, 1);
+
+    let wallet_balance = exchange.account().wallet_balance();
+    assert_eq!(quote!(1000) + ledger.net_change(), wallet_balance);
+    assert_eq!(ledger.entries().last().unwrap().balance, wallet_balance);
+}
@@ -0,0 +1,58 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn lockstep() -> Lockstep<BaseCurrency, BaseCurrency<f64>> {
+    let decimal_spec = ContractSpecification {
+        ticker: "TESTUSD".to_string(),
+        initial_margin: Dec!(0.1),
+        maintenance_margin: Dec!(0.02),
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter::default(),
+        quantity_filter: QuantityFilter::default(),
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+    };
+    let float_spec = ContractSpecification {
+        ticker: "TESTUSD".to_string(),
+        initial_margin: 0.1,
+        maintenance_margin: 0.02,
+        mark_method: MarkMethod::MidPrice,
+        price_filter: PriceFilter::default(),
+        quantity_filter: QuantityFilter::default(),
+        notional_filter: NotionalFilter::default(),
+        max_num_orders_filter: MaxNumOrdersFilter::default(),
+        fee_schedule: FeeSchedule::flat(Fee::new(0.0002), Fee::new(0.0006)),
+    };
+    let decimal = Config::new(quote!(1000), 200, leverage!(10), decimal_spec).unwrap();
+    let float = Config::new(QuoteCurrency::new(1000.0), 200, leverage!(10), float_spec).unwrap();
+    Lockstep::new(
+        Exchange::new(NoAccountTracker, decimal),
+        Exchange::new(NoAccountTracker, float),
+    )
+}
+
+#[test]
+fn lockstep_first_divergence() {
+    let mut lockstep = lockstep();
+    lockstep.update_state(0, bba!(quote!(100), quote!(101)));
+    lockstep.submit_order(Order::market(Side::Buy, base!(1)).unwrap());
+    lockstep.update_state(1, bba!(quote!(103), quote!(104)));
+    lockstep.submit_order(Order::market(Side::Sell, base!(1)).unwrap());
+    for i in 0..3 {
+        lockstep.update_state(2 + i, bba!(quote!(107), quote!(108)));
+        lockstep.submit_order(Order::market(Side::Buy, base!(3)).unwrap());
+        lockstep.submit_order(Order::market(Side::Sell, base!(3)).unwrap());
+    }
+    assert_eq!(lockstep.divergences().len(), 13);
+    assert!(lockstep.divergences().iter().all(|d| !d.outcome_differs));
+
+    // The fees of the round trip at step 6 are the first values which `f64` can not represent exactly.
+    let first = lockstep.first_divergence(0.0).unwrap();
+    assert_eq!(first.step, 6);
+    assert_eq!(first.timestamp_ns, 2);
+    assert!(first.wallet_balance > 0.0);
+    assert_eq!(first.position_size, 0.0);
+    // The rounding errors accumulate with every round trip.
+    assert!(lockstep.divergences()[12].wallet_balance > first.wallet_balance);
+    assert_eq!(lockstep.first_divergence(1e-9), None);
+}
@@ -0,0 +1,61 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*, trade};
+
+fn exchange_with_maker_program() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let maker_program = MakerProgram::new(
+        1_000,
+        vec![MakerProgramTier {
+            min_maker_share: Dec!(0.5),
+            fee_maker: fee!(0.0),
+            fee_taker: fee!(0.0004),
+        }],
+    )
+    .unwrap();
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.01),
+            fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+            ..crate::mock_contract_specification_base()
+        })
+        .maker_program(maker_program)
+        .build()
+        .unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+#[test]
+fn maker_program_improves_fees_for_next_period() {
+    let mut exchange = exchange_with_maker_program();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.maker_program_tier(), None);
+    assert_eq!(exchange.fee_maker(), fee!(0.0002));
+
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(100), base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(1, trade!(quote!(99), base!(1), Side::Sell))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(999.98));
+
+    // The period ended with a maker share of 100%.
+    exchange
+        .update_state(1_000, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.fee_maker(), fee!(0.0));
+    assert_eq!(exchange.fee_taker(), fee!(0.0004));
+
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(999.94));
+
+    // Only taker volume during this period, so the tier is lost again.
+    exchange
+        .update_state(2_000, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.maker_program_tier(), None);
+    assert_eq!(exchange.fee_taker(), fee!(0.0006));
+}
@@ -0,0 +1,51 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*, trade};
+
+#[test]
+fn maker_rebates() {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.01),
+            fee_schedule: FeeSchedule::flat(fee!(-0.00025), fee!(0.0006)),
+            ..crate::mock_contract_specification_base()
+        })
+        .build()
+        .unwrap();
+    let mut exchange =
+        Exchange::<_, BaseCurrency>::new(FullAccountTracker::new(quote!(1000)), config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    // The rebate does not reduce the order margin, as it is only credited once filled.
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(100), base!(10)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().order_margin(), quote!(1000));
+    assert_eq!(exchange.account().available_balance(), quote!(0));
+
+    exchange
+        .update_state(1, trade!(quote!(99), base!(10), Side::Sell))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(10));
+    assert_eq!(exchange.account().wallet_balance(), quote!(1000.25));
+    assert_eq!(exchange.account().available_balance(), quote!(0.25));
+
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(102), base!(10)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(2, trade!(quote!(103), base!(10), Side::Buy))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(
+        exchange.account().wallet_balance(),
+        quote!(1000.25) + quote!(20) + quote!(0.255)
+    );
+
+    let tracker = exchange.account_tracker();
+    assert_eq!(tracker.cumulative_fees(), quote!(0));
+    assert_eq!(tracker.cumulative_rebates(), quote!(0.505));
+    assert_eq!(tracker.net_fees(), quote!(-0.505));
+    assert_eq!(tracker.total_rpnl(), quote!(20.255));
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::{account_tracker::NoAccountTracker, prelude::*, trade};
+
+fn exchange(
+    mark_price_source: Arc<dyn MarkPriceSource>,
+) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .mark_price_source(mark_price_source)
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(90)).unwrap())
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn mid_price_marks_by_default() {
+    let mut exchange = exchange(Arc::new(MidPrice));
+    exchange
+        .update_state(1, bba!(quote!(95), quote!(97)))
+        .unwrap();
+    assert_eq!(exchange.market_state().mark_price(), quote!(96));
+    assert_eq!(exchange.account().position().size(), base!(90));
+}
+
+#[test]
+fn index_series_drives_liquidations() {
+    let index = IndexSeries::new(vec![(1, quote!(90))]).unwrap();
+    let mut exchange = exchange(Arc::new(index));
+    assert_eq!(exchange.market_state().mark_price(), quote!(100.5));
+
+    // The book is unchanged, but the index crashes below the liquidation price.
+    assert_eq!(
+        exchange.update_state(1, bba!(quote!(100), quote!(101))),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.stats().num_liquidations, 1);
+    exchange.assert_invariants();
+}
+
+#[test]
+fn median_of_trades_ignores_outlier_prints() {
+    let mut exchange = exchange(Arc::new(MedianOfTrades::new(3).unwrap()));
+    for (ts, price) in [(1, quote!(100)), (2, quote!(100)), (3, quote!(80))] {
+        exchange
+            .update_state(ts, trade!(price, base!(1), Side::Sell))
+            .unwrap();
+    }
+    assert_eq!(exchange.market_state().mark_price(), quote!(100));
+    assert_eq!(exchange.account().position().size(), base!(90));
+    exchange.assert_invariants();
+}
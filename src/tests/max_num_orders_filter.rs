@@ -0,0 +1,56 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+#[test]
+fn max_num_orders_filter_limits_resting_orders() {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            maintenance_margin: Dec!(0.05),
+            max_num_orders_filter: MaxNumOrdersFilter {
+                max_num_orders: 3,
+                max_num_orders_per_side: 2,
+            },
+            fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+            ..crate::mock_contract_specification_base()
+        })
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::<NoAccountTracker, BaseCurrency>::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    for price in [quote!(98), quote!(99)] {
+        exchange
+            .submit_order(Order::limit(Side::Buy, price, base!(1)).unwrap())
+            .unwrap();
+    }
+    assert_eq!(
+        exchange.submit_order(Order::limit(Side::Buy, quote!(97), base!(1)).unwrap()),
+        Err(Error::OrderError(OrderError::MaxNumOrdersPerSide))
+    );
+    // Market orders don't rest, so they are not limited.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(102), base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.submit_order(Order::limit(Side::Sell, quote!(103), base!(1)).unwrap()),
+        Err(Error::OrderError(OrderError::MaxNumOrders))
+    );
+
+    // Cancelling an order frees up a slot.
+    let order_id = *exchange
+        .account()
+        .active_limit_orders()
+        .keys()
+        .next()
+        .unwrap();
+    exchange.cancel_order(order_id).unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(103), base!(1)).unwrap())
+        .unwrap();
+}
@@ -0,0 +1,115 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+fn mock_exchange_max_risk() -> Exchange<FullAccountTracker<QuoteCurrency>, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+            ..crate::mock_contract_specification_base()
+        })
+        .max_risk_per_trade(Dec!(0.02))
+        .build()
+        .unwrap();
+    Exchange::new(FullAccountTracker::new(quote!(1000)), config)
+}
+
+#[test]
+fn max_risk_per_trade_invalid_config() {
+    let config = mock_exchange_max_risk().config().clone();
+    assert_eq!(
+        config.clone().with_max_risk_per_trade(Dec!(0)).err(),
+        Some(Error::InvalidMaxRiskPerTrade)
+    );
+    assert_eq!(
+        config.with_max_risk_per_trade(Dec!(1.1)).err(),
+        Some(Error::InvalidMaxRiskPerTrade)
+    );
+}
+
+#[test]
+fn max_risk_per_trade_market_order() {
+    let mut exchange = mock_exchange_max_risk();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    // Without a stop, the whole margin of 100 is at risk.
+    let order = Order::market(Side::Buy, base!(1)).unwrap();
+    assert_eq!(
+        exchange.submit_order(order),
+        Err(Error::RiskError(RiskError::MaxRiskPerTradeExceeded))
+    );
+    assert_eq!(exchange.account_tracker().num_risk_violations(), 1);
+
+    // A stop too far away.
+    let mut order = Order::market(Side::Buy, base!(1)).unwrap();
+    order.set_protective_stop(quote!(75));
+    assert_eq!(
+        exchange.submit_order(order),
+        Err(Error::RiskError(RiskError::MaxRiskPerTradeExceeded))
+    );
+    assert_eq!(exchange.account_tracker().num_risk_violations(), 2);
+
+    // Risking 15 out of 1000 is fine.
+    let mut order = Order::market(Side::Buy, base!(1)).unwrap();
+    order.set_protective_stop(quote!(85));
+    exchange.submit_order(order).unwrap();
+    assert_eq!(exchange.account().position().size(), base!(1));
+    assert_eq!(exchange.account_tracker().num_risk_violations(), 2);
+
+    // Reducing the position adds no risk.
+    let order = Order::market(Side::Sell, base!(1)).unwrap();
+    exchange.submit_order(order).unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+}
+
+#[test]
+fn max_risk_per_trade_limit_order() {
+    let mut exchange = mock_exchange_max_risk();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let order = Order::limit(Side::Sell, quote!(110), base!(1)).unwrap();
+    assert_eq!(
+        exchange.submit_order(order),
+        Err(Error::RiskError(RiskError::MaxRiskPerTradeExceeded))
+    );
+
+    let mut order = Order::limit(Side::Sell, quote!(110), base!(1)).unwrap();
+    order.set_protective_stop(quote!(120));
+    exchange.submit_order(order).unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+    assert_eq!(exchange.account_tracker().num_risk_violations(), 1);
+}
+
+#[test]
+fn max_risk_per_trade_fraction_of_equity() {
+    let mut exchange = mock_exchange_max_risk();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    let mut order = Order::market(Side::Buy, base!(1)).unwrap();
+    order.set_protective_stop(quote!(85));
+    exchange.submit_order(order).unwrap();
+
+    // The unrealized profit of 99.5 at the mark price raises the limit above 2% of the wallet balance.
+    exchange
+        .update_state(1, bba!(quote!(199), quote!(200)))
+        .unwrap();
+    let mut order = Order::market(Side::Buy, base!(1)).unwrap();
+    order.set_protective_stop(quote!(179));
+    exchange.submit_order(order).unwrap();
+    assert_eq!(exchange.account().position().size(), base!(2));
+
+    // The unrealized loss at the mark price lowers the limit below 2% of the wallet balance.
+    exchange
+        .update_state(2, bba!(quote!(140), quote!(141)))
+        .unwrap();
+    let mut order = Order::market(Side::Buy, base!(1)).unwrap();
+    order.set_protective_stop(quote!(121.2));
+    assert_eq!(
+        exchange.submit_order(order),
+        Err(Error::RiskError(RiskError::MaxRiskPerTradeExceeded))
+    );
+}
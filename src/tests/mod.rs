@@ -1,4 +1,75 @@
+mod bankruptcy;
+mod basket_orders;
+mod borrow_cost;
+mod borrow_pool;
+mod candle_path;
+mod cash_flows;
+#[cfg(feature = "serde")]
+mod checkpoint;
+mod circuit_breaker;
+mod collateral;
+mod composite_trackers;
+mod cross_exchange;
+mod custom_risk_engine;
+mod dated_futures;
+mod direction_stats;
+mod downtime;
+mod dust_handling;
+#[cfg(feature = "tokio")]
+mod exchange_actor;
+mod exchange_events;
+mod exchange_stats;
+mod f64_backend;
+mod fault_injection;
+mod fee_schedule;
+mod fee_token;
+mod fills;
+#[cfg(feature = "html_report")]
+mod html_report;
+mod iceberg_orders;
+mod idle_margin_interest;
+mod immediate_liquidation;
+mod index_price;
+mod invariants;
+mod journal;
+mod latency;
+mod ledger;
+mod lockstep;
+mod maker_program;
+mod maker_rebates;
+mod mark_price;
+mod max_num_orders_filter;
+mod max_risk_per_trade;
+mod notional_filter;
+mod oco_orders;
+mod options;
+mod order_book_depth;
+mod order_margin_model;
+mod order_status;
+mod performance_report;
+mod pnl_attribution;
+mod position_flip;
+mod position_history;
+mod position_limit;
+mod position_margin;
+mod position_mode;
+mod position_tpsl;
+mod price_improvement;
+mod price_time_priority;
+mod rate_limit;
+mod self_trade_prevention;
+mod session_calendar;
+mod set_leverage;
+mod slippage;
+mod stop_orders;
+mod strategy;
+mod sub_accounts;
 mod submit_limit_buy_order;
 mod submit_limit_sell_order;
 mod submit_market_buy_order;
 mod submit_market_sell_order;
+mod time_in_force;
+mod trailing_take_profit;
+mod update_state_batch;
+mod update_state_into;
+mod user_order_id;
@@ -0,0 +1,44 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+#[test]
+fn notional_filter_rejects_orders() {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            maintenance_margin: Dec!(0.05),
+            quantity_filter: QuantityFilter {
+                min_quantity: base!(0),
+                max_quantity: base!(0),
+                max_market_quantity: base!(0),
+                step_size: base!(0.01),
+            },
+            notional_filter: NotionalFilter {
+                min_notional: quote!(10),
+                max_notional: quote!(500),
+            },
+            fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+            ..crate::mock_contract_specification_base()
+        })
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::<NoAccountTracker, BaseCurrency>::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(0.09)).unwrap()),
+        Err(Error::OrderError(OrderError::NotionalTooLow))
+    );
+    assert_eq!(
+        exchange.submit_order(Order::limit(Side::Buy, quote!(99), base!(6)).unwrap()),
+        Err(Error::OrderError(OrderError::NotionalTooHigh))
+    );
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(5)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(0.1)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+}
@@ -0,0 +1,127 @@
+use crate::{account_tracker::NoAccountTracker, candle, mock_exchange_base, prelude::*, trade};
+
+#[test]
+fn oco_orders_execution_cancels_sibling() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let (buy, sell) = exchange
+        .submit_oco_orders(
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(105), base!(1)).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(buy.group_id(), Some(buy.id()));
+    assert_eq!(sell.group_id(), Some(buy.id()));
+    assert_eq!(exchange.account().active_limit_orders().len(), 2);
+
+    let executed = exchange
+        .update_state(1, trade!(quote!(105), base!(1), Side::Buy))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].id(), sell.id());
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert!(exchange.account().order_groups.is_empty());
+    assert_eq!(exchange.account().order_margin(), quote!(0));
+    assert_eq!(exchange.account().position().size(), base!(-1));
+}
+
+#[test]
+fn oco_orders_both_triggered_by_candle() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let (buy, _sell) = exchange
+        .submit_oco_orders(
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(105), base!(1)).unwrap(),
+        )
+        .unwrap();
+
+    // Only the oldest order gets executed.
+    let executed = exchange
+        .update_state(1, candle!(quote!(99), quote!(100), quote!(90), quote!(110)))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].id(), buy.id());
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account().position().size(), base!(1));
+}
+
+#[test]
+fn oco_orders_cancel_cancels_sibling() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let (buy, _sell) = exchange
+        .submit_oco_orders(
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(105), base!(1)).unwrap(),
+        )
+        .unwrap();
+    exchange.cancel_order(buy.id()).unwrap();
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert!(exchange.account().order_groups.is_empty());
+}
+
+#[test]
+fn oco_orders_all_or_nothing() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    assert_eq!(
+        exchange.submit_oco_orders(
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::market(Side::Sell, base!(1)).unwrap(),
+        ),
+        Err(Error::OrderError(OrderError::InvalidOcoOrder))
+    );
+    // The second order is rejected due to its limit price.
+    assert_eq!(
+        exchange.submit_oco_orders(
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(99), base!(1)).unwrap(),
+        ),
+        Err(Error::OrderError(OrderError::LimitPriceBelowBid))
+    );
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert!(exchange.account().order_groups.is_empty());
+    // The first order was never submitted.
+    assert_eq!(exchange.order_status(0), None);
+    assert_eq!(exchange.stats().num_rejections, 2);
+    assert_eq!(exchange.stats().num_cancellations, 0);
+}
+
+#[test]
+fn oco_orders_all_or_nothing_with_latency() {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_latency(LatencyModel::Fixed(10), 0)
+        .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    assert_eq!(
+        exchange.submit_oco_orders(
+            Order::limit(Side::Buy, quote!(95), base!(1)).unwrap(),
+            Order::limit(Side::Sell, quote!(99), base!(1)).unwrap(),
+        ),
+        Err(Error::OrderError(OrderError::LimitPriceBelowBid))
+    );
+    assert_eq!(exchange.num_in_flight(), 0);
+    exchange
+        .update_state(20, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    assert!(exchange.account().active_limit_orders().is_empty());
+}
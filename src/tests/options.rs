@@ -0,0 +1,95 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+fn call(expiry_ts_ns: i64) -> OptionContract {
+    OptionContract {
+        kind: OptionKind::Call,
+        strike: quote!(100),
+        expiry_ts_ns,
+    }
+}
+
+#[test]
+fn long_option_exercised_at_expiry() {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(1),
+            maintenance_margin: Dec!(0.5),
+            fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+            ..crate::mock_contract_specification_base()
+        })
+        .build()
+        .unwrap();
+    let mut exchange =
+        Exchange::<_, BaseCurrency>::new(FullAccountTracker::new(quote!(1000)), config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    exchange
+        .trade_option(call(10), Side::Buy, base!(2), quote!(6))
+        .unwrap();
+    assert_eq!(exchange.account().wallet_balance(), quote!(994));
+    assert_eq!(exchange.account().option_positions().len(), 1);
+    assert_eq!(exchange.account().option_margin(), quote!(0));
+
+    // Exercised against the mid price of 110.5.
+    exchange
+        .update_state(10, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    assert!(exchange.account().option_positions().is_empty());
+    assert_eq!(exchange.account().wallet_balance(), quote!(1015));
+    assert_eq!(exchange.account_tracker().total_rpnl(), quote!(15));
+}
+
+#[test]
+fn short_option_reserves_margin() {
+    let mut exchange = crate::mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    // The intrinsic value plus one percent of the notional value of the underlying.
+    let position = exchange
+        .trade_option(call(10), Side::Sell, base!(1), quote!(5))
+        .unwrap();
+    assert_eq!(position.margin, quote!(1.505));
+    assert_eq!(exchange.account().wallet_balance(), quote!(1005));
+    assert_eq!(exchange.account().available_balance(), quote!(1003.495));
+    assert_eq!(
+        exchange.trade_option(call(10), Side::Sell, base!(1000), quote!(0)),
+        Err(RiskError::NotEnoughAvailableBalance.into())
+    );
+
+    // Expires worthless.
+    exchange
+        .update_state(10, bba!(quote!(90), quote!(91)))
+        .unwrap();
+    assert!(exchange.account().option_positions().is_empty());
+    assert_eq!(exchange.account().wallet_balance(), quote!(1005));
+    assert_eq!(exchange.account().available_balance(), quote!(1005));
+}
+
+#[test]
+fn trade_option_invalid() {
+    let mut exchange = crate::mock_exchange_base();
+    exchange
+        .update_state(5, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(
+        exchange.trade_option(call(5), Side::Buy, base!(1), quote!(1)),
+        Err(Error::OptionExpired)
+    );
+    assert_eq!(
+        exchange.trade_option(call(10), Side::Buy, base!(0), quote!(1)),
+        Err(Error::OrderError(OrderError::OrderSizeMustBePositive))
+    );
+    assert_eq!(
+        exchange.trade_option(call(10), Side::Buy, base!(1), quote!(-1)),
+        Err(Error::InvalidAmount)
+    );
+    assert_eq!(
+        exchange.trade_option(call(10), Side::Buy, base!(1), quote!(1001)),
+        Err(RiskError::NotEnoughAvailableBalance.into())
+    );
+}
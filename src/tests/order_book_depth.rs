@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use crate::{account_tracker::FullAccountTracker, depth, prelude::*};
+
+fn exchange() -> Exchange<FullAccountTracker<QuoteCurrency>, BaseCurrency> {
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_slippage_model(Arc::new(WalkTheBook {
+            beyond_depth: DepthSlippage {
+                quantity_per_level: Dec!(1),
+                level_spacing: quote!(1),
+            },
+        }));
+    Exchange::new(FullAccountTracker::new(quote!(1000)), config)
+}
+
+fn book() -> MarketUpdate<BaseCurrency> {
+    depth!(
+        vec![(quote!(100), base!(2)), (quote!(99), base!(3))],
+        vec![(quote!(101), base!(3)), (quote!(102), base!(2))]
+    )
+}
+
+#[test]
+fn depth_microstructure_features() {
+    let mut exchange = exchange();
+    assert_eq!(exchange.market_state().microprice(), None);
+    exchange.update_state(0, book()).unwrap();
+
+    let market_state = exchange.market_state();
+    assert_eq!(market_state.bid(), quote!(100));
+    assert_eq!(market_state.ask(), quote!(101));
+    assert_eq!(market_state.ask_depth().len(), 2);
+    assert_eq!(market_state.book_imbalance(1), Some(Dec!(-0.2)));
+    assert_eq!(market_state.book_imbalance(2), Some(Dec!(0)));
+    assert_eq!(market_state.book_imbalance(0), None);
+    assert_eq!(market_state.microprice(), Some(quote!(100.4)));
+
+    // Updates of the best bid and ask without depth make the levels stale.
+    exchange
+        .update_state(1, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(exchange.market_state().bid_depth().is_empty());
+    assert_eq!(exchange.market_state().book_imbalance(1), None);
+}
+
+#[test]
+fn depth_invalid() {
+    let mut exchange = exchange();
+    assert_eq!(
+        exchange.update_state(0, depth!(vec![], vec![(quote!(101), base!(1))])),
+        Err(Error::InvalidMarketUpdateDepth)
+    );
+    assert_eq!(
+        exchange.update_state(
+            0,
+            depth!(
+                vec![(quote!(99), base!(1)), (quote!(100), base!(1))],
+                vec![(quote!(101), base!(1))]
+            )
+        ),
+        Err(Error::InvalidMarketUpdateDepth)
+    );
+    assert_eq!(
+        exchange.update_state(
+            0,
+            depth!(vec![(quote!(100), base!(0))], vec![(quote!(101), base!(1))])
+        ),
+        Err(Error::InvalidMarketUpdateDepth)
+    );
+    assert_eq!(
+        exchange.update_state(
+            0,
+            depth!(vec![(quote!(101), base!(1))], vec![(quote!(101), base!(1))])
+        ),
+        Err(Error::InvalidMarketUpdateBidAskSpread)
+    );
+}
+
+#[test]
+fn market_orders_walk_the_book() {
+    let mut exchange = exchange();
+    exchange.update_state(0, book()).unwrap();
+
+    // 3 @ 101, 1 @ 102
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(4)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().entry_price(), quote!(101.25));
+    assert_eq!(exchange.account_tracker().cumulative_slippage(), quote!(1));
+
+    // 2 @ 100, 2 @ 99
+    exchange.update_state(1, book()).unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(4)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.account_tracker().cumulative_slippage(), quote!(3));
+}
+
+#[test]
+fn market_orders_beyond_the_book() {
+    let mut exchange = exchange();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    // Without depth the levels are extrapolated from the best ask: 1 @ 101, 1 @ 102
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().entry_price(), quote!(101.5));
+
+    // 2 @ 100 and 3 @ 99 observed, then 1 @ 98, 1 @ 97 and 1 @ 96 extrapolated.
+    exchange.update_state(1, book()).unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(8)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(-6));
+    assert_eq!(exchange.account().position().entry_price(), quote!(98.5));
+    assert_eq!(exchange.account_tracker().cumulative_slippage(), quote!(13));
+}
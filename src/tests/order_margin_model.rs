@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*};
+
+fn submit_both_sides(config: Config<QuoteCurrency>) -> Result<Order<BaseCurrency>> {
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(90), base!(6)).unwrap())
+        .unwrap();
+    exchange.submit_order(Order::limit(Side::Sell, quote!(110), base!(5)).unwrap())
+}
+
+#[test]
+fn order_margin_model_max_of_sides() {
+    let config = mock_exchange_base().config().clone();
+    assert!(submit_both_sides(config).is_ok());
+}
+
+#[test]
+fn order_margin_model_sum_of_sides() {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_order_margin_model(Arc::new(SumOfSides));
+    // Both sides together require 540 + 550 plus fees, which exceeds the balance of 1000.
+    assert_eq!(
+        submit_both_sides(config),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+}
@@ -0,0 +1,135 @@
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*, trade};
+
+#[test]
+fn order_status_filled() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(5, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let order = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(100), base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(order.status(), OrderStatus::New);
+    assert_eq!(order.created_ts_ns(), 5);
+    assert_eq!(order.updated_ts_ns(), 5);
+    assert_eq!(order.filled_ts_ns(), None);
+    assert_eq!(exchange.order_status(order.id()), Some(OrderStatus::New));
+
+    let executed = exchange
+        .update_state(8, trade!(quote!(99), base!(2), Side::Sell))
+        .unwrap();
+    assert_eq!(executed[0].status(), OrderStatus::Filled);
+    assert_eq!(executed[0].created_ts_ns(), 5);
+    assert_eq!(executed[0].updated_ts_ns(), 8);
+    assert_eq!(executed[0].filled_ts_ns(), Some(8));
+    assert_eq!(exchange.order_status(order.id()), Some(OrderStatus::Filled));
+
+    let order = exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(order.status(), OrderStatus::Filled);
+    assert_eq!(order.filled_ts_ns(), Some(8));
+    assert_eq!(exchange.order_status(order.id()), Some(OrderStatus::Filled));
+    assert_eq!(exchange.order_status(order.id() + 1), None);
+}
+
+#[test]
+fn order_status_partially_filled() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let mut order = Order::limit(Side::Buy, quote!(100), base!(3)).unwrap();
+    order.set_display_quantity(base!(1)).unwrap();
+    let order = exchange.submit_order(order).unwrap();
+
+    exchange
+        .update_state(1, trade!(quote!(99), base!(5), Side::Sell))
+        .unwrap();
+    assert_eq!(
+        exchange.order_status(order.id()),
+        Some(OrderStatus::PartiallyFilled)
+    );
+    let active = &exchange.account().active_limit_orders()[&order.id()];
+    assert!(active.status().is_active());
+    assert_eq!(active.created_ts_ns(), 0);
+    assert_eq!(active.updated_ts_ns(), 1);
+    assert_eq!(active.filled_ts_ns(), None);
+}
+
+#[test]
+fn order_status_cancelled_and_expired() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let order = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(98), base!(1)).unwrap())
+        .unwrap();
+    let mut gtd = Order::limit(Side::Buy, quote!(97), base!(1)).unwrap();
+    gtd.set_time_in_force(TimeInForce::GoodTilDate(10));
+    let gtd = exchange.submit_order(gtd).unwrap();
+
+    exchange
+        .update_state(3, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    let cancelled = exchange.cancel_order(order.id()).unwrap();
+    assert_eq!(cancelled.status(), OrderStatus::Cancelled);
+    assert_eq!(cancelled.updated_ts_ns(), 3);
+    assert_eq!(
+        exchange.order_status(order.id()),
+        Some(OrderStatus::Cancelled)
+    );
+
+    exchange
+        .update_state(11, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    assert_eq!(exchange.order_status(gtd.id()), Some(OrderStatus::Expired));
+    assert!(!OrderStatus::Expired.is_active());
+}
+
+#[test]
+fn order_status_rejected() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(100)).unwrap()),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    let order = exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.order_status(order.id() - 1),
+        Some(OrderStatus::Rejected)
+    );
+}
+
+#[test]
+fn order_status_closed_order_capacity() {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_closed_order_capacity(2);
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let ids = Vec::from_iter((0..3).map(|_| {
+        exchange
+            .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+            .unwrap()
+            .id()
+    }));
+    // The oldest closed order is forgotten.
+    assert_eq!(exchange.order_status(ids[0]), None);
+    assert_eq!(exchange.order_status(ids[1]), Some(OrderStatus::Filled));
+    assert_eq!(exchange.order_status(ids[2]), Some(OrderStatus::Filled));
+}
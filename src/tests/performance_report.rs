@@ -0,0 +1,57 @@
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+fn exchange_with_trades() -> Exchange<FullAccountTracker<QuoteCurrency>, BaseCurrency> {
+    let mut exchange = Exchange::<_, BaseCurrency>::new(
+        FullAccountTracker::new(quote!(1000)),
+        crate::mock_exchange_base().config().clone(),
+    );
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(3_600_000_000_000, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(7_200_000_000_000, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn performance_report_aggregates_the_tracker_metrics() {
+    let exchange = exchange_with_trades();
+    let tracker = exchange.account_tracker();
+    let report = tracker.report();
+    assert_eq!(report.total_rpnl, tracker.total_rpnl());
+    assert_eq!(report.num_trades, 2);
+    assert_eq!(report.cumulative_fees, tracker.cumulative_fees());
+    assert_eq!(report.long_stats.num_trades, 1);
+    assert_eq!(report.pnl_attribution, tracker.pnl_attribution());
+    assert_eq!(report.kelly_fraction, None);
+
+    // An aligned table, where every row has the same width.
+    let table = report.to_string();
+    let widths = Vec::from_iter(table.lines().map(|line| line.chars().count()));
+    assert!(widths.len() > 2);
+    assert!(widths.iter().all(|width| *width == widths[0]));
+    assert!(table.contains("total_rpnl"));
+    assert_eq!(tracker.to_string(), table);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn performance_report_json() {
+    let exchange = exchange_with_trades();
+    let report = exchange.account_tracker().report();
+    let mut json = Vec::new();
+    report.write_json(&mut json).unwrap();
+    let parsed: crate::account_tracker::PerformanceReport<QuoteCurrency> =
+        serde_json::from_slice(&json).unwrap();
+    assert_eq!(parsed, report);
+}
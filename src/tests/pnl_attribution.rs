@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use crate::{account_tracker::FullAccountTracker, prelude::*, trade};
+
+const YEAR_NS: u64 = 365 * 86_400_000_000_000;
+
+#[test]
+fn pnl_attribution_sums_to_the_equity_change() {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(2))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.5),
+            fee_schedule: FeeSchedule::flat(fee!(-0.0001), fee!(0.001)),
+            ..crate::mock_contract_specification_base()
+        })
+        .borrow_rate(Dec!(0.1))
+        .slippage_model(Arc::new(FixedBpsSlippage { bps: Dec!(10) }))
+        .build()
+        .unwrap();
+    let mut exchange =
+        Exchange::<_, BaseCurrency>::new(FullAccountTracker::new(quote!(1000)), config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(110), base!(1)).unwrap())
+        .unwrap();
+    assert!(exchange.account_tracker().trade_attributions().is_empty());
+
+    exchange
+        .update_state(YEAR_NS / 2, bba!(quote!(109), quote!(110)))
+        .unwrap();
+    exchange
+        .update_state(YEAR_NS / 2 + 1, trade!(quote!(111), base!(1), Side::Buy))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(YEAR_NS, bba!(quote!(104), quote!(105)))
+        .unwrap();
+
+    let tracker = exchange.account_tracker();
+    let trades = tracker.trade_attributions();
+    assert_eq!(trades.len(), 2);
+    // The costs of the opening fill and the funding are attributed to the first realized trade.
+    assert_eq!(trades[0].1.fees_paid, quote!(0.202202));
+    assert_eq!(trades[0].1.fees_received, quote!(0.011));
+    assert_eq!(trades[0].1.slippage, quote!(0.202));
+    assert_eq!(trades[0].1.price_pnl, quote!(9.101));
+    // The slippage of the closing market order belongs to the second trade.
+    assert_eq!(trades[1].1.slippage, quote!(0.109));
+    assert_eq!(trades[1].1.price_pnl, quote!(7.899));
+
+    let attribution = tracker.pnl_attribution();
+    assert_eq!(attribution.fees_paid, tracker.cumulative_fees());
+    assert_eq!(attribution.fees_received, tracker.cumulative_rebates());
+    assert_eq!(attribution.funding_paid, tracker.cumulative_borrow_cost());
+    assert_eq!(attribution.slippage, tracker.cumulative_slippage());
+    assert_eq!(
+        attribution.net_pnl(),
+        exchange.account().wallet_balance() - quote!(1000)
+    );
+    assert_eq!(
+        tracker.mean_trade_attribution().unwrap().price_pnl,
+        quote!(8.5)
+    );
+}
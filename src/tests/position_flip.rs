@@ -0,0 +1,57 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn exchange(position_flip: bool) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .position_flip(position_flip)
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(1, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn position_flip_in_a_single_order() {
+    let mut exchange = exchange(true);
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(5)).unwrap())
+        .unwrap();
+
+    let position = exchange.account().position();
+    assert_eq!(position.size(), base!(-3));
+    assert_eq!(position.entry_price(), quote!(110));
+    assert_eq!(position.position_margin(), quote!(33));
+    // The long realized 2 * (110 - 101).
+    assert_eq!(exchange.account().wallet_balance(), quote!(1018));
+    exchange.assert_invariants();
+}
+
+#[test]
+fn position_flip_disabled() {
+    let mut exchange = exchange(false);
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Sell, base!(5)).unwrap()),
+        Err(Error::RiskError(RiskError::PositionFlipNotAllowed))
+    );
+    assert_eq!(
+        exchange.submit_order(Order::limit(Side::Sell, quote!(120), base!(3)).unwrap()),
+        Err(Error::RiskError(RiskError::PositionFlipNotAllowed))
+    );
+    // Closing the position and opening from flat is still possible.
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(3)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(-3));
+}
@@ -0,0 +1,89 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn exchange() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base().build().unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+#[test]
+fn position_history_audits_the_entry_price() {
+    let mut exchange = exchange();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(1, bba!(quote!(102), quote!(103)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(3)).unwrap())
+        .unwrap();
+
+    let history = exchange.account().position_history();
+    assert_eq!(
+        Vec::from_iter(history.changes().iter().map(|change| change.kind)),
+        vec![
+            PositionChangeKind::Open,
+            PositionChangeKind::Increase,
+            PositionChangeKind::Decrease,
+            PositionChangeKind::Flip,
+        ]
+    );
+    assert_eq!(
+        history.changes()[1],
+        PositionChange {
+            ts_ns: 1,
+            kind: PositionChangeKind::Increase,
+            fill_price: quote!(103),
+            size_before: base!(1),
+            size_after: base!(2),
+            entry_price_before: quote!(101),
+            entry_price_after: quote!(102),
+            position_margin_before: quote!(10.1),
+            position_margin_after: quote!(20.4),
+        }
+    );
+    // Reducing keeps the weighted average entry price.
+    assert_eq!(history.changes()[2].entry_price_after, quote!(102));
+    // Flipping reopens at the fill price.
+    let flip = &history.changes()[3];
+    assert_eq!(flip.quantity(), base!(-3));
+    assert_eq!(flip.size_after, base!(-2));
+    assert_eq!(flip.entry_price_after, quote!(102));
+    assert_eq!(flip.position_margin_after, quote!(20.4));
+}
+
+#[test]
+fn position_history_liquidation() {
+    let mut exchange = exchange();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(5)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.update_state(1, bba!(quote!(150), quote!(151))),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+
+    let history = exchange.account().position_history();
+    assert_eq!(history.changes().len(), 2);
+    let liquidation = &history.changes()[1];
+    assert_eq!(liquidation.kind, PositionChangeKind::Liquidation);
+    assert_eq!(liquidation.fill_price, quote!(151));
+    assert_eq!(liquidation.size_before, base!(-5));
+    assert_eq!(liquidation.size_after, base!(0));
+    assert_eq!(
+        history.changes_of(PositionChangeKind::Liquidation).count(),
+        1
+    );
+}
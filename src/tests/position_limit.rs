@@ -0,0 +1,93 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn mock_exchange_position_limit(
+    position_mode: PositionMode,
+) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let mut config = crate::mock_exchange_base().config().clone();
+    config = config
+        .with_position_mode(position_mode)
+        .with_position_limit(PositionLimit::new(Some(quote!(500)), vec![]).unwrap());
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn position_limit_netting() {
+    let mut exchange = mock_exchange_position_limit(PositionMode::Netting);
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(4)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(1)).unwrap()),
+        Err(Error::RiskError(RiskError::MaxPositionNotionalExceeded))
+    );
+    assert_eq!(
+        exchange.submit_order(Order::limit(Side::Buy, quote!(99), base!(1.1)).unwrap()),
+        Err(Error::RiskError(RiskError::MaxPositionNotionalExceeded))
+    );
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+        .unwrap();
+
+    // Reducing the position is always possible, but flipping it beyond the limit is not.
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Sell, base!(10)).unwrap()),
+        Err(Error::RiskError(RiskError::MaxPositionNotionalExceeded))
+    );
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(8)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(-4));
+}
+
+#[test]
+fn position_limit_hedge() {
+    let mut exchange = mock_exchange_position_limit(PositionMode::Hedge);
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(3)).unwrap())
+        .unwrap();
+    // The short position has its own limit.
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(4.9)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Sell, base!(0.2)).unwrap()),
+        Err(Error::RiskError(RiskError::MaxPositionNotionalExceeded))
+    );
+}
+
+#[test]
+fn position_limit_leverage_brackets() {
+    let brackets = vec![
+        LeverageBracket {
+            max_notional: quote!(200),
+            max_leverage: leverage!(5),
+        },
+        LeverageBracket {
+            max_notional: quote!(500),
+            max_leverage: leverage!(1),
+        },
+    ];
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_position_limit(PositionLimit::new(None, brackets).unwrap());
+    let mut exchange = Exchange::<NoAccountTracker, BaseCurrency>::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    // The initial leverage of 1 falls into the larger bracket.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(4.9)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(0.2)).unwrap()),
+        Err(Error::RiskError(RiskError::MaxPositionNotionalExceeded))
+    );
+}
@@ -0,0 +1,120 @@
+use crate::{prelude::*, risk_engine::RiskError};
+
+fn mock_exchange_long() -> Exchange<crate::account_tracker::NoAccountTracker, BaseCurrency> {
+    let mut exchange = crate::mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange.set_leverage(leverage!(5)).unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().position_margin(), quote!(101));
+    exchange
+}
+
+#[test]
+fn deposit_and_withdraw_position_margin() {
+    let mut exchange = crate::mock_exchange_base();
+    assert_eq!(
+        exchange.deposit_position_margin(Side::Buy, quote!(10)),
+        Err(Error::NoOpenPosition)
+    );
+
+    let mut exchange = mock_exchange_long();
+    let available_balance = exchange.account().available_balance();
+    assert_eq!(
+        exchange.deposit_position_margin(Side::Buy, quote!(0)),
+        Err(Error::InvalidAmount)
+    );
+    assert_eq!(
+        exchange.deposit_position_margin(Side::Buy, quote!(1000)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    assert_eq!(
+        exchange.withdraw_position_margin(Side::Buy, quote!(1)),
+        Err(Error::NotEnoughPositionMargin)
+    );
+
+    exchange
+        .deposit_position_margin(Side::Buy, quote!(100))
+        .unwrap();
+    assert_eq!(exchange.account().position().position_margin(), quote!(201));
+    assert_eq!(
+        exchange.account().position().variation_margin(),
+        quote!(100)
+    );
+    assert_eq!(
+        exchange.account().available_balance(),
+        available_balance - quote!(100)
+    );
+
+    // Reducing the position releases the variation margin proportionally.
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(2.5)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.account().position().position_margin(),
+        quote!(100.5)
+    );
+    assert_eq!(
+        exchange.withdraw_position_margin(Side::Buy, quote!(60)),
+        Err(Error::NotEnoughPositionMargin)
+    );
+    exchange
+        .withdraw_position_margin(Side::Buy, quote!(50))
+        .unwrap();
+    assert_eq!(
+        exchange.account().position().position_margin(),
+        quote!(50.5)
+    );
+    assert_eq!(exchange.account().position().variation_margin(), quote!(0));
+}
+
+#[test]
+fn position_margin_prevents_liquidation() {
+    let mut exchange = mock_exchange_long();
+    exchange
+        .deposit_position_margin(Side::Buy, quote!(100))
+        .unwrap();
+    // Without the deposit, the position would be liquidated here.
+    exchange
+        .update_state(1, bba!(quote!(80), quote!(81)))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(5));
+
+    assert_eq!(
+        exchange.withdraw_position_margin(Side::Buy, quote!(100)),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+    assert_eq!(exchange.account().position().position_margin(), quote!(201));
+    exchange
+        .withdraw_position_margin(Side::Buy, quote!(50))
+        .unwrap();
+}
+
+#[test]
+fn position_margin_hedge_mode() {
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_position_mode(PositionMode::Hedge);
+    let mut exchange = Exchange::new(crate::account_tracker::NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.deposit_position_margin(Side::Buy, quote!(10)),
+        Err(Error::NoOpenPosition)
+    );
+    exchange
+        .deposit_position_margin(Side::Sell, quote!(10))
+        .unwrap();
+    assert_eq!(
+        exchange.account().short_position().position_margin(),
+        quote!(210)
+    );
+}
@@ -0,0 +1,161 @@
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*, trade};
+
+fn mock_exchange_hedge() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .initial_leverage(leverage!(1))
+        .contract_specification(ContractSpecification {
+            initial_margin: Dec!(0.01),
+            fee_schedule: FeeSchedule::flat(fee!(0.0002), fee!(0.0006)),
+            ..crate::mock_contract_specification_base()
+        })
+        .position_mode(PositionMode::Hedge)
+        .build()
+        .unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+fn reduce_only(mut order: Order<BaseCurrency>) -> Order<BaseCurrency> {
+    order.set_reduce_only(true);
+    order
+}
+
+#[test]
+fn netting_flip_through_zero() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(5)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.account().position,
+        Position {
+            size: base!(-3),
+            entry_price: quote!(100),
+            position_margin: quote!(300),
+            leverage: leverage!(1),
+        }
+    );
+    assert_eq!(exchange.account().short_position().size(), base!(0));
+    assert_eq!(
+        exchange.account().wallet_balance(),
+        quote!(1000) - quote!(0.1212) - quote!(0.3) - quote!(2)
+    );
+
+    // Reduce-only orders must not increase or flip the position.
+    assert_eq!(
+        exchange.submit_order(reduce_only(Order::market(Side::Sell, base!(1)).unwrap())),
+        Err(Error::RiskError(RiskError::ReduceOnlyExceedsPosition))
+    );
+    assert_eq!(
+        exchange.submit_order(reduce_only(Order::market(Side::Buy, base!(4)).unwrap())),
+        Err(Error::RiskError(RiskError::ReduceOnlyExceedsPosition))
+    );
+    exchange
+        .submit_order(reduce_only(Order::market(Side::Buy, base!(3)).unwrap()))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.account().position().position_margin(), quote!(0));
+}
+
+#[test]
+fn netting_reduce_only_limit_order_cancelled_when_position_reduced() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(reduce_only(
+            Order::limit(Side::Sell, quote!(105), base!(2)).unwrap(),
+        ))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+
+    let executed = exchange
+        .update_state(1, trade!(quote!(106), base!(2), Side::Buy))
+        .unwrap();
+    assert!(executed.is_empty());
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account().position().size(), base!(1));
+}
+
+#[test]
+fn hedge_no_flip_through_zero() {
+    let mut exchange = mock_exchange_hedge();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(5)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.account().position,
+        Position {
+            size: base!(2),
+            entry_price: quote!(101),
+            position_margin: quote!(202),
+            leverage: leverage!(1),
+        }
+    );
+    assert_eq!(
+        exchange.account().short_position,
+        Position {
+            size: base!(-5),
+            entry_price: quote!(100),
+            position_margin: quote!(500),
+            leverage: leverage!(1),
+        }
+    );
+    let wallet_balance = quote!(1000) - quote!(0.1212) - quote!(0.3);
+    assert_eq!(exchange.account().wallet_balance(), wallet_balance);
+    assert_eq!(exchange.account().position_margin(), quote!(702));
+    assert_eq!(
+        exchange.account().available_balance(),
+        wallet_balance - quote!(702)
+    );
+    assert_eq!(
+        exchange.account().unrealized_pnl(quote!(100), quote!(101)),
+        quote!(-7)
+    );
+
+    // Close the long without touching the short.
+    assert_eq!(
+        exchange.submit_order(reduce_only(Order::market(Side::Sell, base!(3)).unwrap())),
+        Err(Error::RiskError(RiskError::ReduceOnlyExceedsPosition))
+    );
+    exchange
+        .submit_order(reduce_only(Order::market(Side::Sell, base!(2)).unwrap()))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.account().short_position().size(), base!(-5));
+    assert_eq!(
+        exchange.account().wallet_balance(),
+        wallet_balance - quote!(0.12) - quote!(2)
+    );
+
+    // Reduce the short with a resting reduce-only buy order.
+    exchange
+        .submit_order(reduce_only(
+            Order::limit(Side::Buy, quote!(95), base!(5)).unwrap(),
+        ))
+        .unwrap();
+    exchange
+        .update_state(1, trade!(quote!(94), base!(5), Side::Sell))
+        .unwrap();
+    assert_eq!(exchange.account().short_position().size(), base!(0));
+    assert_eq!(exchange.account().position().size(), base!(0));
+}
@@ -0,0 +1,105 @@
+use crate::{account_tracker::NoAccountTracker, candle, prelude::*};
+
+fn exchange() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base().build().unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn position_tpsl_take_profit_closes_whole_position() {
+    let mut exchange = exchange();
+    assert_eq!(
+        exchange.set_position_tpsl(Some(quote!(110)), None),
+        Err(Error::InvalidPositionTpSl)
+    );
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    // The take-profit of a long position must be above the mark price.
+    assert_eq!(
+        exchange.set_position_tpsl(Some(quote!(100)), Some(quote!(95))),
+        Err(Error::InvalidPositionTpSl)
+    );
+    exchange
+        .set_position_tpsl(Some(quote!(110)), Some(quote!(95)))
+        .unwrap();
+    assert_eq!(exchange.position_tpsl().unwrap().side(), Side::Buy);
+
+    // The position grows after the take-profit was set.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    let executed = exchange
+        .update_state(1, bba!(quote!(109), quote!(110)))
+        .unwrap();
+    assert!(executed.is_empty());
+
+    let executed = exchange
+        .update_state(2, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert!(executed[0].reduce_only());
+    assert_eq!(executed[0].quantity(), base!(3));
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert!(exchange.position_tpsl().is_none());
+    assert_eq!(exchange.account().wallet_balance(), quote!(1027));
+}
+
+#[test]
+fn position_tpsl_stop_loss_of_short_position() {
+    let mut exchange = exchange();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    exchange.set_position_tpsl(None, Some(quote!(105))).unwrap();
+    assert_eq!(exchange.position_tpsl().unwrap().side(), Side::Sell);
+
+    // The wick of the candle reaches the stop-loss.
+    let executed = exchange
+        .update_state(
+            1,
+            candle!(quote!(101), quote!(102), quote!(100), quote!(106)),
+        )
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.account().wallet_balance(), quote!(998));
+}
+
+#[test]
+fn position_tpsl_removed_with_position() {
+    let mut exchange = exchange();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .set_position_tpsl(Some(quote!(110)), Some(quote!(95)))
+        .unwrap();
+    exchange.set_position_tpsl(None, None).unwrap();
+    assert!(exchange.position_tpsl().is_none());
+
+    exchange
+        .set_position_tpsl(Some(quote!(110)), Some(quote!(95)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(1, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(exchange.position_tpsl().is_none());
+
+    // A new position is not protected by the removed take-profit.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    let executed = exchange
+        .update_state(2, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    assert!(executed.is_empty());
+    assert_eq!(exchange.account().position().size(), base!(1));
+}
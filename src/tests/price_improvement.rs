@@ -0,0 +1,68 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn exchange(price_improvement: PriceImprovement) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base()
+        .price_improvement(price_improvement)
+        .build()
+        .unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+}
+
+fn aggressive_limit(side: Side, limit_price: QuoteCurrency) -> Order<BaseCurrency> {
+    let mut order = Order::limit(side, limit_price, base!(1)).unwrap();
+    order.set_time_in_force(TimeInForce::ImmediateOrCancel);
+    order
+}
+
+#[test]
+fn price_improvement_best_quote() {
+    let mut exchange = exchange(PriceImprovement::BestQuote);
+    let order = exchange
+        .submit_order(aggressive_limit(Side::Buy, quote!(105)))
+        .unwrap();
+    assert_eq!(
+        order.filled(),
+        Filled::Yes {
+            fill_price: quote!(101)
+        }
+    );
+    let order = exchange
+        .submit_order(aggressive_limit(Side::Sell, quote!(95)))
+        .unwrap();
+    assert_eq!(
+        order.filled(),
+        Filled::Yes {
+            fill_price: quote!(100)
+        }
+    );
+    assert_eq!(exchange.account().wallet_balance(), quote!(999));
+}
+
+#[test]
+fn price_improvement_off() {
+    let mut exchange = exchange(PriceImprovement::Off);
+    let order = exchange
+        .submit_order(aggressive_limit(Side::Buy, quote!(105)))
+        .unwrap();
+    assert_eq!(
+        order.filled(),
+        Filled::Yes {
+            fill_price: quote!(105)
+        }
+    );
+    assert_eq!(exchange.account().position().entry_price(), quote!(105));
+    let order = exchange
+        .submit_order(aggressive_limit(Side::Sell, quote!(95)))
+        .unwrap();
+    assert_eq!(
+        order.filled(),
+        Filled::Yes {
+            fill_price: quote!(95)
+        }
+    );
+    assert_eq!(exchange.account().wallet_balance(), quote!(990));
+}
@@ -0,0 +1,72 @@
+use crate::{mock_exchange_base, prelude::*, trade};
+
+#[test]
+fn resting_orders_execute_in_price_time_priority() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let first = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+        .unwrap();
+    let second = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+        .unwrap();
+    let best = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(100), base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(98), base!(1)).unwrap())
+        .unwrap();
+
+    // Only the best price level is crossed.
+    let executed = exchange
+        .update_state(1, trade!(quote!(100), base!(1), Side::Sell))
+        .unwrap();
+    assert_eq!(
+        Vec::from_iter(executed.iter().map(|order| order.id())),
+        vec![best.id()]
+    );
+    assert_eq!(exchange.account().active_limit_orders().len(), 3);
+    exchange.assert_invariants();
+
+    // The front of the queue at 99 executes first, while the level at 98 is not crossed.
+    let executed = exchange
+        .update_state(2, trade!(quote!(99), base!(5), Side::Sell))
+        .unwrap();
+    assert_eq!(
+        Vec::from_iter(executed.iter().map(|order| order.id())),
+        vec![first.id(), second.id()]
+    );
+    assert_eq!(exchange.account().position().size(), base!(3));
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+    exchange.assert_invariants();
+}
+
+#[test]
+fn cancelled_orders_leave_the_queue() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let first = exchange
+        .submit_order(Order::limit(Side::Sell, quote!(102), base!(1)).unwrap())
+        .unwrap();
+    let second = exchange
+        .submit_order(Order::limit(Side::Sell, quote!(102), base!(1)).unwrap())
+        .unwrap();
+    exchange.cancel_order(first.id()).unwrap();
+    exchange.assert_invariants();
+
+    let executed = exchange
+        .update_state(1, trade!(quote!(102), base!(1), Side::Buy))
+        .unwrap();
+    assert_eq!(
+        Vec::from_iter(executed.iter().map(|order| order.id())),
+        vec![second.id()]
+    );
+    assert!(exchange.account().active_limit_orders().is_empty());
+    exchange.assert_invariants();
+}
@@ -0,0 +1,37 @@
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*};
+
+#[test]
+fn order_actions_are_rate_limited() {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_rate_limit(RateLimit::new(2, 1_000_000_000).unwrap());
+    let mut exchange = Exchange::<_, BaseCurrency>::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let order = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(99), base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(98), base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.submit_order(Order::limit(Side::Buy, quote!(97), base!(1)).unwrap()),
+        Err(Error::RateLimited)
+    );
+    // Cancellations count towards the same limit.
+    assert_eq!(exchange.cancel_order(order.id()), Err(Error::RateLimited));
+    assert_eq!(exchange.stats().num_rate_limited, 2);
+    assert_eq!(exchange.stats().num_rejections, 0);
+    assert_eq!(exchange.account().active_limit_orders().len(), 2);
+
+    // Half a second refills one token.
+    exchange
+        .update_state(500_000_000, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange.cancel_order(order.id()).unwrap();
+    assert_eq!(exchange.cancel_order(order.id()), Err(Error::RateLimited));
+    exchange.assert_invariants();
+}
@@ -0,0 +1,108 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+/// An exchange with a resting buy at the bid and a resting sell at the ask.
+fn mock_exchange_stp(
+    self_trade_prevention: SelfTradePrevention,
+) -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_self_trade_prevention(self_trade_prevention);
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(100), base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(101), base!(1)).unwrap())
+        .unwrap();
+    exchange
+}
+
+fn resting_sell_quantity(
+    exchange: &Exchange<NoAccountTracker, BaseCurrency>,
+) -> Option<BaseCurrency> {
+    exchange
+        .account()
+        .active_limit_orders()
+        .values()
+        .find(|order| order.side() == Side::Sell)
+        .map(|order| order.remaining_quantity())
+}
+
+#[test]
+fn self_trade_prevention_off() {
+    let mut exchange = mock_exchange_stp(SelfTradePrevention::Off);
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(0.5)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0.5));
+    assert_eq!(resting_sell_quantity(&exchange), Some(base!(1)));
+}
+
+#[test]
+fn self_trade_prevention_cancel_aggressor() {
+    let mut exchange = mock_exchange_stp(SelfTradePrevention::CancelAggressor);
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(0.5)).unwrap()),
+        Err(Error::OrderError(OrderError::SelfTradePrevented))
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(resting_sell_quantity(&exchange), Some(base!(1)));
+
+    // A market sell fills at the bid, where the resting buy is.
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Sell, base!(0.5)).unwrap()),
+        Err(Error::OrderError(OrderError::SelfTradePrevented))
+    );
+}
+
+#[test]
+fn self_trade_prevention_cancel_resting() {
+    let mut exchange = mock_exchange_stp(SelfTradePrevention::CancelResting);
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(0.5)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0.5));
+    assert_eq!(resting_sell_quantity(&exchange), None);
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+    assert_eq!(exchange.stats().num_cancellations, 1);
+}
+
+#[test]
+fn self_trade_prevention_decrement_both() {
+    let mut exchange = mock_exchange_stp(SelfTradePrevention::DecrementBoth);
+    // The aggressive order is consumed entirely.
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(0.4)).unwrap()),
+        Err(Error::OrderError(OrderError::SelfTradePrevented))
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(resting_sell_quantity(&exchange), Some(base!(0.6)));
+
+    // The resting order is consumed entirely and the rest of the aggressive order executes.
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0.4));
+    assert_eq!(resting_sell_quantity(&exchange), None);
+}
+
+#[test]
+fn self_trade_prevention_rejected_aggressor_keeps_resting_orders() {
+    for mode in [
+        SelfTradePrevention::CancelResting,
+        SelfTradePrevention::DecrementBoth,
+    ] {
+        let mut exchange = mock_exchange_stp(mode);
+        assert_eq!(
+            exchange.submit_order(Order::market(Side::Buy, base!(1000)).unwrap()),
+            Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+        );
+        assert_eq!(exchange.account().position().size(), base!(0));
+        assert_eq!(exchange.account().active_limit_orders().len(), 2);
+        assert_eq!(resting_sell_quantity(&exchange), Some(base!(1)));
+    }
+}
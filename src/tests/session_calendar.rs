@@ -0,0 +1,61 @@
+use crate::{account_tracker::FullAccountTracker, mock_exchange_base, prelude::*};
+
+const HOUR_NS: u64 = 3_600_000_000_000;
+const DAILY_NS: u64 = 24 * HOUR_NS;
+// Monday, 2024-01-01 00:00 UTC.
+const MONDAY_NS: u64 = 1_704_067_200_000_000_000;
+
+#[test]
+fn orders_are_rejected_while_the_market_is_closed() {
+    let calendar = SessionCalendar::default()
+        .with_session(9 * HOUR_NS as i64, 17 * HOUR_NS as i64)
+        .unwrap()
+        .closed_on_weekends();
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_session_calendar(calendar.clone());
+    let tracker = FullAccountTracker::new(quote!(1000)).with_session_calendar(calendar);
+    let mut exchange = Exchange::<_, BaseCurrency>::new(tracker, config);
+
+    exchange
+        .update_state(MONDAY_NS + 8 * HOUR_NS, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    let order = Order::limit(Side::Buy, quote!(99), base!(1)).unwrap();
+    assert_eq!(
+        exchange.submit_order(order.clone()),
+        Err(Error::OrderError(OrderError::MarketClosed))
+    );
+    assert_eq!(exchange.stats().num_rejections, 1);
+
+    exchange
+        .update_state(MONDAY_NS + 9 * HOUR_NS, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    let order = exchange.submit_order(order).unwrap();
+
+    // A `Day` order expires at the close of the session.
+    let mut day_order = Order::limit(Side::Buy, quote!(98), base!(1)).unwrap();
+    day_order.set_time_in_force(TimeInForce::Day);
+    exchange.submit_order(day_order).unwrap();
+    exchange
+        .update_state(MONDAY_NS + 17 * HOUR_NS, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+
+    // Resting orders may still be cancelled on the weekend.
+    let saturday = MONDAY_NS + 5 * DAILY_NS + 10 * HOUR_NS;
+    exchange
+        .update_state(saturday, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(1)).unwrap()),
+        Err(Error::OrderError(OrderError::MarketClosed))
+    );
+    exchange.cancel_order(order.id()).unwrap();
+
+    exchange
+        .update_state(MONDAY_NS + 7 * DAILY_NS, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    // Monday to Friday.
+    assert_eq!(exchange.account_tracker().num_trading_days(), 5);
+}
@@ -0,0 +1,92 @@
+use crate::{prelude::*, risk_engine::RiskError};
+
+#[test]
+fn set_leverage_transfers_margin() {
+    let mut exchange = crate::mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(80), base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().position_margin(), quote!(505));
+    assert_eq!(exchange.account().order_margin(), quote!(160.032));
+
+    exchange.set_leverage(leverage!(5)).unwrap();
+    assert_eq!(exchange.account().position().leverage(), leverage!(5));
+    assert_eq!(exchange.account().position().position_margin(), quote!(101));
+    assert_eq!(exchange.account().order_margin(), quote!(32.032));
+    let available_balance = exchange.account().available_balance();
+
+    exchange.set_leverage(leverage!(1)).unwrap();
+    assert_eq!(exchange.account().position().position_margin(), quote!(505));
+    assert_eq!(
+        exchange.account().available_balance(),
+        available_balance - quote!(404) - quote!(128)
+    );
+}
+
+#[test]
+fn set_leverage_rejected() {
+    let mut exchange = crate::mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange.set_leverage(leverage!(5)).unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(35)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().position_margin(), quote!(707));
+
+    // The available balance cannot cover the margin at a lower leverage.
+    assert_eq!(
+        exchange.set_leverage(leverage!(1)),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    assert_eq!(exchange.account().position().leverage(), leverage!(5));
+    assert_eq!(exchange.account().position().position_margin(), quote!(707));
+
+    // The unrealized loss exceeds the position margin at a higher leverage.
+    exchange
+        .update_state(1, bba!(quote!(90), quote!(91)))
+        .unwrap();
+    assert_eq!(
+        exchange.set_leverage(leverage!(10)),
+        Err(Error::RiskError(RiskError::Liquidate))
+    );
+    assert_eq!(exchange.account().position().leverage(), leverage!(5));
+    assert_eq!(exchange.account().position().position_margin(), quote!(707));
+}
+
+#[test]
+fn set_leverage_position_limit() {
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_position_limit(
+            PositionLimit::new(
+                None,
+                vec![LeverageBracket {
+                    max_notional: quote!(400),
+                    max_leverage: leverage!(10),
+                }],
+            )
+            .unwrap(),
+        );
+    let mut exchange = Exchange::new(crate::account_tracker::NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(3)).unwrap())
+        .unwrap();
+    exchange.set_leverage(leverage!(10)).unwrap();
+    assert_eq!(
+        exchange.set_leverage(leverage!(20)),
+        Err(Error::RiskError(RiskError::MaxPositionNotionalExceeded))
+    );
+    assert_eq!(exchange.account().position().leverage(), leverage!(10));
+}
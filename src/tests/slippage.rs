@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use crate::{account_tracker::FullAccountTracker, prelude::*};
+
+#[test]
+fn market_order_with_slippage() {
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_slippage_model(Arc::new(FixedBpsSlippage { bps: Dec!(100) }));
+    let mut exchange =
+        Exchange::<_, BaseCurrency>::new(FullAccountTracker::new(quote!(1000)), config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().entry_price(), quote!(102.01));
+    assert_eq!(
+        exchange.account_tracker().cumulative_slippage(),
+        quote!(2.02)
+    );
+
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(2)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(
+        exchange.account_tracker().cumulative_slippage(),
+        quote!(4.02)
+    );
+}
+
+#[test]
+fn limit_orders_without_slippage() {
+    let config = crate::mock_exchange_base()
+        .config()
+        .clone()
+        .with_slippage_model(Arc::new(FixedBpsSlippage { bps: Dec!(100) }));
+    let mut exchange =
+        Exchange::<_, BaseCurrency>::new(FullAccountTracker::new(quote!(1000)), config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    let mut order = Order::limit(Side::Buy, quote!(101), base!(1)).unwrap();
+    order.set_time_in_force(TimeInForce::ImmediateOrCancel);
+    exchange.submit_order(order).unwrap();
+    assert_eq!(exchange.account().position().entry_price(), quote!(101));
+    assert_eq!(exchange.account_tracker().cumulative_slippage(), quote!(0));
+}
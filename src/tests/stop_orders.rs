@@ -0,0 +1,233 @@
+use crate::{account_tracker::NoAccountTracker, candle, index, prelude::*, trade};
+
+fn exchange() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base().build().unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+}
+
+fn stop_loss(trigger_source: TriggerSource) -> Order<BaseCurrency> {
+    let mut order = Order::market(Side::Sell, base!(1)).unwrap();
+    order.set_stop_price(quote!(95)).unwrap();
+    order.set_trigger_source(trigger_source);
+    order
+}
+
+#[test]
+fn stop_orders_last_price_triggers_on_wicks() {
+    for trigger_source in [TriggerSource::LastPrice, TriggerSource::MarkPrice] {
+        let mut exchange = exchange();
+        exchange
+            .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+            .unwrap();
+        let stop = exchange.submit_order(stop_loss(trigger_source)).unwrap();
+        assert_eq!(exchange.stop_orders(), std::slice::from_ref(&stop));
+        assert_eq!(exchange.order_status(stop.id()), Some(OrderStatus::New));
+        // The stop does not reserve any margin.
+        assert_eq!(exchange.account().order_margin(), quote!(0));
+
+        // The candle wicks through the stop price, while its mid price stays at 99.5.
+        let executed = exchange
+            .update_state(1, candle!(quote!(99), quote!(100), quote!(94), quote!(101)))
+            .unwrap();
+        match trigger_source {
+            TriggerSource::LastPrice => {
+                assert_eq!(executed.len(), 1);
+                assert_eq!(executed[0].id(), stop.id());
+                assert_eq!(executed[0].stop_price(), None);
+                assert_eq!(
+                    executed[0].filled(),
+                    Filled::Yes {
+                        fill_price: quote!(99)
+                    }
+                );
+                assert!(exchange.stop_orders().is_empty());
+                assert_eq!(exchange.account().position().size(), base!(0));
+            }
+            TriggerSource::MarkPrice => {
+                assert!(executed.is_empty());
+                assert_eq!(exchange.account().position().size(), base!(1));
+                let executed = exchange
+                    .update_state(2, bba!(quote!(94), quote!(95)))
+                    .unwrap();
+                assert_eq!(executed.len(), 1);
+                assert_eq!(exchange.account().position().size(), base!(0));
+            }
+            TriggerSource::IndexPrice => unreachable!(),
+        }
+        assert_eq!(exchange.order_status(stop.id()), Some(OrderStatus::Filled));
+    }
+}
+
+#[test]
+fn stop_orders_index_price() {
+    let mut exchange = exchange();
+    let mut order = Order::market(Side::Buy, base!(1)).unwrap();
+    order.set_stop_price(quote!(105)).unwrap();
+    order.set_trigger_source(TriggerSource::IndexPrice);
+    exchange.submit_order(order).unwrap();
+
+    // Without an index price the stop never triggers.
+    exchange
+        .update_state(1, trade!(quote!(110), base!(1), Side::Buy))
+        .unwrap();
+    exchange
+        .update_state(2, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    assert_eq!(exchange.stop_orders().len(), 1);
+    exchange.update_state(3, index!(quote!(104))).unwrap();
+    assert_eq!(exchange.stop_orders().len(), 1);
+
+    let executed = exchange.update_state(4, index!(quote!(105))).unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(exchange.account().position().size(), base!(1));
+    assert_eq!(exchange.account().position().entry_price(), quote!(111));
+}
+
+#[test]
+fn stop_orders_stop_limit() {
+    let mut exchange = exchange();
+    // Triggered with a limit price crossing the spread, it executes immediately.
+    let mut crossing = Order::limit(Side::Buy, quote!(106), base!(1)).unwrap();
+    crossing.set_stop_price(quote!(104)).unwrap();
+    let crossing = exchange.submit_order(crossing).unwrap();
+    // Triggered with a limit price below the ask, it rests in the book.
+    let mut resting = Order::limit(Side::Buy, quote!(103), base!(1)).unwrap();
+    resting.set_stop_price(quote!(104)).unwrap();
+    let resting = exchange.submit_order(resting).unwrap();
+
+    let executed = exchange
+        .update_state(1, bba!(quote!(104), quote!(105)))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].id(), crossing.id());
+    assert_eq!(exchange.account().position().size(), base!(1));
+    assert!(exchange.stop_orders().is_empty());
+    assert_eq!(exchange.order_status(resting.id()), Some(OrderStatus::New));
+    assert!(exchange
+        .account()
+        .active_limit_orders()
+        .contains_key(&resting.id()));
+}
+
+#[test]
+fn stop_orders_cancel() {
+    let mut exchange = exchange();
+    let mut order = stop_loss(TriggerSource::LastPrice);
+    order.set_user_order_id(7);
+    let stop = exchange.submit_order(order.clone()).unwrap();
+    assert_eq!(
+        exchange.submit_order(order),
+        Err(Error::OrderError(OrderError::DuplicateUserOrderId))
+    );
+    let cancelled = exchange.cancel_order_by_user_id(7).unwrap();
+    assert_eq!(cancelled.id(), stop.id());
+    assert!(exchange.stop_orders().is_empty());
+    assert_eq!(
+        exchange.order_status(stop.id()),
+        Some(OrderStatus::Cancelled)
+    );
+
+    let stop = exchange
+        .submit_order(stop_loss(TriggerSource::LastPrice))
+        .unwrap();
+    exchange.cancel_order(stop.id()).unwrap();
+    exchange
+        .update_state(1, trade!(quote!(90), base!(1), Side::Sell))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(
+        Order::<BaseCurrency>::market(Side::Buy, base!(1))
+            .unwrap()
+            .set_stop_price(quote!(0)),
+        Err(OrderError::StopPriceBelowZero)
+    );
+}
+
+fn stop_limit_loss() -> Order<BaseCurrency> {
+    let mut order = Order::limit(Side::Sell, quote!(94), base!(1)).unwrap();
+    order.set_stop_price(quote!(95)).unwrap();
+    order
+}
+
+#[test]
+fn stop_orders_oco_take_profit_cancels_stop_loss() {
+    let mut exchange = exchange();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    let (take_profit, stop_loss) = exchange
+        .submit_oco_orders(
+            Order::limit(Side::Sell, quote!(105), base!(1)).unwrap(),
+            stop_limit_loss(),
+        )
+        .unwrap();
+    assert_eq!(exchange.stop_orders().len(), 1);
+    exchange.assert_invariants();
+
+    let executed = exchange
+        .update_state(1, trade!(quote!(105), base!(1), Side::Buy))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].id(), take_profit.id());
+    assert!(exchange.stop_orders().is_empty());
+    assert!(exchange.account().order_groups.is_empty());
+    assert_eq!(
+        exchange.order_status(stop_loss.id()),
+        Some(OrderStatus::Cancelled)
+    );
+    assert_eq!(exchange.account().position().size(), base!(0));
+
+    // The cancelled stop loss does not open a new position.
+    exchange
+        .update_state(2, trade!(quote!(90), base!(1), Side::Sell))
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(0));
+    exchange.assert_invariants();
+}
+
+#[test]
+fn stop_orders_oco_triggered_stop_loss_cancels_take_profit() {
+    let mut exchange = exchange();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    let (take_profit, stop_loss) = exchange
+        .submit_oco_orders(
+            Order::limit(Side::Sell, quote!(105), base!(1)).unwrap(),
+            stop_loss(TriggerSource::LastPrice),
+        )
+        .unwrap();
+
+    let executed = exchange
+        .update_state(1, candle!(quote!(94), quote!(95), quote!(93), quote!(101)))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].id(), stop_loss.id());
+    assert_eq!(
+        exchange.order_status(take_profit.id()),
+        Some(OrderStatus::Cancelled)
+    );
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account().position().size(), base!(0));
+    exchange.assert_invariants();
+}
+
+#[test]
+fn stop_orders_oco_rejected_leg_removes_stop() {
+    let mut exchange = exchange();
+    // The second leg crosses the spread, so neither leg is accepted.
+    assert_eq!(
+        exchange.submit_oco_orders(
+            stop_limit_loss(),
+            Order::limit(Side::Buy, quote!(102), base!(1)).unwrap(),
+        ),
+        Err(Error::OrderError(OrderError::LimitPriceAboveAsk))
+    );
+    assert!(exchange.stop_orders().is_empty());
+    assert!(exchange.account().order_groups.is_empty());
+    exchange.assert_invariants();
+}
@@ -0,0 +1,110 @@
+use crate::{account_tracker::NoAccountTracker, mock_exchange_base, prelude::*, trade};
+
+/// Buys into a position on the first update and takes profit with a resting limit order.
+#[derive(Default)]
+struct TakeProfit {
+    entry_quantity: BaseCurrency,
+    updates: Vec<u64>,
+    fills: Vec<Order<BaseCurrency>>,
+    num_liquidations: usize,
+}
+
+impl Strategy<NoAccountTracker, BaseCurrency> for TakeProfit {
+    fn on_market_update(
+        &mut self,
+        exchange: &mut Exchange<NoAccountTracker, BaseCurrency>,
+        timestamp_ns: u64,
+        _market_update: &MarketUpdate<BaseCurrency>,
+    ) {
+        if self.updates.is_empty() {
+            exchange
+                .submit_order(Order::market(Side::Buy, self.entry_quantity).unwrap())
+                .unwrap();
+            let take_profit = exchange.market_state().ask() + quote!(4);
+            exchange
+                .submit_order(Order::limit(Side::Sell, take_profit, self.entry_quantity).unwrap())
+                .unwrap();
+        }
+        self.updates.push(timestamp_ns);
+    }
+
+    fn on_fill(
+        &mut self,
+        exchange: &mut Exchange<NoAccountTracker, BaseCurrency>,
+        order: &Order<BaseCurrency>,
+    ) {
+        assert_eq!(exchange.account().position().size(), base!(0));
+        self.fills.push(order.clone());
+    }
+
+    fn on_liquidation(&mut self, exchange: &mut Exchange<NoAccountTracker, BaseCurrency>) {
+        assert!(exchange.account().active_limit_orders().is_empty());
+        self.num_liquidations += 1;
+    }
+}
+
+#[test]
+fn backtest_strategy() {
+    let strategy = TakeProfit {
+        entry_quantity: base!(5),
+        ..Default::default()
+    };
+    let mut backtest = Backtest::new(mock_exchange_base(), strategy);
+    let summary = backtest
+        .run(vec![
+            (0, bba!(quote!(100), quote!(101))),
+            (1, trade!(quote!(104), base!(1), Side::Buy)),
+            (2, trade!(quote!(106), base!(1), Side::Buy)),
+            (3, bba!(quote!(105), quote!(106))),
+        ])
+        .unwrap();
+    assert_eq!(
+        summary,
+        BacktestSummary {
+            num_updates: 4,
+            num_fills: 1,
+            num_liquidations: 0,
+        }
+    );
+    let (exchange, strategy) = backtest.into_parts();
+    assert_eq!(strategy.updates, vec![0, 1, 2, 3]);
+    assert_eq!(strategy.fills.len(), 1);
+    assert_eq!(strategy.fills[0].limit_price(), Some(quote!(105)));
+    assert_eq!(strategy.num_liquidations, 0);
+    assert_eq!(exchange.stats().num_fills, 2);
+
+    // Invalid market updates are returned as an Error.
+    let mut backtest = Backtest::new(exchange, strategy);
+    assert_eq!(
+        backtest.run(vec![(4, bba!(quote!(107), quote!(106)))]),
+        Err(Error::InvalidMarketUpdateBidAskSpread)
+    );
+}
+
+#[test]
+fn backtest_strategy_liquidation() {
+    let config = crate::mock_config_base().build().unwrap();
+    let strategy = TakeProfit {
+        entry_quantity: base!(50),
+        ..Default::default()
+    };
+    let mut backtest = Backtest::new(Exchange::new(NoAccountTracker, config), strategy);
+    let summary = backtest
+        .run(vec![
+            (0, bba!(quote!(100), quote!(101))),
+            (1, bba!(quote!(92), quote!(93))),
+            (2, bba!(quote!(93), quote!(94))),
+        ])
+        .unwrap();
+    assert_eq!(
+        summary,
+        BacktestSummary {
+            num_updates: 3,
+            num_fills: 0,
+            num_liquidations: 1,
+        }
+    );
+    assert_eq!(backtest.strategy().num_liquidations, 1);
+    assert_eq!(backtest.strategy().updates, vec![0, 1, 2]);
+    assert_eq!(backtest.exchange().account().position().size(), base!(0));
+}
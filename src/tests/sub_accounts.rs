@@ -0,0 +1,89 @@
+use crate::{account_tracker::NoAccountTracker, prelude::*};
+
+fn sub_account() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base().build().unwrap();
+    Exchange::new(NoAccountTracker, config)
+}
+
+#[test]
+fn sub_accounts_isolate_books() {
+    let mut subs = SubAccounts::new().with_transfer_delay(10);
+    subs.add("trend", sub_account()).unwrap();
+    subs.add("hedge", sub_account()).unwrap();
+    assert_eq!(
+        subs.add("trend", sub_account()),
+        Err(Error::DuplicateSubAccount)
+    );
+    assert_eq!(subs.names(), &["trend".to_string(), "hedge".to_string()]);
+
+    for result in subs.update_state(0, &bba!(quote!(100), quote!(101))) {
+        result.unwrap();
+    }
+    subs.get_mut("trend")
+        .unwrap()
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    subs.get_mut("hedge")
+        .unwrap()
+        .submit_order(Order::market(Side::Sell, base!(2)).unwrap())
+        .unwrap();
+    // Each sub-account holds its own position and margin.
+    assert_eq!(
+        subs.get("trend").unwrap().account().position().size(),
+        base!(5)
+    );
+    assert_eq!(
+        subs.get("hedge").unwrap().account().position().size(),
+        base!(-2)
+    );
+    assert_eq!(
+        subs.get("trend").unwrap().account().position_margin(),
+        quote!(50.5)
+    );
+    assert_eq!(
+        subs.get("hedge").unwrap().account().position_margin(),
+        quote!(20)
+    );
+    assert_eq!(subs.net_position_size(), base!(3));
+
+    // Both sub-accounts see the same market update.
+    for result in subs.update_state(5, &bba!(quote!(105), quote!(106))) {
+        result.unwrap();
+    }
+    // The long made 5 * (105 - 101) = 20 and the short lost 2 * (106 - 100) = 12.
+    assert_eq!(subs.combined_equity(), quote!(2008));
+
+    subs.transfer("trend", "hedge", quote!(100)).unwrap();
+    assert_eq!(
+        subs.get("trend").unwrap().account().wallet_balance(),
+        quote!(900)
+    );
+    assert_eq!(subs.total_wallet_balance(), quote!(1900));
+    // Funds in transit still count towards the combined equity.
+    assert_eq!(subs.combined_equity(), quote!(2008));
+
+    for result in subs.update_state(15, &bba!(quote!(105), quote!(106))) {
+        result.unwrap();
+    }
+    assert_eq!(
+        subs.get("hedge").unwrap().account().wallet_balance(),
+        quote!(1100)
+    );
+    assert_eq!(subs.total_wallet_balance(), quote!(2000));
+    assert_eq!(subs.combined_equity(), quote!(2008));
+}
+
+#[test]
+fn sub_accounts_unknown_name() {
+    let mut subs = SubAccounts::new();
+    subs.add("main", sub_account()).unwrap();
+    assert!(subs.get("other").is_none());
+    assert_eq!(
+        subs.transfer("main", "other", quote!(1)),
+        Err(Error::UnknownSubAccount)
+    );
+    assert_eq!(
+        subs.transfer("main", "main", quote!(1)),
+        Err(Error::InvalidTransfer)
+    );
+}
@@ -26,6 +26,7 @@ fn submit_limit_buy_order_no_position() {
     // Now fill the order
     order.set_id(0);
     order.mark_filled(order.limit_price().unwrap());
+    order.set_status(OrderStatus::Filled, 0);
     assert_eq!(
         exchange
             .update_state(0, trade!(quote!(98), base!(1), Side::Sell))
@@ -61,6 +62,7 @@ fn submit_limit_buy_order_no_position() {
 
     order.set_id(1);
     order.mark_filled(order.limit_price().unwrap());
+    order.set_status(OrderStatus::Filled, 0);
     exchange
         .update_state(0, bba!(quote!(96), quote!(98)))
         .unwrap();
@@ -160,6 +162,7 @@ fn submit_limit_buy_order_with_long() {
 
     order.set_id(2);
     order.mark_filled(order.limit_price().unwrap());
+    order.set_status(OrderStatus::Filled, 0);
     assert_eq!(
         exchange
             .update_state(0, trade!(quote!(101), base!(1), Side::Buy))
@@ -213,6 +216,7 @@ fn submit_limit_buy_order_with_short() {
 
     order.set_id(2);
     order.mark_filled(order.limit_price().unwrap());
+    order.set_status(OrderStatus::Filled, 0);
     assert_eq!(
         exchange
             .update_state(0, trade!(quote!(100), base!(1), Side::Sell))
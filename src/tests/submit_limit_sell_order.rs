@@ -25,6 +25,7 @@ fn submit_limit_sell_order_no_position() {
 
     // Now fill the order
     order.mark_filled(order.limit_price().unwrap());
+    order.set_status(OrderStatus::Filled, 0);
     assert_eq!(
         exchange
             .update_state(0, trade!(quote!(100), base!(1), Side::Buy))
@@ -53,6 +54,7 @@ fn submit_limit_sell_order_no_position() {
 
     order.set_id(1);
     order.mark_filled(order.limit_price().unwrap());
+    order.set_status(OrderStatus::Filled, 0);
     assert_eq!(
         exchange
             .update_state(0, trade!(quote!(100), base!(1), Side::Sell))
@@ -145,3 +145,29 @@ fn submit_market_buy_order_turnaround_short() {
         quote!(100) - quote!(0.5346) - quote!(1.08) - quote!(9)
     );
 }
+
+#[test]
+fn submit_market_buy_order_turnaround_short_at_a_loss() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(5)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(1, bba!(quote!(109), quote!(110)))
+        .unwrap();
+
+    // The released margin of the short would cover the new long,
+    // but not after realizing the loss of 50 and paying the fee.
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Buy, base!(14)).unwrap()),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(13)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().position().size(), base!(8));
+    exchange.assert_invariants();
+}
@@ -146,3 +146,26 @@ fn submit_market_sell_order_turnaround_long() {
         quote!(100) - quote!(0.5454) - quote!(1.08) - quote!(9)
     );
 }
+
+#[test]
+fn submit_market_sell_order_turnaround_long_with_resting_sell() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(5)).unwrap())
+        .unwrap();
+    // The resting sell is offset by the long, so it does not require margin yet.
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(200), base!(5)).unwrap())
+        .unwrap();
+    assert_eq!(exchange.account().order_margin(), quote!(0));
+
+    // After the turnaround the resting sell would add to the short and require margin as well.
+    assert_eq!(
+        exchange.submit_order(Order::market(Side::Sell, base!(9)).unwrap()),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+    exchange.assert_invariants();
+}
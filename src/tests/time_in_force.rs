@@ -0,0 +1,247 @@
+use crate::{account_tracker::FullAccountTracker, depth, mock_exchange_base, prelude::*, trade};
+
+#[test]
+fn immediate_or_cancel_crossing_the_spread() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let mut order = Order::limit(Side::Buy, quote!(101), base!(5)).unwrap();
+    order.set_time_in_force(TimeInForce::ImmediateOrCancel);
+    let order = exchange.submit_order(order).unwrap();
+    // Filled entirely at the ask, as taker, as the best ask has unbounded size without depth.
+    assert_eq!(
+        order.filled(),
+        Filled::Yes {
+            fill_price: quote!(100)
+        }
+    );
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(
+        exchange.account().position,
+        Position {
+            size: base!(5),
+            entry_price: quote!(100),
+            position_margin: quote!(500),
+            leverage: leverage!(1),
+        }
+    );
+    assert_eq!(exchange.account().wallet_balance(), quote!(999.7));
+}
+
+#[test]
+fn immediate_or_cancel_not_crossing_the_spread() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    for tif in [TimeInForce::ImmediateOrCancel, TimeInForce::FillOrKill] {
+        let mut order = Order::limit(Side::Sell, quote!(100), base!(5)).unwrap();
+        order.set_time_in_force(tif);
+        assert_eq!(
+            exchange.submit_order(order),
+            Err(Error::OrderError(OrderError::NotImmediatelyExecutable))
+        );
+    }
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account().position().size(), base!(0));
+}
+
+#[test]
+fn immediate_or_cancel_against_depth() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(
+            0,
+            depth!(
+                vec![(quote!(100), base!(2)), (quote!(99), base!(3))],
+                vec![(quote!(101), base!(3)), (quote!(102), base!(2))]
+            ),
+        )
+        .unwrap();
+
+    // Only the 3 quoted up to the limit price are executed and the rest is cancelled.
+    let mut order = Order::limit(Side::Buy, quote!(101), base!(5)).unwrap();
+    order.set_time_in_force(TimeInForce::ImmediateOrCancel);
+    let order = exchange.submit_order(order).unwrap();
+    assert_eq!(order.quantity(), base!(3));
+    assert_eq!(
+        order.filled(),
+        Filled::Yes {
+            fill_price: quote!(101)
+        }
+    );
+    assert_eq!(exchange.account().position().size(), base!(3));
+    assert!(exchange.account().active_limit_orders().is_empty());
+
+    let mut order = Order::limit(Side::Buy, quote!(101), base!(5)).unwrap();
+    order.set_time_in_force(TimeInForce::FillOrKill);
+    assert_eq!(
+        exchange.submit_order(order),
+        Err(Error::OrderError(OrderError::NotImmediatelyExecutable))
+    );
+    let mut order = Order::limit(Side::Buy, quote!(102), base!(5)).unwrap();
+    order.set_time_in_force(TimeInForce::FillOrKill);
+    exchange.submit_order(order).unwrap();
+    assert_eq!(exchange.account().position().size(), base!(8));
+}
+
+#[test]
+fn fill_or_kill_respects_margin() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let mut order = Order::limit(Side::Sell, quote!(99), base!(11)).unwrap();
+    order.set_time_in_force(TimeInForce::FillOrKill);
+    assert_eq!(
+        exchange.submit_order(order),
+        Err(Error::RiskError(RiskError::NotEnoughAvailableBalance))
+    );
+}
+
+#[test]
+fn good_til_date_expiry() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let mut order = Order::limit(Side::Buy, quote!(98), base!(5)).unwrap();
+    order.set_time_in_force(TimeInForce::GoodTilDate(10));
+    exchange.submit_order(order).unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+
+    // Still active at the expiry timestamp.
+    exchange
+        .update_state(10, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+
+    // Expired before it could be filled.
+    assert_eq!(
+        exchange
+            .update_state(11, trade!(quote!(97), base!(10), Side::Sell))
+            .unwrap(),
+        vec![]
+    );
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account().order_margin(), quote!(0));
+    assert_eq!(exchange.account().position().size(), base!(0));
+
+    // Can't submit an order that has already expired.
+    let mut order = Order::limit(Side::Buy, quote!(98), base!(5)).unwrap();
+    order.set_time_in_force(TimeInForce::GoodTilDate(5));
+    assert_eq!(
+        exchange.submit_order(order),
+        Err(Error::OrderError(OrderError::GoodTilDateInPast))
+    );
+}
+
+#[test]
+fn good_til_date_expiry_index() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    // Submitted in reverse order of their expiry, with every other order cancelled before it expires.
+    let mut ids = Vec::new();
+    for expiry_ts_ns in (1..=100).rev() {
+        let mut order = Order::limit(Side::Buy, quote!(90), base!(0.1)).unwrap();
+        order.set_time_in_force(TimeInForce::GoodTilDate(expiry_ts_ns));
+        let order = exchange.submit_order(order).unwrap();
+        if expiry_ts_ns % 2 == 0 {
+            exchange.cancel_order(order.id()).unwrap();
+        } else {
+            ids.push((expiry_ts_ns, order.id()));
+        }
+    }
+    assert_eq!(exchange.account().active_limit_orders().len(), 50);
+
+    for ts in 1..=101 {
+        exchange
+            .update_state(ts, bba!(quote!(99), quote!(100)))
+            .unwrap();
+        let num_active = ids
+            .iter()
+            .filter(|(expiry, _)| *expiry >= ts as i64)
+            .count();
+        assert_eq!(exchange.account().active_limit_orders().len(), num_active);
+        for (expiry, id) in ids.iter() {
+            assert_eq!(
+                exchange.account().active_limit_orders().contains_key(id),
+                *expiry >= ts as i64
+            );
+        }
+    }
+    assert_eq!(exchange.stats().num_expirations, 50);
+    assert_eq!(exchange.account().order_margin(), quote!(0));
+    exchange.assert_invariants();
+}
+
+fn mock_exchange_session_calendar(
+    session_calendar: SessionCalendar,
+) -> Exchange<FullAccountTracker<QuoteCurrency>, BaseCurrency> {
+    let config = mock_exchange_base()
+        .config()
+        .clone()
+        .with_session_calendar(session_calendar);
+    Exchange::new(FullAccountTracker::new(quote!(1000)), config)
+}
+
+#[test]
+fn day_order_expires_at_utc_midnight() {
+    const DAILY_NS: u64 = 86_400_000_000_000;
+    let mut exchange = mock_exchange_session_calendar(SessionCalendar::default());
+    exchange
+        .update_state(DAILY_NS + 5, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let mut order = Order::limit(Side::Buy, quote!(98), base!(5)).unwrap();
+    order.set_time_in_force(TimeInForce::Day);
+    exchange.submit_order(order).unwrap();
+
+    exchange
+        .update_state(2 * DAILY_NS - 1, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+
+    exchange
+        .update_state(2 * DAILY_NS, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    assert!(exchange.account().active_limit_orders().is_empty());
+    assert_eq!(exchange.account_tracker().num_expired_limit_orders(), 1);
+    assert_eq!(exchange.account().order_margin(), quote!(0));
+}
+
+#[test]
+fn day_order_expires_at_custom_session_end() {
+    let mut exchange = mock_exchange_session_calendar(
+        SessionCalendar::default()
+            .with_session_ends(vec![100, 200])
+            .unwrap(),
+    );
+    exchange
+        .update_state(50, bba!(quote!(99), quote!(100)))
+        .unwrap();
+
+    let mut day_order = Order::limit(Side::Buy, quote!(98), base!(1)).unwrap();
+    day_order.set_time_in_force(TimeInForce::Day);
+    exchange.submit_order(day_order).unwrap();
+    let gtc_order = Order::limit(Side::Buy, quote!(97), base!(1)).unwrap();
+    let gtc_order = exchange.submit_order(gtc_order).unwrap();
+
+    exchange
+        .update_state(100, bba!(quote!(99), quote!(100)))
+        .unwrap();
+    assert_eq!(exchange.account().active_limit_orders().len(), 1);
+    assert!(exchange
+        .account()
+        .active_limit_orders()
+        .contains_key(&gtc_order.id()));
+    assert_eq!(exchange.account_tracker().num_expired_limit_orders(), 1);
+}
@@ -0,0 +1,129 @@
+use crate::{account_tracker::NoAccountTracker, candle, prelude::*};
+
+fn exchange() -> Exchange<NoAccountTracker, BaseCurrency> {
+    let config = crate::mock_config_base().build().unwrap();
+    let mut exchange = Exchange::new(NoAccountTracker, config);
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    exchange
+}
+
+#[test]
+fn trailing_take_profit_long() {
+    let mut exchange = exchange();
+    assert_eq!(
+        exchange.set_trailing_take_profit(quote!(5), quote!(3)),
+        Err(Error::InvalidTrailingTakeProfit)
+    );
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    assert_eq!(
+        exchange.set_trailing_take_profit(quote!(5), quote!(0)),
+        Err(Error::InvalidTrailingTakeProfit)
+    );
+    exchange
+        .set_trailing_take_profit(quote!(5), quote!(3))
+        .unwrap();
+    assert_eq!(
+        exchange.trailing_take_profit().unwrap().activation_price(),
+        quote!(106)
+    );
+
+    // Not activated yet, so a retracement does not close the position.
+    exchange
+        .update_state(1, bba!(quote!(104), quote!(105)))
+        .unwrap();
+    exchange
+        .update_state(2, bba!(quote!(98), quote!(99)))
+        .unwrap();
+    assert_eq!(exchange.trailing_take_profit().unwrap().peak(), None);
+
+    exchange
+        .update_state(3, bba!(quote!(106), quote!(107)))
+        .unwrap();
+    assert_eq!(
+        exchange.trailing_take_profit().unwrap().stop_price(),
+        Some(quote!(103.5))
+    );
+    exchange
+        .update_state(4, bba!(quote!(110), quote!(111)))
+        .unwrap();
+    let executed = exchange
+        .update_state(5, bba!(quote!(108), quote!(109)))
+        .unwrap();
+    assert!(executed.is_empty());
+    assert_eq!(
+        exchange.trailing_take_profit().unwrap().stop_price(),
+        Some(quote!(107.5))
+    );
+
+    let executed = exchange
+        .update_state(6, bba!(quote!(107), quote!(108)))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert!(executed[0].reduce_only());
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert!(exchange.trailing_take_profit().is_none());
+    assert_eq!(exchange.account().wallet_balance(), quote!(1006));
+}
+
+#[test]
+fn trailing_take_profit_short_with_candles() {
+    let mut exchange = exchange();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .set_trailing_take_profit(quote!(2), quote!(1))
+        .unwrap();
+
+    // The candle activates the trailing with its low, but its high is not checked against it.
+    let executed = exchange
+        .update_state(1, candle!(quote!(97), quote!(98), quote!(95), quote!(99)))
+        .unwrap();
+    assert!(executed.is_empty());
+    assert_eq!(
+        exchange.trailing_take_profit().unwrap().stop_price(),
+        Some(quote!(96))
+    );
+    exchange
+        .update_state(2, bba!(quote!(95), quote!(96)))
+        .unwrap();
+    assert_eq!(
+        exchange.trailing_take_profit().unwrap().peak(),
+        Some(quote!(95))
+    );
+
+    let executed = exchange
+        .update_state(3, bba!(quote!(96), quote!(97)))
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(exchange.account().position().size(), base!(0));
+    assert_eq!(exchange.account().wallet_balance(), quote!(1003));
+}
+
+#[test]
+fn trailing_take_profit_removed_with_position() {
+    let mut exchange = exchange();
+    exchange
+        .submit_order(Order::market(Side::Buy, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .set_trailing_take_profit(quote!(0), quote!(1))
+        .unwrap();
+    exchange.cancel_trailing_take_profit();
+    assert!(exchange.trailing_take_profit().is_none());
+
+    exchange
+        .set_trailing_take_profit(quote!(0), quote!(1))
+        .unwrap();
+    exchange
+        .submit_order(Order::market(Side::Sell, base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .update_state(1, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    assert!(exchange.trailing_take_profit().is_none());
+}
@@ -0,0 +1,108 @@
+use crate::{
+    account_tracker::{RecordedField, TimeSeriesRecorder},
+    candle, mock_exchange_base,
+    prelude::*,
+    trade,
+};
+
+fn submit_orders<A: AccountTracker<QuoteCurrency>>(exchange: &mut Exchange<A, BaseCurrency>) {
+    exchange
+        .submit_order(Order::limit(Side::Buy, quote!(98), base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(103), base!(1)).unwrap())
+        .unwrap();
+}
+
+#[test]
+fn update_state_batch_matches_update_state() {
+    let updates = vec![
+        (1, bba!(quote!(99), quote!(100))),
+        (2, trade!(quote!(97), base!(1), Side::Sell)),
+        (
+            3,
+            candle!(quote!(100), quote!(101), quote!(99), quote!(104)),
+        ),
+    ];
+
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    submit_orders(&mut exchange);
+    let mut executed = Vec::new();
+    for (ts, update) in updates.iter().cloned() {
+        executed.append(&mut exchange.update_state(ts, update).unwrap());
+    }
+
+    let mut exchange_batch = mock_exchange_base();
+    exchange_batch
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+    submit_orders(&mut exchange_batch);
+    let executed_batch = exchange_batch.update_state_batch(&updates).unwrap();
+
+    assert_eq!(executed_batch.len(), 2);
+    assert_eq!(executed_batch, executed);
+    assert_eq!(
+        exchange_batch.account().position,
+        exchange.account().position
+    );
+    assert_eq!(
+        exchange_batch.account().wallet_balance(),
+        exchange.account().wallet_balance()
+    );
+    assert_eq!(
+        exchange_batch.market_state().mid_price(),
+        exchange.market_state().mid_price()
+    );
+    assert_eq!(exchange_batch.stats(), exchange.stats());
+}
+
+#[test]
+fn update_state_batch_stops_at_error() {
+    let mut exchange = mock_exchange_base();
+    let updates = vec![
+        (1, bba!(quote!(100), quote!(101))),
+        // Crossed best bid and ask.
+        (2, bba!(quote!(102), quote!(101))),
+        (3, bba!(quote!(105), quote!(106))),
+    ];
+    assert_eq!(
+        exchange.update_state_batch(&updates).unwrap_err(),
+        Error::InvalidMarketUpdateBidAskSpread
+    );
+    assert_eq!(exchange.market_state().bid(), quote!(100));
+    assert_eq!(exchange.stats().num_updates, 2);
+}
+
+#[test]
+fn update_state_batch_samples_the_tracker_once() {
+    let recorder = || TimeSeriesRecorder::new(1, &[RecordedField::Equity], Dec!(0.02)).unwrap();
+    let updates = vec![
+        (1, bba!(quote!(100), quote!(101))),
+        (2, bba!(quote!(101), quote!(102))),
+        (3, bba!(quote!(102), quote!(103))),
+    ];
+    let config = mock_exchange_base().config().clone();
+
+    let mut exchange = Exchange::<_, BaseCurrency>::new(recorder(), config.clone());
+    for (ts, update) in updates.iter().cloned() {
+        exchange.update_state(ts, update).unwrap();
+    }
+    assert_eq!(exchange.account_tracker().timestamps_ns(), &[1, 2, 3]);
+
+    let mut exchange_batch = Exchange::<_, BaseCurrency>::new(recorder(), config.clone());
+    exchange_batch.update_state_batch(&updates).unwrap();
+    assert_eq!(exchange_batch.account_tracker().timestamps_ns(), &[3]);
+
+    // A failing update samples the last applied one.
+    let mut exchange_batch = Exchange::<_, BaseCurrency>::new(recorder(), config);
+    let mut updates = updates;
+    updates[2].1 = bba!(quote!(104), quote!(103));
+    assert_eq!(
+        exchange_batch.update_state_batch(&updates).unwrap_err(),
+        Error::InvalidMarketUpdateBidAskSpread
+    );
+    assert_eq!(exchange_batch.account_tracker().timestamps_ns(), &[2]);
+}
@@ -0,0 +1,40 @@
+use crate::{mock_exchange_base, prelude::*, trade};
+
+#[test]
+fn update_state_into() {
+    let mut exchange = mock_exchange_base();
+    let mut executed = Vec::with_capacity(4);
+    let buffer = executed.as_ptr();
+    exchange
+        .update_state_into(0, &bba!(quote!(100), quote!(101)), &mut executed)
+        .unwrap();
+    assert!(executed.is_empty());
+
+    let order = exchange
+        .submit_order(Order::limit(Side::Buy, quote!(98), base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .update_state_into(1, &trade!(quote!(97), base!(1), Side::Sell), &mut executed)
+        .unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].id(), order.id());
+    assert_eq!(executed[0].status(), OrderStatus::Filled);
+    assert_eq!(exchange.account().position.size, base!(1));
+
+    // The buffer is appended to, not cleared.
+    exchange
+        .submit_order(Order::limit(Side::Sell, quote!(103), base!(1)).unwrap())
+        .unwrap();
+    exchange
+        .update_state_into(2, &trade!(quote!(104), base!(1), Side::Buy), &mut executed)
+        .unwrap();
+    assert_eq!(executed.len(), 2);
+    assert_eq!(exchange.account().position.size, base!(0));
+
+    executed.clear();
+    exchange
+        .update_state_into(3, &bba!(quote!(100), quote!(101)), &mut executed)
+        .unwrap();
+    assert!(executed.is_empty());
+    assert_eq!(executed.as_ptr(), buffer);
+}
@@ -0,0 +1,57 @@
+use crate::{mock_exchange_base, prelude::*};
+
+#[test]
+fn user_order_id_lookup_and_cancel() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let mut order = Order::limit(Side::Buy, quote!(99), base!(1)).unwrap();
+    order.set_user_order_id("entry-1");
+    let entry = exchange.submit_order(order).unwrap();
+    let mut order = Order::limit(Side::Sell, quote!(102), base!(1)).unwrap();
+    order.set_user_order_id(7);
+    let exit = exchange.submit_order(order).unwrap();
+    assert_eq!(
+        *entry.user_order_id(),
+        Some(UserOrderId::Text("entry-1".to_string()))
+    );
+
+    assert_eq!(
+        exchange.account().active_order_by_user_id("entry-1"),
+        Some(&entry)
+    );
+    assert_eq!(exchange.account().active_order_by_user_id(7), Some(&exit));
+    assert_eq!(exchange.account().active_order_by_user_id("7"), None);
+
+    let cancelled = exchange.cancel_order_by_user_id("entry-1").unwrap();
+    assert_eq!(cancelled.id(), entry.id());
+    assert_eq!(exchange.account().active_order_by_user_id("entry-1"), None);
+    assert_eq!(
+        exchange.cancel_order_by_user_id("entry-1"),
+        Err(Error::UserOrderIdNotFound)
+    );
+    assert_eq!(exchange.cancel_order_by_user_id(7).unwrap().id(), exit.id());
+    assert!(exchange.account().active_limit_orders().is_empty());
+}
+
+#[test]
+fn user_order_id_duplicate() {
+    let mut exchange = mock_exchange_base();
+    exchange
+        .update_state(0, bba!(quote!(100), quote!(101)))
+        .unwrap();
+
+    let mut order = Order::limit(Side::Buy, quote!(99), base!(1)).unwrap();
+    order.set_user_order_id("entry");
+    exchange.submit_order(order.clone()).unwrap();
+    assert_eq!(
+        exchange.submit_order(order.clone()),
+        Err(Error::OrderError(OrderError::DuplicateUserOrderId))
+    );
+
+    // The id can be reused once the order is no longer active.
+    exchange.cancel_order_by_user_id("entry").unwrap();
+    exchange.submit_order(order).unwrap();
+}
@@ -0,0 +1,11 @@
+/// Defines what happens when a loss exceeds the wallet balance,
+/// e.g. because the price gapped through the liquidation price.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BankruptcyMode {
+    /// The wallet balance becomes negative and the account owes the deficit.
+    #[default]
+    NegativeEquity,
+    /// The wallet balance is clamped to zero
+    /// and the deficit is covered by the insurance fund of the exchange.
+    NegativeBalanceProtection,
+}
@@ -1,12 +1,12 @@
-use std::ops::{Add, Div, Mul, Rem, Sub};
+use core::ops::{Add, Div, Mul, Rem, Sub};
 
-use derive_more::{Add, AddAssign, Display, Div, From, Into, Mul, Sub, SubAssign};
+use derive_more::{Add, AddAssign, Display, Div, From, Mul, Sub, SubAssign};
 use fpdec::Decimal;
 
 use super::MarginCurrency;
 use crate::{
     prelude::Leverage,
-    types::{Currency, Fee, QuoteCurrency},
+    types::{Currency, Fee, Mon, QuoteCurrency},
 };
 
 /// Allows the quick construction of `BaseCurrency`
@@ -23,10 +23,8 @@ macro_rules! base {
     Debug,
     Clone,
     Copy,
-    Eq,
     PartialEq,
     PartialOrd,
-    Ord,
     Add,
     Sub,
     Mul,
@@ -34,34 +32,38 @@ macro_rules! base {
     AddAssign,
     SubAssign,
     Display,
-    Into,
     From,
 )]
 #[mul(forward)]
 #[div(forward)]
-pub struct BaseCurrency(Decimal);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BaseCurrency<T = Decimal>(T);
 
-impl Currency for BaseCurrency {
-    type PairedCurrency = QuoteCurrency;
+impl<T> Currency for BaseCurrency<T>
+where
+    T: Mon,
+{
+    type Num = T;
+    type PairedCurrency = QuoteCurrency<T>;
 
     #[inline(always)]
-    fn new(val: Decimal) -> Self {
+    fn new(val: T) -> Self {
         Self(val)
     }
 
     #[inline(always)]
-    fn inner(self) -> Decimal {
+    fn inner(self) -> T {
         self.0
     }
 
     #[inline(always)]
     fn new_zero() -> Self {
-        Self::new(Decimal::ZERO)
+        Self::new(T::ZERO)
     }
 
     #[inline(always)]
     fn is_zero(&self) -> bool {
-        self.0.eq(&Decimal::ZERO)
+        self.0 == T::ZERO
     }
 
     #[inline(always)]
@@ -70,12 +72,12 @@ impl Currency for BaseCurrency {
     }
 
     #[inline(always)]
-    fn fee_portion(&self, fee: Fee) -> Self {
+    fn fee_portion(&self, fee: Fee<T>) -> Self {
         Self(self.0 * fee.inner())
     }
 
     #[inline(always)]
-    fn convert(&self, rate: QuoteCurrency) -> Self::PairedCurrency {
+    fn convert(&self, rate: QuoteCurrency<T>) -> Self::PairedCurrency {
         QuoteCurrency::new(self.0 * rate.inner())
     }
 
@@ -85,57 +87,119 @@ impl Currency for BaseCurrency {
     }
 }
 
-impl MarginCurrency for BaseCurrency {
+impl<T> MarginCurrency for BaseCurrency<T>
+where
+    T: Mon,
+{
     /// This represents the pnl calculation for inverse futures contracts
     fn pnl<S>(
-        entry_price: QuoteCurrency,
-        exit_price: QuoteCurrency,
+        entry_price: QuoteCurrency<T>,
+        exit_price: QuoteCurrency<T>,
         quantity: S,
     ) -> S::PairedCurrency
     where
-        S: Currency,
+        S: Currency<Num = T>,
     {
         if quantity.is_zero() {
             return S::PairedCurrency::new_zero();
         }
         quantity.convert(entry_price) - quantity.convert(exit_price)
     }
+
+    /// Solves `collateral + pnl(price) = |size| / price * maintenance_margin` for `price`.
+    fn liquidation_price(
+        entry_price: QuoteCurrency<T>,
+        position_size: QuoteCurrency<T>,
+        collateral: Self,
+        maintenance_margin: T,
+    ) -> Option<QuoteCurrency<T>> {
+        if position_size.is_zero() {
+            return None;
+        }
+        let size = position_size.inner().abs();
+        let (numerator, denominator) = if position_size > QuoteCurrency::new_zero() {
+            (
+                size * (T::ONE + maintenance_margin),
+                collateral.0 + size / entry_price.inner(),
+            )
+        } else {
+            (
+                size * (T::ONE - maintenance_margin),
+                size / entry_price.inner() - collateral.0,
+            )
+        };
+        if numerator <= T::ZERO || denominator <= T::ZERO {
+            return None;
+        }
+        Some(QuoteCurrency::new(numerator / denominator))
+    }
+}
+
+impl<T> Eq for BaseCurrency<T> where T: Mon {}
+
+/// The numeric backends never produce `NaN` from valid inputs.
+#[allow(clippy::derive_ord_xor_partial_ord)]
+impl<T> Ord for BaseCurrency<T>
+where
+    T: Mon,
+{
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .expect("Currency values are comparable")
+    }
 }
 
-/// ### Arithmetic with `Decimal` on the right hand side
-impl Add<Decimal> for BaseCurrency {
+/// ### Arithmetic with the numeric type on the right hand side
+impl<T> Add<T> for BaseCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn add(self, rhs: Decimal) -> Self::Output {
+    fn add(self, rhs: T) -> Self::Output {
         Self(self.0 + rhs)
     }
 }
 
-impl Sub<Decimal> for BaseCurrency {
+impl<T> Sub<T> for BaseCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn sub(self, rhs: Decimal) -> Self::Output {
+    fn sub(self, rhs: T) -> Self::Output {
         Self(self.0 - rhs)
     }
 }
 
-impl Mul<Decimal> for BaseCurrency {
+impl<T> Mul<T> for BaseCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn mul(self, rhs: Decimal) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         Self(self.0 * rhs)
     }
 }
 
-impl Div<Decimal> for BaseCurrency {
+impl<T> Div<T> for BaseCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn div(self, rhs: Decimal) -> Self::Output {
+    fn div(self, rhs: T) -> Self::Output {
         Self(self.0 / rhs)
     }
 }
 
-impl Rem for BaseCurrency {
+impl<T> Rem for BaseCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
     fn rem(self, rhs: Self) -> Self::Output {
@@ -143,26 +207,35 @@ impl Rem for BaseCurrency {
     }
 }
 
-impl Div<Leverage> for BaseCurrency {
+impl<T> Div<Leverage> for BaseCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
     fn div(self, rhs: Leverage) -> Self::Output {
-        Self(self.0 / rhs.inner())
+        Self(self.0 / T::from_u64(rhs.inner() as u64))
     }
 }
 
-impl Mul<Fee> for BaseCurrency {
+impl<T> Mul<Fee<T>> for BaseCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn mul(self, rhs: Fee) -> Self::Output {
+    fn mul(self, rhs: Fee<T>) -> Self::Output {
         Self(self.0 * rhs.inner())
     }
 }
 
-impl Add<Fee> for BaseCurrency {
+impl<T> Add<Fee<T>> for BaseCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn add(self, rhs: Fee) -> Self::Output {
+    fn add(self, rhs: Fee<T>) -> Self::Output {
         Self(self.0 + rhs.inner())
     }
 }
@@ -196,4 +269,23 @@ mod tests {
             base!(2.5)
         );
     }
+
+    #[test]
+    fn inverse_futures_liquidation_price() {
+        // 10x long, 2% maintenance margin
+        assert_eq!(
+            BaseCurrency::liquidation_price(quote!(100), quote!(1000), base!(1), Dec!(0.02)),
+            Some(quote!(92.727272727272727273))
+        );
+        // 10x short, 2% maintenance margin
+        assert_eq!(
+            BaseCurrency::liquidation_price(quote!(100), quote!(-1000), base!(1), Dec!(0.02)),
+            Some(quote!(108.888888888888888889))
+        );
+        // A short backed by its full notional value can never be liquidated.
+        assert_eq!(
+            BaseCurrency::liquidation_price(quote!(100), quote!(-1000), base!(10), Dec!(0.02)),
+            None
+        );
+    }
 }
@@ -1,49 +1,52 @@
-use fpdec::Decimal;
-
 use crate::{
     prelude::Leverage,
-    types::{Fee, QuoteCurrency},
+    types::{Fee, MaybeSerde, Mon, QuoteCurrency},
 };
 
 /// Every unit of account must implement this trait
 pub trait Currency:
     Copy
+    + 'static
     + Default
     + Send
     + Sized
     + Ord
-    + std::fmt::Debug
-    + std::fmt::Display
+    + core::fmt::Debug
+    + core::fmt::Display
     // Require to do arithmetic with `Self` on the right hand side
-    + std::ops::Add<Output = Self>
-    + std::ops::Sub<Output = Self>
-    + std::ops::Mul<Output = Self>
-    + std::ops::Div<Output = Self>
-    + std::ops::Rem<Output = Self>
-    // Require to do arithmetic with `Decimal` on the right hand side
-    + std::ops::Add<Decimal, Output = Self>
-    + std::ops::Sub<Decimal, Output = Self>
-    + std::ops::Mul<Decimal, Output = Self>
-    + std::ops::Div<Decimal, Output = Self>
-    + std::ops::Div<Leverage, Output = Self>
-    + std::ops::Mul<Fee, Output = Self>
-    + std::ops::Add<Fee, Output = Self>
-    + std::ops::AddAssign
-    + std::ops::SubAssign
+    + core::ops::Add<Output = Self>
+    + core::ops::Sub<Output = Self>
+    + core::ops::Mul<Output = Self>
+    + core::ops::Div<Output = Self>
+    + core::ops::Rem<Output = Self>
+    // Require to do arithmetic with the numeric type on the right hand side
+    + core::ops::Add<<Self as Currency>::Num, Output = Self>
+    + core::ops::Sub<<Self as Currency>::Num, Output = Self>
+    + core::ops::Mul<<Self as Currency>::Num, Output = Self>
+    + core::ops::Div<<Self as Currency>::Num, Output = Self>
+    + core::ops::Div<Leverage, Output = Self>
+    + core::ops::Mul<Fee<<Self as Currency>::Num>, Output = Self>
+    + core::ops::Add<Fee<<Self as Currency>::Num>, Output = Self>
+    + core::ops::AddAssign
+    + core::ops::SubAssign
     + PartialEq
     + PartialOrd
+    + MaybeSerde
 {
+    /// The numeric type backing the currency, `Decimal` by default.
+    type Num: Mon;
+
     /// The paired currency.
     /// e.g.: for the BTCUSD market the BTC currency is paired with USD, so the
     /// `PairedCurrency` would be USD
-    type PairedCurrency: Currency<PairedCurrency = Self>;
+    type PairedCurrency: Currency<Num = Self::Num, PairedCurrency = Self>;
 
-    /// Create a new instance from a `Rational` value
+    /// Create a new instance from a numeric value
     #[must_use]
-    fn new(val: Decimal) -> Self;
+    fn new(val: Self::Num) -> Self;
 
-    /// Return the inner `Decimal`
-    fn inner(self) -> Decimal;
+    /// Return the inner numeric value
+    fn inner(self) -> Self::Num;
 
     /// Create a new currency instance with zero value
     #[must_use]
@@ -57,12 +60,12 @@ pub trait Currency:
     fn abs(self) -> Self;
 
     /// Compute the fee denoted in the currency
-    fn fee_portion(&self, fee: Fee) -> Self;
+    fn fee_portion(&self, fee: Fee<Self::Num>) -> Self;
 
     /// Convert this `Currency`'s value into its pair at the conversion `rate`.
     /// E.g:
     /// 1 BTC @ 20_000 USD means that 1 USD = 1 / 20_000 BTC
-    fn convert(&self, rate: QuoteCurrency) -> Self::PairedCurrency;
+    fn convert(&self, rate: QuoteCurrency<Self::Num>) -> Self::PairedCurrency;
 
     /// Convert the Currency to a negative value
     /// TODO: rename for greater clarity
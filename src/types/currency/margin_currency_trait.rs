@@ -17,10 +17,28 @@ pub trait MarginCurrency: Currency {
     /// Returns the profit and loss measured in the `PairedCurrency` of the size
     /// currency.
     fn pnl<S>(
-        entry_price: QuoteCurrency,
-        exit_price: QuoteCurrency,
+        entry_price: QuoteCurrency<Self::Num>,
+        exit_price: QuoteCurrency<Self::Num>,
         quantity: S,
     ) -> S::PairedCurrency
     where
-        S: Currency;
+        S: Currency<Num = Self::Num>;
+
+    /// Compute the price at which the equity backing a position equals its maintenance margin requirement.
+    ///
+    /// # Arguments:
+    /// `entry_price`: The entry price of the position.
+    /// `position_size`: The position size, negative denoting a short position.
+    /// `collateral`: The margin backing the position.
+    /// `maintenance_margin`: The maintenance margin requirement as a fraction.
+    ///
+    /// # Returns:
+    /// `None` if there is no position or the position can never reach its maintenance margin,
+    /// otherwise the liquidation price.
+    fn liquidation_price(
+        entry_price: QuoteCurrency<Self::Num>,
+        position_size: Self::PairedCurrency,
+        collateral: Self,
+        maintenance_margin: Self::Num,
+    ) -> Option<QuoteCurrency<Self::Num>>;
 }
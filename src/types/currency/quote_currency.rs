@@ -1,12 +1,12 @@
-use std::ops::{Add, Div, Mul, Rem, Sub};
+use core::ops::{Add, Div, Mul, Rem, Sub};
 
-use derive_more::{Add, AddAssign, Display, Div, From, Into, Mul, Sub, SubAssign};
+use derive_more::{Add, AddAssign, Display, Div, From, Mul, Sub, SubAssign};
 use fpdec::Decimal;
 
 use super::MarginCurrency;
 use crate::{
     prelude::Leverage,
-    types::{BaseCurrency, Currency, Fee},
+    types::{BaseCurrency, Currency, Fee, Mon},
 };
 
 /// Allows the quick construction of `QuoteCurrency`
@@ -23,10 +23,8 @@ macro_rules! quote {
     Debug,
     Clone,
     Copy,
-    Eq,
     PartialEq,
     PartialOrd,
-    Ord,
     Add,
     Sub,
     Mul,
@@ -34,34 +32,38 @@ macro_rules! quote {
     AddAssign,
     SubAssign,
     Display,
-    Into,
     From,
 )]
 #[mul(forward)]
 #[div(forward)]
-pub struct QuoteCurrency(Decimal);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuoteCurrency<T = Decimal>(T);
 
-impl Currency for QuoteCurrency {
-    type PairedCurrency = BaseCurrency;
+impl<T> Currency for QuoteCurrency<T>
+where
+    T: Mon,
+{
+    type Num = T;
+    type PairedCurrency = BaseCurrency<T>;
 
     #[inline(always)]
-    fn new(val: Decimal) -> Self {
+    fn new(val: T) -> Self {
         Self(val)
     }
 
     #[inline(always)]
-    fn inner(self) -> Decimal {
+    fn inner(self) -> T {
         self.0
     }
 
     #[inline(always)]
     fn new_zero() -> Self {
-        Self::new(Decimal::ZERO)
+        Self::new(T::ZERO)
     }
 
     #[inline(always)]
     fn is_zero(&self) -> bool {
-        self.0.eq(&Decimal::ZERO)
+        self.0 == T::ZERO
     }
 
     #[inline(always)]
@@ -70,12 +72,12 @@ impl Currency for QuoteCurrency {
     }
 
     #[inline(always)]
-    fn fee_portion(&self, fee: Fee) -> Self {
+    fn fee_portion(&self, fee: Fee<T>) -> Self {
         Self(self.0 * fee.inner())
     }
 
     #[inline(always)]
-    fn convert(&self, rate: QuoteCurrency) -> Self::PairedCurrency {
+    fn convert(&self, rate: QuoteCurrency<T>) -> Self::PairedCurrency {
         BaseCurrency::new(self.0 / rate.0)
     }
 
@@ -85,57 +87,119 @@ impl Currency for QuoteCurrency {
     }
 }
 
-impl MarginCurrency for QuoteCurrency {
+impl<T> MarginCurrency for QuoteCurrency<T>
+where
+    T: Mon,
+{
     /// This represents a linear futures contract pnl calculation
     fn pnl<S>(
-        entry_price: QuoteCurrency,
-        exit_price: QuoteCurrency,
+        entry_price: QuoteCurrency<T>,
+        exit_price: QuoteCurrency<T>,
         quantity: S,
     ) -> S::PairedCurrency
     where
-        S: Currency,
+        S: Currency<Num = T>,
     {
         if quantity.is_zero() {
             return S::PairedCurrency::new_zero();
         }
         quantity.convert(exit_price) - quantity.convert(entry_price)
     }
+
+    /// Solves `collateral + pnl(price) = |size| * price * maintenance_margin` for `price`.
+    fn liquidation_price(
+        entry_price: QuoteCurrency<T>,
+        position_size: BaseCurrency<T>,
+        collateral: Self,
+        maintenance_margin: T,
+    ) -> Option<QuoteCurrency<T>> {
+        if position_size.is_zero() {
+            return None;
+        }
+        let size = position_size.inner().abs();
+        let (numerator, denominator) = if position_size > BaseCurrency::new_zero() {
+            (
+                size * entry_price.0 - collateral.0,
+                size * (T::ONE - maintenance_margin),
+            )
+        } else {
+            (
+                collateral.0 + size * entry_price.0,
+                size * (T::ONE + maintenance_margin),
+            )
+        };
+        if numerator <= T::ZERO || denominator <= T::ZERO {
+            return None;
+        }
+        Some(QuoteCurrency(numerator / denominator))
+    }
 }
 
-/// ### Arithmetic with `Rational` on the right hand side
-impl Add<Decimal> for QuoteCurrency {
+impl<T> Eq for QuoteCurrency<T> where T: Mon {}
+
+/// The numeric backends never produce `NaN` from valid inputs.
+#[allow(clippy::derive_ord_xor_partial_ord)]
+impl<T> Ord for QuoteCurrency<T>
+where
+    T: Mon,
+{
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .expect("Currency values are comparable")
+    }
+}
+
+/// ### Arithmetic with the numeric type on the right hand side
+impl<T> Add<T> for QuoteCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn add(self, rhs: Decimal) -> Self::Output {
+    fn add(self, rhs: T) -> Self::Output {
         Self(self.0 + rhs)
     }
 }
 
-impl Sub<Decimal> for QuoteCurrency {
+impl<T> Sub<T> for QuoteCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn sub(self, rhs: Decimal) -> Self::Output {
+    fn sub(self, rhs: T) -> Self::Output {
         Self(self.0 - rhs)
     }
 }
 
-impl Mul<Decimal> for QuoteCurrency {
+impl<T> Mul<T> for QuoteCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn mul(self, rhs: Decimal) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         Self(self.0 * rhs)
     }
 }
 
-impl Div<Decimal> for QuoteCurrency {
+impl<T> Div<T> for QuoteCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn div(self, rhs: Decimal) -> Self::Output {
+    fn div(self, rhs: T) -> Self::Output {
         Self(self.0 / rhs)
     }
 }
 
-impl Rem for QuoteCurrency {
+impl<T> Rem for QuoteCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
     fn rem(self, rhs: Self) -> Self::Output {
@@ -143,26 +207,35 @@ impl Rem for QuoteCurrency {
     }
 }
 
-impl Div<Leverage> for QuoteCurrency {
+impl<T> Div<Leverage> for QuoteCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
     fn div(self, rhs: Leverage) -> Self::Output {
-        Self(self.0 / rhs.inner())
+        Self(self.0 / T::from_u64(rhs.inner() as u64))
     }
 }
 
-impl Mul<Fee> for QuoteCurrency {
+impl<T> Mul<Fee<T>> for QuoteCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn mul(self, rhs: Fee) -> Self::Output {
+    fn mul(self, rhs: Fee<T>) -> Self::Output {
         Self(self.0 * rhs.inner())
     }
 }
 
-impl Add<Fee> for QuoteCurrency {
+impl<T> Add<Fee<T>> for QuoteCurrency<T>
+where
+    T: Mon,
+{
     type Output = Self;
 
-    fn add(self, rhs: Fee) -> Self::Output {
+    fn add(self, rhs: Fee<T>) -> Self::Output {
         Self(self.0 + rhs.inner())
     }
 }
@@ -196,4 +269,27 @@ mod tests {
             quote!(100.0)
         );
     }
+
+    #[test]
+    fn linear_futures_liquidation_price() {
+        // 10x long, 2% maintenance margin
+        assert_eq!(
+            QuoteCurrency::liquidation_price(quote!(100), base!(1), quote!(10), Dec!(0.02)),
+            Some(quote!(91.836734693877551020))
+        );
+        // 10x short, 2% maintenance margin
+        assert_eq!(
+            QuoteCurrency::liquidation_price(quote!(100), base!(-1), quote!(10), Dec!(0.02)),
+            Some(quote!(107.843137254901960784))
+        );
+        // A fully collateralized long can never be liquidated.
+        assert_eq!(
+            QuoteCurrency::liquidation_price(quote!(100), base!(1), quote!(100), Dec!(0.02)),
+            None
+        );
+        assert_eq!(
+            QuoteCurrency::liquidation_price(quote!(100), base!(0), quote!(100), Dec!(0.02)),
+            None
+        );
+    }
 }
@@ -0,0 +1,15 @@
+/// Defines what happens to residuals below the minimum quantity or notional value of the contract,
+/// which remain after partial fills and can't be traded with any valid order.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DustHandling {
+    /// The residuals remain as they are.
+    #[default]
+    Keep,
+    /// Dust positions are closed at the best bid or ask as taker
+    /// and dust remainders of partially filled orders are cancelled.
+    AutoClose,
+    /// Dust positions are taken out of the position at their entry price and accumulated in `Exchange::dust`,
+    /// without recording a trade, so neither profit and loss nor fees are realized for them.
+    /// Dust remainders of partially filled orders are cancelled.
+    Track,
+}
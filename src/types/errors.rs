@@ -1,3 +1,5 @@
+use alloc::string::String;
+
 use crate::risk_engine::RiskError;
 
 /// Defines the possible order errors that can occur when submitting a new order
@@ -7,6 +9,15 @@ pub enum OrderError {
     #[error("Maximum number of active orders reached")]
     MaxActiveOrders,
 
+    #[error("The maximum number of resting limit orders is reached")]
+    MaxNumOrders,
+
+    #[error("The maximum number of resting limit orders on this side is reached")]
+    MaxNumOrdersPerSide,
+
+    #[error("The order would trade against a resting order of the same account")]
+    SelfTradePrevented,
+
     #[error("The limit order price is lower than the low price multiple.")]
     LimitPriceBelowMultiple,
 
@@ -46,12 +57,46 @@ pub enum OrderError {
     #[error("The order quantity is too high")]
     QuantityTooHigh,
 
+    #[error("The market order quantity is too high")]
+    MarketQuantityTooHigh,
+
+    #[error("The notional value of the order is too low")]
+    NotionalTooLow,
+
+    #[error("The notional value of the order is too high")]
+    NotionalTooHigh,
+
     #[error("The order quantity does not conform to the step size")]
     InvalidQuantityStepSize,
+
+    #[error("The order could not be executed immediately as required by its time in force")]
+    NotImmediatelyExecutable,
+
+    #[error("The expiry timestamp of the good til date order has already passed")]
+    GoodTilDateInPast,
+
+    #[error("One-cancels-other orders must be resting limit orders")]
+    InvalidOcoOrder,
+
+    #[error("The display quantity of an iceberg order must be in range (0, quantity]")]
+    InvalidDisplayQuantity,
+
+    #[error("The user order id is already used by another active order")]
+    DuplicateUserOrderId,
+
+    #[error("The market is closed according to the trading calendar")]
+    MarketClosed,
+
+    #[error("Trading is halted by the circuit breaker")]
+    TradingHalted,
+
+    #[error("The stop price must be > 0")]
+    StopPriceBelowZero,
 }
 
 /// Describes possible Errors that may occur when calling methods in this crate
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[allow(missing_docs)]
 pub enum Error {
     #[error("Wrong leverage provided")]
@@ -84,6 +129,135 @@ pub enum Error {
     #[error("The provided starting balance must be > 0")]
     InvalidStartingBalance,
 
+    #[error("The maximum risk per trade must be in range (0, 1]")]
+    InvalidMaxRiskPerTrade,
+
+    #[error(
+        "The maker program period must be > 0 and the maker shares of its tiers in range [0, 1]"
+    )]
+    InvalidMakerProgram,
+
+    #[error("The fee schedule window must be > 0 and one tier must have a minimum volume of zero")]
+    InvalidFeeSchedule,
+
+    #[error("The annualized borrow rate must be >= 0")]
+    InvalidBorrowRate,
+
+    #[error("The annualized interest rate on idle margin must be >= 0")]
+    InvalidIdleMarginRate,
+
+    #[error("The expiry timestamp must be positive")]
+    InvalidExpiry,
+
+    #[error("Latencies must not be negative")]
+    InvalidLatencyModel,
+
+    #[error("The notional values of a position limit must be > 0")]
+    InvalidPositionLimit,
+
+    #[error("The borrowable amount and the hourly rate of a borrow pool must be >= 0")]
+    InvalidBorrowPool,
+
+    #[error("The haircut of a collateral asset must be in range [0, 1) and its ticker unique")]
+    InvalidCollateralAsset,
+
+    #[error("The collateral asset is not configured")]
+    UnknownCollateralAsset,
+
+    #[error("The discount of the fee token must be in range [0, 1)")]
+    InvalidFeeToken,
+
+    #[error("No fee token is configured")]
+    FeeTokenNotConfigured,
+
+    #[error("A transfer requires two distinct and existing venues")]
+    InvalidTransfer,
+
+    #[error("There is no venue at that index")]
+    UnknownVenue,
+
+    #[error("A position take-profit and stop-loss requires a single open position and prices on either side of the mark price")]
+    InvalidPositionTpSl,
+
+    #[error("A trailing take-profit requires a single open position, an activation profit >= 0 and an offset > 0")]
+    InvalidTrailingTakeProfit,
+
+    #[error("The sub-account name is already taken")]
+    DuplicateSubAccount,
+
+    #[error("There is no sub-account of that name")]
+    UnknownSubAccount,
+
+    #[error("The quanto multiplier must be > 0")]
+    InvalidQuantoMultiplier,
+
+    #[error(
+        "A trading session must be a non-empty part of the day and session ends must be sorted"
+    )]
+    InvalidSessionCalendar,
+
+    #[error("A downtime window must end after it starts")]
+    InvalidDowntime,
+
+    #[error("The maximum move and the halt duration of a circuit breaker must be > 0")]
+    InvalidCircuitBreaker,
+
+    #[error("The exchange is unavailable due to a scheduled downtime")]
+    ExchangeUnavailable,
+
+    #[error("A rate limit must allow at least one request per positive interval")]
+    InvalidRateLimit,
+
+    #[error("The rate limit of order submissions and cancellations is exhausted")]
+    RateLimited,
+
+    #[error(
+        "Fault injection probabilities must be within [0, 1] and the delay must not be negative"
+    )]
+    InvalidFaultInjection,
+
+    #[error("The exchange is overloaded, the request was not processed")]
+    ExchangeOverloaded,
+
+    #[error("The request timed out before it reached the exchange")]
+    RequestTimeout,
+
+    #[error("The parameters of the mark price source are invalid")]
+    InvalidMarkPriceSource,
+
+    #[error("The drawdown threshold of the risk of ruin must be in range (0, 1]")]
+    InvalidRuinDrawdown,
+
+    #[error("The trading days per year must be in range (0, 366], the trading hours per day in (0, 24] and the risk-free rate > -1")]
+    InvalidMetricsConfig,
+
+    #[error("The sampling interval must be > 0")]
+    InvalidSamplingInterval,
+
+    #[error("The recorded fields must be non-empty and unique")]
+    InvalidRecordedFields,
+
+    #[error("The ConfigBuilder requires a ContractSpecification")]
+    MissingContractSpecification,
+
+    #[error("The initial and maintenance margin must be in range (0, 1]")]
+    InvalidMarginRequirement,
+
+    #[error("Invalid exchange spec: {0}")]
+    InvalidExchangeSpec(String),
+
+    #[error("Invalid market data: {0}")]
+    InvalidMarketData(String),
+
+    #[error("The futures contract has expired")]
+    ContractExpired,
+
+    #[error("The option has already expired")]
+    OptionExpired,
+
+    #[error("The replay diverged from the journal")]
+    ReplayDiverged,
+
     #[error("Some price in MarketUpdate is too low.")]
     MarketUpdatePriceTooLow,
 
@@ -99,6 +273,11 @@ pub enum Error {
     #[error("An invalid price was provided in MarketUpdate")]
     InvalidMarketUpdatePrice,
 
+    #[error(
+        "The depth levels in MarketUpdate are empty, out of order or have a non-positive quantity."
+    )]
+    InvalidMarketUpdateDepth,
+
     #[error("The Account does not have enough available balance.")]
     NotEnoughAvailableBalance,
 
@@ -111,6 +290,9 @@ pub enum Error {
     #[error("The provided amount is invalid.")]
     InvalidAmount,
 
+    #[error("There is no open position.")]
+    NoOpenPosition,
+
     #[error("The provided price is invalid.")]
     InvalidPrice,
 
@@ -132,9 +314,17 @@ pub enum Error {
     #[error("The specified leverage must be > 0")]
     InvalidLeverage,
 
-    #[error(transparent)]
-    Decimal(#[from] fpdec::DecimalError),
+    // Not `transparent`, as `DecimalError` only implements `Error` with `std`.
+    #[error("{0}")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Decimal(fpdec::DecimalError),
+}
+
+impl From<fpdec::DecimalError> for Error {
+    fn from(error: fpdec::DecimalError) -> Self {
+        Self::Decimal(error)
+    }
 }
 
 /// This is defined as a convenience.
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
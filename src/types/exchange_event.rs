@@ -0,0 +1,209 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, MutexGuard};
+
+#[cfg(not(feature = "std"))]
+use spin::{Mutex, MutexGuard};
+
+use super::{Currency, Error, Order, QuoteCurrency};
+use crate::journal::{Journal, JournalEntry};
+
+/// Something that happened on the `Exchange`, delivered to subscribers
+/// registered with [`Exchange::subscribe`](crate::prelude::Exchange::subscribe).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+#[allow(clippy::large_enum_variant)]
+pub enum ExchangeEvent<S>
+where
+    S: Currency,
+{
+    /// An order was accepted, either resting in the book or executed immediately.
+    OrderAccepted(Order<S>),
+    /// A stop order was triggered and is submitted as the order it was created as.
+    StopTriggered(Order<S>),
+    /// An order was rejected on submission.
+    OrderRejected {
+        /// The rejected order.
+        order: Order<S>,
+        /// The reason for the rejection.
+        error: Error,
+    },
+    /// An order was completely filled.
+    Fill {
+        /// The filled order.
+        order: Order<S>,
+        /// The quantity executed by this fill.
+        quantity: S,
+        /// The fill price.
+        price: QuoteCurrency<S::Num>,
+        /// Whether the order provided liquidity.
+        is_maker: bool,
+    },
+    /// A slice of an iceberg order was filled, while the rest of it keeps resting in the book.
+    PartialFill {
+        /// The order after the fill.
+        order: Order<S>,
+        /// The quantity executed by this fill.
+        quantity: S,
+        /// The fill price.
+        price: QuoteCurrency<S::Num>,
+    },
+    /// A resting order was removed without being filled,
+    /// due to a cancellation, a one-cancels-other sibling, its `TimeInForce` or a liquidation.
+    Cancel(Order<S>),
+    /// A funding payment of a perpetual contract between the account and the exchange.
+    /// A negative amount is paid by the account.
+    FundingPayment(S::PairedCurrency),
+    /// The interest paid by the account on the borrowed notional of leveraged positions
+    /// or on the borrowed quantity of short positions, as recorded in `LedgerEntryKind::BorrowCost`.
+    BorrowCost(S::PairedCurrency),
+    /// The interest earned by the account on idle margin, as recorded in `LedgerEntryKind::Interest`.
+    Interest(S::PairedCurrency),
+    /// The positions were liquidated at the best bid and ask.
+    Liquidation {
+        /// The price at which a long position was closed.
+        bid: QuoteCurrency<S::Num>,
+        /// The price at which a short position was closed.
+        ask: QuoteCurrency<S::Num>,
+    },
+    /// The circuit breaker halted trading until the timestamp `until_ts_ns`.
+    TradingHalted {
+        /// The timestamp in nanoseconds at which trading resumes.
+        until_ts_ns: i64,
+    },
+    /// The dated futures contract expired and the positions were settled at the `price`.
+    Settlement {
+        /// The settlement price.
+        price: QuoteCurrency<S::Num>,
+    },
+}
+
+/// Lock the `mutex`, which is never poisoned as neither the subscribers nor the `Journal` must panic.
+#[cfg(feature = "std")]
+fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().expect("The subscribers must not panic")
+}
+
+/// Lock the `mutex`, spinning until it is available.
+#[cfg(not(feature = "std"))]
+fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock()
+}
+
+/// Consume the `mutex`, returning the wrapped value.
+#[cfg(feature = "std")]
+fn into_inner<T>(mutex: Mutex<T>) -> T {
+    mutex.into_inner().expect("Is not poisoned")
+}
+
+/// Consume the `mutex`, returning the wrapped value.
+#[cfg(not(feature = "std"))]
+fn into_inner<T>(mutex: Mutex<T>) -> T {
+    mutex.into_inner()
+}
+
+/// A callback receiving the timestamp in nanoseconds and the `ExchangeEvent`.
+type Subscriber<S> = Arc<Mutex<dyn FnMut(i64, &ExchangeEvent<S>) + Send>>;
+
+/// The callbacks subscribed to the events of an `Exchange` and the `Journal` being recorded, if any.
+/// Clones of an `Exchange` share the same subscribers, but record into their own copy of the `Journal`.
+pub(crate) struct EventSubscribers<S>
+where
+    S: Currency,
+{
+    subscribers: Vec<Subscriber<S>>,
+    journal: Option<Mutex<Journal<S>>>,
+}
+
+impl<S> Default for EventSubscribers<S>
+where
+    S: Currency,
+{
+    fn default() -> Self {
+        Self {
+            subscribers: Vec::new(),
+            journal: None,
+        }
+    }
+}
+
+impl<S> Clone for EventSubscribers<S>
+where
+    S: Currency,
+{
+    fn clone(&self) -> Self {
+        Self {
+            subscribers: self.subscribers.clone(),
+            journal: self
+                .journal
+                .as_ref()
+                .map(|journal| Mutex::new(lock(journal).clone())),
+        }
+    }
+}
+
+impl<S> Debug for EventSubscribers<S>
+where
+    S: Currency,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "EventSubscribers({})", self.subscribers.len())
+    }
+}
+
+impl<S> EventSubscribers<S>
+where
+    S: Currency,
+{
+    /// Whether nobody is interested in the events.
+    #[inline(always)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.subscribers.is_empty() && self.journal.is_none()
+    }
+
+    pub(crate) fn push<F>(&mut self, subscriber: F)
+    where
+        F: FnMut(i64, &ExchangeEvent<S>) + Send + 'static,
+    {
+        self.subscribers.push(Arc::new(Mutex::new(subscriber)));
+    }
+
+    /// Deliver the event created by `event` to all subscribers and the `Journal`.
+    /// The event is only created if there are any.
+    pub(crate) fn emit<F>(&self, ts_ns: i64, event: F)
+    where
+        F: FnOnce() -> ExchangeEvent<S>,
+    {
+        if self.is_empty() {
+            return;
+        }
+        let event = event();
+        for subscriber in self.subscribers.iter() {
+            let mut subscriber = lock(subscriber);
+            subscriber(ts_ns, &event);
+        }
+        self.record(|| JournalEntry::Event { ts_ns, event });
+    }
+
+    /// Start recording a new `Journal`, discarding the current one.
+    pub(crate) fn start_journal(&mut self) {
+        self.journal = Some(Mutex::new(Journal::default()));
+    }
+
+    /// Stop recording and return the `Journal`, if it was being recorded.
+    pub(crate) fn take_journal(&mut self) -> Option<Journal<S>> {
+        self.journal.take().map(into_inner)
+    }
+
+    /// Append the entry created by `entry` to the `Journal`.
+    /// The entry is only created while recording.
+    pub(crate) fn record<F>(&self, entry: F)
+    where
+        F: FnOnce() -> JournalEntry<S>,
+    {
+        if let Some(journal) = self.journal.as_ref() {
+            lock(journal).push(entry());
+        }
+    }
+}
@@ -0,0 +1,26 @@
+/// Counters of the events processed by the `Exchange`, to monitor the progress of a simulation.
+/// Created by [`Exchange::stats`](crate::prelude::Exchange::stats).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExchangeStats {
+    /// The number of calls to `update_state`, including the ones with an invalid `MarketUpdate`.
+    pub num_updates: u64,
+    /// The number of order executions, where each execution of an iceberg slice counts separately.
+    pub num_fills: u64,
+    /// The number of cancelled limit orders, including one-cancels-other siblings.
+    pub num_cancellations: u64,
+    /// The number of limit orders which expired due to their `TimeInForce`.
+    pub num_expirations: u64,
+    /// The number of orders which were rejected on submission.
+    pub num_rejections: u64,
+    /// The number of order submissions and cancellations which exceeded the `RateLimit`.
+    pub num_rate_limited: u64,
+    /// The number of order submissions and cancellations which were rejected or delayed by the `FaultInjection`.
+    pub num_injected_faults: u64,
+    /// The number of times the position had to be liquidated.
+    pub num_liquidations: u64,
+    /// The current step of the `MarketState`, which counts the valid market updates.
+    pub step: u64,
+    /// The simulated time in nanoseconds between the first and the latest market update.
+    pub elapsed_ns: i64,
+}
@@ -1,6 +1,8 @@
 use derive_more::Display;
 use fpdec::Decimal;
 
+use crate::types::Mon;
+
 /// Allows the quick construction of `Fee`
 #[macro_export]
 macro_rules! fee {
@@ -11,27 +13,31 @@ macro_rules! fee {
 
 /// Fee as a fraction
 #[derive(Default, Debug, Clone, Copy, PartialEq, Display)]
-pub struct Fee(Decimal);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Fee<T = Decimal>(T);
 
-impl Fee {
-    /// Create a new instance from a `Decimal` value
+impl<T> Fee<T>
+where
+    T: Mon,
+{
+    /// Create a new instance from a numeric value
     #[inline(always)]
-    pub fn new(val: Decimal) -> Self {
+    pub fn new(val: T) -> Self {
         Self(val)
     }
 
-    /// Get access to the inner `Decimal`
+    /// Get access to the inner numeric value
     #[inline(always)]
-    pub fn inner(self) -> Decimal {
+    pub fn inner(self) -> T {
         self.0
     }
 }
 
 /// The two types of fees in the maker-taker model.
 #[derive(Debug, Clone)]
-pub enum FeeType {
+pub enum FeeType<T = Decimal> {
     /// The fee limit orders pay.
-    Maker(Fee),
+    Maker(Fee<T>),
     /// The fee market orders pay.
-    Taker(Fee),
+    Taker(Fee<T>),
 }
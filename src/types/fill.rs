@@ -0,0 +1,27 @@
+use super::{Currency, QuoteCurrency, Side};
+
+/// A single execution of an order, identified by the trade id the `Exchange` assigns in ascending order.
+/// A partially filled iceberg order has one `Fill` per executed slice.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Fill<S>
+where
+    S: Currency,
+{
+    /// The id of the trade, unique per `Exchange`.
+    pub trade_id: u64,
+    /// The id of the executed order.
+    pub order_id: u64,
+    /// The side of the executed order.
+    pub side: Side,
+    /// The execution price.
+    pub price: QuoteCurrency<S::Num>,
+    /// The executed quantity.
+    pub quantity: S,
+    /// The paid fee, denoted in the margin currency, which is negative for rebates.
+    pub fee: S::PairedCurrency,
+    /// Whether the order provided liquidity.
+    pub is_maker: bool,
+    /// The timestamp of the execution in nanoseconds.
+    pub timestamp_ns: i64,
+}
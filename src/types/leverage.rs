@@ -15,6 +15,7 @@ macro_rules! leverage {
 
 /// Leverage
 #[derive(Default, Debug, Clone, Copy, PartialEq, Display, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Leverage(u8);
 
 impl Leverage {
@@ -0,0 +1,26 @@
+use crate::types::{Currency, QuoteCurrency};
+
+/// The margin situation of an account after a hypothetical, instantaneous price shock.
+/// Created by [`Account::margin_after_shock`](crate::prelude::Account::margin_after_shock).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarginShock<M>
+where
+    M: Currency,
+{
+    /// The mid price after applying the shock.
+    pub shocked_price: QuoteCurrency<M::Num>,
+    /// The unrealized profit and loss of the position at the shocked prices.
+    pub unrealized_pnl: M,
+    /// The position margin plus the unrealized profit and loss.
+    pub margin_balance: M,
+    /// The maintenance margin required to keep the position open at the shocked price.
+    pub maintenance_margin: M,
+    /// `maintenance_margin / margin_balance`.
+    /// A value of one or larger means the position would be liquidated.
+    pub margin_ratio: M::Num,
+    /// The price at which the position would be liquidated, if any.
+    pub liquidation_price: Option<QuoteCurrency<M::Num>>,
+    /// The relative price move from `shocked_price` until the `liquidation_price` is reached,
+    /// e.g.: -0.05 means the price can drop another 5% before liquidation.
+    pub liquidation_distance: Option<M::Num>,
+}
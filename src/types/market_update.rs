@@ -1,8 +1,11 @@
+use alloc::vec::Vec;
+
 use super::{Currency, Side};
 use crate::types::QuoteCurrency;
 
 /// Decribes the possible updates to the market state
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub enum MarketUpdate<S>
 where
     S: Currency,
@@ -12,15 +15,15 @@ where
     /// This will change in future versions.
     Bba {
         /// The new best bid
-        bid: QuoteCurrency,
+        bid: QuoteCurrency<S::Num>,
         /// The new best ask
-        ask: QuoteCurrency,
+        ask: QuoteCurrency<S::Num>,
     },
     /// A taker trade that consumes liquidity in the book.
     ///
     Trade {
         /// The price at which the trade executed at.
-        price: QuoteCurrency,
+        price: QuoteCurrency<S::Num>,
         /// The executed quantity.
         /// Generic denotation, e.g either Quote or Base currency denoted.
         quantity: S,
@@ -32,13 +35,30 @@ where
     /// have been executed over the last period as a proxy in absence of actual `Trade` flow.
     Candle {
         /// The best bid at the time of candle creation
-        bid: QuoteCurrency,
+        bid: QuoteCurrency<S::Num>,
         /// The best ask at the time of candle creation
-        ask: QuoteCurrency,
+        ask: QuoteCurrency<S::Num>,
         /// The low price of the candle
-        low: QuoteCurrency,
+        low: QuoteCurrency<S::Num>,
         /// The high price of the candle
-        high: QuoteCurrency,
+        high: QuoteCurrency<S::Num>,
+    },
+    /// A new price of the external index the contract references, e.g. the spot index of a perpetual.
+    /// It does not trade, so it leaves the book and the resting orders untouched,
+    /// but it may drive the mark price, see `LastIndexPrice`.
+    Index {
+        /// The new index price.
+        price: QuoteCurrency<S::Num>,
+    },
+    /// A snapshot of the top levels of the limit order book, e.g. from an L2 feed.
+    /// It moves the best bid and ask like a `Bba` update and does not trigger resting limit orders either,
+    /// but the `MarketState` keeps the levels to derive microstructure features like the book imbalance
+    /// and the microprice, which a `SlippageModel` can consume.
+    Depth {
+        /// The bid levels as price and resting quantity, best first.
+        bids: Vec<(QuoteCurrency<S::Num>, S)>,
+        /// The ask levels as price and resting quantity, best first.
+        asks: Vec<(QuoteCurrency<S::Num>, S)>,
     },
 }
 
@@ -75,6 +95,25 @@ macro_rules! candle {
     }};
 }
 
+/// Creates the `MarketUpdate::Index` variant.
+#[macro_export]
+macro_rules! index {
+    ( $price:expr ) => {{
+        $crate::prelude::MarketUpdate::Index { price: $price }
+    }};
+}
+
+/// Creates the `MarketUpdate::Depth` variant.
+#[macro_export]
+macro_rules! depth {
+    ( $bids:expr, $asks:expr ) => {{
+        $crate::prelude::MarketUpdate::Depth {
+            bids: $bids,
+            asks: $asks,
+        }
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,6 +132,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn trade_macro() {
+        let t: MarketUpdate<BaseCurrency> = trade!(quote!(100.0), base!(0.5), Side::Sell);
+
+        assert_eq!(
+            t,
+            MarketUpdate::Trade {
+                price: quote!(100.0),
+                quantity: base!(0.5),
+                side: Side::Sell,
+            }
+        );
+    }
+
     #[test]
     fn candle_macro() {
         let c: MarketUpdate<BaseCurrency> =
@@ -108,4 +161,32 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn index_macro() {
+        let i: MarketUpdate<BaseCurrency> = index!(quote!(100.05));
+
+        assert_eq!(
+            i,
+            MarketUpdate::Index {
+                price: quote!(100.05)
+            }
+        )
+    }
+
+    #[test]
+    fn depth_macro() {
+        let d: MarketUpdate<BaseCurrency> = depth!(
+            vec![(quote!(100.0), base!(1))],
+            vec![(quote!(100.1), base!(2))]
+        );
+
+        assert_eq!(
+            d,
+            MarketUpdate::Depth {
+                bids: vec![(quote!(100.0), base!(1))],
+                asks: vec![(quote!(100.1), base!(2))],
+            }
+        )
+    }
 }
@@ -1,20 +1,50 @@
+mod bankruptcy_mode;
 mod currency;
+mod dust_handling;
 mod errors;
+mod exchange_event;
+mod exchange_stats;
 mod fee;
+mod fill;
 mod leverage;
+mod margin_shock;
 mod market_update;
+mod mon;
 mod order;
+mod order_status;
 mod order_type;
+mod position_mode;
+mod price_improvement;
+mod self_trade_prevention;
 mod side;
+mod time_in_force;
+mod trigger_source;
+mod user_order_id;
 
+pub use bankruptcy_mode::BankruptcyMode;
 pub use currency::{BaseCurrency, Currency, MarginCurrency, QuoteCurrency};
+pub use dust_handling::DustHandling;
 pub use errors::*;
+pub(crate) use exchange_event::EventSubscribers;
+pub use exchange_event::ExchangeEvent;
+pub use exchange_stats::ExchangeStats;
 pub use fee::{Fee, FeeType};
+pub use fill::Fill;
 pub use leverage::Leverage;
+pub use margin_shock::MarginShock;
 pub use market_update::MarketUpdate;
+pub(crate) use mon::MaybeSerde;
+pub use mon::Mon;
 pub use order::{Filled, Order};
+pub use order_status::OrderStatus;
 pub use order_type::OrderType;
+pub use position_mode::PositionMode;
+pub use price_improvement::PriceImprovement;
+pub use self_trade_prevention::SelfTradePrevention;
 pub use side::Side;
+pub use time_in_force::TimeInForce;
+pub use trigger_source::TriggerSource;
+pub use user_order_id::UserOrderId;
 
 /// Natural Logarithmic Returns newtype wrapping a borrowed slice of generic floats.
 pub struct LnReturns<'a, T: num_traits::Float>(pub &'a [T]);
@@ -0,0 +1,228 @@
+use core::{
+    fmt::{Debug, Display},
+    ops::{Add, AddAssign, Div, Mul, Neg, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+use fpdec::Decimal;
+use num_traits::Float;
+
+use crate::{
+    types::Result,
+    utils::{decimal_sqrt, decimal_to_f64},
+};
+
+/// Requires currencies to be (de)serializable when the `serde` feature is enabled,
+/// so that any state generic over a `Currency` can be checkpointed.
+#[cfg(feature = "serde")]
+pub trait MaybeSerde: serde::Serialize + serde::de::DeserializeOwned {}
+
+#[cfg(feature = "serde")]
+impl<T> MaybeSerde for T where T: serde::Serialize + serde::de::DeserializeOwned {}
+
+/// Requires currencies to be (de)serializable when the `serde` feature is enabled,
+/// so that any state generic over a `Currency` can be checkpointed.
+#[cfg(not(feature = "serde"))]
+pub trait MaybeSerde {}
+
+#[cfg(not(feature = "serde"))]
+impl<T> MaybeSerde for T {}
+
+/// The numeric type of monetary values (money), which backs every `Currency`.
+///
+/// `Decimal` is exact and the default everywhere, which makes it the choice for validation runs.
+/// `f64` is considerably faster, e.g. for parameter sweeps,
+/// at the cost of rounding errors in the last digits.
+pub trait Mon:
+    Copy
+    + 'static
+    + Default
+    + Send
+    + Sync
+    + Debug
+    + Display
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + FromStr
+    + MaybeSerde
+{
+    /// The additive identity.
+    const ZERO: Self;
+    /// The multiplicative identity.
+    const ONE: Self;
+    /// The value two.
+    const TWO: Self;
+    /// The largest representable value.
+    const MAX: Self;
+
+    /// Convert a `Decimal`, e.g. a literal created with `Dec!`.
+    fn from_decimal(val: Decimal) -> Self;
+
+    /// Convert an `f64`, possibly losing precision.
+    ///
+    /// # Returns:
+    /// An Error if `val` can not be represented, e.g. if it is not finite and the backend is `Decimal`.
+    fn from_f64(val: f64) -> Result<Self>;
+
+    /// Convert an unsigned integer.
+    fn from_u64(val: u64) -> Self;
+
+    /// Convert a signed integer.
+    fn from_i64(val: i64) -> Self;
+
+    /// Convert into an `f64`, possibly losing precision.
+    fn to_f64(self) -> f64;
+
+    /// The absolute value.
+    fn abs(self) -> Self;
+
+    /// The square root, which may be approximated.
+    fn sqrt(self) -> Self;
+
+    /// The integer part, rounding towards zero.
+    fn trunc(self) -> Self;
+
+    /// Whether the value is an integer multiple of `step`.
+    /// Floating point backends allow for rounding errors.
+    fn is_multiple_of(self, step: Self) -> bool;
+}
+
+impl Mon for Decimal {
+    const ZERO: Self = Decimal::ZERO;
+    const ONE: Self = Decimal::ONE;
+    const TWO: Self = Decimal::TWO;
+    const MAX: Self = Decimal::MAX;
+
+    #[inline(always)]
+    fn from_decimal(val: Decimal) -> Self {
+        val
+    }
+
+    #[inline(always)]
+    fn from_f64(val: f64) -> Result<Self> {
+        Ok(Decimal::try_from(val)?)
+    }
+
+    #[inline(always)]
+    fn from_u64(val: u64) -> Self {
+        Decimal::from(val)
+    }
+
+    #[inline(always)]
+    fn from_i64(val: i64) -> Self {
+        Decimal::from(val)
+    }
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        decimal_to_f64(self)
+    }
+
+    #[inline(always)]
+    fn abs(self) -> Self {
+        Decimal::abs(&self)
+    }
+
+    #[inline(always)]
+    fn sqrt(self) -> Self {
+        decimal_sqrt(self)
+    }
+
+    #[inline(always)]
+    fn trunc(self) -> Self {
+        Decimal::trunc(&self)
+    }
+
+    #[inline(always)]
+    fn is_multiple_of(self, step: Self) -> bool {
+        self % step == Decimal::ZERO
+    }
+}
+
+impl Mon for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    const TWO: Self = 2.0;
+    const MAX: Self = f64::MAX;
+
+    #[inline(always)]
+    fn from_decimal(val: Decimal) -> Self {
+        decimal_to_f64(val)
+    }
+
+    #[inline(always)]
+    fn from_f64(val: f64) -> Result<Self> {
+        Ok(val)
+    }
+
+    #[inline(always)]
+    fn from_u64(val: u64) -> Self {
+        val as f64
+    }
+
+    #[inline(always)]
+    fn from_i64(val: i64) -> Self {
+        val as f64
+    }
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    #[inline(always)]
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+
+    #[inline(always)]
+    fn sqrt(self) -> Self {
+        Float::sqrt(self)
+    }
+
+    #[inline(always)]
+    fn trunc(self) -> Self {
+        Float::trunc(self)
+    }
+
+    #[inline(always)]
+    fn is_multiple_of(self, step: Self) -> bool {
+        let multiple = Float::round(self / step);
+        (self - multiple * step).abs() <= step.abs() * 1e-9
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fpdec::Dec;
+
+    use super::*;
+
+    #[test]
+    fn mon_decimal() {
+        assert_eq!(<Decimal as Mon>::from_u64(5), Dec!(5));
+        assert_eq!(<Decimal as Mon>::from_f64(0.25).unwrap(), Dec!(0.25));
+        assert!(<Decimal as Mon>::from_f64(f64::NAN).is_err());
+        assert_eq!(Dec!(-1.5).to_f64(), -1.5);
+        assert_eq!(<Decimal as Mon>::abs(Dec!(-1.5)), Dec!(1.5));
+        assert!(Dec!(100.1).is_multiple_of(Dec!(0.1)));
+        assert!(!Dec!(100.15).is_multiple_of(Dec!(0.1)));
+    }
+
+    #[test]
+    fn mon_f64() {
+        assert_eq!(f64::from_decimal(Dec!(0.25)), 0.25);
+        assert_eq!(<f64 as Mon>::sqrt(4.0), 2.0);
+        // `100.1 % 0.1` is not zero due to the binary representation.
+        assert!(100.1_f64.is_multiple_of(0.1));
+        assert!(!100.15_f64.is_multiple_of(0.1));
+    }
+}
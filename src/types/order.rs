@@ -1,38 +1,79 @@
-use crate::types::{Currency, OrderError, OrderType, QuoteCurrency, Side};
+use fpdec::Decimal;
+
+use crate::{
+    session_calendar::SessionCalendar,
+    types::{
+        Currency, Mon, OrderError, OrderStatus, OrderType, QuoteCurrency, Side, TimeInForce,
+        TriggerSource, UserOrderId,
+    },
+};
 
 /// Defines an order
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Order<S> {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct Order<S>
+where
+    S: Currency,
+{
     /// id will be filled in using exchange.submit_order()
     id: u64,
     /// Order Id provided by user
-    user_order_id: Option<u64>,
+    user_order_id: Option<UserOrderId>,
     /// timestamp will be filled in using exchange.submit_order()
     timestamp: i64,
     /// order type
     order_type: OrderType,
     /// the limit order price
-    limit_price: Option<QuoteCurrency>,
+    limit_price: Option<QuoteCurrency<S::Num>>,
     /// The amount of Currency `S` the order is for
     quantity: S,
+    /// The quantity that is visible in the book at a time (iceberg order).
+    /// If `None`, the whole order is visible.
+    display_quantity: Option<S>,
+    /// The quantity that has been executed so far.
+    filled_quantity: S,
     /// order side
     side: Side,
+    /// How long the order remains active
+    time_in_force: TimeInForce,
+    /// Whether the order may only reduce a position.
+    reduce_only: bool,
+    /// Links orders where the execution of one cancels the others (one-cancels-other).
+    group_id: Option<u64>,
+    /// The price at which the strategy intends to exit if the trade goes wrong.
+    /// Only used to bound the worst-case loss in pre-trade risk checks.
+    protective_stop: Option<QuoteCurrency<S::Num>>,
+    /// The price at which a stop order is triggered, `None` for regular orders.
+    stop_price: Option<QuoteCurrency<S::Num>>,
+    /// The price which triggers a stop order.
+    trigger_source: TriggerSource,
     /// whether or not the order has been executed
-    pub(crate) filled: Filled,
+    pub(crate) filled: Filled<S::Num>,
+    /// The stage of the lifecycle of the order.
+    status: OrderStatus,
+    /// When the order was submitted to the exchange, in nanoseconds.
+    created_ts_ns: i64,
+    /// When the status of the order last changed, in nanoseconds.
+    updated_ts_ns: i64,
+    /// When the order was completely filled, in nanoseconds.
+    filled_ts_ns: Option<i64>,
 }
 
 /// Whether the order has been executed
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Filled {
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Filled<T = Decimal> {
     /// The order has not been filled yet
     No,
     /// The order has been filled
     Yes {
         /// The average price this order has been filled at
-        fill_price: QuoteCurrency,
+        fill_price: QuoteCurrency<T>,
     },
 }
 
+impl<T> Eq for Filled<T> where T: Mon {}
+
 impl<S> Order<S>
 where
     S: Currency,
@@ -47,7 +88,11 @@ where
     /// # Returns:
     /// Either a successfully created order or an [`OrderError`]
     #[inline]
-    pub fn limit(side: Side, limit_price: QuoteCurrency, size: S) -> Result<Self, OrderError> {
+    pub fn limit(
+        side: Side,
+        limit_price: QuoteCurrency<S::Num>,
+        size: S,
+    ) -> Result<Self, OrderError> {
         if limit_price <= QuoteCurrency::new_zero() {
             return Err(OrderError::LimitPriceBelowZero);
         }
@@ -61,8 +106,20 @@ where
             order_type: OrderType::Limit,
             limit_price: Some(limit_price),
             quantity: size,
+            display_quantity: None,
+            filled_quantity: S::new_zero(),
             side,
+            time_in_force: TimeInForce::GoodTilCancel,
+            reduce_only: false,
+            group_id: None,
+            protective_stop: None,
+            stop_price: None,
+            trigger_source: TriggerSource::LastPrice,
             filled: Filled::No,
+            status: OrderStatus::New,
+            created_ts_ns: 0,
+            updated_ts_ns: 0,
+            filled_ts_ns: None,
         })
     }
 
@@ -86,8 +143,20 @@ where
             order_type: OrderType::Market,
             limit_price: None,
             quantity: size,
+            display_quantity: None,
+            filled_quantity: S::new_zero(),
             side,
+            time_in_force: TimeInForce::GoodTilCancel,
+            reduce_only: false,
+            group_id: None,
+            protective_stop: None,
+            stop_price: None,
+            trigger_source: TriggerSource::LastPrice,
             filled: Filled::No,
+            status: OrderStatus::New,
+            created_ts_ns: 0,
+            updated_ts_ns: 0,
+            filled_ts_ns: None,
         })
     }
 
@@ -99,14 +168,15 @@ where
 
     /// User id of Order
     #[inline(always)]
-    pub fn user_order_id(&self) -> &Option<u64> {
+    pub fn user_order_id(&self) -> &Option<UserOrderId> {
         &self.user_order_id
     }
 
-    /// Set the user id of Order
+    /// Set the user id of Order, either a `u64` or a string.
+    /// The exchange rejects orders whose user id is already used by another active order.
     #[inline(always)]
-    pub fn set_user_order_id(&mut self, id: u64) {
-        self.user_order_id = Some(id)
+    pub fn set_user_order_id<I: Into<UserOrderId>>(&mut self, id: I) {
+        self.user_order_id = Some(id.into())
     }
 
     /// Timestamp of Order
@@ -123,7 +193,7 @@ where
 
     /// limit price of Order
     #[inline(always)]
-    pub fn limit_price(&self) -> Option<QuoteCurrency> {
+    pub fn limit_price(&self) -> Option<QuoteCurrency<S::Num>> {
         self.limit_price
     }
 
@@ -133,21 +203,199 @@ where
         self.quantity
     }
 
+    /// The quantity that is visible in the book at a time, if this is an iceberg order.
+    #[inline(always)]
+    pub fn display_quantity(&self) -> Option<S> {
+        self.display_quantity
+    }
+
+    /// Turn the limit order into an iceberg order, where only the `display_quantity` rests in the book.
+    /// After each execution of the displayed quantity, it is refilled from the hidden remainder
+    /// and placed at the back of the queue.
+    ///
+    /// # Returns:
+    /// An [`OrderError`] if its not a limit order or the `display_quantity` is not in range (0, quantity].
+    pub fn set_display_quantity(&mut self, display_quantity: S) -> Result<(), OrderError> {
+        if !matches!(self.order_type, OrderType::Limit)
+            || display_quantity <= S::new_zero()
+            || display_quantity > self.quantity
+        {
+            return Err(OrderError::InvalidDisplayQuantity);
+        }
+        self.display_quantity = Some(display_quantity);
+        Ok(())
+    }
+
+    /// The quantity of the `Order` that has been executed so far.
+    #[inline(always)]
+    pub fn filled_quantity(&self) -> S {
+        self.filled_quantity
+    }
+
+    /// The quantity of the `Order` that has not been executed yet.
+    #[inline(always)]
+    pub fn remaining_quantity(&self) -> S {
+        self.quantity - self.filled_quantity
+    }
+
+    /// The quantity that is currently visible in the book and can be executed at once.
+    #[inline]
+    pub fn visible_quantity(&self) -> S {
+        let remaining = self.remaining_quantity();
+        match self.display_quantity {
+            Some(display_quantity) if display_quantity < remaining => display_quantity,
+            _ => remaining,
+        }
+    }
+
+    /// Remove `quantity` from the order without executing it, e.g. due to self-trade prevention.
+    #[inline(always)]
+    pub(crate) fn decrement_quantity(&mut self, quantity: S) {
+        debug_assert!(quantity <= self.remaining_quantity());
+        self.quantity -= quantity
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_filled_quantity(&mut self, quantity: S) {
+        debug_assert!(quantity <= self.remaining_quantity());
+        self.filled_quantity += quantity
+    }
+
     /// Side of Order
     #[inline(always)]
     pub fn side(&self) -> Side {
         self.side
     }
 
+    /// Time in force of the `Order`
+    #[inline(always)]
+    pub fn time_in_force(&self) -> TimeInForce {
+        self.time_in_force
+    }
+
+    /// Set the time in force of the `Order`.
+    /// Only relevant for limit orders, as market orders are always executed immediately.
+    #[inline(always)]
+    pub fn set_time_in_force(&mut self, time_in_force: TimeInForce) {
+        self.time_in_force = time_in_force
+    }
+
+    /// Whether the `Order` may only reduce a position.
+    #[inline(always)]
+    pub fn reduce_only(&self) -> bool {
+        self.reduce_only
+    }
+
+    /// Mark the `Order` as reduce-only.
+    /// In `PositionMode::Netting`, the order is rejected if it would increase or flip the position.
+    /// In `PositionMode::Hedge`, the order decreases the position opposite to its side,
+    /// e.g. a reduce-only sell order decreases the long position.
+    #[inline(always)]
+    pub fn set_reduce_only(&mut self, reduce_only: bool) {
+        self.reduce_only = reduce_only
+    }
+
+    /// The id of the one-cancels-other group this `Order` belongs to, if any.
+    /// Assigned by the exchange in `submit_oco_orders`.
+    #[inline(always)]
+    pub fn group_id(&self) -> Option<u64> {
+        self.group_id
+    }
+
+    #[inline(always)]
+    pub(crate) fn set_group_id(&mut self, group_id: u64) {
+        self.group_id = Some(group_id)
+    }
+
+    /// Whether the `Order` has expired at `ts_ns` due to its `TimeInForce`.
+    ///
+    /// # Arguments:
+    /// `ts_ns`: The current timestamp in nanoseconds.
+    /// `session_calendar`: Defines when `TimeInForce::Day` orders expire.
+    pub fn is_expired(&self, ts_ns: i64, session_calendar: &SessionCalendar) -> bool {
+        self.expires_at(session_calendar)
+            .is_some_and(|expires_at| ts_ns >= expires_at)
+    }
+
+    /// The first timestamp in nanoseconds at which the `Order` is expired, if its `TimeInForce` expires at all.
+    ///
+    /// # Arguments:
+    /// `session_calendar`: Defines when `TimeInForce::Day` orders expire.
+    pub fn expires_at(&self, session_calendar: &SessionCalendar) -> Option<i64> {
+        match self.time_in_force {
+            TimeInForce::GoodTilDate(expiry_ts_ns) => Some(expiry_ts_ns.saturating_add(1)),
+            TimeInForce::Day => session_calendar.session_end(self.timestamp),
+            TimeInForce::GoodTilCancel
+            | TimeInForce::ImmediateOrCancel
+            | TimeInForce::FillOrKill => None,
+        }
+    }
+
+    /// The protective stop price of the `Order`, if any.
+    #[inline(always)]
+    pub fn protective_stop(&self) -> Option<QuoteCurrency<S::Num>> {
+        self.protective_stop
+    }
+
+    /// Attach a protective stop price to the `Order`,
+    /// which bounds the worst-case loss of the trade in the pre-trade risk checks.
+    /// Note that the exchange does not execute the stop,
+    /// it is the responsibility of the strategy to exit at this price.
+    #[inline(always)]
+    pub fn set_protective_stop(&mut self, stop_price: QuoteCurrency<S::Num>) {
+        self.protective_stop = Some(stop_price)
+    }
+
+    /// The price at which the stop order is triggered, or `None` if it is not a stop order.
+    #[inline(always)]
+    pub fn stop_price(&self) -> Option<QuoteCurrency<S::Num>> {
+        self.stop_price
+    }
+
+    /// Turn the `Order` into a stop order, which the exchange holds without reserving margin
+    /// until its `TriggerSource` reaches the `stop_price`:
+    /// At or above it for buy orders, at or below it for sell orders.
+    /// Once triggered, it is submitted as the market or limit order it was created as,
+    /// where a limit price crossing the spread is executed immediately.
+    ///
+    /// # Returns:
+    /// An [`OrderError`] if the `stop_price` is not positive.
+    pub fn set_stop_price(&mut self, stop_price: QuoteCurrency<S::Num>) -> Result<(), OrderError> {
+        if stop_price <= QuoteCurrency::new_zero() {
+            return Err(OrderError::StopPriceBelowZero);
+        }
+        self.stop_price = Some(stop_price);
+        Ok(())
+    }
+
+    /// Turn the triggered stop order into the regular order it was created as.
+    #[inline(always)]
+    pub(crate) fn clear_stop_price(&mut self) {
+        self.stop_price = None
+    }
+
+    /// The price which triggers the stop order.
+    #[inline(always)]
+    pub fn trigger_source(&self) -> TriggerSource {
+        self.trigger_source
+    }
+
+    /// Set the price which triggers the stop order, `TriggerSource::LastPrice` by default.
+    #[inline(always)]
+    pub fn set_trigger_source(&mut self, trigger_source: TriggerSource) {
+        self.trigger_source = trigger_source
+    }
+
     /// Fill status of the `Order`
     #[inline(always)]
-    pub fn filled(&self) -> Filled {
+    pub fn filled(&self) -> Filled<S::Num> {
         self.filled
     }
 
-    /// Marks the order as filled at the `fill_price`
+    /// Marks the order as completely filled at the `fill_price`
     #[inline(always)]
-    pub(crate) fn mark_filled(&mut self, fill_price: QuoteCurrency) {
+    pub(crate) fn mark_filled(&mut self, fill_price: QuoteCurrency<S::Num>) {
+        self.filled_quantity = self.quantity;
         self.filled = Filled::Yes { fill_price }
     }
 
@@ -156,6 +404,46 @@ where
         self.id = id
     }
 
+    /// The stage of the lifecycle the `Order` is in.
+    #[inline(always)]
+    pub fn status(&self) -> OrderStatus {
+        self.status
+    }
+
+    /// When the `Order` was submitted to the exchange, in nanoseconds.
+    /// Unlike the `timestamp`, it does not change when an iceberg order loses its time priority.
+    #[inline(always)]
+    pub fn created_ts_ns(&self) -> i64 {
+        self.created_ts_ns
+    }
+
+    /// When the `status` of the `Order` last changed, in nanoseconds.
+    #[inline(always)]
+    pub fn updated_ts_ns(&self) -> i64 {
+        self.updated_ts_ns
+    }
+
+    /// When the `Order` was completely filled, in nanoseconds.
+    #[inline(always)]
+    pub fn filled_ts_ns(&self) -> Option<i64> {
+        self.filled_ts_ns
+    }
+
+    #[inline(always)]
+    pub(crate) fn set_created_ts_ns(&mut self, ts_ns: i64) {
+        self.created_ts_ns = ts_ns;
+        self.updated_ts_ns = ts_ns;
+    }
+
+    /// Move the `Order` to the next stage of its lifecycle at `ts_ns`.
+    pub(crate) fn set_status(&mut self, status: OrderStatus, ts_ns: i64) {
+        self.status = status;
+        self.updated_ts_ns = ts_ns;
+        if matches!(status, OrderStatus::Filled) {
+            self.filled_ts_ns = Some(ts_ns);
+        }
+    }
+
     /// Set the timestamp of the order,
     /// note that the timestamps will be overwritten if set_order_timestamps is
     /// set in Config
@@ -0,0 +1,26 @@
+/// The stage of the lifecycle an `Order` is in.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OrderStatus {
+    /// The order has been accepted and has not been executed yet.
+    #[default]
+    New,
+    /// Some of the order quantity has been executed and the rest is still active.
+    PartiallyFilled,
+    /// The whole order quantity has been executed.
+    Filled,
+    /// The order has been cancelled, either by the user or by the exchange,
+    /// e.g. as part of a one-cancels-other group or in a liquidation.
+    Cancelled,
+    /// The order has expired due to its `TimeInForce`.
+    Expired,
+    /// The order has been rejected upon submission.
+    Rejected,
+}
+
+impl OrderStatus {
+    /// Whether the order can still be executed.
+    #[inline(always)]
+    pub fn is_active(&self) -> bool {
+        matches!(self, OrderStatus::New | OrderStatus::PartiallyFilled)
+    }
+}
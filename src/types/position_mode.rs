@@ -0,0 +1,13 @@
+/// Defines how fills of opposite sides are accounted for in the position.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PositionMode {
+    /// A single position per market, where opposite orders reduce the position
+    /// and can flip it through zero into the other direction.
+    #[default]
+    Netting,
+    /// Separate long and short positions per market (hedge mode).
+    /// Buy orders increase the long and sell orders increase the short position,
+    /// unless the order is reduce-only, in which case it decreases the opposite position.
+    /// A position never flips through zero.
+    Hedge,
+}
@@ -0,0 +1,12 @@
+/// Defines the fill price of an aggressive limit order which crosses the spread,
+/// e.g. with `TimeInForce::ImmediateOrCancel` or as a triggered stop limit order.
+/// It changes the expectancy of strategies trading with marketable limit orders.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PriceImprovement {
+    /// The order fills at the better of its limit price and the opposite best quote,
+    /// like it would against a real order book.
+    #[default]
+    BestQuote,
+    /// The order always fills at its limit price, which is the worst case within the limit.
+    Off,
+}
@@ -0,0 +1,16 @@
+/// Defines what happens when an aggressive order would trade against a resting order of the same account.
+/// The resting orders are only changed once the `RiskEngine` approves the aggressive order.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SelfTradePrevention {
+    /// The aggressive order executes against the market as usual, ignoring the resting orders.
+    #[default]
+    Off,
+    /// The crossed resting orders are cancelled before the aggressive order executes.
+    CancelResting,
+    /// The aggressive order is rejected with `OrderError::SelfTradePrevented`.
+    CancelAggressor,
+    /// The quantity which would self-trade is removed from both the aggressive and the crossed resting orders.
+    /// Resting orders without any remaining quantity are cancelled,
+    /// and the aggressive order is rejected if nothing of it remains.
+    DecrementBoth,
+}
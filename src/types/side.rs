@@ -1,4 +1,7 @@
-use std::fmt::Formatter;
+use alloc::format;
+use core::fmt::Formatter;
+
+use crate::types::Error;
 
 /// Side of the order
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
@@ -19,8 +22,21 @@ impl Side {
     }
 }
 
-impl std::fmt::Display for Side {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl core::str::FromStr for Side {
+    type Err = Error;
+
+    /// Parse `buy` / `sell` or their abbreviations `b` / `s`, ignoring the case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "buy" | "b" => Ok(Side::Buy),
+            "sell" | "s" => Ok(Side::Sell),
+            _ => Err(Error::InvalidMarketData(format!("unknown side {s}"))),
+        }
+    }
+}
+
+impl core::fmt::Display for Side {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{self:?}")
     }
 }
@@ -0,0 +1,33 @@
+/// Defines how long an order remains active before it is executed or expires.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TimeInForce {
+    /// The order rests until it is either filled or cancelled.
+    #[default]
+    GoodTilCancel,
+    /// The order is executed immediately against the current best bid / ask,
+    /// any part that cannot be executed is cancelled, which reduces the quantity of the order to the executed part.
+    /// The executable quantity is the one quoted up to the limit price in the latest `MarketUpdate::Depth`.
+    /// Without known depth the best bid and ask have unbounded size, so the order is executed entirely like `FillOrKill`.
+    ImmediateOrCancel,
+    /// The order is executed immediately and entirely against the current best bid / ask,
+    /// or not at all if the latest `MarketUpdate::Depth` quotes less than its quantity up to the limit price.
+    FillOrKill,
+    /// The order rests until it is filled, cancelled or
+    /// the given timestamp in nanoseconds has passed.
+    GoodTilDate(i64),
+    /// The order rests until it is filled, cancelled or
+    /// the trading session in which it was submitted ends,
+    /// as defined by the `SessionCalendar` in `Config`.
+    Day,
+}
+
+impl TimeInForce {
+    /// Whether the order must be resolved at the time of submission.
+    #[inline(always)]
+    pub fn is_immediate(&self) -> bool {
+        matches!(
+            self,
+            TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill
+        )
+    }
+}
@@ -0,0 +1,12 @@
+/// The price which triggers a stop order once it reaches the stop price.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TriggerSource {
+    /// The price of the latest trade, which includes the wicks of candles.
+    /// Updates of the best bid and ask count as a trade at the mid price.
+    #[default]
+    LastPrice,
+    /// The mark price according to the `MarkMethod` of the contract, which is less prone to wicks.
+    MarkPrice,
+    /// The price of the external index, which never triggers before the first `MarketUpdate::Index`.
+    IndexPrice,
+}
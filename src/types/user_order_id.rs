@@ -0,0 +1,57 @@
+use alloc::string::{String, ToString};
+use core::fmt::Formatter;
+
+/// An order id chosen by the user, to correlate orders with those of the trading system,
+/// in addition to the `id` assigned by the exchange.
+/// Like the client order ids of real venues it is either numeric or a string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UserOrderId {
+    /// A numeric id.
+    Number(u64),
+    /// A string id.
+    Text(String),
+}
+
+impl From<u64> for UserOrderId {
+    fn from(id: u64) -> Self {
+        Self::Number(id)
+    }
+}
+
+impl From<&str> for UserOrderId {
+    fn from(id: &str) -> Self {
+        Self::Text(id.to_string())
+    }
+}
+
+impl From<String> for UserOrderId {
+    fn from(id: String) -> Self {
+        Self::Text(id)
+    }
+}
+
+impl core::fmt::Display for UserOrderId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Number(id) => write!(f, "{id}"),
+            Self::Text(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_order_id() {
+        assert_eq!(UserOrderId::from(5), UserOrderId::Number(5));
+        assert_eq!(
+            UserOrderId::from("tp-1"),
+            UserOrderId::Text("tp-1".to_string())
+        );
+        assert_ne!(UserOrderId::from(5), UserOrderId::from("5"));
+        assert_eq!(UserOrderId::from("tp-1").to_string(), "tp-1");
+        assert_eq!(UserOrderId::from(5).to_string(), "5");
+    }
+}
@@ -1,4 +1,7 @@
 use fpdec::{Dec, Decimal};
+use num_traits::Float;
+
+use crate::types::Mon;
 
 /// Return the minimum of two values
 #[inline(always)]
@@ -34,7 +37,7 @@ pub fn get_num_digits(step_size: Decimal) -> i32 {
         return 0;
     }
     let n = Decimal::ONE / step_size;
-    decimal_to_f64(n).log10().ceil() as i32
+    Float::ceil(Float::log10(decimal_to_f64(n))) as i32
 }
 
 /// Convert a `Decimal` value into `f64`.
@@ -46,7 +49,7 @@ pub(crate) fn decimal_to_f64(val: Decimal) -> f64 {
         return val.coefficient() as f64;
     }
 
-    val.coefficient() as f64 / 10_f64.powi(val.n_frac_digits() as _)
+    val.coefficient() as f64 / Float::powi(10_f64, val.n_frac_digits() as _)
 }
 
 /// Convert a `f64` value to a `Decimal` type given the `step_size` of the
@@ -56,7 +59,7 @@ pub fn f64_to_decimal(price: f64, step_size: Decimal) -> Decimal {
     if decimal_places == 0 {
         return Decimal::from(price as i64);
     }
-    let scaling = 10_f64.powi(decimal_places);
+    let scaling = Float::powi(10_f64, decimal_places);
     let scaled_f64 = price * scaling;
 
     if decimal_places > 0 {
@@ -66,46 +69,49 @@ pub fn f64_to_decimal(price: f64, step_size: Decimal) -> Decimal {
     }
 }
 
-/// Sum an iterator of `Decimal` values.
-pub(crate) fn decimal_sum<I>(vals: I) -> Decimal
+/// Sum an iterator of monetary values.
+pub(crate) fn sum<T, I>(vals: I) -> T
 where
-    I: IntoIterator<Item = Decimal>,
+    T: Mon,
+    I: IntoIterator<Item = T>,
 {
-    let mut out = Dec!(0);
+    let mut out = T::ZERO;
     for v in vals.into_iter() {
         out += v;
     }
     out
 }
 
-/// Apply a power to the `Decimal` value
-pub(crate) fn decimal_pow(val: Decimal, pow: u32) -> Decimal {
+/// Apply a power to the monetary value
+pub(crate) fn pow<T: Mon>(val: T, pow: u32) -> T {
     if pow == 0 {
-        return Decimal::ONE;
+        return T::ONE;
     }
     let mut out = val;
     for _ in 1..pow {
-        out *= val;
+        out = out * val;
     }
     out
 }
 
 /// Take the square root of a `Decimal` value.
 pub(crate) fn decimal_sqrt(val: Decimal) -> Decimal {
-    f64_to_decimal(decimal_to_f64(val).sqrt(), Dec!(0.0000001))
+    f64_to_decimal(Float::sqrt(decimal_to_f64(val)), Dec!(0.0000001))
 }
 
-/// Compute the variance of `Decimal` values
-pub(crate) fn variance(vals: &[Decimal]) -> Decimal {
-    let n: Decimal = (vals.len() as u64).into();
-    let avg: Decimal = decimal_sum(vals.iter().cloned()) / n;
-    decimal_sum(vals.iter().map(|v| (v - avg) * (v - avg))) / n
+/// Compute the variance of monetary values
+pub(crate) fn variance<T: Mon>(vals: &[T]) -> T {
+    let n = T::from_u64(vals.len() as u64);
+    let avg = sum(vals.iter().cloned()) / n;
+    sum(vals.iter().map(|v| (*v - avg) * (*v - avg))) / n
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use std::convert::TryFrom;
+    #[cfg(feature = "std")]
+    use core::convert::TryFrom;
 
+    #[cfg(feature = "std")]
     use rand::{thread_rng, Rng};
 
     use super::*;
@@ -136,6 +142,7 @@ pub(crate) mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn test_decimal_to_f64() {
         let _ = pretty_env_logger::try_init();
 
@@ -190,22 +197,22 @@ pub(crate) mod tests {
     }
 
     #[test]
-    fn test_decimal_sum() {
+    fn test_sum() {
         let vals: Vec<Decimal> = (0..100).map(|v| v.into()).collect();
-        assert_eq!(decimal_sum(vals), Dec!(4950));
+        assert_eq!(sum(vals), Dec!(4950));
     }
 
     #[test]
-    fn test_decimal_pow() {
-        assert_eq!(decimal_pow(Dec!(2), 0), Dec!(1));
-        assert_eq!(decimal_pow(Dec!(2), 1), Dec!(2));
-        assert_eq!(decimal_pow(Dec!(2), 2), Dec!(4));
-        assert_eq!(decimal_pow(Dec!(2), 3), Dec!(8));
-
-        assert_eq!(decimal_pow(Dec!(0.5), 0), Dec!(1));
-        assert_eq!(decimal_pow(Dec!(0.5), 1), Dec!(0.5));
-        assert_eq!(decimal_pow(Dec!(0.5), 2), Dec!(0.25));
-        assert_eq!(decimal_pow(Dec!(0.5), 3), Dec!(0.125));
+    fn test_pow() {
+        assert_eq!(pow(Dec!(2), 0), Dec!(1));
+        assert_eq!(pow(Dec!(2), 1), Dec!(2));
+        assert_eq!(pow(Dec!(2), 2), Dec!(4));
+        assert_eq!(pow(Dec!(2), 3), Dec!(8));
+
+        assert_eq!(pow(Dec!(0.5), 0), Dec!(1));
+        assert_eq!(pow(Dec!(0.5), 1), Dec!(0.5));
+        assert_eq!(pow(Dec!(0.5), 2), Dec!(0.25));
+        assert_eq!(pow(Dec!(0.5), 3), Dec!(0.125));
     }
 
     #[test]
@@ -5,7 +5,7 @@ use lfest::{mock_exchange_quote, prelude::*, trade};
 
 #[test]
 fn inv_long_market_win_full() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange
@@ -21,7 +21,7 @@ fn inv_long_market_win_full() {
         .update_state(1, bba!(quote!(1000), quote!(1001)))
         .unwrap();
 
-    let fee_quote = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote = size.fee_portion(exchange.fee_taker());
     let fee_base1 = fee_quote.convert(exchange.market_state().bid());
 
     assert_eq!(exchange.account().position().size(), size);
@@ -52,7 +52,7 @@ fn inv_long_market_win_full() {
     );
 
     let size = quote!(800.0);
-    let fee_base2 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_base2 = size.fee_portion(exchange.fee_taker());
     let fee_asset2 = fee_base2.convert(quote!(2000.0));
 
     let o = Order::market(Side::Sell, size).unwrap();
@@ -79,7 +79,7 @@ fn inv_long_market_win_full() {
 
 #[test]
 fn inv_long_market_loss_full() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange
@@ -118,10 +118,10 @@ fn inv_long_market_loss_full() {
     let o = Order::market(Side::Sell, size).unwrap();
     exchange.submit_order(o).unwrap();
 
-    let fee_quote0 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote0 = size.fee_portion(exchange.fee_taker());
     let fee_base0 = fee_quote0.convert(quote!(1000.0));
 
-    let fee_quote1 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote1 = size.fee_portion(exchange.fee_taker());
     let fee_base1 = fee_quote1.convert(quote!(800.0));
 
     let fee_combined = fee_base0 + fee_base1;
@@ -147,7 +147,7 @@ fn inv_long_market_loss_full() {
 
 #[test]
 fn inv_short_market_win_full() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange
@@ -178,10 +178,10 @@ fn inv_short_market_win_full() {
         .update_state(2, bba!(quote!(799), quote!(800)))
         .unwrap();
 
-    let fee_quote0 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote0 = size.fee_portion(exchange.fee_taker());
     let fee_base0 = fee_quote0.convert(quote!(1000));
 
-    let fee_quote1 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote1 = size.fee_portion(exchange.fee_taker());
     let fee_base1 = fee_quote1.convert(quote!(800));
 
     let fee_combined: BaseCurrency = fee_base0 + fee_base1;
@@ -207,7 +207,7 @@ fn inv_short_market_win_full() {
 
 #[test]
 fn inv_short_market_loss_full() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange
@@ -223,7 +223,7 @@ fn inv_short_market_loss_full() {
         .update_state(1, bba!(quote!(999), quote!(1000)))
         .unwrap();
 
-    let fee_quote1 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote1 = size.fee_portion(exchange.fee_taker());
     let fee_base1 = fee_quote1.convert(quote!(1000));
 
     assert_eq!(exchange.account().position().size(), size.into_negative());
@@ -250,7 +250,7 @@ fn inv_short_market_loss_full() {
         .unwrap();
 
     let size = quote!(400.0);
-    let fee_quote2 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote2 = size.fee_portion(exchange.fee_taker());
     let fee_base2: BaseCurrency = fee_quote2.convert(quote!(2000.0));
 
     assert_eq!(
@@ -289,7 +289,7 @@ fn inv_short_market_loss_full() {
 
 #[test]
 fn inv_long_market_win_partial() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange
@@ -305,7 +305,7 @@ fn inv_long_market_win_partial() {
         .update_state(1, bba!(quote!(1000), quote!(1001)))
         .unwrap();
 
-    let fee_quote = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote = size.fee_portion(exchange.fee_taker());
     let fee_base1: BaseCurrency = fee_quote.convert(exchange.market_state().bid());
 
     assert_eq!(exchange.account().position().size(), size);
@@ -332,7 +332,7 @@ fn inv_long_market_win_partial() {
         .unwrap();
 
     let size = quote!(400.0);
-    let fee_quote2 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote2 = size.fee_portion(exchange.fee_taker());
     let fee_base2: BaseCurrency = fee_quote2.convert(quote!(2000.0));
 
     assert_eq!(
@@ -377,7 +377,7 @@ fn inv_long_market_win_partial() {
 
 #[test]
 fn inv_long_market_loss_partial() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange
@@ -409,12 +409,10 @@ fn inv_long_market_loss_partial() {
         .update_state(1, bba!(quote!(800), quote!(801)))
         .unwrap();
 
-    let fee_quote0 =
-        quote!(800.0).fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote0 = quote!(800.0).fee_portion(exchange.fee_taker());
     let fee_base0: BaseCurrency = fee_quote0.convert(quote!(1000.0));
 
-    let fee_quote1 =
-        quote!(400.0).fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote1 = quote!(400.0).fee_portion(exchange.fee_taker());
     let fee_base1: BaseCurrency = fee_quote1.convert(quote!(800.0));
 
     let fee_combined: BaseCurrency = fee_base0 + fee_base1;
@@ -440,7 +438,7 @@ fn inv_long_market_loss_partial() {
 
 #[test]
 fn inv_short_market_win_partial() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange
@@ -485,12 +483,10 @@ fn inv_short_market_win_partial() {
         .update_state(3, bba!(quote!(799), quote!(800)))
         .unwrap();
 
-    let fee_quote0 =
-        quote!(800.0).fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote0 = quote!(800.0).fee_portion(exchange.fee_taker());
     let fee_base0: BaseCurrency = fee_quote0.convert(quote!(1000.0));
 
-    let fee_quote1 =
-        quote!(400.0).fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote1 = quote!(400.0).fee_portion(exchange.fee_taker());
     let fee_base1: BaseCurrency = fee_quote1.convert(quote!(800.0));
 
     let fee_combined = fee_base0 + fee_base1;
@@ -516,7 +512,7 @@ fn inv_short_market_win_partial() {
 
 #[test]
 fn inv_short_market_loss_partial() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange
@@ -532,7 +528,7 @@ fn inv_short_market_loss_partial() {
         .update_state(1, bba!(quote!(999), quote!(1000)))
         .unwrap();
 
-    let fee_quote1 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote1 = size.fee_portion(exchange.fee_taker());
     let fee_base1: BaseCurrency = fee_quote1.convert(quote!(1000));
 
     assert_eq!(exchange.account().position().size(), size.into_negative());
@@ -556,7 +552,7 @@ fn inv_short_market_loss_partial() {
         .unwrap();
 
     let size = quote!(400.0);
-    let fee_quote2 = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote2 = size.fee_portion(exchange.fee_taker());
     let fee_base2: BaseCurrency = fee_quote2.convert(quote!(2000.0));
 
     assert_eq!(
@@ -591,7 +587,7 @@ fn inv_short_market_loss_partial() {
 
 #[test]
 fn inv_test_market_roundtrip() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange
@@ -610,7 +606,7 @@ fn inv_test_market_roundtrip() {
 
     exchange.submit_order(sell_order).unwrap();
 
-    let fee_quote = size.fee_portion(exchange.config().contract_specification().fee_taker);
+    let fee_quote = size.fee_portion(exchange.fee_taker());
     let fee_base: BaseCurrency = fee_quote.convert(quote!(1000.0));
 
     let _ = exchange
@@ -668,7 +664,7 @@ fn inv_test_market_roundtrip() {
 
 #[test]
 fn inv_execute_limit() {
-    if let Err(_) = pretty_env_logger::try_init() {}
+    let _ = pretty_env_logger::try_init();
 
     let mut exchange = mock_exchange_quote(base!(1));
     let _ = exchange